@@ -0,0 +1,96 @@
+/**
+ * Fixtures for the hot-path benchmarks: a four.meme token mid-curve, a 0.5 BNB-sized buy of it and
+ * the receipt logs that buy emits. A mainnet buy captured by `npm run capture:fixtures`
+ * (tests/fixtures/buy.json) is used when present: its token's curve the block before, its spend,
+ * gas and receipt logs. Without one, the same shapes are built on the standard BNB launch curve
+ * (1.07B virtual supply, 800M offered for a 24 BNB raise) a third sold.
+ */
+
+import * as fs from 'fs';
+import * as path from 'path';
+import { TransactionReceipt } from 'ethers';
+import { getErc20Interface, getFourTradingInterface } from '../src/abi';
+import { FOUR_MEME_ADDRESS } from '../src/constants';
+import { curveBuyAmount, curveParams } from '../src/curve';
+import { decodeTokenInfo } from '../src/tokenInfo';
+import { TokenInfo } from '../src/types';
+import type { CapturedBuy } from '../scripts/captureFixtures';
+
+const ONE = 10n ** 18n;
+const four = getFourTradingInterface();
+
+const CAPTURED_PATH = path.join(__dirname, '../tests/fixtures/buy.json');
+const captured: CapturedBuy | undefined = fs.existsSync(CAPTURED_PATH) ? JSON.parse(fs.readFileSync(CAPTURED_PATH, 'utf8')) : undefined;
+
+// ==================== Synthetic buy on the standard launch curve ====================
+
+const SYNTHETIC_TOKEN = '0x6f2a4c3e8d1b7a9c0e5f4d3b2a1c9e8d7f6b4444'; // four.meme tokens end in 4444
+const T = 1_073_000_000n * ONE;
+const K = 6_675_000_000n * ONE * ONE;
+const MAX_OFFERS = 800_000_000n * ONE;
+const SOLD = 266_000_000n * ONE;
+
+function syntheticTokenInfo(): TokenInfo {
+  return {
+    base: SYNTHETIC_TOKEN,
+    quote: '0x0000000000000000000000000000000000000000',
+    template: 2n,
+    totalSupply: 1_000_000_000n * ONE,
+    maxOffers: MAX_OFFERS,
+    maxRaising: 24n * ONE,
+    launchTime: 1_717_000_000n,
+    offers: MAX_OFFERS - SOLD,
+    funds: K / (T - SOLD) - K / T,
+    lastPrice: (K / (T - SOLD) * ONE) / (T - SOLD),
+    K,
+    T,
+    status: 0n,
+  };
+}
+
+// ==================== Fixtures ====================
+
+// The captured buy's TokenPurchase: what it spent, paid in fees and received
+const purchase = captured && captured.logs
+  .map((log) => (log.address.toLowerCase() === FOUR_MEME_ADDRESS.toLowerCase() ? four.parseLog(log) : null))
+  .find((log) => log?.name === 'TokenPurchase');
+
+export const TOKEN_INFO: TokenInfo = captured ? decodeTokenInfo(captured.tokenInfo) : syntheticTokenInfo();
+export const TOKEN = captured?.token ?? SYNTHETIC_TOKEN;
+export const RECIPIENT = captured?.buyer ?? '0x8894e0a0c962cb723c1976a4421c95949be2d4e3';
+export const CHAIN_ID = captured ? BigInt(captured.chainId) : 56n;
+
+export const CURVE = curveParams(TOKEN, TOKEN_INFO);
+
+export const BUY_FUNDS = captured ? BigInt(captured.value) : ONE / 2n;
+export const BUY_FEE: bigint = purchase ? purchase.args.fee : BUY_FUNDS / 100n;
+export const BUY_TOKENS: bigint = purchase ? purchase.args.amount : curveBuyAmount(CURVE, BUY_FUNDS - BUY_FEE);
+export const GAS_LIMIT = captured ? BigInt(captured.gasLimit) : 280_000n;
+export const GAS_PRICE = captured ? BigInt(captured.gasPrice) : 3_000_000_000n; // 3 gwei
+
+/**
+ * Receipt logs of the buy: the token's Transfer to the buyer, then the manager's TokenPurchase
+ */
+export function buyReceipt(): TransactionReceipt {
+  if (captured) {
+    return { blockNumber: captured.blockNumber, logs: captured.logs } as unknown as TransactionReceipt;
+  }
+  const transfer = getErc20Interface().encodeEventLog('Transfer', [FOUR_MEME_ADDRESS, RECIPIENT, BUY_TOKENS]);
+  const purchaseLog = four.encodeEventLog('TokenPurchase', [
+    TOKEN,
+    RECIPIENT,
+    TOKEN_INFO.lastPrice,
+    BUY_TOKENS,
+    BUY_FUNDS - BUY_FEE,
+    BUY_FEE,
+    TOKEN_INFO.offers - BUY_TOKENS,
+    TOKEN_INFO.funds + BUY_FUNDS - BUY_FEE,
+  ]);
+  return {
+    blockNumber: 39_000_000,
+    logs: [
+      { address: TOKEN, ...transfer },
+      { address: FOUR_MEME_ADDRESS, ...purchaseLog },
+    ],
+  } as unknown as TransactionReceipt;
+}
@@ -0,0 +1,68 @@
+/**
+ * Minimal benchmark harness
+ * Runs a case for a warmup, then times every iteration with the high-resolution clock. Kept
+ * dependency-free so the budget test can run the same cases under jest.
+ */
+
+export interface BenchCase {
+  name: string;
+  budgetUs?: number; // Mean time per iteration the case must stay under; baselines have none
+  run: () => unknown | Promise<unknown>;
+}
+
+export interface BenchOptions {
+  iterations?: number; // Timed runs (default: 2000)
+  warmup?: number; // Untimed runs first, so the JIT has settled (default: iterations / 10)
+}
+
+export interface BenchResult {
+  name: string;
+  iterations: number;
+  meanUs: number;
+  p50Us: number;
+  p99Us: number;
+  budgetUs?: number;
+  withinBudget: boolean;
+}
+
+const nowUs = () => Number(process.hrtime.bigint()) / 1000;
+
+export async function runBench(bench: BenchCase, options: BenchOptions = {}): Promise<BenchResult> {
+  const iterations = options.iterations ?? 2000;
+  const warmup = options.warmup ?? Math.ceil(iterations / 10);
+  for (let i = 0; i < warmup; i++) {
+    await bench.run();
+  }
+
+  const samples: number[] = [];
+  for (let i = 0; i < iterations; i++) {
+    const start = nowUs();
+    await bench.run();
+    samples.push(nowUs() - start);
+  }
+
+  samples.sort((a, b) => a - b);
+  const meanUs = samples.reduce((sum, sample) => sum + sample, 0) / samples.length;
+  return {
+    name: bench.name,
+    iterations,
+    meanUs,
+    p50Us: samples[Math.floor(samples.length * 0.5)],
+    p99Us: samples[Math.min(samples.length - 1, Math.floor(samples.length * 0.99))],
+    budgetUs: bench.budgetUs,
+    withinBudget: bench.budgetUs === undefined || meanUs <= bench.budgetUs,
+  };
+}
+
+export function formatResults(results: BenchResult[]): string {
+  const width = Math.max(...results.map((result) => result.name.length));
+  const us = (value: number) => value.toFixed(1).padStart(9);
+  const lines = [`${'case'.padEnd(width)}  ${'mean µs'.padStart(9)}  ${'p50 µs'.padStart(9)}  ${'p99 µs'.padStart(9)}  ${'budget'.padStart(9)}`];
+  for (const result of results) {
+    lines.push(
+      `${result.name.padEnd(width)}  ${us(result.meanUs)}  ${us(result.p50Us)}  ${us(result.p99Us)}  ${result.budgetUs === undefined ? '-'.padStart(9) : us(result.budgetUs)}` +
+      (result.withinBudget ? '' : '  OVER BUDGET')
+    );
+  }
+  return lines.join('\n');
+}
@@ -0,0 +1,100 @@
+/**
+ * Benchmarks for the snipe path that needs no network: calldata encoding, slippage and bounds
+ * math, local curve quoting, signing and receipt decoding. Run with `npm run bench`; the budget
+ * test (tests/unit/hotPathBudget.test.ts) runs the same cases against their budgets.
+ *
+ * Budgets are several times the reference timings so only real regressions trip them, except
+ * encoding and signing a buy, held to the 1 ms a snipe can spend before broadcasting. Cases
+ * without a budget are baselines kept to show what a fix bought: the signature-string encode is
+ * how calldata was built before the function fragments were cached.
+ */
+
+import { ethers } from 'ethers';
+import { getFourTradingInterface } from '../src/abi';
+import { encodeBuyCalldata, encodeSellCalldata } from '../src/calldata';
+import { FOUR_MEME_ADDRESS } from '../src/constants';
+import { curveSellCost, simulateBuy } from '../src/curve';
+import { checkBuyLimits } from '../src/priceCalculator';
+import { MOCK_PRIVATE_KEY } from '../src/testing';
+import { applySlippage, parseTradeEvents, resolveMinAmount } from '../src/utils';
+import { BenchCase, BenchResult, formatResults, runBench } from './harness';
+import { BUY_FUNDS, BUY_TOKENS, CHAIN_ID, CURVE, GAS_LIMIT, GAS_PRICE, RECIPIENT, TOKEN, buyReceipt } from './fixtures';
+
+const wallet = new ethers.Wallet(MOCK_PRIVATE_KEY);
+const contract = new ethers.Contract(FOUR_MEME_ADDRESS, getFourTradingInterface());
+const receipt = buyReceipt();
+let nonce = 0;
+
+export const HOT_PATH_BENCHES: BenchCase[] = [
+  {
+    name: 'encodeBuyCalldata',
+    budgetUs: 250,
+    run: () => encodeBuyCalldata(TOKEN, RECIPIENT, BUY_FUNDS, BUY_TOKENS),
+  },
+  {
+    name: 'encodeBuyCalldata by signature (baseline)',
+    run: () =>
+      getFourTradingInterface().encodeFunctionData('buyTokenAMAP(address,address,uint256,uint256)', [
+        TOKEN,
+        RECIPIENT,
+        BUY_FUNDS,
+        BUY_TOKENS,
+      ]),
+  },
+  {
+    name: 'encodeSellCalldata',
+    budgetUs: 250,
+    run: () => encodeSellCalldata(TOKEN, BUY_TOKENS, 0n),
+  },
+  {
+    name: 'slippage and bounds',
+    budgetUs: 25,
+    run: () => {
+      checkBuyLimits(TOKEN, BUY_FUNDS, { minFunds: 10n ** 15n, maxFunds: 10n ** 20n });
+      return resolveMinAmount(applySlippage(BUY_TOKENS, 2.5), BUY_TOKENS / 2n);
+    },
+  },
+  {
+    name: 'local curve quote (buy and sell)',
+    budgetUs: 100,
+    run: () => [simulateBuy(CURVE, BUY_FUNDS, 100), curveSellCost(CURVE, BUY_TOKENS)],
+  },
+  {
+    name: 'encode and sign a buy',
+    budgetUs: 1000,
+    run: () =>
+      wallet.signTransaction({
+        type: 0,
+        to: FOUR_MEME_ADDRESS,
+        data: encodeBuyCalldata(TOKEN, RECIPIENT, BUY_FUNDS, BUY_TOKENS),
+        value: BUY_FUNDS,
+        nonce: nonce++,
+        chainId: CHAIN_ID,
+        gasLimit: GAS_LIMIT,
+        gasPrice: GAS_PRICE,
+      }),
+  },
+  {
+    name: 'parseTradeEvents on a buy receipt',
+    budgetUs: 500,
+    run: () => parseTradeEvents(receipt, contract),
+  },
+];
+
+async function main(): Promise<void> {
+  const results: BenchResult[] = [];
+  for (const bench of HOT_PATH_BENCHES) {
+    results.push(await runBench(bench));
+  }
+  console.log(formatResults(results));
+  if (results.some((result) => !result.withinBudget)) {
+    process.exitCode = 1;
+  }
+}
+
+if (require.main === module) {
+  main().catch((error) => {
+    console.error(error);
+    process.exitCode = 1;
+  });
+}
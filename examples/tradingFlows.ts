@@ -0,0 +1,27 @@
+import { ethers } from 'ethers';
+import * as dotenv from 'dotenv';
+import { FourTrading } from '../src/fourTrading';
+import { snipeOnCreateWithExit } from '../src/testing';
+
+dotenv.config();
+
+/**
+ * Run the snipe flow live: npx ts-node examples/tradingFlows.ts
+ * The flows themselves live in src/testing/tradingFlows.ts, where the integration tests run them
+ * against an in-process chain.
+ */
+const trading = new FourTrading({
+  rpcUrl: process.env.BSC_RPC_URL || 'https://bsc-dataseed.bnbchain.org',
+  wssUrl: process.env.BSC_WSS_URL || 'wss://bsc-rpc.publicnode.com',
+  privateKey: process.env.PRIVATE_KEY!,
+});
+
+snipeOnCreateWithExit(trading, {
+  fundsInBNB: ethers.parseEther('0.01'),
+  filter: (event) => event.launchFee === 0n,
+  takeProfitMultiple: 2,
+  maxHoldMs: 60_000,
+})
+  .then((result) => console.log(`Exited ${result.token} (${result.exitReason}): ${result.sell.txHash}`))
+  .catch(console.error)
+  .finally(() => trading.removeAllListeners());
@@ -0,0 +1,109 @@
+/**
+ * Captures mainnet fixtures straight from a BSC node. Run with
+ *
+ *   BSC_RPC_URL=<archive node> FIXTURE_BLOCK=<block> FIXTURE_TOKENS=<token>,<token> FIXTURE_BUY_TX=<hash> npm run capture:fixtures
+ *
+ * - tests/fixtures/tokenInfos.json: the raw `_tokenInfos` return data of each of FIXTURE_TOKENS at
+ *   FIXTURE_BLOCK; pick a token still on its curve and a graduated one. tests/unit/tokenInfo.test.ts
+ *   decodes every entry.
+ * - tests/fixtures/buy.json (with FIXTURE_BUY_TX, an AMAP buy on the four.meme manager): the buy,
+ *   its receipt logs and the token's `_tokenInfos` the block before. The hot-path benchmarks
+ *   (benches/fixtures.ts) run on it.
+ */
+
+import * as fs from 'fs';
+import * as path from 'path';
+import { ethers } from 'ethers';
+import { getFourTradingInterface } from '../src/abi';
+import { FOUR_MEME_ADDRESS } from '../src/constants';
+
+const FIXTURES = path.join(__dirname, '..', 'tests', 'fixtures');
+
+export interface CapturedTokenInfo {
+  token: string;
+  block: number;
+  returnData: string; // Undecoded `_tokenInfos(token)` result at `block`
+}
+
+export interface CapturedBuy {
+  hash: string;
+  token: string;
+  buyer: string;
+  blockNumber: number;
+  tokenInfo: string; // Undecoded `_tokenInfos(token)` result the block before the buy
+  value: string; // Decimal wei, as are the other amounts
+  gasLimit: string;
+  gasPrice: string;
+  data: string;
+  chainId: string;
+  logs: Array<{ address: string; topics: string[]; data: string }>;
+}
+
+function required(name: string): string {
+  const value = process.env[name];
+  if (!value) {
+    throw new Error(`Set ${name}`);
+  }
+  return value;
+}
+
+const four = getFourTradingInterface();
+
+function tokenInfoAt(provider: ethers.JsonRpcProvider, token: string, blockTag: number): Promise<string> {
+  return provider.call({ to: FOUR_MEME_ADDRESS, data: four.encodeFunctionData('_tokenInfos', [token]), blockTag });
+}
+
+function write(file: string, value: unknown): void {
+  fs.mkdirSync(FIXTURES, { recursive: true });
+  fs.writeFileSync(path.join(FIXTURES, file), JSON.stringify(value, null, 2) + '\n');
+  console.log(`Wrote ${path.relative(process.cwd(), path.join(FIXTURES, file))}`);
+}
+
+async function captureBuy(provider: ethers.JsonRpcProvider, hash: string): Promise<CapturedBuy> {
+  const [tx, receipt] = await Promise.all([provider.getTransaction(hash), provider.getTransactionReceipt(hash)]);
+  if (!tx || !receipt) {
+    throw new Error(`Transaction ${hash} not found`);
+  }
+  const call = four.parseTransaction({ data: tx.data, value: tx.value });
+  if (tx.to?.toLowerCase() !== FOUR_MEME_ADDRESS.toLowerCase() || call?.name !== 'buyTokenAMAP') {
+    throw new Error(`${hash} is not an AMAP buy on the four.meme manager`);
+  }
+  const token = ethers.getAddress(call.args[0]);
+  return {
+    hash,
+    token,
+    buyer: tx.from,
+    blockNumber: receipt.blockNumber,
+    tokenInfo: await tokenInfoAt(provider, token, receipt.blockNumber - 1),
+    value: tx.value.toString(),
+    gasLimit: tx.gasLimit.toString(),
+    gasPrice: tx.gasPrice.toString(),
+    data: tx.data,
+    chainId: tx.chainId.toString(),
+    logs: receipt.logs.map((log) => ({ address: log.address, topics: [...log.topics], data: log.data })),
+  };
+}
+
+async function main(): Promise<void> {
+  const provider = new ethers.JsonRpcProvider(required('BSC_RPC_URL'));
+  const block = Number(required('FIXTURE_BLOCK'));
+  const tokens = required('FIXTURE_TOKENS').split(',').map((token) => ethers.getAddress(token.trim()));
+
+  const captured: CapturedTokenInfo[] = [];
+  for (const token of tokens) {
+    captured.push({ token, block, returnData: await tokenInfoAt(provider, token, block) });
+  }
+  write('tokenInfos.json', captured);
+
+  if (process.env.FIXTURE_BUY_TX) {
+    write('buy.json', await captureBuy(provider, process.env.FIXTURE_BUY_TX));
+  }
+  provider.destroy();
+}
+
+if (require.main === module) {
+  main().catch((error) => {
+    console.error(error);
+    process.exitCode = 1;
+  });
+}
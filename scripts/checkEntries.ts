@@ -0,0 +1,67 @@
+/**
+ * Type-checks every entry point in package.json's `exports` on its own, with the project's
+ * compiler options. A clean full build can hide an entry point that only compiles because some
+ * other module happens to be in the program; here each one is the program's only root. Run with
+ * `npm run check:entries`; exits non-zero on the first entry point with errors reported.
+ */
+
+import * as fs from 'fs';
+import * as path from 'path';
+import * as ts from 'typescript';
+
+const ROOT = path.join(__dirname, '..');
+
+/**
+ * Source file of each subpath export, keyed by subpath, with the main entry point as '.'
+ */
+function entryPoints(): Map<string, string> {
+  const pkg = JSON.parse(fs.readFileSync(path.join(ROOT, 'package.json'), 'utf8'));
+  const entries = new Map<string, string>();
+  for (const [subpath, target] of Object.entries(pkg.exports as Record<string, any>)) {
+    if (typeof target !== 'object' || subpath.includes('*')) {
+      continue;
+    }
+    const source = target.default.replace('./dist/', 'src/').replace(/\.js$/, '.ts');
+    entries.set(subpath, path.join(ROOT, source));
+  }
+  return entries;
+}
+
+function compilerOptions(): ts.CompilerOptions {
+  const configPath = path.join(ROOT, 'tsconfig.json');
+  const { config, error } = ts.readConfigFile(configPath, ts.sys.readFile);
+  if (error) {
+    throw new Error(ts.flattenDiagnosticMessageText(error.messageText, '\n'));
+  }
+  const parsed = ts.parseJsonConfigFileContent(config, ts.sys, ROOT);
+  return { ...parsed.options, noEmit: true, declaration: false, declarationMap: false };
+}
+
+function main(): void {
+  const options = compilerOptions();
+  const host: ts.FormatDiagnosticsHost = {
+    getCanonicalFileName: (fileName) => fileName,
+    getCurrentDirectory: () => ROOT,
+    getNewLine: () => '\n',
+  };
+  let failed = 0;
+
+  for (const [subpath, entry] of entryPoints()) {
+    const program = ts.createProgram([entry], options);
+    const diagnostics = ts.getPreEmitDiagnostics(program);
+    if (diagnostics.length === 0) {
+      console.log(`ok   ${subpath}`);
+      continue;
+    }
+    failed++;
+    console.log(`FAIL ${subpath}`);
+    console.log(ts.formatDiagnostics(diagnostics, host));
+  }
+
+  if (failed > 0) {
+    console.error(`${failed} entry point(s) failed to type-check on their own`);
+    process.exit(1);
+  }
+}
+
+main();
@@ -1,3 +1,5 @@
+import { Interface } from 'ethers';
+
 /**
  * Complete ABI for FOUR Trading Platform Contract
  * Contract: 0x5c952063c7fc8610FFDB798152D69F0B9550762b
@@ -97,3 +99,142 @@ export const FOUR_TRADING_ABI = [
   "event TradeStop(address token)",
   "event Upgraded(address indexed implementation)"
 ] as const;
+
+/**
+ * Minimal PancakeSwap V2 router and factory ABIs used for graduated tokens
+ */
+export const PANCAKE_ROUTER_ABI = [
+  "function getAmountsOut(uint256 amountIn, address[] path) view returns (uint256[] amounts)",
+  "function WETH() view returns (address)",
+] as const;
+
+export const PANCAKE_FACTORY_ABI = [
+  "function getPair(address tokenA, address tokenB) view returns (address pair)",
+] as const;
+
+/**
+ * Minimal ERC20 ABI used for approvals and balance reads
+ */
+export const ERC20_ABI = [
+  "function approve(address spender, uint256 amount) returns (bool)",
+  "function balanceOf(address owner) view returns (uint256)",
+  "function allowance(address owner, address spender) view returns (uint256)",
+  "function transfer(address to, uint256 amount) returns (bool)",
+  "function totalSupply() view returns (uint256)",
+  "event Transfer(address indexed from, address indexed to, uint256 value)",
+] as const;
+
+/**
+ * Multicall3 aggregate3, for batching reads into one eth_call
+ */
+export const MULTICALL3_ABI = [
+  "function aggregate3(tuple(address target, bool allowFailure, bytes callData)[] calls) payable returns (tuple(bool success, bytes returnData)[] returnData)",
+] as const;
+
+/**
+ * The V1 TokenManager's trading methods; V1 tokens can only be traded through it
+ */
+export const TOKEN_MANAGER_V1_ABI = [
+  "function purchaseTokenAMAP(uint256 origin, address token, address to, uint256 funds, uint256 minAmount) payable",
+  "function purchaseToken(uint256 origin, address token, address to, uint256 amount, uint256 maxFunds) payable",
+  "function saleToken(address token, uint256 amount)",
+] as const;
+
+/**
+ * TokenManagerHelper3, which reports the manager version and address handling a token and quotes
+ * trades on either version
+ */
+export const TOKEN_MANAGER_HELPER_ABI = [
+  "function getTokenInfo(address token) view returns (uint256 version, address tokenManager, address quote, uint256 lastPrice, uint256 tradingFeeRate, uint256 minTradingFee, uint256 launchTime, uint256 offers, uint256 maxOffers, uint256 funds, uint256 maxFunds, bool liquidityAdded)",
+  "function tryBuy(address token, uint256 amount, uint256 funds) view returns (address tokenManager, address quote, uint256 estimatedAmount, uint256 estimatedCost, uint256 estimatedFee, uint256 amountMsgValue, uint256 amountApproval, uint256 amountFunds)",
+  "function trySell(address token, uint256 amount) view returns (address tokenManager, address quote, uint256 funds, uint256 fee)",
+] as const;
+
+export const WBNB_ABI = [
+  "function balanceOf(address owner) view returns (uint256)",
+  "function deposit() payable",
+  "function withdraw(uint256 wad)",
+] as const;
+
+export const CHAINLINK_AGGREGATOR_ABI = [
+  "function decimals() view returns (uint8)",
+  "function latestRoundData() view returns (uint80 roundId, int256 answer, uint256 startedAt, uint256 updatedAt, uint80 answeredInRound)",
+] as const;
+
+/**
+ * Per-transaction sell caps some anti-dump tokens expose; each token implements at most a few
+ */
+export const SELL_LIMIT_ABI = [
+  "function maxSellAmount() view returns (uint256)",
+  "function maxTxAmount() view returns (uint256)",
+  "function _maxTxAmount() view returns (uint256)",
+  "function maxTransactionAmount() view returns (uint256)",
+] as const;
+
+/**
+ * EIP-2612 permit, for approvals signed off-chain by the holder
+ */
+export const PERMIT_ABI = [
+  "function name() view returns (string)",
+  "function version() view returns (string)",
+  "function nonces(address owner) view returns (uint256)",
+  "function DOMAIN_SEPARATOR() view returns (bytes32)",
+  "function permit(address owner, address spender, uint256 value, uint256 deadline, uint8 v, bytes32 r, bytes32 s)",
+] as const;
+
+// Parsed interfaces, built once on first use and shared by every contract instance
+let fourTradingInterface: Interface | undefined;
+let erc20Interface: Interface | undefined;
+let pancakeRouterInterface: Interface | undefined;
+let pancakeFactoryInterface: Interface | undefined;
+let multicall3Interface: Interface | undefined;
+let tokenManagerV1Interface: Interface | undefined;
+let tokenManagerHelperInterface: Interface | undefined;
+let wbnbInterface: Interface | undefined;
+let chainlinkAggregatorInterface: Interface | undefined;
+let sellLimitInterface: Interface | undefined;
+let permitInterface: Interface | undefined;
+
+export function getFourTradingInterface(): Interface {
+  return (fourTradingInterface ??= new Interface(FOUR_TRADING_ABI));
+}
+
+export function getErc20Interface(): Interface {
+  return (erc20Interface ??= new Interface(ERC20_ABI));
+}
+
+export function getPancakeRouterInterface(): Interface {
+  return (pancakeRouterInterface ??= new Interface(PANCAKE_ROUTER_ABI));
+}
+
+export function getPancakeFactoryInterface(): Interface {
+  return (pancakeFactoryInterface ??= new Interface(PANCAKE_FACTORY_ABI));
+}
+
+export function getMulticall3Interface(): Interface {
+  return (multicall3Interface ??= new Interface(MULTICALL3_ABI));
+}
+
+export function getTokenManagerV1Interface(): Interface {
+  return (tokenManagerV1Interface ??= new Interface(TOKEN_MANAGER_V1_ABI));
+}
+
+export function getTokenManagerHelperInterface(): Interface {
+  return (tokenManagerHelperInterface ??= new Interface(TOKEN_MANAGER_HELPER_ABI));
+}
+
+export function getWbnbInterface(): Interface {
+  return (wbnbInterface ??= new Interface(WBNB_ABI));
+}
+
+export function getChainlinkAggregatorInterface(): Interface {
+  return (chainlinkAggregatorInterface ??= new Interface(CHAINLINK_AGGREGATOR_ABI));
+}
+
+export function getSellLimitInterface(): Interface {
+  return (sellLimitInterface ??= new Interface(SELL_LIMIT_ABI));
+}
+
+export function getPermitInterface(): Interface {
+  return (permitInterface ??= new Interface(PERMIT_ABI));
+}
@@ -0,0 +1,68 @@
+/**
+ * EIP-2930 access lists
+ * Pre-declares the storage a transaction touches so it is charged the warm rate on access
+ */
+
+import { AccessList, accessListify } from 'ethers';
+
+// EIP-2929 / EIP-2930 gas schedule
+export const COLD_ACCOUNT_ACCESS_GAS = 2600n;
+export const COLD_SLOAD_GAS = 2100n;
+export const WARM_ACCESS_GAS = 100n;
+export const ACCESS_LIST_ADDRESS_GAS = 2400n;
+export const ACCESS_LIST_STORAGE_KEY_GAS = 1900n;
+
+export interface AccessListRequest {
+  from: string;
+  to: string;
+  data: string;
+  value?: bigint;
+}
+
+/**
+ * Minimal JSON-RPC surface (satisfied by ethers' JsonRpcProvider)
+ */
+export interface AccessListRpc {
+  send(method: string, params: unknown[]): Promise<any>;
+}
+
+export interface AccessListResult {
+  accessList: AccessList;
+  gasUsed: bigint; // Node's gas estimate with the access list attached
+}
+
+/**
+ * Ask the node for the transaction's access list via eth_createAccessList.
+ * Returns null when the node doesn't support the method or the call fails, so callers
+ * can send the transaction without one.
+ */
+export async function createAccessList(rpc: AccessListRpc, request: AccessListRequest): Promise<AccessListResult | null> {
+  const tx: Record<string, string> = { from: request.from, to: request.to, data: request.data };
+  if (request.value !== undefined) {
+    tx.value = '0x' + request.value.toString(16);
+  }
+
+  try {
+    const result = await rpc.send('eth_createAccessList', [tx, 'latest']);
+    if (!result || !Array.isArray(result.accessList) || result.error) {
+      return null;
+    }
+    return { accessList: accessListify(result.accessList), gasUsed: BigInt(result.gasUsed ?? 0) };
+  } catch {
+    return null;
+  }
+}
+
+/**
+ * Gas saved by an access list whose entries are each accessed once: every listed address
+ * and slot is paid at the list rate plus a warm access instead of the cold rate.
+ * Negative when the list includes entries the transaction never reads.
+ */
+export function accessListSavings(accessList: AccessList): bigint {
+  let saved = 0n;
+  for (const entry of accessList) {
+    saved += COLD_ACCOUNT_ACCESS_GAS - (ACCESS_LIST_ADDRESS_GAS + WARM_ACCESS_GAS);
+    saved += BigInt(entry.storageKeys.length) * (COLD_SLOAD_GAS - (ACCESS_LIST_STORAGE_KEY_GAS + WARM_ACCESS_GAS));
+  }
+  return saved;
+}
@@ -0,0 +1,46 @@
+/**
+ * API: token research and reporting
+ * Imported separately (`@fnzero/four-trading-sdk/api`) by screeners, dashboards and HTTP services
+ * that answer questions about tokens, creators and past trades without trading.
+ *
+ * Token discovery, verification, holder concentration, symbol collisions, creation-block and
+ * funding analysis, creator reputation, USD conversion and trade performance reports. Chain reads
+ * go through the source interfaces each helper takes; nothing here connects or signs, and
+ * `attach(trader)` only takes the trader by type.
+ */
+
+export { TokenInfo, TokenStatus, TokenCreateEvent } from '../types';
+export {
+  TokenDirectory,
+  TokenDirectoryCursor,
+  TokenDirectoryOptions,
+  TokenDirectoryPage,
+  TokenDirectorySource,
+  TokenSummary,
+  TokenCreationRecord,
+  DirectoryLog,
+} from '../tokenDirectory';
+export { TokenVerificationStatus, VerificationReport, classifyToken, isRegisteredTokenInfo } from '../tokenVerification';
+export { HolderStats, HolderBalance, computeHolderStats } from '../holders';
+export { TokenMatch, CollisionReason, HOMOGLYPHS, normalizeTokenText, editDistance, matchCollision, findCollisions } from '../symbolCollisions';
+export { TokenCreation, TokenCreateLogSource, findTokenCreationBlock } from '../tokenAge';
+export { BlockTransaction, CreationBlockSource, CreationBlockBuy, CreationBlockReport, analyzeCreationBlock } from '../creationBlock';
+export { FundingTraceSource, FundingHop, FundingHopStatus, FundingTraceOptions, traceFirstFunding, traceFunding } from '../fundingTrace';
+export {
+  CreatorRegistry,
+  CreatorRegistryOptions,
+  CreatorRegistryStore,
+  MemoryCreatorRegistryStore,
+  CreatorTokenRecord,
+  CreatorTokenOutcome,
+  CreatorOutcomes,
+  CreatorReport,
+} from '../creatorRegistry';
+export { BnbUsdPrice, bnbUsdPriceFromRound, bnbToUsd } from '../usdPrice';
+export { CHAINLINK_AGGREGATOR_ABI, getChainlinkAggregatorInterface } from '../abi';
+export { TradeRecord } from '../tradeHistory';
+export { PerformanceBucket, performanceByHour, performanceByWeekday, performanceToJson, realizedSlippageBps } from '../tradeStats';
+export { VolumeStats, summarizeVolume } from '../volume';
+export { BlockTrade, SandwichReport, detectSandwich } from '../sandwich';
+export { Price } from '../price';
+export { FourTradingError, ValidationError, TokenCreationNotFoundError } from '../errors';
@@ -0,0 +1,137 @@
+/**
+ * Out-of-band approval of large trades (two-man rule)
+ * A StrategyRunner configured with an ApprovalGate asks it to confirm every buy above a BNB
+ * threshold before executing it. A gate that doesn't answer within the timeout rejects the trade.
+ */
+
+import { TradeIntent } from './strategy';
+
+export interface ApprovalRequest {
+  id: string; // Unique per request; replies to a ChannelApprovalGate quote it
+  intent: TradeIntent;
+  fundsInBNB: bigint; // Spend that triggered the approval
+  requestedAt: number; // Unix ms
+}
+
+export interface ApprovalDecision {
+  approved: boolean;
+  approver?: string; // Who decided, for the journal
+  reason?: string;
+}
+
+/**
+ * Decides whether a trade may go ahead. `signal` fires when the runner stops waiting (timeout),
+ * after which the decision is ignored.
+ */
+export interface ApprovalGate {
+  confirm(request: ApprovalRequest, signal: AbortSignal): Promise<ApprovalDecision>;
+}
+
+export type ApprovalOutcome = 'approved' | 'denied' | 'timedOut' | 'error' | 'skipped';
+
+/**
+ * Journal entry for one approval, including pre-authorized intents that skipped the gate
+ */
+export interface ApprovalRecord {
+  id: string;
+  tokenAddress: string;
+  fundsInBNB: bigint;
+  outcome: ApprovalOutcome;
+  approver?: string;
+  reason?: string;
+  requestedAt: number; // Unix ms
+  latencyMs: number; // Time until the decision, the timeout or the error
+}
+
+export class AutoApproveGate implements ApprovalGate {
+  async confirm(): Promise<ApprovalDecision> {
+    return { approved: true, approver: 'auto' };
+  }
+}
+
+export class DenyAllGate implements ApprovalGate {
+  constructor(private reason: string = 'all trades above the threshold are denied') {}
+
+  async confirm(): Promise<ApprovalDecision> {
+    return { approved: false, approver: 'denyAll', reason: this.reason };
+  }
+}
+
+/**
+ * Hands each request to `send` (a chat bot, a queue, a UI) and waits for `reply` with its id.
+ * Requests still waiting are listed by `pending`; the ones the runner gave up on are dropped.
+ */
+export class ChannelApprovalGate implements ApprovalGate {
+  private waiting: Map<string, { request: ApprovalRequest; resolve: (decision: ApprovalDecision) => void }> = new Map();
+
+  constructor(private send: (request: ApprovalRequest) => void | Promise<void>) {}
+
+  confirm(request: ApprovalRequest, signal: AbortSignal): Promise<ApprovalDecision> {
+    return new Promise<ApprovalDecision>((resolve, reject) => {
+      this.waiting.set(request.id, { request, resolve });
+      signal.addEventListener('abort', () => this.waiting.delete(request.id), { once: true });
+      Promise.resolve()
+        .then(() => this.send(request))
+        .catch((error) => {
+          this.waiting.delete(request.id);
+          reject(error);
+        });
+    });
+  }
+
+  /**
+   * Answer a pending request; returns false if it is unknown, answered or timed out
+   */
+  reply(id: string, decision: ApprovalDecision): boolean {
+    const entry = this.waiting.get(id);
+    if (!entry) {
+      return false;
+    }
+    this.waiting.delete(id);
+    entry.resolve(decision);
+    return true;
+  }
+
+  pending(): ApprovalRequest[] {
+    return [...this.waiting.values()].map((entry) => entry.request);
+  }
+}
+
+/**
+ * Ask `gate` about `request`, giving up after `timeoutMs`. Never throws: denials, timeouts and gate
+ * errors all come back as a record whose outcome isn't 'approved'.
+ */
+export async function requestApproval(
+  gate: ApprovalGate,
+  request: ApprovalRequest,
+  timeoutMs: number,
+  now: () => number = Date.now
+): Promise<ApprovalRecord> {
+  const controller = new AbortController();
+  let timer: ReturnType<typeof setTimeout> | undefined;
+  const timedOut = new Promise<'timedOut'>((resolve) => {
+    timer = setTimeout(() => resolve('timedOut'), timeoutMs);
+  });
+  const base = { id: request.id, tokenAddress: request.intent.tokenAddress, fundsInBNB: request.fundsInBNB, requestedAt: request.requestedAt };
+
+  try {
+    const decision = await Promise.race([gate.confirm(request, controller.signal), timedOut]);
+    const latencyMs = now() - request.requestedAt;
+    if (decision === 'timedOut') {
+      controller.abort();
+      return { ...base, outcome: 'timedOut', reason: `no decision within ${timeoutMs}ms`, latencyMs };
+    }
+    return {
+      ...base,
+      outcome: decision.approved ? 'approved' : 'denied',
+      approver: decision.approver,
+      reason: decision.reason,
+      latencyMs,
+    };
+  } catch (error) {
+    controller.abort();
+    return { ...base, outcome: 'error', reason: error instanceof Error ? error.message : String(error), latencyMs: now() - request.requestedAt };
+  } finally {
+    clearTimeout(timer);
+  }
+}
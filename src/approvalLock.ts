@@ -0,0 +1,117 @@
+/**
+ * Approval coordination for auto-approving sells
+ * Two sells of a token whose allowance is short would each send an approval, the second wasting
+ * gas or racing the first's nonce. An ApprovalLock lets one approval per wallet, token and spender
+ * be in flight; other sells wait for it to settle and re-read the allowance. Trader handles share
+ * a lock (by default the process-wide sharedApprovalLock), so clones of a handle coordinate too.
+ */
+
+import { TransactionReceipt } from 'ethers';
+
+/**
+ * An approval someone holds the lock for: every hash sent for its nonce, and when it settles
+ */
+export class InFlightApproval {
+  readonly hashes: string[] = []; // The approval, then any same-nonce speed-ups, oldest first
+  readonly settled: Promise<void>; // Resolves once the holder releases, whether the approval mined or failed; never rejects
+  readonly replacementMined: Promise<TransactionReceipt>; // Resolves when a waiter's speed-up mines in place of the holder's send
+  private resolveSettled!: () => void;
+  private resolveReplacement!: (receipt: TransactionReceipt) => void;
+  private speedingUp = false;
+
+  constructor() {
+    this.settled = new Promise((resolve) => (this.resolveSettled = resolve));
+    this.replacementMined = new Promise((resolve) => (this.resolveReplacement = resolve));
+  }
+
+  sent(txHash: string): void {
+    this.hashes.push(txHash);
+  }
+
+  /**
+   * Claim the speed-up of this approval once it outlives the wait timeout. Only the first waiter
+   * to time out gets it; the others keep waiting instead of repricing the same nonce again.
+   */
+  claimSpeedUp(): boolean {
+    if (this.speedingUp) {
+      return false;
+    }
+    this.speedingUp = true;
+    return true;
+  }
+
+  /**
+   * Give up a claimed speed-up that failed, so the next waiter to time out may try
+   */
+  releaseSpeedUp(): void {
+    this.speedingUp = false;
+  }
+
+  /**
+   * Hand the holder the receipt of a speed-up that mined for this approval's nonce
+   */
+  replaced(receipt: TransactionReceipt): void {
+    this.resolveReplacement(receipt);
+  }
+
+  /**
+   * Whether the approval settles within `timeoutMs`
+   */
+  settledWithin(timeoutMs: number): Promise<boolean> {
+    let timer: NodeJS.Timeout | undefined;
+    const expired = new Promise<boolean>((resolve) => (timer = setTimeout(() => resolve(false), timeoutMs)));
+    return Promise.race([this.settled.then(() => true), expired]).finally(() => clearTimeout(timer));
+  }
+
+  /**
+   * Wake the waiters; the lock calls this on release
+   */
+  settle(): void {
+    this.resolveSettled();
+  }
+}
+
+export interface ApprovalClaim {
+  approval: InFlightApproval;
+  release(): void; // Free the key and wake the waiters; call once the approval mined or failed
+}
+
+export class ApprovalLock {
+  private inFlight: Map<string, InFlightApproval> = new Map();
+
+  static key(wallet: string, tokenAddress: string, spender: string): string {
+    return [wallet, tokenAddress, spender].map((address) => address.toLowerCase()).join(':');
+  }
+
+  /**
+   * Claim `key` for an approval; undefined while another approval holds it
+   */
+  acquire(key: string): ApprovalClaim | undefined {
+    if (this.inFlight.has(key)) {
+      return undefined;
+    }
+    const approval = new InFlightApproval();
+    this.inFlight.set(key, approval);
+    return {
+      approval,
+      release: () => {
+        if (this.inFlight.get(key) === approval) {
+          this.inFlight.delete(key);
+        }
+        approval.settle();
+      },
+    };
+  }
+
+  /**
+   * The approval holding `key`, if any
+   */
+  current(key: string): InFlightApproval | undefined {
+    return this.inFlight.get(key);
+  }
+}
+
+/**
+ * Lock every FourTrading shares unless configured with its own
+ */
+export const sharedApprovalLock = new ApprovalLock();
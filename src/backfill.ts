@@ -0,0 +1,207 @@
+/**
+ * Multi-token trade event backfill
+ * Pages getLogs over a block range once for all tokens and demuxes events by token client-side
+ */
+
+import { Interface } from 'ethers';
+import { getFourTradingInterface } from './abi';
+import { BACKFILL_PAGE_SIZE, BATCH_DELAY } from './constants';
+import { TokenPurchaseEvent, TokenSaleEvent } from './types';
+
+export interface BackfillLog {
+  topics: readonly string[];
+  data: string;
+  blockNumber: number;
+  transactionHash: string;
+}
+
+/**
+ * Fetches the contract's TokenPurchase/TokenSale logs for an inclusive block range
+ */
+export type BackfillLogSource = (fromBlock: number, toBlock: number) => Promise<BackfillLog[]>;
+
+export interface BackfillEvent {
+  side: 'buy' | 'sell';
+  event: TokenPurchaseEvent | TokenSaleEvent;
+  blockNumber: number;
+  txHash: string;
+}
+
+/**
+ * Trade of a TokenPurchase or TokenSale log, or null for any other log
+ */
+export function parseTradeLog(log: BackfillLog, iface: Interface = getFourTradingInterface()): BackfillEvent | null {
+  let parsed;
+  try {
+    parsed = iface.parseLog({ topics: [...log.topics], data: log.data });
+  } catch {
+    return null;
+  }
+  if (!parsed || (parsed.name !== 'TokenPurchase' && parsed.name !== 'TokenSale')) {
+    return null;
+  }
+
+  return {
+    side: parsed.name === 'TokenPurchase' ? 'buy' : 'sell',
+    event: {
+      token: parsed.args.token,
+      account: parsed.args.account,
+      price: parsed.args.price,
+      amount: parsed.args.amount,
+      cost: parsed.args.cost,
+      fee: parsed.args.fee,
+      offers: parsed.args.offers,
+      funds: parsed.args.funds,
+    },
+    blockNumber: log.blockNumber,
+    txHash: log.transactionHash,
+  };
+}
+
+/**
+ * Receives each token's events as soon as a range completes
+ */
+export interface BackfillSink {
+  onEvents(token: string, events: BackfillEvent[]): void | Promise<void>;
+}
+
+export interface BackfillOptions {
+  toBlock: number; // Inclusive end of the backfill
+  pageSize?: number; // Blocks per getLogs query (default: BACKFILL_PAGE_SIZE)
+  requestIntervalMs?: number; // Minimum delay between getLogs queries (default: BATCH_DELAY)
+  sink?: BackfillSink; // Forward events instead of keeping them in memory
+  signal?: AbortSignal; // Same as cancel(): stops before the next range, events so far are kept
+}
+
+export interface BackfillProgress {
+  fromBlock: number; // Range just completed (inclusive)
+  toBlock: number;
+  rangesCompleted: number;
+  rangesTotal: number;
+  eventsPerToken: Record<string, number>; // Cumulative, keyed by lowercase token address
+  estimatedRemainingMs: number;
+  cursor: number; // Next block to fetch; pass as fromBlock to resume
+}
+
+/**
+ * A running backfill. Iterate it to drive the queries and receive progress;
+ * stopping early (break or cancel) leaves `cursor` at the first unfetched block.
+ */
+export class BackfillJob implements AsyncIterable<BackfillProgress> {
+  private tokens: Map<string, string>;
+  private results: Map<string, BackfillEvent[]> = new Map();
+  private counts: Record<string, number> = {};
+  private nextBlock: number;
+  private cancelled = false;
+  private iface: Interface;
+
+  constructor(
+    tokens: string[],
+    fromBlock: number,
+    private fetchLogs: BackfillLogSource,
+    private options: BackfillOptions
+  ) {
+    this.tokens = new Map(tokens.map((token) => [token.toLowerCase(), token]));
+    for (const key of this.tokens.keys()) {
+      this.results.set(key, []);
+      this.counts[key] = 0;
+    }
+    this.nextBlock = fromBlock;
+    this.iface = getFourTradingInterface();
+  }
+
+  /**
+   * First block not yet fetched
+   */
+  get cursor(): number {
+    return this.nextBlock;
+  }
+
+  get done(): boolean {
+    return this.nextBlock > this.options.toBlock;
+  }
+
+  /**
+   * Stop before the next range; the range in flight completes first
+   */
+  cancel(): void {
+    this.cancelled = true;
+  }
+
+  /**
+   * Events collected so far for a token (empty when a sink is used)
+   */
+  getEvents(token: string): BackfillEvent[] {
+    return [...(this.results.get(token.toLowerCase()) ?? [])];
+  }
+
+  async *[Symbol.asyncIterator](): AsyncIterator<BackfillProgress> {
+    const pageSize = Math.max(1, this.options.pageSize ?? BACKFILL_PAGE_SIZE);
+    const interval = this.options.requestIntervalMs ?? BATCH_DELAY;
+    const startBlock = this.nextBlock;
+    const rangesTotal = Math.max(0, Math.ceil((this.options.toBlock - startBlock + 1) / pageSize));
+    const startedAt = Date.now();
+    let rangesCompleted = 0;
+    let lastRequestAt = 0;
+
+    while (!this.cancelled && !this.options.signal?.aborted && !this.done) {
+      const wait = lastRequestAt + interval - Date.now();
+      if (wait > 0) {
+        await new Promise((resolve) => setTimeout(resolve, wait));
+      }
+
+      const fromBlock = this.nextBlock;
+      const toBlock = Math.min(fromBlock + pageSize - 1, this.options.toBlock);
+      lastRequestAt = Date.now();
+      const logs = await this.fetchLogs(fromBlock, toBlock);
+
+      await this.deliver(this.demux(logs));
+      this.nextBlock = toBlock + 1;
+      rangesCompleted++;
+
+      const elapsed = Date.now() - startedAt;
+      yield {
+        fromBlock,
+        toBlock,
+        rangesCompleted,
+        rangesTotal,
+        eventsPerToken: { ...this.counts },
+        estimatedRemainingMs: Math.round((elapsed / rangesCompleted) * (rangesTotal - rangesCompleted)),
+        cursor: this.nextBlock,
+      };
+    }
+  }
+
+  private demux(logs: BackfillLog[]): Map<string, BackfillEvent[]> {
+    const byToken: Map<string, BackfillEvent[]> = new Map();
+
+    for (const log of logs) {
+      const trade = parseTradeLog(log, this.iface);
+      if (!trade) {
+        continue;
+      }
+
+      const key = String(trade.event.token).toLowerCase();
+      if (!this.tokens.has(key)) {
+        continue;
+      }
+
+      const entries = byToken.get(key) ?? [];
+      entries.push(trade);
+      byToken.set(key, entries);
+    }
+
+    return byToken;
+  }
+
+  private async deliver(byToken: Map<string, BackfillEvent[]>): Promise<void> {
+    for (const [key, events] of byToken) {
+      this.counts[key] += events.length;
+      if (this.options.sink) {
+        await this.options.sink.onEvents(this.tokens.get(key)!, events);
+      } else {
+        this.results.get(key)!.push(...events);
+      }
+    }
+  }
+}
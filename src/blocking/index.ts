@@ -0,0 +1,159 @@
+/**
+ * Synchronous facade over FourTrading for scripts
+ * Imported separately (`@fnzero/four-trading-sdk/blocking`) so the main entry point never loads worker_threads
+ */
+
+import * as fs from 'fs';
+import * as path from 'path';
+import { MessageChannel, MessagePort, Worker, isMainThread, receiveMessageOnPort, workerData } from 'worker_threads';
+import { FourTradingConfig, BuyParams, SellParams, GasOptions } from '../fourTrading';
+import { PriceInfo, QuoteOptions } from '../priceCalculator';
+import { Price } from '../price';
+import { TokenInfo, TransactionResult } from '../types';
+import * as errors from '../errors';
+import { BlockingMethod, WorkerInit, WorkerResponse, deserializeError, takeResponse } from './protocol';
+
+export interface BlockingTraderOptions {
+  callTimeoutMs?: number; // Maximum time a single call may block (default: 5 minutes); the worker keeps running a call that timed out
+}
+
+const DEFAULT_CALL_TIMEOUT = 300000;
+
+function reviveQuote(quote: PriceInfo): PriceInfo {
+  return { ...quote, price: new Price(quote.price.numerator, quote.price.denominator) };
+}
+
+function reviveResult(result: TransactionResult): TransactionResult {
+  return result.price ? { ...result, price: new Price(result.price.numerator, result.price.denominator) } : result;
+}
+
+/**
+ * Compiled builds run worker.js; under ts-node/ts-jest the TypeScript source is loaded via ts-node
+ */
+function createWorker(init: WorkerInit): Worker {
+  const compiled = path.join(__dirname, 'worker.js');
+  const options = { workerData: init, transferList: [init.port] };
+
+  if (fs.existsSync(compiled)) {
+    return new Worker(compiled, options);
+  }
+  return new Worker(path.join(__dirname, 'worker.ts'), { ...options, execArgv: ['-r', 'ts-node/register/transpile-only'] });
+}
+
+/**
+ * Blocking wrapper around FourTrading. A worker thread owns the async trader and its
+ * providers; each call blocks the calling thread until the worker answers.
+ * Errors are the same classes the async API throws. Call `close` when done.
+ *
+ * A call that times out is not cancelled: the worker finishes it, so a timed-out buy or sell may
+ * still land on chain without a result reaching the caller. Check balances before retrying one.
+ */
+export class BlockingTrader {
+  private worker: Worker;
+  private port: MessagePort;
+  private flag: Int32Array;
+  private nextId = 0;
+  private closed = false;
+  private callTimeoutMs: number;
+
+  constructor(config: Omit<FourTradingConfig, 'provider' | 'eventProvider'>, options: BlockingTraderOptions = {}) {
+    // A blocking call made from the worker that serves it would wait on itself forever
+    if (!isMainThread && (workerData as WorkerInit | undefined)?.signal instanceof SharedArrayBuffer) {
+      throw new errors.FourTradingError(
+        'BlockingTrader cannot be created inside a BlockingTrader worker',
+        'BLOCKING_CONTEXT'
+      );
+    }
+
+    this.callTimeoutMs = options.callTimeoutMs ?? DEFAULT_CALL_TIMEOUT;
+    const signal = new SharedArrayBuffer(4);
+    this.flag = new Int32Array(signal);
+
+    const channel = new MessageChannel();
+    this.port = channel.port1;
+    this.worker = createWorker({ config, signal, port: channel.port2 });
+    this.worker.unref();
+    this.port.unref();
+  }
+
+  getTokenInfo(tokenAddress: string): TokenInfo {
+    return this.call('getTokenInfo', [tokenAddress]) as TokenInfo;
+  }
+
+  quoteBuy(tokenAddress: string, bnbAmount: bigint, options: QuoteOptions = {}): PriceInfo {
+    return reviveQuote(this.call('quoteBuy', [tokenAddress, bnbAmount, options]) as PriceInfo);
+  }
+
+  quoteSell(tokenAddress: string, tokenAmount: bigint, options: QuoteOptions = {}): PriceInfo {
+    return reviveQuote(this.call('quoteSell', [tokenAddress, tokenAmount, options]) as PriceInfo);
+  }
+
+  /**
+   * Same as FourTrading.buyToken; `receipt` in the result is plain JSON (bigints as strings)
+   */
+  buyToken(params: BuyParams): TransactionResult {
+    return reviveResult(this.call('buyToken', [params]) as TransactionResult);
+  }
+
+  /**
+   * Same as FourTrading.sellToken; `receipt` in the result is plain JSON (bigints as strings)
+   */
+  sellToken(params: SellParams): TransactionResult {
+    return reviveResult(this.call('sellToken', [params]) as TransactionResult);
+  }
+
+  getTokenBalance(tokenAddress: string): string {
+    return this.call('getTokenBalance', [tokenAddress]) as string;
+  }
+
+  approveToken(tokenAddress: string, amount?: bigint, gas?: GasOptions): TransactionResult {
+    return this.call('approveToken', [tokenAddress, amount, gas]) as TransactionResult;
+  }
+
+  /**
+   * Shut the worker down, removing its subscriptions first. Safe to call more than once.
+   */
+  close(): void {
+    if (this.closed) {
+      return;
+    }
+    try {
+      this.call('shutdown', []);
+    } catch {
+      // The worker may already be gone; terminate below regardless
+    }
+    this.closed = true;
+    this.port.close();
+    void this.worker.terminate();
+  }
+
+  private call(method: BlockingMethod | 'shutdown', args: unknown[]): unknown {
+    if (this.closed) {
+      throw new errors.FourTradingError('BlockingTrader is closed', 'BLOCKING_CLOSED');
+    }
+
+    const id = ++this.nextId;
+    Atomics.store(this.flag, 0, 0);
+    this.port.postMessage({ id, method, args });
+
+    // A late response to an earlier, timed-out call can wake us first; skip it and wait on
+    const deadline = Date.now() + this.callTimeoutMs;
+    for (;;) {
+      const response = takeResponse(() => receiveMessageOnPort(this.port)?.message as WorkerResponse | undefined, id);
+      if (response) {
+        if (!response.ok) {
+          throw deserializeError(response.error);
+        }
+        return response.value;
+      }
+
+      const remaining = deadline - Date.now();
+      if (remaining <= 0 || Atomics.wait(this.flag, 0, 0, remaining) === 'timed-out') {
+        throw new errors.ConnectionError(
+          `Blocking call ${method} timed out after ${this.callTimeoutMs}ms; the worker may still complete it`
+        );
+      }
+      Atomics.store(this.flag, 0, 0);
+    }
+  }
+}
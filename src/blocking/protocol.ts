@@ -0,0 +1,94 @@
+/**
+ * Messages exchanged between BlockingTrader and its worker
+ */
+
+import { FourTradingConfig } from '../fourTrading';
+import * as errors from '../errors';
+
+export const BLOCKING_METHODS = [
+  'getTokenInfo',
+  'quoteBuy',
+  'quoteSell',
+  'buyToken',
+  'sellToken',
+  'getTokenBalance',
+  'approveToken',
+] as const;
+
+export type BlockingMethod = typeof BLOCKING_METHODS[number];
+
+export interface WorkerInit {
+  config: Omit<FourTradingConfig, 'provider' | 'eventProvider'>;
+  signal: SharedArrayBuffer; // Int32 slot the worker sets to 1 when a response is posted
+  port: MessagePort;
+}
+
+export interface WorkerRequest {
+  id: number;
+  method: BlockingMethod | 'shutdown';
+  args: unknown[];
+}
+
+export interface SerializedError {
+  name: string;
+  message: string;
+  code?: string;
+  details?: unknown;
+  stack?: string;
+}
+
+export type WorkerResponse =
+  | { id: number; ok: true; value: unknown }
+  | { id: number; ok: false; error: SerializedError };
+
+/**
+ * Drain responses through `receive` until the one for request `id`. Responses to earlier requests
+ * that timed out arrive late and are discarded; undefined once nothing is queued.
+ */
+export function takeResponse(receive: () => WorkerResponse | undefined, id: number): WorkerResponse | undefined {
+  for (let response = receive(); response; response = receive()) {
+    if (response.id === id) {
+      return response;
+    }
+  }
+  return undefined;
+}
+
+/**
+ * JSON round trip with bigints as decimal strings, for values holding non-cloneable references
+ */
+export function jsonSafe(value: unknown): unknown {
+  return JSON.parse(JSON.stringify(value, (_key, item) => (typeof item === 'bigint' ? item.toString() : item)));
+}
+
+export function serializeError(error: any): SerializedError {
+  return {
+    name: error?.name ?? 'Error',
+    message: error?.message ?? String(error),
+    code: error?.code,
+    details: error instanceof errors.FourTradingError && error.details !== undefined ? jsonSafe(error.details) : undefined,
+    stack: error?.stack,
+  };
+}
+
+// SDK error classes by name, so errors rebuilt on the calling thread pass the same instanceof checks
+const ERROR_CLASSES: Record<string, Function> = Object.fromEntries(
+  Object.values(errors).map((errorClass) => [errorClass.name, errorClass])
+);
+
+export function deserializeError(serialized: SerializedError): Error {
+  const errorClass = ERROR_CLASSES[serialized.name] ?? Error;
+  const error = Object.create(errorClass.prototype);
+  Object.defineProperties(error, {
+    message: { value: serialized.message, writable: true, configurable: true },
+    stack: { value: serialized.stack, writable: true, configurable: true },
+  });
+  error.name = serialized.name;
+  if (serialized.code !== undefined) {
+    error.code = serialized.code;
+  }
+  if (serialized.details !== undefined) {
+    error.details = serialized.details;
+  }
+  return error;
+}
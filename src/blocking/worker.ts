@@ -0,0 +1,55 @@
+/**
+ * Worker thread owning the async FourTrading instance behind BlockingTrader
+ */
+
+import { workerData } from 'worker_threads';
+import { FourTrading } from '../fourTrading';
+import { BLOCKING_METHODS, WorkerInit, WorkerRequest, WorkerResponse, jsonSafe, serializeError } from './protocol';
+
+const { config, signal, port } = workerData as WorkerInit;
+const flag = new Int32Array(signal);
+
+// A failed construction is reported on every call instead of leaving the caller blocked
+let trader: FourTrading | undefined;
+let initError: unknown;
+try {
+  trader = new FourTrading(config);
+} catch (error) {
+  initError = error;
+}
+
+// Transaction receipts hold a provider reference and can't cross threads as-is
+function toTransferable(value: any): unknown {
+  if (value && typeof value === 'object' && 'receipt' in value && value.receipt) {
+    return { ...value, receipt: jsonSafe(value.receipt) };
+  }
+  return value;
+}
+
+function respond(response: WorkerResponse): void {
+  port.postMessage(response);
+  Atomics.store(flag, 0, 1);
+  Atomics.notify(flag, 0);
+}
+
+port.on('message', async (request: WorkerRequest) => {
+  if (request.method === 'shutdown') {
+    trader?.removeAllListeners();
+    respond({ id: request.id, ok: true, value: undefined });
+    port.close();
+    process.exit(0);
+  }
+
+  try {
+    if (!trader) {
+      throw initError;
+    }
+    if (!(BLOCKING_METHODS as readonly string[]).includes(request.method)) {
+      throw new Error(`Unsupported blocking method ${request.method}`);
+    }
+    const value = await (trader as any)[request.method](...request.args);
+    respond({ id: request.id, ok: true, value: toTransferable(value) });
+  } catch (error) {
+    respond({ id: request.id, ok: false, error: serializeError(error) });
+  }
+});
@@ -0,0 +1,235 @@
+/**
+ * Block-time-aware scheduling
+ * One BlockClock follows the chain head for everything that waits on blocks: it holds a single
+ * heads subscription (or a poll when the source has none) and fans each new header out to its
+ * subscribers. Block times are learned from the headers seen, so "roughly N seconds from now"
+ * can be turned into a block number.
+ */
+
+import { BLOCK_CLOCK_DEFAULT_INTERVAL_MS, BLOCK_CLOCK_POLL_INTERVAL, BLOCK_CLOCK_WINDOW } from './constants';
+import type { ChainHead } from './chainHealth';
+import { CancelledError, ValidationError } from './errors';
+
+export interface BlockHeader extends ChainHead {
+  hash: string | null;
+}
+
+export type BlockHeaderListener = (header: BlockHeader) => void;
+
+/**
+ * Where a BlockClock reads the chain head from
+ */
+export interface BlockSource {
+  latest(): Promise<BlockHeader>;
+  subscribe?(listener: BlockHeaderListener): () => void; // New heads; the clock polls `latest` without it
+}
+
+export interface BlockClockOptions {
+  window?: number; // Headers the average block interval is taken over (default: BLOCK_CLOCK_WINDOW)
+  pollIntervalMs?: number; // Poll period without a heads subscription (default: BLOCK_CLOCK_POLL_INTERVAL)
+  defaultIntervalMs?: number; // Assumed block interval until two headers were seen (default: BLOCK_CLOCK_DEFAULT_INTERVAL_MS)
+}
+
+/**
+ * Average block interval (ms) across `headers`, oldest first; undefined with fewer than two blocks
+ * between them. Timestamps are whole seconds, so the span is taken end to end rather than per block.
+ */
+export function averageBlockInterval(headers: readonly ChainHead[]): number | undefined {
+  if (headers.length < 2) {
+    return undefined;
+  }
+  const first = headers[0];
+  const last = headers[headers.length - 1];
+  const blocks = last.number - first.number;
+  return blocks > 0 ? ((last.timestamp - first.timestamp) * 1000) / blocks : undefined;
+}
+
+/**
+ * Block expected at `timestamp` (Unix seconds), counting on from `head` at `intervalMs` per block.
+ * Timestamps at or before the head give the head.
+ */
+export function estimateBlockAt(head: ChainHead, intervalMs: number, timestamp: number): number {
+  if (!(intervalMs > 0)) {
+    throw new ValidationError(`Block interval must be positive, got ${intervalMs}`);
+  }
+  const ahead = timestamp - head.timestamp;
+  return ahead <= 0 ? head.number : head.number + Math.round((ahead * 1000) / intervalMs);
+}
+
+export class BlockClock {
+  private readonly window: number;
+  private readonly pollIntervalMs: number;
+  private readonly defaultIntervalMs: number;
+  private readonly listeners = new Set<BlockHeaderListener>();
+  private readonly recent: BlockHeader[] = []; // Oldest first, at most `window`
+  private stopSource?: () => void;
+
+  constructor(private readonly source: BlockSource, options: BlockClockOptions = {}) {
+    this.window = options.window ?? BLOCK_CLOCK_WINDOW;
+    this.pollIntervalMs = options.pollIntervalMs ?? BLOCK_CLOCK_POLL_INTERVAL;
+    this.defaultIntervalMs = options.defaultIntervalMs ?? BLOCK_CLOCK_DEFAULT_INTERVAL_MS;
+    if (!Number.isInteger(this.window) || this.window < 2) {
+      throw new ValidationError(`Block clock window must be an integer of at least 2, got ${this.window}`);
+    }
+  }
+
+  /**
+   * Latest header seen, if any
+   */
+  get head(): BlockHeader | undefined {
+    return this.recent[this.recent.length - 1];
+  }
+
+  /**
+   * Average block interval (ms) over the recent headers, or the default until two were seen
+   */
+  averageIntervalMs(): number {
+    return averageBlockInterval(this.recent) ?? this.defaultIntervalMs;
+  }
+
+  /**
+   * Feed a header in, as the heads subscription does. Headers not above the current head are ignored.
+   */
+  observe(header: BlockHeader): void {
+    const head = this.head;
+    if (head && header.number <= head.number) {
+      return;
+    }
+    this.recent.push(header);
+    if (this.recent.length > this.window) {
+      this.recent.shift();
+    }
+    for (const listener of [...this.listeners]) {
+      listener(header);
+    }
+  }
+
+  /**
+   * Call `listener` with every new header. The heads subscription starts with the first
+   * subscriber and stops after the last one leaves.
+   */
+  subscribe(listener: BlockHeaderListener): () => void {
+    this.listeners.add(listener);
+    this.start();
+    return () => {
+      if (this.listeners.delete(listener) && this.listeners.size === 0) {
+        this.stop();
+      }
+    };
+  }
+
+  /**
+   * New headers as an async iterable, ending when `signal` fires or the loop breaks
+   */
+  async *headers(signal?: AbortSignal): AsyncGenerator<BlockHeader> {
+    const queue: BlockHeader[] = [];
+    let wake: (() => void) | undefined;
+    const unsubscribe = this.subscribe((header) => {
+      queue.push(header);
+      wake?.();
+    });
+    const onAbort = () => wake?.();
+    signal?.addEventListener('abort', onAbort);
+    try {
+      while (!signal?.aborted) {
+        const next = queue.shift();
+        if (next) {
+          yield next;
+        } else {
+          await new Promise<void>((resolve) => (wake = resolve));
+          wake = undefined;
+        }
+      }
+    } finally {
+      signal?.removeEventListener('abort', onAbort);
+      unsubscribe();
+    }
+  }
+
+  /**
+   * Resolve with the first header at or above block `n`. Rejects with CancelledError when `signal` fires.
+   */
+  awaitBlock(n: number, signal?: AbortSignal): Promise<BlockHeader> {
+    const head = this.head;
+    if (head && head.number >= n) {
+      return Promise.resolve(head);
+    }
+
+    return new Promise<BlockHeader>((resolve, reject) => {
+      const finish = (action: () => void) => {
+        unsubscribe();
+        signal?.removeEventListener('abort', onAbort);
+        action();
+      };
+      const onAbort = () => finish(() => reject(new CancelledError('beforeBroadcast')));
+      const unsubscribe = this.subscribe((header) => {
+        if (header.number >= n) {
+          finish(() => resolve(header));
+        }
+      });
+      if (signal?.aborted) {
+        onAbort();
+        return;
+      }
+      signal?.addEventListener('abort', onAbort, { once: true });
+    });
+  }
+
+  /**
+   * Resolve once `k` blocks past the current head have been seen
+   */
+  async awaitBlocksFromNow(k: number, signal?: AbortSignal): Promise<BlockHeader> {
+    if (!Number.isInteger(k) || k < 0) {
+      throw new ValidationError(`Block count must be a non-negative integer, got ${k}`);
+    }
+    const head = await this.currentHead();
+    return await this.awaitBlock(head.number + k, signal);
+  }
+
+  /**
+   * Block expected at `timestamp` (Unix seconds) at the average interval seen so far
+   */
+  async estimateBlockAt(timestamp: number): Promise<number> {
+    return estimateBlockAt(await this.currentHead(), this.averageIntervalMs(), timestamp);
+  }
+
+  /**
+   * The head as of now: the last header seen while the heads subscription runs, otherwise read
+   * from the source, since nothing keeps the recent headers current between subscribers
+   */
+  private async currentHead(): Promise<BlockHeader> {
+    if (!this.head || !this.stopSource) {
+      this.observe(await this.source.latest());
+    }
+    return this.head!;
+  }
+
+  private start(): void {
+    if (this.stopSource) {
+      return;
+    }
+    if (this.source.subscribe) {
+      this.stopSource = this.source.subscribe((header) => this.observe(header));
+      return;
+    }
+
+    let polling = false;
+    const timer = setInterval(() => {
+      if (polling) {
+        return;
+      }
+      polling = true;
+      this.source
+        .latest()
+        .then((header) => this.observe(header))
+        .catch(() => undefined) // The next poll retries
+        .finally(() => (polling = false));
+    }, this.pollIntervalMs);
+    this.stopSource = () => clearInterval(timer);
+  }
+
+  private stop(): void {
+    this.stopSource?.();
+    this.stopSource = undefined;
+  }
+}
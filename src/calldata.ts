@@ -0,0 +1,55 @@
+/**
+ * Unsigned calldata encoding for externally signed transactions
+ * Send the result to the four.meme contract; buys must carry `fundsInBNB` as the transaction value
+ */
+
+import { FunctionFragment } from 'ethers';
+import { getFourTradingInterface } from './abi';
+
+interface TradeFragments {
+  buyTokenAMAP: FunctionFragment;
+  buyToken: FunctionFragment;
+  sellToken: FunctionFragment;
+}
+
+// Resolved once: looking a function up by signature parses the signature on every call
+let tradeFragments: TradeFragments | undefined;
+
+function getTradeFragments(): TradeFragments {
+  const iface = getFourTradingInterface();
+  return (tradeFragments ??= {
+    buyTokenAMAP: iface.getFunction('buyTokenAMAP(address,address,uint256,uint256)')!,
+    buyToken: iface.getFunction('buyToken(address,address,uint256,uint256)')!,
+    sellToken: iface.getFunction('sellToken(address,uint256,uint256)')!,
+  });
+}
+
+/**
+ * Calldata for buyTokenAMAP(token, to, funds, minAmount)
+ */
+export function encodeBuyCalldata(tokenAddress: string, recipient: string, fundsInBNB: bigint, minAmount: bigint): string {
+  return getFourTradingInterface().encodeFunctionData(
+    getTradeFragments().buyTokenAMAP,
+    [tokenAddress, recipient, fundsInBNB, minAmount]
+  );
+}
+
+/**
+ * Calldata for buyToken(token, to, amount, maxFunds); value must cover `maxFunds`
+ */
+export function encodeBuyExactCalldata(tokenAddress: string, recipient: string, amount: bigint, maxFunds: bigint): string {
+  return getFourTradingInterface().encodeFunctionData(
+    getTradeFragments().buyToken,
+    [tokenAddress, recipient, amount, maxFunds]
+  );
+}
+
+/**
+ * Calldata for sellToken(token, amount, minFunds); no value, tokens must be approved first
+ */
+export function encodeSellCalldata(tokenAddress: string, amount: bigint, minFunds: bigint): string {
+  return getFourTradingInterface().encodeFunctionData(
+    getTradeFragments().sellToken,
+    [tokenAddress, amount, minFunds]
+  );
+}
@@ -0,0 +1,55 @@
+/**
+ * Cancellation via AbortSignal
+ * Before a transaction is broadcast, cancelling aborts cleanly. After broadcast the transaction
+ * can't be recalled, so cancelling only stops the wait and hands back a PendingTrade.
+ */
+
+import { CancelledError } from './errors';
+import { PendingTrade } from './types';
+
+/**
+ * Throw CancelledError('beforeBroadcast') if `signal` has fired
+ */
+export function throwIfCancelled(signal?: AbortSignal): void {
+  if (signal?.aborted) {
+    throw new CancelledError('beforeBroadcast');
+  }
+}
+
+/**
+ * Await `waiting` unless `signal` fires first, in which case reject with
+ * CancelledError('afterBroadcast') carrying the handle from `pending`.
+ * An abandoned wait keeps running in the background; its outcome is discarded.
+ */
+export function waitUnlessCancelled<T>(
+  waiting: Promise<T>,
+  signal: AbortSignal | undefined,
+  pending: () => PendingTrade
+): Promise<T> {
+  if (!signal) {
+    return waiting;
+  }
+
+  return new Promise<T>((resolve, reject) => {
+    const onAbort = () => {
+      waiting.catch(() => undefined);
+      reject(new CancelledError('afterBroadcast', pending()));
+    };
+    if (signal.aborted) {
+      onAbort();
+      return;
+    }
+
+    signal.addEventListener('abort', onAbort, { once: true });
+    waiting.then(
+      (value) => {
+        signal.removeEventListener('abort', onAbort);
+        resolve(value);
+      },
+      (error) => {
+        signal.removeEventListener('abort', onAbort);
+        reject(error);
+      }
+    );
+  });
+}
@@ -0,0 +1,121 @@
+/**
+ * OHLCV candle aggregation from trade events
+ */
+
+import { Price } from './price';
+import { TokenPurchaseEvent, TokenSaleEvent } from './types';
+
+export interface Candle {
+  token: string;
+  openTime: number; // Interval start (ms since epoch, aligned to the interval)
+  closeTime: number; // Interval end, exclusive
+  open: bigint; // Prices are BNB per token scaled by 1e18, as emitted by trade events
+  high: bigint;
+  low: bigint;
+  close: bigint;
+  volume: bigint; // BNB traded (wei)
+  tokenVolume: bigint; // Tokens traded (wei)
+  vwap: Price; // Exact BNB per token over the interval (volume / tokenVolume); a flat candle carries the previous one's
+  trades: number; // 0 for a flat candle carried over from the previous close
+}
+
+export type CandleListener = (candle: Candle) => void;
+
+/**
+ * Aggregates one token's trades into fixed-interval candles.
+ * Intervals without trades produce a flat candle at the previous close, so the series has
+ * no gaps once the first trade has been seen.
+ */
+export class CandleAggregator {
+  private current: Candle | null = null;
+  private lastClose: bigint | null = null;
+  private lastVwap: Price = Price.ZERO;
+  private nextOpenTime: number | null = null;
+
+  constructor(private token: string, private intervalMs: number) {
+    if (!Number.isFinite(intervalMs) || intervalMs <= 0) {
+      throw new RangeError('Candle interval must be a positive number of milliseconds');
+    }
+  }
+
+  /**
+   * Add a trade made at `timestamp` (ms). Returns candles closed by moving into a later interval.
+   * A trade whose interval was already emitted counts toward the oldest interval still open.
+   */
+  addTrade(event: TokenPurchaseEvent | TokenSaleEvent, timestamp: number): Candle[] {
+    const floor = this.current?.openTime ?? this.nextOpenTime;
+    if (floor !== null && timestamp < floor) {
+      timestamp = floor;
+    }
+    const closed = this.closeUntil(timestamp);
+
+    if (!this.current) {
+      this.current = this.emptyCandle(this.alignedStart(timestamp), event.price, this.lastVwap);
+    }
+
+    const candle = this.current;
+    if (candle.trades === 0) {
+      candle.open = event.price;
+      candle.high = event.price;
+      candle.low = event.price;
+    }
+    candle.high = event.price > candle.high ? event.price : candle.high;
+    candle.low = event.price < candle.low ? event.price : candle.low;
+    candle.close = event.price;
+    candle.volume += event.cost;
+    candle.tokenVolume += event.amount;
+    candle.vwap = Price.fromAmounts(candle.volume, candle.tokenVolume);
+    candle.trades++;
+
+    return closed;
+  }
+
+  /**
+   * Close every interval that ended at or before `now` (ms), filling gaps with flat candles
+   */
+  closeUntil(now: number): Candle[] {
+    const closed: Candle[] = [];
+
+    while (true) {
+      if (!this.current) {
+        // Nothing traded yet, or the next interval hasn't ended: it opens lazily
+        if (this.lastClose === null || this.nextOpenTime === null || this.nextOpenTime + this.intervalMs > now) {
+          break;
+        }
+        this.current = this.emptyCandle(this.nextOpenTime, this.lastClose, this.lastVwap);
+      }
+
+      if (this.current.closeTime > now) {
+        break;
+      }
+
+      closed.push(this.current);
+      this.lastClose = this.current.close;
+      this.lastVwap = this.current.vwap;
+      this.nextOpenTime = this.current.closeTime;
+      this.current = null;
+    }
+
+    return closed;
+  }
+
+  private alignedStart(timestamp: number): number {
+    return timestamp - (timestamp % this.intervalMs);
+  }
+
+  private emptyCandle(openTime: number, price: bigint, vwap: Price): Candle {
+    return {
+      token: this.token,
+      openTime,
+      closeTime: openTime + this.intervalMs,
+      open: price,
+      high: price,
+      low: price,
+      close: price,
+      volume: 0n,
+      tokenVolume: 0n,
+      vwap,
+      trades: 0,
+    };
+  }
+}
@@ -0,0 +1,184 @@
+/**
+ * Node capability detection
+ * Nodes differ in what they serve beyond the core JSON-RPC methods. Each capability is probed once,
+ * on first need, and cached, so a feature missing its prerequisite fails with CapabilityMissingError
+ * instead of a confusing downstream error. Overrides replace probing for nodes that misreport.
+ * A capability is only reported missing when the node says so; a probe that fails in transit
+ * rejects and is retried on next use.
+ */
+
+import { ethers } from 'ethers';
+import { CAPABILITY_ARCHIVE_PROBE_DEPTHS, MULTICALL3_ADDRESS } from './constants';
+import { CapabilityMissingError } from './errors';
+import { isMethodUnsupportedError, isStateUnavailableError } from './utils';
+
+export interface NodeCapabilities {
+  pendingSubscription: boolean; // eth_subscribe to newPendingTransactions on the WebSocket
+  trace: boolean; // debug_traceCall
+  archiveDepth: number | null; // Blocks behind the head whose state can be read; null when all of it can
+  feeHistory: boolean; // eth_feeHistory
+  batchRequests: boolean; // JSON-RPC batches over HTTP
+  multicall: boolean; // Multicall3 deployed at MULTICALL3_ADDRESS
+}
+
+export type NodeCapability = keyof NodeCapabilities;
+
+/**
+ * Raw access to the node for the probes
+ */
+export interface CapabilityProbeSource {
+  send(method: string, params: unknown[]): Promise<any>;
+  sendBatch(requests: Array<{ method: string; params: unknown[] }>): Promise<unknown[]>; // One result per request, in order; rejects when the node refuses batches
+  subscribePending(): Promise<void>; // Subscribe to pending transactions and unsubscribe again
+}
+
+// Messages for a feature the node doesn't offer beyond a single method: batches, subscriptions
+const FEATURE_UNSUPPORTED = /not supported|unsupported|does not support|not enabled/i;
+
+/**
+ * Whether a probe's rejection is the node answering that it lacks the capability, as opposed to
+ * a timeout, dropped connection or rate limit, which says nothing about it
+ */
+export function isCapabilityAbsent(error: any): boolean {
+  if (isMethodUnsupportedError(error) || isStateUnavailableError(error)) {
+    return true;
+  }
+  for (let current = error, depth = 0; current && depth < 4; current = current.error ?? current.info?.error, depth++) {
+    if (current.code === -32600 || FEATURE_UNSUPPORTED.test(String(current.message ?? ''))) {
+      return true;
+    }
+  }
+  return false;
+}
+
+/**
+ * False for a rejection saying the capability is absent; rethrows any other
+ */
+function absent(error: unknown): false {
+  if (!isCapabilityAbsent(error)) {
+    throw error;
+  }
+  return false;
+}
+
+const succeeds = (probe: Promise<unknown>): Promise<boolean> => probe.then(() => true, absent);
+
+async function probeArchiveDepth(source: CapabilityProbeSource): Promise<number | null> {
+  const head = Number(await source.send('eth_blockNumber', []));
+  const readable = (block: number) => succeeds(source.send('eth_getBalance', [ethers.ZeroAddress, ethers.toQuantity(block)]));
+
+  let depth = 0;
+  for (const candidate of CAPABILITY_ARCHIVE_PROBE_DEPTHS) {
+    if (candidate >= head) {
+      break;
+    }
+    if (!(await readable(head - candidate))) {
+      return depth;
+    }
+    depth = candidate;
+  }
+  return (await readable(1)) ? null : depth;
+}
+
+/**
+ * One probe per capability; each resolves with what the node answered, or rejects when it got no answer
+ */
+export const CAPABILITY_PROBES: { [K in NodeCapability]: (source: CapabilityProbeSource) => Promise<NodeCapabilities[K]> } = {
+  pendingSubscription: (source) => succeeds(source.subscribePending()),
+  trace: (source) => succeeds(source.send('debug_traceCall', [{ to: ethers.ZeroAddress, data: '0x' }, 'latest', {}])),
+  archiveDepth: probeArchiveDepth,
+  feeHistory: (source) => succeeds(source.send('eth_feeHistory', ['0x1', 'latest', []])),
+  batchRequests: (source) =>
+    source
+      .sendBatch([{ method: 'eth_chainId', params: [] }, { method: 'eth_blockNumber', params: [] }])
+      .then((results) => Array.isArray(results) && results.length === 2, absent),
+  // Code '0x' is a definitive answer: nothing is deployed there
+  multicall: (source) =>
+    source.send('eth_getCode', [MULTICALL3_ADDRESS, 'latest']).then((code) => typeof code === 'string' && code !== '0x'),
+};
+
+export class CapabilityProbe {
+  private probes: Map<NodeCapability, Promise<unknown>> = new Map();
+  private settled: Map<NodeCapability, unknown> = new Map();
+
+  constructor(private source: CapabilityProbeSource, private overrides: Partial<NodeCapabilities> = {}) {}
+
+  /**
+   * A capability, probing it on first use; an override is returned without probing
+   */
+  async get<K extends NodeCapability>(capability: K): Promise<NodeCapabilities[K]> {
+    if (this.overrides[capability] !== undefined) {
+      return this.overrides[capability] as NodeCapabilities[K];
+    }
+    let probe = this.probes.get(capability);
+    if (!probe) {
+      probe = CAPABILITY_PROBES[capability](this.source).then(
+        (value) => {
+          this.settled.set(capability, value);
+          return value;
+        },
+        (error) => {
+          this.probes.delete(capability); // Not an answer about the node; probe again next time
+          throw error;
+        }
+      );
+      this.probes.set(capability, probe);
+    }
+    return (await probe) as NodeCapabilities[K];
+  }
+
+  /**
+   * A capability if already known (overridden or probed), without probing
+   */
+  known<K extends NodeCapability>(capability: K): NodeCapabilities[K] | undefined {
+    if (this.overrides[capability] !== undefined) {
+      return this.overrides[capability];
+    }
+    return this.settled.get(capability) as NodeCapabilities[K] | undefined;
+  }
+
+  /**
+   * Every capability, probing those not known yet in parallel
+   */
+  async all(): Promise<NodeCapabilities> {
+    const names = Object.keys(CAPABILITY_PROBES) as NodeCapability[];
+    const values = await Promise.all(names.map((name) => this.get(name)));
+    return Object.fromEntries(names.map((name, index) => [name, values[index]])) as unknown as NodeCapabilities;
+  }
+
+  /**
+   * Forget probed results so the next use probes again; overrides stay
+   */
+  reset(): void {
+    this.probes.clear();
+    this.settled.clear();
+  }
+
+  /**
+   * Throw CapabilityMissingError unless the node has `capability`; for archiveDepth, state at
+   * least `depth` blocks behind the head must be readable
+   */
+  async require(feature: string, capability: NodeCapability, depth: number = 0): Promise<void> {
+    const value = await this.get(capability);
+    if (!satisfies(capability, value, depth)) {
+      throw new CapabilityMissingError(feature, capability, capability === 'archiveDepth' ? depth : undefined);
+    }
+  }
+
+  /**
+   * Like require, but only against what is already known; an unprobed capability passes
+   */
+  requireKnown(feature: string, capability: NodeCapability, depth: number = 0): void {
+    const value = this.known(capability);
+    if (value !== undefined && !satisfies(capability, value, depth)) {
+      throw new CapabilityMissingError(feature, capability, capability === 'archiveDepth' ? depth : undefined);
+    }
+  }
+}
+
+function satisfies(capability: NodeCapability, value: unknown, depth: number): boolean {
+  if (capability === 'archiveDepth') {
+    return value === null || (value as number) >= depth;
+  }
+  return value === true;
+}
@@ -0,0 +1,66 @@
+/**
+ * Chain stall detection
+ * A halted chain makes gas estimates and receipt waits hang; these helpers turn that into an error
+ */
+
+import { ChainStalledError } from './errors';
+
+export interface ChainHead {
+  number: number;
+  timestamp: number; // Unix seconds
+}
+
+/**
+ * Seconds since the head block was produced (never negative, to tolerate clock skew)
+ */
+export function secondsSinceBlock(head: ChainHead, nowMs: number = Date.now()): number {
+  return Math.max(0, Math.floor(nowMs / 1000) - head.timestamp);
+}
+
+/**
+ * Throw ChainStalledError when the head block is older than `threshold` seconds
+ */
+export function assertChainProgressing(head: ChainHead, threshold: number, nowMs: number = Date.now()): void {
+  const age = secondsSinceBlock(head, nowMs);
+  if (age > threshold) {
+    throw new ChainStalledError(age, threshold, head.number);
+  }
+}
+
+/**
+ * Whether a head seen earlier still proves the chain is progressing. The latest block is at least
+ * as new, so the chain can't count as stalled before this head ages past `threshold` seconds.
+ */
+export function headProvesProgress(head: ChainHead | undefined, threshold: number, nowMs: number = Date.now()): boolean {
+  return head !== undefined && secondsSinceBlock(head, nowMs) <= threshold;
+}
+
+/**
+ * Resolve with `pending`, unless `check` throws ChainStalledError first. `check` runs every
+ * `intervalMs` while `pending` is outstanding; any other error from it is ignored.
+ */
+export function watchForStall<T>(pending: Promise<T>, check: () => Promise<void>, intervalMs: number): Promise<T> {
+  return new Promise<T>((resolve, reject) => {
+    let settled = false;
+    const finish = (action: () => void) => {
+      if (!settled) {
+        settled = true;
+        clearInterval(timer);
+        action();
+      }
+    };
+
+    const timer = setInterval(() => {
+      check().catch((error) => {
+        if (error instanceof ChainStalledError) {
+          finish(() => reject(error));
+        }
+      });
+    }, intervalMs);
+
+    pending.then(
+      (value) => finish(() => resolve(value)),
+      (error) => finish(() => reject(error))
+    );
+  });
+}
@@ -0,0 +1,159 @@
+/**
+ * Sell chunk planning
+ * Splits a sell into chunks whose price impact stays under a limit, replaying each chunk
+ * against the curve state left by the previous ones
+ */
+
+import { TokenInfo } from './types';
+import { bpsOf } from './math';
+import { curveParams, curveSellCost } from './curve';
+
+/**
+ * Gross (pre-fee) BNB returned for selling `amount` tokens against `state`
+ */
+export type SellCostFn = (state: TokenInfo, amount: bigint) => Promise<bigint>;
+
+/**
+ * SellCostFn computed on the local curve model, so replayed states need no RPC. Needs the
+ * state's K and T, which V1 tokens don't report.
+ */
+export function localSellCost(token: string): SellCostFn {
+  return async (state, amount) => curveSellCost(curveParams(token, state), amount);
+}
+
+export interface ChunkPlan {
+  // 'complete': chunks add up to the total. 'impactLimited': even the smallest allowed chunk
+  // exceeds the limit, or maxChunks was reached; `remaining` was left unplanned
+  outcome: 'complete' | 'impactLimited';
+  chunks: bigint[]; // Sell amounts in order, ready to pass to sellToken one by one
+  impactsBps: number[]; // Impact of each chunk against the spot price before it
+  remaining: bigint;
+}
+
+export interface ChunkPlanOptions {
+  maxChunks?: number; // Give up after this many chunks (default: CHUNK_PLAN_MAX_CHUNKS)
+  minChunk?: bigint; // Smallest chunk worth sending, at least 1 (default: total / maxChunks)
+}
+
+export const CHUNK_PLAN_MAX_CHUNKS = 100;
+const SPOT_PROBE_DIVISOR = 1_000_000n; // Spot price is sampled with a sell of total / divisor
+const SEARCH_STEPS = 12; // Refinement precision: chunk size / 2^steps
+
+/**
+ * Curve state after selling `amount` tokens for `grossCost` BNB
+ */
+export function applySell(state: TokenInfo, amount: bigint, grossCost: bigint): TokenInfo {
+  return {
+    ...state,
+    offers: state.offers + amount,
+    funds: state.funds > grossCost ? state.funds - grossCost : 0n,
+  };
+}
+
+/**
+ * Shortfall of `proceeds` vs selling `amount` at the spot price `spotCost / spotAmount`, in bps
+ */
+export function sellImpactBps(amount: bigint, proceeds: bigint, spotAmount: bigint, spotCost: bigint): number {
+  const atSpot = (amount * spotCost) / spotAmount;
+  if (atSpot === 0n) {
+    return 0;
+  }
+  return proceeds >= atSpot ? 0 : bpsOf(atSpot - proceeds, atSpot, 'sell impact');
+}
+
+/**
+ * Impact (bps) of trading `amount` against `state`, where `quote` gives the output: tokens for a
+ * buy's spend, BNB for a sell's tokens. Spot is sampled with a trade of amount / SPOT_PROBE_DIVISOR.
+ */
+export async function priceImpactBps(
+  state: TokenInfo,
+  amount: bigint,
+  quote: (state: TokenInfo, amount: bigint) => Promise<bigint>
+): Promise<number> {
+  const probe = amount / SPOT_PROBE_DIVISOR > 0n ? amount / SPOT_PROBE_DIVISOR : 1n;
+  const [spotOut, out] = await Promise.all([quote(state, probe), quote(state, amount)]);
+  return sellImpactBps(amount, out, probe, spotOut);
+}
+
+/**
+ * Plan chunks for selling `total` tokens so each chunk's impact stays at or under `maxImpactBps`.
+ * Each chunk is the largest that fits the limit given the state left by the earlier ones, so
+ * sizes shrink as the curve is drawn down.
+ */
+export async function planSellChunks(
+  state: TokenInfo,
+  total: bigint,
+  maxImpactBps: number,
+  sellCost: SellCostFn,
+  options: ChunkPlanOptions = {}
+): Promise<ChunkPlan> {
+  if (total <= 0n) {
+    throw new RangeError('Total amount must be positive');
+  }
+  const maxChunks = options.maxChunks ?? CHUNK_PLAN_MAX_CHUNKS;
+  if (!Number.isInteger(maxChunks) || maxChunks <= 0) {
+    throw new RangeError('maxChunks must be a positive integer');
+  }
+  if (options.minChunk !== undefined && options.minChunk <= 0n) {
+    // A zero-size chunk would always fit the limit and fill the plan with empty sells
+    throw new RangeError('minChunk must be positive');
+  }
+  const minChunk = options.minChunk ?? (total / BigInt(maxChunks) > 0n ? total / BigInt(maxChunks) : 1n);
+  const probe = total / SPOT_PROBE_DIVISOR > 0n ? total / SPOT_PROBE_DIVISOR : 1n;
+
+  const chunks: bigint[] = [];
+  const impactsBps: number[] = [];
+  let remaining = total;
+  let current = state;
+
+  while (remaining > 0n && chunks.length < maxChunks) {
+    const spotCost = await sellCost(current, probe);
+    const impactOf = async (amount: bigint) => {
+      const proceeds = await sellCost(current, amount);
+      return { proceeds, impact: sellImpactBps(amount, proceeds, probe, spotCost) };
+    };
+
+    // Whole remainder fits: finish
+    let best = await impactOf(remaining);
+    let size = remaining;
+
+    if (best.impact > maxImpactBps) {
+      // Halve until a size fits, then refine between it and the last size that didn't
+      let high = remaining;
+      let low = remaining / 2n;
+      best = { proceeds: 0n, impact: 0 };
+      while (low > 0n) {
+        const attempt = await impactOf(low);
+        if (attempt.impact <= maxImpactBps) {
+          best = attempt;
+          break;
+        }
+        high = low;
+        low /= 2n;
+      }
+
+      for (let step = 0; step < SEARCH_STEPS && low > 0n && high - low > 1n; step++) {
+        const middle = (low + high) / 2n;
+        const attempt = await impactOf(middle);
+        if (attempt.impact <= maxImpactBps) {
+          low = middle;
+          best = attempt;
+        } else {
+          high = middle;
+        }
+      }
+      size = low;
+    }
+
+    if (size < minChunk && size < remaining) {
+      break;
+    }
+
+    chunks.push(size);
+    impactsBps.push(best.impact);
+    current = applySell(current, size, best.proceeds);
+    remaining -= size;
+  }
+
+  return { outcome: remaining === 0n ? 'complete' : 'impactLimited', chunks, impactsBps, remaining };
+}
@@ -0,0 +1,109 @@
+/**
+ * Confirmation strategies
+ * Either a number of block confirmations, or BSC fast finality via the `finalized` block tag
+ */
+
+import { BalanceWaitTimeoutError, TransactionDroppedError, TransactionFailedError } from './errors';
+
+export type ConfirmationStrategy =
+  | { type: 'confirmations'; count: number }
+  | { type: 'finalized' };
+
+export const DEFAULT_CONFIRMATION: ConfirmationStrategy = { type: 'confirmations', count: 1 };
+
+/**
+ * Wait for `count` confirmations through `wait` (e.g. provider.waitForTransaction), which comes
+ * back with reverted receipts, or none, instead of throwing; both reject with TransactionFailedError
+ */
+export async function waitForConfirmations<R extends { status: number | null }>(
+  txHash: string,
+  count: number,
+  wait: (txHash: string, count: number) => Promise<R | null>
+): Promise<R> {
+  const receipt = await wait(txHash, count);
+  if (!receipt || receipt.status !== 1) {
+    throw new TransactionFailedError('Transaction reverted', txHash, { receipt });
+  }
+  return receipt;
+}
+
+/**
+ * Resolve once the finalized block reaches `blockNumber`, polling `getFinalized` every `intervalMs`
+ */
+export async function waitForFinalized(
+  blockNumber: number,
+  getFinalized: () => Promise<number>,
+  intervalMs: number
+): Promise<number> {
+  for (;;) {
+    const finalized = await getFinalized();
+    if (finalized >= blockNumber) {
+      return finalized;
+    }
+    await new Promise((resolve) => setTimeout(resolve, intervalMs));
+  }
+}
+
+/**
+ * Resolve with the first balance `readBalance` returns above `previous`, polling every `intervalMs`.
+ * Failed reads are retried, so a flaky node only delays the answer. Rejects with
+ * BalanceWaitTimeoutError when no read within `timeoutMs` showed an increase.
+ */
+export async function waitForBalanceAbove(
+  tokenAddress: string,
+  previous: bigint,
+  readBalance: () => Promise<bigint>,
+  timeoutMs: number,
+  intervalMs: number
+): Promise<bigint> {
+  const deadline = Date.now() + timeoutMs;
+  let lastBalance: bigint | undefined;
+  for (;;) {
+    try {
+      lastBalance = await readBalance();
+      if (lastBalance > previous) {
+        return lastBalance;
+      }
+    } catch {
+      // Retried on the next poll
+    }
+    const remaining = deadline - Date.now();
+    if (remaining <= 0) {
+      throw new BalanceWaitTimeoutError(tokenAddress, previous, lastBalance, timeoutMs);
+    }
+    await new Promise((resolve) => setTimeout(resolve, Math.min(intervalMs, remaining)));
+  }
+}
+
+/**
+ * Chain reads behind waitForConfirmationsOrDrop, for one transaction
+ */
+export interface InclusionWatch<R extends { blockNumber: number }> {
+  getReceipt(): Promise<R | null>;
+  isPending(): Promise<boolean>; // The node still knows the transaction, unmined
+  getBlockNumber(): Promise<number>;
+}
+
+/**
+ * Resolve with the receipt once it has `count` confirmations, polling every `intervalMs`. A receipt
+ * that disappears (its block reorged out) is waited for again while the transaction is still
+ * pending; once it is neither mined nor pending, rejects with TransactionDroppedError.
+ */
+export async function waitForConfirmationsOrDrop<R extends { blockNumber: number }>(
+  txHash: string,
+  count: number,
+  watch: InclusionWatch<R>,
+  intervalMs: number
+): Promise<R> {
+  for (;;) {
+    const receipt = await watch.getReceipt();
+    if (receipt) {
+      if ((await watch.getBlockNumber()) - receipt.blockNumber + 1 >= count) {
+        return receipt;
+      }
+    } else if (!(await watch.isPending())) {
+      throw new TransactionDroppedError(txHash);
+    }
+    await new Promise((resolve) => setTimeout(resolve, intervalMs));
+  }
+}
@@ -9,7 +9,25 @@ import { WeiPerEther } from 'ethers';
 export const ONE_ETHER = WeiPerEther;
 export const SLIPPAGE_DENOMINATOR = 10000n; // For slippage calculations (basis points)
 export const MIN_GAS_LIMIT = 21000n; // Minimum gas limit for any transaction
+export const BUY_GAS_LIMIT_ESTIMATE = 300000n; // Gas assumed for a buy when reserving BNB
+export const SELL_GAS_LIMIT_ESTIMATE = 250000n; // Gas assumed for a sell when estimation fails
+// Gas limits per operation when the node doesn't support eth_estimateGas
+export const DEFAULT_FALLBACK_GAS_LIMITS = {
+  buy: BUY_GAS_LIMIT_ESTIMATE,
+  sell: SELL_GAS_LIMIT_ESTIMATE,
+  approve: 80000n,
+  createToken: 3000000n,
+  addLiquidity: 4000000n,
+  unwrap: 50000n,
+  permit: 100000n,
+  raw: 500000n, // rawSend, whose function the SDK knows nothing about
+};
+export const PARTIAL_FILL_GAS_BUFFER_BPS = 15000n; // Gas estimate multiplier (1.5x) for buys taking the AMAP refund path
 export const MAX_SLIPPAGE_PERCENT = 100; // Maximum allowed slippage percentage
+export const SLIPPAGE_SUGGESTION_BLOCKS = 200; // Recent blocks of trades suggestSlippage learns from (about 10 minutes at 3s blocks)
+export const SLIPPAGE_SUGGESTION_PERCENTILE = 95; // Percentile of per-block net buy volume assumed to land ahead of a buy
+export const SLIPPAGE_SUGGESTION_MIN_BPS = 50; // Floor of a suggested slippage
+export const SWEEP_SLIPPAGE_PERCENT = 5; // Minimum proceeds of a sweep sell below its quote
 
 // Time constants (milliseconds)
 export const DEFAULT_CACHE_TTL = 60000; // 1 minute
@@ -31,6 +49,18 @@ export const CACHE_CLEANUP_INTERVAL = 300000; // 5 minutes
 
 // Contract addresses
 export const FOUR_MEME_ADDRESS = '0x5c952063c7fc8610FFDB798152D69F0B9550762b';
+export const PANCAKE_V2_ROUTER_ADDRESS = '0x10ED43C718714eb63d5aA57B78B54704E256024E';
+export const PANCAKE_V2_FACTORY_ADDRESS = '0xcA143Ce32Fe78f1f7019d7d551a6402fC5350c73';
+export const WBNB_ADDRESS = '0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c';
+export const TOKEN_MANAGER_V1_ADDRESS = '0xEC4549caDcE5DA21Df6E6422d448034B5233bFbC'; // Manager of tokens launched before V2
+export const TOKEN_MANAGER_HELPER_ADDRESS = '0xF251F83e40a78868FcfA3FA4599Dad6494E46034'; // TokenManagerHelper3
+export const MULTICALL3_ADDRESS = '0xcA11bde05977b3631167028862bE2a173976CA11'; // Same address on every chain
+export const CHAINLINK_BNB_USD_FEED = '0x0567F2323251f0Aab15c8dFb1967E4e8A7D42aeE'; // Chainlink BNB/USD aggregator on BSC
+
+// keccak256 of the runtime bytecode of official four.meme token implementations.
+// Extend with FourTradingConfig.officialTokenCodeHashes (see FourTrading.getTokenCodeHash)
+// when the launchpad deploys a new implementation.
+export const OFFICIAL_TOKEN_CODE_HASHES: readonly string[] = [];
 
 // Event topics (precomputed for performance)
 export const TOKEN_CREATE_TOPIC = '0x396d5e902b675b032348d3d2e9517ee8f0c4a926603fbc075d3d282ff00cad20';
@@ -48,16 +78,75 @@ export const PERF_SLOW_TRANSACTION_THRESHOLD = 30000; // 30 seconds
 // Batch operation limits
 export const MAX_BATCH_SIZE = 50; // Maximum items in a batch operation
 export const BATCH_DELAY = 100; // Delay between batched RPC calls (ms)
+export const BACKFILL_PAGE_SIZE = 5000; // Blocks per getLogs query when backfilling events
+export const CURSOR_RING_SIZE = 1000; // Recent event ids a CursorStream persists to deduplicate after a restart
+export const TOKEN_CREATE_LOOKBACK_BLOCKS = 1000000; // How far back token creation lookups search
+export const HOLDER_TOP_N = 10; // Holders counted towards top-holder concentration
+export const HOLDER_CACHE_TTL_MS = 15000; // How long holders() reuses a token's scan before extending it to the head
+export const NEAR_NAME_MIN_LENGTH = 5; // Shortest normalized token name matched within one edit of another
+export const CREATION_FUNDING_LOOKBACK_BLOCKS = 100; // Blocks before a launch searched for BNB the creator sent its buyers
+export const MOMENTUM_WINDOW_SECONDS = 30; // Rolling window for counting first-time buyers
+export const MOMENTUM_MAX_TRACKED_BUYERS = 5000; // Buyer addresses a MomentumTrigger remembers per token
+export const MOMENTUM_MAX_TOKENS = 1000; // Tokens a MomentumTrigger watches at once
+export const CREATOR_FUNDING_LOOKBACK_BLOCKS = 28800; // Blocks searched per hop for a wallet's first funding (about a day at 3s blocks)
+export const CREATOR_FUNDING_HOPS = 2; // Funding hops traced from a creator
+export const CREATOR_FUNDER_HUB_SIZE = 25; // Funders of more wallets are treated as exchanges or bridges and not clustered
+export const CREATOR_TOKEN_DEAD_AFTER_SECONDS = 86400; // Quiet time after which an ungraduated token counts as dead
+export const CREATOR_REGISTRY_MAX_TOKENS = 200000; // Token records a CreatorRegistry keeps
+export const CREATOR_REGISTRY_FLUSH_INTERVAL = 30000; // ms between flushes of an attached CreatorRegistry
+export const WIRE_VERSION = 1; // Wire format version written by encodeMessage
+export const TRADER_STATE_VERSION = 1; // Schema version written by FourTrading.exportState
+export const WIRE_MAX_FRAME_BYTES = 1024 * 1024; // Largest frame payload a WireDecoder accepts
 
 // Validation limits
 export const MAX_UINT256 = 2n ** 256n - 1n;
 export const MIN_BNB_AMOUNT = 1000000000000000n; // 0.001 BNB minimum
 export const MAX_TOKEN_DECIMALS = 18;
+export const DEFAULT_TOKEN_DECIMALS = 18; // four.meme tokens; others are set with the tokenDecimals config
 
 // Provider configuration
+export const BSC_CHAIN_ID = 56n;
 export const DEFAULT_POLLING_INTERVAL = 12000; // 12 seconds (BSC block time)
 export const TRANSACTION_CONFIRMATION_BLOCKS = 1;
 export const TRANSACTION_TIMEOUT = 120000; // 2 minutes
+export const FINALITY_POLL_INTERVAL = 1000; // How often finalized waits re-check the finalized block (ms)
+export const BALANCE_POLL_INTERVAL = 1000; // How often waitForBalanceIncrease re-reads the balance (ms)
+export const REORG_WATCH_INTERVAL = 1000; // How often trades with retryOnReorg re-read their receipt (ms)
+export const CHAIN_STALL_THRESHOLD = 30; // Seconds without a new block before the chain counts as stalled
+export const CHAIN_STALL_CHECK_INTERVAL = 5000; // How often receipt waits re-check chain progress (ms)
+export const CANDLE_CLOSE_GRACE_MS = 3000; // How long onCandle waits past an interval's end for trades mined in it to arrive
+export const BLOCK_CLOCK_WINDOW = 20; // Recent headers a BlockClock averages block intervals over
+export const BLOCK_CLOCK_POLL_INTERVAL = 1000; // How often a BlockClock without a heads subscription polls the head (ms)
+export const BLOCK_CLOCK_DEFAULT_INTERVAL_MS = 3000; // Block interval a BlockClock assumes before it has seen two headers
+export const CAPABILITY_ARCHIVE_PROBE_DEPTHS = [128, 100_000, 10_000_000]; // Depths the archive probe tries reading state at, shallowest first
+export const REPLACEMENT_MIN_BUMP_BPS = 1000; // Nodes drop same-nonce replacements raising the gas price by less than 10%
+export const APPROVAL_WAIT_TIMEOUT_MS = 60000; // How long an auto-approving sell waits on another approval of the token before speeding it up
+export const EMERGENCY_GAS_PRICE_BPS = 30000; // Aggressive exit preset of emergencySell: 3x the network gas price
+export const QUOTE_DIVERGENCE_THRESHOLD_BPS = 50; // Secondary quote disagreement worth reporting (0.5%)
+export const SECONDARY_QUOTE_BUDGET_MS = 100; // Max extra wait for the secondary quote (ms)
+export const QUOTE_DIVERGENCE_LOG_SIZE = 100; // Divergences kept in memory
+export const READ_CONSISTENCY_RETRIES = 20; // Attempts to reach the minimum block before giving up
+export const READ_CONSISTENCY_RETRY_INTERVAL = 250; // Delay between attempts while a replica lags (ms)
+export const APPROVAL_TIMEOUT_MS = 300000; // Wait for an out-of-band trade approval before the trade is rejected (5 minutes)
+export const BNB_USD_MAX_AGE = 3600; // Seconds a BNB/USD feed round may age before it's refused as stale
+export const DEFAULT_TRADING_FEE_BPS = 100; // four.meme bonding-curve trading fee (1%), for offline quotes
+export const COOLDOWN_PROBE_OFFSETS: readonly number[] = [3, 10, 30, 60, 300, 900, 3600]; // Delays (s) tried when probing for a cooldown
+export const SELL_LIMIT_PROBE_BPS: readonly number[] = [10, 100, 1000, 5000]; // Shares of the balance tried, smallest first, when probing for a sell cap
+export const SELL_LIMIT_PRECISION_BPS = 10; // maxSellable stops refining within 0.1% of the balance
+
+// four.meme token creation limits, checked client-side by validateTokenLaunch before createToken.
+// This is the one place to update when the platform changes them.
+export const TOKEN_LAUNCH_LIMITS = {
+  nameLength: { min: 1, max: 20 },
+  symbolLength: { min: 1, max: 10 },
+  symbolPattern: /^[A-Za-z0-9]+$/, // Letters and digits only
+  descriptionLength: { max: 256 },
+  imageExtensions: ['png', 'jpg', 'jpeg', 'gif', 'webp'] as readonly string[],
+  linkProtocols: ['https:'] as readonly string[], // Image, website and social links
+  labels: ['Meme', 'AI', 'Defi', 'Games', 'Infra', 'De-Sci', 'Social', 'Depin', 'Charity', 'Others'] as readonly string[],
+  preSaleBnb: { min: 0n, max: 18n * WeiPerEther }, // Creator's initial buy (wei), below the 24 BNB raise target
+  launchDelaySeconds: { min: 0, max: 7 * 24 * 3600 }, // Scheduled launch, from now
+} as const;
 
 // Error messages
 export const ERROR_MESSAGES = {
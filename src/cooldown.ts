@@ -0,0 +1,64 @@
+/**
+ * Trade cooldown detection
+ * Some graduated tokens revert transfers made too soon after the sender's previous trade.
+ * A cooldown is inferred by simulating the same transfer at increasing delays.
+ */
+
+import { estimateBlockAt } from './blocks';
+import type { ChainHead } from './chainHealth';
+import { ValidationError } from './errors';
+
+/**
+ * Whether the simulated transfer succeeds `delaySeconds` after the current block
+ */
+export type CooldownProbe = (delaySeconds: number) => Promise<boolean>;
+
+/**
+ * Block a probe `delaySeconds` after `head` simulates: its timestamp, and the block number the
+ * chain reaches by then at `intervalMs` per block, so tokens that count the cooldown in blocks
+ * see it pass too. Always at least the block after the head.
+ */
+export function cooldownProbeBlock(head: ChainHead, intervalMs: number, delaySeconds: number): ChainHead {
+  const timestamp = head.timestamp + delaySeconds;
+  return { number: Math.max(head.number + 1, estimateBlockAt(head, intervalMs, timestamp)), timestamp };
+}
+
+/**
+ * Smallest delay (seconds, 1s precision) after which the probe succeeds, or null when it already
+ * succeeds immediately. Delays are tried in `offsets` order, then refined by binary search
+ * between the last failing and first succeeding offset.
+ */
+export async function inferCooldown(probe: CooldownProbe, offsets: readonly number[]): Promise<number | null> {
+  if (await probe(0)) {
+    return null;
+  }
+
+  let failing = 0;
+  let succeeding: number | null = null;
+  for (const offset of offsets) {
+    if (offset <= 0) {
+      continue;
+    }
+    if (await probe(offset)) {
+      succeeding = offset;
+      break;
+    }
+    failing = offset;
+  }
+
+  if (succeeding === null) {
+    throw new ValidationError(
+      `Transfer still reverts ${failing}s later; the token blocks it outright rather than enforcing a cooldown`
+    );
+  }
+
+  while (succeeding - failing > 1) {
+    const middle = Math.floor((failing + succeeding) / 2);
+    if (await probe(middle)) {
+      succeeding = middle;
+    } else {
+      failing = middle;
+    }
+  }
+  return succeeding;
+}
@@ -0,0 +1,60 @@
+/**
+ * Core: types, ABI encoders and local curve math
+ * Imported separately (`@fnzero/four-trading-sdk/core`) for environments that only quote and encode.
+ *
+ * Nothing here opens a connection, signs or touches the filesystem: the module graph behind this
+ * entry point stops at ethers' ABI and number helpers. tests/unit/entryPoints.test.ts walks it to
+ * keep it that way.
+ */
+
+export {
+  TokenInfo,
+  TokenInfoEx,
+  Template,
+  TokenStatus,
+  TradeTags,
+  TokenCreateEvent,
+  TokenPurchaseEvent,
+  TokenSaleEvent,
+  LiquidityAddedEvent,
+} from '../types';
+export {
+  FOUR_TRADING_ABI,
+  ERC20_ABI,
+  MULTICALL3_ABI,
+  TOKEN_MANAGER_V1_ABI,
+  TOKEN_MANAGER_HELPER_ABI,
+  WBNB_ABI,
+  getFourTradingInterface,
+  getErc20Interface,
+  getMulticall3Interface,
+  getTokenManagerV1Interface,
+  getTokenManagerHelperInterface,
+  getWbnbInterface,
+} from '../abi';
+export { encodeBuyCalldata, encodeBuyExactCalldata, encodeSellCalldata } from '../calldata';
+export {
+  FourmemeVersion,
+  TokenManagerRoute,
+  routeFromHelperInfo,
+  encodeVersionedBuy,
+  encodeVersionedBuyExact,
+  encodeVersionedSell,
+} from '../version';
+export { TOKEN_INFO_FIELDS, decodeTokenInfo } from '../tokenInfo';
+export { BuySimulation, CurveParams, CurveReserves, curveParams, curveBuyAmount, curveSellCost, calcBuyAtReserves, simulateBuy } from '../curve';
+export { isStrictMath, setStrictMath, toNumberChecked, mulDiv, checkedBps, bpsOf } from '../math';
+export { isEconomicalSell, assertEconomicalSell } from '../guards';
+export { Price } from '../price';
+export { TokenLaunchParams, TokenLaunchViolation, memeTokenTemplate, validateTokenLaunch } from '../tokenLaunch';
+export { FOUR_MEME_ADDRESS, SLIPPAGE_DENOMINATOR, DEFAULT_TRADING_FEE_BPS, WBNB_ADDRESS, MULTICALL3_ADDRESS } from '../constants';
+export {
+  FourTradingError,
+  ValidationError,
+  FeeExceedsAmountError,
+  MathTruncationError,
+  UneconomicalTradeError,
+  UnsupportedVersionError,
+  TokenInfoLayoutError,
+  TokenLaunchValidationError,
+} from '../errors';
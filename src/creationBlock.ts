@@ -0,0 +1,152 @@
+/**
+ * Creation block analysis
+ * Buys landing in the same block as a token's creation are placed before anyone outside could react,
+ * so they show how pre-loaded a launch is. Funding is traced one hop: a buyer counts as the
+ * creator's when the creator sent it BNB within a bounded number of blocks before the launch.
+ */
+
+import { getFourTradingInterface } from './abi';
+import { BackfillLog } from './backfill';
+import { ValidationError } from './errors';
+import { bpsOf } from './math';
+
+/**
+ * The fields of a block's transaction used by the analysis
+ */
+export interface BlockTransaction {
+  hash: string;
+  from: string;
+  to: string | null;
+  value: bigint;
+  gasPrice: bigint; // Effective price paid
+}
+
+export interface CreationBlockSource {
+  getBlockLogs(block: number): Promise<BackfillLog[]>; // The contract's TokenCreate and TokenPurchase logs in `block`
+  getBlockTransactions(block: number): Promise<BlockTransaction[]>;
+}
+
+export interface CreationBlockBuy {
+  txHash: string;
+  buyer: string; // The purchase's recipient account
+  sender: string; // Transaction sender
+  amount: bigint; // Tokens bought
+  cost: bigint; // BNB spent (wei), excluding the fee
+  gasPrice: bigint;
+  byCreator: boolean; // Bought by the creator's wallet
+  fundedByCreator: boolean; // Buyer or sender received BNB from the creator within the lookback
+}
+
+export interface CreationBlockReport {
+  token: string;
+  creator: string;
+  block: number;
+  buys: CreationBlockBuy[]; // In log order
+  distinctBuyers: number;
+  tokensBought: bigint;
+  supplyBps: number; // `tokensBought` as basis points of total supply
+  creatorBought: boolean;
+  creatorFundedBuyers: string[]; // Buyers other than the creator that it funded, lowercase
+  minGasPrice: bigint | null; // null when nothing was bought
+  maxGasPrice: bigint | null;
+  fundingLookbackBlocks: number; // Blocks before the creation block searched for creator transfers
+}
+
+/**
+ * Analyze the buys of `tokenAddress` in its creation block. Transfers from the creator are searched
+ * in the `fundingLookbackBlocks` blocks before it and the creation block itself (one block read
+ * each), and only when someone other than the creator bought.
+ */
+export async function analyzeCreationBlock(
+  tokenAddress: string,
+  block: number,
+  source: CreationBlockSource,
+  fundingLookbackBlocks: number
+): Promise<CreationBlockReport> {
+  if (!Number.isInteger(fundingLookbackBlocks) || fundingLookbackBlocks < 0) {
+    throw new ValidationError(`Funding lookback must be a non-negative integer, got ${fundingLookbackBlocks}`);
+  }
+
+  const iface = getFourTradingInterface();
+  const token = tokenAddress.toLowerCase();
+  let creator: string | null = null;
+  let totalSupply = 0n;
+  const purchases: Array<{ txHash: string; buyer: string; amount: bigint; cost: bigint }> = [];
+
+  for (const log of await source.getBlockLogs(block)) {
+    let parsed;
+    try {
+      parsed = iface.parseLog({ topics: [...log.topics], data: log.data });
+    } catch {
+      continue;
+    }
+    if (!parsed || String(parsed.args.token).toLowerCase() !== token) {
+      continue;
+    }
+    if (parsed.name === 'TokenCreate') {
+      creator = String(parsed.args.creator);
+      totalSupply = BigInt(parsed.args.totalSupply);
+    } else if (parsed.name === 'TokenPurchase') {
+      purchases.push({
+        txHash: log.transactionHash,
+        buyer: String(parsed.args.account),
+        amount: BigInt(parsed.args.amount),
+        cost: BigInt(parsed.args.cost),
+      });
+    }
+  }
+  if (creator === null) {
+    throw new ValidationError(`No TokenCreate event for ${tokenAddress} in block ${block}`);
+  }
+
+  const creatorKey = creator.toLowerCase();
+  const transactions = new Map((await source.getBlockTransactions(block)).map((tx) => [tx.hash.toLowerCase(), tx]));
+  const bought = purchases.map((purchase) => {
+    const tx = transactions.get(purchase.txHash.toLowerCase());
+    const sender = tx?.from ?? purchase.buyer;
+    return {
+      ...purchase,
+      sender,
+      gasPrice: tx?.gasPrice ?? 0n,
+      byCreator: purchase.buyer.toLowerCase() === creatorKey || sender.toLowerCase() === creatorKey,
+    };
+  });
+
+  const funded = new Set<string>();
+  const outsiders = new Set(
+    bought.filter((buy) => !buy.byCreator).flatMap((buy) => [buy.buyer.toLowerCase(), buy.sender.toLowerCase()])
+  );
+  if (outsiders.size > 0) {
+    for (let scan = Math.max(0, block - fundingLookbackBlocks); scan <= block; scan++) {
+      const txs = scan === block ? [...transactions.values()] : await source.getBlockTransactions(scan);
+      for (const tx of txs) {
+        const to = tx.to?.toLowerCase();
+        if (tx.from.toLowerCase() === creatorKey && tx.value > 0n && to && outsiders.has(to)) {
+          funded.add(to);
+        }
+      }
+    }
+  }
+
+  const buys: CreationBlockBuy[] = bought.map((buy) => ({
+    ...buy,
+    fundedByCreator: !buy.byCreator && (funded.has(buy.buyer.toLowerCase()) || funded.has(buy.sender.toLowerCase())),
+  }));
+  const tokensBought = buys.reduce((sum, buy) => sum + buy.amount, 0n);
+  const gasPrices = buys.map((buy) => buy.gasPrice);
+
+  return {
+    token: tokenAddress,
+    creator,
+    block,
+    buys,
+    distinctBuyers: new Set(buys.map((buy) => buy.buyer.toLowerCase())).size,
+    tokensBought,
+    supplyBps: totalSupply > 0n ? bpsOf(tokensBought, totalSupply, 'creation block share') : 0,
+    creatorBought: buys.some((buy) => buy.byCreator),
+    creatorFundedBuyers: [...new Set(buys.filter((buy) => buy.fundedByCreator).map((buy) => buy.buyer.toLowerCase()))],
+    minGasPrice: gasPrices.length > 0 ? gasPrices.reduce((a, b) => (b < a ? b : a)) : null,
+    maxGasPrice: gasPrices.length > 0 ? gasPrices.reduce((a, b) => (b > a ? b : a)) : null,
+    fundingLookbackBlocks,
+  };
+}
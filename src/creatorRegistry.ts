@@ -0,0 +1,347 @@
+/**
+ * Creator reputation registry
+ * Remembers the tokens each creator launched and how they ended (graduated, died, or dumped by the
+ * creator before graduating), plus the funding links traced between wallets. Serial ruggers rotate
+ * creator wallets but tend to fund them from the same source, so a creator is scored on its own
+ * history and, at half weight, on the history of creators sharing a funder within two hops.
+ *
+ * The score is a smoothed success rate, 100 * (successes + 1) / (resolved + 2): 50 with no history,
+ * moving toward the observed rate as outcomes accumulate. It only knows what this registry has seen,
+ * and funders serving many wallets (exchanges, bridges) are skipped, so treat it as one weak signal.
+ */
+
+import { promises as fs } from 'fs';
+import {
+  CREATOR_FUNDER_HUB_SIZE,
+  CREATOR_REGISTRY_FLUSH_INTERVAL,
+  CREATOR_REGISTRY_MAX_TOKENS,
+  CREATOR_TOKEN_DEAD_AFTER_SECONDS,
+} from './constants';
+import type { FourTrading } from './fourTrading';
+import { FundingHop } from './fundingTrace';
+
+export interface CreatorTokenRecord {
+  token: string; // Lowercase
+  creator: string; // Lowercase
+  createdAt: number; // Unix seconds
+  lastTradeAt: number;
+  graduatedAt?: number;
+  creatorSells: number; // Curve sells by the creator before graduation
+}
+
+export type CreatorTokenOutcome = 'graduated' | 'dead' | 'dumped' | 'active';
+
+export interface CreatorOutcomes {
+  tokens: number;
+  graduated: number; // Graduated without the creator selling first
+  dead: number; // No trade for `deadAfterSeconds` before graduating
+  dumped: number; // The creator sold before graduation
+  active: number;
+}
+
+export interface CreatorReport {
+  creator: string;
+  score: number; // 0-100, 50 without resolved history
+  own: CreatorOutcomes;
+  cluster: CreatorOutcomes & { creators: string[] }; // Other creators sharing a funder, lowercase
+  funding: FundingHop[];
+  clusterFunders: string[]; // Funders the cluster was built from
+  hubFunders: string[]; // Funders skipped for funding more than `hubSize` wallets
+}
+
+/**
+ * Everything a registry persists; plain JSON
+ */
+export interface CreatorRegistryState {
+  tokens: CreatorTokenRecord[];
+  funding: FundingHop[]; // 'found' and 'internal' hops only, which never change
+}
+
+export interface CreatorRegistryStore {
+  load(): Promise<CreatorRegistryState | null>;
+  save(state: CreatorRegistryState): Promise<void>;
+}
+
+export class MemoryCreatorRegistryStore implements CreatorRegistryStore {
+  private state: CreatorRegistryState | null = null;
+
+  async load(): Promise<CreatorRegistryState | null> {
+    return this.state && JSON.parse(JSON.stringify(this.state));
+  }
+
+  async save(state: CreatorRegistryState): Promise<void> {
+    this.state = JSON.parse(JSON.stringify(state));
+  }
+}
+
+/**
+ * One JSON file, replaced atomically by temp-file rename on each save
+ */
+export class JsonFileCreatorRegistryStore implements CreatorRegistryStore {
+  constructor(private file: string) {}
+
+  async load(): Promise<CreatorRegistryState | null> {
+    try {
+      return JSON.parse(await fs.readFile(this.file, 'utf8'));
+    } catch (error: any) {
+      if (error.code === 'ENOENT') {
+        return null;
+      }
+      throw error;
+    }
+  }
+
+  async save(state: CreatorRegistryState): Promise<void> {
+    const temp = `${this.file}.tmp`;
+    const handle = await fs.open(temp, 'w');
+    try {
+      await handle.writeFile(JSON.stringify(state));
+      await handle.sync();
+    } finally {
+      await handle.close();
+    }
+    await fs.rename(temp, this.file);
+  }
+}
+
+export interface CreatorRegistryOptions {
+  store?: CreatorRegistryStore; // Where `flush` persists to (default: memory only)
+  deadAfterSeconds?: number; // Quiet time after which an ungraduated token counts as dead (default: CREATOR_TOKEN_DEAD_AFTER_SECONDS)
+  hubSize?: number; // Funders of more wallets than this are skipped (default: CREATOR_FUNDER_HUB_SIZE)
+  maxTokens?: number; // Token records kept; the oldest are dropped beyond it (default: CREATOR_REGISTRY_MAX_TOKENS)
+  flushIntervalMs?: number; // How often an attached registry persists changes (default: CREATOR_REGISTRY_FLUSH_INTERVAL)
+}
+
+const OWN_WEIGHT = 1;
+const CLUSTER_WEIGHT = 0.5;
+
+function emptyOutcomes(): CreatorOutcomes {
+  return { tokens: 0, graduated: 0, dead: 0, dumped: 0, active: 0 };
+}
+
+export class CreatorRegistry {
+  private tokens: Map<string, CreatorTokenRecord> = new Map(); // Insertion order is creation order
+  private byCreator: Map<string, Set<string>> = new Map();
+  private funding: Map<string, FundingHop> = new Map();
+  private fundees: Map<string, Set<string>> = new Map(); // Funder -> wallets it first funded
+  private dirty = false;
+  private deadAfterSeconds: number;
+  private hubSize: number;
+  private maxTokens: number;
+
+  constructor(private options: CreatorRegistryOptions = {}, private now: () => number = () => Math.floor(Date.now() / 1000)) {
+    this.deadAfterSeconds = options.deadAfterSeconds ?? CREATOR_TOKEN_DEAD_AFTER_SECONDS;
+    this.hubSize = options.hubSize ?? CREATOR_FUNDER_HUB_SIZE;
+    this.maxTokens = options.maxTokens ?? CREATOR_REGISTRY_MAX_TOKENS;
+  }
+
+  /**
+   * Merge the store's saved state into the registry
+   */
+  async load(): Promise<void> {
+    const state = await this.options.store?.load();
+    if (!state) {
+      return;
+    }
+    const dirty = this.dirty;
+    for (const record of state.tokens) {
+      if (!this.tokens.has(record.token)) {
+        this.insert({ ...record });
+      }
+    }
+    for (const hop of state.funding) {
+      this.recordFunding(hop);
+    }
+    this.dirty = dirty;
+  }
+
+  /**
+   * Persist the registry to its store, if it changed since the last flush
+   */
+  async flush(): Promise<void> {
+    if (!this.dirty || !this.options.store) {
+      return;
+    }
+    this.dirty = false;
+    try {
+      await this.options.store.save(this.snapshot());
+    } catch (error) {
+      this.dirty = true;
+      throw error;
+    }
+  }
+
+  snapshot(): CreatorRegistryState {
+    return {
+      tokens: [...this.tokens.values()].map((record) => ({ ...record })),
+      funding: [...this.funding.values()].map((hop) => ({ ...hop })),
+    };
+  }
+
+  recordCreation(token: string, creator: string, timestamp: number = this.now()): void {
+    const key = token.toLowerCase();
+    if (this.tokens.has(key)) {
+      return;
+    }
+    this.insert({ token: key, creator: creator.toLowerCase(), createdAt: timestamp, lastTradeAt: timestamp, creatorSells: 0 });
+    this.dirty = true;
+  }
+
+  recordTrade(token: string, account: string, side: 'buy' | 'sell', timestamp: number = this.now()): void {
+    const record = this.tokens.get(token.toLowerCase());
+    if (!record) {
+      return;
+    }
+    record.lastTradeAt = Math.max(record.lastTradeAt, timestamp);
+    if (side === 'sell' && record.graduatedAt === undefined && account.toLowerCase() === record.creator) {
+      record.creatorSells += 1;
+    }
+    this.dirty = true;
+  }
+
+  recordGraduation(token: string, timestamp: number = this.now()): void {
+    const record = this.tokens.get(token.toLowerCase());
+    if (record && record.graduatedAt === undefined) {
+      record.graduatedAt = timestamp;
+      this.dirty = true;
+    }
+  }
+
+  /**
+   * Remember a traced hop; only 'found' and 'internal' hops are kept, since they never change
+   */
+  recordFunding(hop: FundingHop): void {
+    if (hop.status !== 'found' && hop.status !== 'internal') {
+      return;
+    }
+    const key = hop.address.toLowerCase();
+    const previous = this.funding.get(key);
+    if (previous?.funder) {
+      this.fundees.get(previous.funder.toLowerCase())?.delete(key);
+    }
+    this.funding.set(key, { ...hop });
+    if (hop.funder) {
+      const funder = hop.funder.toLowerCase();
+      this.fundees.set(funder, (this.fundees.get(funder) ?? new Set()).add(key));
+    }
+    this.dirty = true;
+  }
+
+  fundingOf(address: string): FundingHop | undefined {
+    const hop = this.funding.get(address.toLowerCase());
+    return hop && { ...hop };
+  }
+
+  /**
+   * How a token ended, judged at the registry's clock
+   */
+  outcome(record: CreatorTokenRecord): CreatorTokenOutcome {
+    if (record.creatorSells > 0) {
+      return 'dumped';
+    }
+    if (record.graduatedAt !== undefined) {
+      return 'graduated';
+    }
+    return this.now() - record.lastTradeAt >= this.deadAfterSeconds ? 'dead' : 'active';
+  }
+
+  tokensOf(creator: string): CreatorTokenRecord[] {
+    return [...(this.byCreator.get(creator.toLowerCase()) ?? [])].map((token) => ({ ...this.tokens.get(token)! }));
+  }
+
+  /**
+   * Score `creator` from its own tokens and those of creators sharing a funder in `funding`
+   * (its trace, funder first). A funder links the wallets it funded and, for the second hop,
+   * the wallets those funded.
+   */
+  report(creator: string, funding: FundingHop[]): CreatorReport {
+    const self = creator.toLowerCase();
+    const hubFunders: string[] = [];
+    const clusterFunders: string[] = [];
+    const related = new Set<string>();
+    funding.forEach((hop, depth) => {
+      const funder = hop.funder?.toLowerCase();
+      if (!funder) {
+        return;
+      }
+      if ((this.fundees.get(funder)?.size ?? 0) > this.hubSize) {
+        hubFunders.push(funder);
+        return;
+      }
+      clusterFunders.push(funder);
+      for (const fundee of this.fundees.get(funder) ?? []) {
+        related.add(fundee);
+        if (depth > 0) {
+          this.fundees.get(fundee)?.forEach((wallet) => related.add(wallet));
+        }
+      }
+    });
+    related.delete(self);
+    const creators = [...related].filter((wallet) => this.byCreator.has(wallet));
+
+    const own = this.outcomes([self]);
+    const cluster = this.outcomes(creators);
+    const successes = OWN_WEIGHT * own.graduated + CLUSTER_WEIGHT * cluster.graduated;
+    const failures = OWN_WEIGHT * (own.dead + own.dumped) + CLUSTER_WEIGHT * (cluster.dead + cluster.dumped);
+
+    return {
+      creator,
+      score: Math.round((100 * (successes + 1)) / (successes + failures + 2)),
+      own,
+      cluster: { ...cluster, creators },
+      funding: funding.map((hop) => ({ ...hop })),
+      clusterFunders,
+      hubFunders,
+    };
+  }
+
+  /**
+   * Record creations, trades and graduations from a trader's event streams, and flush to the store
+   * every `flushIntervalMs`; returns a detach function that stops both and flushes once more
+   */
+  attach(trader: FourTrading): () => Promise<void> {
+    const ids = [
+      trader.onTokenCreate((event) => this.recordCreation(event.token, event.creator)),
+      trader.onTokenPurchase((event) => this.recordTrade(event.token, event.account, 'buy')),
+      trader.onTokenSale((event) => this.recordTrade(event.token, event.account, 'sell')),
+      trader.onLiquidityAdded((event) => this.recordGraduation(event.base)),
+    ];
+    const timer = setInterval(() => {
+      this.flush().catch((error) => console.error('Creator registry flush failed:', error.message));
+    }, this.options.flushIntervalMs ?? CREATOR_REGISTRY_FLUSH_INTERVAL);
+    timer.unref();
+
+    return async () => {
+      clearInterval(timer);
+      for (const id of ids) {
+        trader.off(id);
+      }
+      await this.flush();
+    };
+  }
+
+  private outcomes(creators: string[]): CreatorOutcomes {
+    const outcomes = emptyOutcomes();
+    for (const creator of creators) {
+      for (const token of this.byCreator.get(creator) ?? []) {
+        outcomes.tokens += 1;
+        outcomes[this.outcome(this.tokens.get(token)!)] += 1;
+      }
+    }
+    return outcomes;
+  }
+
+  private insert(record: CreatorTokenRecord): void {
+    if (this.tokens.size >= this.maxTokens) {
+      const oldest = this.tokens.values().next().value!;
+      this.tokens.delete(oldest.token);
+      const owned = this.byCreator.get(oldest.creator);
+      owned?.delete(oldest.token);
+      if (owned?.size === 0) {
+        this.byCreator.delete(oldest.creator);
+      }
+    }
+    this.tokens.set(record.token, record);
+    this.byCreator.set(record.creator, (this.byCreator.get(record.creator) ?? new Set()).add(record.token));
+  }
+}
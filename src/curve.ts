@@ -0,0 +1,255 @@
+/**
+ * Bonding curve parameters
+ * Exposes the four.meme curve invariant so quotes can be reproduced off-chain.
+ *
+ * The curve is a constant product over virtual reserves:
+ *
+ *   (T - sold) * (funds + K / T) = K
+ *
+ * where `sold = maxOffers - offers` is the number of tokens bought from the curve so far and
+ * `funds` the quote (BNB) raised. Buying with `f` quote (after the trading fee) yields
+ * `(T - sold) - K / (funds + K / T + f)` tokens; selling `a` tokens returns
+ * `(funds + K / T) - K / (T - sold + a)` before the fee. The contract's calcBuyAmount and
+ * calcSellCost remain the authoritative quotes; these helpers exist to check them independently.
+ */
+
+import { Template, TokenInfo } from './types';
+import type { PriceInfo } from './priceCalculator';
+import { Price } from './price';
+import { Validator } from './validator';
+import { FeeExceedsAmountError, TargetPriceUnreachableError } from './errors';
+import { DEFAULT_TRADING_FEE_BPS, ONE_ETHER, SLIPPAGE_DENOMINATOR } from './constants';
+import { bpsOf, checkedBps, mulDiv } from './math';
+
+/**
+ * Virtual reserves of a curve; their product is the invariant K
+ */
+export interface CurveReserves {
+  virtualTokenReserve: bigint;
+  virtualQuoteReserve: bigint;
+}
+
+export interface CurveParams extends CurveReserves {
+  token: string;
+  K: bigint; // Curve invariant
+  T: bigint; // Virtual token supply at launch
+  sold: bigint; // Tokens bought from the curve: maxOffers - offers
+  funds: bigint; // Quote raised so far
+  maxOffers: bigint;
+  maxRaising: bigint;
+  lastPrice: bigint;
+}
+
+function ceilDiv(a: bigint, b: bigint): bigint {
+  return (a + b - 1n) / b;
+}
+
+/**
+ * Curve parameters for a token from its on-chain `_tokenInfos` entry
+ */
+export function curveParams(token: string, info: TokenInfo): CurveParams {
+  const sold = info.maxOffers > info.offers ? info.maxOffers - info.offers : 0n;
+  const initialQuoteReserve = info.T > 0n ? info.K / info.T : 0n;
+  return {
+    token,
+    K: info.K,
+    T: info.T,
+    sold,
+    funds: info.funds,
+    virtualTokenReserve: info.T > sold ? info.T - sold : 0n,
+    virtualQuoteReserve: info.funds + initialQuoteReserve,
+    maxOffers: info.maxOffers,
+    maxRaising: info.maxRaising,
+    lastPrice: info.lastPrice,
+  };
+}
+
+/**
+ * The launch template a token was created from and the curve it fixed at launch
+ */
+export interface LaunchTemplate {
+  token: string;
+  templateId: bigint;
+  quote: string; // Quote token the curve raises, the zero address for BNB
+  totalSupply: bigint;
+  maxOffers: bigint; // Tokens sold on the curve before it graduates
+  maxRaising: bigint; // Graduation threshold: quote raised when the curve is sold out
+  initialLiquidity: bigint; // Template's liquidity seeded at graduation
+  minTradingFee: bigint; // Template's floor on the trading fee
+  K: bigint; // Curve invariant
+  T: bigint; // Virtual token supply at launch
+  initialPrice: bigint; // Quote wei per whole token before the first buy
+  graduationPrice: bigint; // Quote wei per whole token once maxOffers are sold; 0 if the curve never gets there
+}
+
+/**
+ * Combine a token's `_tokenInfos` entry with its `_templates` entry
+ */
+export function launchTemplate(token: string, info: TokenInfo, template: Template): LaunchTemplate {
+  const tokensLeft = info.T > info.maxOffers ? info.T - info.maxOffers : 0n;
+  return {
+    token,
+    templateId: info.template,
+    quote: info.quote,
+    totalSupply: info.totalSupply,
+    maxOffers: info.maxOffers,
+    maxRaising: info.maxRaising,
+    initialLiquidity: template.initialLiquidity,
+    minTradingFee: template.minTradingFee,
+    K: info.K,
+    T: info.T,
+    initialPrice: info.T > 0n ? mulDiv(info.K, ONE_ETHER, info.T * info.T) : 0n,
+    graduationPrice: tokensLeft > 0n ? mulDiv(info.K, ONE_ETHER, tokensLeft * tokensLeft) : 0n,
+  };
+}
+
+function buyAmount(K: bigint, reserves: CurveReserves, funds: bigint): bigint {
+  if (funds <= 0n || K === 0n) {
+    return 0n;
+  }
+  const reserveAfter = ceilDiv(K, reserves.virtualQuoteReserve + funds);
+  return reserves.virtualTokenReserve > reserveAfter ? reserves.virtualTokenReserve - reserveAfter : 0n;
+}
+
+/**
+ * Tokens received for `funds` quote (after fee), rounded in the curve's favour
+ */
+export function curveBuyAmount(params: CurveParams, funds: bigint): bigint {
+  return buyAmount(params.K, params, funds);
+}
+
+/**
+ * Quote returned (before fee) for selling `amount` tokens, rounded in the curve's favour
+ */
+export function curveSellCost(params: CurveParams, amount: bigint): bigint {
+  if (amount <= 0n || params.K === 0n) {
+    return 0n;
+  }
+  const reserveAfter = ceilDiv(params.K, params.virtualTokenReserve + amount);
+  return params.virtualQuoteReserve > reserveAfter ? params.virtualQuoteReserve - reserveAfter : 0n;
+}
+
+/**
+ * Quote a buy of `bnbAmount` against hypothetical reserves, with no RPC. K is taken as the
+ * product of the reserves, so any state can be tried (pass a CurveParams to start from a real one).
+ * The fee is `feeBps` of the spend; the curve's raise cap is unknown here, so partial fills
+ * aren't flagged.
+ */
+export function calcBuyAtReserves(
+  reserves: CurveReserves,
+  bnbAmount: bigint,
+  feeBps: number = DEFAULT_TRADING_FEE_BPS
+): PriceInfo {
+  Validator.validateAmount(bnbAmount, 'bnbAmount');
+
+  const fee = mulDiv(bnbAmount, checkedBps(feeBps, 'feeBps'), SLIPPAGE_DENOMINATOR);
+  if (fee >= bnbAmount) {
+    throw new FeeExceedsAmountError(fee, bnbAmount);
+  }
+
+  const K = reserves.virtualTokenReserve * reserves.virtualQuoteReserve;
+  const tokenAmount = buyAmount(K, reserves, bnbAmount - fee);
+  return {
+    tokenAmount,
+    bnbCost: bnbAmount,
+    pricePerToken: tokenAmount > 0n ? (bnbAmount * ONE_ETHER) / tokenAmount : 0n,
+    price: Price.fromAmounts(bnbAmount, tokenAmount),
+    fee,
+    grossTokenAmount: buyAmount(K, reserves, bnbAmount),
+    netTokenAmount: tokenAmount,
+  };
+}
+
+/**
+ * Spend (fee included) whose average fill price equals `targetAvgPrice`, rounded down. Prices are
+ * wei per whole token with the fee, as PriceInfo.pricePerToken. Buying `f` after the fee from
+ * reserves (x, y) averages (y + f) / x before the fee, so at fee rate r the spend b solves
+ * b / tokensOut = (y + b(1 - r)) / (x(1 - r)), giving b = target * x - y / (1 - r).
+ * Averages run from the current price up to that of buying the rest of the raise; a target
+ * outside that range throws TargetPriceUnreachableError.
+ */
+export function buySizeForAvgPrice(
+  params: CurveParams,
+  targetAvgPrice: bigint,
+  feeBps: number = DEFAULT_TRADING_FEE_BPS
+): bigint {
+  Validator.validateAmount(targetAvgPrice, 'targetAvgPrice');
+
+  const keep = SLIPPAGE_DENOMINATOR - checkedBps(feeBps, 'feeBps');
+  const x = params.virtualTokenReserve;
+  const y = params.virtualQuoteReserve;
+  const capacity = params.maxRaising > params.funds ? params.maxRaising - params.funds : 0n;
+  const scale = SLIPPAGE_DENOMINATOR * ONE_ETHER;
+  const minAvgPrice = x > 0n && keep > 0n ? (y * scale) / (x * keep) : 0n;
+  const maxAvgPrice = x > 0n && keep > 0n ? ((y + capacity) * scale) / (x * keep) : 0n;
+
+  const bnbAmount = targetAvgPrice > minAvgPrice && targetAvgPrice <= maxAvgPrice
+    ? (targetAvgPrice * x * keep - y * scale) / (ONE_ETHER * keep)
+    : 0n;
+  if (bnbAmount <= 0n) {
+    throw new TargetPriceUnreachableError(params.token, targetAvgPrice, minAvgPrice, maxAvgPrice);
+  }
+  return bnbAmount;
+}
+
+export interface BuySimulation {
+  token: string;
+  bnbAmount: bigint; // Spend requested
+  bnbUsed: bigint; // Spend the curve absorbs, fee included
+  refund: bigint; // bnbAmount - bnbUsed, returned when the buy overshoots the curve
+  fee: bigint;
+  tokensOut: bigint;
+  reservesAfter: CurveReserves;
+  fundsAfter: bigint; // Quote raised after the buy
+  triggersGraduation: boolean; // The buy fills the curve, so the token migrates with it
+  remainingFunds: bigint; // Quote the curve can still raise after the buy
+  remainingOffers: bigint; // Tokens left on the curve after the buy
+  progressBps: number; // fundsAfter as basis points of maxRaising
+}
+
+/**
+ * Replay a buy of `bnbAmount` on a curve, with no RPC. A spend past the raise cap is cut at the cap,
+ * with the fee scaled pro rata as the contract refunds the rest; filling the cap, or every offer,
+ * triggers graduation.
+ */
+export function simulateBuy(
+  params: CurveParams,
+  bnbAmount: bigint,
+  feeBps: number = DEFAULT_TRADING_FEE_BPS
+): BuySimulation {
+  Validator.validateAmount(bnbAmount, 'bnbAmount');
+
+  const fullFee = mulDiv(bnbAmount, checkedBps(feeBps, 'feeBps'), SLIPPAGE_DENOMINATOR);
+  if (fullFee >= bnbAmount) {
+    throw new FeeExceedsAmountError(fullFee, bnbAmount);
+  }
+
+  const capacity = params.maxRaising > params.funds ? params.maxRaising - params.funds : 0n;
+  const offers = params.maxOffers > params.sold ? params.maxOffers - params.sold : 0n;
+  const wanted = bnbAmount - fullFee;
+  const fundsIn = wanted < capacity ? wanted : capacity;
+  const fee = fundsIn < wanted ? (fullFee * fundsIn) / wanted : fullFee;
+  const bought = curveBuyAmount(params, fundsIn);
+  const tokensOut = bought < offers ? bought : offers;
+
+  const fundsAfter = params.funds + fundsIn;
+  const bnbUsed = fundsIn > 0n ? fundsIn + fee : 0n;
+  const filled = fundsIn > 0n && (fundsIn === capacity || tokensOut === offers);
+  return {
+    token: params.token,
+    bnbAmount,
+    bnbUsed,
+    refund: bnbAmount - bnbUsed,
+    fee: fundsIn > 0n ? fee : 0n,
+    tokensOut,
+    reservesAfter: {
+      virtualTokenReserve: params.virtualTokenReserve - tokensOut,
+      virtualQuoteReserve: params.virtualQuoteReserve + fundsIn,
+    },
+    fundsAfter,
+    triggersGraduation: filled,
+    remainingFunds: capacity - fundsIn,
+    remainingOffers: offers - tokensOut,
+    progressBps: params.maxRaising > 0n ? bpsOf(fundsAfter, params.maxRaising, 'curve progress') : 0,
+  };
+}
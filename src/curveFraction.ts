@@ -0,0 +1,56 @@
+/**
+ * Buy sizing as a share of the supply still for sale on the bonding curve
+ * Fractions are whole basis points of TokenInfo.offers; amounts round down, so a buy never takes
+ * more than the share asked for.
+ */
+
+import { SLIPPAGE_DENOMINATOR } from './constants';
+import { CurveFractionError, InvalidAmountError, ValidationError } from './errors';
+import { bpsOf, mulDiv } from './math';
+
+/**
+ * A fraction request resolved against the per-transaction limit
+ */
+export interface ResolvedCurveFraction {
+  bps: number; // Fraction to buy
+  clamped: boolean; // The request was reduced to the limit
+}
+
+/**
+ * Check `fractionBps` against `maxBps` (at most 10000, the whole remaining supply). Larger
+ * requests are reduced to the limit when `clamp` is set, and throw CurveFractionError otherwise.
+ */
+export function resolveCurveFraction(fractionBps: number, maxBps: number, clamp: boolean): ResolvedCurveFraction {
+  if (!Number.isInteger(fractionBps) || fractionBps <= 0) {
+    throw new ValidationError(`fractionBps must be a positive whole number of basis points, got ${fractionBps}`);
+  }
+  if (!Number.isInteger(maxBps) || maxBps <= 0 || maxBps > Number(SLIPPAGE_DENOMINATOR)) {
+    throw new ValidationError(`maxCurveFractionBps must be a whole number of basis points in 1-10000, got ${maxBps}`);
+  }
+  if (fractionBps <= maxBps) {
+    return { bps: fractionBps, clamped: false };
+  }
+  if (!clamp) {
+    throw new CurveFractionError(fractionBps, maxBps);
+  }
+  return { bps: maxBps, clamped: true };
+}
+
+/**
+ * Tokens making up `fractionBps` of `offers`, rounded down. Throws InvalidAmountError when that
+ * rounds to zero tokens (including a curve with nothing left).
+ */
+export function curveFractionAmount(offers: bigint, fractionBps: number): bigint {
+  const amount = mulDiv(offers, BigInt(fractionBps), SLIPPAGE_DENOMINATOR);
+  if (amount === 0n) {
+    throw new InvalidAmountError(amount, `${fractionBps} bps of the ${offers} tokens left on the curve rounds to zero`);
+  }
+  return amount;
+}
+
+/**
+ * Share of `offersBefore` that `bought` tokens took, in basis points rounded down
+ */
+export function realizedCurveFraction(bought: bigint, offersBefore: bigint): number {
+  return offersBefore === 0n ? 0 : bpsOf(bought, offersBefore, 'realized curve fraction');
+}
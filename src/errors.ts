@@ -3,6 +3,9 @@
  * Provides better error handling and debugging capabilities
  */
 
+import { PendingTrade, TransactionResult } from './types';
+import { TokenLaunchViolation } from './tokenLaunch';
+
 export class FourTradingError extends Error {
   constructor(message: string, public readonly code: string, public readonly details?: unknown) {
     super(message);
@@ -71,6 +74,25 @@ export class GasConfigurationError extends ValidationError {
   }
 }
 
+export class TokenLaunchValidationError extends ValidationError {
+  constructor(public readonly violations: TokenLaunchViolation[]) {
+    super(
+      `Invalid token launch parameters: ${violations.map((violation) => violation.message).join('; ')}`,
+      { violations }
+    );
+    this.name = 'TokenLaunchValidationError';
+    Object.setPrototypeOf(this, TokenLaunchValidationError.prototype);
+  }
+}
+
+export class MathTruncationError extends FourTradingError {
+  constructor(message: string) {
+    super(message, 'MATH_TRUNCATION');
+    this.name = 'MathTruncationError';
+    Object.setPrototypeOf(this, MathTruncationError.prototype);
+  }
+}
+
 export class SlippageExceededError extends FourTradingError {
   constructor(expected: bigint, actual: bigint) {
     super(
@@ -93,3 +115,410 @@ export class FeeExceedsAmountError extends ValidationError {
     Object.setPrototypeOf(this, FeeExceedsAmountError.prototype);
   }
 }
+
+export class BnbReserveError extends FourTradingError {
+  constructor(required: bigint, available: bigint, reserve: bigint) {
+    super(
+      `BNB balance too low: spending ${required} from ${available} would leave less than the ${reserve} reserve`,
+      'BNB_RESERVE_VIOLATED',
+      { required: required.toString(), available: available.toString(), reserve: reserve.toString() }
+    );
+    this.name = 'BnbReserveError';
+    Object.setPrototypeOf(this, BnbReserveError.prototype);
+  }
+}
+
+export class UneconomicalTradeError extends FourTradingError {
+  constructor(public readonly proceeds: bigint, public readonly gasCost: bigint) {
+    super(
+      `Uneconomical trade: proceeds ${proceeds} do not cover gas cost ${gasCost} with the required margin`,
+      'UNECONOMICAL_TRADE',
+      { proceeds: proceeds.toString(), gasCost: gasCost.toString() }
+    );
+    this.name = 'UneconomicalTradeError';
+    Object.setPrototypeOf(this, UneconomicalTradeError.prototype);
+  }
+}
+
+export class UnverifiedTokenError extends FourTradingError {
+  constructor(token: string, status: string) {
+    super(`Token ${token} is not an official four.meme token (${status})`, 'UNVERIFIED_TOKEN', { token, status });
+    this.name = 'UnverifiedTokenError';
+    Object.setPrototypeOf(this, UnverifiedTokenError.prototype);
+  }
+}
+
+export class TokenTooOldError extends FourTradingError {
+  constructor(
+    public readonly age: number, // A lower bound when the token was created before the creation lookback
+    public readonly maxAge: number,
+    public readonly beforeLookback: boolean = false
+  ) {
+    super(
+      beforeLookback
+        ? `Token was created before the creation lookback, over ${age}s ago; the limit is ${maxAge}s`
+        : `Token is ${age}s old, older than the ${maxAge}s limit`,
+      'TOKEN_TOO_OLD',
+      { age, maxAge, beforeLookback }
+    );
+    this.name = 'TokenTooOldError';
+    Object.setPrototypeOf(this, TokenTooOldError.prototype);
+  }
+}
+
+export class TokenCreationNotFoundError extends ValidationError {
+  constructor(
+    public readonly tokenAddress: string,
+    public readonly searchedFrom: number // Oldest block searched; the token was created before it, if at all
+  ) {
+    super(`No TokenCreate event for ${tokenAddress} since block ${searchedFrom}`, { tokenAddress, searchedFrom });
+    this.name = 'TokenCreationNotFoundError';
+    Object.setPrototypeOf(this, TokenCreationNotFoundError.prototype);
+  }
+}
+
+export class TokenTooYoungError extends FourTradingError {
+  constructor(public readonly age: number, public readonly minAge: number) {
+    super(`Token is ${age}s old, younger than the ${minAge}s minimum`, 'TOKEN_TOO_YOUNG', { age, minAge });
+    this.name = 'TokenTooYoungError';
+    Object.setPrototypeOf(this, TokenTooYoungError.prototype);
+  }
+}
+
+export class HolderConcentrationError extends FourTradingError {
+  constructor(public readonly concentrationBps: number, public readonly maxBps: number, public readonly topN: number) {
+    super(
+      `Top ${topN} holders own ${concentrationBps / 100}% of supply, above the ${maxBps / 100}% limit`,
+      'HOLDER_CONCENTRATION',
+      { concentrationBps, maxBps, topN }
+    );
+    this.name = 'HolderConcentrationError';
+    Object.setPrototypeOf(this, HolderConcentrationError.prototype);
+  }
+}
+
+export class SubAccountBalanceError extends FourTradingError {
+  constructor(
+    public readonly subAccount: string,
+    public readonly asset: string, // 'BNB' or a token address
+    public readonly requested: bigint,
+    public readonly available: bigint
+  ) {
+    super(
+      `Sub-account ${subAccount} cannot spend ${requested} ${asset}, only ${available} available`,
+      'SUB_ACCOUNT_BALANCE',
+      { subAccount, asset, requested: requested.toString(), available: available.toString() }
+    );
+    this.name = 'SubAccountBalanceError';
+    Object.setPrototypeOf(this, SubAccountBalanceError.prototype);
+  }
+}
+
+export class ChainStalledError extends FourTradingError {
+  constructor(public readonly secondsSinceBlock: number, public readonly threshold: number, public readonly blockNumber: number) {
+    super(
+      `Chain stalled: no block for ${secondsSinceBlock}s (last block ${blockNumber}, threshold ${threshold}s)`,
+      'CHAIN_STALLED',
+      { secondsSinceBlock, threshold, blockNumber }
+    );
+    this.name = 'ChainStalledError';
+    Object.setPrototypeOf(this, ChainStalledError.prototype);
+  }
+}
+
+export class CancelledError extends FourTradingError {
+  constructor(
+    public readonly stage: 'beforeBroadcast' | 'afterBroadcast',
+    public readonly pending?: PendingTrade // Set after broadcast: the transaction is still live and trackable
+  ) {
+    super(
+      stage === 'beforeBroadcast'
+        ? 'Cancelled before the transaction was broadcast'
+        : `Cancelled while waiting for ${pending?.txHash ?? 'the transaction'}`,
+      'CANCELLED',
+      { stage, txHash: pending?.txHash }
+    );
+    this.name = 'CancelledError';
+    Object.setPrototypeOf(this, CancelledError.prototype);
+  }
+}
+
+export class ReplicaLaggingError extends FourTradingError {
+  constructor(public readonly minBlock: number, public readonly observedBlock: number) {
+    super(
+      `RPC still behind block ${minBlock} (latest seen ${observedBlock}) after retrying`,
+      'REPLICA_LAGGING',
+      { minBlock, observedBlock }
+    );
+    this.name = 'ReplicaLaggingError';
+    Object.setPrototypeOf(this, ReplicaLaggingError.prototype);
+  }
+}
+
+export class RpcDivergenceError extends FourTradingError {
+  constructor(public readonly diff: string, public readonly method: string, public readonly expectedSeq?: number) {
+    super(`RPC request diverged from the recording:\n${diff}`, 'RPC_DIVERGENCE', { method, expectedSeq });
+    this.name = 'RpcDivergenceError';
+    Object.setPrototypeOf(this, RpcDivergenceError.prototype);
+  }
+}
+
+export class UnsupportedVersionError extends FourTradingError {
+  constructor(public readonly tokenAddress: string, public readonly version: bigint) {
+    super(
+      version === 0n
+        ? `${tokenAddress} is not managed by any four.meme TokenManager`
+        : `${tokenAddress} is managed by four.meme TokenManager version ${version}, which this SDK does not support`,
+      'UNSUPPORTED_VERSION',
+      { tokenAddress, version: version.toString() }
+    );
+    this.name = 'UnsupportedVersionError';
+    Object.setPrototypeOf(this, UnsupportedVersionError.prototype);
+  }
+}
+
+export class TokenLaunchTimeoutError extends FourTradingError {
+  constructor(public readonly tokenAddress: string, public readonly timeoutMs: number) {
+    super(`${tokenAddress} did not go live within ${timeoutMs}ms`, 'TOKEN_LAUNCH_TIMEOUT', { tokenAddress, timeoutMs });
+    this.name = 'TokenLaunchTimeoutError';
+    Object.setPrototypeOf(this, TokenLaunchTimeoutError.prototype);
+  }
+}
+
+export class SymbolCollisionError extends FourTradingError {
+  constructor(public readonly tokenAddress: string, public readonly symbol: string, public readonly collidesWith: string[]) {
+    super(
+      `${tokenAddress} (${symbol}) collides with ${collidesWith.length} earlier token(s): ${collidesWith.join(', ')}`,
+      'SYMBOL_COLLISION',
+      { tokenAddress, symbol, collidesWith }
+    );
+    this.name = 'SymbolCollisionError';
+    Object.setPrototypeOf(this, SymbolCollisionError.prototype);
+  }
+}
+
+export class SymbolCollisionUncheckedError extends FourTradingError {
+  constructor(public readonly tokenAddress: string, public readonly reason: string) {
+    super(`Can't check ${tokenAddress} for symbol collisions: ${reason}`, 'SYMBOL_COLLISION_UNCHECKED', { tokenAddress, reason });
+    this.name = 'SymbolCollisionUncheckedError';
+    Object.setPrototypeOf(this, SymbolCollisionUncheckedError.prototype);
+  }
+}
+
+export class WireFormatError extends FourTradingError {
+  constructor(message: string, public readonly offset?: number) {
+    super(offset !== undefined ? `${message} (at byte ${offset})` : message, 'WIRE_FORMAT', { offset });
+    this.name = 'WireFormatError';
+    Object.setPrototypeOf(this, WireFormatError.prototype);
+  }
+}
+
+export class CreatorReputationError extends FourTradingError {
+  constructor(
+    public readonly tokenAddress: string,
+    public readonly creator: string,
+    public readonly score: number,
+    public readonly minScore: number
+  ) {
+    super(
+      `Creator ${creator} of ${tokenAddress} scores ${score}, below the required ${minScore}`,
+      'CREATOR_REPUTATION',
+      { tokenAddress, creator, score, minScore }
+    );
+    this.name = 'CreatorReputationError';
+    Object.setPrototypeOf(this, CreatorReputationError.prototype);
+  }
+}
+
+export class QuoteReturnedZeroError extends FourTradingError {
+  constructor(
+    public readonly side: 'buy' | 'sell',
+    public readonly tokenAddress: string,
+    public readonly amount: bigint,
+    public readonly reason: 'curveFull' | 'roundsToZero' // The curve has nothing left to sell, or the amount is too small to move it
+  ) {
+    super(
+      side === 'buy'
+        ? `Buying ${tokenAddress} with ${amount} wei returns zero tokens (${reason})`
+        : `Selling ${amount} of ${tokenAddress} returns zero BNB (${reason})`,
+      'QUOTE_RETURNED_ZERO',
+      { side, tokenAddress, amount: amount.toString(), reason }
+    );
+    this.name = 'QuoteReturnedZeroError';
+    Object.setPrototypeOf(this, QuoteReturnedZeroError.prototype);
+  }
+}
+
+export class AmountOutOfRangeError extends FourTradingError {
+  constructor(
+    public readonly tokenAddress: string,
+    public readonly amount: bigint,
+    public readonly min: bigint,
+    public readonly max: bigint
+  ) {
+    super(
+      amount < min
+        ? `Buy of ${amount} wei on ${tokenAddress} is below the minimum of ${min} wei; the trading fee would take all of it`
+        : `Buy of ${amount} wei on ${tokenAddress} is above the maximum of ${max} wei`,
+      'AMOUNT_OUT_OF_RANGE',
+      { tokenAddress, amount: amount.toString(), min: min.toString(), max: max.toString() }
+    );
+    this.name = 'AmountOutOfRangeError';
+    Object.setPrototypeOf(this, AmountOutOfRangeError.prototype);
+  }
+}
+
+export class TokenInfoLayoutError extends FourTradingError {
+  constructor(
+    public readonly expectedBytes: number,
+    public readonly actualBytes: number
+  ) {
+    super(
+      `_tokenInfos returned ${actualBytes} bytes, expected ${expectedBytes}; the TokenManager's token struct has changed`,
+      'TOKEN_INFO_LAYOUT',
+      { expectedBytes, actualBytes }
+    );
+    this.name = 'TokenInfoLayoutError';
+    Object.setPrototypeOf(this, TokenInfoLayoutError.prototype);
+  }
+}
+
+export class PriceFeedError extends FourTradingError {
+  constructor(public readonly feed: string, reason: string) {
+    super(`Price feed ${feed} is unusable: ${reason}`, 'PRICE_FEED_INVALID', { feed, reason });
+    this.name = 'PriceFeedError';
+    Object.setPrototypeOf(this, PriceFeedError.prototype);
+  }
+}
+
+export class StateSnapshotError extends FourTradingError {
+  constructor(reason: string) {
+    super(`Trader state snapshot can't be restored: ${reason}`, 'STATE_SNAPSHOT_INVALID', { reason });
+    this.name = 'StateSnapshotError';
+    Object.setPrototypeOf(this, StateSnapshotError.prototype);
+  }
+}
+
+export class TransactionDroppedError extends TransactionFailedError {
+  constructor(txHash: string) {
+    super('Transaction dropped before confirming: it is neither mined nor pending', txHash);
+    this.name = 'TransactionDroppedError';
+    Object.setPrototypeOf(this, TransactionDroppedError.prototype);
+  }
+}
+
+export class CurveFractionError extends FourTradingError {
+  constructor(public readonly requestedBps: number, public readonly maxBps: number) {
+    super(
+      `Buying ${requestedBps} bps of the remaining curve supply exceeds the per-transaction limit of ${maxBps} bps`,
+      'CURVE_FRACTION_EXCEEDED',
+      { requestedBps, maxBps }
+    );
+    this.name = 'CurveFractionError';
+    Object.setPrototypeOf(this, CurveFractionError.prototype);
+  }
+}
+
+export class GaslessApprovalUnsupportedError extends FourTradingError {
+  constructor(public readonly tokenAddress: string, public readonly reason: string) {
+    super(`${tokenAddress} does not support gasless approvals: ${reason}`, 'GASLESS_APPROVAL_UNSUPPORTED', { tokenAddress, reason });
+    this.name = 'GaslessApprovalUnsupportedError';
+    Object.setPrototypeOf(this, GaslessApprovalUnsupportedError.prototype);
+  }
+}
+
+export class CapabilityMissingError extends FourTradingError {
+  constructor(public readonly feature: string, public readonly required: string, public readonly depth?: number) {
+    super(
+      depth === undefined
+        ? `${feature} needs ${required}, which the node does not support`
+        : `${feature} needs state ${depth} blocks behind the head, older than the node keeps`,
+      'CAPABILITY_MISSING',
+      { feature, required, depth }
+    );
+    this.name = 'CapabilityMissingError';
+    Object.setPrototypeOf(this, CapabilityMissingError.prototype);
+  }
+}
+
+export class TargetPriceUnreachableError extends FourTradingError {
+  constructor(
+    public readonly tokenAddress: string,
+    public readonly targetAvgPrice: bigint,
+    public readonly minAvgPrice: bigint, // Average of a vanishingly small buy at the current price
+    public readonly maxAvgPrice: bigint // Average of a buy taking the rest of the curve's raise
+  ) {
+    super(
+      `No buy of ${tokenAddress} averages ${targetAvgPrice} wei per token; averages range from ${minAvgPrice} to ${maxAvgPrice}`,
+      'TARGET_PRICE_UNREACHABLE',
+      { tokenAddress, targetAvgPrice: targetAvgPrice.toString(), minAvgPrice: minAvgPrice.toString(), maxAvgPrice: maxAvgPrice.toString() }
+    );
+    this.name = 'TargetPriceUnreachableError';
+    Object.setPrototypeOf(this, TargetPriceUnreachableError.prototype);
+  }
+}
+
+export class PriceImpactTooHighError extends FourTradingError {
+  constructor(
+    public readonly tokenAddress: string,
+    public readonly side: 'buy' | 'sell',
+    public readonly impactBps: number,
+    public readonly maxImpactBps: number
+  ) {
+    super(
+      `${side === 'buy' ? 'Buy' : 'Sell'} of ${tokenAddress} would move the price ${impactBps / 100}%, above the ${maxImpactBps / 100}% limit`,
+      'PRICE_IMPACT_TOO_HIGH',
+      { tokenAddress, side, impactBps, maxImpactBps }
+    );
+    this.name = 'PriceImpactTooHighError';
+    Object.setPrototypeOf(this, PriceImpactTooHighError.prototype);
+  }
+}
+
+export class BalanceWaitTimeoutError extends FourTradingError {
+  constructor(
+    public readonly tokenAddress: string,
+    public readonly previous: bigint,
+    public readonly lastBalance: bigint | undefined, // Last successful read; undefined when every read failed
+    public readonly timeoutMs: number
+  ) {
+    super(
+      `Balance of ${tokenAddress} did not rise above ${previous} within ${timeoutMs}ms` +
+        (lastBalance === undefined ? ' (no balance read succeeded)' : ` (last read ${lastBalance})`),
+      'BALANCE_WAIT_TIMEOUT',
+      { tokenAddress, previous: previous.toString(), lastBalance: lastBalance?.toString(), timeoutMs }
+    );
+    this.name = 'BalanceWaitTimeoutError';
+    Object.setPrototypeOf(this, BalanceWaitTimeoutError.prototype);
+  }
+}
+
+export class ApprovalWaitTimeoutError extends FourTradingError {
+  constructor(
+    public readonly tokenAddress: string,
+    public readonly timeoutMs: number
+  ) {
+    super(
+      `Another approval of ${tokenAddress} did not settle within ${timeoutMs}ms and has no transaction to speed up`,
+      'APPROVAL_WAIT_TIMEOUT',
+      { tokenAddress, timeoutMs }
+    );
+    this.name = 'ApprovalWaitTimeoutError';
+    Object.setPrototypeOf(this, ApprovalWaitTimeoutError.prototype);
+  }
+}
+
+export class PositionPersistError extends FourTradingError {
+  constructor(
+    public readonly result: TransactionResult, // The trade itself went through and is booked in memory
+    public readonly writeError: unknown
+  ) {
+    super(
+      `Trade ${result.txHash} confirmed but its position update was not persisted: ${(writeError as any)?.message ?? writeError}`,
+      'POSITION_PERSIST_FAILED',
+      { txHash: result.txHash }
+    );
+    this.name = 'PositionPersistError';
+    Object.setPrototypeOf(this, PositionPersistError.prototype);
+  }
+}
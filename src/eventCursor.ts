@@ -0,0 +1,276 @@
+/**
+ * Resumable event streams
+ * A CursorStream delivers a contract's logs in chain order: first backfilled from where the stream
+ * last stopped, then live. After each delivered event it persists the event's position as the
+ * cursor, together with a short ring of recently delivered event ids. On restart the backfill starts
+ * at the cursor's block. Events in the ring are skipped, events older than the whole ring are taken
+ * as delivered, and anything else is delivered.
+ *
+ * Delivery is at-least-once. An event whose listener was interrupted by a crash is delivered again,
+ * so every event carries an `eventId` (tx hash + log index) that consumers can use to drop repeats.
+ */
+
+import { promises as fs } from 'fs';
+import * as path from 'path';
+import { getFourTradingInterface } from './abi';
+import { BackfillLog } from './backfill';
+import { BACKFILL_PAGE_SIZE, CURSOR_RING_SIZE } from './constants';
+import { ValidationError } from './errors';
+import { TokenCreateEvent } from './types';
+
+export interface EventPosition {
+  blockNumber: number;
+  logIndex: number;
+}
+
+export interface CursorLog extends BackfillLog {
+  index: number; // Log index within the block
+}
+
+/**
+ * A parsed event with where it sits on chain and its idempotency key
+ */
+export type CursorEvent<T> = T & EventPosition & { txHash: string; eventId: string };
+
+export interface CursorState {
+  cursor: EventPosition | null; // Last delivered event
+  recent: Array<EventPosition & { id: string }>; // Recently delivered events, oldest position first
+}
+
+export interface CursorStore {
+  load(key: string): Promise<CursorState | null>;
+  save(key: string, state: CursorState): Promise<void>;
+}
+
+export class MemoryCursorStore implements CursorStore {
+  private states: Map<string, string> = new Map();
+
+  async load(key: string): Promise<CursorState | null> {
+    const saved = this.states.get(key);
+    return saved === undefined ? null : JSON.parse(saved);
+  }
+
+  async save(key: string, state: CursorState): Promise<void> {
+    this.states.set(key, JSON.stringify(state));
+  }
+}
+
+/**
+ * One JSON file per stream key in `dir`, replaced atomically by temp-file rename on each save
+ */
+export class JsonFileCursorStore implements CursorStore {
+  constructor(private dir: string) {}
+
+  async load(key: string): Promise<CursorState | null> {
+    try {
+      return JSON.parse(await fs.readFile(this.file(key), 'utf8'));
+    } catch (error: any) {
+      if (error.code === 'ENOENT') {
+        return null;
+      }
+      throw error;
+    }
+  }
+
+  async save(key: string, state: CursorState): Promise<void> {
+    await fs.mkdir(this.dir, { recursive: true });
+    const file = this.file(key);
+    const temp = `${file}.tmp`;
+    const handle = await fs.open(temp, 'w');
+    try {
+      await handle.writeFile(JSON.stringify(state));
+      await handle.sync();
+    } finally {
+      await handle.close();
+    }
+    await fs.rename(temp, file);
+  }
+
+  private file(key: string): string {
+    return path.join(this.dir, `${encodeURIComponent(key)}.json`);
+  }
+}
+
+export interface CursorEventSource {
+  getBlockNumber(): Promise<number>;
+  getLogs(fromBlock: number, toBlock: number): Promise<CursorLog[]>; // The stream's logs in an inclusive range
+  watch(listener: (log: CursorLog) => void): () => void; // The stream's logs as they are mined; returns an unsubscribe
+}
+
+export interface CursorStreamOptions {
+  fromBlock?: number; // Backfill start when nothing is stored (default: live only)
+  overlapBlocks?: number; // Blocks before the cursor's fetched again, to catch events a reorg moved (default: 0)
+  ringSize?: number; // Event ids remembered for deduplication (default: CURSOR_RING_SIZE)
+  pageSize?: number; // Blocks per backfill getLogs query (default: BACKFILL_PAGE_SIZE)
+  onError?: (error: Error) => void; // A live delivery or save failed and the stream stopped (default: logged)
+}
+
+/**
+ * Idempotency key of a log: its transaction hash and log index
+ */
+export function eventId(txHash: string, logIndex: number): string {
+  return `${txHash.toLowerCase()}:${logIndex}`;
+}
+
+export function comparePositions(a: EventPosition, b: EventPosition): number {
+  return a.blockNumber - b.blockNumber || a.logIndex - b.logIndex;
+}
+
+/**
+ * TokenCreate event of a contract log, or null for any other log
+ */
+export function parseTokenCreateLog(log: BackfillLog): TokenCreateEvent | null {
+  let parsed;
+  try {
+    parsed = getFourTradingInterface().parseLog({ topics: [...log.topics], data: log.data });
+  } catch {
+    return null;
+  }
+  if (!parsed || parsed.name !== 'TokenCreate') {
+    return null;
+  }
+  return {
+    creator: parsed.args.creator,
+    token: parsed.args.token,
+    requestId: parsed.args.requestId,
+    name: parsed.args.name,
+    symbol: parsed.args.symbol,
+    totalSupply: parsed.args.totalSupply,
+    launchTime: parsed.args.launchTime,
+    launchFee: parsed.args.launchFee,
+  };
+}
+
+export class CursorStream<T> {
+  private state: CursorState = { cursor: null, recent: [] };
+  private ids: Set<string> = new Set();
+  private buffer: CursorLog[] = [];
+  private live = false;
+  private stopped = false;
+  private unwatch?: () => void;
+  private queue: Promise<void> = Promise.resolve();
+  private ringSize: number;
+
+  constructor(
+    private source: CursorEventSource,
+    private store: CursorStore,
+    private key: string,
+    private parse: (log: CursorLog) => T | null,
+    private listener: (event: CursorEvent<T>) => void | Promise<void>,
+    private options: CursorStreamOptions = {}
+  ) {
+    this.ringSize = options.ringSize ?? CURSOR_RING_SIZE;
+    if (!Number.isInteger(this.ringSize) || this.ringSize < 1) {
+      throw new ValidationError(`Cursor ring size must be a positive integer, got ${options.ringSize}`);
+    }
+  }
+
+  /**
+   * Last delivered event
+   */
+  get cursor(): EventPosition | null {
+    return this.state.cursor && { ...this.state.cursor };
+  }
+
+  /**
+   * Deliver everything after the stored cursor up to the current block, then go live. Resolves once
+   * live; rejects if the backfill fails (a listener error included), leaving the cursor at the last
+   * delivered event.
+   */
+  async start(): Promise<void> {
+    this.state = (await this.store.load(this.key)) ?? { cursor: null, recent: [] };
+    this.ids = new Set(this.state.recent.map((entry) => entry.id));
+    this.unwatch = this.source.watch((log) => this.onLive(log));
+
+    try {
+      const head = await this.source.getBlockNumber();
+      const cursor = this.state.cursor;
+      const fromBlock = cursor ? Math.max(0, cursor.blockNumber - (this.options.overlapBlocks ?? 0)) : this.options.fromBlock ?? head + 1;
+      const pageSize = Math.max(1, this.options.pageSize ?? BACKFILL_PAGE_SIZE);
+      for (let from = fromBlock; from <= head && !this.stopped; from += pageSize) {
+        const logs = await this.source.getLogs(from, Math.min(from + pageSize - 1, head));
+        for (const log of sortLogs(logs)) {
+          await this.deliver(log);
+        }
+      }
+
+      // Logs mined while backfilling; the ones the backfill already covered are in the ring
+      this.buffer = sortLogs(this.buffer);
+      while (this.buffer.length > 0) {
+        await this.deliver(this.buffer.shift()!);
+      }
+      this.live = true;
+    } catch (error) {
+      this.halt();
+      throw error;
+    }
+  }
+
+  /**
+   * Stop receiving logs and wait for the delivery in flight
+   */
+  async stop(): Promise<void> {
+    this.halt();
+    await this.queue;
+  }
+
+  private onLive(log: CursorLog): void {
+    if (this.stopped) {
+      return;
+    }
+    if (!this.live) {
+      this.buffer.push(log);
+      return;
+    }
+    this.queue = this.queue.then(() => this.deliver(log)).catch((error) => {
+      this.halt();
+      if (this.options.onError) {
+        this.options.onError(error);
+      } else {
+        console.error(`Cursor stream ${this.key} stopped:`, error.message);
+      }
+    });
+  }
+
+  private halt(): void {
+    this.stopped = true;
+    this.unwatch?.();
+    this.unwatch = undefined;
+  }
+
+  private async deliver(log: CursorLog): Promise<void> {
+    const id = eventId(log.transactionHash, log.index);
+    const position = { blockNumber: log.blockNumber, logIndex: log.index };
+    const oldest = this.state.recent[0];
+    if (this.stopped || this.ids.has(id) || (oldest && comparePositions(position, oldest) < 0)) {
+      return;
+    }
+    const event = this.parse(log);
+    if (!event) {
+      return;
+    }
+
+    await this.listener({ ...event, ...position, txHash: log.transactionHash, eventId: id });
+    this.remember(id, position);
+    await this.store.save(this.key, this.state);
+  }
+
+  private remember(id: string, position: EventPosition): void {
+    const recent = this.state.recent;
+    let at = recent.length;
+    while (at > 0 && comparePositions(recent[at - 1], position) > 0) {
+      at--;
+    }
+    recent.splice(at, 0, { id, ...position });
+    this.ids.add(id);
+    while (recent.length > this.ringSize) {
+      this.ids.delete(recent.shift()!.id);
+    }
+    const last = recent[recent.length - 1];
+    this.state.cursor = { blockNumber: last.blockNumber, logIndex: last.logIndex };
+  }
+}
+
+function sortLogs(logs: CursorLog[]): CursorLog[] {
+  return [...logs].sort((a, b) => a.blockNumber - b.blockNumber || a.index - b.index);
+}
@@ -0,0 +1,53 @@
+/**
+ * Events: streaming and indexing four.meme activity
+ * Imported separately (`@fnzero/four-trading-sdk/events`) by indexers, dashboards and signal
+ * processes that follow the chain without trading.
+ *
+ * WebSocket subscriptions, log backfill, resumable cursor streams, the block clock and what is
+ * built on the event stream: candles, indicators, momentum signals and watchlists. Connections are
+ * opened here, but nothing signs: the trader is not loaded, and sources that a trader implements
+ * (`attach(trader)`) only take it by type.
+ */
+
+export { TokenCreateEvent, TokenPurchaseEvent, TokenSaleEvent, LiquidityAddedEvent } from '../types';
+export { FOUR_TRADING_ABI, getFourTradingInterface } from '../abi';
+export { ConnectionConfig, ConnectionTlsOptions, createFetchRequest, createJsonRpcProvider, createWebSocketProvider, createProxyAgent } from '../connection';
+export { WebSocketManager, WebSocketManagerConfig } from '../websocketManager';
+export {
+  BackfillJob,
+  BackfillOptions,
+  BackfillProgress,
+  BackfillEvent,
+  BackfillSink,
+  BackfillLog,
+  BackfillLogSource,
+  parseTradeLog,
+} from '../backfill';
+export { WalletHistoryOptions, walletTradeHistory } from '../walletHistory';
+export {
+  CursorStream,
+  CursorStreamOptions,
+  CursorEventSource,
+  CursorEvent,
+  CursorLog,
+  CursorState,
+  CursorStore,
+  EventPosition,
+  MemoryCursorStore,
+  eventId,
+  comparePositions,
+  parseTokenCreateLog,
+} from '../eventCursor';
+export { BlockClock, BlockClockOptions, BlockHeader, BlockHeaderListener, BlockSource, averageBlockInterval, estimateBlockAt } from '../blocks';
+export { ChainHead, secondsSinceBlock, assertChainProgressing, headProvesProgress, watchForStall } from '../chainHealth';
+export { Candle, CandleAggregator, CandleListener } from '../candles';
+export { PriceUpdate, PriceListener, PriceChangeFilter } from '../priceStream';
+export { PriceSample, IndicatorInput, IndicatorListener, Series, Sma, Ema, Rsi, RateOfChange, Crossover, IndicatorStream } from '../indicators';
+export { MomentumTrigger, MomentumTriggerConfig, MomentumSignal, MomentumListener } from '../momentum';
+export { Watchlist, WatchlistConfig, WatchedToken, WatchlistEvent, WatchlistListener } from '../watchlist';
+export { VolumeStats, summarizeVolume } from '../volume';
+export { BlockTrade, SandwichReport, detectSandwich } from '../sandwich';
+export { Price } from '../price';
+export { parseTradeEvents, parseTokenCreateEvent, isFourMemeTransaction, getTransactionType, isLogRangeError, TOKEN_CREATE_TOPIC } from '../utils';
+export { BACKFILL_PAGE_SIZE, CURSOR_RING_SIZE, FOUR_MEME_ADDRESS } from '../constants';
+export { FourTradingError, ValidationError, ConnectionError, ChainStalledError, CancelledError } from '../errors';
@@ -0,0 +1,104 @@
+/**
+ * Extra trade costs
+ * Attributes relay fees, bundle tips and replacement costs to the trade that actually executed
+ */
+
+import { ExtraCost } from './types';
+import { ValidationError } from './errors';
+
+/**
+ * One transaction in a speed-up/cancel chain. All attempts share a nonce and only one mines.
+ */
+export interface ReplacementAttempt {
+  txHash: string;
+  nonce: number;
+  extraCosts?: ExtraCost[]; // Costs paid for this attempt whether or not it mines (relay fees, tips)
+}
+
+export function totalExtraCost(costs: readonly ExtraCost[] = []): bigint {
+  return costs.reduce((sum, cost) => sum + cost.amount, 0n);
+}
+
+/**
+ * Extra costs of a replacement chain once `confirmedHash` has mined: the confirmed attempt's own
+ * extra costs, plus the fees the replaced attempts paid regardless of mining (their gas is never
+ * charged), tagged with the replaced hash
+ */
+export function attributeReplacementCosts(attempts: readonly ReplacementAttempt[], confirmedHash: string): ExtraCost[] {
+  const confirmed = attempts.find((attempt) => attempt.txHash.toLowerCase() === confirmedHash.toLowerCase());
+  if (!confirmed) {
+    throw new ValidationError(`Confirmed transaction ${confirmedHash} is not part of the replacement chain`);
+  }
+  const foreign = attempts.find((attempt) => attempt.nonce !== confirmed.nonce);
+  if (foreign) {
+    throw new ValidationError(`Replacement ${foreign.txHash} has nonce ${foreign.nonce}, expected ${confirmed.nonce}`);
+  }
+
+  const costs: ExtraCost[] = (confirmed.extraCosts ?? []).map((cost) => ({ ...cost }));
+  for (const attempt of attempts) {
+    if (attempt === confirmed) {
+      continue;
+    }
+    for (const cost of attempt.extraCosts ?? []) {
+      costs.push({ ...cost, txHash: attempt.txHash });
+    }
+  }
+  return costs;
+}
+
+/**
+ * Split `total` across fills in proportion to their BNB amounts (the last fill takes the remainder)
+ */
+export function allocateExtraCost(total: bigint, amounts: readonly bigint[]): bigint[] {
+  const sum = amounts.reduce((acc, amount) => acc + amount, 0n);
+  if (amounts.length === 0) {
+    return [];
+  }
+  if (sum === 0n) {
+    return amounts.map((_, index) => (index === amounts.length - 1 ? total : 0n));
+  }
+
+  let allocated = 0n;
+  return amounts.map((amount, index) => {
+    if (index === amounts.length - 1) {
+      return total - allocated;
+    }
+    const share = (total * amount) / sum;
+    allocated += share;
+    return share;
+  });
+}
+
+/**
+ * First non-null result among the attempts' receipt waits; resolves null once every wait has
+ * settled without one, rethrowing the last error if all of them failed
+ */
+export function firstReceipt<T>(waits: Promise<T | null>[]): Promise<T | null> {
+  return new Promise((resolve, reject) => {
+    let remaining = waits.length;
+    let lastError: unknown;
+    let failed = 0;
+    const settle = () => {
+      if (--remaining > 0) return;
+      if (failed === waits.length) reject(lastError);
+      else resolve(null);
+    };
+    if (remaining === 0) {
+      resolve(null);
+      return;
+    }
+    for (const wait of waits) {
+      wait.then(
+        (value) => {
+          if (value !== null && value !== undefined) resolve(value);
+          settle();
+        },
+        (error) => {
+          lastError = error;
+          failed++;
+          settle();
+        }
+      );
+    }
+  });
+}
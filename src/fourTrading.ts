@@ -1,16 +1,178 @@
-import { ethers, Wallet, JsonRpcProvider, WebSocketProvider, EventLog, Log } from 'ethers';
-import { FOUR_TRADING_ABI } from './abi';
+import { ethers, Wallet, JsonRpcProvider, WebSocketProvider, ContractEventPayload, EventLog, Signature, SignatureLike, Transaction, TransactionResponse } from 'ethers';
+import {
+  getFourTradingInterface,
+  getErc20Interface,
+  getPancakeRouterInterface,
+  getPancakeFactoryInterface,
+  getTokenManagerHelperInterface,
+  getWbnbInterface,
+  getChainlinkAggregatorInterface,
+  getPermitInterface,
+} from './abi';
 import {
   TokenInfo,
   TokenInfoEx,
   Template,
   TransactionResult,
+  PendingTrade,
+  Cancellable,
+  ExtraCost,
+  TradeReadScope,
+  NonceStatus,
+  BatchApproveResult,
+  LiquidationResult,
+  TradeTags,
   TokenCreateEvent,
   TokenPurchaseEvent,
   TokenSaleEvent,
   LiquidityAddedEvent,
 } from './types';
-import { PriceCalculator, PriceInfo } from './priceCalculator';
+import { BuyCostBreakdown, BuyLimits, PriceCalculator, PriceInfo, QuoteOptions, buyCostBreakdown, buyPriceInfo, checkBuyLimits, zeroBuyReason } from './priceCalculator';
+import { Position, PositionTracker, breakEvenPrice } from './positionTracker';
+import type { PositionStore } from './positionStore';
+import { ConnectionConfig, createJsonRpcProvider, createWebSocketProvider } from './connection';
+import { TradeHistory } from './tradeHistory';
+import { SubAccountLedger, SubAccountReconciliation, SubAccountReservation } from './subAccounts';
+import { BlockTrade, SandwichReport, detectSandwich } from './sandwich';
+import { BackfillEvent, BackfillJob, BackfillOptions } from './backfill';
+import { CursorEvent, CursorStore, CursorStream, CursorStreamOptions, parseTokenCreateLog } from './eventCursor';
+import { encodeBuyCalldata } from './calldata';
+import { createAccessList } from './accessList';
+import { throwIfCancelled, waitUnlessCancelled } from './cancellation';
+import { ConfirmationStrategy, DEFAULT_CONFIRMATION, waitForBalanceAbove, waitForConfirmations, waitForConfirmationsOrDrop, waitForFinalized } from './confirmation';
+import { QuoteComparator, QuoteDivergence, QuoteDivergenceListener } from './quoteDivergence';
+import { ChainHead, assertChainProgressing, headProvesProgress, secondsSinceBlock, watchForStall } from './chainHealth';
+import { Candle, CandleAggregator, CandleListener } from './candles';
+import { PriceChangeFilter, PriceListener } from './priceStream';
+import { TokenAgeLimits, TokenCreation, assertTokenAge, findTokenCreationBlock } from './tokenAge';
+import { HolderStats, applyTransfers, summarizeHolders } from './holders';
+import { VolumeStats, summarizeVolume } from './volume';
+import { SlippageSuggestionOptions, suggestSlippage } from './slippageSuggestion';
+import { WalletHistoryOptions, walletTradeHistory } from './walletHistory';
+import { cooldownProbeBlock, inferCooldown } from './cooldown';
+import { inferMaxSellable, readSellLimitHint } from './sellLimit';
+import { GasProfiler } from './gasProfiler';
+import { readAllowances, readBuyQuotes, readTokenInfos } from './multicall';
+import { TokenDirectory, TokenDirectoryCursor, TokenDirectoryPage } from './tokenDirectory';
+import { TokenMatch, findCollisions } from './symbolCollisions';
+import { BlockTransaction, CreationBlockReport, analyzeCreationBlock } from './creationBlock';
+import { CreatorRegistry, CreatorReport } from './creatorRegistry';
+import { traceFunding } from './fundingTrace';
+import { replayPosition } from './positionRecovery';
+import { CompetitorBid, GasAuction, GasAuctionConfig, GasAuctionResult, competitorBidFrom } from './gasAuction';
+import {
+  TokenManagerRoute,
+  encodeVersionedBuy,
+  encodeVersionedBuyExact,
+  encodeVersionedSell,
+  isDefinitiveHelperFailure,
+  routeFromHelperInfo,
+} from './version';
+import { BlockClock, BlockHeader } from './blocks';
+import { CapabilityProbe, NodeCapabilities } from './capabilities';
+import { BuySimulation, CurveParams, LaunchTemplate, buySizeForAvgPrice, calcBuyAtReserves, curveParams, launchTemplate, simulateBuy } from './curve';
+import { LaunchWaitOptions, SnipeMode, waitForLaunch } from './snipe';
+import { ReplicaLagEvent, ReplicaLagListener, readAtLeast } from './readConsistency';
+import {
+  ReplacementAttempt,
+  allocateExtraCost,
+  attributeReplacementCosts,
+  firstReceipt,
+  totalExtraCost,
+} from './extraCosts';
+import { ChunkPlan, ChunkPlanOptions, localSellCost, planSellChunks, priceImpactBps } from './chunking';
+import { VerificationReport, classifyToken, isRegisteredTokenInfo } from './tokenVerification';
+import { Price } from './price';
+import { Validator } from './validator';
+import { TokenLaunchParams, validateTokenLaunch } from './tokenLaunch';
+import { applyGasMultiplier, applySlippage, isMethodUnsupportedError, parseTradeEvents, pickTags, resolveMinAmount, slippagePercentToBps } from './utils';
+import { checkedBps, mulDiv, toNumberChecked } from './math';
+import {
+  BnbReserveError,
+  ConnectionError,
+  CreatorReputationError,
+  GasConfigurationError,
+  HolderConcentrationError,
+  InsufficientBalanceError,
+  InvalidAmountError,
+  QuoteReturnedZeroError,
+  SymbolCollisionError,
+  SymbolCollisionUncheckedError,
+  TokenLaunchValidationError,
+  TokenCreationNotFoundError,
+  TransactionFailedError,
+  UnverifiedTokenError,
+  ValidationError,
+  StateSnapshotError,
+  TransactionDroppedError,
+  GaslessApprovalUnsupportedError,
+  ApprovalWaitTimeoutError,
+  CancelledError,
+  PositionPersistError,
+} from './errors';
+import { assertEconomicalSell, assertPriceImpact, isEconomicalSell } from './guards';
+import { TradePreview, previewTransaction } from './tradePreview';
+import { BnbUsdPrice, bnbToUsd, bnbUsdPriceFromRound } from './usdPrice';
+import {
+  OwnPendingTx,
+  PreemptionDecision,
+  PreemptionOutcome,
+  PreemptionPolicy,
+  minReplacementPrice,
+  planPreemption,
+  replacementFees,
+} from './preemption';
+import { ApprovalLock, InFlightApproval, sharedApprovalLock } from './approvalLock';
+import { RawFunction, decodeRawResult, encodeRawCall, parseRawFunction } from './rawCall';
+import { GaslessApproval, PermitRequest, permitRequest, recoverPermitSigner, resolvePermitDomain } from './permit';
+import { curveFractionAmount, realizedCurveFraction, resolveCurveFraction } from './curveFraction';
+import { SweepItem, SweepOptions, SweepReport, sweepCandidates, sweepRecovered } from './sweep';
+import { RestoreOptions, RestoreReport, TraderStateSnapshot, migrateTraderState, traderStateFromJson } from './traderState';
+import type { Watchlist } from './watchlist';
+import {
+  BUY_GAS_LIMIT_ESTIMATE,
+  SELL_GAS_LIMIT_ESTIMATE,
+  DEFAULT_FALLBACK_GAS_LIMITS,
+  PARTIAL_FILL_GAS_BUFFER_BPS,
+  SLIPPAGE_DENOMINATOR,
+  BSC_CHAIN_ID,
+  SLIPPAGE_SUGGESTION_BLOCKS,
+  SWEEP_SLIPPAGE_PERCENT,
+  EMERGENCY_GAS_PRICE_BPS,
+  MIN_GAS_LIMIT,
+  DEFAULT_TOKEN_DECIMALS,
+  PANCAKE_V2_ROUTER_ADDRESS,
+  PANCAKE_V2_FACTORY_ADDRESS,
+  WBNB_ADDRESS,
+  MULTICALL3_ADDRESS,
+  CHAINLINK_BNB_USD_FEED,
+  BNB_USD_MAX_AGE,
+  TOKEN_MANAGER_HELPER_ADDRESS,
+  OFFICIAL_TOKEN_CODE_HASHES,
+  CHAIN_STALL_THRESHOLD,
+  QUOTE_DIVERGENCE_THRESHOLD_BPS,
+  SECONDARY_QUOTE_BUDGET_MS,
+  QUOTE_DIVERGENCE_LOG_SIZE,
+  COOLDOWN_PROBE_OFFSETS,
+  SELL_LIMIT_PROBE_BPS,
+  SELL_LIMIT_PRECISION_BPS,
+  CHAIN_STALL_CHECK_INTERVAL,
+  CANDLE_CLOSE_GRACE_MS,
+  FINALITY_POLL_INTERVAL,
+  BALANCE_POLL_INTERVAL,
+  APPROVAL_WAIT_TIMEOUT_MS,
+  BACKFILL_PAGE_SIZE,
+  TOKEN_CREATE_LOOKBACK_BLOCKS,
+  HOLDER_TOP_N,
+  HOLDER_CACHE_TTL_MS,
+  CREATION_FUNDING_LOOKBACK_BLOCKS,
+  CREATOR_FUNDING_LOOKBACK_BLOCKS,
+  CREATOR_FUNDING_HOPS,
+  READ_CONSISTENCY_RETRIES,
+  READ_CONSISTENCY_RETRY_INTERVAL,
+  TRADER_STATE_VERSION,
+  REORG_WATCH_INTERVAL,
+} from './constants';
 
 /**
  * FOUR Launch Platform Trading SDK for BSC
@@ -22,44 +184,189 @@ export interface FourTradingConfig {
   wssUrl: string; // WebSocket endpoint for event subscriptions (required for real-time events)
   privateKey: string;
   contractAddress?: string;
+  minBnbReserve?: bigint; // BNB (in wei) always kept in the wallet to pay for future gas
+  pancakeRouter?: string; // PancakeSwap router (default: canonical V2 router)
+  pancakeFactory?: string; // PancakeSwap factory (default: canonical V2 factory)
+  minSellProceedsMarginBps?: number; // When set, refuse sells whose net proceeds don't beat gas cost by this margin
+  chainId?: bigint; // Chain id used when signing locally (default: 56, BSC mainnet)
+  connection?: ConnectionConfig; // Proxy, headers, timeouts and TLS options for both providers
+  officialTokenCodeHashes?: string[]; // Extra official token bytecode hashes (added to OFFICIAL_TOKEN_CODE_HASHES)
+  requireOfficialToken?: boolean; // Refuse buys of tokens that don't verify as Official; needs at least one official code hash
+  maxHolderConcentrationBps?: number; // Refuse buys when the top HOLDER_TOP_N holders own more of the supply (HolderConcentrationError)
+  holderCacheTtlMs?: number; // How long holders() reuses a token's scan before extending it (default: HOLDER_CACHE_TTL_MS)
+  maxPriceImpactBps?: number; // Refuse buys and sells that would move the price more than this (PriceImpactTooHighError)
+  autoApprove?: boolean; // Sells approve the TokenManager first (unlimited) when its allowance falls short
+  approvalWaitTimeoutMs?: number; // How long an auto-approving sell waits on another in-flight approval of the token before speeding it up (default: APPROVAL_WAIT_TIMEOUT_MS)
+  approvalLock?: ApprovalLock; // Coordinates auto-approvals between trader handles (default: sharedApprovalLock, process-wide)
+  symbolCollisionWindowBlocks?: number; // Refuse buys of tokens sharing a symbol or name with one created this many blocks earlier (SymbolCollisionError)
+  symbolCollisionWhitelist?: string[]; // Token addresses exempt from the symbol collision check
+  allowUncheckedSymbols?: boolean; // Let buys through when the symbol collision check can't be made; refused with SymbolCollisionUncheckedError otherwise
+  minCreatorReputation?: number; // Refuse buys of tokens whose creator scores below this, 0-100 (CreatorReputationError)
+  creatorRegistry?: CreatorRegistry; // Creator history behind creatorReputation (default: an empty in-memory registry); attached to this trader's streams when minCreatorReputation is set
+  creatorFundingLookbackBlocks?: number; // Blocks searched per hop for a creator's first funding (default: CREATOR_FUNDING_LOOKBACK_BLOCKS)
+  creatorFundingHops?: number; // Funding hops traced from a creator, 1 or 2 (default: CREATOR_FUNDING_HOPS)
+  useAccessLists?: boolean; // Attach an eth_createAccessList access list to buys (skipped if the node lacks it)
+  gasProfiler?: GasProfiler; // Learned gas limits to share or restore (default: a fresh profiler)
+  fallbackGasLimits?: Partial<Record<GasOperation, bigint>>; // Gas limits when the node lacks eth_estimateGas (default: DEFAULT_FALLBACK_GAS_LIMITS)
+  confirmation?: ConfirmationStrategy; // How trades wait for inclusion (default: 1 confirmation)
+  retryOnReorg?: boolean; // Re-quote and resubmit a buy or sell once when its transaction is dropped (e.g. reorged out) before confirming (default: false)
+  chainStallThreshold?: number; // Seconds without a new block before trades fail with ChainStalledError (0 disables)
+  readYourWritesWindowMs?: number; // After a fill, pin balance and token info reads to its block for this long (default: 0, off)
+  tokenCreateLookbackBlocks?: number; // Bound for token creation lookups (default: TOKEN_CREATE_LOOKBACK_BLOCKS)
+  creationFundingLookbackBlocks?: number; // Blocks searched for creator funding of launch-block buyers (default: CREATION_FUNDING_LOOKBACK_BLOCKS)
+  secondaryQuoter?: SecondaryQuoterConfig; // Reference provider every quote is compared against
+  provider?: JsonRpcProvider; // Existing HTTP provider to share (rpcUrl and connection are then ignored for it)
+  eventProvider?: WebSocketProvider; // Existing WebSocket provider to share (wssUrl and connection are then ignored for it)
+  subAccounts?: SubAccountLedger; // Ledger that trades tagged with `subAccount` are booked to and limited by
+  gas?: Omit<GasOptions, 'gasLimit'>; // Default transaction type and pricing; a call's own `gas` pricing replaces it
+  tokenDecimals?: Record<string, number>; // Decimals of tokens that aren't DEFAULT_TOKEN_DECIMALS, by address; used when formatting their amounts
+  bnbUsdFeed?: string; // Chainlink BNB/USD aggregator behind bnbUsdPrice (default: CHAINLINK_BNB_USD_FEED)
+  bnbUsdMaxAge?: number; // Seconds a feed round may age before bnbUsdPrice refuses it (default: BNB_USD_MAX_AGE)
+  maxCurveFractionBps?: number; // Largest share of the remaining curve supply buyCurveFraction buys in one transaction (default: 10000, all of it)
+  clampCurveFraction?: boolean; // Reduce larger buyCurveFraction requests to maxCurveFractionBps instead of throwing CurveFractionError (default: false)
+  capabilities?: Partial<NodeCapabilities>; // Node capabilities to assume instead of probing, for nodes that misreport them
+}
+
+export interface SecondaryQuoterConfig {
+  rpcUrl?: string; // Reference RPC endpoint; required unless `provider` is given, and must differ from the primary
+  provider?: JsonRpcProvider;
+  thresholdBps?: number; // Report divergences above this (default: QUOTE_DIVERGENCE_THRESHOLD_BPS)
+  budgetMs?: number; // Max extra wait for the reference quote (default: SECONDARY_QUOTE_BUDGET_MS)
 }
 
 export interface GasOptions {
+  type?: 'legacy' | 'eip1559'; // Transaction type; unset lets ethers pick from the fields given and the network
   gasLimit?: bigint;
+  gasMultiplier?: number; // Scales the node's gas estimate when gasLimit is unset (>= 1.0); a call's value beats the configured one
   gasPrice?: bigint;  // In wei, use ethers.parseUnits(amount, 'gwei') for gwei
   maxFeePerGas?: bigint;  // In wei, use ethers.parseUnits(amount, 'gwei') for gwei
   maxPriorityFeePerGas?: bigint;  // In wei, use ethers.parseUnits(amount, 'gwei') for gwei
 }
 
-export interface BuyParams {
+export type GasOperation = keyof typeof DEFAULT_FALLBACK_GAS_LIMITS;
+
+export interface BuyParams extends TradeTags, Cancellable {
   tokenAddress: string;
   fundsInBNB: bigint;  // In wei, use ethers.parseEther(amount) to convert from BNB
   minAmount?: bigint;  // In wei, use ethers.parseUnits(amount, 18) to convert
+  // Absolute token floor independent of slippage. The contract minimum is max(minAmount, absMinTokens),
+  // so the buy reverts if either bound is not met
+  absMinTokens?: bigint;
   to?: string; // Optional recipient address
   gas?: GasOptions;
+  // When the spend overshoots the remaining curve buyTokenAMAP refunds the excess, which costs more gas.
+  // 'gasBuffer' (default) pads the gas estimate; 'clamp' spends only what the curve can absorb
+  onPartialFill?: 'gasBuffer' | 'clamp';
+  expectedAmount?: bigint; // Quoted token amount, recorded in trade history to measure realized slippage
+  maxTokenAge?: number; // Seconds since creation; refuse buys of older tokens (TokenTooOldError)
+  minTokenAge?: number; // Seconds since creation; refuse buys of younger tokens (TokenTooYoungError)
+  confirmation?: ConfirmationStrategy; // Overrides the configured strategy for this trade
+  extraCosts?: ExtraCost[]; // Relay fees or tips paid for this trade, added to its cost basis
+  allowDustFills?: boolean; // Send even when the spend quotes zero tokens; otherwise a buy without a minimum throws QuoteReturnedZeroError
+}
+
+/**
+ * Signs transactions outside the SDK, e.g. in an HSM or a remote signing service
+ */
+export interface ExternalSigner {
+  address: string; // Account the key signs for; it pays for the buy and receives the tokens unless `to` is set
+  sign(unsigned: Transaction): Promise<SignatureLike>; // Signature over `unsigned.unsignedHash`
+}
+
+/**
+ * What a trade's send step needs from its pre-send pipeline
+ */
+interface PreparedTrade {
+  route: TokenManagerRoute;
+  txOptions: any;
+  data: string;
+  funds: bigint; // BNB spent (buys)
+  minAmount: bigint; // Minimum tokens out (buys) or BNB out (sells)
+  recipient: string;
+  reservation?: SubAccountReservation; // Sub-account BNB held for the buy until its fill is booked
+}
+
+/**
+ * A token's balances folded from its Transfer logs up to `toBlock`, extended from there on refresh
+ */
+interface HolderScan {
+  fromBlock: number;
+  toBlock: number;
+  balances: Map<string, bigint>;
+  totalSupply: bigint;
+  manager: string; // Holds the unsold curve supply
+  pair: string | null; // PancakeSwap pair holding the liquidity, once the token has graduated
+  scannedAt: number; // Date.now() of the last extension
 }
 
-export interface SellParams {
+export interface SellParams extends TradeTags, Cancellable {
   tokenAddress: string;
   amount: bigint;  // In wei, use ethers.parseUnits(amount, 18) to convert
   minFunds?: bigint;  // In wei, use ethers.parseEther(amount) to convert from BNB
   origin?: number; // Origin identifier (default: 0)
   feeRate?: bigint; // Custom fee rate (optional)
   feeRecipient?: string; // Custom fee recipient (optional)
+  expectedFunds?: bigint; // Quoted BNB proceeds, recorded in trade history to measure realized slippage
+  gas?: GasOptions;
+  force?: boolean; // Skip the uneconomical-sell guard
+  confirmation?: ConfirmationStrategy; // Overrides the configured strategy for this trade
+  extraCosts?: ExtraCost[]; // Relay fees or tips paid for this trade, added to its cost basis
+  allowDustFills?: boolean; // Send even when the amount quotes zero BNB; otherwise a sell without a minimum throws QuoteReturnedZeroError
+  autoApprove?: boolean; // Overrides the configured autoApprove for this sell
+}
+
+/**
+ * Per-buy options of buyTokenExact; the token gates are the same as buyToken's
+ */
+export interface ExactBuyOptions extends TradeTags, Cancellable, TokenAgeLimits {}
+
+export interface CurveFractionBuyOptions extends ExactBuyOptions {
+  clamp?: boolean; // Overrides clampCurveFraction for this buy
+  to?: string; // Recipient (default: the wallet)
+  gas?: GasOptions;
+}
+
+export interface RawCallOptions {
+  to?: string; // Contract to call (default: the configured four.meme contract)
+}
+
+export interface RawSendOptions extends TradeTags {
+  to?: string; // Contract to send to (default: the configured four.meme contract)
+  value?: bigint; // BNB to send with the call, in wei
   gas?: GasOptions;
+  confirmation?: ConfirmationStrategy; // Overrides the configured strategy for this transaction
+}
+
+export interface EmergencySellOptions extends TradeTags {
+  amount?: bigint; // Tokens to sell (default: the wallet's whole balance)
+  gas?: GasOptions; // Prices the exit instead of the aggressive preset (EMERGENCY_GAS_PRICE_BPS of the network price)
+  policy?: PreemptionPolicy; // What to do with this wallet's pending transactions (default: cancel buys of the token, reprice the rest)
+}
+
+export interface EmergencySellResult {
+  sell: TransactionResult;
+  decisions: PreemptionDecision[]; // One per pending transaction the trader had sent, lowest nonce first
+  replacements: PreemptionOutcome[]; // What sending each cancel or reprice did
+  untrackedPending: number; // Pending transactions sent outside this trader; they can't be preempted and still hold earlier nonces
 }
 
 export interface CreateTokenParams {
   args: string; // Encoded arguments
   signature?: string; // Optional signature for verification
   gas?: GasOptions;
+  launch: TokenLaunchParams; // Metadata `args` was built from; validated before sending (TokenLaunchValidationError)
 }
 
 // Event listener types
 export type TokenCreateListener = (event: TokenCreateEvent) => void;
-export type TokenPurchaseListener = (event: TokenPurchaseEvent) => void;
-export type TokenSaleListener = (event: TokenSaleEvent) => void;
+export type TokenPurchaseListener = (event: TokenPurchaseEvent, blockNumber: number) => void; // Block the trade was mined in
+export type TokenSaleListener = (event: TokenSaleEvent, blockNumber: number) => void;
 export type LiquidityAddedListener = (event: LiquidityAddedEvent) => void;
+export type BlockListener = (blockNumber: number) => void;
+export type PendingBuyListener = (bid: CompetitorBid) => void;
+
+// What a trade method passes on for recording its fills
+type FillContext = TradeTags & { expected?: bigint; extraCosts?: ExtraCost[] };
 
 export class FourTrading {
   private provider: JsonRpcProvider; // HTTP provider for transactions
@@ -69,21 +376,153 @@ export class FourTrading {
   private eventContract: ethers.Contract; // Contract instance for event subscriptions
   private contractAddress: string;
   private priceCalculator: PriceCalculator;
+  private tokenManagerHelper: ethers.Contract; // TokenManagerHelper3: token versions, and V1 reads and quotes
+  private minBnbReserve: bigint;
+  private pancakeRouter: string;
+  private pancakeFactory: string;
+  private routerWbnb?: Promise<string>; // WETH() of the configured router, read once
+  private minSellProceedsMarginBps?: number;
+  private positionTracker: PositionTracker = new PositionTracker();
+  private tradeHistory: TradeHistory = new TradeHistory();
+  private subAccounts?: SubAccountLedger;
+  private defaultGas?: Omit<GasOptions, 'gasLimit'>;
+  private chainId: bigint;
+  private nextNonce: number | null = null; // Locally managed nonce for the blind path
+  private officialTokenCodeHashes: Set<string>;
+  private requireOfficialToken: boolean;
+  private maxHolderConcentrationBps?: number;
+  private holderCacheTtlMs: number;
+  private holderScans: Map<string, Promise<HolderScan>> = new Map(); // By lowercased address; one extension in flight per token
+  private maxPriceImpactBps?: number;
+  private autoApprove: boolean;
+  private approvalWaitTimeoutMs: number;
+  private approvalLock: ApprovalLock;
+  private symbolCollisionWindowBlocks?: number;
+  private symbolCollisionWhitelist: Set<string>;
+  private allowUncheckedSymbols: boolean;
+  private detachDirectory?: () => void; // Set once the symbol collision gate has attached the token directory to this trader
+  private tokenDecimals: Map<string, number> = new Map(); // By lowercased address
+  private bnbUsdFeed: string;
+  private bnbUsdMaxAge: number;
+  private bnbUsdDecimals?: number; // Fixed for a feed, read once
+  private ownPending: Map<number, OwnPendingTx> = new Map(); // By nonce; entries below the mined nonce are stale
+  private subAccountReservations: Map<string, SubAccountReservation> = new Map(); // By tx hash, until the fill is booked
+  private verificationCache: Map<string, VerificationReport> = new Map(); // Bytecode is immutable, never expires
+  private launchTemplateCache: Map<string, LaunchTemplate> = new Map(); // Fixed at launch, never expires
+  private creationCache: Map<string, TokenCreation> = new Map(); // Creation never changes, never expires
+  private creationMisses: Map<string, number> = new Map(); // Oldest block searched without finding the creation; it can only be older
+  private tokenCreateLookbackBlocks: number;
+  private creationFundingLookbackBlocks: number;
+  private creationBlockReports: Map<string, CreationBlockReport> = new Map(); // A past block never changes
+  private minCreatorReputation?: number;
+  private creatorRegistry: CreatorRegistry;
+  private detachRegistry?: () => Promise<void>; // Set while the registry records this trader's streams for the reputation gate
+  private creatorFundingLookbackBlocks: number;
+  private creatorFundingHops: number;
+  private tokenDirectory: Promise<TokenDirectory> | null = null;
+  private tradingStatus: Promise<bigint> | null = null; // STATUS_TRADING, a contract constant
+  private versionCache: Map<string, TokenManagerRoute> = new Map(); // A token never changes manager
+  private useAccessLists: boolean;
+  private chainStallThreshold: number;
+  private latestHead?: ChainHead; // Newest head read so far (latest block or a receipt's); spares stall checks a read while recent
+  private confirmation: ConfirmationStrategy;
+  private retryOnReorg: boolean;
+  private maxCurveFractionBps: number;
+  private clampCurveFraction: boolean;
+  private gasProfiler: GasProfiler;
+  private blockClock?: BlockClock; // Built on first use by getBlockClock
+  private capabilityProbe: CapabilityProbe;
+  private fallbackGasLimits: Record<GasOperation, bigint>;
+  private estimateGasUnsupported = false; // Set once the node rejects eth_estimateGas as unsupported
+  private quoteComparator?: QuoteComparator;
+  private quoteDivergences: QuoteDivergence[] = [];
+  private readYourWritesWindowMs: number;
+  private readFloor: { block: number; until: number } | null = null; // Minimum block for reads after a fill
 
   // Event listeners storage with cleanup tracking
   private tokenCreateListeners: Map<string, { listener: TokenCreateListener; contractListener: any }> = new Map();
   private tokenPurchaseListeners: Map<string, { listener: TokenPurchaseListener; contractListener: any }> = new Map();
   private tokenSaleListeners: Map<string, { listener: TokenSaleListener; contractListener: any }> = new Map();
   private liquidityAddedListeners: Map<string, { listener: LiquidityAddedListener; contractListener: any }> = new Map();
+  private blockListeners: Map<string, BlockListener> = new Map();
+  private pendingBuyListeners: Map<string, (txHash: string) => void> = new Map();
+  private candleSubscriptions: Map<string, { tradeIds: string[]; timer?: NodeJS.Timeout }> = new Map();
+  private priceSubscriptions: Map<string, string[]> = new Map(); // Price stream id -> trade listener ids
+  private quoteDivergenceListeners: Map<string, QuoteDivergenceListener> = new Map();
+  private replicaLagListeners: Map<string, ReplicaLagListener> = new Map();
 
   constructor(config: FourTradingConfig) {
     this.contractAddress = config.contractAddress || '0x5c952063c7fc8610FFDB798152D69F0B9550762b';
+    this.minBnbReserve = config.minBnbReserve ?? 0n;
+    this.pancakeRouter = config.pancakeRouter ?? PANCAKE_V2_ROUTER_ADDRESS;
+    this.pancakeFactory = config.pancakeFactory ?? PANCAKE_V2_FACTORY_ADDRESS;
+    if (config.minSellProceedsMarginBps !== undefined) {
+      const marginBps = checkedBps(config.minSellProceedsMarginBps, 'minSellProceedsMarginBps');
+      if (marginBps < 0n) {
+        throw new ValidationError(`minSellProceedsMarginBps must not be negative, got: ${config.minSellProceedsMarginBps}`);
+      }
+      this.minSellProceedsMarginBps = toNumberChecked(marginBps, 'minSellProceedsMarginBps');
+    }
+    this.chainId = config.chainId ?? BSC_CHAIN_ID;
+    this.officialTokenCodeHashes = new Set(
+      [...OFFICIAL_TOKEN_CODE_HASHES, ...(config.officialTokenCodeHashes ?? [])].map((hash) => hash.toLowerCase())
+    );
+    this.requireOfficialToken = config.requireOfficialToken ?? false;
+    if (this.requireOfficialToken && this.officialTokenCodeHashes.size === 0) {
+      throw new ValidationError('requireOfficialToken needs at least one official token code hash; set officialTokenCodeHashes');
+    }
+    const secondary = config.secondaryQuoter;
+    if (secondary && !secondary.provider && !secondary.rpcUrl) {
+      throw new ValidationError('secondaryQuoter needs its own rpcUrl or provider');
+    }
+    if (secondary && !secondary.provider && secondary.rpcUrl === config.rpcUrl) {
+      // Quoting the primary against itself can never diverge
+      throw new ValidationError('secondaryQuoter.rpcUrl must differ from the primary rpcUrl');
+    }
+    this.maxHolderConcentrationBps = config.maxHolderConcentrationBps;
+    this.holderCacheTtlMs = config.holderCacheTtlMs ?? HOLDER_CACHE_TTL_MS;
+    this.maxPriceImpactBps = config.maxPriceImpactBps;
+    this.autoApprove = config.autoApprove ?? false;
+    this.approvalWaitTimeoutMs = config.approvalWaitTimeoutMs ?? APPROVAL_WAIT_TIMEOUT_MS;
+    this.approvalLock = config.approvalLock ?? sharedApprovalLock;
+    this.symbolCollisionWindowBlocks = config.symbolCollisionWindowBlocks;
+    this.symbolCollisionWhitelist = new Set((config.symbolCollisionWhitelist ?? []).map((token) => token.toLowerCase()));
+    this.allowUncheckedSymbols = config.allowUncheckedSymbols ?? false;
+    for (const [token, decimals] of Object.entries(config.tokenDecimals ?? {})) {
+      this.setTokenDecimals(token, decimals);
+    }
+    this.bnbUsdFeed = config.bnbUsdFeed ?? CHAINLINK_BNB_USD_FEED;
+    Validator.validateAddress(this.bnbUsdFeed, 'bnbUsdFeed');
+    this.bnbUsdMaxAge = config.bnbUsdMaxAge ?? BNB_USD_MAX_AGE;
+    this.tokenCreateLookbackBlocks = config.tokenCreateLookbackBlocks ?? TOKEN_CREATE_LOOKBACK_BLOCKS;
+    this.creationFundingLookbackBlocks = config.creationFundingLookbackBlocks ?? CREATION_FUNDING_LOOKBACK_BLOCKS;
+    this.minCreatorReputation = config.minCreatorReputation;
+    this.creatorRegistry = config.creatorRegistry ?? new CreatorRegistry();
+    this.creatorFundingLookbackBlocks = config.creatorFundingLookbackBlocks ?? CREATOR_FUNDING_LOOKBACK_BLOCKS;
+    this.creatorFundingHops = config.creatorFundingHops ?? CREATOR_FUNDING_HOPS;
+    this.useAccessLists = config.useAccessLists ?? false;
+    this.chainStallThreshold = config.chainStallThreshold ?? CHAIN_STALL_THRESHOLD;
+    this.confirmation = config.confirmation ?? DEFAULT_CONFIRMATION;
+    this.retryOnReorg = config.retryOnReorg ?? false;
+    this.maxCurveFractionBps = config.maxCurveFractionBps ?? toNumberChecked(SLIPPAGE_DENOMINATOR);
+    this.clampCurveFraction = config.clampCurveFraction ?? false;
+    this.gasProfiler = config.gasProfiler ?? new GasProfiler();
+    this.fallbackGasLimits = { ...DEFAULT_FALLBACK_GAS_LIMITS, ...config.fallbackGasLimits };
+    for (const gasLimit of Object.values(this.fallbackGasLimits)) {
+      Validator.validateGasOptions({ gasLimit });
+    }
+    this.readYourWritesWindowMs = config.readYourWritesWindowMs ?? 0;
+    this.subAccounts = config.subAccounts;
+    if (config.gas) {
+      Validator.validateGasOptions(config.gas);
+      this.defaultGas = { ...config.gas };
+    }
 
     // HTTP provider for transactions (required)
-    this.provider = new JsonRpcProvider(config.rpcUrl);
+    this.provider = config.provider ?? createJsonRpcProvider(config.rpcUrl, config.connection);
 
     // WebSocket provider for events (required - cannot use polling)
-    this.eventProvider = new WebSocketProvider(config.wssUrl);
+    this.eventProvider = config.eventProvider ?? createWebSocketProvider(config.wssUrl, config.connection);
 
     // Wallet connected to transaction provider
     this.wallet = new Wallet(config.privateKey, this.provider);
@@ -91,36 +530,116 @@ export class FourTrading {
     // Contract for transactions (uses wallet with HTTP provider)
     this.contract = new ethers.Contract(
       this.contractAddress,
-      FOUR_TRADING_ABI,
+      getFourTradingInterface(),
       this.wallet
     );
 
     // Contract for events (uses WebSocket provider for real-time subscriptions)
     this.eventContract = new ethers.Contract(
       this.contractAddress,
-      FOUR_TRADING_ABI,
+      getFourTradingInterface(),
       this.eventProvider
     );
 
     // Initialize price calculator with read-only contract on transaction provider
     const readOnlyContract = new ethers.Contract(
       this.contractAddress,
-      FOUR_TRADING_ABI,
+      getFourTradingInterface(),
       this.provider
     );
-    this.priceCalculator = new PriceCalculator(readOnlyContract);
+    this.tokenManagerHelper = new ethers.Contract(TOKEN_MANAGER_HELPER_ADDRESS, getTokenManagerHelperInterface(), this.provider);
+    const versionOf = async (token: string) => (await this.detectVersion(token)).version;
+    this.priceCalculator = new PriceCalculator(readOnlyContract, this.tokenManagerHelper, versionOf);
+
+    this.capabilityProbe = new CapabilityProbe(
+      {
+        send: (method, params) => this.provider.send(method, params),
+        sendBatch: async (requests) => {
+          const payload = requests.map(({ method, params }, id) => ({ method, params, id, jsonrpc: '2.0' as const }));
+          const responses: Array<{ id: number; result?: unknown; error?: any }> = await this.provider._send(payload);
+          if (!Array.isArray(responses)) {
+            throw new ConnectionError('Node does not support batch requests');
+          }
+          return payload.map(({ id, method }) => {
+            const response = responses.find((candidate) => candidate.id === id);
+            if (!response) {
+              throw new ConnectionError(`Node does not support batch requests: no answer to batched ${method}`);
+            }
+            if (response.error !== undefined) {
+              throw new ConnectionError(`Batched ${method} failed: ${response.error?.message ?? response.error}`, response.error);
+            }
+            return response.result;
+          });
+        },
+        subscribePending: async () => {
+          const id = await this.eventProvider.send('eth_subscribe', ['newPendingTransactions']);
+          await this.eventProvider.send('eth_unsubscribe', [id]);
+        },
+      },
+      config.capabilities
+    );
+
+    if (secondary) {
+      const secondaryProvider = secondary.provider ?? createJsonRpcProvider(secondary.rpcUrl!, config.connection);
+      const secondaryContract = new ethers.Contract(this.contractAddress, getFourTradingInterface(), secondaryProvider);
+      this.quoteComparator = new QuoteComparator(
+        new PriceCalculator(
+          secondaryContract,
+          new ethers.Contract(TOKEN_MANAGER_HELPER_ADDRESS, getTokenManagerHelperInterface(), secondaryProvider),
+          versionOf
+        ),
+        () => this.provider.getBlockNumber(),
+        () => secondaryProvider.getBlockNumber(),
+        {
+          thresholdBps: secondary.thresholdBps ?? QUOTE_DIVERGENCE_THRESHOLD_BPS,
+          budgetMs: secondary.budgetMs ?? SECONDARY_QUOTE_BUDGET_MS,
+        },
+        (divergence) => this.recordQuoteDivergence(divergence)
+      );
+    }
+
+    if (this.minCreatorReputation !== undefined) {
+      // The gate scores creators from what the registry has seen, so it has to be watching
+      this.detachRegistry = this.creatorRegistry.attach(this);
+    }
   }
 
   // ==================== Private Helpers ====================
 
+  /**
+   * Merge a call's gas options over the configured defaults. A call that sets a type or any price
+   * replaces the default pricing as a whole, so legacy and EIP-1559 fields never mix. The gas
+   * multiplier is merged on its own: the call's value if set, else the configured one.
+   */
+  private resolveGas(gas?: GasOptions): GasOptions | undefined {
+    if (!this.defaultGas) {
+      return gas;
+    }
+    if (!gas) {
+      return { ...this.defaultGas };
+    }
+    const overridesPricing = gas.type !== undefined
+      || gas.gasPrice !== undefined
+      || gas.maxFeePerGas !== undefined
+      || gas.maxPriorityFeePerGas !== undefined;
+    const gasMultiplier = gas.gasMultiplier ?? this.defaultGas.gasMultiplier;
+    return overridesPricing
+      ? { ...gas, gasMultiplier }
+      : { ...this.defaultGas, gasLimit: gas.gasLimit, gasMultiplier };
+  }
+
   private buildTxOptions(gas?: GasOptions, value?: bigint): any {
     const options: any = {};
+    gas = this.resolveGas(gas);
 
     if (value !== undefined) {
       options.value = value;
     }
 
     if (gas) {
+      if (gas.type) {
+        options.type = gas.type === 'legacy' ? 0 : 2;
+      }
       if (gas.gasLimit) {
         options.gasLimit = gas.gasLimit;
       }
@@ -138,460 +657,3563 @@ export class FourTrading {
     return options;
   }
 
-  // ==================== Trading Functions ====================
-
   /**
-   * Buy tokens using BNB (AMAP - As Much As Possible)
-   * This is the recommended method for buying tokens
+   * Without an explicit gas limit, set one from `estimate` scaled by the resolved gas multiplier.
+   * When the node doesn't support eth_estimateGas, the operation's fallback limit is used instead,
+   * and later transactions skip the estimate.
    */
-  async buyToken(params: BuyParams): Promise<TransactionResult> {
-    try {
-      const fundsWei = params.fundsInBNB;
-      const minAmount = params.minAmount || 0n;
-
-      console.log(`Buying token ${params.tokenAddress}`);
-      console.log(`Spending: ${ethers.formatEther(params.fundsInBNB)} BNB`);
-      console.log(`Min tokens: ${ethers.formatUnits(minAmount, 18)}`);
-
-      const txOptions = this.buildTxOptions(params.gas, fundsWei);
-      const recipient = params.to || this.wallet.address;
-
-      // Correct method signature: buyTokenAMAP(token, to, funds, minAmount)
-      const tx = await this.contract.buyTokenAMAP(
-        params.tokenAddress,
-        recipient,
-        fundsWei,
-        minAmount,
-        txOptions
-      );
-
-      console.log(`Transaction sent: ${tx.hash}`);
-      const receipt = await tx.wait();
-      console.log(`Transaction confirmed in block ${receipt.blockNumber}`);
+  private async applyGasLimit(
+    operation: GasOperation,
+    txOptions: any,
+    gas: GasOptions | undefined,
+    estimate: (txOptions: any) => Promise<bigint>
+  ): Promise<void> {
+    if (txOptions.gasLimit !== undefined) {
+      return;
+    }
+    const gasMultiplier = this.resolveGas(gas)?.gasMultiplier ?? 1;
+    Validator.validateGasOptions({ gasMultiplier });
+    if (this.estimateGasUnsupported) {
+      txOptions.gasLimit = this.fallbackGasLimits[operation];
+      return;
+    }
 
-      return {
-        success: true,
-        txHash: tx.hash,
-        receipt,
-      };
-    } catch (error: any) {
-      console.error('Buy transaction failed:', error.message);
-      throw error;
+    try {
+      txOptions.gasLimit = applyGasMultiplier(await estimate(txOptions), gasMultiplier);
+    } catch (error) {
+      if (!isMethodUnsupportedError(error)) {
+        throw error;
+      }
+      console.warn(`eth_estimateGas is not supported by the node, using fallback gas limits`);
+      this.estimateGasUnsupported = true;
+      txOptions.gasLimit = this.fallbackGasLimits[operation];
     }
   }
 
   /**
-   * Buy exact amount of tokens with maximum funds limit
+   * Add an access list for `data` to the transaction options when `useAccessLists` is set.
+   * Nodes without eth_createAccessList leave the options unchanged.
    */
-  async buyTokenExact(
-    tokenAddress: string,
-    amount: bigint,
-    maxFunds: bigint,
-    to?: string,
-    gas?: GasOptions
-  ): Promise<TransactionResult> {
-    try {
-      const tokenAmount = amount;
-      const maxFundsWei = maxFunds;
-
-      console.log(`Buying exact ${ethers.formatUnits(amount, 18)} tokens`);
-      console.log(`Max funds: ${ethers.formatEther(maxFunds)} BNB`);
+  private async attachAccessList(
+    txOptions: any,
+    data: string,
+    value?: bigint,
+    to: string = this.contractAddress,
+    from: string = this.wallet.address
+  ): Promise<void> {
+    if (!this.useAccessLists) {
+      return;
+    }
 
-      const txOptions = this.buildTxOptions(gas, maxFundsWei);
-      const recipient = to || this.wallet.address;
+    const result = await createAccessList(this.provider, {
+      from,
+      to,
+      data,
+      value,
+    });
+    if (result && result.accessList.length > 0) {
+      txOptions.accessList = result.accessList;
+    }
+  }
 
-      // Correct method signature: buyToken(token, to, amount, maxFunds)
-      const tx = await this.contract.buyToken(
-        tokenAddress,
-        recipient,
-        tokenAmount,
-        maxFundsWei,
-        txOptions
-      );
+  /**
+   * Throw ChainStalledError if no block arrived within `chainStallThreshold` seconds. The latest
+   * block is only read once the newest head already seen is older than the threshold.
+   */
+  private async enforceChainProgress(): Promise<void> {
+    if (this.chainStallThreshold <= 0 || headProvesProgress(this.latestHead, this.chainStallThreshold)) {
+      return;
+    }
+    assertChainProgressing(await this.currentBlock(), this.chainStallThreshold);
+  }
 
-      console.log(`Transaction sent: ${tx.hash}`);
-      const receipt = await tx.wait();
-      console.log(`Transaction confirmed in block ${receipt.blockNumber}`);
+  private noteHead(head: ChainHead): void {
+    if (!this.latestHead || head.number > this.latestHead.number) {
+      this.latestHead = head;
+    }
+  }
 
-      return {
-        success: true,
-        txHash: tx.hash,
-        receipt,
-      };
-    } catch (error: any) {
-      console.error('Buy exact transaction failed:', error.message);
-      throw error;
+  /**
+   * Wait for a receipt, failing with ChainStalledError instead of hanging if blocks stop.
+   * Sent transactions wait per `strategy` (default: the configured confirmation strategy).
+   */
+  private async waitForReceipt(
+    tx: { hash: string; wait(): Promise<any> } | Promise<any>,
+    strategy: ConfirmationStrategy = this.confirmation
+  ): Promise<any> {
+    const pending = 'wait' in tx ? this.confirm(tx, strategy) : tx;
+    if (this.chainStallThreshold <= 0) {
+      return await pending;
     }
+    return await watchForStall(pending, () => this.enforceChainProgress(), CHAIN_STALL_CHECK_INTERVAL);
   }
 
   /**
-   * Sell tokens for BNB
-   * IMPORTANT: Must approve token spending before calling this method
+   * Receipt of a sent transaction once it satisfies the confirmation strategy. For 'finalized',
+   * the receipt is re-read after finality so a reorg before finalization is reflected. With
+   * retryOnReorg, a transaction that is dropped before confirming rejects with TransactionDroppedError.
    */
-  async sellToken(params: SellParams): Promise<TransactionResult> {
-    try {
-      const amount = params.amount;
-      const minFunds = params.minFunds || 0n;
+  private async confirm(tx: { hash: string; wait(): Promise<any> }, strategy: ConfirmationStrategy): Promise<any> {
+    if (strategy.type === 'confirmations' && this.retryOnReorg) {
+      const receipt = await waitForConfirmationsOrDrop(tx.hash, strategy.count, {
+        getReceipt: () => this.provider.getTransactionReceipt(tx.hash),
+        isPending: async () => (await this.provider.getTransaction(tx.hash)) !== null,
+        getBlockNumber: () => this.provider.getBlockNumber(),
+      }, REORG_WATCH_INTERVAL);
+      if (receipt.status !== 1) {
+        throw new TransactionFailedError('Transaction reverted', tx.hash, { receipt });
+      }
+      return receipt;
+    }
+    if (strategy.type === 'confirmations') {
+      return strategy.count <= 1
+        ? await tx.wait()
+        : await waitForConfirmations(tx.hash, strategy.count, (hash, count) => this.provider.waitForTransaction(hash, count));
+    }
 
-      console.log(`Selling token ${params.tokenAddress}`);
-      console.log(`Amount: ${ethers.formatUnits(params.amount, 18)}`);
-      console.log(`Min funds: ${ethers.formatEther(minFunds)} BNB`);
+    for (;;) {
+      const receipt = await this.provider.waitForTransaction(tx.hash, 1);
+      if (!receipt) {
+        throw new TransactionFailedError('Transaction dropped before confirmation', tx.hash);
+      }
+      await waitForFinalized(receipt.blockNumber, () => this.getFinalizedBlockNumber(), FINALITY_POLL_INTERVAL);
 
-      const txOptions = this.buildTxOptions(params.gas);
+      const final = await this.provider.getTransactionReceipt(tx.hash);
+      if (final && final.blockHash === receipt.blockHash) {
+        if (final.status !== 1) {
+          throw new TransactionFailedError('Transaction reverted', tx.hash, { receipt: final });
+        }
+        return final;
+      }
+      if (this.retryOnReorg && !final && !(await this.provider.getTransaction(tx.hash))) {
+        throw new TransactionDroppedError(tx.hash);
+      }
+      // Reorged out before finality: wait for its new inclusion
+    }
+  }
 
-      // Use explicit function signature to avoid ambiguity with overloaded functions
-      // sellToken(address token, uint256 amount, uint256 minFunds)
-      const tx = await this.contract['sellToken(address,uint256,uint256)'](
-        params.tokenAddress,
-        amount,
-        minFunds,
-        txOptions
-      );
+  private async getFinalizedBlockNumber(): Promise<number> {
+    const block = await this.provider.getBlock('finalized');
+    if (!block) {
+      throw new ConnectionError('Provider returned no finalized block');
+    }
+    return block.number;
+  }
 
-      console.log(`Transaction sent: ${tx.hash}`);
-      const receipt = await tx.wait();
-      console.log(`Transaction confirmed in block ${receipt.blockNumber}`);
+  /**
+   * Estimate the BNB cost of a transaction's gas from the configured or current gas price
+   */
+  private async estimateGasCost(
+    gas?: GasOptions,
+    gasLimit: bigint = this.gasProfiler.get('buyTokenAMAP') ?? BUY_GAS_LIMIT_ESTIMATE
+  ): Promise<bigint> {
+    gas = this.resolveGas(gas);
+    const limit = gas?.gasLimit ?? gasLimit;
+    let price = gas?.gasPrice ?? gas?.maxFeePerGas;
+    if (price === undefined) {
+      const feeData = await this.provider.getFeeData();
+      price = feeData.gasPrice ?? feeData.maxFeePerGas ?? 0n;
+    }
+    return limit * price;
+  }
 
-      return {
-        success: true,
-        txHash: tx.hash,
-        receipt,
-      };
-    } catch (error: any) {
-      console.error('Sell transaction failed:', error.message);
-      throw error;
+  /**
+   * Refuse to trade tokens that don't verify as Official when `requireOfficialToken` is set
+   */
+  private async enforceOfficialToken(tokenAddress: string): Promise<void> {
+    if (!this.requireOfficialToken) {
+      return;
+    }
+    const report = await this.verifyFourmemeToken(tokenAddress);
+    if (report.status !== 'Official') {
+      throw new UnverifiedTokenError(tokenAddress, report.status);
     }
   }
 
   /**
-   * Create a new token on the platform
+   * Refuse buys of tokens whose top holders own more than `maxHolderConcentrationBps` of the supply
    */
-  async createToken(params: CreateTokenParams): Promise<TransactionResult> {
-    try {
-      console.log('Creating new token...');
+  private async enforceHolderConcentration(tokenAddress: string): Promise<void> {
+    if (this.maxHolderConcentrationBps === undefined) {
+      return;
+    }
+    const stats = await this.holders(tokenAddress);
+    if (stats.topConcentrationBps > this.maxHolderConcentrationBps) {
+      throw new HolderConcentrationError(stats.topConcentrationBps, this.maxHolderConcentrationBps, stats.topHolders.length);
+    }
+  }
 
-      const txOptions = this.buildTxOptions(params.gas);
+  /**
+   * Refuse trades that would move the price more than `maxPriceImpactBps`, measured as suggestChunks
+   * does: the trade's output against that of a tiny trade at the current price
+   */
+  private async enforcePriceImpact(tokenAddress: string, side: 'buy' | 'sell', amount: bigint, preview: TradePreview): Promise<void> {
+    if (this.maxPriceImpactBps === undefined) {
+      return;
+    }
+    const info = await this.priceCalculator.getTokenInfo(tokenAddress);
+    const impactBps = await priceImpactBps(info, amount, (state, size) =>
+      side === 'buy' ? this.calcBuyAmount(state, size) : this.calcSellCost(state, size)
+    );
+    preview.set('priceImpactBps', impactBps);
+    assertPriceImpact(tokenAddress, side, impactBps, this.maxPriceImpactBps);
+  }
 
-      let tx;
-      if (params.signature) {
-        tx = await this.contract.createToken(params.args, params.signature, txOptions);
-      } else {
-        tx = await this.contract.createToken(params.args, txOptions);
-      }
+  /**
+   * Refuse buys of tokens whose symbol or name collides with a token created up to
+   * `symbolCollisionWindowBlocks` blocks before it, unless whitelisted. The first check attaches
+   * the token directory to this trader, so later ones compare against its live index instead of
+   * scanning logs. A token that can't be compared (created beyond the lookback bound, or below
+   * what the index covers) is refused with SymbolCollisionUncheckedError unless
+   * `allowUncheckedSymbols` is set.
+   */
+  private async enforceSymbolCollision(tokenAddress: string): Promise<void> {
+    const window = this.symbolCollisionWindowBlocks;
+    if (window === undefined || this.symbolCollisionWhitelist.has(tokenAddress.toLowerCase())) {
+      return;
+    }
 
-      console.log(`Transaction sent: ${tx.hash}`);
-      const receipt = await tx.wait();
-      console.log(`Transaction confirmed in block ${receipt.blockNumber}`);
+    const directory = await this.getTokenDirectory();
+    this.detachDirectory ??= directory.attach(this);
+    let createdBlock = directory.indexedCreation(tokenAddress)?.block;
+    if (createdBlock === undefined) {
+      try {
+        createdBlock = (await this.tokenCreatedAt(tokenAddress)).block;
+      } catch (error) {
+        if (error instanceof TokenCreationNotFoundError) {
+          return this.uncheckedSymbol(tokenAddress, error.message);
+        }
+        throw error;
+      }
+    }
+    const records = await directory.creationsSince(createdBlock - window);
+    const position = records.findIndex((record) => record.token.toLowerCase() === tokenAddress.toLowerCase());
+    if (position < 0) {
+      return this.uncheckedSymbol(tokenAddress, `its creation at block ${createdBlock} is below the token index`);
+    }
+    const target = records[position];
+    const collisions = findCollisions(target, records.slice(0, position));
+    if (collisions.length > 0) {
+      throw new SymbolCollisionError(target.token, target.symbol, collisions.map((match) => match.token));
+    }
+  }
 
-      return {
-        success: true,
-        txHash: tx.hash,
-        receipt,
-      };
-    } catch (error: any) {
-      console.error('Create token failed:', error.message);
-      throw error;
+  private uncheckedSymbol(tokenAddress: string, reason: string): void {
+    if (!this.allowUncheckedSymbols) {
+      throw new SymbolCollisionUncheckedError(tokenAddress, reason);
     }
   }
 
   /**
-   * Add liquidity to a token (admin function)
+   * Refuse a token whose creator scores below `minCreatorReputation`, if set
    */
-  async addLiquidity(tokenAddress: string, gas?: GasOptions): Promise<TransactionResult> {
-    try {
-      console.log(`Adding liquidity for token ${tokenAddress}`);
+  private async enforceCreatorReputation(tokenAddress: string): Promise<void> {
+    if (this.minCreatorReputation === undefined) {
+      return;
+    }
+    const { creator } = await this.getTokenInfoEx(tokenAddress);
+    const head = await this.provider.getBlockNumber();
+    const depth = await this.capabilityProbe.get('archiveDepth');
+    // Without the history to trace funding, the creator is still scored on its own tokens
+    const report = depth === null || depth >= this.creatorFundingLookbackBlocks * this.creatorFundingHops
+      ? await this.scoreCreator(creator, head)
+      : this.creatorRegistry.report(creator, []);
+    if (report.score < this.minCreatorReputation) {
+      throw new CreatorReputationError(tokenAddress, creator, report.score, this.minCreatorReputation);
+    }
+  }
 
-      const txOptions = this.buildTxOptions(gas);
-      const tx = await this.contract.addLiquidity(tokenAddress, txOptions);
+  /**
+   * The token gates every buy passes, recorded in `preview`: chain progress, official token, age,
+   * holder concentration, symbol collision and creator reputation
+   */
+  private async checkBuyGates(preview: TradePreview, tokenAddress: string, limits: TokenAgeLimits): Promise<void> {
+    await preview.check('chainProgress', () => this.enforceChainProgress());
+    await preview.check('officialToken', () => this.enforceOfficialToken(tokenAddress));
+    await preview.check('tokenAge', () => this.enforceTokenAge(tokenAddress, limits));
+    await preview.check('holderConcentration', () => this.enforceHolderConcentration(tokenAddress));
+    await preview.check('symbolCollision', () => this.enforceSymbolCollision(tokenAddress));
+    await preview.check('creatorReputation', () => this.enforceCreatorReputation(tokenAddress));
+  }
 
-      console.log(`Transaction sent: ${tx.hash}`);
-      const receipt = await tx.wait();
-      console.log(`Transaction confirmed in block ${receipt.blockNumber}`);
+  /**
+   * Enforce the buy's token age limits, if any
+   */
+  private async enforceTokenAge(tokenAddress: string, limits: TokenAgeLimits): Promise<void> {
+    if (limits.maxTokenAge === undefined && limits.minTokenAge === undefined) {
+      return;
+    }
 
-      return {
-        success: true,
-        txHash: tx.hash,
-        receipt,
-      };
-    } catch (error: any) {
-      console.error('Add liquidity failed:', error.message);
-      throw error;
+    let created: number;
+    let beforeLookback = false;
+    try {
+      created = (await this.tokenCreatedAt(tokenAddress)).timestamp;
+    } catch (error) {
+      if (!(error instanceof TokenCreationNotFoundError)) {
+        throw error;
+      }
+      // Created before the oldest block searched, so that block's time bounds its age
+      const header = await this.provider.getBlock(error.searchedFrom);
+      if (!header) {
+        throw new ConnectionError(`Provider returned no block ${error.searchedFrom}`);
+      }
+      created = header.timestamp;
+      beforeLookback = true;
     }
+    assertTokenAge(Math.floor(Date.now() / 1000) - created, limits, beforeLookback);
   }
 
-  // ==================== Token Approval ====================
-
   /**
-   * Approve token spending before selling
+   * Ensure spending `spend` wei (plus gas) keeps the wallet above the configured BNB reserve
    */
-  async approveToken(
-    tokenAddress: string,
-    amount?: bigint,
-    gas?: GasOptions
-  ): Promise<TransactionResult> {
-    try {
-      const tokenABI = ['function approve(address spender, uint256 amount) returns (bool)'];
-      const tokenContract = new ethers.Contract(tokenAddress, tokenABI, this.wallet);
+  private async enforceBnbReserve(spend: bigint, gas?: GasOptions, account: string = this.wallet.address): Promise<void> {
+    if (this.minBnbReserve === 0n) {
+      return;
+    }
 
-      const approveAmount = amount || ethers.MaxUint256;
+    const [balance, gasCost] = await Promise.all([
+      this.provider.getBalance(account),
+      this.estimateGasCost(gas),
+    ]);
 
-      console.log(`Approving ${this.contractAddress} to spend tokens`);
+    if (balance < spend + gasCost + this.minBnbReserve) {
+      throw new BnbReserveError(spend + gasCost, balance, this.minBnbReserve);
+    }
+  }
 
-      const txOptions = this.buildTxOptions(gas);
-      const tx = await tokenContract.approve(this.contractAddress, approveAmount, txOptions);
+  /**
+   * Ledger for trades booked to `subAccount`; fails when none is configured
+   */
+  private requireSubAccounts(subAccount: string): SubAccountLedger {
+    if (!this.subAccounts) {
+      throw new ValidationError(`Trade is booked to sub-account ${subAccount} but no subAccounts ledger is configured`);
+    }
+    return this.subAccounts;
+  }
 
-      console.log(`Approval transaction sent: ${tx.hash}`);
-      const receipt = await tx.wait();
-      console.log(`Approval confirmed in block ${receipt.blockNumber}`);
+  /**
+   * Refuse buys the sub-account's BNB can't cover, estimated gas included, and reserve what it
+   * can so a concurrent buy can't pass the same check with it
+   */
+  private async enforceSubAccountBuy(
+    subAccount: string | undefined,
+    spend: bigint,
+    gas?: GasOptions
+  ): Promise<SubAccountReservation | undefined> {
+    if (subAccount === undefined) {
+      return undefined;
+    }
+    const ledger = this.requireSubAccounts(subAccount);
+    const gasCost = await this.estimateGasCost(gas);
+    return ledger.reserve(subAccount, spend + gasCost);
+  }
 
-      return {
-        success: true,
-        txHash: tx.hash,
-        receipt,
-      };
-    } catch (error: any) {
-      console.error('Approval failed:', error.message);
-      throw error;
+  /**
+   * Hold a sent buy's reservation until completeTrade books its fill
+   */
+  private holdReservation(txHash: string, reservation: SubAccountReservation | undefined): void {
+    if (reservation) {
+      this.subAccountReservations.set(txHash, reservation);
     }
   }
 
-  // ==================== Query Functions ====================
+  private releaseReservation(txHash: string): void {
+    this.subAccountReservations.get(txHash)?.release();
+    this.subAccountReservations.delete(txHash);
+  }
 
   /**
-   * Get token information
+   * Release a failed buy's reservation, unless it was only cancelled after broadcast: that trade is
+   * still live, so the reservation stays held until its PendingTrade's wait books the fill
    */
-  async getTokenInfo(tokenAddress: string): Promise<TokenInfo> {
-    return await this.priceCalculator.getTokenInfo(tokenAddress);
+  private releaseUnlessPending(reservation: SubAccountReservation | undefined, error: unknown): void {
+    if (!reservation || (error instanceof CancelledError && error.stage === 'afterBroadcast')) {
+      return;
+    }
+    reservation.release();
+    for (const [txHash, held] of this.subAccountReservations) {
+      if (held === reservation) {
+        this.subAccountReservations.delete(txHash);
+      }
+    }
   }
 
   /**
-   * Quote buy - calculate how many tokens you get for given BNB amount
-   * @param bnbAmount - Amount in wei, use ethers.parseEther(amount) to convert from BNB
+   * Refuse sells of more tokens than the sub-account holds
    */
-  async quoteBuy(tokenAddress: string, bnbAmount: bigint): Promise<PriceInfo> {
-    return await this.priceCalculator.quoteBuy(tokenAddress, bnbAmount);
+  private enforceSubAccountSell(subAccount: string | undefined, tokenAddress: string, amount: bigint): void {
+    if (subAccount === undefined) {
+      return;
+    }
+    this.requireSubAccounts(subAccount).assertCanSell(subAccount, tokenAddress, amount);
   }
 
   /**
-   * Quote sell - calculate how much BNB you get for given token amount
+   * Warn when the wallet is below the configured BNB reserve.
+   * Sells only spend gas, so they are never refused for the reserve.
+   */
+  private async warnIfBelowReserve(): Promise<void> {
+    if (this.minBnbReserve === 0n) {
+      return;
+    }
+
+    const balance = await this.provider.getBalance(this.wallet.address);
+    if (balance < this.minBnbReserve) {
+      console.warn(
+        `BNB balance ${ethers.formatEther(balance)} is below the configured reserve of ` +
+        `${ethers.formatEther(this.minBnbReserve)} BNB, top up the wallet to keep paying for gas`
+      );
+    }
+  }
+
+  /**
+   * Ensure the configured PancakeSwap address is usable before touching the DEX
+   */
+  private requirePancakeAddress(address: string, label: string): string {
+    if (!ethers.isAddress(address) || address === ethers.ZeroAddress) {
+      throw new ValidationError(`${label} must be a non-zero address to use PancakeSwap features, got: ${address}`);
+    }
+    return address;
+  }
+
+  /**
+   * Refuse sells whose quoted net proceeds don't exceed the gas we'd actually pay.
+   * Gas is estimated against the token's own manager (see detectVersion), so V1 sells are priced right.
+   */
+  private async enforceEconomicalSell(params: SellParams): Promise<void> {
+    if (this.minSellProceedsMarginBps === undefined || params.force) {
+      return;
+    }
+
+    let gasLimit = params.gas?.gasLimit;
+    if (gasLimit === undefined) {
+      try {
+        const route = await this.detectVersion(params.tokenAddress);
+        gasLimit = await this.provider.estimateGas({
+          from: this.wallet.address,
+          to: route.manager,
+          data: encodeVersionedSell(route.version, params.tokenAddress, params.amount, params.minFunds || 0n),
+        });
+      } catch {
+        gasLimit = this.gasProfiler.get('sellToken') ?? SELL_GAS_LIMIT_ESTIMATE;
+      }
+    }
+
+    const [quote, gasCost] = await Promise.all([
+      this.priceCalculator.quoteSell(params.tokenAddress, params.amount),
+      this.estimateGasCost(params.gas, gasLimit),
+    ]);
+
+    assertEconomicalSell(quote.bnbCost, gasCost, this.minSellProceedsMarginBps);
+  }
+
+  /**
+   * Build a transaction result stamped with its block's timestamp (not local wall-clock)
+   */
+  private async completeResult(txHash: string, receipt: any): Promise<TransactionResult> {
+    const timestamp = await this.blockTimestamp(receipt.blockNumber);
+    return { success: true, txHash, receipt, timestamp };
+  }
+
+  /**
+   * Timestamp of a block a receipt or log was seen in. A load-balanced RPC can hand those out
+   * before the replica answering getBlock has the block, so a missing block is retried.
+   */
+  private async blockTimestamp(blockNumber: number): Promise<number> {
+    for (let attempt = 0; attempt <= READ_CONSISTENCY_RETRIES; attempt++) {
+      const block = await this.provider.getBlock(blockNumber);
+      if (block) {
+        this.noteHead({ number: block.number, timestamp: block.timestamp });
+        return block.timestamp;
+      }
+      if (attempt < READ_CONSISTENCY_RETRIES) {
+        await new Promise((resolve) => setTimeout(resolve, READ_CONSISTENCY_RETRY_INTERVAL));
+      }
+    }
+    throw new ConnectionError(`Provider returned no block ${blockNumber} after it was mined`);
+  }
+
+  /**
+   * Build a trade result and record the wallet's fills
+   */
+  private async completeTrade(txHash: string, receipt: any, context: FillContext = {}): Promise<TransactionResult> {
+    try {
+      const result: TransactionResult = { ...(await this.completeResult(txHash, receipt)), ...pickTags(context) };
+      if (context.extraCosts && context.extraCosts.length > 0) {
+        result.extraCosts = context.extraCosts.map((cost) => ({ ...cost }));
+      }
+      this.raiseReadFloor(receipt.blockNumber);
+      const price = await this.recordFill(receipt, result.timestamp, context);
+      if (price) {
+        result.price = price;
+      }
+      this.forgetPending(txHash);
+      await this.flushPositions(result);
+      return result;
+    } finally {
+      this.releaseReservation(txHash); // The fill now debits the sub-account itself
+    }
+  }
+
+  /**
+   * Wait for the position store to hold a completed trade's fills, so a trade never resolves
+   * before its position survives a restart
+   */
+  private async flushPositions(result: TransactionResult): Promise<void> {
+    try {
+      await this.positionTracker.flush();
+    } catch (error) {
+      throw new PositionPersistError(result, error);
+    }
+  }
+
+  /**
+   * Pin reads to at least `block` for the read-your-writes window
+   */
+  private raiseReadFloor(block: number): void {
+    if (this.readYourWritesWindowMs <= 0) {
+      return;
+    }
+    const floor = this.readFloor && this.readFloor.until > Date.now() ? this.readFloor.block : 0;
+    this.readFloor = { block: Math.max(floor, block), until: Date.now() + this.readYourWritesWindowMs };
+  }
+
+  /**
+   * Run a read at a block no older than `minBlock` (default: the read-your-writes floor, if active).
+   * Without a minimum the read goes to the latest block as usual.
+   */
+  private async pinnedRead<T>(read: (blockTag?: number) => Promise<T>, minBlock?: number): Promise<T> {
+    if (minBlock === undefined && this.readFloor && this.readFloor.until > Date.now()) {
+      minBlock = this.readFloor.block;
+    }
+    if (minBlock === undefined) {
+      return await read();
+    }
+    return await readAtLeast(minBlock, () => this.provider.getBlockNumber(), read, {
+      retries: READ_CONSISTENCY_RETRIES,
+      intervalMs: READ_CONSISTENCY_RETRY_INTERVAL,
+      onLag: (event) => this.reportReplicaLag(event),
+    });
+  }
+
+  private reportReplicaLag(event: ReplicaLagEvent): void {
+    console.log(
+      `RPC replica lagging: needed block ${event.minBlock}, read at ${event.observedBlock} after ${event.retries} retries (${event.waitedMs}ms)`
+    );
+    this.replicaLagListeners.forEach((listener) => listener(event));
+  }
+
+  /**
+   * Reads that observe at least the block `result` was included in, for RPCs that load-balance
+   * across replicas which may lag behind the one that saw the trade
+   */
+  afterTrade(result: TransactionResult): TradeReadScope {
+    const minBlock: number = result.receipt.blockNumber;
+    return {
+      minBlock,
+      getTokenInfo: (tokenAddress) => this.readTokenInfo(tokenAddress, minBlock),
+      getTokenBalance: async (tokenAddress) =>
+        ethers.formatUnits(await this.readTokenBalance(tokenAddress, minBlock), this.getTokenDecimals(tokenAddress)),
+      getTokenBalanceRaw: (tokenAddress) => this.readTokenBalance(tokenAddress, minBlock),
+      getBNBBalance: async () => ethers.formatEther(await this.readBNBBalance(minBlock)),
+    };
+  }
+
+  private async readTokenInfo(tokenAddress: string, minBlock?: number): Promise<TokenInfo> {
+    return await this.pinnedRead((blockTag) => this.priceCalculator.getTokenInfo(tokenAddress, blockTag), minBlock);
+  }
+
+  private async readTokenBalance(tokenAddress: string, minBlock?: number): Promise<bigint> {
+    const tokenContract = new ethers.Contract(tokenAddress, getErc20Interface(), this.provider);
+    return await this.pinnedRead(
+      (blockTag) => tokenContract.balanceOf(this.wallet.address, blockTag === undefined ? {} : { blockTag }),
+      minBlock
+    );
+  }
+
+  private async readBNBBalance(minBlock?: number): Promise<bigint> {
+    return await this.pinnedRead((blockTag) => this.provider.getBalance(this.wallet.address, blockTag), minBlock);
+  }
+
+  /**
+   * Record this wallet's fills from a trade receipt in the position tracker and trade history.
+   * Returns the exact price over all of them, or undefined when the receipt has none.
+   */
+  private async recordFill(receipt: any, timestamp: number, context: FillContext = {}): Promise<Price | undefined> {
+    const { purchases, sales } = await parseTradeEvents(receipt, this.contract);
+    const wallet = this.wallet.address.toLowerCase();
+    const ownPurchases = purchases.filter((purchase) => purchase.account.toLowerCase() === wallet);
+    const ownSales = sales.filter((sale) => sale.account.toLowerCase() === wallet);
+
+    if (ownPurchases.length === 0 && ownSales.length === 0) {
+      return undefined;
+    }
+
+    const gasCost: bigint = receipt.gasUsed * (receipt.gasPrice ?? 0n);
+    const base = {
+      timestamp,
+      txHash: receipt.hash,
+      blockNumber: receipt.blockNumber,
+      tags: [...(context.tags ?? [])],
+      correlationId: context.correlationId,
+      subAccount: context.subAccount,
+      expectedAmount: context.expected,
+    };
+    // Gas is paid once per transaction, by the sub-account that sent it
+    const ledger = context.subAccount !== undefined ? this.subAccounts : undefined;
+    ledger?.chargeGas(context.subAccount!, gasCost);
+
+    // Extra costs raise the cost basis of buys and reduce the proceeds of sells
+    const fills = [...ownPurchases.map((p) => p.cost + p.fee), ...ownSales.map((s) => s.cost - s.fee)];
+    const extras = allocateExtraCost(totalExtraCost(context.extraCosts), fills);
+
+    for (const [index, purchase] of ownPurchases.entries()) {
+      const bnbAmount = purchase.cost + purchase.fee;
+      const extraCost = extras[index];
+      this.positionTracker.recordBuy(purchase.token, purchase.amount, bnbAmount + extraCost);
+      ledger?.recordBuy(context.subAccount!, purchase.token, purchase.amount, bnbAmount + extraCost);
+      this.tradeHistory.record({
+        ...base,
+        token: purchase.token,
+        direction: 'buy',
+        tokenAmount: purchase.amount,
+        bnbAmount,
+        fee: purchase.fee,
+        price: Price.fromAmounts(bnbAmount, purchase.amount),
+        gasCost,
+        extraCost,
+      });
+    }
+    for (const [index, sale] of ownSales.entries()) {
+      const bnbAmount = sale.cost - sale.fee;
+      const extraCost = extras[ownPurchases.length + index];
+      const realized = this.positionTracker.recordSell(sale.token, sale.amount, bnbAmount - extraCost);
+      ledger?.recordSell(context.subAccount!, sale.token, sale.amount, bnbAmount - extraCost);
+      this.tradeHistory.record({
+        ...base,
+        token: sale.token,
+        direction: 'sell',
+        tokenAmount: sale.amount,
+        bnbAmount,
+        fee: sale.fee,
+        price: Price.fromAmounts(bnbAmount, sale.amount),
+        gasCost,
+        extraCost,
+        realizedPnl: realized.basisUnknown ? undefined : realized.pnl,
+      });
+    }
+
+    const tokens = [...ownPurchases, ...ownSales].reduce((sum, fill) => sum + fill.amount, 0n);
+    return Price.fromAmounts(fills.reduce((sum, bnb) => sum + bnb, 0n), tokens);
+  }
+
+  // ==================== Trading Functions ====================
+
+  /**
+   * Buy tokens using BNB (AMAP - As Much As Possible)
+   * This is the recommended method for buying tokens
+   */
+  async buyToken(params: BuyParams): Promise<TransactionResult> {
+    return await this.withReorgRetry(() => this.executeBuy(params));
+  }
+
+  /**
+   * Buy like buyToken, but have `signer` sign the transaction instead of the configured wallet.
+   * The SDK builds the transaction for `signer.address` (nonce, gas, fees, chain id), passes it
+   * unsigned to `signer.sign`, checks the signature recovers to that address, then broadcasts it
+   * and waits for the receipt as usual. Signing may be remote and take as long as it needs.
+   */
+  async buyWithExternalSigner(params: BuyParams, signer: ExternalSigner): Promise<TransactionResult> {
+    Validator.validateAddress(signer.address, 'signer.address');
+    return await this.withReorgRetry(() => this.executeBuy(params, signer));
+  }
+
+  /**
+   * Run every step of buyToken short of sending: buy limits, token filters, curve capacity, the
+   * quote, reserve and sub-account limits, manager routing and gas. Failed checks are recorded,
+   * not thrown; `explain()` on the result renders the breakdown.
+   */
+  async previewBuy(params: BuyParams, account: string = this.wallet.address): Promise<TradePreview> {
+    const preview = new TradePreview('buy', params.tokenAddress);
+    await this.prepareBuy(params, account, preview);
+    return preview;
+  }
+
+  /**
+   * Run a trade and, with retryOnReorg, run it once more from scratch (re-quoted, same nonce) if its
+   * transaction is dropped before confirming. A second drop is thrown.
+   */
+  private async withReorgRetry(trade: () => Promise<TransactionResult>): Promise<TransactionResult> {
+    try {
+      return await trade();
+    } catch (error) {
+      if (!this.retryOnReorg || !(error instanceof TransactionDroppedError)) {
+        throw error;
+      }
+      console.log(`Transaction ${error.txHash} dropped before confirming, resubmitting once`);
+      return { ...(await trade()), reorgRetryOf: error.txHash };
+    }
+  }
+
+  private async executeBuy(params: BuyParams, signer?: ExternalSigner): Promise<TransactionResult> {
+    let reservation: SubAccountReservation | undefined;
+    try {
+      const account = signer?.address ?? this.wallet.address;
+      const preview = new TradePreview('buy', params.tokenAddress, true);
+      const prepared = (await this.prepareBuy(params, account, preview))!;
+      const { route, txOptions, data, funds, minAmount, recipient } = prepared;
+      reservation = prepared.reservation;
+
+      console.log(`Buying token ${params.tokenAddress}`);
+      console.log(`Spending: ${ethers.formatEther(funds)} BNB`);
+      console.log(`Min tokens: ${ethers.formatUnits(minAmount, this.getTokenDecimals(params.tokenAddress))}`);
+
+      // V2: buyTokenAMAP(token, to, funds, minAmount); V1: purchaseTokenAMAP on the V1 manager
+      const context = { ...params, expected: params.expectedAmount };
+      throwIfCancelled(params.signal);
+      const tx = signer
+        ? await this.sendExternallySigned(signer, { ...txOptions, to: route.manager, data })
+        : route.version === 2
+          ? await this.contract.buyTokenAMAP(params.tokenAddress, recipient, funds, minAmount, txOptions)
+          : await this.wallet.sendTransaction({ ...txOptions, to: route.manager, data });
+
+      console.log(`Transaction sent: ${tx.hash}`);
+      this.holdReservation(tx.hash, reservation);
+      if (!signer) {
+        this.trackPending(tx, params.tokenAddress, 'buy', params.subAccount);
+      }
+      const receipt = await waitUnlessCancelled(this.waitForReceipt(tx, params.confirmation), params.signal, () =>
+        this.pendingTrade(tx.hash, tx.nonce, context)
+      );
+      console.log(`Transaction confirmed in block ${receipt.blockNumber}`);
+      this.gasProfiler.observe('buyTokenAMAP', receipt.gasUsed);
+      return await this.completeTrade(tx.hash, receipt, context);
+    } catch (error: any) {
+      console.error('Buy transaction failed:', error.message);
+      this.releaseUnlessPending(reservation, error);
+      throw error;
+    }
+  }
+
+  /**
+   * Everything a buy does before sending, recorded in `preview`. Returns what the send needs, or
+   * null when a lenient preview failed a step the transaction is built from.
+   */
+  private async prepareBuy(params: BuyParams, account: string, preview: TradePreview): Promise<PreparedTrade | null> {
+    const tokenAddress = params.tokenAddress;
+    let fundsWei = params.fundsInBNB;
+    const minAmount = resolveMinAmount(params.minAmount, params.absMinTokens);
+    const recipient = params.to || account;
+    preview.set('account', account);
+    preview.set('recipient', recipient);
+    preview.set('fundsInBNB', fundsWei);
+    preview.set('minAmount', minAmount);
+
+    await preview.check('buyLimits', async () => {
+      const limits = await this.buyLimits(tokenAddress);
+      preview.set('minFunds', limits.minFunds);
+      preview.set('maxFunds', limits.maxFunds);
+      checkBuyLimits(tokenAddress, fundsWei, limits);
+    });
+    await this.checkBuyGates(preview, tokenAddress, params);
+
+    let gas = params.gas;
+    await preview.check('curveCapacity', async () => {
+      const fill = await this.priceCalculator.checkPartialFill(tokenAddress, fundsWei);
+      preview.set('willPartiallyFill', fill.willPartiallyFill);
+      if (!fill.willPartiallyFill) {
+        return;
+      }
+      preview.set('fillableAmount', fill.fillableAmount);
+      if (params.onPartialFill === 'clamp') {
+        if (fill.fillableAmount === 0n) {
+          throw new ValidationError(`Bonding curve of ${tokenAddress} is already full`);
+        }
+        if (preview.strict) {
+          console.log(`Spend exceeds remaining curve, clamping to ${ethers.formatEther(fill.fillableAmount)} BNB`);
+        }
+        fundsWei = fill.fillableAmount;
+      } else if (gas?.gasLimit === undefined) {
+        // Estimated against the token's own manager; detectVersion caches it for the managerRoute step
+        const route = await this.detectVersion(tokenAddress);
+        gas = { ...gas, gasLimit: await this.estimateRefundPathGas(route, tokenAddress, recipient, fundsWei, minAmount, account) };
+      }
+    });
+    preview.set('funds', fundsWei);
+
+    // Without a minimum nothing stops a dust fill on-chain, so make sure the spend buys something
+    const dustGuard = minAmount === 0n && !params.allowDustFills;
+    await preview.check('quote', async () => {
+      if (dustGuard || !preview.strict) {
+        const quote = await this.priceCalculator.quoteBuy(tokenAddress, fundsWei, { allowDustFills: !dustGuard });
+        preview.set('quotedTokens', quote.tokenAmount);
+        preview.set('fee', quote.fee);
+      }
+    });
+    await preview.check('priceImpact', () => this.enforcePriceImpact(tokenAddress, 'buy', fundsWei, preview));
+
+    await preview.check('bnbReserve', () => this.enforceBnbReserve(fundsWei, gas, account));
+    const reservation = await preview.check('subAccountLimits', () =>
+      this.enforceSubAccountBuy(params.subAccount, fundsWei, gas)
+    );
+    if (!preview.strict) {
+      reservation?.release(); // A preview sends nothing, so it holds nothing
+    }
+    try {
+      if (!preview.strict) {
+        // Left to the node on send, which rejects a transaction the account can't pay for
+        await preview.check('balance', async () => {
+          const [balance, gasCost] = await Promise.all([this.provider.getBalance(account), this.estimateGasCost(gas)]);
+          preview.set('balance', balance);
+          if (balance < fundsWei + gasCost) {
+            throw new InsufficientBalanceError(fundsWei + gasCost, balance);
+          }
+        });
+      }
+
+      const route = await preview.check('managerRoute', () => this.detectVersion(tokenAddress));
+      if (!route) {
+        return null;
+      }
+      const manager = route.manager;
+      preview.set('version', route.version);
+      preview.set('manager', manager);
+
+      const data = encodeVersionedBuy(route.version, tokenAddress, recipient, fundsWei, minAmount);
+      const txOptions = await this.prepareGas(preview, 'buy', gas, fundsWei, data, manager, account);
+      if (!txOptions) {
+        return null;
+      }
+      preview.transaction = previewTransaction(account, manager, data, fundsWei, txOptions);
+      return { route, txOptions, data, funds: fundsWei, minAmount, recipient, reservation };
+    } catch (error) {
+      reservation?.release();
+      throw error;
+    }
+  }
+
+  /**
+   * Transaction options for a trade with its gas limit applied, recorded as the preview's 'gas'
+   * check; undefined when a lenient preview couldn't build them
+   */
+  private async prepareGas(
+    preview: TradePreview,
+    operation: GasOperation,
+    gas: GasOptions | undefined,
+    value: bigint | undefined,
+    data: string,
+    to: string,
+    from: string
+  ): Promise<any> {
+    let txOptions: any;
+    await preview.check('gas', async () => {
+      txOptions = this.buildTxOptions(gas, value);
+      if (operation === 'buy') {
+        await this.attachAccessList(txOptions, data, value, to, from);
+      }
+      await this.applyGasLimit(operation, txOptions, gas, (options) => this.provider.estimateGas({ ...options, from, to, data }));
+    });
+    return txOptions;
+  }
+
+  /**
+   * Gas limit for a buy that takes the AMAP refund branch: the node's estimate of the call on the
+   * token's TokenManager, padded by PARTIAL_FILL_GAS_BUFFER_BPS since it is taken against pre-trade state
+   */
+  private async estimateRefundPathGas(
+    route: TokenManagerRoute,
+    tokenAddress: string,
+    recipient: string,
+    funds: bigint,
+    minAmount: bigint,
+    from: string = this.wallet.address
+  ): Promise<bigint> {
+    let estimate: bigint;
+    try {
+      estimate = await this.provider.estimateGas({
+        from,
+        to: route.manager,
+        data: encodeVersionedBuy(route.version, tokenAddress, recipient, funds, minAmount),
+        value: funds,
+      });
+    } catch {
+      estimate = this.gasProfiler.get('buyTokenAMAP') ?? BUY_GAS_LIMIT_ESTIMATE;
+    }
+    return (estimate * PARTIAL_FILL_GAS_BUFFER_BPS) / SLIPPAGE_DENOMINATOR;
+  }
+
+  /**
+   * Complete `request` as a wallet would (pending nonce, chain id, network fees when no pricing is
+   * set), have `signer` sign it and broadcast it
+   */
+  private async sendExternallySigned(signer: ExternalSigner, request: any): Promise<TransactionResponse> {
+    const priced = request.gasPrice !== undefined || request.maxFeePerGas !== undefined;
+    const [nonce, feeData] = await Promise.all([
+      this.provider.getTransactionCount(signer.address, 'pending'),
+      priced ? null : this.provider.getFeeData(),
+    ]);
+    let fees = {};
+    if (feeData) {
+      fees = request.type !== 0 && feeData.maxFeePerGas !== null
+        ? { type: 2, maxFeePerGas: feeData.maxFeePerGas, maxPriorityFeePerGas: feeData.maxPriorityFeePerGas }
+        : { type: 0, gasPrice: feeData.gasPrice };
+    }
+
+    const unsigned = Transaction.from({ ...request, ...fees, nonce, chainId: this.chainId });
+    const signed = unsigned.clone();
+    signed.signature = Signature.from(await signer.sign(unsigned));
+    if (signed.from!.toLowerCase() !== signer.address.toLowerCase()) {
+      throw new ValidationError(`External signer signed for ${signed.from}, expected ${signer.address}`);
+    }
+    return await this.provider.broadcastTransaction(signed.serialized);
+  }
+
+  /**
+   * Buy tokens with the whole BNB balance, minus estimated gas and the configured reserve
+   */
+  async buyMax(
+    tokenAddress: string,
+    minAmount?: bigint,
+    to?: string,
+    gas?: GasOptions,
+    tagging?: TradeTags & Cancellable
+  ): Promise<TransactionResult> {
+    const [balance, gasCost] = await Promise.all([
+      this.provider.getBalance(this.wallet.address),
+      this.estimateGasCost(gas),
+    ]);
+
+    const spendable = balance - gasCost - this.minBnbReserve;
+    if (spendable <= 0n) {
+      throw new BnbReserveError(gasCost, balance, this.minBnbReserve);
+    }
+
+    return await this.buyToken({
+      tokenAddress,
+      fundsInBNB: spendable,
+      minAmount,
+      to,
+      gas,
+      ...tagging,
+    });
+  }
+
+  /**
+   * The wallet's transaction count at the latest block and including pending transactions.
+   * A gap between them means transactions are stuck in the mempool; both come from the node
+   * the trader reads from, so another node's mempool may differ.
+   */
+  async nonceStatus(): Promise<NonceStatus> {
+    const [latest, pending] = await Promise.all([
+      this.provider.getTransactionCount(this.wallet.address, 'latest'),
+      this.provider.getTransactionCount(this.wallet.address, 'pending'),
+    ]);
+    return { latest, pending };
+  }
+
+  /**
+   * Load the wallet's pending nonce into the local nonce manager used by `buyBlind`
+   */
+  async syncNonce(): Promise<number> {
+    this.nextNonce = await this.provider.getTransactionCount(this.wallet.address, 'pending');
+    return this.nextNonce;
+  }
+
+  /**
+   * Wait until a token is live (registered, trading and past its launch time) and return the first
+   * fresh TokenInfo showing it. Checked on every block and on the token's TokenCreate event; in
+   * 'mempool' mode a pending buy of the token by anyone also counts, ignoring launchTime.
+   * Rejects with TokenLaunchTimeoutError after `timeoutMs`.
+   */
+  async waitForToken(
+    tokenAddress: string,
+    timeoutMs: number,
+    options: Omit<LaunchWaitOptions, 'timeoutMs' | 'now'> = {}
+  ): Promise<TokenInfo> {
+    Validator.validateAddress(tokenAddress, 'tokenAddress');
+    if (options.mode === 'mempool') {
+      await this.capabilityProbe.require("waitForToken's mempool mode", 'pendingSubscription');
+    }
+    const tradingStatus = await this.getTradingStatus();
+    const token = tokenAddress.toLowerCase();
+
+    return await waitForLaunch(tokenAddress, {
+      // Reads at a block refresh the token info cache, so the buy that follows reuses them
+      readInfo: async (blockTag) =>
+        this.priceCalculator.getTokenInfo(tokenAddress, blockTag ?? await this.provider.getBlockNumber()),
+      watchBlocks: (listener) => {
+        const id = this.onBlock(listener);
+        return () => this.off(id);
+      },
+      watchCreation: (listener) => {
+        const id = this.onTokenCreate((event) => {
+          if (event.token.toLowerCase() === token) {
+            listener();
+          }
+        });
+        return () => this.off(id);
+      },
+      watchPendingBuys: (listener) => {
+        const id = this.onPendingBuy(tokenAddress, () => listener());
+        return () => this.off(id);
+      },
+    }, tradingStatus, { ...options, timeoutMs });
+  }
+
+  /**
+   * Wait for a token to go live, then buy it at once. The minimum is quoted locally from the fresh
+   * TokenInfo (curve math at the default trading fee), so no quote round trip sits between the
+   * launch and the buy.
+   */
+  async snipe(
+    tokenAddress: string,
+    fundsInBNB: bigint,
+    slippagePercent: number,
+    timeoutMs: number,
+    options: Omit<BuyParams, 'tokenAddress' | 'fundsInBNB' | 'minAmount' | 'expectedAmount'> & { mode?: SnipeMode } = {}
+  ): Promise<TransactionResult> {
+    Validator.validateAmount(fundsInBNB, 'fundsInBNB');
+    Validator.validateSlippage(slippagePercent);
+    const { mode, ...buy } = options;
+
+    const info = await this.waitForToken(tokenAddress, timeoutMs, { mode, signal: options.signal });
+    const quote = calcBuyAtReserves(curveParams(tokenAddress, info), fundsInBNB);
+
+    return await this.buyToken({
+      ...buy,
+      tokenAddress,
+      fundsInBNB,
+      minAmount: applySlippage(quote.tokenAmount, slippagePercent),
+      expectedAmount: quote.tokenAmount,
+    });
+  }
+
+  private getTradingStatus(): Promise<bigint> {
+    if (!this.tradingStatus) {
+      this.tradingStatus = this.contract.STATUS_TRADING() as Promise<bigint>;
+      this.tradingStatus.catch(() => {
+        this.tradingStatus = null;
+      });
+    }
+    return this.tradingStatus;
+  }
+
+  /**
+   * Latency-optimized buy that performs zero reads.
+   * Everything normally derived from quotes and the node is supplied by the caller:
+   * `minAmount` (may be 0n), a pre-resolved gas price (`gasPrice`, or `maxFeePerGas` + `maxPriorityFeePerGas`)
+   * and the local nonce (call `syncNonce` once first; concurrent blind buys take consecutive nonces). Without `gas.gasLimit` the gas profiler's
+   * learned limit is used, not a scaled estimate (`gasMultiplier` does not apply); the very first blind
+   * buy without one runs a single estimateGas to learn it, or takes the fallback buy limit from a node
+   * without eth_estimateGas.
+   * Otherwise the transaction is signed locally and exactly one RPC (`eth_sendRawTransaction`) is made
+   * before this resolves; use the returned handle's `wait` to complete the result from the receipt.
+   * Gas, reserve and position checks are skipped on this path; a sub-account's budget is still
+   * enforced, from the ledger and the supplied gas price alone, and stays reserved until the handle's
+   * `wait` books the fill. The buy always goes to the configured
+   * (V2) contract, since finding a token's manager version takes a read.
+   */
+  async buyBlind(
+    tokenAddress: string,
+    fundsInBNB: bigint,
+    minAmount: bigint,
+    gas: GasOptions,
+    to?: string,
+    tagging?: TradeTags & Cancellable
+  ): Promise<PendingTrade> {
+    if (this.nextNonce === null) {
+      throw new ValidationError('Local nonce not initialized, call syncNonce() before buyBlind()');
+    }
+    gas = this.resolveGas(gas) ?? gas;
+    const legacy = gas.type !== undefined ? gas.type === 'legacy' : gas.gasPrice !== undefined;
+    if (legacy ? gas.gasPrice === undefined : gas.maxFeePerGas === undefined || gas.maxPriorityFeePerGas === undefined) {
+      throw new GasConfigurationError('buyBlind requires gasPrice or both maxFeePerGas and maxPriorityFeePerGas');
+    }
+
+    const nonce = this.nextNonce++; // Reserved before the first await, so concurrent blind buys never share one
+    const recipient = to || this.wallet.address;
+    const data = encodeBuyCalldata(tokenAddress, recipient, fundsInBNB, minAmount);
+    let txHash: string;
+    let reservation: SubAccountReservation | undefined;
+    try {
+      const gasLimit = gas.gasLimit ?? await this.gasProfiler.limitFor('buyTokenAMAP', () =>
+        this.contract['buyTokenAMAP(address,address,uint256,uint256)'].estimateGas(
+          tokenAddress,
+          recipient,
+          fundsInBNB,
+          minAmount,
+          { value: fundsInBNB }
+        )
+      ).catch((error) => {
+        if (!isMethodUnsupportedError(error)) {
+          throw error;
+        }
+        this.estimateGasUnsupported = true;
+        return this.fallbackGasLimits.buy;
+      });
+
+      reservation = await this.enforceSubAccountBuy(tagging?.subAccount, fundsInBNB, { ...gas, gasLimit });
+
+      const signedTx = await this.wallet.signTransaction({
+        to: this.contractAddress,
+        from: this.wallet.address,
+        data,
+        value: fundsInBNB,
+        nonce,
+        chainId: this.chainId,
+        gasLimit,
+        ...(legacy
+          ? { type: 0, gasPrice: gas.gasPrice }
+          : { type: 2, maxFeePerGas: gas.maxFeePerGas, maxPriorityFeePerGas: gas.maxPriorityFeePerGas }),
+      });
+      txHash = await this.provider.send('eth_sendRawTransaction', [signedTx]);
+    } catch (error) {
+      reservation?.release();
+      await this.resyncBlindNonce(nonce);
+      throw error;
+    }
+    this.holdReservation(txHash, reservation);
+    this.trackPending(
+      { hash: txHash, nonce, gasPrice: gas.gasPrice, maxFeePerGas: gas.maxFeePerGas },
+      tokenAddress,
+      'buy',
+      tagging?.subAccount
+    );
+
+    return this.pendingTrade(txHash, nonce, tagging);
+  }
+
+  /**
+   * Give back the nonce a failed blind buy reserved. The send may or may not have reached the node,
+   * so the local nonce is resynced from its pending count; a later reservation already made by a
+   * concurrent blind buy is left in place, and a failed resync forces a syncNonce() before the next.
+   */
+  private async resyncBlindNonce(reserved: number): Promise<void> {
+    if (this.nextNonce !== reserved + 1) {
+      return;
+    }
+    try {
+      const pending = await this.provider.getTransactionCount(this.wallet.address, 'pending');
+      if (this.nextNonce === reserved + 1) {
+        this.nextNonce = pending;
+      }
+    } catch {
+      this.nextNonce = null;
+    }
+  }
+
+  /**
+   * Build a handle that completes a broadcast trade from its receipt
+   */
+  private pendingTrade(txHash: string, nonce: number, context: FillContext = {}): PendingTrade {
+    const handle: PendingTrade = {
+      txHash,
+      nonce,
+      ...pickTags(context),
+      wait: async (confirmations: number = 1, signal?: AbortSignal): Promise<TransactionResult> => {
+        const receipt = await waitUnlessCancelled(
+          this.waitForReceipt(this.provider.waitForTransaction(txHash, confirmations)),
+          signal,
+          () => handle
+        );
+        if (!receipt || receipt.status !== 1) {
+          this.releaseReservation(txHash);
+          throw new TransactionFailedError('Transaction reverted', txHash, { receipt });
+        }
+        return await this.completeTrade(txHash, receipt, context);
+      },
+    };
+    return handle;
+  }
+
+  /**
+   * Remember a transaction this wallet sent, so emergencySell can preempt it while it's pending
+   */
+  private trackPending(
+    tx: { hash: string; nonce: number; gasPrice?: bigint | null; maxFeePerGas?: bigint | null },
+    token: string,
+    side: OwnPendingTx['side'],
+    subAccount?: string
+  ): void {
+    this.ownPending.set(tx.nonce, {
+      txHash: tx.hash,
+      nonce: tx.nonce,
+      token,
+      side,
+      gasPrice: tx.gasPrice ?? tx.maxFeePerGas ?? 0n,
+      ...(subAccount !== undefined ? { subAccount } : {}),
+    });
+  }
+
+  private forgetPending(txHash: string): void {
+    for (const [nonce, pending] of this.ownPending) {
+      if (pending.txHash === txHash) {
+        this.ownPending.delete(nonce);
+      }
+    }
+  }
+
+  /**
+   * Complete a trade that was sped up or replaced: waits for whichever attempt of the chain mines
+   * (they share a nonce, so only one can) and records it with the chain's extra costs attributed.
+   * Replaced attempts' gas is never charged; only their `extraCosts` carry over.
+   */
+  async completeReplacedTrade(
+    attempts: ReplacementAttempt[],
+    context: TradeTags & { expected?: bigint } = {},
+    confirmations: number = 1
+  ): Promise<TransactionResult> {
+    if (attempts.length === 0) {
+      throw new ValidationError('Replacement chain is empty');
+    }
+
+    const receipt = await this.waitForReceipt(
+      firstReceipt(attempts.map((attempt) => this.provider.waitForTransaction(attempt.txHash, confirmations)))
+    );
+    if (!receipt || receipt.status !== 1) {
+      attempts.forEach((attempt) => this.releaseReservation(attempt.txHash));
+      throw new TransactionFailedError('Transaction reverted', receipt?.hash, { receipt });
+    }
+
+    const extraCosts = attributeReplacementCosts(attempts, receipt.hash);
+    try {
+      return await this.completeTrade(receipt.hash, receipt, { ...context, extraCosts });
+    } finally {
+      attempts.forEach((attempt) => this.releaseReservation(attempt.txHash)); // Held under the original hash
+    }
+  }
+
+  /**
+   * Resend a pending transaction of this wallet with the same nonce at a higher gas price.
+   * EIP-1559 transactions get `gasPrice` as both max fee and priority fee. Returns the new hash.
+   */
+  async speedUp(txHash: string, gasPrice: bigint): Promise<string> {
+    const tx = await this.provider.getTransaction(txHash);
+    if (!tx) {
+      throw new ValidationError(`Transaction ${txHash} not found`);
+    }
+    return await this.resendAt(tx, gasPrice);
+  }
+
+  /**
+   * Sign and send a same-nonce replacement of `tx` at `gasPrice`, optionally with a different
+   * call (e.g. a zero-value self-transfer to cancel it). An EIP-1559 original keeps at least a
+   * valid bump of its own fee cap and tip (see replacementFees).
+   */
+  private async resendAt(
+    tx: ethers.TransactionResponse,
+    gasPrice: bigint,
+    call: { to?: string; data?: string; value?: bigint; gasLimit?: bigint } = {}
+  ): Promise<string> {
+    if (tx.from.toLowerCase() !== this.wallet.address.toLowerCase()) {
+      throw new ValidationError(`Transaction ${tx.hash} was not sent by this wallet`);
+    }
+    const signedTx = await this.wallet.signTransaction({
+      to: call.to ?? tx.to,
+      from: tx.from,
+      data: call.data ?? tx.data,
+      value: call.value ?? tx.value,
+      nonce: tx.nonce,
+      chainId: this.chainId,
+      gasLimit: call.gasLimit ?? tx.gasLimit,
+      ...(tx.type === 2 ? { type: 2, ...replacementFees(tx, gasPrice) } : { type: 0, gasPrice }),
+    });
+    return await this.provider.send('eth_sendRawTransaction', [signedTx]);
+  }
+
+  /**
+   * Keep a pending buy ahead of competing mempool buys of the same token: each competitor at or above
+   * our price is answered with a speed-up `config.increment` above it, within `maxGasPrice` and
+   * `maxReplacements`. Stops as soon as any attempt mines, then records the fill like
+   * completeReplacedTrade. The result carries the full bidding history.
+   */
+  async gasAuction(pending: PendingTrade, tokenAddress: string, config: GasAuctionConfig): Promise<GasAuctionResult> {
+    await this.capabilityProbe.require('gasAuction', 'pendingSubscription');
+    const original = await this.provider.getTransaction(pending.txHash);
+    if (!original) {
+      throw new ValidationError(`Transaction ${pending.txHash} not found`);
+    }
+    const startPrice = (original.type === 2 ? original.maxPriorityFeePerGas : original.gasPrice) ?? 0n;
+    const wallet = this.wallet.address.toLowerCase();
+
+    const auction = new GasAuction(pending.txHash, startPrice, {
+      watchCompetitors: (listener) => {
+        const id = this.onPendingBuy(tokenAddress, (bid) => {
+          if (bid.from.toLowerCase() !== wallet) {
+            listener(bid);
+          }
+        });
+        return () => this.off(id);
+      },
+      watchBlocks: (listener) => {
+        const id = this.onBlock(() => listener());
+        return () => this.off(id);
+      },
+      replace: (gasPrice) => this.resendAt(original, gasPrice),
+      minedAttempt: async (txHashes) => {
+        const receipts = await Promise.all(txHashes.map((hash) => this.provider.getTransactionReceipt(hash)));
+        return receipts.find((receipt) => receipt !== null)?.hash ?? null;
+      },
+    }, config);
+
+    const result = await auction.run();
+    if (result.outcome === 'mined') {
+      const attempts = result.attempts.map((txHash) => ({ txHash, nonce: pending.nonce }));
+      result.trade = await this.completeReplacedTrade(attempts, pickTags(pending));
+    }
+    return result;
+  }
+
+  /**
+   * Buy exact amount of tokens with maximum funds limit. Runs the same gates as buyToken; price
+   * impact is measured on the quoted cost of `amount`.
+   */
+  async buyTokenExact(
+    tokenAddress: string,
+    amount: bigint,
+    maxFunds: bigint,
+    to?: string,
+    gas?: GasOptions,
+    options: ExactBuyOptions = {}
+  ): Promise<TransactionResult> {
+    let reservation: SubAccountReservation | undefined;
+    try {
+      const tokenAmount = amount;
+      const maxFundsWei = maxFunds;
+
+      const preview = new TradePreview('buy', tokenAddress, true);
+      await this.checkBuyGates(preview, tokenAddress, options);
+      if (this.maxPriceImpactBps !== undefined) {
+        const info = await this.priceCalculator.getTokenInfo(tokenAddress);
+        const { bnbCost } = await this.priceCalculator.calcBuyCost(info, tokenAmount);
+        await this.enforcePriceImpact(tokenAddress, 'buy', bnbCost, preview);
+      }
+      await this.enforceBnbReserve(maxFundsWei, gas);
+      reservation = await this.enforceSubAccountBuy(options.subAccount, maxFundsWei, gas);
+
+      console.log(`Buying exact ${ethers.formatUnits(amount, this.getTokenDecimals(tokenAddress))} tokens`);
+      console.log(`Max funds: ${ethers.formatEther(maxFunds)} BNB`);
+
+      const route = await this.detectVersion(tokenAddress);
+      const txOptions = this.buildTxOptions(gas, maxFundsWei);
+      const recipient = to || this.wallet.address;
+      const data = encodeVersionedBuyExact(route.version, tokenAddress, recipient, tokenAmount, maxFundsWei);
+      await this.attachAccessList(txOptions, data, maxFundsWei, route.manager);
+      await this.applyGasLimit('buy', txOptions, gas, (request) =>
+        this.provider.estimateGas({ ...request, from: this.wallet.address, to: route.manager, data })
+      );
+
+      // V2: buyToken(token, to, amount, maxFunds); V1: purchaseToken on the V1 manager
+      throwIfCancelled(options.signal);
+      const tx = route.version === 2
+        ? await this.contract.buyToken(tokenAddress, recipient, tokenAmount, maxFundsWei, txOptions)
+        : await this.wallet.sendTransaction({ ...txOptions, to: route.manager, data });
+
+      console.log(`Transaction sent: ${tx.hash}`);
+      this.holdReservation(tx.hash, reservation);
+      this.trackPending(tx, tokenAddress, 'buy', options.subAccount);
+      const receipt = await waitUnlessCancelled(this.waitForReceipt(tx), options.signal, () =>
+        this.pendingTrade(tx.hash, tx.nonce, options)
+      );
+      console.log(`Transaction confirmed in block ${receipt.blockNumber}`);
+      this.gasProfiler.observe('buyToken', receipt.gasUsed);
+      return await this.completeTrade(tx.hash, receipt, options);
+    } catch (error: any) {
+      console.error('Buy exact transaction failed:', error.message);
+      this.releaseUnlessPending(reservation, error);
+      throw error;
+    }
+  }
+
+  /**
+   * Buy `fractionBps` of the supply still for sale on the token's curve (TokenInfo.offers), rounded
+   * down to whole token units, through the exact-amount path with `maxFunds` the quoted cost plus
+   * `slippagePercent`. Requests above maxCurveFractionBps throw CurveFractionError, or are reduced
+   * to it with clampCurveFraction; a share that rounds to zero tokens throws InvalidAmountError.
+   * The result's `curveFraction` records the requested and realized shares.
+   */
+  async buyCurveFraction(
+    tokenAddress: string,
+    fractionBps: number,
+    slippagePercent: number,
+    options: CurveFractionBuyOptions = {}
+  ): Promise<TransactionResult> {
+    Validator.validateAddress(tokenAddress, 'tokenAddress');
+    Validator.validateSlippage(slippagePercent);
+    const fraction = resolveCurveFraction(fractionBps, this.maxCurveFractionBps, options.clamp ?? this.clampCurveFraction);
+
+    const info = await this.readTokenInfo(tokenAddress);
+    const amount = curveFractionAmount(info.offers, fraction.bps);
+    const [quote, limits] = await Promise.all([this.priceCalculator.calcBuyCost(info, amount), this.buyLimits(tokenAddress)]);
+    checkBuyLimits(tokenAddress, quote.bnbCost, limits);
+    const maxFunds = mulDiv(quote.bnbCost, SLIPPAGE_DENOMINATOR + slippagePercentToBps(slippagePercent), SLIPPAGE_DENOMINATOR);
+
+    const result = await this.buyTokenExact(tokenAddress, amount, maxFunds, options.to, options.gas, options);
+    const { purchases } = await parseTradeEvents(result.receipt, this.contract);
+    const bought = purchases
+      .filter((purchase) => purchase.token.toLowerCase() === tokenAddress.toLowerCase())
+      .reduce((sum, purchase) => sum + purchase.amount, 0n);
+    return {
+      ...result,
+      curveFraction: {
+        requestedBps: fractionBps,
+        executedBps: fraction.bps,
+        clamped: fraction.clamped,
+        offersBefore: info.offers,
+        tokenAmount: bought,
+        realizedBps: realizedCurveFraction(bought, info.offers),
+      },
+    };
+  }
+
+  /**
+   * Sell tokens for BNB
+   * IMPORTANT: Must approve token spending before calling this method
+   */
+  async sellToken(params: SellParams): Promise<TransactionResult> {
+    return await this.withReorgRetry(() => this.executeSell(params));
+  }
+
+  private async executeSell(params: SellParams): Promise<TransactionResult> {
+    try {
+      await this.warnIfBelowReserve();
+      if (params.autoApprove ?? this.autoApprove) {
+        await this.ensureAllowance(params.tokenAddress, params.amount, params.gas);
+      }
+      const preview = new TradePreview('sell', params.tokenAddress, true);
+      const { route, txOptions, data, minAmount: minFunds } = (await this.prepareSell(params, preview))!;
+      const amount = params.amount;
+
+      console.log(`Selling token ${params.tokenAddress}`);
+      console.log(`Amount: ${ethers.formatUnits(amount, this.getTokenDecimals(params.tokenAddress))}`);
+      console.log(`Min funds: ${ethers.formatEther(minFunds)} BNB`);
+
+      // Use explicit function signature to avoid ambiguity with overloaded functions
+      // V2: sellToken(address token, uint256 amount, uint256 minFunds); V1: saleToken on the V1 manager
+      const context = { ...params, expected: params.expectedFunds };
+      throwIfCancelled(params.signal);
+      const tx = route.version === 2
+        ? await this.contract['sellToken(address,uint256,uint256)'](params.tokenAddress, amount, minFunds, txOptions)
+        : await this.wallet.sendTransaction({ ...txOptions, to: route.manager, data });
+
+      console.log(`Transaction sent: ${tx.hash}`);
+      this.trackPending(tx, params.tokenAddress, 'sell', params.subAccount);
+      const receipt = await waitUnlessCancelled(this.waitForReceipt(tx, params.confirmation), params.signal, () =>
+        this.pendingTrade(tx.hash, tx.nonce, context)
+      );
+      console.log(`Transaction confirmed in block ${receipt.blockNumber}`);
+      this.gasProfiler.observe('sellToken', receipt.gasUsed);
+      return await this.completeTrade(tx.hash, receipt, context);
+    } catch (error: any) {
+      console.error('Sell transaction failed:', error.message);
+      throw error;
+    }
+  }
+
+  /**
+   * Run every step of sellToken short of sending: sub-account limits, the economical-sell guard,
+   * the quote, manager routing and gas, plus the wallet's balance and allowance, which only the
+   * contract checks on send. Failed checks are recorded, not thrown.
+   */
+  async previewSell(params: SellParams): Promise<TradePreview> {
+    const preview = new TradePreview('sell', params.tokenAddress);
+    await this.prepareSell(params, preview);
+    return preview;
+  }
+
+  /**
+   * Everything a sell does before sending, recorded in `preview`; `minAmount` of the result is the
+   * sell's minimum BNB out. Null when a lenient preview failed a step the transaction is built from.
+   */
+  private async prepareSell(params: SellParams, preview: TradePreview): Promise<PreparedTrade | null> {
+    const tokenAddress = params.tokenAddress;
+    const account = this.wallet.address;
+    const amount = params.amount;
+    const minFunds = params.minFunds || 0n;
+    preview.set('account', account);
+    preview.set('amount', amount);
+    preview.set('minFunds', minFunds);
+
+    await preview.check('chainProgress', () => this.enforceChainProgress());
+    await preview.check('subAccountLimits', () => this.enforceSubAccountSell(params.subAccount, tokenAddress, amount));
+    await preview.check('economicalSell', () => this.enforceEconomicalSell(params));
+    const dustGuard = minFunds === 0n && !params.allowDustFills;
+    await preview.check('quote', async () => {
+      if (dustGuard || !preview.strict) {
+        const quote = await this.priceCalculator.quoteSell(tokenAddress, amount, { allowDustFills: !dustGuard });
+        preview.set('quotedFunds', quote.bnbCost);
+        preview.set('fee', quote.fee);
+      }
+    });
+    await preview.check('priceImpact', () => this.enforcePriceImpact(tokenAddress, 'sell', amount, preview));
+
+    const route = await preview.check('managerRoute', () => this.detectVersion(tokenAddress));
+    if (!route) {
+      return null;
+    }
+    const manager = route.manager;
+    preview.set('version', route.version);
+    preview.set('manager', manager);
+
+    if (!preview.strict) {
+      await preview.check('balance', async () => {
+        const balance = await this.readTokenBalance(tokenAddress);
+        preview.set('balance', balance);
+        if (balance < amount) {
+          throw new InsufficientBalanceError(amount, balance);
+        }
+      });
+      await preview.check('allowance', async () => {
+        const [allowance] = await this.allowances([tokenAddress], manager);
+        if (allowance === null) {
+          throw new ConnectionError(`Could not read the allowance of ${tokenAddress}`);
+        }
+        preview.set('allowance', allowance);
+        if (allowance < amount) {
+          throw new ValidationError(`Allowance of ${allowance} to ${manager} is below the sell amount ${amount}; approve first`);
+        }
+      });
+    }
+
+    const data = encodeVersionedSell(route.version, tokenAddress, amount, minFunds);
+    const txOptions = await this.prepareGas(preview, 'sell', params.gas, undefined, data, manager, account);
+    if (!txOptions) {
+      return null;
+    }
+    preview.transaction = previewTransaction(account, manager, data, 0n, txOptions);
+    return { route, txOptions, data, funds: 0n, minAmount: minFunds, recipient: account };
+  }
+
+  /**
+   * Sell the wallet's entire balance of a token, or only the sub-account's share when tagged with one
+   * Works for tokens with no recorded buy (airdrops, transfers); their proceeds are tracked as basis-unknown
+   */
+  async sellAll(tokenAddress: string, minFunds?: bigint, gas?: GasOptions, tagging?: TradeTags & Cancellable): Promise<TransactionResult> {
+    const subAccount = tagging?.subAccount;
+    const balance = subAccount !== undefined
+      ? this.requireSubAccounts(subAccount).getBalance(subAccount)?.tokens.get(tokenAddress.toLowerCase()) ?? 0n
+      : await this.getTokenBalanceRaw(tokenAddress);
+    if (balance === 0n) {
+      throw new InvalidAmountError(balance, `no ${tokenAddress} balance to sell`);
+    }
+
+    return await this.sellToken({ tokenAddress, amount: balance, minFunds, gas, ...tagging });
+  }
+
+  /**
+   * Sell the wallet's whole balance of each token, e.g. for a "sell everything" button. Balances
+   * are read first and zero ones skipped; batchApprove then approves, in one allowance read, every
+   * held token whose allowance is below its own balance. Tokens are sold one at a time through
+   * sellAll, each sell mined before the next is sent so nonces stay in order. A failure on one
+   * token, its approval included, is reported without stopping the rest. Sells are tagged 'liquidate'.
+   * @param slippagePercent - Minimum proceeds of each sell below its quote
+   */
+  async liquidateAll(tokens: string[], slippagePercent: number, gas?: GasOptions): Promise<LiquidationResult[]> {
+    Validator.validateSlippage(slippagePercent);
+    const results: LiquidationResult[] = [];
+    const held: Array<{ index: number; balance: bigint }> = []; // Tokens with a balance, by their index in results
+
+    for (const token of tokens) {
+      try {
+        const balance = await this.readTokenBalance(token);
+        if (balance !== 0n) {
+          held.push({ index: results.length, balance });
+        }
+        results.push({ token, status: balance === 0n ? 'skipped' : 'failed', balance });
+      } catch (error: any) {
+        results.push({ token, status: 'failed', balance: null, error: error?.message ?? String(error) });
+      }
+    }
+    if (held.length === 0) {
+      return results;
+    }
+
+    let approvals: BatchApproveResult[];
+    try {
+      approvals = await this.batchApprove(
+        held.map(({ index }) => results[index].token),
+        held.map(({ balance }) => balance),
+        undefined,
+        gas
+      );
+    } catch (error: any) {
+      for (const { index } of held) {
+        results[index].error = error?.message ?? String(error);
+      }
+      return results;
+    }
+
+    for (const [position, { index, balance }] of held.entries()) {
+      const token = results[index].token;
+      const approval = approvals[position];
+      if (approval.status === 'failed') {
+        results[index].error = `Approval failed: ${approval.error}`;
+        continue;
+      }
+
+      try {
+        const quote = await this.quoteSell(token, balance);
+        const result = await this.sellAll(token, applySlippage(quote.bnbCost, slippagePercent), gas, { tags: ['liquidate'] });
+        results[index] = { token, status: 'sold', balance, result };
+      } catch (error: any) {
+        results[index].error = error?.message ?? String(error);
+      }
+    }
+
+    return results;
+  }
+
+  /**
+   * Call a contract function the SDK doesn't wrap yet, without sending a transaction. `fn` is a
+   * human-readable signature, e.g. "function _tokenInfos(address) view returns (address, ...)"
+   * (the keyword and mutability are optional), or a FunctionFragment. The call goes to the
+   * configured four.meme contract unless `options.to` names another, and honours the
+   * read-your-writes floor. A single output is returned as is, several as an ethers Result, and
+   * the raw return data when the signature declares no outputs.
+   */
+  async rawCall<T = any>(fn: RawFunction, args: readonly unknown[] = [], options: RawCallOptions = {}): Promise<T> {
+    const to = options.to ?? this.contractAddress;
+    Validator.validateAddress(to, 'to');
+    const fragment = parseRawFunction(fn);
+    const data = encodeRawCall(fragment, args);
+    const result = await this.pinnedRead((blockTag) => this.provider.call({ to, data, from: this.wallet.address, blockTag }));
+    return decodeRawResult(fragment, result) as T;
+  }
+
+  /**
+   * Send a transaction to a function the SDK doesn't wrap yet, addressed like rawCall. The chain
+   * progress check and gas pricing and estimation apply as for the typed methods (the `raw` fallback
+   * limit on nodes without eth_estimateGas), the nonce comes from the node, and the transaction is
+   * tracked while pending so emergencySell reprices it. Four.meme fills in the receipt are recorded
+   * in positions and trade history like any trade's.
+   */
+  async rawSend(fn: RawFunction, args: readonly unknown[] = [], options: RawSendOptions = {}): Promise<TransactionResult> {
+    try {
+      const to = options.to ?? this.contractAddress;
+      Validator.validateAddress(to, 'to');
+      const fragment = parseRawFunction(fn);
+      const data = encodeRawCall(fragment, args);
+      await this.warnIfBelowReserve();
+      await this.enforceChainProgress();
+
+      const txOptions = this.buildTxOptions(options.gas, options.value);
+      await this.applyGasLimit('raw', txOptions, options.gas, (estimateOptions) =>
+        this.provider.estimateGas({ ...estimateOptions, from: this.wallet.address, to, data })
+      );
+      const tx = await this.wallet.sendTransaction({ ...txOptions, to, data });
+
+      console.log(`Raw ${fragment.name} transaction sent: ${tx.hash}`);
+      this.trackPending(tx, to, 'raw');
+      const receipt = await this.waitForReceipt(tx, options.confirmation);
+      console.log(`Transaction confirmed in block ${receipt.blockNumber}`);
+      return await this.completeTrade(tx.hash, receipt, pickTags(options));
+    } catch (error: any) {
+      console.error('Raw transaction failed:', error.message);
+      throw error;
+    }
+  }
+
+  /**
+   * Exit a token now: sell the wallet's balance at the aggressive exit gas price (network price
+   * scaled by EMERGENCY_GAS_PRICE_BPS, unless `options.gas` prices it) after preempting this
+   * wallet's pending transactions per `options.policy`: pending buys of the token are cancelled and
+   * other pending transactions repriced by default, so none holds an earlier nonce below the exit.
+   * Only the reads the sell needs are made; the token filters, chain progress check, sub-account
+   * limits and economical-sell guard are skipped, and the gas limit is the learned one rather than
+   * an estimate.
+   */
+  async emergencySell(tokenAddress: string, slippagePercent: number, options: EmergencySellOptions = {}): Promise<EmergencySellResult> {
+    Validator.validateAddress(tokenAddress, 'tokenAddress');
+    Validator.validateSlippage(slippagePercent);
+    if (options.amount !== undefined) {
+      Validator.validateAmount(options.amount, 'amount');
+    }
+    const gas = options.gas; // Not merged with the configured default, whose pricing would undercut the exit preset
+
+    const [status, amount, route, exitGasPrice] = await Promise.all([
+      this.nonceStatus(),
+      options.amount ?? this.readTokenBalance(tokenAddress),
+      this.detectVersion(tokenAddress),
+      gas?.gasPrice ?? gas?.maxFeePerGas ?? this.provider.getFeeData().then((feeData) =>
+        mulDiv(feeData.gasPrice ?? feeData.maxFeePerGas ?? 0n, BigInt(EMERGENCY_GAS_PRICE_BPS), SLIPPAGE_DENOMINATOR)
+      ),
+    ]);
+    if (amount === 0n) {
+      throw new InvalidAmountError(amount, `no ${tokenAddress} balance to sell`);
+    }
+    for (const nonce of this.ownPending.keys()) {
+      if (nonce < status.latest) {
+        this.ownPending.delete(nonce);
+      }
+    }
+    const decisions = planPreemption(tokenAddress, [...this.ownPending.values()], exitGasPrice, options.policy);
+    // Quoted alongside the preemptions, so a failed quote rejects here rather than unobserved
+    const [{ bnbCost: quotedFunds }, replacements] = await Promise.all([
+      this.priceCalculator.quoteSell(tokenAddress, amount, { allowDustFills: true }),
+      Promise.all(decisions.map((decision) => this.preempt(decision))),
+    ]);
+
+    const lastTracked = Math.max(-1, ...this.ownPending.keys());
+    const nonce = Math.max(status.pending, lastTracked + 1);
+    const minFunds = applySlippage(quotedFunds, slippagePercent);
+    const data = encodeVersionedSell(route.version, tokenAddress, amount, minFunds);
+    const legacy = gas?.type !== undefined ? gas.type === 'legacy' : gas?.maxFeePerGas === undefined;
+    const signedTx = await this.wallet.signTransaction({
+      to: route.manager,
+      from: this.wallet.address,
+      data,
+      value: 0n,
+      nonce,
+      chainId: this.chainId,
+      gasLimit: gas?.gasLimit ?? this.gasProfiler.get('sellToken') ?? this.fallbackGasLimits.sell,
+      ...(legacy
+        ? { type: 0, gasPrice: exitGasPrice }
+        : { type: 2, maxFeePerGas: exitGasPrice, maxPriorityFeePerGas: gas?.maxPriorityFeePerGas ?? exitGasPrice }),
+    });
+    const txHash: string = await this.provider.send('eth_sendRawTransaction', [signedTx]);
+    console.log(`Emergency sell sent: ${txHash} (nonce ${nonce})`);
+    this.trackPending({ hash: txHash, nonce, gasPrice: exitGasPrice }, tokenAddress, 'sell');
+
+    const tags = [...(options.tags ?? []), 'emergency'];
+    const receipt = await this.waitForReceipt(this.provider.waitForTransaction(txHash));
+    if (!receipt || receipt.status !== 1) {
+      throw new TransactionFailedError('Emergency sell reverted', txHash, { receipt, replacements });
+    }
+    const sell = await this.completeTrade(txHash, receipt, { ...pickTags(options), tags, expected: quotedFunds });
+    return { sell, decisions, replacements, untrackedPending: Math.max(0, status.pending - status.latest - this.ownPendingBelow(nonce)) };
+  }
+
+  /**
+   * Carry out one preemption decision: a same-nonce self-transfer (cancel) or resend (reprice).
+   * Failures, e.g. the transaction mined meanwhile, are reported rather than thrown.
+   */
+  private async preempt(decision: PreemptionDecision): Promise<PreemptionOutcome> {
+    const pending = this.ownPending.get(decision.nonce);
+    if (decision.action === 'wait' || !pending) {
+      return { decision };
+    }
+    try {
+      const tx = await this.provider.getTransaction(decision.txHash);
+      if (!tx || tx.blockNumber !== null) {
+        return { decision, error: 'no longer pending' };
+      }
+      const txHash = decision.action === 'cancel'
+        ? await this.resendAt(tx, decision.gasPrice!, { to: this.wallet.address, data: '0x', value: 0n, gasLimit: MIN_GAS_LIMIT })
+        : await this.resendAt(tx, decision.gasPrice!);
+      this.trackPending({ hash: txHash, nonce: decision.nonce, gasPrice: decision.gasPrice }, pending.token, pending.side, pending.subAccount);
+      return { decision, txHash };
+    } catch (error: any) {
+      return { decision, error: error?.message ?? String(error) };
+    }
+  }
+
+  private ownPendingBelow(nonce: number): number {
+    return [...this.ownPending.keys()].filter((pending) => pending < nonce).length;
+  }
+
+  /**
+   * Recover BNB the wallet holds but no position tracks: unwrap WBNB, and sell residual balances
+   * of tokens whose tracked position is closed (plus `options.tokens`) when the proceeds beat gas
+   * by the margin. Tokens with an open position are never touched. Balances left alone are
+   * reported with the reason; a failure on one item is reported and the sweep moves on, so it is
+   * safe to run on a schedule. Sells are recorded in trade history tagged 'sweep'.
+   */
+  async sweep(options: SweepOptions = {}, gas?: GasOptions): Promise<SweepReport> {
+    const dryRun = options.dryRun ?? false;
+    const marginBps = options.minProceedsMarginBps ?? this.minSellProceedsMarginBps ?? 0;
+    const slippagePercent = options.slippagePercent ?? SWEEP_SLIPPAGE_PERCENT;
+    Validator.validateSlippage(slippagePercent);
+    const report: SweepReport = { dryRun, startedAt: Date.now(), items: [], recovered: 0n };
+
+    const wbnb = await this.readTokenBalance(WBNB_ADDRESS);
+    if (wbnb > 0n) {
+      report.items.push(await this.sweepWbnb(wbnb, options.unwrapWbnb ?? true, dryRun, marginBps, gas));
+    }
+
+    for (const { token, open } of sweepCandidates(this.positionTracker.getPositions(), options.tokens)) {
+      if (token === WBNB_ADDRESS.toLowerCase()) {
+        continue;
+      }
+      let amount: bigint;
+      try {
+        amount = await this.readTokenBalance(token);
+      } catch (error: any) {
+        report.items.push({ kind: 'token', token, amount: 0n, action: 'failed', reason: error?.message ?? String(error) });
+        continue;
+      }
+      if (amount === 0n) {
+        continue;
+      }
+      if (open) {
+        report.items.push({ kind: 'token', token, amount, action: 'skipped', skipReason: 'openPosition', reason: 'Position is open' });
+        continue;
+      }
+      report.items.push(await this.sweepToken(token, amount, dryRun, marginBps, slippagePercent, gas));
+    }
+
+    report.recovered = sweepRecovered(report.items);
+    return report;
+  }
+
+  /**
+   * Unwrap the wallet's WBNB when it is worth the gas
+   */
+  private async sweepWbnb(amount: bigint, unwrap: boolean, dryRun: boolean, marginBps: number, gas?: GasOptions): Promise<SweepItem> {
+    const item: SweepItem = { kind: 'wbnb', token: WBNB_ADDRESS, amount, action: 'skipped' };
+    if (!unwrap) {
+      return { ...item, skipReason: 'unwrapDisabled', reason: 'WBNB unwrapping is disabled' };
+    }
+    try {
+      const gasCost = await this.estimateGasCost(gas, this.fallbackGasLimits.unwrap);
+      if (!isEconomicalSell(amount, gasCost, marginBps)) {
+        return { ...item, gasCost, skipReason: 'uneconomical', reason: `Unwrapping ${amount} does not cover gas of ${gasCost}` };
+      }
+      if (dryRun) {
+        return { ...item, gasCost, proceeds: amount, action: 'wouldUnwrap' };
+      }
+
+      const data = getWbnbInterface().encodeFunctionData('withdraw', [amount]);
+      const txOptions = this.buildTxOptions(gas);
+      await this.applyGasLimit('unwrap', txOptions, gas, (options) =>
+        this.provider.estimateGas({ ...options, from: this.wallet.address, to: WBNB_ADDRESS, data })
+      );
+      const tx = await this.wallet.sendTransaction({ ...txOptions, to: WBNB_ADDRESS, data });
+      console.log(`Unwrap transaction sent: ${tx.hash}`);
+      await this.waitForReceipt(tx);
+      return { ...item, gasCost, proceeds: amount, action: 'unwrapped', txHash: tx.hash };
+    } catch (error: any) {
+      return { ...item, action: 'failed', reason: error?.message ?? String(error) };
+    }
+  }
+
+  /**
+   * Sell a closed position's leftover balance when it is approved and worth the gas
+   */
+  private async sweepToken(
+    token: string,
+    amount: bigint,
+    dryRun: boolean,
+    marginBps: number,
+    slippagePercent: number,
+    gas?: GasOptions
+  ): Promise<SweepItem> {
+    const item: SweepItem = { kind: 'token', token, amount, action: 'skipped' };
+    try {
+      const { manager } = await this.detectVersion(token);
+      const [quote, [allowance], gasCost] = await Promise.all([
+        this.priceCalculator.quoteSell(token, amount, { allowDustFills: true }),
+        this.allowances([token], manager),
+        this.estimateGasCost(gas, this.gasProfiler.get('sellToken') ?? SELL_GAS_LIMIT_ESTIMATE),
+      ]);
+      const proceeds = quote.bnbCost;
+      if (!isEconomicalSell(proceeds, gasCost, marginBps)) {
+        return { ...item, proceeds, gasCost, skipReason: 'uneconomical', reason: `Proceeds of ${proceeds} do not cover gas of ${gasCost}` };
+      }
+      if (allowance === null || allowance < amount) {
+        return { ...item, proceeds, gasCost, skipReason: 'notApproved', reason: `Allowance to ${manager} is below the balance; approve to sweep it` };
+      }
+      if (dryRun) {
+        return { ...item, proceeds, gasCost, action: 'wouldSell' };
+      }
+
+      const minFunds = applySlippage(proceeds, slippagePercent);
+      const result = await this.sellToken({ tokenAddress: token, amount, minFunds, expectedFunds: proceeds, gas, force: true, tags: ['sweep'] });
+      return { ...item, proceeds, gasCost, action: 'sold', txHash: result.txHash };
+    } catch (error: any) {
+      return { ...item, action: 'failed', reason: error?.message ?? String(error) };
+    }
+  }
+
+  /**
+   * Create a new token on the platform
+   */
+  async createToken(params: CreateTokenParams): Promise<TransactionResult> {
+    try {
+      if (!params.launch) {
+        throw new ValidationError('createToken needs the launch metadata its args were built from');
+      }
+      const violations = validateTokenLaunch(params.launch);
+      if (violations.length > 0) {
+        throw new TokenLaunchValidationError(violations);
+      }
+
+      console.log('Creating new token...');
+
+      const txOptions = this.buildTxOptions(params.gas);
+      await this.applyGasLimit('createToken', txOptions, params.gas, (options) =>
+        params.signature
+          ? this.contract.createToken.estimateGas(params.args, params.signature, options)
+          : this.contract.createToken.estimateGas(params.args, options)
+      );
+
+      let tx;
+      if (params.signature) {
+        tx = await this.contract.createToken(params.args, params.signature, txOptions);
+      } else {
+        tx = await this.contract.createToken(params.args, txOptions);
+      }
+
+      console.log(`Transaction sent: ${tx.hash}`);
+      const receipt = await this.waitForReceipt(tx);
+      console.log(`Transaction confirmed in block ${receipt.blockNumber}`);
+      this.gasProfiler.observe('createToken', receipt.gasUsed);
+
+      return await this.completeResult(tx.hash, receipt);
+    } catch (error: any) {
+      console.error('Create token failed:', error.message);
+      throw error;
+    }
+  }
+
+  /**
+   * Add liquidity to a token (admin function)
+   */
+  async addLiquidity(tokenAddress: string, gas?: GasOptions): Promise<TransactionResult> {
+    try {
+      console.log(`Adding liquidity for token ${tokenAddress}`);
+
+      const txOptions = this.buildTxOptions(gas);
+      await this.applyGasLimit('addLiquidity', txOptions, gas, (options) => this.contract.addLiquidity.estimateGas(tokenAddress, options));
+      const tx = await this.contract.addLiquidity(tokenAddress, txOptions);
+
+      console.log(`Transaction sent: ${tx.hash}`);
+      const receipt = await this.waitForReceipt(tx);
+      console.log(`Transaction confirmed in block ${receipt.blockNumber}`);
+
+      return await this.completeResult(tx.hash, receipt);
+    } catch (error: any) {
+      console.error('Add liquidity failed:', error.message);
+      throw error;
+    }
+  }
+
+  // ==================== Token Approval ====================
+
+  /**
+   * Approve token spending before selling
+   */
+  async approveToken(
+    tokenAddress: string,
+    amount?: bigint,
+    gas?: GasOptions
+  ): Promise<TransactionResult> {
+    try {
+      const { manager } = await this.detectVersion(tokenAddress);
+      const receipt = await this.sendApproval(tokenAddress, manager, amount || ethers.MaxUint256, gas);
+      return await this.completeResult(receipt.hash, receipt);
+    } catch (error: any) {
+      console.error('Approval failed:', error.message);
+      throw error;
+    }
+  }
+
+  /**
+   * Approve `manager` for `amount` and wait for the receipt. With `approval`, the hash is shared
+   * with waiting sells, and a speed-up of theirs that mines instead counts as this approval's.
+   */
+  private async sendApproval(
+    tokenAddress: string,
+    manager: string,
+    amount: bigint,
+    gas?: GasOptions,
+    approval?: InFlightApproval
+  ): Promise<ethers.TransactionReceipt> {
+    const tokenContract = new ethers.Contract(tokenAddress, getErc20Interface(), this.wallet);
+    console.log(`Approving ${manager} to spend tokens`);
+
+    const txOptions = this.buildTxOptions(gas);
+    await this.applyGasLimit('approve', txOptions, gas, (options) => tokenContract.approve.estimateGas(manager, amount, options));
+    const tx = await tokenContract.approve(manager, amount, txOptions);
+
+    console.log(`Approval transaction sent: ${tx.hash}`);
+    approval?.sent(tx.hash);
+    // A same-nonce speed-up mining instead is this approval's receipt, not a failure
+    const mined = this.waitForReceipt(tx).catch((error: any) => {
+      if (error?.code === 'TRANSACTION_REPLACED' && error.reason === 'repriced') {
+        return error.receipt;
+      }
+      throw error;
+    });
+    let receipt: ethers.TransactionReceipt;
+    if (approval) {
+      mined.catch(() => undefined); // Settled by replacementMined instead when the node never reports the swap
+      receipt = await Promise.race([mined, approval.replacementMined]);
+    } else {
+      receipt = await mined;
+    }
+    if (receipt.status !== 1) {
+      throw new TransactionFailedError('Approval reverted', receipt.hash, { receipt });
+    }
+    console.log(`Approval confirmed in block ${receipt.blockNumber}`);
+    this.gasProfiler.observe('approve', receipt.gasUsed);
+    return receipt;
+  }
+
+  /**
+   * Make sure the TokenManager may spend `amount` before an auto-approving sell. Only one approval
+   * per wallet, token and manager is in flight across handles sharing the approval lock: other
+   * sells wait for it to mine or fail, then read the allowance again. One still pending after
+   * approvalWaitTimeoutMs is sped up, once, by the first sell to time out rather than waited on forever.
+   */
+  private async ensureAllowance(tokenAddress: string, amount: bigint, gas?: GasOptions): Promise<void> {
+    const { manager } = await this.detectVersion(tokenAddress);
+    const token = new ethers.Contract(tokenAddress, getErc20Interface(), this.provider);
+    const key = ApprovalLock.key(this.wallet.address, tokenAddress, manager);
+
+    for (;;) {
+      const allowance: bigint = await token.allowance(this.wallet.address, manager);
+      if (allowance >= amount) {
+        return;
+      }
+
+      const claim = this.approvalLock.acquire(key);
+      if (claim) {
+        try {
+          // The sell's gas limit is sized for the sell, not the approval
+          await this.sendApproval(tokenAddress, manager, ethers.MaxUint256, gas && { ...gas, gasLimit: undefined }, claim.approval);
+        } finally {
+          claim.release();
+        }
+        return;
+      }
+
+      const inFlight = this.approvalLock.current(key)!;
+      if (!(await inFlight.settledWithin(this.approvalWaitTimeoutMs)) && inFlight.claimSpeedUp()) {
+        try {
+          await this.speedUpApproval(tokenAddress, inFlight);
+        } catch (error) {
+          inFlight.releaseSpeedUp();
+          throw error;
+        }
+      }
+    }
+  }
+
+  /**
+   * Resend an approval that outlived approvalWaitTimeoutMs at the minimum replacement price and
+   * wait for whichever attempt mines, handing the receipt to its holder. Throws
+   * ApprovalWaitTimeoutError when there is no pending transaction to speed up.
+   */
+  private async speedUpApproval(tokenAddress: string, approval: InFlightApproval): Promise<void> {
+    const latest = approval.hashes[approval.hashes.length - 1];
+    const tx = latest ? await this.provider.getTransaction(latest) : null;
+    if (!tx) {
+      throw new ApprovalWaitTimeoutError(tokenAddress, this.approvalWaitTimeoutMs);
+    }
+    if (tx.blockNumber !== null) {
+      return; // Mined meanwhile; the holder settles shortly
+    }
+
+    const price = minReplacementPrice((tx.type === 2 ? tx.maxFeePerGas : tx.gasPrice) ?? 0n);
+    const replacement = await this.resendAt(tx, price);
+    approval.sent(replacement);
+    console.log(`Approval ${latest} pending for ${this.approvalWaitTimeoutMs}ms, sped up as ${replacement}`);
+
+    const receipt = await this.waitForReceipt(
+      firstReceipt(approval.hashes.map((hash) => this.provider.waitForTransaction(hash)))
+    );
+    if (!receipt || receipt.status !== 1) {
+      throw new TransactionFailedError('Approval reverted', receipt?.hash, { receipt });
+    }
+    approval.replaced(receipt);
+  }
+
+  /**
+   * EIP-2612 payload for `owner` to sign, approving `spender` (default: the token's TokenManager)
+   * for `value` until `deadline` (Unix seconds). The holder signs it with `signTypedData` and hands
+   * the signature to approveGasless. Throws GaslessApprovalUnsupportedError for tokens without permit.
+   */
+  async permitRequest(tokenAddress: string, owner: string, value: bigint, deadline: bigint, spender?: string): Promise<PermitRequest> {
+    Validator.validateAddress(tokenAddress, 'tokenAddress');
+    Validator.validateAddress(owner, 'owner');
+    const token = new ethers.Contract(tokenAddress, getPermitInterface(), this.provider);
+
+    let separator: string;
+    let nonce: bigint;
+    try {
+      [separator, nonce] = await Promise.all([token.DOMAIN_SEPARATOR(), token.nonces(owner)]);
+    } catch {
+      throw new GaslessApprovalUnsupportedError(tokenAddress, 'no DOMAIN_SEPARATOR or nonces');
+    }
+    const [name, version] = await Promise.all([
+      token.name() as Promise<string>,
+      (token.version() as Promise<string>).catch(() => undefined),
+    ]);
+    const domain = resolvePermitDomain(tokenAddress, this.chainId, name, version, separator);
+    if (!domain) {
+      throw new GaslessApprovalUnsupportedError(tokenAddress, 'DOMAIN_SEPARATOR matches no known EIP-712 domain');
+    }
+
+    return permitRequest(domain, {
+      owner,
+      spender: spender ?? (await this.detectVersion(tokenAddress)).manager,
+      value,
+      nonce,
+      deadline,
+    });
+  }
+
+  /**
+   * Submit a holder's signed permit with the SDK's wallet paying the gas, so the holder needs no
+   * BNB to approve. Only for tokens implementing EIP-2612 (GaslessApprovalUnsupportedError otherwise);
+   * the signature is checked against the holder's current nonce before anything is sent.
+   */
+  async approveGasless(tokenAddress: string, approval: GaslessApproval, gas?: GasOptions): Promise<TransactionResult> {
+    try {
+      if (approval.deadline <= BigInt(Math.floor(Date.now() / 1000))) {
+        throw new ValidationError(`Permit deadline ${approval.deadline} has passed`);
+      }
+      const request = await this.permitRequest(tokenAddress, approval.owner, approval.value, approval.deadline, approval.spender);
+      if (recoverPermitSigner(request, approval.signature).toLowerCase() !== approval.owner.toLowerCase()) {
+        throw new ValidationError(`Permit is not signed by ${approval.owner} for nonce ${request.message.nonce}`);
+      }
+
+      const { owner, spender, value, deadline } = request.message;
+      const { v, r, s } = Signature.from(approval.signature);
+      const token = new ethers.Contract(tokenAddress, getPermitInterface(), this.wallet);
+      console.log(`Submitting permit from ${owner} for ${spender}`);
+
+      const txOptions = this.buildTxOptions(gas);
+      await this.applyGasLimit('permit', txOptions, gas, (options) =>
+        token.permit.estimateGas(owner, spender, value, deadline, v, r, s, options)
+      );
+      const tx = await token.permit(owner, spender, value, deadline, v, r, s, txOptions);
+
+      console.log(`Permit transaction sent: ${tx.hash}`);
+      const receipt = await this.waitForReceipt(tx);
+      console.log(`Permit confirmed in block ${receipt.blockNumber}`);
+
+      return await this.completeResult(tx.hash, receipt);
+    } catch (error: any) {
+      console.error('Gasless approval failed:', error.message);
+      throw error;
+    }
+  }
+
+  /**
+   * The wallet's allowance to `spender` (default: each token's own TokenManager, see detectVersion)
+   * for each token, read in a single Multicall3 call. Tokens whose read or route lookup fails are
+   * reported as null.
+   */
+  async allowances(tokens: string[], spender?: string): Promise<Array<bigint | null>> {
+    await this.capabilityProbe.require('allowances', 'multicall');
+    const spenders = spender !== undefined
+      ? tokens.map(() => spender)
+      : await Promise.all(tokens.map((token) => this.detectVersion(token).then((route) => route.manager, () => null)));
+    const routed = tokens.filter((_, index) => spenders[index] !== null);
+    const read = await readAllowances(
+      (data) => this.provider.call({ to: MULTICALL3_ADDRESS, data }),
+      routed,
+      this.wallet.address,
+      spenders.filter((candidate): candidate is string => candidate !== null)
+    );
+    let next = 0;
+    return spenders.map((candidate) => (candidate === null ? null : read[next++]));
+  }
+
+  /**
+   * Approve each token's TokenManager for every token whose allowance is below `threshold`.
+   * Allowances are read in one call; approvals are sent one at a time and a failure on one
+   * token doesn't stop the rest.
+   * @param threshold - One threshold for every token, or one per token in the same order
+   * @param amount - Amount to approve (default: unlimited)
+   */
+  async batchApprove(
+    tokens: string[],
+    threshold: bigint | readonly bigint[],
+    amount?: bigint,
+    gas?: GasOptions
+  ): Promise<BatchApproveResult[]> {
+    const thresholds = typeof threshold === 'bigint' ? tokens.map(() => threshold) : threshold;
+    if (thresholds.length !== tokens.length) {
+      throw new ValidationError(`Expected ${tokens.length} thresholds, one per token, got ${thresholds.length}`);
+    }
+    const current = await this.allowances(tokens);
+    const results: BatchApproveResult[] = [];
+
+    for (const [index, token] of tokens.entries()) {
+      const allowance = current[index];
+      if (allowance === null) {
+        results.push({ token, status: 'failed', allowance, error: 'Allowance read failed' });
+        continue;
+      }
+      if (allowance >= thresholds[index]) {
+        results.push({ token, status: 'sufficient', allowance });
+        continue;
+      }
+
+      try {
+        const result = await this.approveToken(token, amount, gas);
+        results.push({ token, status: 'approved', allowance, result });
+      } catch (error: any) {
+        results.push({ token, status: 'failed', allowance, error: error?.message ?? String(error) });
+      }
+    }
+
+    return results;
+  }
+
+  // ==================== Query Functions ====================
+
+  /**
+   * Get token information
+   */
+  async getTokenInfo(tokenAddress: string): Promise<TokenInfo> {
+    return await this.readTokenInfo(tokenAddress);
+  }
+
+  /**
+   * Quote buy - calculate how many tokens you get for given BNB amount
+   * @param bnbAmount - Amount in wei, use ethers.parseEther(amount) to convert from BNB
+   */
+  async quoteBuy(tokenAddress: string, bnbAmount: bigint, options: QuoteOptions = {}): Promise<PriceInfo> {
+    if (this.quoteComparator && options.blockTag === undefined) {
+      return await this.quoteComparator.quote('buy', tokenAddress, bnbAmount, (blockTag) =>
+        this.priceCalculator.quoteBuy(tokenAddress, bnbAmount, { ...options, blockTag })
+      );
+    }
+    return await this.priceCalculator.quoteBuy(tokenAddress, bnbAmount, options);
+  }
+
+  /**
+   * Buy quotes for several spends at once, e.g. 0.1/0.5/1/2 BNB to see how price impact grows
+   * with size. One token info read plus two Multicall3 calls however many amounts are given;
+   * each quote matches what quoteBuy returns for that amount.
+   */
+  async quoteBuyLadder(tokenAddress: string, amounts: bigint[], options: QuoteOptions = {}): Promise<PriceInfo[]> {
+    Validator.validateAddress(tokenAddress, 'tokenAddress');
+    amounts.forEach((amount, index) => Validator.validateAmount(amount, `amounts[${index}]`));
+    if ((await this.detectVersion(tokenAddress)).version === 1) {
+      // The ladder batches the V2 manager's calc reads; V1 tokens are quoted one by one by the helper
+      return await Promise.all(amounts.map((amount) => this.priceCalculator.quoteBuy(tokenAddress, amount, options)));
+    }
+    await this.capabilityProbe.require('quoteBuyLadder', 'multicall');
+
+    const tokenInfo = await this.priceCalculator.quoteTokenInfo(tokenAddress, options.blockTag);
+    const reads = await readBuyQuotes(
+      (data) => this.provider.call({ to: MULTICALL3_ADDRESS, data, blockTag: options.blockTag }),
+      this.contractAddress,
+      tokenInfo,
+      amounts
+    );
+    return reads.map((read, index) => {
+      if (!read) {
+        throw new ConnectionError(`Buy quote read failed for ${amounts[index]} wei of ${tokenAddress}`);
+      }
+      if (read.fee < amounts[index] && read.tokenAmount === 0n && !options.allowDustFills) {
+        throw new QuoteReturnedZeroError('buy', tokenAddress, amounts[index], zeroBuyReason(tokenInfo));
+      }
+      return buyPriceInfo(tokenInfo, amounts[index], read.fee, read.grossTokenAmount, read.tokenAmount);
+    });
+  }
+
+  /**
+   * Everything a buy of `bnbAmount` costs: the BNB entering the curve, the platform fee and the
+   * estimated gas, plus the token floor `slippagePercent` allows. Spends that overshoot the curve
+   * take the refund path, so their gas estimate carries the same buffer buyToken applies.
+   */
+  async totalBuyCost(tokenAddress: string, bnbAmount: bigint, slippagePercent: number = 0, gas?: GasOptions): Promise<BuyCostBreakdown> {
+    const quote = await this.quoteBuy(tokenAddress, bnbAmount);
+    let gasLimit = this.gasProfiler.get('buyTokenAMAP') ?? BUY_GAS_LIMIT_ESTIMATE;
+    if (quote.willPartiallyFill) {
+      gasLimit = (gasLimit * PARTIAL_FILL_GAS_BUFFER_BPS) / SLIPPAGE_DENOMINATOR;
+    }
+    const gasCost = await this.estimateGasCost(gas, gasLimit);
+    return buyCostBreakdown(quote, gasCost, slippagePercent);
+  }
+
+  /**
+   * Quote sell - calculate how much BNB you get for given token amount
+   * @param tokenAmount - Amount in wei, use ethers.parseUnits(amount, 18) to convert
+   */
+  async quoteSell(tokenAddress: string, tokenAmount: bigint, options: QuoteOptions = {}): Promise<PriceInfo> {
+    if (this.quoteComparator && options.blockTag === undefined) {
+      return await this.quoteComparator.quote('sell', tokenAddress, tokenAmount, (blockTag) =>
+        this.priceCalculator.quoteSell(tokenAddress, tokenAmount, { ...options, blockTag })
+      );
+    }
+    return await this.priceCalculator.quoteSell(tokenAddress, tokenAmount, options);
+  }
+
+  /**
+   * Smallest and largest BNB spend (wei) the manager accepts for a buy of the token. Below the
+   * minimum the trading fee takes the whole spend and the buy reverts; there is no maximum, so
+   * `maxFunds` is MAX_UINT256. buyToken throws AmountOutOfRangeError for spends outside the range.
+   */
+  async buyLimits(tokenAddress: string): Promise<BuyLimits> {
+    return await this.priceCalculator.getBuyLimits(tokenAddress);
+  }
+
+  /**
+   * Bonding-curve parameters (invariant and virtual reserves) of a token; see curve.ts for the formula
+   */
+  async curveParams(tokenAddress: string): Promise<CurveParams> {
+    const info = await this.priceCalculator.getTokenInfo(tokenAddress);
+    return curveParams(tokenAddress, info);
+  }
+
+  /**
+   * The launch template a token was created from: its curve shape, graduation threshold and
+   * initial price. Cached per token; throws UnverifiedTokenError for tokens four.meme doesn't know.
+   */
+  async launchTemplate(tokenAddress: string): Promise<LaunchTemplate> {
+    Validator.validateAddress(tokenAddress, 'tokenAddress');
+    const key = tokenAddress.toLowerCase();
+    const cached = this.launchTemplateCache.get(key);
+    if (cached) {
+      return { ...cached };
+    }
+
+    if ((await this.detectVersion(tokenAddress)).version === 1) {
+      throw new ValidationError(`${tokenAddress} trades on the V1 TokenManager, which has no launch templates`);
+    }
+    const info = await this.readTokenInfo(tokenAddress);
+    if (!isRegisteredTokenInfo(info)) {
+      throw new UnverifiedTokenError(tokenAddress, 'NotAFourmemeToken');
+    }
+    const template = await this.getTemplate(toNumberChecked(info.template, 'template'));
+
+    const result = launchTemplate(tokenAddress, info, template);
+    this.launchTemplateCache.set(key, result);
+    return { ...result };
+  }
+
+  /**
+   * Replay a buy of `bnbAmount` on the token's current curve at the contract's trading fee: tokens
+   * received, the reserves after it, whether it fills the curve and graduates the token, and what
+   * capacity is left. Nothing is sent.
+   */
+  async simulateBuyDetailed(tokenAddress: string, bnbAmount: bigint): Promise<BuySimulation> {
+    Validator.validateAddress(tokenAddress, 'tokenAddress');
+    const [info, feeRate] = await Promise.all([
+      this.priceCalculator.getTokenInfo(tokenAddress),
+      this.getTradingFeeRate(),
+    ]);
+    return simulateBuy(curveParams(tokenAddress, info), bnbAmount, toNumberChecked(feeRate, 'feeRate'));
+  }
+
+  /**
+   * BNB to spend, fee included, for a buy whose average fill price is `targetAvgPrice` (wei per
+   * whole token, as PriceInfo.pricePerToken), solved on the token's current curve at the contract's
+   * trading fee. Throws TargetPriceUnreachableError for targets no buy on the curve averages.
+   */
+  async buySizeForAvgPrice(tokenAddress: string, targetAvgPrice: bigint): Promise<bigint> {
+    Validator.validateAddress(tokenAddress, 'tokenAddress');
+    const [info, feeRate] = await Promise.all([
+      this.priceCalculator.getTokenInfo(tokenAddress),
+      this.getTradingFeeRate(),
+    ]);
+    return buySizeForAvgPrice(curveParams(tokenAddress, info), targetAvgPrice, toNumberChecked(feeRate, 'feeRate'));
+  }
+
+  /**
+   * Split a sell of `totalAmount` tokens into chunks that each move the price by at most
+   * `maxImpactBps`, replaying every chunk on the curve state left by the previous ones.
+   * The replay runs on the local curve model from one token info read, with no further RPC.
+   * V1 tokens don't report their curve parameters and are refused with ValidationError.
+   * Send the chunks in order with sellToken; an 'impactLimited' plan leaves `remaining` unplanned.
+   */
+  async suggestChunks(
+    tokenAddress: string,
+    totalAmount: bigint,
+    maxImpactBps: number,
+    options?: ChunkPlanOptions
+  ): Promise<ChunkPlan> {
+    const state = await this.priceCalculator.getTokenInfo(tokenAddress);
+    if (state.K === 0n || state.T === 0n) {
+      throw new ValidationError(`suggestChunks needs the curve parameters of ${tokenAddress}, which V1 tokens don't report`);
+    }
+    return await planSellChunks(state, totalAmount, maxImpactBps, localSellCost(tokenAddress), options);
+  }
+
+  /**
+   * Recent divergences from the secondary quoter, oldest first (last QUOTE_DIVERGENCE_LOG_SIZE)
+   */
+  getQuoteDivergences(): QuoteDivergence[] {
+    return [...this.quoteDivergences];
+  }
+
+  private recordQuoteDivergence(divergence: QuoteDivergence): void {
+    console.log(
+      `Quote divergence on ${divergence.side} ${divergence.token}: ${divergence.divergenceBps} bps ` +
+      `at block ${divergence.blockTag} (heads: primary ${divergence.primaryBlock}, secondary ${divergence.secondaryBlock})`
+    );
+    this.quoteDivergences.push(divergence);
+    if (this.quoteDivergences.length > QUOTE_DIVERGENCE_LOG_SIZE) {
+      this.quoteDivergences.shift();
+    }
+    for (const listener of this.quoteDivergenceListeners.values()) {
+      listener(divergence);
+    }
+  }
+
+  /**
+   * Get current price of token
+   */
+  async getCurrentPrice(tokenAddress: string): Promise<string> {
+    const price = await this.priceCalculator.getCurrentPrice(tokenAddress);
+    return ethers.formatEther(price);
+  }
+
+  /**
+   * Calculate buy amount with slippage protection
+   * @param bnbAmount - Amount in wei, use ethers.parseEther(amount) to convert from BNB
+   */
+  async calculateBuyWithSlippage(
+    tokenAddress: string,
+    bnbAmount: bigint,
+    slippagePercent: number = 1,
+    decimals?: number
+  ): Promise<{ minTokenAmount: string; estimatedTokenAmount: string; pricePerToken: string }> {
+    if (decimals !== undefined) {
+      Validator.validateDecimals(decimals);
+    }
+    const priceInfo = await this.priceCalculator.quoteBuy(tokenAddress, bnbAmount);
+    const minAmount = await this.priceCalculator.estimateBuySlippage(tokenAddress, bnbAmount, slippagePercent);
+    const tokenDecimals = decimals ?? this.getTokenDecimals(tokenAddress);
+
+    return {
+      minTokenAmount: ethers.formatUnits(minAmount, tokenDecimals),
+      estimatedTokenAmount: ethers.formatUnits(priceInfo.tokenAmount, tokenDecimals),
+      pricePerToken: priceInfo.price.toDecimalString(),
+    };
+  }
+
+  /**
+   * Calculate sell with slippage protection
    * @param tokenAmount - Amount in wei, use ethers.parseUnits(amount, 18) to convert
    */
-  async quoteSell(tokenAddress: string, tokenAmount: bigint): Promise<PriceInfo> {
-    return await this.priceCalculator.quoteSell(tokenAddress, tokenAmount);
+  async calculateSellWithSlippage(
+    tokenAddress: string,
+    tokenAmount: bigint,
+    slippagePercent: number = 1
+  ): Promise<{ minBnbAmount: string; estimatedBnbAmount: string; pricePerToken: string }> {
+    const priceInfo = await this.priceCalculator.quoteSell(tokenAddress, tokenAmount);
+    const minFunds = await this.priceCalculator.estimateSellSlippage(tokenAddress, tokenAmount, slippagePercent);
+
+    return {
+      minBnbAmount: ethers.formatEther(minFunds),
+      estimatedBnbAmount: ethers.formatEther(priceInfo.bnbCost),
+      pricePerToken: priceInfo.price.toDecimalString(),
+    };
+  }
+
+  /**
+   * Get extended token information
+   */
+  async getTokenInfoEx(tokenAddress: string): Promise<TokenInfoEx> {
+    const info = await this.contract._tokenInfoExs(tokenAddress);
+    return {
+      creator: info.creator,
+      founder: info.founder,
+      reserves: info.reserves,
+    };
+  }
+
+  /**
+   * keccak256 of a contract's runtime bytecode, e.g. to seed `officialTokenCodeHashes`
+   * from a token known to be official
+   */
+  async getTokenCodeHash(tokenAddress: string): Promise<string> {
+    const code = await this.provider.getCode(tokenAddress);
+    return ethers.keccak256(code);
+  }
+
+  /**
+   * Verify a token was launched through four.meme: its bytecode hash must match an official
+   * token implementation and the TokenManager must return sane TokenInfo for it.
+   * Results are cached for the lifetime of the instance since bytecode is immutable. Only
+   * definitive answers are cached: a failed read throws and is retried by the next call, so a
+   * transient RPC error never marks a legitimate token as unregistered.
+   */
+  async verifyFourmemeToken(tokenAddress: string): Promise<VerificationReport> {
+    const key = tokenAddress.toLowerCase();
+    const cached = this.verificationCache.get(key);
+    if (cached) {
+      return { ...cached };
+    }
+
+    const [codeHash, registered] = await Promise.all([
+      this.getTokenCodeHash(tokenAddress),
+      this.isRegisteredToken(tokenAddress).catch((error) => {
+        if (error?.code === 'CALL_EXCEPTION') {
+          return false; // The manager refused the read: not a token it launched
+        }
+        throw error;
+      }),
+    ]);
+
+    const report: VerificationReport = {
+      token: tokenAddress,
+      status: classifyToken(codeHash, registered, this.officialTokenCodeHashes),
+      codeHash,
+      registered,
+    };
+    this.verificationCache.set(key, report);
+    return { ...report };
+  }
+
+  /**
+   * Whether a TokenManager launched the token: V1 tokens by the helper routing them, V2 tokens by
+   * their `_tokenInfos` entry
+   */
+  private async isRegisteredToken(tokenAddress: string): Promise<boolean> {
+    if ((await this.detectVersion(tokenAddress)).version === 1) {
+      return true;
+    }
+    return isRegisteredTokenInfo(await this.getTokenInfo(tokenAddress));
+  }
+
+  /**
+   * Get template information
+   */
+  async getTemplate(templateId: number): Promise<Template> {
+    const template = await this.contract._templates(templateId);
+    return {
+      quote: template.quote,
+      initialLiquidity: template.initialLiquidity,
+      maxRaising: template.maxRaising,
+      totalSupply: template.totalSupply,
+      maxOffers: template.maxOffers,
+      minTradingFee: template.minTradingFee,
+    };
+  }
+
+  /**
+   * Get token address by index
+   */
+  async getTokenByIndex(index: number): Promise<string> {
+    return await this.contract._tokens(index);
+  }
+
+  /**
+   * Get total token count
+   */
+  async getTokenCount(): Promise<bigint> {
+    return await this.contract._tokenCount();
+  }
+
+  /**
+   * Get template count
+   */
+  async getTemplateCount(): Promise<bigint> {
+    return await this.contract._templateCount();
+  }
+
+  /**
+   * Get current trading fee rate
+   */
+  async getTradingFeeRate(): Promise<bigint> {
+    return await this.contract._tradingFeeRate();
+  }
+
+  /**
+   * Get launch fee
+   */
+  async getLaunchFee(): Promise<bigint> {
+    return await this.contract._launchFee();
+  }
+
+  /**
+   * Get fee recipient address
+   */
+  async getFeeRecipient(): Promise<string> {
+    return await this.contract._feeRecipient();
+  }
+
+  /**
+   * Get referral reward rate
+   */
+  async getReferralRewardRate(): Promise<bigint> {
+    return await this.contract._referralRewardRate();
+  }
+
+  /**
+   * Check if trading is halted
+   */
+  async isTradingHalted(): Promise<boolean> {
+    return await this.contract._tradingHalt();
+  }
+
+  /**
+   * Get contract owner
+   */
+  async getOwner(): Promise<string> {
+    return await this.contract.owner();
+  }
+
+  /**
+   * Get signer address
+   */
+  async getSigner(): Promise<string> {
+    return await this.contract.signer();
+  }
+
+  /**
+   * Get status constants
+   */
+  async getStatusConstants(): Promise<{
+    TRADING: bigint;
+    ADDING_LIQUIDITY: bigint;
+    COMPLETED: bigint;
+    HALT: bigint;
+  }> {
+    const [TRADING, ADDING_LIQUIDITY, COMPLETED, HALT] = await Promise.all([
+      this.contract.STATUS_TRADING(),
+      this.contract.STATUS_ADDING_LIQUIDITY(),
+      this.contract.STATUS_COMPLETED(),
+      this.contract.STATUS_HALT(),
+    ]);
+    return { TRADING, ADDING_LIQUIDITY, COMPLETED, HALT };
+  }
+
+  /**
+   * Calculate buy amount for given funds; V1 tokens are quoted by TokenManagerHelper3 (see PriceCalculator.calcBuyAmount)
+   */
+  async calcBuyAmount(tokenInfo: TokenInfo, funds: bigint): Promise<bigint> {
+    return await this.priceCalculator.calcBuyAmount(tokenInfo, funds);
+  }
+
+  /**
+   * Calculate buy cost for given amount
+   */
+  async calcBuyCost(tokenInfo: TokenInfo, amount: bigint): Promise<bigint> {
+    return await this.contract.calcBuyCost(tokenInfo, amount);
+  }
+
+  /**
+   * Calculate sell cost for given amount; V1 tokens are quoted by TokenManagerHelper3
+   */
+  async calcSellCost(tokenInfo: TokenInfo, amount: bigint): Promise<bigint> {
+    return await this.priceCalculator.calcSellCost(tokenInfo, amount);
+  }
+
+  /**
+   * Calculate trading fee
+   */
+  async calcTradingFee(tokenInfo: TokenInfo, funds: bigint): Promise<bigint> {
+    return await this.contract.calcTradingFee(tokenInfo, funds);
+  }
+
+  /**
+   * Calculate last price
+   */
+  async calcLastPrice(tokenInfo: TokenInfo): Promise<bigint> {
+    return await this.contract.calcLastPrice(tokenInfo);
+  }
+
+  /**
+   * Get the latest block number and its timestamp (Unix seconds)
+   */
+  async currentBlock(): Promise<ChainHead> {
+    const block = await this.provider.getBlock('latest');
+    if (!block) {
+      throw new ConnectionError('Provider returned no latest block');
+    }
+    const head = { number: block.number, timestamp: block.timestamp };
+    this.noteHead(head);
+    return head;
+  }
+
+  /**
+   * Check whether a four.meme trade was sandwiched: a buy by another address right before it
+   * and a sell by that same address after it, on the same token in the same block.
+   * The block's trades are read from the manager's TokenPurchase and TokenSale logs (one getLogs),
+   * so legs sent through a bot or router contract are found as well as direct calls.
+   */
+  async analyzeSandwich(txHash: string): Promise<SandwichReport> {
+    const receipt = await this.provider.getTransactionReceipt(txHash);
+    if (!receipt) {
+      throw new ValidationError(`Transaction ${txHash} not found or not yet mined`);
+    }
+
+    const iface = getFourTradingInterface();
+    const topics = [iface.getEvent('TokenPurchase')!.topicHash, iface.getEvent('TokenSale')!.topicHash];
+    const ownLogs = receipt.logs.filter((log) => topics.includes(log.topics[0]));
+    const own = this.decodeBlockTrades(ownLogs);
+    if (own.length === 0) {
+      return { txHash, blockNumber: receipt.blockNumber, sandwiched: false, valueExtracted: 0n };
+    }
+
+    const logs = await this.provider.getLogs({
+      address: ownLogs[0].address,
+      topics: [topics],
+      fromBlock: receipt.blockNumber,
+      toBlock: receipt.blockNumber,
+    });
+    const neighbours = logs.filter((log) => log.transactionHash !== receipt.hash && !log.removed);
+
+    return detectSandwich(own[0], [...own, ...this.decodeBlockTrades(neighbours)], receipt.blockNumber);
+  }
+
+  /**
+   * Decode four.meme trade logs for block-level analysis, each tagged with its transaction
+   */
+  private decodeBlockTrades(logs: ReadonlyArray<ethers.Log>): BlockTrade[] {
+    const trades: BlockTrade[] = [];
+    for (const log of logs) {
+      const parsed = this.contract.interface.parseLog({ topics: [...log.topics], data: log.data });
+      if (!parsed || (parsed.name !== 'TokenPurchase' && parsed.name !== 'TokenSale')) {
+        continue;
+      }
+      const buy = parsed.name === 'TokenPurchase';
+      trades.push({
+        txHash: log.transactionHash,
+        txIndex: log.transactionIndex,
+        side: buy ? 'buy' : 'sell',
+        token: parsed.args.token,
+        account: parsed.args.account,
+        amount: parsed.args.amount,
+        bnbAmount: buy ? parsed.args.cost + parsed.args.fee : parsed.args.cost - parsed.args.fee,
+      });
+    }
+    return trades;
+  }
+
+  /**
+   * Whether the latest block is younger than the stall threshold (CHAIN_STALL_THRESHOLD when
+   * stall detection is disabled). Provider errors are thrown, not reported as unhealthy.
+   */
+  async isChainHealthy(): Promise<boolean> {
+    const threshold = this.chainStallThreshold > 0 ? this.chainStallThreshold : CHAIN_STALL_THRESHOLD;
+    return secondsSinceBlock(await this.currentBlock()) <= threshold;
+  }
+
+  /**
+   * Block and timestamp at which a token was created, from its TokenCreate event.
+   * Searches backward from the current block in BACKFILL_PAGE_SIZE windows, at most
+   * `tokenCreateLookbackBlocks` deep, and throws TokenCreationNotFoundError past that. Results,
+   * including misses, are cached for the lifetime of the instance.
+   */
+  async tokenCreatedAt(tokenAddress: string): Promise<TokenCreation> {
+    const key = tokenAddress.toLowerCase();
+    const cached = this.creationCache.get(key);
+    if (cached) {
+      return { ...cached };
+    }
+    const missedFrom = this.creationMisses.get(key);
+    if (missedFrom !== undefined) {
+      throw new TokenCreationNotFoundError(tokenAddress, missedFrom);
+    }
+
+    const topics = [getFourTradingInterface().getEvent('TokenCreate')!.topicHash];
+    const latest = await this.provider.getBlockNumber();
+    const block = await findTokenCreationBlock(
+      tokenAddress,
+      latest,
+      (fromBlock, toBlock) => this.provider.getLogs({ address: this.contractAddress, topics, fromBlock, toBlock }),
+      BACKFILL_PAGE_SIZE,
+      this.tokenCreateLookbackBlocks
+    );
+    if (block === null) {
+      const searchedFrom = Math.max(0, latest - this.tokenCreateLookbackBlocks);
+      this.creationMisses.set(key, searchedFrom);
+      throw new TokenCreationNotFoundError(tokenAddress, searchedFrom);
+    }
+
+    const header = await this.provider.getBlock(block);
+    if (!header) {
+      throw new ConnectionError(`Provider returned no block ${block}`);
+    }
+
+    const creation = { block, timestamp: header.timestamp };
+    this.creationCache.set(key, creation);
+    return { ...creation };
+  }
+
+  /**
+   * The four.meme TokenManager version and contract that trade a token, from TokenManagerHelper3.
+   * Trades and approvals are sent to that manager with its own method names. Results are cached.
+   * The configured (V2) contract is assumed only when the chain answers definitively: no helper
+   * deployed (e.g. off mainnet), a revert, or a token the helper doesn't know. Transport errors
+   * are rethrown rather than guessed, since a V1 token sent to the V2 manager would fail.
+   */
+  async detectVersion(tokenAddress: string): Promise<TokenManagerRoute> {
+    const key = tokenAddress.toLowerCase();
+    const cached = this.versionCache.get(key);
+    if (cached) {
+      return { ...cached };
+    }
+
+    let route: TokenManagerRoute;
+    try {
+      const info = await this.tokenManagerHelper.getTokenInfo(tokenAddress);
+      route = info.tokenManager === ethers.ZeroAddress
+        ? { version: 2, manager: this.contractAddress }
+        : routeFromHelperInfo(tokenAddress, info.version, info.tokenManager);
+    } catch (error) {
+      if (!isDefinitiveHelperFailure(error)) {
+        throw error;
+      }
+      route = { version: 2, manager: this.contractAddress };
+    }
+    if (route.version === 2) {
+      route.manager = this.contractAddress;
+    }
+    this.versionCache.set(key, route);
+    return { ...route };
+  }
+
+  /**
+   * Tokens still trading on the bonding curve, newest first, `limit` per page. Pass the returned
+   * cursor to continue; it is null once `tokenCreateLookbackBlocks` below the first head is exhausted.
+   */
+  async listActiveTokens(limit: number, cursor?: TokenDirectoryCursor): Promise<TokenDirectoryPage> {
+    return await (await this.getTokenDirectory()).list(limit, cursor);
+  }
+
+  /**
+   * Tokens created in the last `windowBlocks` blocks whose symbol (or, when given, name) matches
+   * `symbol` after case, whitespace, accent and homoglyph normalization, oldest first
+   */
+  async findSymbolCollisions(symbol: string, windowBlocks: number, name?: string): Promise<TokenMatch[]> {
+    if (!Number.isInteger(windowBlocks) || windowBlocks < 0) {
+      throw new ValidationError(`windowBlocks must be a non-negative integer, got ${windowBlocks}`);
+    }
+    const directory = await this.getTokenDirectory();
+    const head = await directory.head();
+    const records = await directory.creationsSince(Math.max(0, head - windowBlocks));
+
+    const matches: TokenMatch[] = [];
+    for (const match of findCollisions({ symbol, name }, records)) {
+      matches.push({
+        token: match.token,
+        name: match.name,
+        symbol: match.symbol,
+        creator: match.creator,
+        block: match.block,
+        createdAt: await directory.blockTimestamp(match.block),
+        reason: match.reason,
+      });
+    }
+    return matches;
+  }
+
+  /**
+   * The cache behind listActiveTokens; `attach(trader)` keeps it current from the live streams
+   */
+  async getTokenDirectory(): Promise<TokenDirectory> {
+    if (!this.tokenDirectory) {
+      await this.capabilityProbe.require('listActiveTokens', 'multicall');
+      const iface = getFourTradingInterface();
+      const topics = [['TokenCreate', 'TokenPurchase', 'TokenSale', 'LiquidityAdded'].map((name) => iface.getEvent(name)!.topicHash)];
+      this.tokenDirectory = this.getTradingStatus().then((tradingStatus) => new TokenDirectory(
+        {
+          getBlockNumber: () => this.provider.getBlockNumber(),
+          getLogs: (fromBlock, toBlock) => this.provider.getLogs({ address: this.contractAddress, topics, fromBlock, toBlock }),
+          readTokenInfos: (tokens) => readTokenInfos(
+            (data) => this.provider.call({ to: MULTICALL3_ADDRESS, data }),
+            this.contractAddress,
+            tokens
+          ),
+          getBlockTimestamp: async (block) => {
+            const header = await this.provider.getBlock(block);
+            if (!header) {
+              throw new ConnectionError(`Provider returned no block ${block}`);
+            }
+            return header.timestamp;
+          },
+        },
+        { tradingStatus, maxLookback: this.tokenCreateLookbackBlocks }
+      ));
+      this.tokenDirectory.catch(() => {
+        this.tokenDirectory = null;
+      });
+    }
+    return await this.tokenDirectory;
+  }
+
+  /**
+   * Holder count and top-holder concentration, rebuilt from the token's Transfer logs since its
+   * creation in BACKFILL_PAGE_SIZE windows. The balances are kept per token: within
+   * `holderCacheTtlMs` they are reused as they are, and after it only the blocks since the last
+   * scan are read. The TokenManager's unsold curve supply and the PancakeSwap pair's liquidity are
+   * not holders.
+   */
+  async holders(tokenAddress: string, topN: number = HOLDER_TOP_N): Promise<HolderStats> {
+    if (!Number.isInteger(topN) || topN <= 0) {
+      throw new ValidationError(`Top holder count must be a positive integer, got ${topN}`);
+    }
+
+    const scan = await this.holderScan(tokenAddress);
+    const excluded = scan.pair ? [scan.manager, scan.pair] : [scan.manager];
+    return summarizeHolders(tokenAddress, scan.balances, scan.totalSupply, topN, excluded, scan.fromBlock, scan.toBlock);
+  }
+
+  /**
+   * The token's scan, extended to the head once it is older than `holderCacheTtlMs`. Callers
+   * arriving during an extension share it; a failed one leaves the previous scan in place.
+   */
+  private async holderScan(tokenAddress: string): Promise<HolderScan> {
+    const key = tokenAddress.toLowerCase();
+    const cached = this.holderScans.get(key);
+    const previous = cached ? await cached : undefined;
+    if (previous && Date.now() - previous.scannedAt < this.holderCacheTtlMs) {
+      return previous;
+    }
+    const current = this.holderScans.get(key);
+    if (current !== cached) {
+      return await current!; // Another caller started the extension while this one waited
+    }
+
+    const extension = this.extendHolderScan(tokenAddress, previous);
+    this.holderScans.set(key, extension);
+    extension.catch(() => {
+      if (this.holderScans.get(key) === extension) {
+        if (previous) {
+          this.holderScans.set(key, Promise.resolve(previous));
+        } else {
+          this.holderScans.delete(key);
+        }
+      }
+    });
+    return await extension;
+  }
+
+  private async extendHolderScan(tokenAddress: string, previous?: HolderScan): Promise<HolderScan> {
+    const token = new ethers.Contract(tokenAddress, getErc20Interface(), this.provider);
+    const topics = [getErc20Interface().getEvent('Transfer')!.topicHash];
+    const [fromBlock, head, totalSupply, route, pair] = await Promise.all([
+      previous ? previous.fromBlock : this.tokenCreatedAt(tokenAddress).then((creation) => creation.block),
+      this.provider.getBlockNumber(),
+      token.totalSupply() as Promise<bigint>,
+      this.detectVersion(tokenAddress),
+      previous?.pair ?? this.pancakePairIfAny(tokenAddress),
+    ]);
+
+    const balances = new Map(previous?.balances ?? []);
+    const start = previous ? previous.toBlock + 1 : fromBlock;
+    for (let windowStart = start; windowStart <= head; windowStart += BACKFILL_PAGE_SIZE) {
+      const end = Math.min(head, windowStart + BACKFILL_PAGE_SIZE - 1);
+      applyTransfers(balances, await this.provider.getLogs({ address: tokenAddress, topics, fromBlock: windowStart, toBlock: end }));
+    }
+
+    return {
+      fromBlock,
+      toBlock: Math.max(head, start - 1),
+      balances,
+      totalSupply,
+      manager: route.manager,
+      pair,
+      scannedAt: Date.now(),
+    };
+  }
+
+  /**
+   * The token's PancakeSwap pair, or null before graduation or without a configured router and factory
+   */
+  private async pancakePairIfAny(tokenAddress: string): Promise<string | null> {
+    const configured = (address: string) => ethers.isAddress(address) && address !== ethers.ZeroAddress;
+    if (!configured(this.pancakeFactory) || !configured(this.pancakeRouter)) {
+      return null;
+    }
+    const pair = await this.getPancakePair(tokenAddress);
+    return pair === ethers.ZeroAddress ? null : pair;
+  }
+
+  /**
+   * Who bought in the token's creation block: distinct buyers, their share of supply, whether the
+   * creator or wallets it funded (one hop, `creationFundingLookbackBlocks` deep) bought, and the gas
+   * prices paid. Reports are cached per token.
+   */
+  async creationBlockAnalysis(tokenAddress: string): Promise<CreationBlockReport> {
+    const key = tokenAddress.toLowerCase();
+    const cached = this.creationBlockReports.get(key);
+    if (cached) {
+      return cached;
+    }
+
+    const iface = getFourTradingInterface();
+    const topics = [['TokenCreate', 'TokenPurchase'].map((name) => iface.getEvent(name)!.topicHash)];
+    const { block } = await this.tokenCreatedAt(tokenAddress);
+    const report = await analyzeCreationBlock(
+      tokenAddress,
+      block,
+      {
+        getBlockLogs: (blockNumber) =>
+          this.provider.getLogs({ address: this.contractAddress, topics, fromBlock: blockNumber, toBlock: blockNumber }),
+        getBlockTransactions: (blockNumber) => this.getBlockTransactions(blockNumber),
+      },
+      this.creationFundingLookbackBlocks
+    );
+    this.creationBlockReports.set(key, report);
+    return report;
+  }
+
+  /**
+   * Score a token creator (0-100) from the outcomes of its earlier tokens and those of creators
+   * sharing a funder. The funder is traced from the creator's first incoming BNB, up to
+   * `creatorFundingHops` hops of `creatorFundingLookbackBlocks` blocks each back from `atBlock`
+   * (default: latest); this reads historical balances, so it needs an archive node. Traced hops are
+   * remembered in the registry, which scores only what it has observed; see creatorRegistry.ts.
+   */
+  async creatorReputation(creator: string, atBlock?: number): Promise<CreatorReport> {
+    Validator.validateAddress(creator, 'creator');
+    const head = await this.provider.getBlockNumber();
+    const from = atBlock ?? head;
+    await this.capabilityProbe.require(
+      'creatorReputation',
+      'archiveDepth',
+      head - from + this.creatorFundingLookbackBlocks * this.creatorFundingHops
+    );
+    return await this.scoreCreator(creator, from);
+  }
+
+  /**
+   * Trace `creator`'s funding back from `from` and score it. A hop whose state the node has
+   * pruned ends the trace there instead of failing it.
+   */
+  private async scoreCreator(creator: string, from: number): Promise<CreatorReport> {
+    const funding = await traceFunding(
+      creator,
+      from,
+      {
+        getBalance: (address, block) => this.provider.getBalance(address, block),
+        getTransactionCount: (address, block) => this.provider.getTransactionCount(address, block),
+        getBlockTransactions: (block) => this.getBlockTransactions(block),
+      },
+      {
+        lookbackBlocks: this.creatorFundingLookbackBlocks,
+        hops: this.creatorFundingHops,
+        known: (address) => this.creatorRegistry.fundingOf(address),
+      }
+    );
+    funding.forEach((hop) => this.creatorRegistry.recordFunding(hop));
+    return this.creatorRegistry.report(creator, funding);
+  }
+
+  /**
+   * The registry behind creatorReputation; attach it to the trader to record creations and outcomes.
+   * With minCreatorReputation set it is already attached.
+   */
+  getCreatorRegistry(): CreatorRegistry {
+    return this.creatorRegistry;
+  }
+
+  /**
+   * Stop recording into the registry attached for minCreatorReputation, and flush it
+   */
+  async detachCreatorRegistry(): Promise<void> {
+    const detach = this.detachRegistry;
+    this.detachRegistry = undefined;
+    await detach?.();
+  }
+
+  /**
+   * Stop the live updates of the token directory attached by the symbol collision gate; the next
+   * gated buy attaches it again
+   */
+  detachTokenDirectory(): void {
+    const detach = this.detachDirectory;
+    this.detachDirectory = undefined;
+    detach?.();
+  }
+
+  private async getBlockTransactions(blockNumber: number): Promise<BlockTransaction[]> {
+    const header = await this.provider.getBlock(blockNumber, true);
+    if (!header) {
+      throw new ConnectionError(`Provider returned no block ${blockNumber}`);
+    }
+    return header.prefetchedTransactions.map((tx) => ({
+      hash: tx.hash,
+      from: tx.from,
+      to: tx.to,
+      value: tx.value,
+      gasPrice: tx.gasPrice,
+    }));
+  }
+
+  /**
+   * Get BNB balance of wallet
+   */
+  async getBNBBalance(): Promise<string> {
+    const balance = await this.readBNBBalance();
+    return ethers.formatEther(balance);
+  }
+
+  /**
+   * USD per BNB from the configured Chainlink feed. Throws PriceFeedError when the latest round is
+   * older than `bnbUsdMaxAge` or its answer isn't positive.
+   */
+  async bnbUsdPrice(): Promise<number> {
+    return (await this.readBnbUsdPrice()).usdPerBnb;
+  }
+
+  /**
+   * USD value of a BNB amount (wei) at the current feed price, e.g. for fiat cost and PnL displays
+   */
+  async toUsd(bnbWei: bigint): Promise<number> {
+    return bnbToUsd(bnbWei, await this.readBnbUsdPrice());
+  }
+
+  private async readBnbUsdPrice(): Promise<BnbUsdPrice> {
+    const feed = new ethers.Contract(this.bnbUsdFeed, getChainlinkAggregatorInterface(), this.provider);
+    this.bnbUsdDecimals ??= toNumberChecked(await feed.decimals(), 'feed decimals');
+    const round = await feed.latestRoundData();
+    return bnbUsdPriceFromRound(
+      this.bnbUsdFeed,
+      round.answer,
+      this.bnbUsdDecimals,
+      toNumberChecked(round.updatedAt, 'updatedAt'),
+      Math.floor(Date.now() / 1000),
+      this.bnbUsdMaxAge
+    );
+  }
+
+  /**
+   * Get token balance of wallet, formatted with `decimals` or else the token's configured decimals
+   */
+  async getTokenBalance(tokenAddress: string, decimals?: number): Promise<string> {
+    if (decimals !== undefined) {
+      Validator.validateDecimals(decimals);
+    }
+    const balance = await this.readTokenBalance(tokenAddress);
+    return ethers.formatUnits(balance, decimals ?? this.getTokenDecimals(tokenAddress));
+  }
+
+  /**
+   * Decimals used to format a token's amounts: its override if set, else DEFAULT_TOKEN_DECIMALS.
+   * Nothing is read from the token, so tokens whose `decimals()` is wrong or reverts still format.
+   */
+  getTokenDecimals(tokenAddress: string): number {
+    return this.tokenDecimals.get(tokenAddress.toLowerCase()) ?? DEFAULT_TOKEN_DECIMALS;
+  }
+
+  /**
+   * Set the decimals of a token, or clear its override with undefined
+   */
+  setTokenDecimals(tokenAddress: string, decimals: number | undefined): void {
+    Validator.validateAddress(tokenAddress, 'tokenAddress');
+    if (decimals === undefined) {
+      this.tokenDecimals.delete(tokenAddress.toLowerCase());
+      return;
+    }
+    Validator.validateDecimals(decimals);
+    this.tokenDecimals.set(tokenAddress.toLowerCase(), decimals);
+  }
+
+  /**
+   * Get the configured PancakeSwap router and factory addresses
+   */
+  getPancakeAddresses(): { router: string; factory: string } {
+    return { router: this.pancakeRouter, factory: this.pancakeFactory };
+  }
+
+  /**
+   * Wrapped BNB as the configured PancakeSwap router knows it (its WETH()), so forks and testnets
+   * pair against their own. Read once per instance; a failed read is retried on the next call.
+   */
+  async getWbnbAddress(): Promise<string> {
+    this.routerWbnb ??= this.pancakeRouterContract().WETH().catch((error: unknown) => {
+      this.routerWbnb = undefined;
+      throw error;
+    });
+    return await this.routerWbnb!;
+  }
+
+  /**
+   * Get the PancakeSwap token/WBNB pair of a graduated token (zero address if none)
+   */
+  async getPancakePair(tokenAddress: string): Promise<string> {
+    const factory = new ethers.Contract(
+      this.requirePancakeAddress(this.pancakeFactory, 'pancakeFactory'),
+      getPancakeFactoryInterface(),
+      this.provider
+    );
+    return await factory.getPair(tokenAddress, await this.getWbnbAddress());
   }
 
   /**
-   * Get current price of token
+   * Quote a PancakeSwap swap along the token/WBNB path
+   * @param side - 'buy' quotes tokens for `amountIn` BNB, 'sell' quotes BNB for `amountIn` tokens
    */
-  async getCurrentPrice(tokenAddress: string): Promise<string> {
-    const price = await this.priceCalculator.getCurrentPrice(tokenAddress);
-    return ethers.formatEther(price);
+  async quotePancake(tokenAddress: string, amountIn: bigint, side: 'buy' | 'sell'): Promise<bigint> {
+    const router = this.pancakeRouterContract();
+    const wbnb = await this.getWbnbAddress();
+    const path = side === 'buy' ? [wbnb, tokenAddress] : [tokenAddress, wbnb];
+    const amounts: bigint[] = await router.getAmountsOut(amountIn, path);
+    return amounts[amounts.length - 1];
+  }
+
+  private pancakeRouterContract(): ethers.Contract {
+    return new ethers.Contract(
+      this.requirePancakeAddress(this.pancakeRouter, 'pancakeRouter'),
+      getPancakeRouterInterface(),
+      this.provider
+    );
   }
 
   /**
-   * Calculate buy amount with slippage protection
-   * @param bnbAmount - Amount in wei, use ethers.parseEther(amount) to convert from BNB
+   * Detect a per-wallet trade cooldown on a graduated token. Simulates (eth_call) selling the
+   * wallet's balance into the PancakeSwap pair at increasing block-time overrides and returns
+   * the cooldown in seconds, or null when a sell would go through now. The block number override
+   * advances with the time at the block clock's average interval, for tokens that count the
+   * cooldown in blocks. Probe right after a small buy so the cooldown, if any, is active. Needs a
+   * node that accepts eth_call block overrides.
    */
-  async calculateBuyWithSlippage(
-    tokenAddress: string,
-    bnbAmount: bigint,
-    slippagePercent: number = 1
-  ): Promise<{ minTokenAmount: string; estimatedTokenAmount: string; pricePerToken: string }> {
-    const priceInfo = await this.priceCalculator.quoteBuy(tokenAddress, bnbAmount);
-    const minAmount = await this.priceCalculator.estimateBuySlippage(tokenAddress, bnbAmount, slippagePercent);
+  async hasTradeCooldown(tokenAddress: string): Promise<number | null> {
+    const [balance, pair, head] = await Promise.all([
+      this.getTokenBalanceRaw(tokenAddress),
+      this.getPancakePair(tokenAddress),
+      this.currentBlock(),
+    ]);
+    if (balance === 0n) {
+      throw new ValidationError(`Wallet holds no ${tokenAddress}; buy a small amount before probing for a cooldown`);
+    }
+    if (pair === ethers.ZeroAddress) {
+      throw new ValidationError(`${tokenAddress} has no PancakeSwap pair yet`);
+    }
 
-    return {
-      minTokenAmount: ethers.formatUnits(minAmount, 18),
-      estimatedTokenAmount: ethers.formatUnits(priceInfo.tokenAmount, 18),
-      pricePerToken: ethers.formatEther(priceInfo.pricePerToken),
+    const intervalMs = this.getBlockClock().averageIntervalMs();
+    const probe = async (delaySeconds: number): Promise<boolean> => {
+      const block = cooldownProbeBlock(head, intervalMs, delaySeconds);
+      return await this.simulateTransfer(tokenAddress, pair, balance, {
+        number: ethers.toQuantity(block.number),
+        time: ethers.toQuantity(block.timestamp),
+      });
     };
+
+    return await inferCooldown(probe, COOLDOWN_PROBE_OFFSETS);
   }
 
   /**
-   * Calculate sell with slippage protection
-   * @param tokenAmount - Amount in wei, use ethers.parseUnits(amount, 18) to convert
+   * Largest amount of a token the wallet can sell in one transaction right now, for tokens whose
+   * anti-dump rules revert large sells. Simulates (eth_call) the sell's transfer from the wallet, into
+   * the PancakeSwap pair for a graduated token or the TokenManager otherwise, at increasing sizes,
+   * seeded with the cap the token reports through a SELL_LIMIT_ABI getter if any. Returns the
+   * balance when all of it can be sold, and otherwise a size that passed within
+   * SELL_LIMIT_PRECISION_BPS of the balance (0n when no size did, e.g. during a cooldown).
    */
-  async calculateSellWithSlippage(
-    tokenAddress: string,
-    tokenAmount: bigint,
-    slippagePercent: number = 1
-  ): Promise<{ minBnbAmount: string; estimatedBnbAmount: string; pricePerToken: string }> {
-    const priceInfo = await this.priceCalculator.quoteSell(tokenAddress, tokenAmount);
-    const minFunds = await this.priceCalculator.estimateSellSlippage(tokenAddress, tokenAmount, slippagePercent);
+  async maxSellable(tokenAddress: string): Promise<bigint> {
+    Validator.validateAddress(tokenAddress, 'tokenAddress');
+    const [balance, pair, route, hint] = await Promise.all([
+      this.getTokenBalanceRaw(tokenAddress),
+      this.getPancakePair(tokenAddress),
+      this.detectVersion(tokenAddress),
+      readSellLimitHint((data) => this.provider.call({ to: MULTICALL3_ADDRESS, data }), tokenAddress),
+    ]);
+    const destination = pair === ethers.ZeroAddress ? route.manager : pair;
 
-    return {
-      minBnbAmount: ethers.formatEther(minFunds),
-      estimatedBnbAmount: ethers.formatEther(priceInfo.bnbCost),
-      pricePerToken: ethers.formatEther(priceInfo.pricePerToken),
-    };
+    return await inferMaxSellable(
+      (amount) => this.simulateTransfer(tokenAddress, destination, amount),
+      balance,
+      SELL_LIMIT_PROBE_BPS,
+      SELL_LIMIT_PRECISION_BPS,
+      hint
+    );
   }
 
   /**
-   * Get extended token information
+   * Whether a transfer of `amount` from the wallet to `to` succeeds in an eth_call, optionally
+   * under block overrides. Reverts are a no; other errors are thrown.
    */
-  async getTokenInfoEx(tokenAddress: string): Promise<TokenInfoEx> {
-    const info = await this.contract._tokenInfoExs(tokenAddress);
-    return {
-      creator: info.creator,
-      founder: info.founder,
-      reserves: info.reserves,
+  private async simulateTransfer(tokenAddress: string, to: string, amount: bigint, blockOverrides?: Record<string, string>): Promise<boolean> {
+    const call = {
+      from: this.wallet.address,
+      to: tokenAddress,
+      data: getErc20Interface().encodeFunctionData('transfer', [to, amount]),
     };
+    try {
+      const params = blockOverrides ? [call, 'latest', {}, blockOverrides] : [call, 'latest'];
+      const result: string = await this.provider.send('eth_call', params);
+      // Tokens that return nothing succeed on no revert; others must return true
+      return result === '0x' || BigInt(result) !== 0n;
+    } catch (error: any) {
+      if (error?.code === 'CALL_EXCEPTION' || /revert/i.test(error?.message ?? '')) {
+        return false;
+      }
+      throw error;
+    }
   }
 
   /**
-   * Get template information
+   * Get raw token balance of wallet (smallest unit)
    */
-  async getTemplate(templateId: number): Promise<Template> {
-    const template = await this.contract._templates(templateId);
-    return {
-      quote: template.quote,
-      initialLiquidity: template.initialLiquidity,
-      maxRaising: template.maxRaising,
-      totalSupply: template.totalSupply,
-      maxOffers: template.maxOffers,
-      minTradingFee: template.minTradingFee,
-    };
+  async getTokenBalanceRaw(tokenAddress: string): Promise<bigint> {
+    return await this.readTokenBalance(tokenAddress);
   }
 
   /**
-   * Get token address by index
+   * Poll the wallet's raw balance of a token until it exceeds `previous` and return it: confirms a
+   * fire-and-forget buy without relying on its receipt. Rejects with BalanceWaitTimeoutError after `timeoutMs`.
    */
-  async getTokenByIndex(index: number): Promise<string> {
-    return await this.contract._tokens(index);
+  async waitForBalanceIncrease(
+    tokenAddress: string,
+    previous: bigint,
+    timeoutMs: number,
+    intervalMs: number = BALANCE_POLL_INTERVAL
+  ): Promise<bigint> {
+    Validator.validateAddress(tokenAddress, 'tokenAddress');
+    return await waitForBalanceAbove(tokenAddress, previous, () => this.readTokenBalance(tokenAddress), timeoutMs, intervalMs);
   }
 
   /**
-   * Get total token count
+   * What the node supports beyond core JSON-RPC: pending-transaction subscriptions, tracing, archive
+   * depth, feeHistory, batches and Multicall3. Each capability is probed once and cached (`refresh`
+   * probes again); the `capabilities` config overrides any of them. Log it at boot to see which
+   * features will refuse with CapabilityMissingError.
    */
-  async getTokenCount(): Promise<bigint> {
-    return await this.contract._tokenCount();
+  async capabilities(refresh: boolean = false): Promise<NodeCapabilities> {
+    if (refresh) {
+      this.capabilityProbe.reset();
+    }
+    return await this.capabilityProbe.all();
   }
 
   /**
-   * Get template count
+   * Shared clock following the chain head over one WebSocket `block` subscription, for waiting on
+   * block numbers and estimating the block at a time
    */
-  async getTemplateCount(): Promise<bigint> {
-    return await this.contract._templateCount();
+  getBlockClock(): BlockClock {
+    this.blockClock ??= new BlockClock({
+      latest: async () => this.readBlockHeader('latest'),
+      subscribe: (listener) => {
+        const onBlock = (number: number) => {
+          this.readBlockHeader(number).then(listener, () => undefined); // Missed headers are caught up by the next one
+        };
+        this.eventProvider.on('block', onBlock);
+        return () => void this.eventProvider.off('block', onBlock);
+      },
+    });
+    return this.blockClock;
   }
 
-  /**
-   * Get current trading fee rate
-   */
-  async getTradingFeeRate(): Promise<bigint> {
-    return await this.contract._tradingFeeRate();
+  private async readBlockHeader(tag: number | 'latest'): Promise<BlockHeader> {
+    const block = await this.provider.getBlock(tag);
+    if (!block) {
+      throw new ConnectionError(`Provider returned no block ${tag}`);
+    }
+    return { number: block.number, timestamp: block.timestamp, hash: block.hash };
   }
 
   /**
-   * Get launch fee
+   * Gas profiler holding the learned per-method gas limits (inspect, override or snapshot it)
    */
-  async getLaunchFee(): Promise<bigint> {
-    return await this.contract._launchFee();
+  getGasProfiler(): GasProfiler {
+    return this.gasProfiler;
   }
 
   /**
-   * Get fee recipient address
+   * Get the position tracker fed by this instance's trades
    */
-  async getFeeRecipient(): Promise<string> {
-    return await this.contract._feeRecipient();
+  getPositionTracker(): PositionTracker {
+    return this.positionTracker;
   }
 
   /**
-   * Get referral reward rate
+   * Replace the position tracker with one restored from `store`; later trades are persisted to it
    */
-  async getReferralRewardRate(): Promise<bigint> {
-    return await this.contract._referralRewardRate();
+  async openPositionStore(store: PositionStore): Promise<PositionTracker> {
+    this.positionTracker = await PositionTracker.open(store);
+    return this.positionTracker;
   }
 
   /**
-   * Check if trading is halted
+   * Share one tracker between several instances (e.g. wallets of a TraderPool), so a single
+   * writer owns the store and concurrent trades are persisted in order
    */
-  async isTradingHalted(): Promise<boolean> {
-    return await this.contract._tradingHalt();
+  setPositionTracker(tracker: PositionTracker): void {
+    this.positionTracker = tracker;
   }
 
   /**
-   * Get contract owner
+   * Runtime state a new process needs to take over from this one: the local nonce, transactions
+   * whose fill isn't recorded yet, positions, learned gas limits, the sub-account ledger if
+   * configured and, if given, the watchlist.
+   * Stop trading before exporting, so nothing changes after the snapshot is taken.
    */
-  async getOwner(): Promise<string> {
-    return await this.contract.owner();
+  exportState(watchlist?: Watchlist): TraderStateSnapshot {
+    return {
+      version: TRADER_STATE_VERSION,
+      takenAt: Date.now(),
+      wallet: this.wallet.address,
+      chainId: this.chainId,
+      nextNonce: this.nextNonce,
+      pending: [...this.ownPending.values()].map((tx) => ({ ...tx })),
+      positions: this.positionTracker.getPositions(),
+      gasProfile: this.gasProfiler.snapshot(),
+      ...(this.subAccounts ? { subAccounts: this.subAccounts.snapshot() } : {}),
+      ...(watchlist ? { watchlist: watchlist.snapshot() } : {}),
+    };
   }
 
   /**
-   * Get signer address
+   * Create a trader from `config` and restore `snapshot` (an exportState result or its
+   * traderStateToJson text) into it. The trader is returned only once its state is reconciled
+   * against the chain; see restoreState.
    */
-  async getSigner(): Promise<string> {
-    return await this.contract.signer();
+  static async restore(
+    config: FourTradingConfig,
+    snapshot: TraderStateSnapshot | string,
+    options: RestoreOptions = {}
+  ): Promise<{ trader: FourTrading; report: RestoreReport }> {
+    const trader = new FourTrading(config);
+    const report = await trader.restoreState(snapshot, options);
+    return { trader, report };
   }
 
   /**
-   * Get status constants
+   * Load a snapshot from exportState, migrated to the current version, and reconcile it against
+   * the chain before taking new work. Pending transactions that mined meanwhile have their fills
+   * recorded (tagged 'restored'); those still pending are handed back as PendingTrades; any that
+   * vanished are reported. The local nonce is reset to the chain's pending count, and each
+   * position is compared with the wallet's balance. Differences are reported, not corrected.
    */
-  async getStatusConstants(): Promise<{
-    TRADING: bigint;
-    ADDING_LIQUIDITY: bigint;
-    COMPLETED: bigint;
-    HALT: bigint;
-  }> {
-    const [TRADING, ADDING_LIQUIDITY, COMPLETED, HALT] = await Promise.all([
-      this.contract.STATUS_TRADING(),
-      this.contract.STATUS_ADDING_LIQUIDITY(),
-      this.contract.STATUS_COMPLETED(),
-      this.contract.STATUS_HALT(),
-    ]);
-    return { TRADING, ADDING_LIQUIDITY, COMPLETED, HALT };
+  async restoreState(
+    snapshot: TraderStateSnapshot | string,
+    options: RestoreOptions = {}
+  ): Promise<RestoreReport> {
+    const state = typeof snapshot === 'string'
+      ? traderStateFromJson(snapshot, options.migrations)
+      : migrateTraderState(snapshot, options.migrations);
+    if (state.wallet.toLowerCase() !== this.wallet.address.toLowerCase()) {
+      throw new StateSnapshotError(`it was taken for wallet ${state.wallet}, not ${this.wallet.address}`);
+    }
+    if (state.chainId !== this.chainId) {
+      throw new StateSnapshotError(`it was taken on chain ${state.chainId}, not ${this.chainId}`);
+    }
+    if (state.subAccounts && !this.subAccounts) {
+      throw new StateSnapshotError('it holds sub-account balances but no subAccounts ledger is configured');
+    }
+
+    state.positions.forEach((position) => this.positionTracker.restore(position));
+    if (state.subAccounts) {
+      this.subAccounts!.restore(state.subAccounts);
+    }
+    this.gasProfiler.restore(state.gasProfile);
+    options.watchlist?.restore(state.watchlist ?? []);
+
+    const report: RestoreReport = { settled: [], pending: [], discrepancies: [] };
+    const status = await this.nonceStatus();
+    const tags = ['restored'];
+    for (const tx of [...state.pending].sort((a, b) => a.nonce - b.nonce)) {
+      const receipt = await this.provider.getTransactionReceipt(tx.txHash);
+      if (receipt) {
+        const confirmed = receipt.status === 1;
+        const result = confirmed ? await this.completeTrade(tx.txHash, receipt, { tags, subAccount: tx.subAccount }) : undefined;
+        report.settled.push({ tx, status: confirmed ? 'confirmed' : 'reverted', result });
+      } else if (await this.provider.getTransaction(tx.txHash)) {
+        this.ownPending.set(tx.nonce, { ...tx });
+        report.pending.push(this.pendingTrade(tx.txHash, tx.nonce, { tags, subAccount: tx.subAccount }));
+      } else {
+        report.discrepancies.push({ kind: 'dropped', txHash: tx.txHash, nonce: tx.nonce, nonceUsed: tx.nonce < status.latest });
+      }
+    }
+
+    if (state.nextNonce !== null) {
+      if (state.nextNonce !== status.pending) {
+        report.discrepancies.push({ kind: 'nonce', expected: state.nextNonce, actual: status.pending });
+      }
+      this.nextNonce = status.pending;
+    }
+
+    const positions = this.positionTracker.getPositions().filter((position) => position.amount > 0n);
+    const balances = await Promise.all(positions.map((position) => this.readTokenBalance(position.token)));
+    positions.forEach((position, i) => {
+      if (balances[i] !== position.amount) {
+        report.discrepancies.push({ kind: 'balance', token: position.token, expected: position.amount, actual: balances[i] });
+      }
+    });
+    return report;
   }
 
   /**
-   * Calculate buy amount for given funds
+   * Price per token (BNB) at which selling the whole tracked position nets its recorded cost,
+   * after estimated sell gas and the trading fee. The fee fraction comes from a sell quote of
+   * the position, so minimum fees are accounted for at the current size.
    */
-  async calcBuyAmount(tokenInfo: TokenInfo, funds: bigint): Promise<bigint> {
-    return await this.contract.calcBuyAmount(tokenInfo, funds);
+  async breakEvenPrice(tokenAddress: string, gas?: GasOptions): Promise<Price> {
+    const position = this.positionTracker.getPosition(tokenAddress);
+    if (!position || position.amount === 0n) {
+      throw new ValidationError(`No tracked position in ${tokenAddress}`);
+    }
+    if (position.basisUnknown) {
+      throw new ValidationError(`Cost basis of ${tokenAddress} is unknown for part of the position`);
+    }
+
+    const [quote, gasCost] = await Promise.all([
+      this.priceCalculator.quoteSell(tokenAddress, position.amount),
+      this.estimateGasCost(gas, this.gasProfiler.get('sellToken') ?? SELL_GAS_LIMIT_ESTIMATE),
+    ]);
+    const feeRate = new Price(quote.fee, quote.bnbCost + quote.fee);
+
+    return breakEvenPrice(position.knownCost, position.amount, gasCost, feeRate);
   }
 
   /**
-   * Calculate buy cost for given amount
+   * Get the history of this instance's fills
    */
-  async calcBuyCost(tokenInfo: TokenInfo, amount: bigint): Promise<bigint> {
-    return await this.contract.calcBuyCost(tokenInfo, amount);
+  getTradeHistory(): TradeHistory {
+    return this.tradeHistory;
   }
 
   /**
-   * Calculate sell cost for given amount
+   * Export recorded trades as CSV (one row per fill, human-readable units), optionally only one sub-account's
    */
-  async calcSellCost(tokenInfo: TokenInfo, amount: bigint): Promise<bigint> {
-    return await this.contract.calcSellCost(tokenInfo, amount);
+  exportHistoryCsv(subAccount?: string): string {
+    return this.tradeHistory.toCsv((token) => this.getTokenDecimals(token), subAccount);
   }
 
   /**
-   * Calculate trading fee
+   * Sub-account ledger trades are booked to, if configured
    */
-  async calcTradingFee(tokenInfo: TokenInfo, funds: bigint): Promise<bigint> {
-    return await this.contract.calcTradingFee(tokenInfo, funds);
+  getSubAccounts(): SubAccountLedger | undefined {
+    return this.subAccounts;
   }
 
   /**
-   * Calculate last price
+   * Compare the sub-account ledger with the wallet's BNB and the balances of every token it books
    */
-  async calcLastPrice(tokenInfo: TokenInfo): Promise<bigint> {
-    return await this.contract.calcLastPrice(tokenInfo);
+  async reconcileSubAccounts(): Promise<SubAccountReconciliation> {
+    if (!this.subAccounts) {
+      throw new ValidationError('No subAccounts ledger is configured');
+    }
+    const ledger = this.subAccounts;
+    const tokens = ledger.heldTokens();
+    const [bnb, ...balances] = await Promise.all([
+      this.readBNBBalance(),
+      ...tokens.map((token) => this.readTokenBalance(token)),
+    ]);
+    return ledger.reconcile(bnb, new Map(tokens.map((token, index) => [token, balances[index]])));
   }
 
   /**
-   * Get BNB balance of wallet
+   * Rebuild this wallet's position in a token from chain data alone, e.g. after a restart without a
+   * position store: its four.meme fills since the token's creation are replayed and reconciled with
+   * `balanceOf`. The result replaces the tracker's position for the token. For a token created
+   * before the creation lookback, fills from the lookback on are replayed and any balance they
+   * don't explain is tracked with an unknown basis.
    */
-  async getBNBBalance(): Promise<string> {
-    const balance = await this.provider.getBalance(this.wallet.address);
-    return ethers.formatEther(balance);
+  async reconstructPosition(tokenAddress: string): Promise<Position> {
+    const [fromBlock, toBlock] = await Promise.all([
+      this.tokenCreatedAt(tokenAddress).then(
+        (creation) => creation.block,
+        (error) => {
+          if (!(error instanceof TokenCreationNotFoundError)) {
+            throw error;
+          }
+          return error.searchedFrom;
+        }
+      ),
+      this.provider.getBlockNumber(),
+    ]);
+    const job = await this.backfillMany([tokenAddress], fromBlock, { toBlock });
+    for await (const _progress of job) {
+      // Drive the job to completion
+    }
+    const balance = await this.readTokenBalance(tokenAddress, toBlock);
+
+    return this.positionTracker.restore(
+      replayPosition(tokenAddress, this.wallet.address, job.getEvents(tokenAddress), balance)
+    );
   }
 
   /**
-   * Get token balance of wallet
+   * Backfill tokens acquired outside the SDK (airdrops, transfers)
+   * @param costBasis - BNB paid in wei if known; omit to track the tokens with an unknown basis
    */
-  async getTokenBalance(tokenAddress: string): Promise<string> {
-    const tokenABI = ['function balanceOf(address owner) view returns (uint256)'];
-    const tokenContract = new ethers.Contract(tokenAddress, tokenABI, this.provider);
-    const balance = await tokenContract.balanceOf(this.wallet.address);
-    return ethers.formatUnits(balance, 18);
+  markExternalAcquisition(tokenAddress: string, amount: bigint, costBasis?: bigint): void {
+    this.positionTracker.markExternalAcquisition(tokenAddress, amount, costBasis);
   }
 
   /**
@@ -635,7 +4257,10 @@ export class FourTrading {
   onTokenPurchase(listener: TokenPurchaseListener, tokenAddress?: string): string {
     const id = `tokenPurchase_${Date.now()}_${Math.random()}`;
 
-    const contractListener = (token: string, account: string, price: bigint, amount: bigint, cost: bigint, fee: bigint, offers: bigint, funds: bigint) => {
+    const contractListener = (
+      token: string, account: string, price: bigint, amount: bigint, cost: bigint, fee: bigint, offers: bigint, funds: bigint,
+      payload: ContractEventPayload
+    ) => {
       if (tokenAddress && token.toLowerCase() !== tokenAddress.toLowerCase()) {
         return; // Filter by token address if provided
       }
@@ -650,7 +4275,7 @@ export class FourTrading {
         offers,
         funds,
       };
-      listener(event);
+      listener(event, payload.log.blockNumber);
     };
 
     this.tokenPurchaseListeners.set(id, { listener, contractListener });
@@ -665,7 +4290,10 @@ export class FourTrading {
   onTokenSale(listener: TokenSaleListener, tokenAddress?: string): string {
     const id = `tokenSale_${Date.now()}_${Math.random()}`;
 
-    const contractListener = (token: string, account: string, price: bigint, amount: bigint, cost: bigint, fee: bigint, offers: bigint, funds: bigint) => {
+    const contractListener = (
+      token: string, account: string, price: bigint, amount: bigint, cost: bigint, fee: bigint, offers: bigint, funds: bigint,
+      payload: ContractEventPayload
+    ) => {
       if (tokenAddress && token.toLowerCase() !== tokenAddress.toLowerCase()) {
         return;
       }
@@ -680,7 +4308,7 @@ export class FourTrading {
         offers,
         funds,
       };
-      listener(event);
+      listener(event, payload.log.blockNumber);
     };
 
     this.tokenSaleListeners.set(id, { listener, contractListener });
@@ -711,6 +4339,134 @@ export class FourTrading {
     return id;
   }
 
+  /**
+   * Subscribe to new blocks on the WebSocket provider
+   */
+  onBlock(listener: BlockListener): string {
+    const id = `block_${Date.now()}_${Math.random()}`;
+
+    this.blockListeners.set(id, listener);
+    this.eventProvider.on('block', listener);
+
+    return id;
+  }
+
+  /**
+   * Subscribe to pending (mempool) buys of a token through the four.meme contract.
+   * Needs a node that streams pending transaction hashes over the WebSocket; each is fetched to decode it.
+   */
+  onPendingBuy(tokenAddress: string, listener: PendingBuyListener): string {
+    this.capabilityProbe.requireKnown('onPendingBuy', 'pendingSubscription');
+    const id = `pendingBuy_${Date.now()}_${Math.random()}`;
+
+    const providerListener = (txHash: string) => {
+      this.eventProvider.getTransaction(txHash).then((tx) => {
+        const bid = tx && competitorBidFrom(tx, this.contractAddress, tokenAddress);
+        if (bid && this.pendingBuyListeners.has(id)) {
+          listener(bid);
+        }
+      }, () => undefined); // Already mined or dropped
+    };
+
+    this.pendingBuyListeners.set(id, providerListener);
+    this.eventProvider.on('pending', providerListener);
+
+    return id;
+  }
+
+  /**
+   * Subscribe to OHLCV candles for a token, built from its trade events and bucketed by the
+   * timestamp of the block each trade was mined in. A candle is emitted CANDLE_CLOSE_GRACE_MS after
+   * its interval ends, so trades mined in it can still arrive; intervals without trades produce a
+   * flat candle at the previous close once the first trade has been seen.
+   */
+  onCandle(tokenAddress: string, intervalMs: number, listener: CandleListener): string {
+    const id = `candle_${Date.now()}_${Math.random()}`;
+    const aggregator = new CandleAggregator(tokenAddress, intervalMs);
+
+    const emit = (candles: Candle[]) => {
+      for (const candle of candles) {
+        listener(candle);
+      }
+    };
+    // Block reads are chained so trades, and the closes between them, reach the aggregator in order
+    let queue: Promise<void> = Promise.resolve();
+    let lastBlock: ChainHead | undefined;
+    const onTrade = (event: TokenPurchaseEvent | TokenSaleEvent, blockNumber: number) => {
+      queue = queue.then(async () => {
+        if (lastBlock?.number !== blockNumber) {
+          lastBlock = { number: blockNumber, timestamp: await this.blockTimestamp(blockNumber) };
+        }
+        emit(aggregator.addTrade(event, lastBlock.timestamp * 1000));
+      }).catch((error) => {
+        console.error(`Dropped a ${event.token} trade from its candles: ${error?.message ?? error}`);
+      });
+    };
+
+    const subscription: { tradeIds: string[]; timer?: NodeJS.Timeout } = {
+      tradeIds: [this.onTokenPurchase(onTrade, tokenAddress), this.onTokenSale(onTrade, tokenAddress)],
+    };
+    const schedule = () => {
+      subscription.timer = setTimeout(() => {
+        const until = Date.now() - CANDLE_CLOSE_GRACE_MS;
+        queue = queue.then(() => emit(aggregator.closeUntil(until))).catch((error) => {
+          console.error(`Candle listener failed: ${error?.message ?? error}`);
+        });
+        schedule();
+      }, intervalMs - ((Date.now() - CANDLE_CLOSE_GRACE_MS) % intervalMs));
+    };
+    schedule();
+
+    this.candleSubscriptions.set(id, subscription);
+    return id;
+  }
+
+  /**
+   * Subscribe to a token's price, updated by every trade
+   */
+  onPrice(tokenAddress: string, listener: PriceListener): string {
+    const id = `price_${Date.now()}_${Math.random()}`;
+    const update = (side: 'buy' | 'sell') => (event: TokenPurchaseEvent | TokenSaleEvent) =>
+      listener({ token: event.token, price: event.price, side, timestamp: Date.now() });
+
+    this.priceSubscriptions.set(id, [
+      this.onTokenPurchase(update('buy'), tokenAddress),
+      this.onTokenSale(update('sell'), tokenAddress),
+    ]);
+    return id;
+  }
+
+  /**
+   * Subscribe to a token's price, emitting only when it moved at least `minChangeBps` since the
+   * last emission (the first trade always emits)
+   */
+  onPriceThrottled(tokenAddress: string, minChangeBps: number, listener: PriceListener): string {
+    const filter = new PriceChangeFilter(minChangeBps);
+    return this.onPrice(tokenAddress, (update) => {
+      if (filter.accept(update.price)) {
+        listener(update);
+      }
+    });
+  }
+
+  /**
+   * Subscribe to quotes where the secondary quoter disagrees with the primary beyond the threshold
+   */
+  onQuoteDivergence(listener: QuoteDivergenceListener): string {
+    const id = `quoteDivergence_${Date.now()}_${Math.random()}`;
+    this.quoteDivergenceListeners.set(id, listener);
+    return id;
+  }
+
+  /**
+   * Subscribe to reads that had to wait for a lagging RPC replica to reach the required block
+   */
+  onReplicaLag(listener: ReplicaLagListener): string {
+    const id = `replicaLag_${Date.now()}_${Math.random()}`;
+    this.replicaLagListeners.set(id, listener);
+    return id;
+  }
+
   /**
    * Unsubscribe from an event
    */
@@ -746,6 +4502,39 @@ export class FourTrading {
       this.liquidityAddedListeners.delete(listenerId);
       return;
     }
+
+    // Check block listeners
+    if (this.blockListeners.has(listenerId)) {
+      this.eventProvider.off('block', this.blockListeners.get(listenerId)!);
+      this.blockListeners.delete(listenerId);
+      return;
+    }
+
+    // Check pending buy listeners
+    if (this.pendingBuyListeners.has(listenerId)) {
+      this.eventProvider.off('pending', this.pendingBuyListeners.get(listenerId)!);
+      this.pendingBuyListeners.delete(listenerId);
+      return;
+    }
+
+    // Check candle subscriptions
+    if (this.candleSubscriptions.has(listenerId)) {
+      const { tradeIds, timer } = this.candleSubscriptions.get(listenerId)!;
+      clearTimeout(timer);
+      tradeIds.forEach((id) => this.off(id));
+      this.candleSubscriptions.delete(listenerId);
+      return;
+    }
+
+    // Check price subscriptions
+    if (this.priceSubscriptions.has(listenerId)) {
+      this.priceSubscriptions.get(listenerId)!.forEach((id) => this.off(id));
+      this.priceSubscriptions.delete(listenerId);
+      return;
+    }
+
+    this.quoteDivergenceListeners.delete(listenerId);
+    this.replicaLagListeners.delete(listenerId);
   }
 
   /**
@@ -753,12 +4542,161 @@ export class FourTrading {
    */
   removeAllListeners(): void {
     this.eventContract.removeAllListeners();
+    for (const listener of this.blockListeners.values()) {
+      this.eventProvider.off('block', listener);
+    }
+    this.blockListeners.clear();
+    for (const listener of this.pendingBuyListeners.values()) {
+      this.eventProvider.off('pending', listener);
+    }
+    this.pendingBuyListeners.clear();
+    for (const { timer } of this.candleSubscriptions.values()) {
+      clearTimeout(timer);
+    }
+    this.candleSubscriptions.clear();
+    this.priceSubscriptions.clear();
+    this.quoteDivergenceListeners.clear();
+    this.replicaLagListeners.clear();
     this.tokenCreateListeners.clear();
     this.tokenPurchaseListeners.clear();
     this.tokenSaleListeners.clear();
     this.liquidityAddedListeners.clear();
   }
 
+  /**
+   * Token creations from where the stream named `key` in `store` last stopped, then live, each
+   * delivered at least once and in chain order; see CursorStream. Resolves once the backfill is
+   * delivered. Without a stored cursor it starts at `options.fromBlock`, or live only.
+   * The listener is awaited before the cursor moves past an event, so a crash mid-delivery
+   * replays that event; dedupe on `event.eventId`.
+   */
+  async subscribeTokenCreationsFromCursor(
+    store: CursorStore,
+    key: string,
+    listener: (event: CursorEvent<TokenCreateEvent>) => void | Promise<void>,
+    options: CursorStreamOptions = {}
+  ): Promise<CursorStream<TokenCreateEvent>> {
+    const filter = { address: this.contractAddress, topics: [getFourTradingInterface().getEvent('TokenCreate')!.topicHash] };
+    const stream = new CursorStream<TokenCreateEvent>(
+      {
+        getBlockNumber: () => this.provider.getBlockNumber(),
+        getLogs: (fromBlock, toBlock) => this.provider.getLogs({ ...filter, fromBlock, toBlock }),
+        watch: (onLog) => {
+          this.eventProvider.on(filter, onLog);
+          return () => {
+            this.eventProvider.off(filter, onLog);
+          };
+        },
+      },
+      store,
+      key,
+      parseTokenCreateLog,
+      listener,
+      options
+    );
+    await stream.start();
+    return stream;
+  }
+
+  /**
+   * Backfill trade events for many tokens at once. Each block range is fetched with a single
+   * getLogs query for the contract and demuxed by token, spaced by `requestIntervalMs`.
+   * Iterate the returned job to run it; `job.cursor` resumes a cancelled backfill. Only V2 events
+   * are decoded, so V1 tokens are refused with ValidationError rather than backfilled empty.
+   */
+  async backfillMany(
+    tokens: string[],
+    fromBlock: number,
+    options: Omit<BackfillOptions, 'toBlock'> & { toBlock?: number } = {}
+  ): Promise<BackfillJob> {
+    const routes = await Promise.all(tokens.map((token) => this.detectVersion(token)));
+    const v1 = tokens.filter((_, index) => routes[index].version === 1);
+    if (v1.length > 0) {
+      throw new ValidationError(`${v1.join(', ')} trade on the V1 TokenManager, whose events backfill doesn't decode`);
+    }
+    const toBlock = options.toBlock ?? await this.provider.getBlockNumber();
+    const iface = getFourTradingInterface();
+    const topics = [[iface.getEvent('TokenPurchase')!.topicHash, iface.getEvent('TokenSale')!.topicHash]];
+
+    const fetchLogs = (from: number, to: number) => this.provider.getLogs({
+      address: this.contractAddress,
+      topics,
+      fromBlock: from,
+      toBlock: to,
+    });
+
+    return new BackfillJob(tokens, fromBlock, fetchLogs, { ...options, toBlock });
+  }
+
+  /**
+   * Every buy and sell `account` made between `fromBlock` and `toBlock` (default: the latest block),
+   * oldest first, for backfilling a wallet into external accounting. Ranges are paged like
+   * backfillMany and shrunk automatically when the node limits getLogs. Trades are read from the
+   * configured (V2) manager's events; trades of V1 tokens are not included.
+   */
+  async *walletTradeHistory(
+    account: string,
+    fromBlock: number,
+    toBlock?: number,
+    options: WalletHistoryOptions = {}
+  ): AsyncGenerator<BackfillEvent> {
+    Validator.validateAddress(account, 'account');
+    const iface = getFourTradingInterface();
+    const topics = [[iface.getEvent('TokenPurchase')!.topicHash, iface.getEvent('TokenSale')!.topicHash]];
+    const fetchLogs = (from: number, to: number) => this.provider.getLogs({
+      address: this.contractAddress,
+      topics,
+      fromBlock: from,
+      toBlock: to,
+    });
+
+    yield* walletTradeHistory(account, fromBlock, toBlock ?? await this.provider.getBlockNumber(), fetchLogs, options);
+  }
+
+  /**
+   * Buy and sell BNB volume of a token over the last `blocks` blocks (including the latest),
+   * fetched with the same paged log queries as backfillMany
+   */
+  async volume(tokenAddress: string, blocks: number): Promise<VolumeStats> {
+    const { fromBlock, toBlock, events } = await this.recentTrades(tokenAddress, blocks);
+    return summarizeVolume(tokenAddress, fromBlock, toBlock, events);
+  }
+
+  /**
+   * Slippage in basis points that keeps a buy of `bnbAmount` filling given the token's recent
+   * trading: the output it loses when a high percentile (default: the 95th) of recent per-block net
+   * buy volume lands ahead of it, replayed on the token's curve. Learns from the last `blocks` blocks.
+   */
+  async suggestSlippage(
+    tokenAddress: string,
+    bnbAmount: bigint,
+    blocks: number = SLIPPAGE_SUGGESTION_BLOCKS,
+    options: SlippageSuggestionOptions = {}
+  ): Promise<number> {
+    const [info, { events }] = await Promise.all([
+      this.priceCalculator.getTokenInfo(tokenAddress),
+      this.recentTrades(tokenAddress, blocks),
+    ]);
+    return suggestSlippage(curveParams(tokenAddress, info), bnbAmount, events, options).bps;
+  }
+
+  /**
+   * A token's trade events over the last `blocks` blocks, including the latest
+   */
+  private async recentTrades(tokenAddress: string, blocks: number): Promise<{ fromBlock: number; toBlock: number; events: BackfillEvent[] }> {
+    if (!Number.isInteger(blocks) || blocks <= 0) {
+      throw new ValidationError(`Block window must be a positive integer, got ${blocks}`);
+    }
+
+    const toBlock = await this.provider.getBlockNumber();
+    const fromBlock = Math.max(0, toBlock - blocks + 1);
+    const job = await this.backfillMany([tokenAddress], fromBlock, { toBlock });
+    for await (const _progress of job) {
+      // Drive the job to completion
+    }
+    return { fromBlock, toBlock, events: job.getEvents(tokenAddress) };
+  }
+
   /**
    * Query historical events
    */
@@ -0,0 +1,120 @@
+/**
+ * Funding trace
+ * Finds where a wallet's first BNB came from. An EOA's balance can only drop through its own
+ * transactions, so "balance > 0 or nonce > 0" turns true at its first funding and never turns false
+ * again; binary search over historical state finds that block in O(log lookback) reads, and the
+ * block's transactions name the sender. Needs a node that serves historical state (an archive node)
+ * over the lookback.
+ */
+
+import { BlockTransaction } from './creationBlock';
+import { ValidationError } from './errors';
+import { isStateUnavailableError } from './utils';
+
+export interface FundingTraceSource {
+  getBalance(address: string, block: number): Promise<bigint>;
+  getTransactionCount(address: string, block: number): Promise<number>;
+  getBlockTransactions(block: number): Promise<BlockTransaction[]>;
+}
+
+export type FundingHopStatus =
+  | 'found' // `funder` sent the first BNB in `txHash`
+  | 'internal' // Funded in `block`, but not by a plain transfer (e.g. a contract's internal transfer)
+  | 'beforeLookback' // Already funded at the start of the lookback
+  | 'unfunded' // Never funded up to the block traced from
+  | 'stateUnavailable'; // The node no longer serves the state this hop needs (not an archive node)
+
+export interface FundingHop {
+  address: string;
+  status: FundingHopStatus;
+  funder: string | null; // Set when 'found'
+  block: number | null; // Block of the first funding; set when 'found' or 'internal'
+  txHash: string | null;
+}
+
+export interface FundingTraceOptions {
+  lookbackBlocks: number; // Blocks before `atBlock` searched at each hop
+  hops: number; // 1 for the funder, 2 to also trace the funder's funder
+  known?: (address: string) => FundingHop | undefined; // Previously traced hops, used instead of tracing again
+}
+
+async function funded(source: FundingTraceSource, address: string, block: number): Promise<boolean> {
+  return (await source.getBalance(address, block)) > 0n || (await source.getTransactionCount(address, block)) > 0;
+}
+
+/**
+ * First funding of `address` at or before `atBlock`, searched back `lookbackBlocks` blocks;
+ * 'stateUnavailable' when the node has pruned a block the search reads
+ */
+export async function traceFirstFunding(
+  address: string,
+  atBlock: number,
+  source: FundingTraceSource,
+  lookbackBlocks: number
+): Promise<FundingHop> {
+  const hop = (status: FundingHopStatus, block: number | null = null, funder: string | null = null, txHash: string | null = null): FundingHop =>
+    ({ address, status, funder, block, txHash });
+
+  try {
+    if (!(await funded(source, address, atBlock))) {
+      return hop('unfunded');
+    }
+    let low = Math.max(0, atBlock - lookbackBlocks); // Unfunded here, once checked
+    if (await funded(source, address, low)) {
+      return hop('beforeLookback');
+    }
+    let high = atBlock; // Funded here
+    while (high - low > 1) {
+      const mid = Math.floor((low + high) / 2);
+      if (await funded(source, address, mid)) {
+        high = mid;
+      } else {
+        low = mid;
+      }
+    }
+
+    const key = address.toLowerCase();
+    const transfer = (await source.getBlockTransactions(high)).find(
+      (tx) => tx.to?.toLowerCase() === key && tx.value > 0n
+    );
+    return transfer ? hop('found', high, transfer.from, transfer.hash) : hop('internal', high);
+  } catch (error) {
+    if (!isStateUnavailableError(error)) {
+      throw error;
+    }
+    return hop('stateUnavailable'); // Pruned history ends the trace rather than failing it
+  }
+}
+
+/**
+ * Follow first fundings from `address` for up to `hops` hops. Each hop searches back from the
+ * block the previous one was funded in; the trace stops at the first hop without a funder.
+ * A first funding never changes, so `known` hops (from a CreatorRegistry) skip the search.
+ */
+export async function traceFunding(
+  address: string,
+  atBlock: number,
+  source: FundingTraceSource,
+  options: FundingTraceOptions
+): Promise<FundingHop[]> {
+  if (!Number.isInteger(options.hops) || options.hops < 1) {
+    throw new ValidationError(`Funding hops must be a positive integer, got ${options.hops}`);
+  }
+  if (!Number.isInteger(options.lookbackBlocks) || options.lookbackBlocks < 1) {
+    throw new ValidationError(`Funding lookback must be a positive integer, got ${options.lookbackBlocks}`);
+  }
+
+  const hops: FundingHop[] = [];
+  let current = address;
+  let block = atBlock;
+  for (let i = 0; i < options.hops; i++) {
+    const hop = options.known?.(current) ?? (await traceFirstFunding(current, block, source, options.lookbackBlocks));
+    hops.push(hop);
+    if (hop.status !== 'found') {
+      break;
+    }
+    current = hop.funder!;
+    block = hop.block!;
+  }
+  return hops;
+}
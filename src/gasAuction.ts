@@ -0,0 +1,235 @@
+/**
+ * Gas auction for contested launch blocks
+ * While our buy is unmined, every competing buy of the same token seen in the mempool at or above our
+ * gas price is answered with a same-nonce replacement priced `increment` above it, up to `maxGasPrice`
+ * and `maxReplacements`. The auction ends as soon as any attempt of the chain mines.
+ */
+
+import { getFourTradingInterface } from './abi';
+import { REPLACEMENT_MIN_BUMP_BPS, SLIPPAGE_DENOMINATOR } from './constants';
+import { ValidationError } from './errors';
+import { TransactionResult } from './types';
+
+export interface CompetitorBid {
+  txHash: string;
+  from: string;
+  gasPrice: bigint; // Effective price: gasPrice, or the priority fee of an EIP-1559 transaction
+}
+
+/**
+ * The fields of a pending transaction needed to recognise a competing buy
+ */
+export interface MempoolTransaction {
+  hash: string;
+  from: string;
+  to: string | null;
+  data: string;
+  type: number | null;
+  gasPrice: bigint | null;
+  maxPriorityFeePerGas: bigint | null;
+}
+
+export interface GasAuctionConfig {
+  increment: bigint; // Wei per gas to bid above the highest competitor
+  maxGasPrice: bigint; // Never bid above this
+  maxReplacements: number; // Speed-ups allowed after the initial transaction
+  minBumpBps?: number; // Smallest raise nodes accept for a replacement (default: REPLACEMENT_MIN_BUMP_BPS)
+  signal?: AbortSignal; // Stops bidding; the chain stays pending and is reported as such
+}
+
+export type GasAuctionEvent =
+  | { type: 'bid'; txHash: string; gasPrice: bigint; timestamp: number; competitor?: string }
+  | { type: 'competitor'; txHash: string; from: string; gasPrice: bigint; timestamp: number }
+  | { type: 'skipped'; competitor: string; reason: 'cap' | 'maxReplacements' | 'replaceFailed'; timestamp: number; error?: string }
+  | { type: 'mined'; txHash: string; timestamp: number };
+
+export interface GasAuctionResult {
+  outcome: 'mined' | 'cancelled';
+  minedTxHash: string | null;
+  attempts: string[]; // Every hash of the chain, initial first
+  finalGasPrice: bigint; // Price of the last attempt sent
+  replacements: number;
+  history: GasAuctionEvent[]; // In order of occurrence
+  trade?: TransactionResult; // Set by FourTrading.gasAuction once the mined attempt is recorded
+}
+
+/**
+ * What the auction needs from the chain: the mempool stream, block ticks, same-nonce replacement
+ * and a check for which attempt mined
+ */
+export interface GasAuctionDriver {
+  watchCompetitors(listener: (bid: CompetitorBid) => void): () => void;
+  watchBlocks(listener: () => void): () => void;
+  replace(gasPrice: bigint): Promise<string>; // Resends our transaction at `gasPrice`, returns the new hash
+  minedAttempt(txHashes: string[]): Promise<string | null>; // Hash of the attempt that mined, if any
+}
+
+/**
+ * `tx` as a competing bid when it buys `tokenAddress` through the four.meme contract at `contractAddress`
+ */
+export function competitorBidFrom(tx: MempoolTransaction, contractAddress: string, tokenAddress: string): CompetitorBid | null {
+  if (tx.to?.toLowerCase() !== contractAddress.toLowerCase()) {
+    return null;
+  }
+  let call;
+  try {
+    call = getFourTradingInterface().parseTransaction({ data: tx.data });
+  } catch {
+    return null;
+  }
+  if (!call || (call.name !== 'buyTokenAMAP' && call.name !== 'buyToken')) {
+    return null;
+  }
+  if (String(call.args[0]).toLowerCase() !== tokenAddress.toLowerCase()) {
+    return null;
+  }
+  const gasPrice = tx.type === 2 ? tx.maxPriorityFeePerGas : tx.gasPrice;
+  return gasPrice === null ? null : { txHash: tx.hash, from: tx.from, gasPrice };
+}
+
+export class GasAuction {
+  private history: GasAuctionEvent[] = [];
+  private attempts: string[];
+  private gasPrice: bigint;
+  private highest = 0n;
+  private highestFrom = '';
+  private replacing = false;
+  private rebid = false;
+  private done = false;
+  private minBumpBps: bigint;
+
+  constructor(
+    txHash: string,
+    gasPrice: bigint,
+    private driver: GasAuctionDriver,
+    private config: GasAuctionConfig,
+    private now: () => number = Date.now
+  ) {
+    if (config.increment <= 0n) {
+      throw new ValidationError('Gas auction increment must be positive');
+    }
+    if (!Number.isInteger(config.maxReplacements) || config.maxReplacements < 0) {
+      throw new ValidationError(`maxReplacements must be a non-negative integer, got ${config.maxReplacements}`);
+    }
+    this.minBumpBps = BigInt(config.minBumpBps ?? REPLACEMENT_MIN_BUMP_BPS);
+    this.attempts = [txHash];
+    this.gasPrice = gasPrice;
+    this.history.push({ type: 'bid', txHash, gasPrice, timestamp: this.now() });
+  }
+
+  /**
+   * Bid until an attempt mines (or `signal` fires) and return the full bidding history
+   */
+  run(): Promise<GasAuctionResult> {
+    return new Promise((resolve) => {
+      const stops: Array<() => void> = [];
+      const finish = (outcome: GasAuctionResult['outcome'], minedTxHash: string | null) => {
+        if (this.done) {
+          return;
+        }
+        this.done = true;
+        stops.forEach((stop) => stop());
+        this.config.signal?.removeEventListener('abort', onAbort);
+        if (minedTxHash) {
+          this.history.push({ type: 'mined', txHash: minedTxHash, timestamp: this.now() });
+        }
+        resolve({
+          outcome,
+          minedTxHash,
+          attempts: [...this.attempts],
+          finalGasPrice: this.gasPrice,
+          replacements: this.attempts.length - 1,
+          history: this.history,
+        });
+      };
+      const checkMined = async () => {
+        if (this.done) {
+          return;
+        }
+        try {
+          const mined = await this.driver.minedAttempt([...this.attempts]);
+          if (mined) {
+            finish('mined', mined);
+          }
+        } catch {
+          // Transient read failure; the next block checks again
+        }
+      };
+      const onAbort = () => finish('cancelled', null);
+
+      if (this.config.signal?.aborted) {
+        onAbort();
+        return;
+      }
+      this.config.signal?.addEventListener('abort', onAbort, { once: true });
+      stops.push(this.driver.watchBlocks(() => void checkMined()));
+      stops.push(this.driver.watchCompetitors((bid) => void this.onCompetitor(bid, checkMined)));
+      void checkMined();
+    });
+  }
+
+  private async onCompetitor(bid: CompetitorBid, checkMined: () => Promise<void>): Promise<void> {
+    if (this.done || this.attempts.some((hash) => hash.toLowerCase() === bid.txHash.toLowerCase())) {
+      return;
+    }
+    this.history.push({ type: 'competitor', txHash: bid.txHash, from: bid.from, gasPrice: bid.gasPrice, timestamp: this.now() });
+    if (bid.gasPrice > this.highest) {
+      this.highest = bid.gasPrice;
+      this.highestFrom = bid.txHash;
+    }
+    if (this.replacing) {
+      this.rebid = true; // Answered with the highest competitor once the in-flight replacement is sent
+      return;
+    }
+
+    this.replacing = true;
+    try {
+      do {
+        this.rebid = false;
+        await this.outbid(checkMined);
+      } while (this.rebid && !this.done);
+    } finally {
+      this.replacing = false;
+    }
+  }
+
+  private async outbid(checkMined: () => Promise<void>): Promise<void> {
+    if (this.done || this.highest < this.gasPrice) {
+      return;
+    }
+    const competitor = this.highestFrom;
+    const skip = (reason: 'cap' | 'maxReplacements' | 'replaceFailed', error?: string) =>
+      this.history.push({ type: 'skipped', competitor, reason, timestamp: this.now(), ...(error ? { error } : {}) });
+
+    if (this.attempts.length - 1 >= this.config.maxReplacements) {
+      skip('maxReplacements');
+      return;
+    }
+    const minimum = this.gasPrice + (this.gasPrice * this.minBumpBps + SLIPPAGE_DENOMINATOR - 1n) / SLIPPAGE_DENOMINATOR;
+    let target = this.highest + this.config.increment;
+    if (target < minimum) {
+      target = minimum;
+    }
+    if (target > this.config.maxGasPrice) {
+      // Still worth bidding the cap when it beats the competitor and is a valid replacement
+      if (this.config.maxGasPrice <= this.highest || this.config.maxGasPrice < minimum) {
+        skip('cap');
+        return;
+      }
+      target = this.config.maxGasPrice;
+    }
+
+    try {
+      const txHash = await this.driver.replace(target);
+      if (this.done) {
+        return;
+      }
+      this.attempts.push(txHash);
+      this.gasPrice = target;
+      this.history.push({ type: 'bid', txHash, gasPrice: target, timestamp: this.now(), competitor });
+    } catch (error: any) {
+      skip('replaceFailed', error?.message ?? String(error));
+      await checkMined(); // A replacement is rejected once the nonce is used, i.e. an attempt has mined
+    }
+  }
+}
@@ -0,0 +1,132 @@
+/**
+ * Gas limit discovery
+ * Learns each contract method's gas limit from eth_estimateGas on first use and from real receipts,
+ * replacing fixed fallback limits that go stale when the contract is upgraded
+ */
+
+import { SLIPPAGE_DENOMINATOR } from './constants';
+
+export type GasProfiledMethod = 'buyTokenAMAP' | 'buyToken' | 'sellToken' | 'approve' | 'createToken';
+
+export interface GasProfileEntry {
+  method: GasProfiledMethod;
+  limit: bigint; // Gas limit to use: observed gas times the safety multiplier, or the override
+  observed: bigint; // Gas the limit was derived from (estimate or receipt gasUsed)
+  source: 'estimate' | 'receipt' | 'override';
+  updatedAt: number; // ms
+}
+
+export interface GasProfilerOptions {
+  multiplierBps?: number; // Safety multiplier on observed gas (default: GAS_PROFILE_MULTIPLIER_BPS)
+  refreshMarginBps?: number; // Raise the limit when gasUsed comes within this margin of it (default: GAS_PROFILE_REFRESH_MARGIN_BPS)
+}
+
+export const GAS_PROFILE_MULTIPLIER_BPS = 13000; // 1.3x
+export const GAS_PROFILE_REFRESH_MARGIN_BPS = 1000; // Within 10% of the limit
+
+export class GasProfiler {
+  private entries: Map<GasProfiledMethod, GasProfileEntry> = new Map();
+  private inFlight: Map<GasProfiledMethod, Promise<bigint>> = new Map();
+  private multiplierBps: bigint;
+  private refreshMarginBps: bigint;
+
+  /**
+   * @param initial - Entries from a previous `snapshot()`, to carry learned limits across restarts
+   */
+  constructor(options: GasProfilerOptions = {}, initial: GasProfileEntry[] = []) {
+    this.multiplierBps = BigInt(options.multiplierBps ?? GAS_PROFILE_MULTIPLIER_BPS);
+    this.refreshMarginBps = BigInt(options.refreshMarginBps ?? GAS_PROFILE_REFRESH_MARGIN_BPS);
+    for (const entry of initial) {
+      this.entries.set(entry.method, { ...entry });
+    }
+  }
+
+  /**
+   * Known limit for a method, if it has been profiled or overridden
+   */
+  get(method: GasProfiledMethod): bigint | undefined {
+    return this.entries.get(method)?.limit;
+  }
+
+  /**
+   * Limit for a method, running `estimate` on first use. Concurrent first uses share one estimate.
+   */
+  async limitFor(method: GasProfiledMethod, estimate: () => Promise<bigint>): Promise<bigint> {
+    const known = this.entries.get(method);
+    if (known) {
+      return known.limit;
+    }
+
+    let running = this.inFlight.get(method);
+    if (!running) {
+      running = estimate()
+        .then((observed) => this.store(method, observed, 'estimate').limit)
+        .finally(() => this.inFlight.delete(method));
+      this.inFlight.set(method, running);
+    }
+    return await running;
+  }
+
+  /**
+   * Record a real transaction's gasUsed. When it comes within the refresh margin of the stored
+   * limit, the limit is re-derived from it. Overrides are never changed. Returns whether it refreshed.
+   */
+  observe(method: GasProfiledMethod, gasUsed: bigint): boolean {
+    const entry = this.entries.get(method);
+    if (entry?.source === 'override') {
+      return false;
+    }
+    if (entry && gasUsed * SLIPPAGE_DENOMINATOR < entry.limit * (SLIPPAGE_DENOMINATOR - this.refreshMarginBps)) {
+      return false;
+    }
+    this.store(method, gasUsed, 'receipt');
+    return true;
+  }
+
+  /**
+   * Pin a method's limit; it is used as is and never refreshed
+   */
+  override(method: GasProfiledMethod, limit: bigint): void {
+    this.entries.set(method, { method, limit, observed: limit, source: 'override', updatedAt: Date.now() });
+  }
+
+  /**
+   * Forget a method's profile (including an override); the next use re-estimates
+   */
+  reset(method?: GasProfiledMethod): void {
+    if (method) {
+      this.entries.delete(method);
+    } else {
+      this.entries.clear();
+    }
+  }
+
+  /**
+   * Current profile, for debugging or persisting
+   */
+  snapshot(): GasProfileEntry[] {
+    return [...this.entries.values()].map((entry) => ({ ...entry }));
+  }
+
+  /**
+   * Replace the profile with entries from a previous `snapshot()`
+   */
+  restore(entries: GasProfileEntry[]): void {
+    this.entries.clear();
+    for (const entry of entries) {
+      this.entries.set(entry.method, { ...entry });
+    }
+  }
+
+  private store(method: GasProfiledMethod, observed: bigint, source: 'estimate' | 'receipt'): GasProfileEntry {
+    const entry = {
+      method,
+      limit: (observed * this.multiplierBps) / SLIPPAGE_DENOMINATOR,
+      observed,
+      source,
+      updatedAt: Date.now(),
+    };
+    this.entries.set(method, entry);
+    return entry;
+  }
+}
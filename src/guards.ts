@@ -0,0 +1,35 @@
+/**
+ * Pre-trade guards
+ * Pure checks that decide whether a trade is worth sending
+ */
+
+import { PriceImpactTooHighError, UneconomicalTradeError } from './errors';
+import { SLIPPAGE_DENOMINATOR } from './constants';
+import { checkedBps, mulDiv } from './math';
+
+/**
+ * Whether sell proceeds exceed the gas cost by at least `marginBps` basis points
+ * e.g. a margin of 5000 requires proceeds > 1.5x gas cost
+ */
+export function isEconomicalSell(proceeds: bigint, gasCost: bigint, marginBps: number): boolean {
+  const required = mulDiv(gasCost, SLIPPAGE_DENOMINATOR + checkedBps(Math.floor(marginBps), 'marginBps'), SLIPPAGE_DENOMINATOR);
+  return proceeds > required;
+}
+
+/**
+ * Throw UneconomicalTradeError when a sell would not cover its own gas with the margin
+ */
+export function assertEconomicalSell(proceeds: bigint, gasCost: bigint, marginBps: number): void {
+  if (!isEconomicalSell(proceeds, gasCost, marginBps)) {
+    throw new UneconomicalTradeError(proceeds, gasCost);
+  }
+}
+
+/**
+ * Throw PriceImpactTooHighError when a trade would move the price more than `maxImpactBps`
+ */
+export function assertPriceImpact(tokenAddress: string, side: 'buy' | 'sell', impactBps: number, maxImpactBps: number): void {
+  if (impactBps > maxImpactBps) {
+    throw new PriceImpactTooHighError(tokenAddress, side, impactBps, maxImpactBps);
+  }
+}
@@ -0,0 +1,109 @@
+/**
+ * Holder concentration
+ * Rebuilds a token's balances from its Transfer logs; four.meme tokens are young enough that a
+ * scan from creation is cheap, and no indexer is needed
+ */
+
+import { ethers } from 'ethers';
+import { getErc20Interface } from './abi';
+import { BackfillLog } from './backfill';
+import { bpsOf } from './math';
+
+export interface HolderBalance {
+  address: string;
+  balance: bigint;
+  shareBps: number; // Share of total supply in basis points
+}
+
+export interface HolderStats {
+  token: string;
+  holderCount: number; // Addresses with a non-zero balance, excluding the curve, the PancakeSwap pair and burn addresses
+  topHolders: HolderBalance[]; // Largest first
+  topConcentrationBps: number; // Combined share of `topHolders`
+  totalSupply: bigint;
+  fromBlock: number; // Inclusive scan window
+  toBlock: number;
+}
+
+function shareBps(balance: bigint, totalSupply: bigint): number {
+  return totalSupply > 0n ? bpsOf(balance, totalSupply, 'holder share') : 0;
+}
+
+/**
+ * Add Transfer logs to running balances (lowercase address to balance), so a scan can be extended
+ * from where it stopped. Logs that aren't Transfers are skipped.
+ */
+export function applyTransfers(balances: Map<string, bigint>, logs: BackfillLog[]): void {
+  const iface = getErc20Interface();
+  for (const log of logs) {
+    let parsed;
+    try {
+      parsed = iface.parseLog({ topics: [...log.topics], data: log.data });
+    } catch {
+      continue;
+    }
+    if (parsed?.name !== 'Transfer') {
+      continue;
+    }
+
+    const from = String(parsed.args.from).toLowerCase();
+    const to = String(parsed.args.to).toLowerCase();
+    const value: bigint = parsed.args.value;
+    balances.set(from, (balances.get(from) ?? 0n) - value);
+    balances.set(to, (balances.get(to) ?? 0n) + value);
+  }
+}
+
+/**
+ * Holder statistics from balances built by applyTransfers. `excluded` addresses (the TokenManager
+ * holding unsold curve supply, the PancakeSwap pair holding the liquidity, burn addresses) are
+ * left out of the count and the top holders.
+ */
+export function summarizeHolders(
+  token: string,
+  balances: ReadonlyMap<string, bigint>,
+  totalSupply: bigint,
+  topN: number,
+  excluded: string[],
+  fromBlock: number,
+  toBlock: number
+): HolderStats {
+  const skip = new Set([ethers.ZeroAddress, ...excluded].map((address) => address.toLowerCase()));
+  const holders = [...balances]
+    .filter(([address, balance]) => balance > 0n && !skip.has(address))
+    .sort(([, a], [, b]) => (a === b ? 0 : a > b ? -1 : 1));
+
+  const topHolders = holders.slice(0, topN).map(([address, balance]) => ({
+    address: ethers.getAddress(address),
+    balance,
+    shareBps: shareBps(balance, totalSupply),
+  }));
+  const topBalance = topHolders.reduce((sum, holder) => sum + holder.balance, 0n);
+
+  return {
+    token,
+    holderCount: holders.length,
+    topHolders,
+    topConcentrationBps: shareBps(topBalance, totalSupply),
+    totalSupply,
+    fromBlock,
+    toBlock,
+  };
+}
+
+/**
+ * Fold Transfer logs into holder statistics in one pass; see summarizeHolders for `excluded`
+ */
+export function computeHolderStats(
+  token: string,
+  logs: BackfillLog[],
+  totalSupply: bigint,
+  topN: number,
+  excluded: string[],
+  fromBlock: number,
+  toBlock: number
+): HolderStats {
+  const balances: Map<string, bigint> = new Map();
+  applyTransfers(balances, logs);
+  return summarizeHolders(token, balances, totalSupply, topN, excluded, fromBlock, toBlock);
+}
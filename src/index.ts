@@ -4,12 +4,23 @@ export {
   FourTradingConfig,
   BuyParams,
   SellParams,
+  CurveFractionBuyOptions,
+  ExactBuyOptions,
+  EmergencySellOptions,
+  EmergencySellResult,
+  RawCallOptions,
+  RawSendOptions,
+  ExternalSigner,
   CreateTokenParams,
   GasOptions,
+  GasOperation,
+  SecondaryQuoterConfig,
   TokenCreateListener,
   TokenPurchaseListener,
   TokenSaleListener,
   LiquidityAddedListener,
+  BlockListener,
+  PendingBuyListener,
 } from './fourTrading';
 
 export {
@@ -18,15 +29,63 @@ export {
   Template,
   TokenStatus,
   TransactionResult,
+  CurveFractionFill,
+  ExtraCost,
+  TradeReadScope,
+  PendingTrade,
+  NonceStatus,
+  BatchApproveResult,
+  LiquidationResult,
+  Cancellable,
+  TradeTags,
   TokenCreateEvent,
   TokenPurchaseEvent,
   TokenSaleEvent,
   LiquidityAddedEvent,
 } from './types';
 
-export { FOUR_TRADING_ABI } from './abi';
+export {
+  FOUR_TRADING_ABI,
+  PANCAKE_ROUTER_ABI,
+  PANCAKE_FACTORY_ABI,
+  ERC20_ABI,
+  MULTICALL3_ABI,
+  TOKEN_MANAGER_V1_ABI,
+  TOKEN_MANAGER_HELPER_ABI,
+  WBNB_ABI,
+  CHAINLINK_AGGREGATOR_ABI,
+  SELL_LIMIT_ABI,
+  PERMIT_ABI,
+  getFourTradingInterface,
+  getErc20Interface,
+  getPancakeRouterInterface,
+  getPancakeFactoryInterface,
+  getMulticall3Interface,
+  getTokenManagerV1Interface,
+  getTokenManagerHelperInterface,
+  getWbnbInterface,
+  getChainlinkAggregatorInterface,
+  getSellLimitInterface,
+  getPermitInterface,
+} from './abi';
+
+export {
+  PriceCalculator,
+  PriceInfo,
+  PartialFill,
+  QuoteOptions,
+  BuyCostBreakdown,
+  BuyLimits,
+  bnbUntilGraduation,
+  detectPartialFill,
+  buyCostBreakdown,
+  buyPriceInfo,
+  zeroBuyReason,
+  buyLimits,
+  checkBuyLimits,
+} from './priceCalculator';
 
-export { PriceCalculator, PriceInfo } from './priceCalculator';
+export { Price } from './price';
 
 // Error classes
 export {
@@ -40,8 +99,104 @@ export {
   GasConfigurationError,
   SlippageExceededError,
   FeeExceedsAmountError,
+  TokenLaunchValidationError,
+  MathTruncationError,
+  BnbReserveError,
+  UneconomicalTradeError,
+  UnverifiedTokenError,
+  TokenTooOldError,
+  TokenCreationNotFoundError,
+  TokenTooYoungError,
+  HolderConcentrationError,
+  SubAccountBalanceError,
+  ChainStalledError,
+  CancelledError,
+  ReplicaLaggingError,
+  RpcDivergenceError,
+  UnsupportedVersionError,
+  TokenLaunchTimeoutError,
+  SymbolCollisionError,
+  SymbolCollisionUncheckedError,
+  WireFormatError,
+  CreatorReputationError,
+  QuoteReturnedZeroError,
+  AmountOutOfRangeError,
+  TokenInfoLayoutError,
+  PriceFeedError,
+  StateSnapshotError,
+  TransactionDroppedError,
+  CurveFractionError,
+  GaslessApprovalUnsupportedError,
+  CapabilityMissingError,
+  TargetPriceUnreachableError,
+  PriceImpactTooHighError,
+  BalanceWaitTimeoutError,
+  ApprovalWaitTimeoutError,
+  PositionPersistError,
 } from './errors';
 
+// Checked math
+export { isStrictMath, setStrictMath, toNumberChecked, mulDiv, checkedBps, bpsOf } from './math';
+
+// Pre-trade guards
+export { isEconomicalSell, assertEconomicalSell, assertPriceImpact } from './guards';
+
+// Slippage suggestions
+export { SlippageSuggestion, SlippageSuggestionOptions, netBuyVolumePerBlock, suggestSlippage } from './slippageSuggestion';
+
+// Trade previews
+export { PreviewCheck, PreviewTransaction, PreviewValue, TradePreview, previewTransaction } from './tradePreview';
+
+// Curve-fraction buy sizing
+export { ResolvedCurveFraction, resolveCurveFraction, curveFractionAmount, realizedCurveFraction } from './curveFraction';
+
+// Gasless approvals
+export {
+  GaslessApproval,
+  PERMIT_TYPES,
+  PermitDomain,
+  PermitMessage,
+  PermitRequest,
+  permitRequest,
+  recoverPermitSigner,
+  resolvePermitDomain,
+} from './permit';
+
+// Approval coordination
+export { ApprovalClaim, ApprovalLock, InFlightApproval, sharedApprovalLock } from './approvalLock';
+
+// Runtime state handover
+export {
+  TraderStateSnapshot,
+  TraderStateMigration,
+  TRADER_STATE_MIGRATIONS,
+  RestoreOptions,
+  StateDiscrepancy,
+  SettledPending,
+  RestoreReport,
+  migrateTraderState,
+  traderStateToJson,
+  traderStateFromJson,
+} from './traderState';
+
+// Emergency exits
+export {
+  OwnPendingTx,
+  PreemptionAction,
+  PreemptionPolicy,
+  PreemptionDecision,
+  PreemptionOutcome,
+  minReplacementPrice,
+  replacementFees,
+  planPreemption,
+} from './preemption';
+
+// USD conversion
+export { BnbUsdPrice, bnbUsdPriceFromRound, bnbToUsd } from './usdPrice';
+
+// Sweeping
+export { SweepAction, SweepItem, SweepOptions, SweepReport, SweepSkipReason, sweepCandidates, sweepRecovered, sweepReportToJson } from './sweep';
+
 // Logger
 export { Logger, LogLevel, LoggerConfig } from './logger';
 
@@ -54,9 +209,345 @@ export { Validator } from './validator';
 // Performance monitoring
 export { PerformanceMonitor, PerformanceMetrics } from './performance';
 
+// Reference quote comparison
+export {
+  QuoteDivergence,
+  QuoteDivergenceListener,
+  Quoter,
+  QuoteComparisonOptions,
+  QuoteComparator,
+  quoteDivergenceBps,
+} from './quoteDivergence';
+
+// Bonding curve
+export { BuySimulation, CurveParams, CurveReserves, LaunchTemplate, curveParams, launchTemplate, buySizeForAvgPrice, curveBuyAmount, curveSellCost, calcBuyAtReserves, simulateBuy } from './curve';
+
+// Sell chunk planning
+export {
+  ChunkPlan,
+  ChunkPlanOptions,
+  SellCostFn,
+  CHUNK_PLAN_MAX_CHUNKS,
+  localSellCost,
+  planSellChunks,
+  applySell,
+  sellImpactBps,
+  priceImpactBps,
+} from './chunking';
+
+// Trade cooldowns
+export { CooldownProbe, inferCooldown } from './cooldown';
+
+// Anti-dump sell limits
+export { SellSizeProbe, inferMaxSellable, readSellLimitHint } from './sellLimit';
+
+// Multicall
+export { MulticallCall, MulticallResult, MulticallExecutor, BuyQuoteRead, aggregate3, readAllowances, readTokenInfos, readBuyQuotes } from './multicall';
+export { TOKEN_INFO_FIELDS, decodeTokenInfo } from './tokenInfo';
+
+// Read-your-writes consistency
+export { ReplicaLagEvent, ReplicaLagListener, ReadAtLeastOptions, readAtLeast } from './readConsistency';
+
+// Extra trade costs
+export {
+  ReplacementAttempt,
+  totalExtraCost,
+  attributeReplacementCosts,
+  allocateExtraCost,
+  firstReceipt,
+} from './extraCosts';
+
+// Gas auction
+export {
+  GasAuction,
+  GasAuctionConfig,
+  GasAuctionDriver,
+  GasAuctionEvent,
+  GasAuctionResult,
+  CompetitorBid,
+  MempoolTransaction,
+  competitorBidFrom,
+} from './gasAuction';
+
+// Gas profiling
+export {
+  GasProfiler,
+  GasProfiledMethod,
+  GasProfileEntry,
+  GasProfilerOptions,
+  GAS_PROFILE_MULTIPLIER_BPS,
+  GAS_PROFILE_REFRESH_MARGIN_BPS,
+} from './gasProfiler';
+
+// Confirmation strategies
+export { ConfirmationStrategy, DEFAULT_CONFIRMATION, InclusionWatch, waitForFinalized, waitForConfirmations, waitForConfirmationsOrDrop, waitForBalanceAbove } from './confirmation';
+
+// Cancellation
+export { throwIfCancelled, waitUnlessCancelled } from './cancellation';
+
+// Chain health
+export { ChainHead, secondsSinceBlock, assertChainProgressing, headProvesProgress, watchForStall } from './chainHealth';
+
+// Node capabilities
+export {
+  CAPABILITY_PROBES,
+  CapabilityProbe,
+  CapabilityProbeSource,
+  isCapabilityAbsent,
+  NodeCapabilities,
+  NodeCapability,
+} from './capabilities';
+
+// Block-time-aware scheduling
+export {
+  BlockClock,
+  BlockClockOptions,
+  BlockHeader,
+  BlockHeaderListener,
+  BlockSource,
+  averageBlockInterval,
+  estimateBlockAt,
+} from './blocks';
+
+// Access lists
+export {
+  AccessListRequest,
+  AccessListRpc,
+  AccessListResult,
+  createAccessList,
+  accessListSavings,
+} from './accessList';
+
+// Token launch parameters
+export { TokenLaunchParams, TokenLaunchViolation, memeTokenTemplate, validateTokenLaunch } from './tokenLaunch';
+
+// Token creation lookup
+export { TokenCreation, TokenCreateLogSource, findTokenCreationBlock } from './tokenAge';
+
+// Launch sniping
+export { SnipeMode, LaunchWatchSource, LaunchWaitOptions, isTokenLive, waitForLaunch } from './snipe';
+
+// Active token discovery
+export {
+  TokenDirectory,
+  TokenDirectoryCursor,
+  TokenDirectoryOptions,
+  TokenDirectoryPage,
+  TokenDirectorySource,
+  TokenSummary,
+  TokenCreationRecord,
+  DirectoryLog,
+} from './tokenDirectory';
+
+// Symbol collision detection
+export {
+  TokenMatch,
+  CollisionReason,
+  HOMOGLYPHS,
+  normalizeTokenText,
+  editDistance,
+  matchCollision,
+  findCollisions,
+} from './symbolCollisions';
+
+// Creation block analysis
+export {
+  BlockTransaction,
+  CreationBlockSource,
+  CreationBlockBuy,
+  CreationBlockReport,
+  analyzeCreationBlock,
+} from './creationBlock';
+
+// Creator reputation
+export {
+  FundingTraceSource,
+  FundingHop,
+  FundingHopStatus,
+  FundingTraceOptions,
+  traceFirstFunding,
+  traceFunding,
+} from './fundingTrace';
+export {
+  CreatorRegistry,
+  CreatorRegistryOptions,
+  CreatorRegistryState,
+  CreatorRegistryStore,
+  MemoryCreatorRegistryStore,
+  JsonFileCreatorRegistryStore,
+  CreatorTokenRecord,
+  CreatorTokenOutcome,
+  CreatorOutcomes,
+  CreatorReport,
+} from './creatorRegistry';
+
+// Holder concentration
+export { HolderStats, HolderBalance, computeHolderStats } from './holders';
+
+// Token verification
+export {
+  TokenVerificationStatus,
+  VerificationReport,
+  classifyToken,
+  isRegisteredTokenInfo,
+} from './tokenVerification';
+
+// Candles
+export { Candle, CandleAggregator, CandleListener } from './candles';
+
+// Price stream
+export { PriceUpdate, PriceListener, PriceChangeFilter } from './priceStream';
+
+// Indicators
+export {
+  PriceSample,
+  IndicatorInput,
+  IndicatorListener,
+  Series,
+  Sma,
+  Ema,
+  Rsi,
+  RateOfChange,
+  Crossover,
+  IndicatorStream,
+} from './indicators';
+
+// Raw contract calls
+export { RawFunction, parseRawFunction, encodeRawCall, decodeRawResult } from './rawCall';
+
+// Unsigned calldata
+export { encodeBuyCalldata, encodeBuyExactCalldata, encodeSellCalldata } from './calldata';
+
+// TokenManager versions
+export {
+  FourmemeVersion,
+  TokenManagerRoute,
+  routeFromHelperInfo,
+  encodeVersionedBuy,
+  encodeVersionedBuyExact,
+  encodeVersionedSell,
+} from './version';
+
+// Multi-wallet pool
+export { TraderPool } from './traderPool';
+
+// Event backfill
+export {
+  BackfillJob,
+  BackfillOptions,
+  BackfillProgress,
+  BackfillEvent,
+  BackfillSink,
+  BackfillLog,
+  BackfillLogSource,
+  parseTradeLog,
+} from './backfill';
+export { WalletHistoryOptions, walletTradeHistory } from './walletHistory';
+
+// Resumable event streams
+export {
+  CursorStream,
+  CursorStreamOptions,
+  CursorEventSource,
+  CursorEvent,
+  CursorLog,
+  CursorState,
+  CursorStore,
+  EventPosition,
+  MemoryCursorStore,
+  JsonFileCursorStore,
+  eventId,
+  comparePositions,
+  parseTokenCreateLog,
+} from './eventCursor';
+export { VolumeStats, summarizeVolume } from './volume';
+
+// Sandwich detection
+export { BlockTrade, SandwichReport, detectSandwich } from './sandwich';
+
+// Connection configuration
+export {
+  ConnectionConfig,
+  ConnectionTlsOptions,
+  createFetchRequest,
+  createJsonRpcProvider,
+  createWebSocketProvider,
+  createProxyAgent,
+} from './connection';
+
 // WebSocket manager
 export { WebSocketManager, WebSocketManagerConfig } from './websocketManager';
 
+// Strategy framework
+export {
+  Strategy,
+  StrategyRunner,
+  StrategyRunnerConfig,
+  StrategyDriver,
+  StrategyEvent,
+  TradeExecutor,
+  TradeIntent,
+  TradeEvent,
+  BlockTick,
+  OwnFill,
+  ExpiredIntent,
+  ExpiryStage,
+  RiskLimits,
+  ApprovalConfig,
+  StrategySignal,
+  SignalSource,
+  LiveDriver,
+  LiveExecutor,
+  BacktestDriver,
+  BacktestExecutor,
+} from './strategy';
+export { MomentumTrigger, MomentumTriggerConfig, MomentumSignal, MomentumListener } from './momentum';
+export {
+  ApprovalGate,
+  ApprovalRequest,
+  ApprovalDecision,
+  ApprovalOutcome,
+  ApprovalRecord,
+  AutoApproveGate,
+  DenyAllGate,
+  ChannelApprovalGate,
+  requestApproval,
+} from './approvalGate';
+
+// Position tracking
+export { PositionTracker, Position, RealizedPnl, breakEvenPrice } from './positionTracker';
+export { replayPosition } from './positionRecovery';
+export {
+  PositionStore,
+  PositionTrade,
+  PositionUpdate,
+  MemoryPositionStore,
+  JsonFilePositionStore,
+} from './positionStore';
+
+// Virtual sub-accounts
+export {
+  SubAccountLedger,
+  SubAccountBalance,
+  SubAccountReconciliation,
+  SubAccountReservation,
+  SubAccountLedgerState,
+  TokenDrift,
+} from './subAccounts';
+
+// Trade history
+export { TradeHistory, TradeRecord } from './tradeHistory';
+export {
+  PerformanceBucket,
+  performanceByHour,
+  performanceByWeekday,
+  performanceToJson,
+  realizedSlippageBps,
+} from './tradeStats';
+
+// Watchlist
+export { Watchlist, WatchlistConfig, WatchedToken, WatchlistEvent, WatchlistListener } from './watchlist';
+
 // Constants
 export * from './constants';
 
@@ -70,6 +561,14 @@ export {
   parseBNB,
   parseTokenAmount,
   calculatePriceChange,
+  slippagePercentToBps,
+  applySlippage,
+  applyGasMultiplier,
+  isMethodUnsupportedError,
+  isLogRangeError,
+  isStateUnavailableError,
+  resolveMinAmount,
+  pickTags,
   isValidAddress,
   normalizeAddress,
   isFourMemeTransaction,
@@ -0,0 +1,208 @@
+/**
+ * Streaming technical indicators over closed candles or raw price samples
+ * Indicators advance one step per input and return null until they have enough samples.
+ * Steps are counted in inputs, not time: feed closed candles for a time-regular series
+ * (CandleAggregator fills quiet intervals with flat candles at the previous close), or raw
+ * samples to have each trade count as one step however far apart they are.
+ */
+
+import { ethers } from 'ethers';
+import { Candle } from './candles';
+
+export interface PriceSample {
+  price: bigint; // BNB per token scaled by 1e18, as emitted by trade events
+  timestamp: number; // ms since epoch
+}
+
+export type IndicatorInput = Candle | PriceSample;
+
+/**
+ * A value derived step by step from the price stream; null while warming up
+ */
+export interface Series<T> {
+  update(input: IndicatorInput): T | null;
+}
+
+export type IndicatorListener<T> = (value: T, timestamp: number) => void;
+
+function priceOf(input: IndicatorInput): number {
+  return Number(ethers.formatUnits('close' in input ? input.close : input.price, 18));
+}
+
+function timeOf(input: IndicatorInput): number {
+  return 'closeTime' in input ? input.closeTime : input.timestamp;
+}
+
+function assertPeriod(period: number): void {
+  if (!Number.isInteger(period) || period <= 0) {
+    throw new RangeError('Indicator period must be a positive integer');
+  }
+}
+
+/**
+ * Simple moving average of the last `period` prices
+ */
+export class Sma implements Series<number> {
+  private window: number[] = [];
+  private sum = 0;
+
+  constructor(private period: number) {
+    assertPeriod(period);
+  }
+
+  update(input: IndicatorInput): number | null {
+    const price = priceOf(input);
+    this.window.push(price);
+    this.sum += price;
+    if (this.window.length > this.period) {
+      this.sum -= this.window.shift()!;
+    }
+    return this.window.length === this.period ? this.sum / this.period : null;
+  }
+}
+
+/**
+ * Exponential moving average with smoothing 2 / (period + 1), seeded with the SMA of the first `period` prices
+ */
+export class Ema implements Series<number> {
+  private seed: Sma;
+  private value: number | null = null;
+  private alpha: number;
+
+  constructor(period: number) {
+    this.seed = new Sma(period);
+    this.alpha = 2 / (period + 1);
+  }
+
+  update(input: IndicatorInput): number | null {
+    if (this.value === null) {
+      this.value = this.seed.update(input);
+      return this.value;
+    }
+    this.value += this.alpha * (priceOf(input) - this.value);
+    return this.value;
+  }
+}
+
+/**
+ * Wilder's relative strength index (0-100). Needs `period` price changes, so `period + 1` prices.
+ * A window with no losses reads 100, and one with no movement at all reads 50.
+ */
+export class Rsi implements Series<number> {
+  private previous: number | null = null;
+  private changes = 0;
+  private avgGain = 0;
+  private avgLoss = 0;
+
+  constructor(private period: number) {
+    assertPeriod(period);
+  }
+
+  update(input: IndicatorInput): number | null {
+    const price = priceOf(input);
+    const previous = this.previous;
+    this.previous = price;
+    if (previous === null) {
+      return null;
+    }
+
+    const change = price - previous;
+    const gain = change > 0 ? change : 0;
+    const loss = change < 0 ? -change : 0;
+    this.changes++;
+
+    if (this.changes <= this.period) {
+      // Warm-up: plain averages of the first `period` changes
+      this.avgGain += gain / this.period;
+      this.avgLoss += loss / this.period;
+      if (this.changes < this.period) {
+        return null;
+      }
+    } else {
+      this.avgGain = (this.avgGain * (this.period - 1) + gain) / this.period;
+      this.avgLoss = (this.avgLoss * (this.period - 1) + loss) / this.period;
+    }
+
+    if (this.avgLoss === 0) {
+      return this.avgGain === 0 ? 50 : 100;
+    }
+    return 100 - 100 / (1 + this.avgGain / this.avgLoss);
+  }
+}
+
+/**
+ * Percent change against the price `period` steps ago; null while the older price is missing or zero
+ */
+export class RateOfChange implements Series<number> {
+  private window: number[] = [];
+
+  constructor(private period: number) {
+    assertPeriod(period);
+  }
+
+  update(input: IndicatorInput): number | null {
+    this.window.push(priceOf(input));
+    if (this.window.length <= this.period) {
+      return null;
+    }
+    const past = this.window.shift()!;
+    return past === 0 ? null : ((this.window[this.period - 1] - past) / past) * 100;
+  }
+}
+
+/**
+ * True on the step where `fast` crosses `slow` in `direction`, false otherwise; null until both
+ * series have warmed up. The first step with both values only establishes the side, so it is false.
+ * Each series must belong to this crossover alone, since it advances them itself.
+ */
+export class Crossover implements Series<boolean> {
+  private previous: { fast: number; slow: number } | null = null;
+
+  constructor(private fast: Series<number>, private slow: Series<number>, private direction: 'above' | 'below') {}
+
+  update(input: IndicatorInput): boolean | null {
+    const fast = this.fast.update(input);
+    const slow = this.slow.update(input);
+    if (fast === null || slow === null) {
+      return null;
+    }
+
+    const previous = this.previous;
+    this.previous = { fast, slow };
+    if (!previous) {
+      return false;
+    }
+    return this.direction === 'above'
+      ? previous.fast <= previous.slow && fast > slow
+      : previous.fast >= previous.slow && fast < slow;
+  }
+}
+
+/**
+ * Feeds a series and notifies subscribers of every warmed-up value, e.g. wire a candle
+ * subscription into `push` and subscribe to a Crossover for crossing events
+ */
+export class IndicatorStream<T> {
+  private listeners: Map<string, IndicatorListener<T>> = new Map();
+
+  constructor(private series: Series<T>) {}
+
+  push(input: IndicatorInput): T | null {
+    const value = this.series.update(input);
+    if (value !== null) {
+      const timestamp = timeOf(input);
+      this.listeners.forEach((listener) => listener(value, timestamp));
+    }
+    return value;
+  }
+
+  subscribe(listener: IndicatorListener<T>): string {
+    const id = `indicator_${Date.now()}_${Math.random()}`;
+    this.listeners.set(id, listener);
+    return id;
+  }
+
+  unsubscribe(id: string): boolean {
+    return this.listeners.delete(id);
+  }
+}
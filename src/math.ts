@@ -0,0 +1,79 @@
+/**
+ * Checked numeric conversions
+ * Every bigint -> number conversion and basis-point calculation goes through these helpers.
+ * By default they behave like the plain casts they replace. In strict mode (FOUR_STRICT_MATH=1,
+ * or setStrictMath(true)), they throw MathTruncationError instead of losing precision.
+ *
+ * Exempt, because they can't lose precision or are floats by contract:
+ * - Price.toNumber and indicator inputs (src/indicators.ts), which convert decimal strings to floats
+ * - Wire u64 fields, range-checked by the codec with its own WireFormatError
+ * - TokenManager versions, validated as 1 or 2 before the cast
+ * - JSON-RPC quantities parsed from hex strings (node responses, block numbers)
+ */
+
+import { MathTruncationError, ValidationError } from './errors';
+import { MAX_UINT256, SLIPPAGE_DENOMINATOR } from './constants';
+
+// Float noise tolerated when a number meant to be whole (e.g. 0.29 * 100) is converted to bps
+const INTEGER_EPSILON = 1e-9;
+
+let strict = typeof process !== 'undefined' && process.env?.FOUR_STRICT_MATH === '1';
+
+export function isStrictMath(): boolean {
+  return strict;
+}
+
+export function setStrictMath(enabled: boolean): void {
+  strict = enabled;
+}
+
+/**
+ * Convert to a JS number; strict mode refuses values beyond Number.MAX_SAFE_INTEGER
+ */
+export function toNumberChecked(value: bigint, what: string = 'value'): number {
+  if (strict && (value > BigInt(Number.MAX_SAFE_INTEGER) || value < BigInt(Number.MIN_SAFE_INTEGER))) {
+    throw new MathTruncationError(`${what} ${value} does not fit a JS number exactly`);
+  }
+  return Number(value);
+}
+
+/**
+ * a * b / denominator, truncated toward zero like bigint division. Strict mode requires operands
+ * and result to fit a uint256, as they would on-chain.
+ */
+export function mulDiv(a: bigint, b: bigint, denominator: bigint): bigint {
+  if (denominator === 0n) {
+    throw new MathTruncationError('mulDiv by zero');
+  }
+  const result = (a * b) / denominator;
+  if (strict) {
+    for (const [name, value] of [['a', a], ['b', b], ['denominator', denominator], ['result', result]] as const) {
+      if (value < -MAX_UINT256 || value > MAX_UINT256) {
+        throw new MathTruncationError(`mulDiv ${name} ${value} exceeds uint256`);
+      }
+    }
+  }
+  return result;
+}
+
+/**
+ * Whole basis points from a number. Rounds to the nearest bp; strict mode refuses negative,
+ * non-finite or fractional values (beyond float noise) instead of rounding them away.
+ */
+export function checkedBps(bps: number, what: string = 'bps'): bigint {
+  if (!Number.isFinite(bps)) {
+    throw new ValidationError(`${what} ${bps} is not a finite number`);
+  }
+  const rounded = Math.round(bps);
+  if (strict && (bps < 0 || Math.abs(bps - rounded) > INTEGER_EPSILON)) {
+    throw new MathTruncationError(`${what} ${bps} is not a whole number of basis points`);
+  }
+  return BigInt(rounded);
+}
+
+/**
+ * `part` as basis points of `whole`, truncated toward zero
+ */
+export function bpsOf(part: bigint, whole: bigint, what: string = 'bps'): number {
+  return toNumberChecked(mulDiv(part, SLIPPAGE_DENOMINATOR, whole), what);
+}
@@ -0,0 +1,213 @@
+/**
+ * Momentum entries on accelerating buyer counts
+ * Counts, per watched token, the buyers seen buying it for the first time within a rolling window,
+ * and signals an entry when that count reaches a threshold while the price is still within a
+ * multiple of the launch price.
+ *
+ * Buyer memory is bounded: each token remembers at most `maxTrackedBuyers` addresses, least recently
+ * active first out. Counts are exact until a token has more distinct buyers than that; past it, a
+ * forgotten buyer who buys again counts as new, so the rate can only be overstated, and signals say
+ * when that may have happened.
+ */
+
+import { MOMENTUM_MAX_TRACKED_BUYERS, MOMENTUM_MAX_TOKENS, MOMENTUM_WINDOW_SECONDS, SLIPPAGE_DENOMINATOR } from './constants';
+import { ValidationError } from './errors';
+import type { FourTrading } from './fourTrading';
+import { bpsOf, checkedBps } from './math';
+import { SignalSource, StrategyEvent, StrategySignal } from './strategy';
+import { TokenPurchaseEvent } from './types';
+
+export interface MomentumTriggerConfig {
+  minNewBuyers: number; // First-time buyers within one window needed to signal
+  maxPriceMultiple: number; // Only signal while price < launch price times this (e.g. 3 for 3x)
+  windowSeconds?: number; // Rolling window (default: MOMENTUM_WINDOW_SECONDS)
+  cooldownSeconds?: number; // Minimum time between two signals for the same token (default: the window)
+  maxTrackedBuyers?: number; // Buyer addresses remembered per token (default: MOMENTUM_MAX_TRACKED_BUYERS)
+  maxTokens?: number; // Tokens watched at once; the oldest is dropped beyond it (default: MOMENTUM_MAX_TOKENS)
+  watchNewTokens?: boolean; // Watch every token from the creation stream (default: true)
+}
+
+export interface MomentumSignal extends StrategySignal {
+  type: 'momentum';
+  newBuyers: number; // First-time buyers within the latest window
+  previousNewBuyers: number; // First-time buyers within the window before it
+  windowSeconds: number;
+  trackedBuyers: number; // Distinct buyers remembered for the token
+  buyersSaturated: boolean; // maxTrackedBuyers was reached, so `newBuyers` may include returning buyers
+  price: bigint; // Latest trade price, BNB per token scaled by 1e18
+  launchPrice: bigint;
+  priceMultipleBps: number; // price / launchPrice in basis points
+  blockNumber?: number;
+}
+
+export type MomentumListener = (signal: MomentumSignal) => void;
+
+interface MomentumState {
+  token: string;
+  launchPrice: bigint | null; // First trade price unless given to watch()
+  buyers: Map<string, true>; // Insertion order is recency order
+  saturated: boolean;
+  firstBuys: number[]; // Timestamps of first-time buys within the last two windows
+  lastSignalAt: number | null;
+}
+
+export class MomentumTrigger implements SignalSource {
+  private tokens: Map<string, MomentumState> = new Map();
+  private listeners: MomentumListener[] = [];
+  private clock: number | null = null; // Latest block tick (Unix seconds)
+  private windowSeconds: number;
+  private cooldownSeconds: number;
+  private maxTrackedBuyers: number;
+  private maxTokens: number;
+  private maxPriceMultipleBps: bigint;
+
+  constructor(private config: MomentumTriggerConfig, private now: () => number = () => Math.floor(Date.now() / 1000)) {
+    if (!Number.isInteger(config.minNewBuyers) || config.minNewBuyers <= 0) {
+      throw new ValidationError(`minNewBuyers must be a positive integer, got ${config.minNewBuyers}`);
+    }
+    if (!(config.maxPriceMultiple > 0)) {
+      throw new ValidationError(`maxPriceMultiple must be positive, got ${config.maxPriceMultiple}`);
+    }
+    if (config.windowSeconds !== undefined && !(config.windowSeconds > 0)) {
+      throw new ValidationError(`windowSeconds must be positive, got ${config.windowSeconds}`);
+    }
+    this.windowSeconds = config.windowSeconds ?? MOMENTUM_WINDOW_SECONDS;
+    this.cooldownSeconds = config.cooldownSeconds ?? this.windowSeconds;
+    this.maxTrackedBuyers = config.maxTrackedBuyers ?? MOMENTUM_MAX_TRACKED_BUYERS;
+    this.maxTokens = config.maxTokens ?? MOMENTUM_MAX_TOKENS;
+    this.maxPriceMultipleBps = checkedBps(config.maxPriceMultiple * 10000, 'maxPriceMultiple');
+  }
+
+  /**
+   * Start watching a token; `launchPrice` defaults to the price of the first trade seen
+   */
+  watch(token: string, launchPrice?: bigint): void {
+    const key = token.toLowerCase();
+    if (this.tokens.has(key)) {
+      return;
+    }
+    if (this.tokens.size >= this.maxTokens) {
+      this.tokens.delete(this.tokens.keys().next().value!);
+    }
+    this.tokens.set(key, {
+      token,
+      launchPrice: launchPrice ?? null,
+      buyers: new Map(),
+      saturated: false,
+      firstBuys: [],
+      lastSignalAt: null,
+    });
+  }
+
+  unwatch(token: string): void {
+    this.tokens.delete(token.toLowerCase());
+  }
+
+  isWatching(token: string): boolean {
+    return this.tokens.has(token.toLowerCase());
+  }
+
+  /**
+   * Feed a market event; returns the signals it caused. Trades are timed by the latest block tick
+   * seen, or `now()` before the first one.
+   */
+  observe(event: StrategyEvent): MomentumSignal[] {
+    if (event.kind === 'block') {
+      this.clock = event.block.timestamp;
+      return [];
+    }
+    if (event.kind === 'tokenCreated') {
+      if (this.config.watchNewTokens ?? true) {
+        this.watch(event.event.token);
+      }
+      return [];
+    }
+    const { trade } = event;
+    const signal = trade.side === 'buy'
+      ? this.recordBuy(trade.event, this.clock ?? this.now(), trade.blockNumber)
+      : null;
+    return signal ? [signal] : [];
+  }
+
+  /**
+   * Subscribe to signals raised while attached to a trader; returns an unsubscribe function
+   */
+  onSignal(listener: MomentumListener): () => void {
+    this.listeners.push(listener);
+    return () => {
+      const index = this.listeners.indexOf(listener);
+      if (index > -1) {
+        this.listeners.splice(index, 1);
+      }
+    };
+  }
+
+  /**
+   * Feed the trigger from a trader's creation, buy and block streams; returns a detach function.
+   * To drive a StrategyRunner, pass the trigger in its `signals` instead.
+   */
+  attach(trader: FourTrading): () => void {
+    const emit = (signals: MomentumSignal[]) => signals.forEach((signal) => this.listeners.forEach((listener) => listener(signal)));
+    const ids = [
+      trader.onTokenCreate((event) => emit(this.observe({ kind: 'tokenCreated', event }))),
+      trader.onTokenPurchase((event) => emit(this.observe({ kind: 'trade', trade: { side: 'buy', event } }))),
+      trader.onBlock((number) => emit(this.observe({ kind: 'block', block: { number, timestamp: this.now() } }))),
+    ];
+
+    return () => {
+      for (const id of ids) {
+        trader.off(id);
+      }
+    };
+  }
+
+  private recordBuy(event: TokenPurchaseEvent, timestamp: number, blockNumber?: number): MomentumSignal | null {
+    const state = this.tokens.get(event.token.toLowerCase());
+    if (!state) {
+      return null;
+    }
+    state.launchPrice ??= event.price;
+
+    const buyer = event.account.toLowerCase();
+    const known = state.buyers.delete(buyer);
+    state.buyers.set(buyer, true);
+    if (state.buyers.size > this.maxTrackedBuyers) {
+      state.buyers.delete(state.buyers.keys().next().value!);
+      state.saturated = true;
+    }
+    if (!known) {
+      state.firstBuys.push(timestamp);
+    }
+
+    const windowStart = timestamp - this.windowSeconds;
+    while (state.firstBuys.length > 0 && state.firstBuys[0] <= windowStart - this.windowSeconds) {
+      state.firstBuys.shift();
+    }
+    const newBuyers = state.firstBuys.filter((at) => at > windowStart).length;
+    if (newBuyers < this.config.minNewBuyers) {
+      return null;
+    }
+    if (state.lastSignalAt !== null && timestamp - state.lastSignalAt < this.cooldownSeconds) {
+      return null;
+    }
+    if (state.launchPrice === 0n || event.price * SLIPPAGE_DENOMINATOR >= state.launchPrice * this.maxPriceMultipleBps) {
+      return null;
+    }
+
+    state.lastSignalAt = timestamp;
+    return {
+      type: 'momentum',
+      tokenAddress: state.token,
+      timestamp,
+      newBuyers,
+      previousNewBuyers: state.firstBuys.length - newBuyers,
+      windowSeconds: this.windowSeconds,
+      trackedBuyers: state.buyers.size,
+      buyersSaturated: state.saturated,
+      price: event.price,
+      launchPrice: state.launchPrice,
+      priceMultipleBps: bpsOf(event.price, state.launchPrice, 'price multiple'),
+      ...(blockNumber !== undefined ? { blockNumber } : {}),
+    };
+  }
+}
@@ -0,0 +1,154 @@
+/**
+ * Batched reads through Multicall3
+ * Each call may fail on its own without failing the batch
+ */
+
+import { getErc20Interface, getFourTradingInterface, getMulticall3Interface } from './abi';
+import { TokenInfoLayoutError } from './errors';
+import { decodeTokenInfo } from './tokenInfo';
+import { TokenInfo } from './types';
+
+export interface MulticallCall {
+  target: string;
+  callData: string;
+}
+
+export interface MulticallResult {
+  success: boolean;
+  returnData: string;
+}
+
+/**
+ * Performs eth_call of `data` against the Multicall3 contract and returns the raw result
+ */
+export type MulticallExecutor = (data: string) => Promise<string>;
+
+/**
+ * Run `calls` in one aggregate3 eth_call with per-call failure allowed
+ */
+export async function aggregate3(execute: MulticallExecutor, calls: MulticallCall[]): Promise<MulticallResult[]> {
+  if (calls.length === 0) {
+    return [];
+  }
+  const iface = getMulticall3Interface();
+  const data = iface.encodeFunctionData('aggregate3', [
+    calls.map((call) => ({ target: call.target, allowFailure: true, callData: call.callData })),
+  ]);
+  const [results] = iface.decodeFunctionResult('aggregate3', await execute(data));
+  return results.map((result: any) => ({ success: result.success, returnData: result.returnData }));
+}
+
+/**
+ * `owner`'s allowance to `spender` for each token, in one round trip. `spender` may instead list
+ * one spender per token, e.g. each token's own manager.
+ * Tokens whose read reverts or returns malformed data are reported as null.
+ */
+export async function readAllowances(
+  execute: MulticallExecutor,
+  tokens: string[],
+  owner: string,
+  spender: string | readonly string[]
+): Promise<Array<bigint | null>> {
+  const erc20 = getErc20Interface();
+  const results = await aggregate3(execute, tokens.map((target, index) => ({
+    target,
+    callData: erc20.encodeFunctionData('allowance', [owner, typeof spender === 'string' ? spender : spender[index]]),
+  })));
+
+  return results.map((result) => {
+    if (!result.success) {
+      return null;
+    }
+    try {
+      return erc20.decodeFunctionResult('allowance', result.returnData)[0] as bigint;
+    } catch {
+      return null;
+    }
+  });
+}
+
+/**
+ * The TokenManager's `_tokenInfos` entry for each token, in one round trip.
+ * Tokens whose read reverts or returns malformed data are reported as null; return data of the
+ * wrong length throws TokenInfoLayoutError for the whole batch.
+ */
+export async function readTokenInfos(
+  execute: MulticallExecutor,
+  manager: string,
+  tokens: string[]
+): Promise<Array<TokenInfo | null>> {
+  const iface = getFourTradingInterface();
+  const results = await aggregate3(
+    execute,
+    tokens.map((token) => ({ target: manager, callData: iface.encodeFunctionData('_tokenInfos', [token]) }))
+  );
+
+  return results.map((result) => {
+    if (!result.success) {
+      return null;
+    }
+    try {
+      return decodeTokenInfo(result.returnData);
+    } catch (error) {
+      if (error instanceof TokenInfoLayoutError) {
+        throw error;
+      }
+      return null;
+    }
+  });
+}
+
+export interface BuyQuoteRead {
+  fee: bigint; // calcTradingFee of the spend
+  grossTokenAmount: bigint; // calcBuyAmount of the whole spend
+  tokenAmount: bigint; // calcBuyAmount of the spend after the fee; 0 when the fee takes it all
+}
+
+function decodeUint(iface: ReturnType<typeof getFourTradingInterface>, fn: string, result: MulticallResult): bigint | null {
+  if (!result.success) {
+    return null;
+  }
+  try {
+    return iface.decodeFunctionResult(fn, result.returnData)[0] as bigint;
+  } catch {
+    return null;
+  }
+}
+
+/**
+ * Fee and token amounts of a buy of each of `amounts` on `tokenInfo`, the same reads quoteBuy makes,
+ * in two round trips whatever the number of amounts: the fees first, then the buy amounts before
+ * and after them. Amounts whose reads fail are reported as null.
+ */
+export async function readBuyQuotes(
+  execute: MulticallExecutor,
+  manager: string,
+  tokenInfo: TokenInfo,
+  amounts: bigint[]
+): Promise<Array<BuyQuoteRead | null>> {
+  const iface = getFourTradingInterface();
+  const call = (fn: string, funds: bigint): MulticallCall => ({
+    target: manager,
+    callData: iface.encodeFunctionData(fn, [tokenInfo, funds]),
+  });
+
+  const fees = (await aggregate3(execute, amounts.map((amount) => call('calcTradingFee', amount)))).map((result) =>
+    decodeUint(iface, 'calcTradingFee', result)
+  );
+  const priced = amounts.flatMap((amount, index) => {
+    const fee = fees[index];
+    return fee !== null && fee < amount ? [index] : [];
+  });
+  const buys = await aggregate3(
+    execute,
+    priced.flatMap((index) => [call('calcBuyAmount', amounts[index]), call('calcBuyAmount', amounts[index] - fees[index]!)])
+  );
+
+  const reads: Array<BuyQuoteRead | null> = fees.map((fee) => (fee === null ? null : { fee, grossTokenAmount: 0n, tokenAmount: 0n }));
+  priced.forEach((index, position) => {
+    const grossTokenAmount = decodeUint(iface, 'calcBuyAmount', buys[2 * position]);
+    const tokenAmount = decodeUint(iface, 'calcBuyAmount', buys[2 * position + 1]);
+    reads[index] = grossTokenAmount === null || tokenAmount === null ? null : { fee: fees[index]!, grossTokenAmount, tokenAmount };
+  });
+  return reads;
+}
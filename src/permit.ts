@@ -0,0 +1,90 @@
+/**
+ * Gasless approvals through EIP-2612 permit
+ * The token holder signs a `Permit` off-chain and anyone may submit it. With approveGasless the
+ * trader's wallet submits it and pays the gas, so a holder with no BNB can still approve.
+ * Only tokens implementing `permit`, `nonces` and `DOMAIN_SEPARATOR` support this.
+ */
+
+import { SignatureLike, TypedDataDomain, TypedDataEncoder, verifyTypedData } from 'ethers';
+
+export const PERMIT_TYPES = {
+  Permit: [
+    { name: 'owner', type: 'address' },
+    { name: 'spender', type: 'address' },
+    { name: 'value', type: 'uint256' },
+    { name: 'nonce', type: 'uint256' },
+    { name: 'deadline', type: 'uint256' },
+  ],
+};
+
+/**
+ * Domain versions tried, after the one the token reports, to match its DOMAIN_SEPARATOR
+ */
+const PERMIT_DOMAIN_VERSIONS = ['1', '2'];
+
+export interface PermitDomain {
+  name: string;
+  version: string;
+  chainId: bigint;
+  verifyingContract: string; // The token
+}
+
+export interface PermitMessage {
+  owner: string;
+  spender: string;
+  value: bigint;
+  nonce: bigint; // The owner's current `nonces(owner)`
+  deadline: bigint; // Unix seconds
+}
+
+/**
+ * EIP-712 payload for the holder to sign, e.g. `signer.signTypedData(domain, types, message)`
+ */
+export interface PermitRequest {
+  domain: PermitDomain;
+  types: typeof PERMIT_TYPES;
+  message: PermitMessage;
+}
+
+/**
+ * A holder's signed approval intent, submitted by approveGasless
+ */
+export interface GaslessApproval {
+  owner: string;
+  spender?: string; // Default: the TokenManager handling the token, as approveToken
+  value: bigint;
+  deadline: bigint; // Unix seconds
+  signature: SignatureLike; // Over the PermitRequest for these fields and the owner's current nonce
+}
+
+/**
+ * The token's EIP-712 domain, found by matching candidate versions against its on-chain
+ * DOMAIN_SEPARATOR. Undefined when none matches, so signatures can't be built for it.
+ */
+export function resolvePermitDomain(
+  token: string,
+  chainId: bigint,
+  name: string,
+  reportedVersion: string | undefined,
+  domainSeparator: string
+): PermitDomain | undefined {
+  const versions = reportedVersion === undefined ? PERMIT_DOMAIN_VERSIONS : [reportedVersion, ...PERMIT_DOMAIN_VERSIONS];
+  for (const version of versions) {
+    const domain: PermitDomain = { name, version, chainId, verifyingContract: token };
+    if (TypedDataEncoder.hashDomain(domain as TypedDataDomain).toLowerCase() === domainSeparator.toLowerCase()) {
+      return domain;
+    }
+  }
+  return undefined;
+}
+
+export function permitRequest(domain: PermitDomain, message: PermitMessage): PermitRequest {
+  return { domain, types: PERMIT_TYPES, message };
+}
+
+/**
+ * Address that signed `request`
+ */
+export function recoverPermitSigner(request: PermitRequest, signature: SignatureLike): string {
+  return verifyTypedData(request.domain as TypedDataDomain, request.types, request.message, signature);
+}
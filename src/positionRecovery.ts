@@ -0,0 +1,56 @@
+/**
+ * Position reconstruction from chain data
+ * Replays a wallet's four.meme fills of a token through a fresh PositionTracker, then reconciles the
+ * result with the wallet's on-chain balance. Tokens the fills don't account for (transfers in, or
+ * PancakeSwap buys after graduation) are added with an unknown basis; tokens that left the wallet
+ * some other way are removed at the average cost, without realizing PnL.
+ */
+
+import { BackfillEvent } from './backfill';
+import { Position, PositionTracker } from './positionTracker';
+
+/**
+ * `account`'s position in `token` from its trade events (oldest first) and current `balance`
+ */
+export function replayPosition(token: string, account: string, events: BackfillEvent[], balance: bigint): Position {
+  const tracker = new PositionTracker();
+  const wallet = account.toLowerCase();
+
+  for (const { side, event } of events) {
+    if (event.account.toLowerCase() !== wallet || event.token.toLowerCase() !== token.toLowerCase()) {
+      continue;
+    }
+    if (side === 'buy') {
+      tracker.recordBuy(token, event.amount, event.cost + event.fee);
+    } else {
+      tracker.recordSell(token, event.amount, event.cost - event.fee);
+    }
+  }
+
+  let position: Position = tracker.getPosition(token) ?? {
+    token,
+    amount: 0n,
+    knownAmount: 0n,
+    knownCost: 0n,
+    unknownAmount: 0n,
+    realizedPnl: 0n,
+    basisUnknown: false,
+  };
+  if (balance > position.amount) {
+    position = tracker.markExternalAcquisition(token, balance - position.amount);
+  } else if (balance < position.amount) {
+    const removed = position.amount - balance;
+    const removedKnown = (removed * position.knownAmount) / position.amount;
+    const removedCost = position.knownAmount > 0n ? (position.knownCost * removedKnown) / position.knownAmount : 0n;
+    const unknownAmount = position.unknownAmount - (removed - removedKnown);
+    position = {
+      ...position,
+      amount: balance,
+      knownAmount: position.knownAmount - removedKnown,
+      knownCost: position.knownCost - removedCost,
+      unknownAmount,
+      basisUnknown: unknownAmount > 0n,
+    };
+  }
+  return { ...position, token };
+}
@@ -0,0 +1,243 @@
+/**
+ * Position persistence
+ * Stores apply a trade and the position it produced as one atomic update, so a restart
+ * restores exactly the state of the last completed update
+ */
+
+import { promises as fs } from 'fs';
+import * as path from 'path';
+import { Position } from './positionTracker';
+
+export interface PositionTrade {
+  kind: 'buy' | 'sell' | 'external' | 'restore'; // 'restore' replaces the position outright
+  token: string;
+  tokenAmount: bigint;
+  bnbAmount?: bigint; // Cost or proceeds (known cost for 'restore'); absent for basis-unknown acquisitions
+  timestamp: number; // Unix seconds
+}
+
+export interface PositionUpdate {
+  trade: PositionTrade;
+  position: Position; // Position after the trade
+}
+
+export interface PositionStore {
+  /**
+   * Current positions, keyed by lowercase token address
+   */
+  load(): Promise<Map<string, Position>>;
+
+  /**
+   * Persist a trade together with its resulting position; either both are stored or neither.
+   * Updates are applied in call order.
+   */
+  apply(update: PositionUpdate): Promise<void>;
+
+  /**
+   * Remove all stored positions
+   */
+  clear(): Promise<void>;
+}
+
+export class MemoryPositionStore implements PositionStore {
+  private positions: Map<string, Position> = new Map();
+  private trades: PositionTrade[] = [];
+
+  async load(): Promise<Map<string, Position>> {
+    return new Map([...this.positions].map(([key, position]) => [key, { ...position }]));
+  }
+
+  async apply(update: PositionUpdate): Promise<void> {
+    this.trades.push({ ...update.trade });
+    this.positions.set(update.position.token.toLowerCase(), { ...update.position });
+  }
+
+  async clear(): Promise<void> {
+    this.positions.clear();
+    this.trades = [];
+  }
+
+  getTrades(): PositionTrade[] {
+    return this.trades.map((trade) => ({ ...trade }));
+  }
+}
+
+interface JournalEntry {
+  seq: number;
+  trade: PositionTrade;
+  position: Position;
+}
+
+interface Snapshot {
+  seq: number; // Last journal entry folded into `positions`
+  positions: Position[];
+}
+
+const JOURNAL_FILE = 'positions.journal';
+const SNAPSHOT_FILE = 'positions.json';
+
+function toJson(value: unknown): string {
+  return JSON.stringify(value, (_key, v) => (typeof v === 'bigint' ? { $bigint: v.toString() } : v));
+}
+
+function fromJson<T>(text: string): T {
+  return JSON.parse(text, (_key, v) => (v && typeof v === 'object' && typeof v.$bigint === 'string' ? BigInt(v.$bigint) : v));
+}
+
+/**
+ * Journaled JSON file store.
+ * Each update is one appended, fsynced journal line holding the trade and the resulting position,
+ * so a crash leaves either the whole update or none of it (a torn last line is discarded).
+ * Every `compactEvery` updates the positions are written to a snapshot by temp-file rename and
+ * the journal is truncated; recovery replays journal entries newer than the snapshot.
+ */
+export class JsonFilePositionStore implements PositionStore {
+  private journalPath: string;
+  private snapshotPath: string;
+  private positions: Map<string, Position> = new Map();
+  private seq = 0;
+  private sinceCompaction = 0;
+  private queue: Promise<void> = Promise.resolve();
+  private loaded = false;
+
+  constructor(private directory: string, private compactEvery: number = 100) {
+    this.journalPath = path.join(directory, JOURNAL_FILE);
+    this.snapshotPath = path.join(directory, SNAPSHOT_FILE);
+  }
+
+  async load(): Promise<Map<string, Position>> {
+    await this.serialize(() => this.recover());
+    return new Map([...this.positions].map(([key, position]) => [key, { ...position }]));
+  }
+
+  async apply(update: PositionUpdate): Promise<void> {
+    await this.serialize(async () => {
+      if (!this.loaded) {
+        await this.recover();
+      }
+
+      const entry: JournalEntry = { seq: this.seq + 1, trade: update.trade, position: update.position };
+      const handle = await fs.open(this.journalPath, 'a');
+      try {
+        await handle.appendFile(toJson(entry) + '\n');
+        await handle.sync();
+      } finally {
+        await handle.close();
+      }
+
+      this.seq = entry.seq;
+      this.positions.set(update.position.token.toLowerCase(), { ...update.position });
+      this.sinceCompaction++;
+
+      if (this.sinceCompaction >= this.compactEvery) {
+        await this.compactNow();
+      }
+    });
+  }
+
+  async clear(): Promise<void> {
+    await this.serialize(async () => {
+      this.positions.clear();
+      this.loaded = true;
+      await this.compactNow();
+    });
+  }
+
+  /**
+   * Fold the journal into the snapshot, after any updates already queued
+   */
+  async compact(): Promise<void> {
+    await this.serialize(() => this.compactNow());
+  }
+
+  private async compactNow(): Promise<void> {
+    // Snapshot first: if we crash before truncating, replay skips entries at or below its seq
+    await this.writeSnapshot();
+    await fs.writeFile(this.journalPath, '');
+    this.sinceCompaction = 0;
+  }
+
+  /**
+   * Run store operations one at a time, so concurrent trades never interleave writes
+   */
+  private serialize(task: () => Promise<void>): Promise<void> {
+    const run = this.queue.then(task, task);
+    this.queue = run.then(() => undefined, () => undefined);
+    return run;
+  }
+
+  private async recover(): Promise<void> {
+    await fs.mkdir(this.directory, { recursive: true });
+    this.positions.clear();
+    this.seq = 0;
+
+    const snapshotText = await readIfExists(this.snapshotPath);
+    if (snapshotText !== null) {
+      const snapshot = fromJson<Snapshot>(snapshotText);
+      this.seq = snapshot.seq;
+      for (const position of snapshot.positions) {
+        this.positions.set(position.token.toLowerCase(), position);
+      }
+    }
+
+    const journalText = (await readIfExists(this.journalPath)) ?? '';
+    const lines = journalText.split('\n');
+    let replayed = 0;
+    let validLength = 0;
+
+    for (const [index, line] of lines.entries()) {
+      if (line === '') {
+        continue;
+      }
+      let entry: JournalEntry;
+      try {
+        entry = fromJson<JournalEntry>(line);
+      } catch {
+        // Torn write from a crash mid-append; only the last line can be incomplete
+        if (index === lines.length - 1) {
+          break;
+        }
+        throw new Error(`Corrupt position journal entry at line ${index + 1}`);
+      }
+      validLength += line.length + 1;
+      if (entry.seq <= this.seq) {
+        continue; // Already in the snapshot (crash between snapshot and truncation)
+      }
+      this.positions.set(entry.position.token.toLowerCase(), entry.position);
+      this.seq = entry.seq;
+      replayed++;
+    }
+
+    if (validLength < journalText.length) {
+      // Drop the torn tail so new entries start on a fresh line
+      await fs.writeFile(this.journalPath, journalText.slice(0, validLength));
+    }
+
+    this.sinceCompaction = replayed;
+    this.loaded = true;
+  }
+
+  private async writeSnapshot(): Promise<void> {
+    const snapshot: Snapshot = { seq: this.seq, positions: [...this.positions.values()] };
+    const temp = `${this.snapshotPath}.tmp`;
+    const handle = await fs.open(temp, 'w');
+    try {
+      await handle.writeFile(toJson(snapshot));
+      await handle.sync();
+    } finally {
+      await handle.close();
+    }
+    await fs.rename(temp, this.snapshotPath);
+  }
+}
+
+async function readIfExists(file: string): Promise<string | null> {
+  try {
+    return await fs.readFile(file, 'utf8');
+  } catch (error: any) {
+    if (error.code === 'ENOENT') {
+      return null;
+    }
+    throw error;
+  }
+}
@@ -0,0 +1,223 @@
+/**
+ * Position tracking with explicit cost basis
+ * Tokens acquired outside the SDK (airdrops, transfers) carry an unknown basis instead of zero
+ */
+
+import { Price } from './price';
+import type { PositionStore, PositionTrade } from './positionStore';
+
+export interface Position {
+  token: string;
+  amount: bigint; // Total tokens held
+  knownAmount: bigint; // Tokens whose cost basis is known
+  knownCost: bigint; // BNB (wei) paid for `knownAmount`
+  unknownAmount: bigint; // Tokens with no known cost basis (airdrops, transfers)
+  realizedPnl: bigint; // Realized PnL in wei, includes basis-unknown proceeds as profit
+  basisUnknown: boolean; // True while any held tokens have an unknown basis
+}
+
+export interface RealizedPnl {
+  token: string;
+  proceeds: bigint;
+  costBasis: bigint; // Basis of the known portion sold
+  pnl: bigint; // proceeds - costBasis
+  basisUnknown: boolean; // Part of the proceeds came from tokens with unknown basis (reported as pure profit)
+}
+
+/**
+ * Price per token at which selling `tokenAmount` returns exactly `costBasis` after the sell's
+ * gas and trading fee. The fee is taken as the fraction `feeRate` of gross proceeds.
+ */
+export function breakEvenPrice(costBasis: bigint, tokenAmount: bigint, sellGasCost: bigint, feeRate: Price): Price {
+  if (tokenAmount === 0n) {
+    throw new RangeError('Cannot compute a break-even price for zero tokens');
+  }
+  const keptFraction = new Price(1n).sub(feeRate);
+  if (keptFraction.numerator <= 0n) {
+    throw new RangeError('Fee rate must be below 100%');
+  }
+  // gross * (1 - feeRate) = costBasis + gas
+  return new Price(costBasis + sellGasCost).div(keptFraction).div(new Price(tokenAmount));
+}
+
+export class PositionTracker {
+  private positions: Map<string, Position> = new Map();
+  private store?: PositionStore;
+  private pending: Promise<void> = Promise.resolve();
+  private persistError: unknown = null;
+
+  /**
+   * Restore positions from `store` and persist every later change to it
+   */
+  static async open(store: PositionStore): Promise<PositionTracker> {
+    const tracker = new PositionTracker();
+    tracker.positions = await store.load();
+    tracker.store = store;
+    return tracker;
+  }
+
+  /**
+   * Wait until every change so far is persisted; rethrows the first failed write
+   */
+  async flush(): Promise<void> {
+    await this.pending;
+    if (this.persistError !== null) {
+      const error = this.persistError;
+      this.persistError = null;
+      throw error;
+    }
+  }
+
+  /**
+   * Record tokens bought through the SDK
+   */
+  recordBuy(token: string, tokenAmount: bigint, bnbCost: bigint): Position {
+    const position = this.getOrCreate(token);
+    position.amount += tokenAmount;
+    position.knownAmount += tokenAmount;
+    position.knownCost += bnbCost;
+    this.persist({ kind: 'buy', token, tokenAmount, bnbAmount: bnbCost }, position);
+    return { ...position };
+  }
+
+  /**
+   * Record tokens acquired outside the SDK
+   * @param costBasis - BNB paid in wei if known; omit for airdrops or when unknown
+   */
+  markExternalAcquisition(token: string, amount: bigint, costBasis?: bigint): Position {
+    const position = this.getOrCreate(token);
+    position.amount += amount;
+    if (costBasis === undefined) {
+      position.unknownAmount += amount;
+    } else {
+      position.knownAmount += amount;
+      position.knownCost += costBasis;
+    }
+    position.basisUnknown = position.unknownAmount > 0n;
+    this.persist({ kind: 'external', token, tokenAmount: amount, bnbAmount: costBasis }, position);
+    return { ...position };
+  }
+
+  /**
+   * Record a sell. Tokens are drawn from known and unknown basis pro rata;
+   * proceeds from the unknown portion are counted as pure profit and flagged.
+   */
+  recordSell(token: string, tokenAmount: bigint, proceeds: bigint): RealizedPnl {
+    const position = this.getOrCreate(token);
+
+    if (position.amount === 0n) {
+      // Nothing tracked at all: treat the whole sale as basis-unknown
+      position.realizedPnl += proceeds;
+      this.persist({ kind: 'sell', token, tokenAmount, bnbAmount: proceeds }, position);
+      return { token, proceeds, costBasis: 0n, pnl: proceeds, basisUnknown: true };
+    }
+
+    const sold = tokenAmount > position.amount ? position.amount : tokenAmount;
+    const soldKnown = (sold * position.knownAmount) / position.amount;
+    const soldUnknown = sold - soldKnown;
+    const costBasis = position.knownAmount > 0n ? (position.knownCost * soldKnown) / position.knownAmount : 0n;
+
+    position.amount -= sold;
+    position.knownAmount -= soldKnown;
+    position.knownCost -= costBasis;
+    position.unknownAmount -= soldUnknown;
+
+    const pnl = proceeds - costBasis;
+    position.realizedPnl += pnl;
+    const basisUnknown = soldUnknown > 0n || tokenAmount > sold;
+    position.basisUnknown = position.unknownAmount > 0n;
+    this.persist({ kind: 'sell', token, tokenAmount, bnbAmount: proceeds }, position);
+
+    return { token, proceeds, costBasis, pnl, basisUnknown };
+  }
+
+  /**
+   * Replace a token's position, e.g. with one reconstructed from chain data after a restart
+   */
+  restore(position: Position): Position {
+    const restored = { ...position };
+    this.positions.set(position.token.toLowerCase(), restored);
+    this.persist({ kind: 'restore', token: position.token, tokenAmount: position.amount, bnbAmount: position.knownCost }, restored);
+    return { ...restored };
+  }
+
+  getPosition(token: string): Position | undefined {
+    const position = this.positions.get(token.toLowerCase());
+    return position ? { ...position } : undefined;
+  }
+
+  getPositions(): Position[] {
+    return [...this.positions.values()].map((position) => ({ ...position }));
+  }
+
+  /**
+   * Average entry price of the known-basis portion, or null when no basis is known
+   */
+  averageEntryPrice(token: string): Price | null {
+    const position = this.positions.get(token.toLowerCase());
+    if (!position || position.knownAmount === 0n) {
+      return null;
+    }
+    return Price.fromAmounts(position.knownCost, position.knownAmount);
+  }
+
+  /**
+   * Unrealized PnL at `price`, or null when any held tokens have an unknown basis
+   */
+  unrealizedPnl(token: string, price: Price): bigint | null {
+    const position = this.positions.get(token.toLowerCase());
+    if (!position || position.basisUnknown) {
+      return null;
+    }
+    const value = (position.amount * price.numerator) / price.denominator;
+    return value - position.knownCost;
+  }
+
+  clear(): void {
+    this.positions.clear();
+    if (this.store) {
+      const store = this.store;
+      this.enqueue(() => store.clear());
+    }
+  }
+
+  /**
+   * Queue the trade and resulting position for the store; writes stay in trade order
+   */
+  private persist(trade: Omit<PositionTrade, 'timestamp'>, position: Position): void {
+    if (!this.store) {
+      return;
+    }
+    const store = this.store;
+    const update = {
+      trade: { ...trade, timestamp: Math.floor(Date.now() / 1000) },
+      position: { ...position },
+    };
+    this.enqueue(() => store.apply(update));
+  }
+
+  private enqueue(write: () => Promise<void>): void {
+    this.pending = this.pending.then(write).catch((error) => {
+      console.error('Failed to persist position:', error?.message ?? error);
+      this.persistError ??= error;
+    });
+  }
+
+  private getOrCreate(token: string): Position {
+    const key = token.toLowerCase();
+    let position = this.positions.get(key);
+    if (!position) {
+      position = {
+        token,
+        amount: 0n,
+        knownAmount: 0n,
+        knownCost: 0n,
+        unknownAmount: 0n,
+        realizedPnl: 0n,
+        basisUnknown: false,
+      };
+      this.positions.set(key, position);
+    }
+    return position;
+  }
+}
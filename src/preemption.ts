@@ -0,0 +1,120 @@
+/**
+ * Preemption of this wallet's pending transactions for an emergency exit
+ * A new transaction can only mine after every pending one at a lower nonce, so an exit sell sent
+ * behind underpriced pending transactions waits for them. Each is handled by policy: a pending buy
+ * of the token being exited is cancelled by default (a same-nonce zero-value self-transfer), since
+ * it would only add to the bag; other pending transactions are repriced to the exit's gas price so
+ * they stop holding the nonce queue up.
+ */
+
+import { REPLACEMENT_MIN_BUMP_BPS, SLIPPAGE_DENOMINATOR } from './constants';
+import { ValidationError } from './errors';
+
+/**
+ * A transaction this wallet sent through the trader, until it mines or is replaced
+ */
+export interface OwnPendingTx {
+  txHash: string;
+  nonce: number;
+  token: string;
+  side: 'buy' | 'sell' | 'raw'; // 'raw': a rawSend, never a conflicting buy, so repriced like any other
+  gasPrice: bigint; // gasPrice, or maxFeePerGas of an EIP-1559 transaction
+  subAccount?: string; // Sub-account the fill is booked to
+}
+
+export type PreemptionAction = 'cancel' | 'reprice' | 'wait';
+
+export interface PreemptionPolicy {
+  conflictingBuy?: PreemptionAction; // Pending buy of the token being exited (default: 'cancel')
+  otherPending?: 'reprice' | 'wait'; // Any other pending transaction, which still holds an earlier nonce (default: 'reprice')
+  maxReplacements?: number; // Cancels plus reprices, lowest nonces first; the rest wait (default: no limit)
+  minBumpBps?: number; // Smallest raise nodes accept for a replacement (default: REPLACEMENT_MIN_BUMP_BPS)
+}
+
+export interface PreemptionDecision {
+  txHash: string;
+  nonce: number;
+  conflicting: boolean; // A pending buy of the token being exited
+  action: PreemptionAction;
+  gasPrice?: bigint; // Price of the cancel or reprice
+  reason: string;
+}
+
+/**
+ * What sending a decision's replacement did
+ */
+export interface PreemptionOutcome {
+  decision: PreemptionDecision;
+  txHash?: string; // Hash of the cancel or reprice
+  error?: string; // Why it wasn't sent, e.g. the transaction mined meanwhile
+}
+
+/**
+ * Smallest gas price a same-nonce replacement of a `gasPrice` transaction is accepted at
+ */
+export function minReplacementPrice(gasPrice: bigint, minBumpBps: number = REPLACEMENT_MIN_BUMP_BPS): bigint {
+  const bump = BigInt(minBumpBps);
+  return gasPrice + (gasPrice * bump + SLIPPAGE_DENOMINATOR - 1n) / SLIPPAGE_DENOMINATOR;
+}
+
+/**
+ * Fee fields for an EIP-1559 same-nonce replacement bidding `bid`. The fee cap and the tip are each
+ * raised to the bid, or to the minimum valid bump of the original's when that is higher, so a bid
+ * below the original cap never lowers it and gets the replacement rejected as underpriced.
+ */
+export function replacementFees(
+  original: { maxFeePerGas?: bigint | null; maxPriorityFeePerGas?: bigint | null },
+  bid: bigint,
+  minBumpBps: number = REPLACEMENT_MIN_BUMP_BPS
+): { maxFeePerGas: bigint; maxPriorityFeePerGas: bigint } {
+  const atLeast = (floor: bigint) => (bid > floor ? bid : floor);
+  return {
+    maxFeePerGas: atLeast(minReplacementPrice(original.maxFeePerGas ?? 0n, minBumpBps)),
+    maxPriorityFeePerGas: atLeast(minReplacementPrice(original.maxPriorityFeePerGas ?? 0n, minBumpBps)),
+  };
+}
+
+/**
+ * Decide, lowest nonce first, what to do with each of the wallet's pending transactions before an
+ * exit sell of `token` priced at `exitGasPrice`. Replacements are priced at the exit price, or the
+ * minimum valid bump when that is higher.
+ */
+export function planPreemption(
+  token: string,
+  pending: OwnPendingTx[],
+  exitGasPrice: bigint,
+  policy: PreemptionPolicy = {}
+): PreemptionDecision[] {
+  const maxReplacements = policy.maxReplacements ?? Infinity;
+  if (maxReplacements !== Infinity && (!Number.isInteger(maxReplacements) || maxReplacements < 0)) {
+    throw new ValidationError(`maxReplacements must be a non-negative integer, got ${maxReplacements}`);
+  }
+  const conflictingBuy = policy.conflictingBuy ?? 'cancel';
+  const otherPending = policy.otherPending ?? 'reprice';
+  const exitToken = token.toLowerCase();
+  let replacements = 0;
+
+  return [...pending].sort((a, b) => a.nonce - b.nonce).map((tx) => {
+    const conflicting = tx.side === 'buy' && tx.token.toLowerCase() === exitToken;
+    const decision = { txHash: tx.txHash, nonce: tx.nonce, conflicting };
+    let action: PreemptionAction = conflicting ? conflictingBuy : otherPending;
+    let reason: string;
+
+    if (action === 'wait') {
+      reason = conflicting ? 'policy waits for pending buys of the token' : 'policy waits for other pending transactions';
+    } else if (action === 'reprice' && tx.gasPrice >= exitGasPrice) {
+      action = 'wait';
+      reason = 'already priced at or above the exit';
+    } else if (replacements >= maxReplacements) {
+      action = 'wait';
+      reason = `replacement limit of ${maxReplacements} reached`;
+    } else {
+      replacements += 1;
+      const floor = minReplacementPrice(tx.gasPrice, policy.minBumpBps);
+      const gasPrice = exitGasPrice > floor ? exitGasPrice : floor;
+      reason = action === 'cancel' ? 'pending buy of the token being exited' : 'holds an earlier nonce below the exit price';
+      return { ...decision, action, gasPrice, reason };
+    }
+    return { ...decision, action, reason };
+  });
+}
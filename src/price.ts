@@ -0,0 +1,144 @@
+/**
+ * Exact rational price representation
+ * Keeps numerator/denominator as bigint so very cheap tokens never round to zero
+ */
+
+function gcd(a: bigint, b: bigint): bigint {
+  a = a < 0n ? -a : a;
+  b = b < 0n ? -b : b;
+  while (b !== 0n) {
+    [a, b] = [b, a % b];
+  }
+  return a;
+}
+
+export class Price {
+  readonly numerator: bigint;
+  readonly denominator: bigint;
+
+  /**
+   * Create a price of `numerator / denominator`, always stored reduced with a positive denominator
+   */
+  constructor(numerator: bigint, denominator: bigint = 1n) {
+    if (denominator === 0n) {
+      throw new RangeError('Price denominator cannot be zero');
+    }
+
+    if (denominator < 0n) {
+      numerator = -numerator;
+      denominator = -denominator;
+    }
+
+    const divisor = gcd(numerator, denominator);
+    this.numerator = divisor > 1n ? numerator / divisor : numerator;
+    this.denominator = divisor > 1n ? denominator / divisor : denominator;
+  }
+
+  static readonly ZERO = new Price(0n);
+
+  /**
+   * Price per token from a BNB amount and a token amount (both in wei)
+   * Returns zero when no tokens are involved
+   */
+  static fromAmounts(bnbAmount: bigint, tokenAmount: bigint): Price {
+    if (tokenAmount === 0n) {
+      return Price.ZERO;
+    }
+    return new Price(bnbAmount, tokenAmount);
+  }
+
+  /**
+   * Price from an integer scaled by 10^decimals (e.g. an 18-decimal wei price)
+   */
+  static fromScaled(value: bigint, decimals: number = 18): Price {
+    return new Price(value, 10n ** BigInt(decimals));
+  }
+
+  isZero(): boolean {
+    return this.numerator === 0n;
+  }
+
+  /**
+   * Integer price scaled by 10^decimals, truncated toward zero
+   */
+  toScaled(decimals: number = 18): bigint {
+    return (this.numerator * 10n ** BigInt(decimals)) / this.denominator;
+  }
+
+  /**
+   * Decimal string with `decimals` fractional digits, truncated toward zero
+   */
+  toDecimalString(decimals: number = 18): string {
+    const negative = this.numerator < 0n;
+    const scaled = this.toScaled(decimals);
+    const digits = (negative ? -scaled : scaled).toString().padStart(decimals + 1, '0');
+    const whole = digits.slice(0, digits.length - decimals);
+    const fraction = decimals > 0 ? `.${digits.slice(digits.length - decimals)}` : '';
+    return `${negative ? '-' : ''}${whole}${fraction}`;
+  }
+
+  /**
+   * Convenience conversion to a JS number.
+   * LOSSY: precision beyond ~15 significant digits is lost; never use it for comparisons or ratio math.
+   */
+  toNumber(): number {
+    return Number(this.numerator) / Number(this.denominator);
+  }
+
+  compare(other: Price): number {
+    const left = this.numerator * other.denominator;
+    const right = other.numerator * this.denominator;
+    return left === right ? 0 : left < right ? -1 : 1;
+  }
+
+  equals(other: Price): boolean {
+    return this.compare(other) === 0;
+  }
+
+  lt(other: Price): boolean {
+    return this.compare(other) < 0;
+  }
+
+  gt(other: Price): boolean {
+    return this.compare(other) > 0;
+  }
+
+  add(other: Price): Price {
+    return new Price(
+      this.numerator * other.denominator + other.numerator * this.denominator,
+      this.denominator * other.denominator
+    );
+  }
+
+  sub(other: Price): Price {
+    return new Price(
+      this.numerator * other.denominator - other.numerator * this.denominator,
+      this.denominator * other.denominator
+    );
+  }
+
+  mul(other: Price): Price {
+    return new Price(this.numerator * other.numerator, this.denominator * other.denominator);
+  }
+
+  div(other: Price): Price {
+    if (other.isZero()) {
+      throw new RangeError('Cannot divide by a zero price');
+    }
+    return new Price(this.numerator * other.denominator, this.denominator * other.numerator);
+  }
+
+  /**
+   * Exact percentage change from this price to `newPrice`, as a rational number of percent
+   */
+  percentChangeTo(newPrice: Price): Price {
+    if (this.isZero()) {
+      return Price.ZERO;
+    }
+    return newPrice.sub(this).div(this).mul(new Price(100n));
+  }
+
+  toString(): string {
+    return this.toDecimalString();
+  }
+}
@@ -7,54 +7,185 @@ import { ethers } from 'ethers';
 import { TokenInfo } from './types';
 import { Validator } from './validator';
 import { Cache } from './cache';
-import { FeeExceedsAmountError } from './errors';
-import { ONE_ETHER, SLIPPAGE_DENOMINATOR, TOKEN_INFO_CACHE_TTL } from './constants';
+import { AmountOutOfRangeError, FeeExceedsAmountError, QuoteReturnedZeroError } from './errors';
+import { Price } from './price';
+import { decodeTokenInfo } from './tokenInfo';
+import { MAX_UINT256, ONE_ETHER, TOKEN_INFO_CACHE_TTL } from './constants';
+import { applySlippage } from './utils';
+import { FourmemeVersion, tokenInfoFromHelper } from './version';
 
 export interface PriceInfo {
   tokenAmount: bigint;
   bnbCost: bigint;
-  pricePerToken: bigint;
+  pricePerToken: bigint; // BNB per token scaled by 1e18, truncated (rounds to zero for very cheap tokens)
+  price: Price; // Exact BNB per token, prefer this for comparisons and ratio math
   fee: bigint;
+  grossTokenAmount?: bigint; // Buy quotes only: tokens the full BNB amount would buy before the fee
+  netTokenAmount?: bigint; // Buy quotes only: tokens actually received after the fee (same as tokenAmount)
+  willPartiallyFill?: boolean; // Buy quotes only: the spend overshoots the curve, buyTokenAMAP takes the refund path
+  fillableAmount?: bigint; // Buy quotes only: BNB (fee included) the curve can still absorb
 }
 
+export interface QuoteOptions {
+  allowDustFills?: boolean; // Return quotes of zero tokens (buys) or zero BNB (sells) instead of throwing QuoteReturnedZeroError
+  blockTag?: number; // Quote against the state at this block; the token info cache is neither read nor written
+}
+
+/**
+ * Spends a buy may send. The manager caps no spend: AMAP buys refund whatever the curve can't absorb.
+ */
+export interface BuyLimits {
+  minFunds: bigint; // Smallest spend (wei) the trading fee doesn't swallow: the template's minimum fee plus one wei
+  maxFunds: bigint; // Largest spend (wei); MAX_UINT256, as the manager enforces no maximum
+}
+
+export interface PartialFill {
+  willPartiallyFill: boolean;
+  fillableAmount: bigint; // BNB (fee included) the curve can still absorb; equals the spend when it fits
+}
+
+/**
+ * BNB (after fee) still needed for the curve to reach its raise target and graduate
+ */
+export function bnbUntilGraduation(tokenInfo: TokenInfo): bigint {
+  return tokenInfo.maxRaising > tokenInfo.funds ? tokenInfo.maxRaising - tokenInfo.funds : 0n;
+}
+
+/**
+ * Whether a buy of `bnbAmount` (paying `fee`) overshoots the remaining curve.
+ * The fillable amount scales the fee pro rata to the part of the spend the curve absorbs.
+ */
+export function detectPartialFill(tokenInfo: TokenInfo, bnbAmount: bigint, fee: bigint): PartialFill {
+  const fundsAfterFee = bnbAmount - fee;
+  const remaining = bnbUntilGraduation(tokenInfo);
+
+  if (fundsAfterFee <= remaining) {
+    return { willPartiallyFill: false, fillableAmount: bnbAmount };
+  }
+
+  const fillableFee = fundsAfterFee > 0n ? (fee * remaining) / fundsAfterFee : 0n;
+  return { willPartiallyFill: true, fillableAmount: remaining + fillableFee };
+}
+
+export interface BuyCostBreakdown {
+  principal: bigint; // BNB that goes into the curve (spend minus fee)
+  fee: bigint; // Platform trading fee
+  gasCost: bigint; // Estimated gas cost
+  total: bigint; // principal + fee + gasCost, the BNB leaving the wallet
+  expectedTokens: bigint; // Tokens quoted for the spend
+  minTokens: bigint; // Fewest tokens accepted at the given slippage
+  willPartiallyFill: boolean; // The spend overshoots the curve; the unfilled part is refunded
+}
+
+/**
+ * Split a buy quote and its estimated gas into what the wallet pays for each part
+ */
+export function buyCostBreakdown(quote: PriceInfo, gasCost: bigint, slippagePercent: number): BuyCostBreakdown {
+  return {
+    principal: quote.bnbCost - quote.fee,
+    fee: quote.fee,
+    gasCost,
+    total: quote.bnbCost + gasCost,
+    expectedTokens: quote.tokenAmount,
+    minTokens: applySlippage(quote.tokenAmount, slippagePercent),
+    willPartiallyFill: quote.willPartiallyFill ?? false,
+  };
+}
+
+/**
+ * Assemble a buy quote from the contract's fee and buy amounts for `bnbAmount`
+ */
+export function buyPriceInfo(
+  tokenInfo: TokenInfo,
+  bnbAmount: bigint,
+  fee: bigint,
+  grossTokenAmount: bigint,
+  tokenAmount: bigint
+): PriceInfo {
+  if (fee >= bnbAmount) {
+    throw new FeeExceedsAmountError(fee, bnbAmount);
+  }
+  const pricePerToken: bigint = tokenAmount > 0n ? (bnbAmount * ONE_ETHER) / tokenAmount : 0n;
+  const price = Price.fromAmounts(bnbAmount, tokenAmount);
+
+  return {
+    tokenAmount,
+    bnbCost: bnbAmount,
+    pricePerToken,
+    price,
+    fee,
+    grossTokenAmount,
+    netTokenAmount: tokenAmount,
+    ...detectPartialFill(tokenInfo, bnbAmount, fee),
+  };
+}
+
+/**
+ * Buy limits for a template's minimum trading fee. The fee is the larger of the rate-based fee and
+ * the minimum, and a buy reverts unless its spend exceeds the fee.
+ */
+export function buyLimits(minTradingFee: bigint): BuyLimits {
+  return { minFunds: minTradingFee + 1n, maxFunds: MAX_UINT256 };
+}
+
+/**
+ * Throw AmountOutOfRangeError if `bnbAmount` is outside `limits`
+ */
+export function checkBuyLimits(tokenAddress: string, bnbAmount: bigint, limits: BuyLimits): void {
+  if (bnbAmount < limits.minFunds || bnbAmount > limits.maxFunds) {
+    throw new AmountOutOfRangeError(tokenAddress, bnbAmount, limits.minFunds, limits.maxFunds);
+  }
+}
+
+/**
+ * Why a buy of `tokenInfo` quoted zero tokens: nothing left on the curve, or a spend too small to buy one unit
+ */
+export function zeroBuyReason(tokenInfo: TokenInfo): 'curveFull' | 'roundsToZero' {
+  return tokenInfo.offers === 0n || bnbUntilGraduation(tokenInfo) === 0n ? 'curveFull' : 'roundsToZero';
+}
+
+/**
+ * The TokenManager version trading a token, e.g. FourTrading.detectVersion's
+ */
+export type VersionLookup = (tokenAddress: string) => Promise<FourmemeVersion>;
+
 export class PriceCalculator {
   private contract: ethers.Contract;
+  private helper?: ethers.Contract; // TokenManagerHelper3, which reads and quotes V1 tokens
+  private versionOf?: VersionLookup;
   private tokenInfoCache: Cache<string, TokenInfo>;
+  private minTradingFeeCache: Cache<string, bigint>; // By template id, or by token for V1 tokens
 
-  constructor(contract: ethers.Contract) {
+  /**
+   * Reads go to `contract` (the V2 manager) unless `versionOf` reports a V1 token, which is read
+   * and quoted through `helper` instead. Without both, every token is treated as V2.
+   */
+  constructor(contract: ethers.Contract, helper?: ethers.Contract, versionOf?: VersionLookup) {
     this.contract = contract;
+    this.helper = helper;
+    this.versionOf = versionOf;
     this.tokenInfoCache = new Cache({ defaultTTL: TOKEN_INFO_CACHE_TTL, maxSize: 500 });
+    this.minTradingFeeCache = new Cache({ defaultTTL: TOKEN_INFO_CACHE_TTL, maxSize: 100 });
+  }
+
+  private async isV1(tokenAddress: string): Promise<boolean> {
+    return this.helper !== undefined && this.versionOf !== undefined && (await this.versionOf(tokenAddress)) === 1;
   }
 
   /**
-   * Get token information with caching
+   * Get token information with caching. With `blockTag`, the cache is bypassed and the state is
+   * read at that block (the cache is then refreshed with it).
    */
-  async getTokenInfo(tokenAddress: string): Promise<TokenInfo> {
+  async getTokenInfo(tokenAddress: string, blockTag?: number): Promise<TokenInfo> {
     Validator.validateAddress(tokenAddress, 'tokenAddress');
 
     // Check cache first
-    const cached = this.tokenInfoCache.get(tokenAddress.toLowerCase());
+    const cached = blockTag === undefined ? this.tokenInfoCache.get(tokenAddress.toLowerCase()) : undefined;
     if (cached) {
       return cached;
     }
 
-    // Fetch from contract
-    const info = await this.contract._tokenInfos(tokenAddress);
-    const tokenInfo: TokenInfo = {
-      base: info.base,
-      quote: info.quote,
-      template: info.template,
-      totalSupply: info.totalSupply,
-      maxOffers: info.maxOffers,
-      maxRaising: info.maxRaising,
-      launchTime: info.launchTime,
-      offers: info.offers,
-      funds: info.funds,
-      lastPrice: info.lastPrice,
-      K: info.K,
-      T: info.T,
-      status: info.status,
-    };
+    const tokenInfo = await this.readTokenInfo(tokenAddress, blockTag);
 
     // Cache the result
     this.tokenInfoCache.set(tokenAddress.toLowerCase(), tokenInfo);
@@ -62,38 +193,128 @@ export class PriceCalculator {
     return tokenInfo;
   }
 
+  /**
+   * Token info for a quote: the cached info for latest-state quotes, an uncached read for pinned
+   * ones, so an older block never replaces fresher cached state
+   */
+  async quoteTokenInfo(tokenAddress: string, blockTag?: number): Promise<TokenInfo> {
+    return blockTag === undefined ? this.getTokenInfo(tokenAddress) : this.readTokenInfo(tokenAddress, blockTag);
+  }
+
+  private async readTokenInfo(tokenAddress: string, blockTag?: number): Promise<TokenInfo> {
+    if (await this.isV1(tokenAddress)) {
+      const info = await this.helper!.getTokenInfo(tokenAddress, { blockTag });
+      this.minTradingFeeCache.set(tokenAddress.toLowerCase(), info.minTradingFee);
+      return tokenInfoFromHelper(tokenAddress, info);
+    }
+    // Fetch raw so the struct layout can be checked before decoding
+    const returnData = await this.contract.runner!.call!({
+      to: this.contract.target,
+      data: this.contract.interface.encodeFunctionData('_tokenInfos', [tokenAddress]),
+      blockTag,
+    });
+    return decodeTokenInfo(returnData);
+  }
+
   /**
    * Quote buy price with validation
+   * The trading fee is taken from the BNB input first, so `tokenAmount` (= `netTokenAmount`)
+   * is what the buy delivers; `grossTokenAmount` is reported for comparison only.
+   * A quote of zero tokens throws QuoteReturnedZeroError unless `allowDustFills` is set.
    */
-  async quoteBuy(tokenAddress: string, bnbAmount: bigint): Promise<PriceInfo> {
+  async quoteBuy(tokenAddress: string, bnbAmount: bigint, options: QuoteOptions = {}): Promise<PriceInfo> {
     Validator.validateAddress(tokenAddress, 'tokenAddress');
     Validator.validateAmount(bnbAmount, 'bnbAmount');
 
-    const tokenInfo = await this.getTokenInfo(tokenAddress);
-    const fee: bigint = await this.contract.calcTradingFee(tokenInfo, bnbAmount);
+    const { blockTag } = options;
+    const tokenInfo = await this.quoteTokenInfo(tokenAddress, blockTag);
+    let fee: bigint;
+    let grossTokenAmount: bigint;
+    let tokenAmount: bigint;
+    if (await this.isV1(tokenAddress)) {
+      // The helper quotes what the spend buys after the fee; V1 quotes report no separate gross amount
+      const estimate = await this.helper!.tryBuy(tokenAddress, 0n, bnbAmount, { blockTag });
+      fee = estimate.estimatedFee;
+      if (fee >= bnbAmount) {
+        throw new FeeExceedsAmountError(fee, bnbAmount);
+      }
+      grossTokenAmount = tokenAmount = estimate.estimatedAmount;
+    } else {
+      fee = await this.contract.calcTradingFee(tokenInfo, bnbAmount, { blockTag });
+
+      // Validate fee doesn't exceed amount
+      if (fee >= bnbAmount) {
+        throw new FeeExceedsAmountError(fee, bnbAmount);
+      }
 
-    // Validate fee doesn't exceed amount
-    if (fee >= bnbAmount) {
-      throw new FeeExceedsAmountError(fee, bnbAmount);
+      const fundsAfterFee: bigint = bnbAmount - fee;
+      [grossTokenAmount, tokenAmount] = await Promise.all([
+        this.contract.calcBuyAmount(tokenInfo, bnbAmount, { blockTag }),
+        this.contract.calcBuyAmount(tokenInfo, fundsAfterFee, { blockTag }),
+      ]);
+    }
+    if (tokenAmount === 0n && !options.allowDustFills) {
+      throw new QuoteReturnedZeroError('buy', tokenAddress, bnbAmount, zeroBuyReason(tokenInfo));
     }
+    return buyPriceInfo(tokenInfo, bnbAmount, fee, grossTokenAmount, tokenAmount);
+  }
 
-    const fundsAfterFee: bigint = bnbAmount - fee;
-    const tokenAmount: bigint = await this.contract.calcBuyAmount(tokenInfo, fundsAfterFee);
-    const pricePerToken: bigint = tokenAmount > 0n ? (bnbAmount * ONE_ETHER) / tokenAmount : 0n;
+  /**
+   * Smallest and largest spend a buy of the token may send, from its template's minimum trading fee
+   */
+  async getBuyLimits(tokenAddress: string): Promise<BuyLimits> {
+    const tokenInfo = await this.getTokenInfo(tokenAddress);
+    if (await this.isV1(tokenAddress)) {
+      // Cached alongside the token's info, which the helper reports together
+      const minTradingFee = this.minTradingFeeCache.get(tokenAddress.toLowerCase())
+        ?? (await this.helper!.getTokenInfo(tokenAddress)).minTradingFee as bigint;
+      return buyLimits(minTradingFee);
+    }
+    const key = tokenInfo.template.toString();
+    let minTradingFee = this.minTradingFeeCache.get(key);
+    if (minTradingFee === undefined) {
+      minTradingFee = (await this.contract._templates(tokenInfo.template)).minTradingFee as bigint;
+      this.minTradingFeeCache.set(key, minTradingFee);
+    }
+    return buyLimits(minTradingFee);
+  }
 
-    return { tokenAmount, bnbCost: bnbAmount, pricePerToken, fee };
+  /**
+   * Check whether a buy would overshoot the curve without quoting token amounts
+   */
+  async checkPartialFill(tokenAddress: string, bnbAmount: bigint): Promise<PartialFill> {
+    const tokenInfo = await this.getTokenInfo(tokenAddress);
+    const fee: bigint = await this.isV1(tokenAddress)
+      ? (await this.helper!.tryBuy(tokenAddress, 0n, bnbAmount)).estimatedFee
+      : await this.contract.calcTradingFee(tokenInfo, bnbAmount);
+    return detectPartialFill(tokenInfo, bnbAmount, fee);
   }
 
   /**
    * Quote sell price with validation
+   * Amounts too small to return any BNB throw QuoteReturnedZeroError unless `allowDustFills` is set
    */
-  async quoteSell(tokenAddress: string, tokenAmount: bigint): Promise<PriceInfo> {
+  async quoteSell(tokenAddress: string, tokenAmount: bigint, options: QuoteOptions = {}): Promise<PriceInfo> {
     Validator.validateAddress(tokenAddress, 'tokenAddress');
     Validator.validateAmount(tokenAmount, 'tokenAmount');
 
-    const tokenInfo = await this.getTokenInfo(tokenAddress);
-    const bnbBeforeFee: bigint = await this.contract.calcSellCost(tokenInfo, tokenAmount);
-    const fee: bigint = await this.contract.calcTradingFee(tokenInfo, bnbBeforeFee);
+    const { blockTag } = options;
+    let bnbBeforeFee: bigint;
+    let fee: bigint | undefined;
+    let tokenInfo: TokenInfo | undefined;
+    if (await this.isV1(tokenAddress)) {
+      ({ funds: bnbBeforeFee, fee } = await this.helper!.trySell(tokenAddress, tokenAmount, { blockTag }));
+    } else {
+      tokenInfo = await this.quoteTokenInfo(tokenAddress, blockTag);
+      bnbBeforeFee = await this.contract.calcSellCost(tokenInfo, tokenAmount, { blockTag });
+    }
+    if (bnbBeforeFee === 0n) {
+      if (!options.allowDustFills) {
+        throw new QuoteReturnedZeroError('sell', tokenAddress, tokenAmount, 'roundsToZero');
+      }
+      return { tokenAmount, bnbCost: 0n, pricePerToken: 0n, price: Price.fromAmounts(0n, tokenAmount), fee: 0n };
+    }
+    fee ??= await this.contract.calcTradingFee(tokenInfo!, bnbBeforeFee, { blockTag }) as bigint;
 
     // Validate fee doesn't exceed proceeds
     if (fee >= bnbBeforeFee) {
@@ -102,22 +323,31 @@ export class PriceCalculator {
 
     const bnbCost: bigint = bnbBeforeFee - fee;
     const pricePerToken: bigint = tokenAmount > 0n ? (bnbCost * ONE_ETHER) / tokenAmount : 0n;
+    const price = Price.fromAmounts(bnbCost, tokenAmount);
 
-    return { tokenAmount, bnbCost, pricePerToken, fee };
+    return { tokenAmount, bnbCost, pricePerToken, price, fee };
   }
 
   /**
-   * Calculate cost to buy exact token amount
+   * Calculate cost to buy exact token amount. V1 tokens are costed by the helper at their current
+   * state, whatever `tokenInfo` holds.
    */
   async calcBuyCost(tokenInfo: TokenInfo, tokenAmount: bigint): Promise<PriceInfo> {
     Validator.validateAmount(tokenAmount, 'tokenAmount');
 
-    const bnbBeforeFee: bigint = await this.contract.calcBuyCost(tokenInfo, tokenAmount);
-    const fee: bigint = await this.contract.calcTradingFee(tokenInfo, bnbBeforeFee);
+    let bnbBeforeFee: bigint;
+    let fee: bigint;
+    if (await this.isV1(tokenInfo.base)) {
+      ({ estimatedCost: bnbBeforeFee, estimatedFee: fee } = await this.helper!.tryBuy(tokenInfo.base, tokenAmount, 0n));
+    } else {
+      bnbBeforeFee = await this.contract.calcBuyCost(tokenInfo, tokenAmount);
+      fee = await this.contract.calcTradingFee(tokenInfo, bnbBeforeFee);
+    }
     const bnbCost: bigint = bnbBeforeFee + fee;
     const pricePerToken: bigint = tokenAmount > 0n ? (bnbCost * ONE_ETHER) / tokenAmount : 0n;
+    const price = Price.fromAmounts(bnbCost, tokenAmount);
 
-    return { tokenAmount, bnbCost, pricePerToken, fee };
+    return { tokenAmount, bnbCost, pricePerToken, price, fee };
   }
 
   /**
@@ -127,11 +357,32 @@ export class PriceCalculator {
     Validator.validateAddress(tokenAddress, 'tokenAddress');
 
     const tokenInfo = await this.getTokenInfo(tokenAddress);
-    return await this.contract.calcLastPrice(tokenInfo);
+    return await this.isV1(tokenAddress) ? tokenInfo.lastPrice : await this.contract.calcLastPrice(tokenInfo);
+  }
+
+  /**
+   * Tokens `funds` buys on `tokenInfo`'s curve before the fee (calcBuyAmount). V1 tokens are quoted
+   * by the helper at their current state and net of the fee, so compare V1 amounts only with each other.
+   */
+  async calcBuyAmount(tokenInfo: TokenInfo, funds: bigint): Promise<bigint> {
+    return await this.isV1(tokenInfo.base)
+      ? (await this.helper!.tryBuy(tokenInfo.base, 0n, funds)).estimatedAmount
+      : await this.contract.calcBuyAmount(tokenInfo, funds);
+  }
+
+  /**
+   * BNB selling `amount` returns on `tokenInfo`'s curve before the fee (calcSellCost). V1 tokens are
+   * quoted by the helper at their current state.
+   */
+  async calcSellCost(tokenInfo: TokenInfo, amount: bigint): Promise<bigint> {
+    return await this.isV1(tokenInfo.base)
+      ? (await this.helper!.trySell(tokenInfo.base, amount)).funds
+      : await this.contract.calcSellCost(tokenInfo, amount);
   }
 
   /**
    * Estimate minimum tokens with slippage protection
+   * Slippage is applied to the net (post-fee) token amount, i.e. what the buy actually delivers
    */
   async estimateBuySlippage(tokenAddress: string, bnbAmount: bigint, slippagePercent: number): Promise<bigint> {
     Validator.validateAddress(tokenAddress, 'tokenAddress');
@@ -139,8 +390,7 @@ export class PriceCalculator {
     Validator.validateSlippage(slippagePercent);
 
     const priceInfo = await this.quoteBuy(tokenAddress, bnbAmount);
-    const slippageFactor = BigInt(Math.floor((100 - slippagePercent) * 100));
-    return (priceInfo.tokenAmount * slippageFactor) / SLIPPAGE_DENOMINATOR;
+    return applySlippage(priceInfo.tokenAmount, slippagePercent);
   }
 
   /**
@@ -152,8 +402,7 @@ export class PriceCalculator {
     Validator.validateSlippage(slippagePercent);
 
     const priceInfo = await this.quoteSell(tokenAddress, tokenAmount);
-    const slippageFactor = BigInt(Math.floor((100 - slippagePercent) * 100));
-    return (priceInfo.bnbCost * slippageFactor) / SLIPPAGE_DENOMINATOR;
+    return applySlippage(priceInfo.bnbCost, slippagePercent);
   }
 
   /**
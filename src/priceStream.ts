@@ -0,0 +1,42 @@
+/**
+ * Price stream helpers
+ * Trade events carry the post-trade price, so the price stream is one update per trade
+ */
+
+import { SLIPPAGE_DENOMINATOR } from './constants';
+
+export interface PriceUpdate {
+  token: string;
+  price: bigint; // BNB per token scaled by 1e18, as emitted by trade events
+  side: 'buy' | 'sell'; // Trade that moved the price
+  timestamp: number; // Local receive time (ms since epoch)
+}
+
+export type PriceListener = (update: PriceUpdate) => void;
+
+/**
+ * Passes a price only when it moved at least `minChangeBps` from the last price it passed.
+ * The first price always passes, and a zero threshold passes everything. Comparing against the last emission rather than the last
+ * trade means a slow drift is still reported once it adds up to the threshold.
+ */
+export class PriceChangeFilter {
+  private last: bigint | null = null;
+
+  constructor(private minChangeBps: number) {
+    if (!Number.isInteger(minChangeBps) || minChangeBps < 0) {
+      throw new RangeError('minChangeBps must be a non-negative integer');
+    }
+  }
+
+  accept(price: bigint): boolean {
+    const last = this.last;
+    if (last !== null) {
+      const move = price > last ? price - last : last - price;
+      if (last === 0n ? move === 0n && this.minChangeBps > 0 : move * SLIPPAGE_DENOMINATOR < BigInt(this.minChangeBps) * last) {
+        return false;
+      }
+    }
+    this.last = price;
+    return true;
+  }
+}
@@ -0,0 +1,115 @@
+/**
+ * Reference quote comparison
+ * Quotes every buy/sell on a secondary provider as well and reports disagreements,
+ * without letting the secondary slow down or break the primary quote. Both sides of a
+ * comparison are quoted at the same block, so provider lag can't show up as a divergence.
+ */
+
+import { PriceInfo, QuoteOptions } from './priceCalculator';
+import { bpsOf } from './math';
+
+export interface QuoteDivergence {
+  side: 'buy' | 'sell';
+  token: string;
+  amount: bigint; // BNB in for buys, tokens in for sells
+  primary: bigint; // Quoted output: tokens for buys, BNB for sells
+  secondary: bigint;
+  divergenceBps: number; // |primary - secondary| relative to the secondary quote
+  blockTag: number; // Block both quotes were taken at: the older of the two heads
+  primaryBlock: number; // Head of each provider when the comparison started
+  secondaryBlock: number;
+  timestamp: number; // ms
+}
+
+export type QuoteDivergenceListener = (divergence: QuoteDivergence) => void;
+
+export interface Quoter {
+  quoteBuy(tokenAddress: string, bnbAmount: bigint, options?: QuoteOptions): Promise<PriceInfo>;
+  quoteSell(tokenAddress: string, tokenAmount: bigint, options?: QuoteOptions): Promise<PriceInfo>;
+}
+
+export interface QuoteComparisonOptions {
+  thresholdBps: number; // Report divergences above this
+  budgetMs: number; // Longest the primary quote waits on the secondary after resolving
+}
+
+/**
+ * Divergence between two quoted outputs in basis points of the reference
+ */
+export function quoteDivergenceBps(primary: bigint, secondary: bigint): number {
+  if (secondary === 0n) {
+    return primary === 0n ? 0 : Number.POSITIVE_INFINITY;
+  }
+  const diff = primary > secondary ? primary - secondary : secondary - primary;
+  return bpsOf(diff, secondary, 'quote divergence');
+}
+
+export class QuoteComparator {
+  constructor(
+    private secondary: Quoter,
+    private primaryBlock: () => Promise<number>,
+    private secondaryBlock: () => Promise<number>,
+    private options: QuoteComparisonOptions,
+    private onDivergence: QuoteDivergenceListener
+  ) {}
+
+  /**
+   * Return `primary()`'s latest-state quote while comparing the two providers concurrently.
+   * The comparison reads both heads, then quotes both sides at the older one through `primary(blockTag)`
+   * and the secondary. Once the primary resolves, the comparison gets at most `budgetMs` more before
+   * the primary is returned; a late comparison still reports when it finishes. Comparison errors are ignored.
+   */
+  async quote(side: 'buy' | 'sell', token: string, amount: bigint, primary: (blockTag?: number) => Promise<PriceInfo>): Promise<PriceInfo> {
+    const primaryQuote = primary();
+    const comparison = this.compare(side, token, amount, primary);
+
+    const result = await primaryQuote;
+
+    let timer: NodeJS.Timeout | undefined;
+    await Promise.race([
+      comparison,
+      new Promise<void>((resolve) => {
+        timer = setTimeout(resolve, this.options.budgetMs);
+      }),
+    ]);
+    clearTimeout(timer);
+
+    return result;
+  }
+
+  private async compare(side: 'buy' | 'sell', token: string, amount: bigint, primaryAt: (blockTag: number) => Promise<PriceInfo>): Promise<void> {
+    try {
+      const [primaryBlock, secondaryBlock] = await Promise.all([this.primaryBlock(), this.secondaryBlock()]);
+      // The older head is one both providers have
+      const blockTag = Math.min(primaryBlock, secondaryBlock);
+      const [primary, secondary] = await Promise.all([
+        primaryAt(blockTag),
+        side === 'buy'
+          ? this.secondary.quoteBuy(token, amount, { blockTag })
+          : this.secondary.quoteSell(token, amount, { blockTag }),
+      ]);
+
+      const primaryOut = side === 'buy' ? primary.tokenAmount : primary.bnbCost;
+      const secondaryOut = side === 'buy' ? secondary.tokenAmount : secondary.bnbCost;
+      const divergenceBps = quoteDivergenceBps(primaryOut, secondaryOut);
+
+      if (divergenceBps > this.options.thresholdBps) {
+        this.onDivergence({
+          side,
+          token,
+          amount,
+          primary: primaryOut,
+          secondary: secondaryOut,
+          divergenceBps,
+          blockTag,
+          primaryBlock,
+          secondaryBlock,
+          timestamp: Date.now(),
+        });
+      }
+    } catch {
+      // Best effort: the returned primary quote reports its own failures; without both heads or
+      // both pinned quotes there is nothing to compare
+    }
+  }
+}
@@ -0,0 +1,28 @@
+/**
+ * Quotes: read-only pricing against a node
+ * Imported separately (`@fnzero/four-trading-sdk/quotes`) for environments that quote but never trade.
+ *
+ * Everything in `core`, plus PriceCalculator and the Multicall3 readers. These need a read-only
+ * provider, e.g. `new ethers.Contract(FOUR_MEME_ADDRESS, getFourTradingInterface(), provider)`, but
+ * nothing here subscribes to events, signs or touches the filesystem.
+ */
+
+export * from '../core';
+export {
+  PriceCalculator,
+  PriceInfo,
+  PartialFill,
+  QuoteOptions,
+  BuyCostBreakdown,
+  BuyLimits,
+  bnbUntilGraduation,
+  detectPartialFill,
+  buyCostBreakdown,
+  buyPriceInfo,
+  zeroBuyReason,
+  buyLimits,
+  checkBuyLimits,
+} from '../priceCalculator';
+export { MulticallCall, MulticallResult, MulticallExecutor, BuyQuoteRead, aggregate3, readAllowances, readTokenInfos, readBuyQuotes } from '../multicall';
+export { SlippageSuggestion, SlippageSuggestionOptions, netBuyVolumePerBlock, suggestSlippage } from '../slippageSuggestion';
+export { QuoteReturnedZeroError, AmountOutOfRangeError } from '../errors';
@@ -0,0 +1,53 @@
+/**
+ * Raw contract calls
+ * The four.meme contracts gain functions faster than the SDK wraps them. These helpers turn a
+ * human-readable signature into a function fragment and encode or decode a call to it, so rawCall
+ * and rawSend can reach any function while keeping the trader's gas, nonce and journal handling.
+ */
+
+import { FunctionFragment, Interface } from 'ethers';
+import { ValidationError } from './errors';
+
+/**
+ * A function to call: a human-readable signature such as "buyToken(address,uint256,uint256)" or
+ * "function _tokenInfos(address) view returns (address, uint256)", or a parsed FunctionFragment
+ */
+export type RawFunction = string | FunctionFragment;
+
+/**
+ * The fragment of `fn`; the `function` keyword of a signature is optional
+ */
+export function parseRawFunction(fn: RawFunction): FunctionFragment {
+  if (typeof fn !== 'string') {
+    return fn;
+  }
+  const signature = fn.trim();
+  try {
+    return FunctionFragment.from(/^function\s/.test(signature) ? signature : `function ${signature}`);
+  } catch (error: any) {
+    throw new ValidationError(`Invalid function signature "${fn}": ${error?.shortMessage ?? error?.message ?? error}`);
+  }
+}
+
+/**
+ * Calldata for `fragment` with `args`; arguments that don't fit its inputs throw ValidationError
+ */
+export function encodeRawCall(fragment: FunctionFragment, args: readonly unknown[]): string {
+  try {
+    return new Interface([fragment]).encodeFunctionData(fragment, args);
+  } catch (error: any) {
+    throw new ValidationError(`Arguments don't match ${fragment.format()}: ${error?.shortMessage ?? error?.message ?? error}`);
+  }
+}
+
+/**
+ * Decode what a call to `fragment` returned: a single output as is, several as an ethers Result,
+ * and the return data unchanged when the fragment declares no outputs
+ */
+export function decodeRawResult(fragment: FunctionFragment, data: string): unknown {
+  if (fragment.outputs.length === 0) {
+    return data;
+  }
+  const result = new Interface([fragment]).decodeFunctionResult(fragment, data);
+  return fragment.outputs.length === 1 ? result[0] : result;
+}
@@ -0,0 +1,68 @@
+/**
+ * Read-your-writes consistency
+ * Load-balanced RPCs can route a read to a replica that hasn't seen the block a trade landed in;
+ * these helpers pin reads to a block at or after a minimum
+ */
+
+import { ReplicaLaggingError } from './errors';
+
+export interface ReplicaLagEvent {
+  minBlock: number; // Block the read had to observe
+  observedBlock: number; // Block the read was finally made at
+  retries: number; // Attempts that hit a lagging replica before it succeeded
+  waitedMs: number;
+}
+
+export type ReplicaLagListener = (event: ReplicaLagEvent) => void;
+
+export interface ReadAtLeastOptions {
+  retries: number; // Attempts after the first before giving up
+  intervalMs: number;
+  onLag?: ReplicaLagListener; // Called once the read succeeds, if any retry was needed
+}
+
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+/**
+ * Run `read` pinned to a block at or after `minBlock`. Each attempt asks the node for its latest
+ * block; while that is behind `minBlock`, or the read at that block fails (another replica not
+ * having it yet), it waits and retries. Throws ReplicaLaggingError when the node never catches up,
+ * or the last read error if the node caught up but reads kept failing.
+ */
+export async function readAtLeast<T>(
+  minBlock: number,
+  getBlockNumber: () => Promise<number>,
+  read: (blockTag: number) => Promise<T>,
+  options: ReadAtLeastOptions
+): Promise<T> {
+  const started = Date.now();
+  let observedBlock = -1;
+  let readError: unknown;
+
+  for (let attempt = 0; attempt <= options.retries; attempt++) {
+    if (attempt > 0) {
+      await sleep(options.intervalMs);
+    }
+
+    observedBlock = await getBlockNumber();
+    if (observedBlock < minBlock) {
+      readError = undefined;
+      continue;
+    }
+
+    try {
+      const value = await read(observedBlock);
+      if (attempt > 0) {
+        options.onLag?.({ minBlock, observedBlock, retries: attempt, waitedMs: Date.now() - started });
+      }
+      return value;
+    } catch (error) {
+      readError = error;
+    }
+  }
+
+  if (readError !== undefined) {
+    throw readError;
+  }
+  throw new ReplicaLaggingError(minBlock, observedBlock);
+}
@@ -0,0 +1,21 @@
+/**
+ * Record and replay of JSON-RPC sessions
+ * Imported separately (`@fnzero/four-trading-sdk/record-replay`) so the main entry point never loads it.
+ *
+ * Record a session by giving FourTrading recording providers, then reproduce it offline by giving a
+ * new trader replay providers over the same journal. A replayed request that the recording never
+ * answered fails with RpcDivergenceError showing the expected and actual requests.
+ */
+
+export { RpcTransport, RpcJournalEntry, RpcExchange, RpcRecorder, parseJournal, readJournal } from './journal';
+export { RpcReplay, RpcReplayOptions, NotificationSink, firstDifference } from './replay';
+export {
+  RecordingJsonRpcProvider,
+  ReplayJsonRpcProvider,
+  RecordingWebSocket,
+  ReplayWebSocket,
+  createRecordingJsonRpcProvider,
+  createRecordingWebSocketProvider,
+  createReplayWebSocketProvider,
+} from './providers';
+export { RpcDivergenceError } from '../errors';
@@ -0,0 +1,63 @@
+/**
+ * RPC journal
+ * One JSON line per JSON-RPC exchange, in the order responses arrived
+ */
+
+import * as fs from 'fs';
+
+export type RpcTransport = 'http' | 'ws';
+
+export interface RpcJournalEntry {
+  seq: number; // Position in the recording
+  time: number; // Wall-clock time the response arrived (Unix ms)
+  transport: RpcTransport;
+  method: string; // 'eth_subscription' for pushed notifications
+  params?: unknown; // Request params; absent for notifications
+  result?: unknown;
+  error?: unknown; // JSON-RPC error object returned by the node
+  failure?: string; // Transport failure (no JSON-RPC response at all)
+  subscription?: string; // Notifications only: the subscription id they were pushed to
+}
+
+export type RpcExchange = Omit<RpcJournalEntry, 'seq' | 'time'>;
+
+/**
+ * Collects exchanges in memory and, with a file, appends each one as it happens so the
+ * journal survives a crash of the session being recorded
+ */
+export class RpcRecorder {
+  private entries: RpcJournalEntry[] = [];
+  private file?: string;
+
+  constructor(file?: string) {
+    this.file = file;
+    if (file) {
+      fs.writeFileSync(file, '');
+    }
+  }
+
+  record(exchange: RpcExchange): RpcJournalEntry {
+    const entry: RpcJournalEntry = { seq: this.entries.length, time: Date.now(), ...exchange };
+    this.entries.push(entry);
+    if (this.file) {
+      fs.appendFileSync(this.file, `${JSON.stringify(entry)}\n`);
+    }
+    return entry;
+  }
+
+  getEntries(): RpcJournalEntry[] {
+    return this.entries.map((entry) => ({ ...entry }));
+  }
+}
+
+export function parseJournal(text: string): RpcJournalEntry[] {
+  return text
+    .split('\n')
+    .filter((line) => line.trim() !== '')
+    .map((line) => JSON.parse(line) as RpcJournalEntry)
+    .sort((a, b) => a.seq - b.seq);
+}
+
+export async function readJournal(file: string): Promise<RpcJournalEntry[]> {
+  return parseJournal(await fs.promises.readFile(file, 'utf8'));
+}
@@ -0,0 +1,235 @@
+/**
+ * Recording and replaying providers
+ * Both are ordinary ethers providers, so FourTrading and its event subscriptions run on them unchanged
+ */
+
+import {
+  JsonRpcApiProviderOptions,
+  JsonRpcPayload,
+  JsonRpcProvider,
+  JsonRpcResult,
+  Networkish,
+  WebSocketLike,
+  WebSocketProvider,
+} from 'ethers';
+import { ConnectionConfig, createJsonRpcProvider, createWebSocket } from '../connection';
+import { RpcExchange, RpcJournalEntry, RpcRecorder } from './journal';
+import { RpcReplay } from './replay';
+
+const WS_OPEN = 1;
+const WS_CLOSED = 3;
+
+// ethers merges identical requests made within 250ms, which would make call counts depend on timing
+const UNCACHED: JsonRpcApiProviderOptions = { cacheTimeout: -1 };
+
+function asArray(payload: JsonRpcPayload | Array<JsonRpcPayload>): JsonRpcPayload[] {
+  return Array.isArray(payload) ? payload : [payload];
+}
+
+/**
+ * The recorded outcome of an exchange, as a JSON-RPC response carrying `id`
+ */
+function responseFor(id: number, entry: RpcJournalEntry): any {
+  if (entry.failure !== undefined) {
+    throw new Error(entry.failure);
+  }
+  return entry.error !== undefined ? { jsonrpc: '2.0', id, error: entry.error } : { jsonrpc: '2.0', id, result: entry.result };
+}
+
+function exchangeOf(response: any): Pick<RpcExchange, 'result' | 'error'> {
+  return 'error' in response ? { error: response.error } : { result: response.result };
+}
+
+/**
+ * HTTP provider that forwards every request to `upstream` and journals the exchange
+ */
+export class RecordingJsonRpcProvider extends JsonRpcProvider {
+  constructor(
+    private upstream: JsonRpcProvider,
+    private recorder: RpcRecorder,
+    network?: Networkish,
+    options?: JsonRpcApiProviderOptions
+  ) {
+    super(undefined, network, { ...UNCACHED, ...options });
+  }
+
+  async _send(payload: JsonRpcPayload | Array<JsonRpcPayload>): Promise<Array<JsonRpcResult>> {
+    const requests = asArray(payload);
+    let responses: any[];
+    try {
+      responses = await this.upstream._send(payload);
+    } catch (error: any) {
+      for (const request of requests) {
+        this.recorder.record({ transport: 'http', method: request.method, params: request.params, failure: error.message });
+      }
+      throw error;
+    }
+
+    for (const request of requests) {
+      const response = responses.find((candidate) => candidate.id === request.id);
+      if (response) {
+        this.recorder.record({ transport: 'http', method: request.method, params: request.params, ...exchangeOf(response) });
+      }
+    }
+    return responses;
+  }
+
+  destroy(): void {
+    this.upstream.destroy();
+    super.destroy();
+  }
+}
+
+/**
+ * HTTP provider answering from a recording; requests never touch the network
+ */
+export class ReplayJsonRpcProvider extends JsonRpcProvider {
+  constructor(private replay: RpcReplay, network?: Networkish, options?: JsonRpcApiProviderOptions) {
+    super(undefined, network, { ...UNCACHED, ...options });
+  }
+
+  async _send(payload: JsonRpcPayload | Array<JsonRpcPayload>): Promise<Array<JsonRpcResult>> {
+    return asArray(payload).map((request) =>
+      responseFor(request.id, this.replay.serve('http', request.method, request.params))
+    );
+  }
+}
+
+/**
+ * WebSocket that journals requests, responses and subscription notifications passing through `socket`
+ */
+export class RecordingWebSocket implements WebSocketLike {
+  private requests: Map<number, JsonRpcPayload> = new Map();
+  private messageHandler: WebSocketLike['onmessage'] = null;
+
+  constructor(private socket: WebSocketLike, private recorder: RpcRecorder) {}
+
+  get onopen(): WebSocketLike['onopen'] {
+    return this.socket.onopen;
+  }
+
+  set onopen(handler: WebSocketLike['onopen']) {
+    this.socket.onopen = handler;
+  }
+
+  get onerror(): WebSocketLike['onerror'] {
+    return this.socket.onerror;
+  }
+
+  set onerror(handler: WebSocketLike['onerror']) {
+    this.socket.onerror = handler;
+  }
+
+  get onmessage(): WebSocketLike['onmessage'] {
+    return this.messageHandler;
+  }
+
+  set onmessage(handler: WebSocketLike['onmessage']) {
+    this.messageHandler = handler;
+    this.socket.onmessage = handler
+      ? (message: { data: unknown }) => {
+          this.observe(String(message.data));
+          handler(message);
+        }
+      : null;
+  }
+
+  get readyState(): number {
+    return this.socket.readyState;
+  }
+
+  send(payload: any): void {
+    const request: JsonRpcPayload = JSON.parse(String(payload));
+    this.requests.set(request.id, request);
+    this.socket.send(payload);
+  }
+
+  close(code?: number, reason?: string): void {
+    this.socket.close(code, reason);
+  }
+
+  private observe(data: string): void {
+    const message = JSON.parse(data);
+    if (message.method === 'eth_subscription') {
+      this.recorder.record({
+        transport: 'ws',
+        method: message.method,
+        subscription: message.params.subscription,
+        result: message.params.result,
+      });
+      return;
+    }
+
+    const request = this.requests.get(message.id);
+    if (request) {
+      this.requests.delete(message.id);
+      this.recorder.record({ transport: 'ws', method: request.method, params: request.params, ...exchangeOf(message) });
+    }
+  }
+}
+
+/**
+ * WebSocket answering from a recording and pushing its notifications in recorded order
+ */
+export class ReplayWebSocket implements WebSocketLike {
+  onopen: WebSocketLike['onopen'] = null;
+  onmessage: WebSocketLike['onmessage'] = null;
+  onerror: WebSocketLike['onerror'] = null;
+  readyState = WS_OPEN;
+
+  constructor(private replay: RpcReplay) {
+    // The provider installs its handlers right after creating the socket
+    setTimeout(() => {
+      if (this.readyState !== WS_OPEN) {
+        return;
+      }
+      this.onopen?.();
+      this.replay.attach('ws', (entry) =>
+        this.deliver({
+          jsonrpc: '2.0',
+          method: 'eth_subscription',
+          params: { subscription: entry.subscription, result: entry.result },
+        })
+      );
+    }, 0);
+  }
+
+  send(payload: any): void {
+    const request: JsonRpcPayload = JSON.parse(String(payload));
+    this.deliver(responseFor(request.id, this.replay.serve('ws', request.method, request.params)));
+  }
+
+  close(): void {
+    this.readyState = WS_CLOSED;
+    this.replay.detach('ws');
+  }
+
+  private deliver(message: unknown): void {
+    setImmediate(() => {
+      if (this.readyState === WS_OPEN) {
+        this.onmessage?.({ data: JSON.stringify(message) });
+      }
+    });
+  }
+}
+
+/**
+ * HTTP provider recording every exchange with `url` into `recorder`
+ */
+export function createRecordingJsonRpcProvider(url: string, recorder: RpcRecorder, config?: ConnectionConfig): RecordingJsonRpcProvider {
+  return new RecordingJsonRpcProvider(createJsonRpcProvider(url, config), recorder);
+}
+
+/**
+ * WebSocket provider recording every exchange and notification with `url` into `recorder`
+ */
+export function createRecordingWebSocketProvider(url: string, recorder: RpcRecorder, config?: ConnectionConfig): WebSocketProvider {
+  return new WebSocketProvider(() => new RecordingWebSocket(createWebSocket(url, config), recorder), undefined, UNCACHED);
+}
+
+/**
+ * WebSocket provider answering from `replay`
+ */
+export function createReplayWebSocketProvider(replay: RpcReplay, network?: Networkish): WebSocketProvider {
+  return new WebSocketProvider(() => new ReplayWebSocket(replay), network, UNCACHED);
+}
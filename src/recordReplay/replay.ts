@@ -0,0 +1,177 @@
+/**
+ * Journal replay
+ * Serves recorded responses to matching requests and pushes recorded notifications once
+ * everything that preceded them in the recording has been replayed
+ */
+
+import { RpcDivergenceError } from '../errors';
+import { RpcJournalEntry, RpcTransport, readJournal } from './journal';
+
+export interface RpcReplayOptions {
+  // Answer a request repeated more often than in the recording with its last recorded response.
+  // Pollers and network checks run on timers, so their call counts drift between runs (default: true)
+  allowRepeats?: boolean;
+  // Methods served from the recording outside its order; ethers re-checks the chain id whenever
+  // it likes, so those calls are neither ordered nor required (default: eth_chainId, net_version)
+  unorderedMethods?: string[];
+}
+
+const DEFAULT_UNORDERED_METHODS = ['eth_chainId', 'net_version'];
+
+export type NotificationSink = (entry: RpcJournalEntry) => void;
+
+function requestKey(transport: RpcTransport, method: string, params: unknown): string {
+  return `${transport} ${method} ${JSON.stringify(params ?? [])}`;
+}
+
+function describeRequest(method: string, params: unknown): string {
+  return `${method} ${JSON.stringify(params ?? [])}`;
+}
+
+/**
+ * Path and values of the first place two JSON values differ, or null when they're equal
+ */
+export function firstDifference(expected: unknown, actual: unknown, path: string = 'params'): string | null {
+  if (typeof expected === 'object' && expected !== null && typeof actual === 'object' && actual !== null) {
+    const keys = new Set([...Object.keys(expected), ...Object.keys(actual)]);
+    for (const key of keys) {
+      const child = Array.isArray(expected) ? `${path}[${key}]` : `${path}.${key}`;
+      const difference = firstDifference((expected as any)[key], (actual as any)[key], child);
+      if (difference) {
+        return difference;
+      }
+    }
+    return null;
+  }
+  return expected === actual ? null : `${path}: expected ${JSON.stringify(expected)}, got ${JSON.stringify(actual)}`;
+}
+
+export class RpcReplay {
+  private entries: RpcJournalEntry[];
+  private consumed: Set<number> = new Set();
+  private lastServed: Map<string, RpcJournalEntry> = new Map();
+  private sinks: Map<RpcTransport, NotificationSink> = new Map();
+  private allowRepeats: boolean;
+  private unorderedMethods: Set<string>;
+  readonly divergences: RpcDivergenceError[] = [];
+
+  constructor(entries: RpcJournalEntry[], options: RpcReplayOptions = {}) {
+    this.entries = [...entries].sort((a, b) => a.seq - b.seq);
+    this.allowRepeats = options.allowRepeats ?? true;
+    this.unorderedMethods = new Set(options.unorderedMethods ?? DEFAULT_UNORDERED_METHODS);
+  }
+
+  static async load(file: string, options?: RpcReplayOptions): Promise<RpcReplay> {
+    return new RpcReplay(await readJournal(file), options);
+  }
+
+  /**
+   * Recorded exchange for a request. Identical requests are served in recorded order; a request
+   * the recording never answered throws RpcDivergenceError describing the nearest expected one.
+   */
+  serve(transport: RpcTransport, method: string, params: unknown): RpcJournalEntry {
+    const key = requestKey(transport, method, params);
+    const entry = this.entries.find(
+      (candidate) =>
+        !this.consumed.has(candidate.seq) &&
+        candidate.subscription === undefined &&
+        requestKey(candidate.transport, candidate.method, candidate.params) === key
+    );
+
+    if (entry) {
+      this.consumed.add(entry.seq);
+      this.lastServed.set(key, entry);
+      // After the caller has sent its response, so a subscription's id arrives before its first message
+      queueMicrotask(() => this.release());
+      return entry;
+    }
+
+    const repeated = this.lastServed.get(key);
+    if (repeated && this.allowRepeats) {
+      return repeated;
+    }
+
+    const error = this.divergence(transport, method, params);
+    this.divergences.push(error);
+    throw error;
+  }
+
+  /**
+   * Deliver `transport`'s notifications to `sink` as the replay reaches them
+   */
+  attach(transport: RpcTransport, sink: NotificationSink): void {
+    this.sinks.set(transport, sink);
+    this.release();
+  }
+
+  detach(transport: RpcTransport): void {
+    this.sinks.delete(transport);
+  }
+
+  /**
+   * Recorded exchanges not replayed yet
+   */
+  remaining(): RpcJournalEntry[] {
+    return this.entries.filter((entry) => !this.consumed.has(entry.seq) && this.isOrdered(entry));
+  }
+
+  /**
+   * Throw if the session diverged or stopped before replaying the whole recording
+   */
+  assertComplete(): void {
+    if (this.divergences.length > 0) {
+      throw this.divergences[0];
+    }
+    const left = this.remaining();
+    if (left.length > 0) {
+      const next = left[0];
+      throw new RpcDivergenceError(
+        `${left.length} recorded exchange(s) never replayed, starting with #${next.seq} (${next.transport}): ` +
+          describeRequest(next.method, next.params ?? next.subscription),
+        next.method,
+        next.seq
+      );
+    }
+  }
+
+  /**
+   * Push notifications, in recorded order, whose preceding requests have all been replayed
+   */
+  private release(): void {
+    for (const entry of this.entries) {
+      if (this.consumed.has(entry.seq) || !this.isOrdered(entry)) {
+        continue;
+      }
+      if (entry.subscription === undefined) {
+        return; // An earlier request is still outstanding
+      }
+      const sink = this.sinks.get(entry.transport);
+      if (!sink) {
+        return;
+      }
+      this.consumed.add(entry.seq);
+      sink(entry);
+    }
+  }
+
+  private isOrdered(entry: RpcJournalEntry): boolean {
+    return !this.unorderedMethods.has(entry.method);
+  }
+
+  private divergence(transport: RpcTransport, method: string, params: unknown): RpcDivergenceError {
+    const pending = this.remaining().filter((entry) => entry.transport === transport && entry.subscription === undefined);
+    const expected = pending.find((entry) => entry.method === method) ?? pending[0];
+    const actual = describeRequest(method, params);
+
+    if (!expected) {
+      return new RpcDivergenceError(`  recording has no further ${transport} requests\n+ ${actual}`, method);
+    }
+
+    const lines = [`- #${expected.seq} ${describeRequest(expected.method, expected.params)}`, `+ ${actual}`];
+    const difference = expected.method === method ? firstDifference(expected.params, params) : null;
+    if (difference) {
+      lines.push(`  first difference at ${difference}`);
+    }
+    return new RpcDivergenceError(lines.join('\n'), method, expected.seq);
+  }
+}
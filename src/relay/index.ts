@@ -0,0 +1,61 @@
+/**
+ * Relay: building and pricing transactions that are signed and submitted elsewhere
+ * Imported separately (`@fnzero/four-trading-sdk/relay`) by services that hand four.meme
+ * transactions to a private relay or bundle builder rather than sending them through the trader.
+ *
+ * Everything in `core`, plus raw contract calls, access lists, permits, replacement pricing,
+ * gas auctions, relay-fee attribution and the confirmation waiters. Nothing here opens a
+ * connection or holds a key: RPCs, signers and providers are passed in.
+ */
+
+export * from '../core';
+export { ExtraCost, TransactionResult, Cancellable } from '../types';
+export { PERMIT_ABI, getPermitInterface } from '../abi';
+export { RawFunction, parseRawFunction, encodeRawCall, decodeRawResult } from '../rawCall';
+export {
+  AccessListRequest,
+  AccessListRpc,
+  AccessListResult,
+  COLD_ACCOUNT_ACCESS_GAS,
+  COLD_SLOAD_GAS,
+  WARM_ACCESS_GAS,
+  ACCESS_LIST_ADDRESS_GAS,
+  ACCESS_LIST_STORAGE_KEY_GAS,
+  createAccessList,
+  accessListSavings,
+} from '../accessList';
+export {
+  GaslessApproval,
+  PERMIT_TYPES,
+  PermitDomain,
+  PermitMessage,
+  PermitRequest,
+  permitRequest,
+  recoverPermitSigner,
+  resolvePermitDomain,
+} from '../permit';
+export {
+  OwnPendingTx,
+  PreemptionAction,
+  PreemptionPolicy,
+  PreemptionDecision,
+  PreemptionOutcome,
+  minReplacementPrice,
+  replacementFees,
+  planPreemption,
+} from '../preemption';
+export {
+  GasAuction,
+  GasAuctionConfig,
+  GasAuctionDriver,
+  GasAuctionEvent,
+  GasAuctionResult,
+  CompetitorBid,
+  MempoolTransaction,
+  competitorBidFrom,
+} from '../gasAuction';
+export { ReplacementAttempt, totalExtraCost, attributeReplacementCosts, allocateExtraCost, firstReceipt } from '../extraCosts';
+export { ConfirmationStrategy, DEFAULT_CONFIRMATION, InclusionWatch, waitForFinalized, waitForConfirmations, waitForConfirmationsOrDrop } from '../confirmation';
+export { throwIfCancelled, waitUnlessCancelled } from '../cancellation';
+export { applyGasMultiplier, applySlippage, resolveMinAmount } from '../utils';
+export { GasConfigurationError, TransactionFailedError, TransactionDroppedError, CancelledError, GaslessApprovalUnsupportedError } from '../errors';
@@ -0,0 +1,77 @@
+/**
+ * Sandwich (front-run / back-run) detection
+ * Works on the four.meme trades decoded from one block, ordered by transaction index
+ */
+
+export interface BlockTrade {
+  txHash: string;
+  txIndex: number;
+  side: 'buy' | 'sell';
+  token: string;
+  account: string;
+  amount: bigint; // Tokens bought or sold
+  bnbAmount: bigint; // BNB spent (buy, fee included) or received (sell, fee deducted)
+}
+
+export interface SandwichReport {
+  txHash: string;
+  blockNumber: number;
+  token?: string; // Token traded by the analyzed transaction (undefined if it was not a four.meme trade)
+  sandwiched: boolean;
+  attacker?: string;
+  frontRunTx?: string;
+  backRunTx?: string;
+  valueExtracted: bigint; // Attacker's BNB profit (back-run proceeds minus front-run cost), 0n when not sandwiched
+}
+
+/**
+ * Find a buy-then-sell by another address bracketing the victim trade on the same token.
+ * The closest bracketing pair wins; value extracted is the attacker's BNB profit on the
+ * matched amount (a partial back-run only counts the pro-rata share of the front-run cost).
+ */
+export function detectSandwich(victim: BlockTrade, trades: BlockTrade[], blockNumber: number): SandwichReport {
+  const report: SandwichReport = {
+    txHash: victim.txHash,
+    blockNumber,
+    token: victim.token,
+    sandwiched: false,
+    valueExtracted: 0n,
+  };
+
+  const token = victim.token.toLowerCase();
+  const victimAccount = victim.account.toLowerCase();
+  const sameToken = trades.filter((trade) =>
+    trade.token.toLowerCase() === token && trade.account.toLowerCase() !== victimAccount
+  );
+
+  // Walk front-runs from the closest one outwards
+  const frontRuns = sameToken
+    .filter((trade) => trade.side === 'buy' && trade.txIndex < victim.txIndex)
+    .sort((a, b) => b.txIndex - a.txIndex);
+
+  for (const frontRun of frontRuns) {
+    const attacker = frontRun.account.toLowerCase();
+    const backRun = sameToken
+      .filter((trade) => trade.side === 'sell' && trade.txIndex > victim.txIndex && trade.account.toLowerCase() === attacker)
+      .sort((a, b) => a.txIndex - b.txIndex)[0];
+
+    if (!backRun) {
+      continue;
+    }
+
+    const matched = backRun.amount < frontRun.amount ? backRun.amount : frontRun.amount;
+    const cost = frontRun.amount > 0n ? (frontRun.bnbAmount * matched) / frontRun.amount : 0n;
+    const proceeds = backRun.amount > 0n ? (backRun.bnbAmount * matched) / backRun.amount : 0n;
+
+    return {
+      ...report,
+      sandwiched: true,
+      attacker: frontRun.account,
+      frontRunTx: frontRun.txHash,
+      backRunTx: backRun.txHash,
+      valueExtracted: proceeds - cost,
+    };
+  }
+
+  return report;
+}
@@ -0,0 +1,92 @@
+/**
+ * Anti-dump sell limit detection
+ * Some tokens revert transfers above a per-transaction (or per-period) amount. The ceiling is found
+ * by simulating the sell's transfer at increasing sizes, seeded with the cap the token reports when
+ * it exposes one.
+ */
+
+import { getSellLimitInterface } from './abi';
+import { SLIPPAGE_DENOMINATOR } from './constants';
+import { ValidationError } from './errors';
+import { MulticallExecutor, aggregate3 } from './multicall';
+
+/**
+ * Whether a simulated sell of `amount` goes through right now
+ */
+export type SellSizeProbe = (amount: bigint) => Promise<boolean>;
+
+/**
+ * Largest amount up to `balance` the probe accepts, within `precisionBps` of the balance (the
+ * result itself always passed the probe, or is 0n when none did). `balance` is tried first; then
+ * the `sharesBps` of it and the `hint` in increasing order, then a binary search between the
+ * largest accepted and smallest rejected size.
+ */
+export async function inferMaxSellable(
+  probe: SellSizeProbe,
+  balance: bigint,
+  sharesBps: readonly number[],
+  precisionBps: number,
+  hint?: bigint
+): Promise<bigint> {
+  if (balance < 0n) {
+    throw new ValidationError(`balance must not be negative, got ${balance}`);
+  }
+  if (balance === 0n || (await probe(balance))) {
+    return balance;
+  }
+
+  const sizes = sharesBps.map((bps) => (balance * BigInt(bps)) / SLIPPAGE_DENOMINATOR);
+  if (hint !== undefined) {
+    sizes.push(hint);
+  }
+  let passing = 0n;
+  let failing = balance;
+  for (const size of [...new Set(sizes)].sort((a, b) => (a < b ? -1 : a > b ? 1 : 0))) {
+    if (size <= passing || size >= failing) {
+      continue;
+    }
+    if (!(await probe(size))) {
+      failing = size;
+      break;
+    }
+    passing = size;
+  }
+
+  const step = (balance * BigInt(precisionBps)) / SLIPPAGE_DENOMINATOR;
+  const tolerance = step > 1n ? step : 1n;
+  while (failing - passing > tolerance) {
+    const middle = (passing + failing) / 2n;
+    if (await probe(middle)) {
+      passing = middle;
+    } else {
+      failing = middle;
+    }
+  }
+  return passing;
+}
+
+/**
+ * The smallest non-zero per-transaction cap `token` reports through a SELL_LIMIT_ABI getter, in one
+ * Multicall3 read, or undefined when it exposes none
+ */
+export async function readSellLimitHint(execute: MulticallExecutor, token: string): Promise<bigint | undefined> {
+  const iface = getSellLimitInterface();
+  const names = iface.fragments.map((fragment) => (fragment as { name: string }).name);
+  const results = await aggregate3(execute, names.map((name) => ({ target: token, callData: iface.encodeFunctionData(name) })));
+
+  let hint: bigint | undefined;
+  results.forEach((result, i) => {
+    if (!result.success) {
+      return;
+    }
+    try {
+      const [cap] = iface.decodeFunctionResult(names[i], result.returnData);
+      if (cap > 0n && (hint === undefined || cap < hint)) {
+        hint = cap;
+      }
+    } catch {
+      // Not a uint256; the token uses the name for something else
+    }
+  });
+  return hint;
+}
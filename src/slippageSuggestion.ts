@@ -0,0 +1,93 @@
+/**
+ * Data-driven slippage for buys
+ * A buy's minimum output is set from a quote, and other buys landing in the same block ahead of it
+ * push the price up before it executes. The recent trades of a token give the net BNB bought per
+ * block; the suggestion is the output a buy loses when a high percentile of that volume lands
+ * first, replayed on the token's curve so the buy's own size and the curve's depth are accounted for.
+ */
+
+import type { BackfillEvent } from './backfill';
+import {
+  DEFAULT_TRADING_FEE_BPS,
+  SLIPPAGE_DENOMINATOR,
+  SLIPPAGE_SUGGESTION_MIN_BPS,
+  SLIPPAGE_SUGGESTION_PERCENTILE,
+} from './constants';
+import { CurveParams, curveBuyAmount } from './curve';
+import { ValidationError } from './errors';
+import { checkedBps, mulDiv, toNumberChecked } from './math';
+import { Validator } from './validator';
+
+export interface SlippageSuggestionOptions {
+  percentile?: number; // Of per-block net buy volume, 1-100 (default: SLIPPAGE_SUGGESTION_PERCENTILE)
+  minBps?: number; // Floor of the suggestion (default: SLIPPAGE_SUGGESTION_MIN_BPS)
+  feeBps?: number; // Trading fee taken from the spend (default: DEFAULT_TRADING_FEE_BPS)
+}
+
+export interface SlippageSuggestion {
+  bps: number; // Suggested slippage
+  aheadBnb: bigint; // Net BNB bought ahead of the buy that the suggestion covers
+  blocksSampled: number; // Blocks with at least one trade
+  tradesSampled: number;
+}
+
+/**
+ * Net BNB bought on the curve in each block with trades (buys less sells, floored at zero), ascending
+ */
+export function netBuyVolumePerBlock(events: BackfillEvent[]): bigint[] {
+  const perBlock: Map<number, bigint> = new Map();
+  for (const { side, event, blockNumber } of events) {
+    const net = perBlock.get(blockNumber) ?? 0n;
+    perBlock.set(blockNumber, side === 'buy' ? net + event.cost : net - event.cost);
+  }
+  return [...perBlock.values()].map((net) => (net > 0n ? net : 0n)).sort((a, b) => (a < b ? -1 : a > b ? 1 : 0));
+}
+
+/**
+ * Slippage that keeps a buy of `bnbAmount` filling when the recent trades' `percentile` of net buy
+ * volume lands in its block first. Rounded up, at least `minBps`; 10000 when that volume would
+ * leave the buy nothing.
+ */
+export function suggestSlippage(
+  params: CurveParams,
+  bnbAmount: bigint,
+  events: BackfillEvent[],
+  options: SlippageSuggestionOptions = {}
+): SlippageSuggestion {
+  Validator.validateAmount(bnbAmount, 'bnbAmount');
+  const percentile = options.percentile ?? SLIPPAGE_SUGGESTION_PERCENTILE;
+  if (!(percentile >= 1 && percentile <= 100)) {
+    throw new ValidationError(`Slippage percentile must be from 1 to 100, got ${percentile}`);
+  }
+  const minBps = checkedBps(options.minBps ?? SLIPPAGE_SUGGESTION_MIN_BPS, 'minBps');
+  const fee = mulDiv(bnbAmount, checkedBps(options.feeBps ?? DEFAULT_TRADING_FEE_BPS, 'feeBps'), SLIPPAGE_DENOMINATOR);
+  const spend = bnbAmount - fee;
+
+  // Nearest-rank percentile
+  const volumes = netBuyVolumePerBlock(events);
+  const aheadBnb = volumes.length > 0 ? volumes[Math.ceil((percentile / 100) * volumes.length) - 1] : 0n;
+
+  const quoted = curveBuyAmount(params, spend);
+  const boughtAhead = curveBuyAmount(params, aheadBnb);
+  const filled = curveBuyAmount(
+    {
+      ...params,
+      virtualTokenReserve: params.virtualTokenReserve - boughtAhead,
+      virtualQuoteReserve: params.virtualQuoteReserve + aheadBnb,
+    },
+    spend
+  );
+
+  let bps = toNumberChecked(SLIPPAGE_DENOMINATOR);
+  if (quoted > 0n && filled > 0n) {
+    const lost = quoted - filled;
+    bps = toNumberChecked((lost * SLIPPAGE_DENOMINATOR + quoted - 1n) / quoted, 'slippage bps');
+  }
+
+  return {
+    bps: Math.max(bps, toNumberChecked(minBps, 'minBps')),
+    aheadBnb,
+    blocksSampled: volumes.length,
+    tradesSampled: events.length,
+  };
+}
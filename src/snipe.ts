@@ -0,0 +1,89 @@
+/**
+ * Waiting for a token to go live
+ * A token is live once it is registered with the TokenManager, trading, and past its launch time.
+ * Every new block re-reads its TokenInfo at that block; its TokenCreate event triggers an extra read.
+ * In mempool mode a pending buy of the token by anyone also counts as the launch signal, ignoring
+ * launchTime, so a buy can target the same block as the first competitors.
+ */
+
+import { CancelledError, TokenLaunchTimeoutError } from './errors';
+import { isRegisteredTokenInfo } from './tokenVerification';
+import { TokenInfo } from './types';
+
+export type SnipeMode = 'block' | 'mempool';
+
+export interface LaunchWatchSource {
+  readInfo(blockTag?: number): Promise<TokenInfo>; // Fresh read, bypassing caches; latest block when untagged
+  watchBlocks(listener: (block: number) => void): () => void;
+  watchCreation(listener: () => void): () => void; // The token's TokenCreate event
+  watchPendingBuys(listener: () => void): () => void; // Pending buys of the token (mempool mode only)
+}
+
+export interface LaunchWaitOptions {
+  timeoutMs: number;
+  mode?: SnipeMode; // Default 'block'
+  signal?: AbortSignal;
+  now?: () => number; // Unix seconds
+}
+
+/**
+ * Whether `info` describes a token open for trading at `nowSeconds`
+ */
+export function isTokenLive(info: TokenInfo, tradingStatus: bigint, nowSeconds: number): boolean {
+  return isRegisteredTokenInfo(info) && info.status === tradingStatus && info.launchTime <= BigInt(nowSeconds);
+}
+
+/**
+ * Resolve with the first fresh TokenInfo showing the token live. Rejects with TokenLaunchTimeoutError
+ * after `timeoutMs`, or CancelledError('beforeBroadcast') when `signal` fires.
+ */
+export function waitForLaunch(
+  tokenAddress: string,
+  source: LaunchWatchSource,
+  tradingStatus: bigint,
+  options: LaunchWaitOptions
+): Promise<TokenInfo> {
+  const now = options.now ?? (() => Math.floor(Date.now() / 1000));
+
+  return new Promise((resolve, reject) => {
+    const stops: Array<() => void> = [];
+    let settled = false;
+    const settle = (outcome: () => void) => {
+      if (settled) {
+        return;
+      }
+      settled = true;
+      clearTimeout(timer);
+      options.signal?.removeEventListener('abort', onAbort);
+      stops.forEach((stop) => stop());
+      outcome();
+    };
+    const check = (blockTag?: number, ignoreLaunchTime: boolean = false) => {
+      source.readInfo(blockTag).then(
+        (info) => {
+          const live = ignoreLaunchTime
+            ? isRegisteredTokenInfo(info) && info.status === tradingStatus
+            : isTokenLive(info, tradingStatus, now());
+          if (live) {
+            settle(() => resolve(info));
+          }
+        },
+        () => undefined // Not readable yet (or a transient failure); the next block retries
+      );
+    };
+    const onAbort = () => settle(() => reject(new CancelledError('beforeBroadcast')));
+    const timer = setTimeout(() => settle(() => reject(new TokenLaunchTimeoutError(tokenAddress, options.timeoutMs))), options.timeoutMs);
+
+    if (options.signal?.aborted) {
+      onAbort();
+      return;
+    }
+    options.signal?.addEventListener('abort', onAbort, { once: true });
+    stops.push(source.watchBlocks((block) => check(block)));
+    stops.push(source.watchCreation(() => check()));
+    if (options.mode === 'mempool') {
+      stops.push(source.watchPendingBuys(() => check(undefined, true)));
+    }
+    check();
+  });
+}
@@ -0,0 +1,27 @@
+/**
+ * Storage: persisting positions, cursors, creator records and trader state
+ * Imported separately (`@fnzero/four-trading-sdk/storage`) by processes that read or maintain
+ * a bot's saved state without running the trader, e.g. a reporting job or a migration script.
+ *
+ * The stores the trader and its helpers write through, in memory and as JSON files, plus trade
+ * history and the trader state snapshot format. Files are read and written here, but nothing
+ * connects or signs.
+ */
+
+export { PositionStore, PositionTrade, PositionUpdate, MemoryPositionStore, JsonFilePositionStore } from '../positionStore';
+export { PositionTracker, Position, RealizedPnl, breakEvenPrice } from '../positionTracker';
+export { replayPosition } from '../positionRecovery';
+export { CursorState, CursorStore, EventPosition, MemoryCursorStore, JsonFileCursorStore } from '../eventCursor';
+export { CreatorRegistryState, CreatorRegistryStore, MemoryCreatorRegistryStore, JsonFileCreatorRegistryStore, CreatorTokenRecord } from '../creatorRegistry';
+export { SubAccountLedgerState } from '../subAccounts';
+export { TradeHistory, TradeRecord } from '../tradeHistory';
+export {
+  TraderStateSnapshot,
+  TraderStateMigration,
+  TRADER_STATE_MIGRATIONS,
+  migrateTraderState,
+  traderStateToJson,
+  traderStateFromJson,
+} from '../traderState';
+export { TRADER_STATE_VERSION } from '../constants';
+export { FourTradingError, ValidationError, StateSnapshotError, PositionPersistError } from '../errors';
@@ -0,0 +1,626 @@
+/**
+ * Strategy framework with lifecycle hooks
+ * A strategy is written once and runs unchanged against live event streams or recorded backtests
+ */
+
+import { ethers } from 'ethers';
+import type { FourTrading, GasOptions } from './fourTrading';
+import { getFourTradingInterface } from './abi';
+import { Logger } from './logger';
+import { parseTradeEvents } from './utils';
+import { APPROVAL_TIMEOUT_MS, ONE_ETHER } from './constants';
+import { ApprovalGate, ApprovalRecord, AutoApproveGate, requestApproval } from './approvalGate';
+import {
+  TokenCreateEvent,
+  TokenPurchaseEvent,
+  TokenSaleEvent,
+  TradeTags,
+  TransactionResult,
+} from './types';
+
+export type TradeIntent = TradeTags & {
+  preApproved?: boolean; // Pre-authorized: skips the runner's approval gate
+  id?: string; // Names the intent for replaceIntent; generated when unset
+  expiresAt?: number; // Unix ms from which the intent is dropped instead of executed
+  expiresAtBlock?: number; // Dropped instead of executed once the runner has seen this block
+} & (
+  | { side: 'buy'; tokenAddress: string; fundsInBNB: bigint; minAmount?: bigint; gas?: GasOptions }
+  | { side: 'sell'; tokenAddress: string; amount: bigint; minFunds?: bigint; gas?: GasOptions }
+);
+
+export interface TradeEvent {
+  side: 'buy' | 'sell';
+  event: TokenPurchaseEvent | TokenSaleEvent;
+  blockNumber?: number;
+}
+
+export interface BlockTick {
+  number: number;
+  timestamp: number; // Unix seconds
+}
+
+export interface OwnFill {
+  intent: TradeIntent;
+  tokenAddress: string;
+  tokenAmount: bigint; // Tokens bought or sold
+  bnbAmount: bigint; // BNB spent or received
+  blockNumber?: number;
+  result?: TransactionResult;
+}
+
+export type ExpiryStage = 'dequeue' | 'execution';
+
+/**
+ * Journal entry for an intent that expired before it reached the chain
+ */
+export interface ExpiredIntent {
+  id: string;
+  intent: TradeIntent;
+  stage: ExpiryStage; // When picked up, or after the risk limits and approval right before execution
+  enqueuedAt: number; // Unix ms the hook returned it
+  expiredAt: number; // Unix ms
+  queueWaitMs: number; // Time spent queued until it expired
+  blockNumber?: number; // Last block the runner had seen
+}
+
+export type StrategyEvent =
+  | { kind: 'tokenCreated'; event: TokenCreateEvent; blockNumber?: number }
+  | { kind: 'trade'; trade: TradeEvent }
+  | { kind: 'block'; block: BlockTick };
+
+/**
+ * A derived market signal, e.g. from a MomentumTrigger; implementations add their supporting stats
+ */
+export interface StrategySignal {
+  type: string;
+  tokenAddress: string;
+  timestamp: number; // Unix seconds
+}
+
+/**
+ * Derives signals from the runner's market events. Signals are dispatched to `onSignal` right after
+ * the event that produced them, so they behave the same live and in backtests.
+ */
+export interface SignalSource {
+  observe(event: StrategyEvent): StrategySignal[];
+}
+
+type HookResult = TradeIntent[] | void | Promise<TradeIntent[] | void>;
+
+/**
+ * Lifecycle hooks a strategy may implement. Every hook may return trade intents to execute.
+ * Hooks are always invoked sequentially, never concurrently.
+ */
+export interface Strategy {
+  onStart?(): HookResult;
+  onTokenCreated?(event: TokenCreateEvent): HookResult;
+  onTradeEvent?(trade: TradeEvent): HookResult;
+  onBlock?(block: BlockTick): HookResult;
+  onSignal?(signal: StrategySignal): HookResult;
+  onOwnFill?(fill: OwnFill): HookResult;
+  onIntentExpired?(expired: ExpiredIntent): HookResult;
+  onShutdown?(): HookResult;
+}
+
+/**
+ * Source of market events (live subscriptions or a recorded timeline)
+ * `start` resolves once the driver has no more events (live drivers resolve on `stop`)
+ */
+export interface StrategyDriver {
+  start(emit: (event: StrategyEvent) => void): Promise<void>;
+  stop(): Promise<void>;
+}
+
+/**
+ * Executes trade intents and reports the resulting fills
+ */
+export interface TradeExecutor {
+  execute(intent: TradeIntent): Promise<OwnFill>;
+}
+
+export interface RiskLimits {
+  maxBnbPerTrade?: bigint; // Largest single buy in wei
+  maxTotalBnbSpent?: bigint; // Cumulative buy budget in wei for the whole run
+  maxIntentsPerHook?: number; // Intents beyond this count from a single hook are dropped
+  subAccounts?: Record<string, Pick<RiskLimits, 'maxBnbPerTrade' | 'maxTotalBnbSpent'>>; // Extra limits per sub-account
+}
+
+/**
+ * Buys spending more than `thresholdBnb` wait for `gate` to approve them after passing the risk
+ * limits. Intents marked `preApproved` skip the gate.
+ */
+export interface ApprovalConfig {
+  thresholdBnb: bigint; // Largest buy in wei executed without approval
+  gate?: ApprovalGate; // Defaults to an AutoApproveGate
+  timeoutMs?: number; // Undecided requests are rejected after this long (default: APPROVAL_TIMEOUT_MS)
+}
+
+export interface StrategyRunnerConfig {
+  driver?: StrategyDriver; // Defaults to a LiveDriver over the trader
+  executor?: TradeExecutor; // Defaults to a LiveExecutor over the trader
+  riskLimits?: RiskLimits;
+  approval?: ApprovalConfig; // Out-of-band confirmation of large buys
+  subAccount?: string; // Sub-account for intents that don't name one
+  signals?: SignalSource[]; // Fed every market event; their signals go to onSignal
+  clock?: () => number; // Unix ms checked against `expiresAt` (default: Date.now)
+  logger?: Logger;
+}
+
+// ==================== Live ====================
+
+/**
+ * Streams token creations, trades and blocks from a FourTrading instance
+ * Block timestamps are taken from the local clock to avoid an extra RPC per block
+ */
+export class LiveDriver implements StrategyDriver {
+  private listenerIds: string[] = [];
+  private resolveStopped: (() => void) | null = null;
+
+  constructor(private trader: FourTrading) {}
+
+  start(emit: (event: StrategyEvent) => void): Promise<void> {
+    this.listenerIds = [
+      this.trader.onTokenCreate((event) => emit({ kind: 'tokenCreated', event })),
+      this.trader.onTokenPurchase((event) => emit({ kind: 'trade', trade: { side: 'buy', event } })),
+      this.trader.onTokenSale((event) => emit({ kind: 'trade', trade: { side: 'sell', event } })),
+      this.trader.onBlock((number) =>
+        emit({ kind: 'block', block: { number, timestamp: Math.floor(Date.now() / 1000) } })
+      ),
+    ];
+
+    return new Promise((resolve) => {
+      this.resolveStopped = resolve;
+    });
+  }
+
+  async stop(): Promise<void> {
+    for (const id of this.listenerIds) {
+      this.trader.off(id);
+    }
+    this.listenerIds = [];
+    this.resolveStopped?.();
+    this.resolveStopped = null;
+  }
+}
+
+/**
+ * Sends intents through FourTrading and decodes the fill from the receipt
+ */
+export class LiveExecutor implements TradeExecutor {
+  private contract = new ethers.Contract(ethers.ZeroAddress, getFourTradingInterface());
+
+  constructor(private trader: FourTrading) {}
+
+  async execute(intent: TradeIntent): Promise<OwnFill> {
+    const result = intent.side === 'buy'
+      ? await this.trader.buyToken({
+        tokenAddress: intent.tokenAddress,
+        fundsInBNB: intent.fundsInBNB,
+        minAmount: intent.minAmount,
+        gas: intent.gas,
+        tags: intent.tags,
+        correlationId: intent.correlationId,
+        subAccount: intent.subAccount,
+      })
+      : await this.trader.sellToken({
+        tokenAddress: intent.tokenAddress,
+        amount: intent.amount,
+        minFunds: intent.minFunds,
+        gas: intent.gas,
+        tags: intent.tags,
+        correlationId: intent.correlationId,
+        subAccount: intent.subAccount,
+      });
+
+    const { purchases, sales } = await parseTradeEvents(result.receipt, this.contract);
+    const trade = intent.side === 'buy' ? purchases[0] : sales[0];
+
+    return {
+      intent,
+      tokenAddress: intent.tokenAddress,
+      tokenAmount: trade?.amount ?? 0n,
+      bnbAmount: trade?.cost ?? 0n,
+      blockNumber: result.receipt?.blockNumber,
+      result,
+    };
+  }
+}
+
+// ==================== Backtest ====================
+
+/**
+ * Replays a recorded event timeline in order
+ */
+export class BacktestDriver implements StrategyDriver {
+  private stopped = false;
+
+  constructor(private events: StrategyEvent[]) {}
+
+  async start(emit: (event: StrategyEvent) => void): Promise<void> {
+    for (const event of this.events) {
+      if (this.stopped) {
+        break;
+      }
+      emit(event);
+    }
+  }
+
+  async stop(): Promise<void> {
+    this.stopped = true;
+  }
+}
+
+/**
+ * Fills intents at the last observed trade price of each token, minus an optional fee
+ * Tokens with no observed trade yet fill at `launchPrice` (BNB per token scaled by 1e18)
+ * Feed it the same events as the driver through `observe` (the runner does this automatically)
+ */
+export class BacktestExecutor implements TradeExecutor {
+  private lastPrices: Map<string, bigint> = new Map();
+  private lastBlock: number | undefined;
+
+  constructor(private feeBps: bigint = 0n, private launchPrice?: bigint) {}
+
+  observe(event: StrategyEvent): void {
+    if (event.kind === 'trade') {
+      this.lastPrices.set(event.trade.event.token.toLowerCase(), event.trade.event.price);
+      this.lastBlock = event.trade.blockNumber ?? this.lastBlock;
+    } else if (event.kind === 'block') {
+      this.lastBlock = event.block.number;
+    }
+  }
+
+  async execute(intent: TradeIntent): Promise<OwnFill> {
+    const price = this.lastPrices.get(intent.tokenAddress.toLowerCase()) ?? this.launchPrice;
+    if (!price) {
+      throw new Error(`No observed price for ${intent.tokenAddress}`);
+    }
+
+    if (intent.side === 'buy') {
+      const funds = intent.fundsInBNB - (intent.fundsInBNB * this.feeBps) / 10000n;
+      return {
+        intent,
+        tokenAddress: intent.tokenAddress,
+        tokenAmount: (funds * ONE_ETHER) / price,
+        bnbAmount: intent.fundsInBNB,
+        blockNumber: this.lastBlock,
+      };
+    }
+
+    const gross = (intent.amount * price) / ONE_ETHER;
+    return {
+      intent,
+      tokenAddress: intent.tokenAddress,
+      tokenAmount: intent.amount,
+      bnbAmount: gross - (gross * this.feeBps) / 10000n,
+      blockNumber: this.lastBlock,
+    };
+  }
+}
+
+// ==================== Runner ====================
+
+interface QueuedIntent {
+  id: string;
+  intent: TradeIntent;
+  source: string;
+  enqueuedAt: number; // Unix ms
+}
+
+/**
+ * Owns the event subscriptions and dispatches events to a strategy one at a time.
+ * Intents returned by a hook are executed before the next event is dispatched, so a
+ * strategy always sees its own fills (onOwnFill) before market events from later blocks.
+ */
+export class StrategyRunner {
+  private queue: StrategyEvent[] = [];
+  private wake: (() => void) | null = null;
+  private driverDone = false;
+  private stopping = false;
+  private totalBnbSpent = 0n;
+  private subAccountSpent: Map<string, bigint> = new Map();
+  private driver: StrategyDriver;
+  private executor: TradeExecutor;
+  private riskLimits: RiskLimits;
+  private approval?: ApprovalConfig;
+  private approvalJournal: ApprovalRecord[] = [];
+  private approvalCount = 0;
+  private queuedIntents: Map<string, QueuedIntent> = new Map(); // Returned by a hook, not picked up yet
+  private intentCount = 0;
+  private expirationJournal: ExpiredIntent[] = [];
+  private lastBlock: number | undefined;
+  private subAccount?: string;
+  private signals: SignalSource[];
+  private clock: () => number;
+  private logger: Logger;
+
+  constructor(private strategy: Strategy, trader: FourTrading | undefined, config: StrategyRunnerConfig = {}) {
+    if ((!config.driver || !config.executor) && !trader) {
+      throw new Error('A trader is required unless both driver and executor are provided');
+    }
+
+    this.driver = config.driver ?? new LiveDriver(trader!);
+    this.executor = config.executor ?? new LiveExecutor(trader!);
+    this.riskLimits = config.riskLimits ?? {};
+    this.approval = config.approval && { ...config.approval, gate: config.approval.gate ?? new AutoApproveGate() };
+    this.subAccount = config.subAccount;
+    this.signals = config.signals ?? [];
+    this.clock = config.clock ?? Date.now;
+    this.logger = config.logger ?? new Logger({ prefix: '[StrategyRunner]' });
+  }
+
+  /**
+   * Run a strategy until the driver is exhausted or `stop` is called
+   */
+  static async run(strategy: Strategy, trader: FourTrading | undefined, config: StrategyRunnerConfig = {}): Promise<StrategyRunner> {
+    const runner = new StrategyRunner(strategy, trader, config);
+    await runner.run();
+    return runner;
+  }
+
+  async run(): Promise<void> {
+    await this.dispatch('start', () => this.strategy.onStart?.());
+
+    const driverFinished = this.driver
+      .start((event) => this.enqueue(event))
+      .finally(() => {
+        this.driverDone = true;
+        this.notify();
+      });
+
+    while (!this.stopping) {
+      const event = this.queue.shift();
+      if (event) {
+        await this.handle(event);
+        continue;
+      }
+      if (this.driverDone) {
+        break;
+      }
+      await new Promise<void>((resolve) => {
+        this.wake = resolve;
+      });
+    }
+
+    await this.driver.stop();
+    await driverFinished;
+    await this.dispatch('shutdown', () => this.strategy.onShutdown?.());
+  }
+
+  /**
+   * Stop after the event currently being handled
+   */
+  async stop(): Promise<void> {
+    this.stopping = true;
+    this.notify();
+  }
+
+  /**
+   * BNB spent on buys this run, by every sub-account or only `subAccount`
+   */
+  getTotalBnbSpent(subAccount?: string): bigint {
+    return subAccount === undefined ? this.totalBnbSpent : this.subAccountSpent.get(subAccount) ?? 0n;
+  }
+
+  /**
+   * Every approval this run, in request order, including pre-approved buys that skipped the gate
+   */
+  getApprovalJournal(): ApprovalRecord[] {
+    return [...this.approvalJournal];
+  }
+
+  /**
+   * Every intent that expired instead of executing this run, in order
+   */
+  getExpirationJournal(): ExpiredIntent[] {
+    return [...this.expirationJournal];
+  }
+
+  /**
+   * Swap a queued intent for `intent`, which takes over its place in the queue, its id and its
+   * queue time. False once the runner has picked the intent up, or for an unknown id.
+   */
+  replaceIntent(id: string, intent: TradeIntent): boolean {
+    const queued = this.queuedIntents.get(id);
+    if (!queued) {
+      return false;
+    }
+    queued.intent = intent;
+    return true;
+  }
+
+  private enqueue(event: StrategyEvent): void {
+    this.queue.push(event);
+    this.notify();
+  }
+
+  private notify(): void {
+    const wake = this.wake;
+    this.wake = null;
+    wake?.();
+  }
+
+  private async handle(event: StrategyEvent): Promise<void> {
+    if (this.executor instanceof BacktestExecutor) {
+      this.executor.observe(event);
+    }
+
+    const blockNumber = event.kind === 'block' ? event.block.number : event.kind === 'trade' ? event.trade.blockNumber : event.blockNumber;
+    this.lastBlock = blockNumber ?? this.lastBlock;
+
+    if (event.kind === 'tokenCreated') {
+      const created = event.event;
+      await this.dispatch('tokenCreated', () => this.strategy.onTokenCreated?.(created));
+    } else if (event.kind === 'trade') {
+      const trade = event.trade;
+      await this.dispatch('trade', () => this.strategy.onTradeEvent?.(trade));
+    } else {
+      const block = event.block;
+      await this.dispatch('block', () => this.strategy.onBlock?.(block));
+    }
+
+    for (const source of this.signals) {
+      for (const signal of source.observe(event)) {
+        await this.dispatch('signal', () => this.strategy.onSignal?.(signal));
+      }
+    }
+  }
+
+  /**
+   * Run a hook and execute its intents in order; each intent is auto-tagged with `source:<hook>` and
+   * booked to the runner's sub-account unless it names its own. Expiry is checked when an intent is
+   * picked up and again right before it executes.
+   */
+  private async dispatch(source: string, hook: () => HookResult): Promise<void> {
+    let intents: TradeIntent[] | void;
+    try {
+      intents = await hook();
+    } catch (error) {
+      this.logger.error('Strategy hook failed', { error: error instanceof Error ? error.message : String(error) });
+      return;
+    }
+
+    if (!intents || intents.length === 0) {
+      return;
+    }
+
+    const limit = this.riskLimits.maxIntentsPerHook;
+    if (limit !== undefined && intents.length > limit) {
+      this.logger.warn('Dropping intents above maxIntentsPerHook', { returned: intents.length, limit });
+      intents = intents.slice(0, limit);
+    }
+
+    const enqueuedAt = this.clock();
+    const batch = intents.map((intent) => {
+      const queued: QueuedIntent = { id: intent.id ?? `intent-${++this.intentCount}`, intent, source, enqueuedAt };
+      this.queuedIntents.set(queued.id, queued);
+      return queued;
+    });
+
+    for (const queued of batch) {
+      this.queuedIntents.delete(queued.id);
+      const returned = queued.intent;
+      const subAccount = returned.subAccount ?? this.subAccount;
+      const intent: TradeIntent = {
+        ...returned,
+        id: queued.id,
+        tags: [...(returned.tags ?? []), `source:${queued.source}`],
+        ...(subAccount !== undefined ? { subAccount } : {}),
+      };
+      if (await this.expire(queued, intent, 'dequeue')) {
+        continue;
+      }
+      const rejection = this.checkRiskLimits(intent);
+      if (rejection) {
+        this.logger.warn('Intent rejected by risk limits', { token: intent.tokenAddress, side: intent.side, reason: rejection });
+        continue;
+      }
+      if (!(await this.approve(intent))) {
+        continue;
+      }
+      if (await this.expire(queued, intent, 'execution')) {
+        continue;
+      }
+
+      let fill: OwnFill;
+      try {
+        fill = await this.executor.execute(intent);
+      } catch (error) {
+        this.logger.error('Intent execution failed', {
+          token: intent.tokenAddress,
+          side: intent.side,
+          error: error instanceof Error ? error.message : String(error),
+        });
+        continue;
+      }
+
+      if (intent.side === 'buy') {
+        this.totalBnbSpent += fill.bnbAmount;
+        if (intent.subAccount !== undefined) {
+          this.subAccountSpent.set(intent.subAccount, this.getTotalBnbSpent(intent.subAccount) + fill.bnbAmount);
+        }
+      }
+
+      await this.dispatch('ownFill', () => this.strategy.onOwnFill?.(fill));
+    }
+  }
+
+  /**
+   * Journal `intent` and hand it to onIntentExpired if its time or block has passed
+   */
+  private async expire(queued: QueuedIntent, intent: TradeIntent, stage: ExpiryStage): Promise<boolean> {
+    const now = this.clock();
+    const timedOut = intent.expiresAt !== undefined && now >= intent.expiresAt;
+    const blockPassed = intent.expiresAtBlock !== undefined && this.lastBlock !== undefined && this.lastBlock >= intent.expiresAtBlock;
+    if (!timedOut && !blockPassed) {
+      return false;
+    }
+
+    const expired: ExpiredIntent = {
+      id: queued.id,
+      intent,
+      stage,
+      enqueuedAt: queued.enqueuedAt,
+      expiredAt: now,
+      queueWaitMs: now - queued.enqueuedAt,
+      blockNumber: this.lastBlock,
+    };
+    this.expirationJournal.push(expired);
+    this.logger.warn('Intent expired before execution', { id: queued.id, token: intent.tokenAddress, side: intent.side, stage, queueWaitMs: expired.queueWaitMs });
+    await this.dispatch('intentExpired', () => this.strategy.onIntentExpired?.(expired));
+    return true;
+  }
+
+  /**
+   * Whether `intent` may execute: small buys and sells always may, large buys once the gate approves
+   */
+  private async approve(intent: TradeIntent): Promise<boolean> {
+    if (!this.approval || intent.side !== 'buy' || intent.fundsInBNB <= this.approval.thresholdBnb) {
+      return true;
+    }
+
+    const id = `approval-${++this.approvalCount}`;
+    const requestedAt = Date.now();
+    const record: ApprovalRecord = intent.preApproved
+      ? { id, tokenAddress: intent.tokenAddress, fundsInBNB: intent.fundsInBNB, outcome: 'skipped', requestedAt, latencyMs: 0 }
+      : await requestApproval(
+        this.approval.gate!,
+        { id, intent, fundsInBNB: intent.fundsInBNB, requestedAt },
+        this.approval.timeoutMs ?? APPROVAL_TIMEOUT_MS
+      );
+    this.approvalJournal.push(record);
+
+    if (record.outcome === 'approved' || record.outcome === 'skipped') {
+      this.logger.info('Large buy approved', { token: intent.tokenAddress, outcome: record.outcome, approver: record.approver, latencyMs: record.latencyMs });
+      return true;
+    }
+    this.logger.warn('Intent rejected by approval gate', { token: intent.tokenAddress, outcome: record.outcome, reason: record.reason, latencyMs: record.latencyMs });
+    return false;
+  }
+
+  private checkRiskLimits(intent: TradeIntent): string | null {
+    if (intent.side !== 'buy') {
+      return null;
+    }
+
+    const rejection = this.checkBuyLimits(intent.fundsInBNB, this.totalBnbSpent, this.riskLimits);
+    if (rejection || intent.subAccount === undefined) {
+      return rejection;
+    }
+
+    const limits = this.riskLimits.subAccounts?.[intent.subAccount];
+    const scoped = limits && this.checkBuyLimits(intent.fundsInBNB, this.getTotalBnbSpent(intent.subAccount), limits);
+    return scoped ? `sub-account ${intent.subAccount}: ${scoped}` : null;
+  }
+
+  private checkBuyLimits(funds: bigint, spent: bigint, limits: Pick<RiskLimits, 'maxBnbPerTrade' | 'maxTotalBnbSpent'>): string | null {
+    const { maxBnbPerTrade, maxTotalBnbSpent } = limits;
+    if (maxBnbPerTrade !== undefined && funds > maxBnbPerTrade) {
+      return `buy of ${funds} exceeds maxBnbPerTrade ${maxBnbPerTrade}`;
+    }
+    if (maxTotalBnbSpent !== undefined && spent + funds > maxTotalBnbSpent) {
+      return `buy would bring total spend above maxTotalBnbSpent ${maxTotalBnbSpent}`;
+    }
+    return null;
+  }
+}
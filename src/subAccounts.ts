@@ -0,0 +1,337 @@
+/**
+ * Virtual sub-accounts over one wallet
+ * Strategies sharing a wallet each get a BNB allocation, token balances and positions of their own.
+ * Everything here is local bookkeeping; only `reconcile` compares it against the chain.
+ */
+
+import { Position, PositionTracker, RealizedPnl } from './positionTracker';
+import { SubAccountBalanceError, ValidationError } from './errors';
+
+export interface SubAccountBalance {
+  id: string;
+  bnb: bigint; // BNB in wei, reservations included
+  reserved: bigint; // Part of `bnb` held back for buys still in flight
+  gasSpent: bigint; // Gas paid by this sub-account's trades (wei)
+  tokens: Map<string, bigint>; // Token (lowercase) -> amount held
+}
+
+export interface TokenDrift {
+  token: string;
+  expected: bigint; // Sum over sub-accounts
+  actual: bigint; // Wallet balance
+  drift: bigint; // actual - expected
+}
+
+export interface SubAccountReconciliation {
+  expectedBnb: bigint; // Unallocated BNB plus every sub-account's BNB
+  actualBnb: bigint;
+  bnbDrift: bigint; // actualBnb - expectedBnb
+  tokens: TokenDrift[]; // Only tokens whose drift is non-zero
+  inSync: boolean;
+}
+
+/**
+ * A ledger's bookkeeping as plain data, for TraderStateSnapshot. Reservations are not included:
+ * they belong to buys of the process that took it.
+ */
+export interface SubAccountLedgerState {
+  unallocated: bigint;
+  accounts: Array<{
+    id: string;
+    bnb: bigint;
+    gasSpent: bigint;
+    tokens: Array<[string, bigint]>; // Token (lowercase), amount held
+    positions: Position[];
+  }>;
+}
+
+/**
+ * BNB held back for a buy from its checks until its fill is booked, so concurrent buys can't each
+ * pass the check and together overspend the allocation
+ */
+export interface SubAccountReservation {
+  id: string;
+  bnb: bigint;
+  release(): void; // Once the fill is booked, or the buy failed; releasing twice does nothing
+}
+
+interface SubAccountState {
+  bnb: bigint;
+  reserved: bigint;
+  gasSpent: bigint;
+  tokens: Map<string, bigint>;
+  positions: PositionTracker;
+}
+
+export class SubAccountLedger {
+  private accounts: Map<string, SubAccountState> = new Map();
+  private unallocated: bigint;
+
+  /**
+   * @param walletBnb - Wallet BNB not yet assigned to any sub-account (wei); `deposit` adds to it later
+   */
+  constructor(walletBnb: bigint = 0n) {
+    this.unallocated = walletBnb;
+  }
+
+  /**
+   * Record BNB that arrived in the wallet without belonging to a sub-account
+   */
+  deposit(bnb: bigint): void {
+    this.unallocated += bnb;
+  }
+
+  /**
+   * Move `bnb` from unallocated wallet funds to `id`, creating the sub-account if needed
+   */
+  allocate(id: string, bnb: bigint): SubAccountBalance {
+    if (bnb > this.unallocated) {
+      throw new SubAccountBalanceError('unallocated', 'BNB', bnb, this.unallocated);
+    }
+    const account = this.getOrCreate(id);
+    this.unallocated -= bnb;
+    account.bnb += bnb;
+    return this.toBalance(id, account);
+  }
+
+  /**
+   * Return `bnb` from `id` to the unallocated pool
+   */
+  release(id: string, bnb: bigint): SubAccountBalance {
+    const account = this.require(id);
+    this.assertAvailable(id, 'BNB', bnb, account.bnb - account.reserved);
+    account.bnb -= bnb;
+    this.unallocated += bnb;
+    return this.toBalance(id, account);
+  }
+
+  /**
+   * Move BNB between sub-accounts
+   */
+  transfer(from: string, to: string, bnb: bigint): void {
+    const source = this.require(from);
+    this.assertAvailable(from, 'BNB', bnb, source.bnb - source.reserved);
+    source.bnb -= bnb;
+    this.getOrCreate(to).bnb += bnb;
+  }
+
+  /**
+   * Move tokens between sub-accounts together with their cost basis; no PnL is realized
+   */
+  transferTokens(from: string, to: string, token: string, amount: bigint): void {
+    const source = this.require(from);
+    const key = token.toLowerCase();
+    this.assertAvailable(from, token, amount, source.tokens.get(key) ?? 0n);
+
+    const position = source.positions.getPosition(token);
+    let knownAmount = 0n;
+    let knownCost = 0n;
+    if (position && position.amount > 0n) {
+      knownAmount = (amount * position.knownAmount) / position.amount;
+      knownCost = position.knownAmount > 0n ? (position.knownCost * knownAmount) / position.knownAmount : 0n;
+    }
+    // Selling at exactly the moved basis leaves the source's realized PnL unchanged
+    source.positions.recordSell(token, amount, knownCost);
+    source.tokens.set(key, (source.tokens.get(key) ?? 0n) - amount);
+
+    const target = this.getOrCreate(to);
+    if (knownAmount > 0n) {
+      target.positions.markExternalAcquisition(token, knownAmount, knownCost);
+    }
+    if (amount > knownAmount) {
+      target.positions.markExternalAcquisition(token, amount - knownAmount);
+    }
+    target.tokens.set(key, (target.tokens.get(key) ?? 0n) + amount);
+  }
+
+  /**
+   * Throw SubAccountBalanceError unless `id` can spend `bnb` on top of its reservations
+   */
+  assertCanBuy(id: string, bnb: bigint): void {
+    const account = this.require(id);
+    this.assertAvailable(id, 'BNB', bnb, account.bnb - account.reserved);
+  }
+
+  /**
+   * Check `id` can spend `bnb` and hold it back until the returned reservation is released
+   */
+  reserve(id: string, bnb: bigint): SubAccountReservation {
+    this.assertCanBuy(id, bnb);
+    const account = this.require(id);
+    account.reserved += bnb;
+    let held = true;
+    return {
+      id,
+      bnb,
+      release: () => {
+        if (held) {
+          held = false;
+          account.reserved -= bnb;
+        }
+      },
+    };
+  }
+
+  /**
+   * Throw SubAccountBalanceError unless `id` holds `amount` of `token`
+   */
+  assertCanSell(id: string, token: string, amount: bigint): void {
+    this.assertAvailable(id, token, amount, this.require(id).tokens.get(token.toLowerCase()) ?? 0n);
+  }
+
+  recordBuy(id: string, token: string, tokenAmount: bigint, bnbCost: bigint): void {
+    const account = this.require(id);
+    const key = token.toLowerCase();
+    account.bnb -= bnbCost;
+    account.tokens.set(key, (account.tokens.get(key) ?? 0n) + tokenAmount);
+    account.positions.recordBuy(token, tokenAmount, bnbCost);
+  }
+
+  recordSell(id: string, token: string, tokenAmount: bigint, proceeds: bigint): RealizedPnl {
+    const account = this.require(id);
+    const key = token.toLowerCase();
+    account.bnb += proceeds;
+    account.tokens.set(key, (account.tokens.get(key) ?? 0n) - tokenAmount);
+    return account.positions.recordSell(token, tokenAmount, proceeds);
+  }
+
+  /**
+   * Charge transaction gas to the sub-account that sent it
+   */
+  chargeGas(id: string, gasCost: bigint): void {
+    const account = this.require(id);
+    account.bnb -= gasCost;
+    account.gasSpent += gasCost;
+  }
+
+  has(id: string): boolean {
+    return this.accounts.has(id);
+  }
+
+  getBalance(id: string): SubAccountBalance | undefined {
+    const account = this.accounts.get(id);
+    return account ? this.toBalance(id, account) : undefined;
+  }
+
+  getBalances(): SubAccountBalance[] {
+    return [...this.accounts].map(([id, account]) => this.toBalance(id, account));
+  }
+
+  getUnallocated(): bigint {
+    return this.unallocated;
+  }
+
+  /**
+   * The ledger's balances and positions, to restore in another process
+   */
+  snapshot(): SubAccountLedgerState {
+    return {
+      unallocated: this.unallocated,
+      accounts: [...this.accounts].map(([id, account]) => ({
+        id,
+        bnb: account.bnb,
+        gasSpent: account.gasSpent,
+        tokens: [...account.tokens],
+        positions: account.positions.getPositions(),
+      })),
+    };
+  }
+
+  /**
+   * Replace the ledger's contents with a snapshot's; nothing is reserved afterwards
+   */
+  restore(state: SubAccountLedgerState): void {
+    this.accounts.clear();
+    this.unallocated = state.unallocated;
+    for (const saved of state.accounts) {
+      const account = this.getOrCreate(saved.id);
+      account.bnb = saved.bnb;
+      account.gasSpent = saved.gasSpent;
+      account.tokens = new Map(saved.tokens);
+      saved.positions.forEach((position) => account.positions.restore(position));
+    }
+  }
+
+  /**
+   * Positions and PnL of one sub-account's trades
+   */
+  getPositionTracker(id: string): PositionTracker {
+    return this.require(id).positions;
+  }
+
+  /**
+   * Realized PnL (wei) of one sub-account, or of all of them when `id` is omitted
+   */
+  realizedPnl(id?: string): bigint {
+    const accounts = id === undefined ? [...this.accounts.values()] : [this.require(id)];
+    return accounts
+      .flatMap((account) => account.positions.getPositions())
+      .reduce((sum, position) => sum + position.realizedPnl, 0n);
+  }
+
+  /**
+   * Tokens held by any sub-account (lowercase)
+   */
+  heldTokens(): string[] {
+    const tokens = new Set<string>();
+    for (const account of this.accounts.values()) {
+      for (const [token, amount] of account.tokens) {
+        if (amount !== 0n) {
+          tokens.add(token);
+        }
+      }
+    }
+    return [...tokens];
+  }
+
+  /**
+   * Compare the ledger against the wallet's on-chain balances. Drift appears when funds move
+   * outside the SDK, or a transaction's gas or fill was never recorded.
+   * @param tokenBalances - Wallet balance per token; tokens missing from it are not checked
+   */
+  reconcile(actualBnb: bigint, tokenBalances: Map<string, bigint> = new Map()): SubAccountReconciliation {
+    const expectedBnb = [...this.accounts.values()].reduce((sum, account) => sum + account.bnb, this.unallocated);
+    const actualTokens = new Map([...tokenBalances].map(([token, amount]) => [token.toLowerCase(), amount]));
+
+    const tokens: TokenDrift[] = [];
+    for (const [token, actual] of actualTokens) {
+      let expected = 0n;
+      for (const account of this.accounts.values()) {
+        expected += account.tokens.get(token) ?? 0n;
+      }
+      if (actual !== expected) {
+        tokens.push({ token, expected, actual, drift: actual - expected });
+      }
+    }
+
+    const bnbDrift = actualBnb - expectedBnb;
+    return { expectedBnb, actualBnb, bnbDrift, tokens, inSync: bnbDrift === 0n && tokens.length === 0 };
+  }
+
+  private assertAvailable(id: string, asset: string, requested: bigint, available: bigint): void {
+    if (requested > available) {
+      throw new SubAccountBalanceError(id, asset, requested, available);
+    }
+  }
+
+  private require(id: string): SubAccountState {
+    const account = this.accounts.get(id);
+    if (!account) {
+      throw new ValidationError(`Unknown sub-account ${id}`);
+    }
+    return account;
+  }
+
+  private getOrCreate(id: string): SubAccountState {
+    let account = this.accounts.get(id);
+    if (!account) {
+      account = { bnb: 0n, reserved: 0n, gasSpent: 0n, tokens: new Map(), positions: new PositionTracker() };
+      this.accounts.set(id, account);
+    }
+    return account;
+  }
+
+  private toBalance(id: string, account: SubAccountState): SubAccountBalance {
+    return { id, bnb: account.bnb, reserved: account.reserved, gasSpent: account.gasSpent, tokens: new Map(account.tokens) };
+  }
+}
@@ -0,0 +1,68 @@
+/**
+ * Sweeping leftovers back to BNB
+ * A sweep looks for value the wallet holds but no strategy is tracking: WBNB (e.g. from a
+ * PancakeSwap route or a refund) and residual balances of tokens whose tracked position is closed.
+ * It unwraps or sells what is worth the gas and reports everything else it found with the reason it
+ * was left alone. Tokens with an open position are never touched.
+ */
+
+import { Position } from './positionTracker';
+
+export interface SweepOptions {
+  dryRun?: boolean; // Decide and report, but send nothing
+  tokens?: string[]; // Extra tokens to check besides closed positions, e.g. airdrops; open positions among them are still skipped
+  minProceedsMarginBps?: number; // Margin proceeds must beat gas by (default: minSellProceedsMarginBps, or 0)
+  slippagePercent?: number; // Minimum proceeds of a sweep sell below its quote (default: SWEEP_SLIPPAGE_PERCENT)
+  unwrapWbnb?: boolean; // Unwrap WBNB found in the wallet (default: true)
+}
+
+export type SweepAction = 'unwrapped' | 'sold' | 'wouldUnwrap' | 'wouldSell' | 'skipped' | 'failed';
+
+export type SweepSkipReason = 'openPosition' | 'uneconomical' | 'notApproved' | 'unwrapDisabled';
+
+export interface SweepItem {
+  kind: 'wbnb' | 'token';
+  token: string;
+  amount: bigint; // Balance found
+  action: SweepAction;
+  skipReason?: SweepSkipReason; // Set when action is 'skipped'
+  reason?: string; // Human-readable detail of a skip or failure
+  proceeds?: bigint; // BNB the unwrap or sell returns (quoted for sells)
+  gasCost?: bigint; // Estimated gas cost of acting on the item
+  txHash?: string;
+}
+
+export interface SweepReport {
+  dryRun: boolean;
+  startedAt: number; // Unix milliseconds
+  items: SweepItem[]; // Every non-zero balance found, acted on or not
+  recovered: bigint; // BNB from items unwrapped or sold (or that would be, in a dry run)
+}
+
+/**
+ * Tokens a sweep should check: closed positions plus `extra`, deduplicated (lowercased), with
+ * whether each has an open position
+ */
+export function sweepCandidates(positions: Position[], extra: string[] = []): Array<{ token: string; open: boolean }> {
+  const open = new Set(positions.filter((position) => position.amount > 0n).map((position) => position.token.toLowerCase()));
+  const tokens = new Set([
+    ...positions.filter((position) => position.amount === 0n).map((position) => position.token.toLowerCase()),
+    ...extra.map((token) => token.toLowerCase()),
+  ]);
+  return [...tokens].map((token) => ({ token, open: open.has(token) }));
+}
+
+/**
+ * Total BNB a report recovered (or would recover)
+ */
+export function sweepRecovered(items: SweepItem[]): bigint {
+  const acted: SweepAction[] = ['unwrapped', 'sold', 'wouldUnwrap', 'wouldSell'];
+  return items.reduce((sum, item) => (acted.includes(item.action) ? sum + (item.proceeds ?? 0n) : sum), 0n);
+}
+
+/**
+ * Serialize a sweep report to JSON, bigints as decimal strings, e.g. for a journal entry
+ */
+export function sweepReportToJson(report: SweepReport): string {
+  return JSON.stringify(report, (_key, value) => (typeof value === 'bigint' ? value.toString() : value));
+}
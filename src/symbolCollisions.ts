@@ -0,0 +1,124 @@
+/**
+ * Symbol collision and impersonation detection
+ * Copycat launches reuse a trending token's symbol, or a name that only differs by case, spacing,
+ * accents or lookalike characters. Text is folded to a canonical form before comparing: compatibility
+ * decomposition, combining marks and whitespace stripped, homoglyphs mapped to ASCII, lowercased.
+ */
+
+import { NEAR_NAME_MIN_LENGTH } from './constants';
+
+/**
+ * Lookalike characters and the ASCII letter they pass for. Applied before lowercasing, since
+ * uppercase and lowercase forms imitate different letters (Greek Ν passes for N, ν for v).
+ */
+export const HOMOGLYPHS: Readonly<Record<string, string>> = {
+  // Cyrillic
+  'А': 'A', 'В': 'B', 'Е': 'E', 'Ѕ': 'S', 'І': 'I', 'Ј': 'J', 'К': 'K', 'М': 'M', 'Н': 'H', 'О': 'O',
+  'Р': 'P', 'С': 'C', 'Т': 'T', 'У': 'Y', 'Х': 'X', 'Ү': 'Y',
+  'а': 'a', 'е': 'e', 'ѕ': 's', 'і': 'i', 'ј': 'j', 'о': 'o', 'р': 'p', 'с': 'c', 'у': 'y', 'х': 'x',
+  'һ': 'h', 'ԁ': 'd', 'ԛ': 'q', 'ԝ': 'w', 'ӏ': 'l',
+  // Greek
+  'Α': 'A', 'Β': 'B', 'Ε': 'E', 'Ζ': 'Z', 'Η': 'H', 'Ι': 'I', 'Κ': 'K', 'Μ': 'M', 'Ν': 'N', 'Ο': 'O',
+  'Ρ': 'P', 'Τ': 'T', 'Υ': 'Y', 'Χ': 'X',
+  'α': 'a', 'ι': 'i', 'κ': 'k', 'ν': 'v', 'ο': 'o', 'ρ': 'p', 'υ': 'u', 'χ': 'x',
+  // Digits and symbols read as letters
+  '0': 'o', '1': 'l', '|': 'l',
+};
+
+const INVISIBLE = /[\s\u200B-\u200F\u2060\uFEFF]/gu; // Whitespace, zero-width and direction marks
+const COMBINING_MARKS = /\p{M}/gu;
+
+export type CollisionReason = 'symbol' | 'name';
+
+/**
+ * A token whose symbol or name collides with the one searched for
+ */
+export interface TokenMatch {
+  token: string;
+  name: string;
+  symbol: string;
+  creator: string;
+  block: number;
+  createdAt: number; // Creation block timestamp (Unix seconds)
+  reason: CollisionReason; // 'symbol' when both match
+}
+
+/**
+ * Canonical form of a token symbol or name for collision checks
+ */
+export function normalizeTokenText(text: string): string {
+  const stripped = text.normalize('NFKD').replace(COMBINING_MARKS, '').replace(INVISIBLE, '');
+  let folded = '';
+  for (const char of stripped) {
+    folded += HOMOGLYPHS[char] ?? char;
+  }
+  return folded.toLowerCase();
+}
+
+/**
+ * Edit distance between `a` and `b`, or `limit + 1` once it is known to exceed `limit`
+ */
+export function editDistance(a: string, b: string, limit: number): number {
+  if (Math.abs(a.length - b.length) > limit) {
+    return limit + 1;
+  }
+  let previous = Array.from({ length: b.length + 1 }, (_, j) => j);
+  for (let i = 1; i <= a.length; i++) {
+    const current = [i];
+    let rowMin = i;
+    for (let j = 1; j <= b.length; j++) {
+      const cost = a[i - 1] === b[j - 1] ? 0 : 1;
+      current[j] = Math.min(previous[j] + 1, current[j - 1] + 1, previous[j - 1] + cost);
+      rowMin = Math.min(rowMin, current[j]);
+    }
+    if (rowMin > limit) {
+      return limit + 1;
+    }
+    previous = current;
+  }
+  return previous[b.length];
+}
+
+/**
+ * Why `candidate` collides with `target`, or null when it doesn't. Symbols must be equal once
+ * normalized; names may also differ by one edit when both are at least NEAR_NAME_MIN_LENGTH long.
+ */
+export function matchCollision(
+  target: { symbol: string; name?: string },
+  candidate: { symbol: string; name: string }
+): CollisionReason | null {
+  const symbol = normalizeTokenText(target.symbol);
+  if (symbol !== '' && symbol === normalizeTokenText(candidate.symbol)) {
+    return 'symbol';
+  }
+  if (target.name === undefined) {
+    return null;
+  }
+  const name = normalizeTokenText(target.name);
+  const other = normalizeTokenText(candidate.name);
+  if (name === '' || other === '') {
+    return null;
+  }
+  if (name === other) {
+    return 'name';
+  }
+  const fuzzy = name.length >= NEAR_NAME_MIN_LENGTH && other.length >= NEAR_NAME_MIN_LENGTH;
+  return fuzzy && editDistance(name, other, 1) <= 1 ? 'name' : null;
+}
+
+/**
+ * The `candidates` colliding with `target`, each tagged with the reason, in input order
+ */
+export function findCollisions<T extends { symbol: string; name: string }>(
+  target: { symbol: string; name?: string },
+  candidates: T[]
+): Array<T & { reason: CollisionReason }> {
+  const matches: Array<T & { reason: CollisionReason }> = [];
+  for (const candidate of candidates) {
+    const reason = matchCollision(target, candidate);
+    if (reason) {
+      matches.push({ ...candidate, reason });
+    }
+  }
+  return matches;
+}
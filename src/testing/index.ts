@@ -0,0 +1,42 @@
+/**
+ * Test doubles for code built on the SDK
+ * Imported separately (`@fnzero/four-trading-sdk/testing`) so the main entry point never loads it.
+ *
+ * MockChain is an in-process node with one four.meme token on a flat-priced curve. A FourTrading
+ * given its providers runs unmodified: quotes come from the chain's price, transactions are mined
+ * on receipt into fake receipts with real TokenPurchase/TokenSale logs, and the chain records every
+ * transaction it mined, so bots can be unit-tested deterministically without a live node.
+ * The trading flows are complete bots built on the public API, run against MockChain by the SDK's
+ * own integration tests.
+ */
+
+export {
+  MockChain,
+  MockChainOptions,
+  MockTransaction,
+  MockJsonRpcProvider,
+  MockWebSocket,
+  MOCK_PRIVATE_KEY,
+  MOCK_TOKEN,
+  MOCK_CREATOR,
+  MOCK_MAX_OFFERS,
+  MOCK_MIN_TRADING_FEE,
+} from './mockChain';
+export { MockTrader, MockTraderOptions, createMockTrader, withMockTrader } from './mockTrader';
+export {
+  SnipeExitOptions,
+  SnipeExitResult,
+  ExactBuyResult,
+  SellAllResult,
+  TwapExitOptions,
+  BlindBuyResult,
+  TakeProfitOptions,
+  StrategyFlowResult,
+  TakeProfitStrategy,
+  snipeOnCreateWithExit,
+  quoteAndBuyExact,
+  sellAllWithApproval,
+  twapExit,
+  blindBuyAndConfirm,
+  runTakeProfitStrategy,
+} from './tradingFlows';
@@ -0,0 +1,411 @@
+/**
+ * In-process BSC node for tests: one four.meme token on a flat-priced curve, served over HTTP
+ * (MockJsonRpcProvider) and WebSocket (MockWebSocket). A FourTrading given these providers runs its
+ * real pipeline (quotes, guards, signing, receipt parsing) with no network.
+ */
+
+import { ethers, JsonRpcPayload, JsonRpcProvider, JsonRpcResult, Transaction } from 'ethers';
+import { getErc20Interface, getFourTradingInterface, getMulticall3Interface, getPancakeRouterInterface, getWbnbInterface } from '../abi';
+import { FOUR_MEME_ADDRESS, MULTICALL3_ADDRESS, PANCAKE_V2_ROUTER_ADDRESS, WBNB_ADDRESS } from '../constants';
+
+const ONE = 10n ** 18n;
+export const MOCK_PRIVATE_KEY = '0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d'; // Well-known test key, never fund it
+export const MOCK_TOKEN = '0x1111111111111111111111111111111111111111';
+export const MOCK_CREATOR = '0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa';
+export const MOCK_MAX_OFFERS = 800_000_000n * ONE;
+export const MOCK_MIN_TRADING_FEE = ONE / 1000n;
+const FIRST_BLOCK = 1000;
+
+const four = getFourTradingInterface();
+const erc20 = getErc20Interface();
+const multicall = getMulticall3Interface();
+const wbnb = getWbnbInterface();
+const router = getPancakeRouterInterface();
+const hash32 = (n: number) => ethers.zeroPadValue(ethers.toBeHex(n), 32);
+
+export interface MockChainOptions {
+  tokensPerBnb?: bigint; // Buy price of the curve (default: 1,000,000 tokens per BNB)
+  sellPriceBps?: number; // What sells pay relative to the buy price (default: 10000, the same price)
+  bnbBalance?: bigint; // Wallet BNB balance the node reports (default: 10 BNB)
+  gasPrice?: bigint; // Default: 1 gwei
+}
+
+/**
+ * A transaction the node mined, decoded
+ */
+export interface MockTransaction {
+  hash: string;
+  to: string;
+  name: string; // Called function, e.g. 'buyTokenAMAP', 'sellToken', 'approve', 'withdraw', or 'transfer' for plain BNB
+  args: ReadonlyArray<any>;
+  value: bigint;
+}
+
+/**
+ * Minimal BSC node: one four.meme token (MOCK_TOKEN) on a flat-priced curve, every transaction
+ * mined on receipt unless `holdTransactions` keeps them pending. Balances and prices are public
+ * fields, so tests can set up any state directly.
+ */
+export class MockChain {
+  block = FIRST_BLOCK;
+  nonce = 0;
+  tokensPerBnb: bigint;
+  sellPriceBps: number;
+  bnbBalance: bigint;
+  gasPrice: bigint;
+  tokenBalance = 0n;
+  allowance = 0n; // Of the wallet to the manager
+  wbnbBalance = 0n;
+  offers = MOCK_MAX_OFFERS;
+  funds = 0n;
+  revertNext?: string; // Reject the next transaction with this revert reason
+  holdTransactions = false; // Keep sent transactions pending in `mempool` until release()
+  mempool: Map<number, Transaction> = new Map(); // Pending transactions by nonce; a same-nonce send replaces
+  transactions: MockTransaction[] = [];
+  receipts: Map<string, unknown> = new Map();
+  private mined: Map<string, { tx: Transaction; block: number }> = new Map();
+  private genesisTime = Math.floor(Date.now() / 1000);
+
+  constructor(options: MockChainOptions = {}) {
+    this.tokensPerBnb = options.tokensPerBnb ?? 1_000_000n;
+    this.sellPriceBps = options.sellPriceBps ?? 10000;
+    this.bnbBalance = options.bnbBalance ?? 10n * ONE;
+    this.gasPrice = options.gasPrice ?? 1_000_000_000n;
+  }
+
+  handle(method: string, params: any[]): unknown {
+    switch (method) {
+      case 'eth_chainId':
+        return '0x38';
+      case 'eth_blockNumber':
+        return ethers.toQuantity(this.block);
+      case 'eth_getBlockByNumber':
+        return this.blockAt(params[0] === 'latest' ? this.block : Number(params[0]));
+      case 'eth_gasPrice':
+        return ethers.toQuantity(this.gasPrice);
+      case 'eth_maxPriorityFeePerGas':
+        return '0x0';
+      case 'eth_getTransactionCount':
+        return ethers.toQuantity(params[1] === 'pending' ? this.nonce + this.mempool.size : this.nonce);
+      case 'eth_getBalance':
+        return ethers.toQuantity(this.bnbBalance);
+      case 'eth_estimateGas':
+        return ethers.toQuantity(200000n);
+      case 'eth_call':
+        return this.call(params[0]);
+      case 'eth_getCode':
+        return this.code(params[0]);
+      case 'eth_sendRawTransaction':
+        return this.mine(params[0]);
+      case 'eth_getTransactionReceipt':
+        return this.receipts.get(params[0]) ?? null;
+      case 'eth_getTransactionByHash':
+        return this.transactionByHash(params[0]);
+      default:
+        throw new Error(`unsupported method ${method}`);
+    }
+  }
+
+  private blockAt(number: number) {
+    return {
+      number: ethers.toQuantity(number),
+      hash: hash32(number),
+      parentHash: hash32(number - 1),
+      timestamp: ethers.toQuantity(this.genesisTime + number - FIRST_BLOCK),
+      nonce: '0x0000000000000000',
+      difficulty: '0x2',
+      gasLimit: ethers.toQuantity(140_000_000),
+      gasUsed: '0x0',
+      miner: ethers.ZeroAddress,
+      extraData: '0x',
+      transactions: [],
+    };
+  }
+
+  // Contracts the mock serves; anything else is an account without code
+  private code(address: string): string {
+    const contracts = [MOCK_TOKEN, WBNB_ADDRESS, MULTICALL3_ADDRESS, FOUR_MEME_ADDRESS, PANCAKE_V2_ROUTER_ADDRESS];
+    return contracts.some((contract) => contract.toLowerCase() === address.toLowerCase()) ? '0x6080604052' : '0x';
+  }
+
+  // BNB a sell of `amount` pays before fees
+  private sellCost(amount: bigint): bigint {
+    return (amount * BigInt(this.sellPriceBps)) / 10000n / this.tokensPerBnb;
+  }
+
+  private call(tx: { to: string; data: string }): string {
+    if (this.code(tx.to) === '0x') {
+      return '0x'; // As a node answers a call to an account without code, e.g. TokenManagerHelper3 here
+    }
+    if (tx.to.toLowerCase() === MOCK_TOKEN.toLowerCase()) {
+      const read = erc20.parseTransaction({ data: tx.data });
+      return read?.name === 'allowance'
+        ? erc20.encodeFunctionResult('allowance', [this.allowance])
+        : erc20.encodeFunctionResult('balanceOf', [this.tokenBalance]);
+    }
+    if (tx.to.toLowerCase() === WBNB_ADDRESS.toLowerCase()) {
+      return wbnb.encodeFunctionResult('balanceOf', [this.wbnbBalance]);
+    }
+    if (tx.to.toLowerCase() === PANCAKE_V2_ROUTER_ADDRESS.toLowerCase()) {
+      return router.encodeFunctionResult('WETH', [WBNB_ADDRESS]); // Only WETH(); pairs and swaps are left to subclasses
+    }
+    if (tx.to.toLowerCase() === MULTICALL3_ADDRESS.toLowerCase()) {
+      const [calls] = multicall.decodeFunctionData('aggregate3', tx.data);
+      const results = calls.map((inner: any) => {
+        try {
+          return [true, this.call({ to: inner.target, data: inner.callData })];
+        } catch {
+          return [false, '0x'];
+        }
+      });
+      return multicall.encodeFunctionResult('aggregate3', [results]);
+    }
+
+    const call = four.parseTransaction({ data: tx.data })!;
+    switch (call.name) {
+      case '_tokenInfos':
+        return four.encodeFunctionResult('_tokenInfos', [
+          MOCK_TOKEN, ethers.ZeroAddress, 0n, 1_000_000_000n * ONE, MOCK_MAX_OFFERS, 24n * ONE,
+          BigInt(this.genesisTime), this.offers, this.funds, 0n, 0n, 0n, 0n,
+        ]);
+      case '_templates':
+        return four.encodeFunctionResult('_templates', [ethers.ZeroAddress, 0n, 24n * ONE, 1_000_000_000n * ONE, MOCK_MAX_OFFERS, MOCK_MIN_TRADING_FEE]);
+      case 'calcTradingFee':
+        return four.encodeFunctionResult('calcTradingFee', [call.args[1] / 100n]);
+      case 'calcBuyCost':
+        return four.encodeFunctionResult('calcBuyCost', [call.args[1] / this.tokensPerBnb]);
+      case 'calcBuyAmount':
+        return four.encodeFunctionResult('calcBuyAmount', [call.args[1] * this.tokensPerBnb]);
+      case 'calcSellCost':
+        return four.encodeFunctionResult('calcSellCost', [this.sellCost(call.args[1])]);
+      default:
+        throw new Error(`unsupported call ${call.name}`);
+    }
+  }
+
+  /**
+   * Mine every held transaction, lowest nonce first
+   */
+  release(): void {
+    const held = [...this.mempool.values()].sort((a, b) => a.nonce - b.nonce);
+    this.mempool.clear();
+    held.forEach((tx) => this.execute(tx));
+  }
+
+  private mine(raw: string): string {
+    const tx = Transaction.from(raw);
+    if (this.revertNext !== undefined) {
+      const reason = this.revertNext;
+      this.revertNext = undefined;
+      throw new Error(`execution reverted: ${reason}`);
+    }
+    if (this.holdTransactions) {
+      this.mempool.set(tx.nonce, tx);
+      return tx.hash!;
+    }
+    this.mempool.delete(tx.nonce); // Replaced by this one
+    return this.execute(tx);
+  }
+
+  private execute(tx: Transaction): string {
+    if (tx.to!.toLowerCase() === tx.from!.toLowerCase()) {
+      return this.seal(tx, 'transfer', [], []);
+    }
+    if (tx.to!.toLowerCase() === MOCK_TOKEN.toLowerCase()) {
+      const approve = erc20.parseTransaction({ data: tx.data })!;
+      this.allowance = approve.args[1];
+      return this.seal(tx, approve.name, approve.args, []);
+    }
+    if (tx.to!.toLowerCase() === WBNB_ADDRESS.toLowerCase()) {
+      const withdraw = wbnb.parseTransaction({ data: tx.data })!;
+      this.wbnbBalance -= withdraw.args[0];
+      return this.seal(tx, withdraw.name, withdraw.args, []);
+    }
+
+    const call = four.parseTransaction({ data: tx.data, value: tx.value })!;
+    const account = tx.from!;
+    const price = ONE / this.tokensPerBnb;
+    let event: { topics: string[]; data: string };
+
+    if (call.name === 'buyToken') {
+      const amount: bigint = call.args[2];
+      const cost = amount / this.tokensPerBnb;
+      const fee = cost / 100n;
+      if (tx.value < cost + fee) {
+        throw new Error('execution reverted: more than maxFunds');
+      }
+      this.tokenBalance += amount;
+      this.offers -= amount;
+      this.funds += cost;
+      event = four.encodeEventLog('TokenPurchase', [MOCK_TOKEN, account, price, amount, cost, fee, this.offers, this.funds]);
+    } else if (call.name === 'buyTokenAMAP') {
+      const fee = tx.value / 100n;
+      const cost = tx.value - fee;
+      const amount = cost * this.tokensPerBnb;
+      this.tokenBalance += amount;
+      this.offers -= amount;
+      this.funds += cost;
+      event = four.encodeEventLog('TokenPurchase', [MOCK_TOKEN, account, price, amount, cost, fee, this.offers, this.funds]);
+    } else {
+      const amount: bigint = call.args[1];
+      const cost = this.sellCost(amount);
+      const fee = cost / 100n;
+      const minFunds: bigint = call.args[2] ?? 0n;
+      if (cost - fee < minFunds) {
+        throw new Error('execution reverted: less than minFunds');
+      }
+      this.tokenBalance -= amount;
+      this.offers += amount;
+      this.funds = this.funds > cost ? this.funds - cost : 0n; // A sell above the buy price pays out more than the buys raised
+      const sellPrice = (price * BigInt(this.sellPriceBps)) / 10000n;
+      event = four.encodeEventLog('TokenSale', [MOCK_TOKEN, account, sellPrice, amount, cost, fee, this.offers, this.funds]);
+    }
+    return this.seal(tx, call.name, call.args, [{ address: FOUR_MEME_ADDRESS, ...event }]);
+  }
+
+  /**
+   * Mine `tx` in a new block with `logs`
+   */
+  private seal(tx: Transaction, name: string, args: ReadonlyArray<any>, logs: Array<{ address: string; topics: string[]; data: string }>): string {
+    this.nonce += 1;
+    this.block += 1;
+    this.transactions.push({ hash: tx.hash!, to: tx.to!, name, args, value: tx.value });
+    this.mined.set(tx.hash!, { tx, block: this.block });
+    const blockNumber = ethers.toQuantity(this.block);
+    this.receipts.set(tx.hash!, {
+      transactionHash: tx.hash,
+      transactionIndex: '0x0',
+      blockHash: hash32(this.block),
+      blockNumber,
+      from: tx.from,
+      to: tx.to,
+      contractAddress: null,
+      gasUsed: ethers.toQuantity(150000n),
+      cumulativeGasUsed: ethers.toQuantity(150000n),
+      effectiveGasPrice: ethers.toQuantity(tx.gasPrice ?? this.gasPrice),
+      logsBloom: `0x${'00'.repeat(256)}`,
+      status: '0x1',
+      type: ethers.toQuantity(tx.type ?? 0),
+      logs: logs.map((log, index) => ({
+        ...log,
+        blockNumber,
+        blockHash: hash32(this.block),
+        transactionHash: tx.hash,
+        transactionIndex: '0x0',
+        logIndex: ethers.toQuantity(index),
+        removed: false,
+      })),
+    });
+    return tx.hash!;
+  }
+
+  private transactionByHash(hash: string) {
+    const pending = [...this.mempool.values()].find((tx) => tx.hash === hash);
+    const found = pending ? { tx: pending, block: null } : this.mined.get(hash);
+    if (!found) {
+      return null;
+    }
+    const { tx, block } = found;
+    const signature = tx.signature!;
+    return {
+      hash: tx.hash,
+      type: ethers.toQuantity(tx.type ?? 0),
+      from: tx.from,
+      to: tx.to,
+      nonce: ethers.toQuantity(tx.nonce),
+      gas: ethers.toQuantity(tx.gasLimit),
+      gasPrice: ethers.toQuantity(tx.gasPrice ?? tx.maxFeePerGas ?? 0n),
+      ...(tx.type === 2
+        ? { maxFeePerGas: ethers.toQuantity(tx.maxFeePerGas!), maxPriorityFeePerGas: ethers.toQuantity(tx.maxPriorityFeePerGas!), accessList: [] }
+        : {}),
+      input: tx.data,
+      value: ethers.toQuantity(tx.value),
+      chainId: ethers.toQuantity(tx.chainId),
+      r: signature.r,
+      s: signature.s,
+      v: ethers.toQuantity(tx.type === 2 ? signature.yParity : signature.networkV ?? signature.v),
+      blockNumber: block === null ? null : ethers.toQuantity(block),
+      blockHash: block === null ? null : hash32(block),
+      transactionIndex: block === null ? null : '0x0',
+    };
+  }
+
+  /**
+   * The TokenCreate log announcing MOCK_TOKEN, pushed to every log subscription
+   */
+  tokenCreateLog() {
+    const event = four.encodeEventLog('TokenCreate', [MOCK_CREATOR, MOCK_TOKEN, 1n, 'Snipe', 'SNP', 1_000_000_000n * ONE, BigInt(this.genesisTime), 0n]);
+    return {
+      address: FOUR_MEME_ADDRESS,
+      topics: event.topics,
+      data: event.data,
+      blockNumber: ethers.toQuantity(this.block),
+      blockHash: hash32(this.block),
+      transactionHash: hash32(1),
+      transactionIndex: '0x0',
+      logIndex: '0x0',
+      removed: false,
+    };
+  }
+}
+
+function rpcResponse(chain: MockChain, request: JsonRpcPayload): any {
+  try {
+    return { jsonrpc: '2.0', id: request.id, result: chain.handle(request.method, request.params as any[]) };
+  } catch (error: any) {
+    return { jsonrpc: '2.0', id: request.id, error: { code: -32000, message: error.message } };
+  }
+}
+
+/**
+ * HTTP endpoint of a mock chain
+ */
+export class MockJsonRpcProvider extends JsonRpcProvider {
+  constructor(private chain: MockChain) {
+    super();
+  }
+
+  async _send(payload: JsonRpcPayload | Array<JsonRpcPayload>): Promise<Array<JsonRpcResult>> {
+    return (Array.isArray(payload) ? payload : [payload]).map((request) => rpcResponse(this.chain, request));
+  }
+}
+
+/**
+ * WebSocket endpoint of a mock chain; announces the token launch once a log subscription exists
+ */
+export class MockWebSocket {
+  onopen: ((...args: any[]) => any) | null = null;
+  onmessage: ((...args: any[]) => any) | null = null;
+  onerror: ((...args: any[]) => any) | null = null;
+  readyState = 1;
+
+  constructor(private chain: MockChain) {
+    setTimeout(() => this.onopen?.(), 0);
+  }
+
+  send(payload: string): void {
+    const request = JSON.parse(payload);
+    if (request.method === 'eth_subscribe' && request.params[0] === 'newPendingTransactions') {
+      this.push({ jsonrpc: '2.0', id: request.id, result: '0x52' }); // Pending buys are not simulated
+      return;
+    }
+    if (request.method === 'eth_subscribe') {
+      this.push({ jsonrpc: '2.0', id: request.id, result: '0x51' });
+      this.push({ jsonrpc: '2.0', method: 'eth_subscription', params: { subscription: '0x51', result: this.chain.tokenCreateLog() } });
+      return;
+    }
+    if (request.method === 'eth_unsubscribe') {
+      this.push({ jsonrpc: '2.0', id: request.id, result: true });
+      return;
+    }
+    this.push(rpcResponse(this.chain, request));
+  }
+
+  close(): void {
+    this.readyState = 3;
+  }
+
+  private push(message: unknown): void {
+    setTimeout(() => this.readyState === 1 && this.onmessage?.({ data: JSON.stringify(message) }), 5);
+  }
+}
@@ -0,0 +1,59 @@
+import { WebSocketProvider } from 'ethers';
+import { FourTrading, FourTradingConfig } from '../fourTrading';
+import { MOCK_PRIVATE_KEY, MockChain, MockChainOptions, MockJsonRpcProvider, MockWebSocket } from './mockChain';
+
+export interface MockTraderOptions extends MockChainOptions {
+  config?: Partial<FourTradingConfig>; // Trader settings, e.g. guards or slippage; providers and the key are supplied
+}
+
+/**
+ * A FourTrading wired to a fresh MockChain, and `close` to release its providers
+ */
+export interface MockTrader {
+  trader: FourTrading;
+  chain: MockChain;
+  close(): Promise<void>;
+}
+
+/**
+ * Build a real FourTrading over an in-process MockChain. Quotes, guards, signing and receipt
+ * parsing all run as they do against a node; the chain's public fields set up balances and prices.
+ */
+export function createMockTrader(options: MockTraderOptions = {}): MockTrader {
+  const chain = new MockChain(options);
+  const provider = new MockJsonRpcProvider(chain);
+  const eventProvider = new WebSocketProvider(() => new MockWebSocket(chain) as any, undefined, { cacheTimeout: -1 });
+  const trader = new FourTrading({
+    rpcUrl: '',
+    wssUrl: '',
+    privateKey: MOCK_PRIVATE_KEY,
+    ...options.config,
+    provider,
+    eventProvider,
+  });
+
+  return {
+    trader,
+    chain,
+    async close() {
+      trader.removeAllListeners();
+      provider.destroy();
+      await eventProvider.destroy();
+    },
+  };
+}
+
+/**
+ * Run `test` against a fresh mock trader, closing it afterwards even when the test throws
+ */
+export async function withMockTrader<T>(
+  test: (trader: FourTrading, chain: MockChain) => Promise<T>,
+  options: MockTraderOptions = {}
+): Promise<T> {
+  const mock = createMockTrader(options);
+  try {
+    return await test(mock.trader, mock.chain);
+  } finally {
+    await mock.close();
+  }
+}
@@ -0,0 +1,237 @@
+/**
+ * End-to-end trading flows built only from the public API
+ * tests/integration/tradingFlows.test.ts runs each flow against an in-process chain and checks the
+ * amounts, tags and journals it produces, so these stay in step with the SDK. examples/tradingFlows.ts
+ * runs them live.
+ */
+
+import type { FourTrading } from '../fourTrading';
+import { ONE_ETHER } from '../constants';
+import { PriceChangeFilter } from '../priceStream';
+import { ApprovalConfig, BacktestDriver, OwnFill, Strategy, StrategyEvent, StrategyRunner, TradeEvent, TradeIntent } from '../strategy';
+import { BatchApproveResult, PendingTrade, TokenCreateEvent, TransactionResult } from '../types';
+
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+// ==================== Snipe on create, exit on profit or timeout ====================
+
+export interface SnipeExitOptions {
+  fundsInBNB: bigint;
+  filter?: (event: TokenCreateEvent) => boolean | Promise<boolean>; // Launches to skip return false
+  takeProfitMultiple: number; // Sell once the bag quotes this multiple of the spend
+  maxHoldMs: number; // Sell regardless after holding this long
+  pollMs?: number; // How often the bag is re-quoted (default: 1000)
+}
+
+export interface SnipeExitResult {
+  token: string;
+  buy: TransactionResult;
+  sell: TransactionResult;
+  exitReason: 'takeProfit' | 'timeout';
+}
+
+/**
+ * Buy the first launch that passes `filter`, then sell the whole bag at the profit target or the timeout
+ */
+export async function snipeOnCreateWithExit(trader: FourTrading, options: SnipeExitOptions): Promise<SnipeExitResult> {
+  const created = await new Promise<TokenCreateEvent>((resolve) => {
+    let done = false;
+    const id = trader.onTokenCreate(async (event) => {
+      if (done || (options.filter && !(await options.filter(event)))) {
+        return;
+      }
+      done = true;
+      trader.off(id);
+      resolve(event);
+    });
+  });
+
+  const buy = await trader.buyToken({ tokenAddress: created.token, fundsInBNB: options.fundsInBNB, tags: ['flow:snipe'] });
+  const target = (options.fundsInBNB * BigInt(Math.round(options.takeProfitMultiple * 10000))) / 10000n;
+  const deadline = Date.now() + options.maxHoldMs;
+
+  let exitReason: SnipeExitResult['exitReason'];
+  for (;;) {
+    const bag = await trader.getTokenBalanceRaw(created.token);
+    const quote = await trader.quoteSell(created.token, bag, { allowDustFills: true });
+    if (quote.bnbCost >= target) {
+      exitReason = 'takeProfit';
+      break;
+    }
+    if (Date.now() >= deadline) {
+      exitReason = 'timeout';
+      break;
+    }
+    await sleep(options.pollMs ?? 1000);
+  }
+
+  const sell = await trader.sellAll(created.token, undefined, undefined, { tags: ['flow:snipe', `exit:${exitReason}`] });
+  return { token: created.token, buy, sell, exitReason };
+}
+
+// ==================== Quote, then buy an exact amount ====================
+
+export interface ExactBuyResult {
+  quotedFunds: bigint; // Curve cost plus trading fee
+  maxFunds: bigint; // Quoted funds plus the slippage allowance
+  result: TransactionResult;
+}
+
+/**
+ * Quote the BNB needed for exactly `amount` tokens and buy them, paying at most `slippageBps` more
+ */
+export async function quoteAndBuyExact(trader: FourTrading, tokenAddress: string, amount: bigint, slippageBps: number): Promise<ExactBuyResult> {
+  const info = await trader.getTokenInfo(tokenAddress);
+  const cost = await trader.calcBuyCost(info, amount);
+  const quotedFunds = cost + (await trader.calcTradingFee(info, cost));
+  const maxFunds = (quotedFunds * (10000n + BigInt(slippageBps))) / 10000n;
+
+  const result = await trader.buyTokenExact(tokenAddress, amount, maxFunds, undefined, undefined, { tags: ['flow:buyExact'] });
+  return { quotedFunds, maxFunds, result };
+}
+
+// ==================== Sell everything, approving first if needed ====================
+
+export interface SellAllResult {
+  approval: BatchApproveResult;
+  sell: TransactionResult;
+}
+
+/**
+ * Approve the manager for the wallet's balance when the allowance falls short, then sell all of it
+ */
+export async function sellAllWithApproval(trader: FourTrading, tokenAddress: string): Promise<SellAllResult> {
+  const balance = await trader.getTokenBalanceRaw(tokenAddress);
+  const [approval] = await trader.batchApprove([tokenAddress], balance);
+  if (approval.status === 'failed') {
+    throw new Error(`Approval of ${tokenAddress} failed: ${approval.error}`);
+  }
+
+  const sell = await trader.sellAll(tokenAddress, undefined, undefined, { tags: ['flow:sellAll'] });
+  return { approval, sell };
+}
+
+// ==================== TWAP exit ====================
+
+export interface TwapExitOptions {
+  slices: number; // Equal sells; the last one also takes the rounding remainder
+  intervalMs: number; // Wait between slices
+  signal?: AbortSignal; // Stops before the next slice; slices already sold stay sold
+}
+
+/**
+ * Sell the wallet's balance in `slices` equal parts spaced `intervalMs` apart
+ */
+export async function twapExit(trader: FourTrading, tokenAddress: string, options: TwapExitOptions): Promise<TransactionResult[]> {
+  const total = await trader.getTokenBalanceRaw(tokenAddress);
+  const slice = total / BigInt(options.slices);
+  const results: TransactionResult[] = [];
+
+  for (let i = 0; i < options.slices; i++) {
+    if (i > 0) {
+      await sleep(options.intervalMs);
+    }
+    if (options.signal?.aborted) {
+      break;
+    }
+    const amount = i === options.slices - 1 ? total - slice * BigInt(i) : slice;
+    results.push(await trader.sellToken({
+      tokenAddress,
+      amount,
+      tags: ['flow:twap', `slice:${i + 1}/${options.slices}`],
+      signal: options.signal,
+    }));
+  }
+  return results;
+}
+
+// ==================== Blind buy, confirmed from its receipt ====================
+
+export interface BlindBuyResult {
+  pending: PendingTrade; // Handed back as soon as the signed buy was broadcast
+  result: TransactionResult;
+}
+
+/**
+ * Send a buy with the caller's gas price and no quote, then complete it through its PendingTrade
+ */
+export async function blindBuyAndConfirm(trader: FourTrading, tokenAddress: string, fundsInBNB: bigint, gasPrice: bigint): Promise<BlindBuyResult> {
+  await trader.syncNonce();
+  const pending = await trader.buyBlind(tokenAddress, fundsInBNB, 0n, { gasPrice }, undefined, { tags: ['flow:blind'] });
+  const result = await pending.wait();
+  return { pending, result };
+}
+
+// ==================== Strategy: buy launches, take profit on filtered price moves ====================
+
+export interface TakeProfitOptions {
+  fundsInBNB: bigint;
+  takeProfitMultiple: number; // Sell the bag once the price reaches this multiple of the entry price
+  minChangeBps: number; // Price moves smaller than this since the last one acted on are ignored
+}
+
+/**
+ * Buys every launch and sells the bag once a trade moves the price past the profit target. Trade
+ * prices go through a PriceChangeFilter per token, so only meaningful moves are acted on.
+ */
+export class TakeProfitStrategy implements Strategy {
+  readonly seenPrices: bigint[] = []; // Prices the filter let through, in order
+  private filters: Map<string, PriceChangeFilter> = new Map();
+  private bags: Map<string, { tokens: bigint; entryPrice: bigint }> = new Map();
+
+  constructor(private options: TakeProfitOptions) {}
+
+  onTokenCreated(event: TokenCreateEvent): TradeIntent[] {
+    return [{ side: 'buy', tokenAddress: event.token, fundsInBNB: this.options.fundsInBNB, tags: ['flow:strategy'] }];
+  }
+
+  onOwnFill(fill: OwnFill): void {
+    if (fill.intent.side === 'buy' && fill.tokenAmount > 0n) {
+      this.bags.set(fill.tokenAddress.toLowerCase(), {
+        tokens: fill.tokenAmount,
+        entryPrice: (fill.bnbAmount * ONE_ETHER) / fill.tokenAmount,
+      });
+    }
+  }
+
+  onTradeEvent(trade: TradeEvent): TradeIntent[] {
+    const key = trade.event.token.toLowerCase();
+    let filter = this.filters.get(key);
+    if (!filter) {
+      filter = new PriceChangeFilter(this.options.minChangeBps);
+      this.filters.set(key, filter);
+    }
+    if (!filter.accept(trade.event.price)) {
+      return [];
+    }
+    this.seenPrices.push(trade.event.price);
+
+    const bag = this.bags.get(key);
+    const multipleBps = BigInt(Math.round(this.options.takeProfitMultiple * 10000));
+    if (!bag || trade.event.price * 10000n < bag.entryPrice * multipleBps) {
+      return [];
+    }
+    this.bags.delete(key);
+    return [{ side: 'sell', tokenAddress: trade.event.token, amount: bag.tokens, tags: ['flow:strategy', 'exit:takeProfit'] }];
+  }
+}
+
+export interface StrategyFlowResult {
+  strategy: TakeProfitStrategy;
+  runner: StrategyRunner; // Its approval journal records every gated buy
+}
+
+/**
+ * Run TakeProfitStrategy over a recorded event timeline, trading for real through `trader`.
+ * Buys above `approval.thresholdBnb` pass the approval gate first.
+ */
+export async function runTakeProfitStrategy(
+  trader: FourTrading,
+  events: StrategyEvent[],
+  options: TakeProfitOptions,
+  approval?: ApprovalConfig
+): Promise<StrategyFlowResult> {
+  const strategy = new TakeProfitStrategy(options);
+  const runner = await StrategyRunner.run(strategy, trader, { driver: new BacktestDriver(events), approval });
+  return { strategy, runner };
+}
@@ -0,0 +1,72 @@
+/**
+ * Token creation lookup
+ * Finds a token's TokenCreate event by searching backward from the current block in bounded windows
+ */
+
+import { getFourTradingInterface } from './abi';
+import { BackfillLog } from './backfill';
+import { TokenTooOldError, TokenTooYoungError } from './errors';
+
+export interface TokenCreation {
+  block: number;
+  timestamp: number; // Block timestamp (Unix seconds)
+}
+
+export interface TokenAgeLimits {
+  minTokenAge?: number; // Seconds
+  maxTokenAge?: number; // Seconds
+}
+
+/**
+ * Throw unless a token `age` seconds old is within `limits`. A token created before the creation
+ * lookback (`beforeLookback`) only has a lower bound on its age: it is taken to be older than any
+ * maximum, and old enough for any minimum.
+ */
+export function assertTokenAge(age: number, limits: TokenAgeLimits, beforeLookback: boolean = false): void {
+  if (limits.maxTokenAge !== undefined && (beforeLookback || age > limits.maxTokenAge)) {
+    throw new TokenTooOldError(age, limits.maxTokenAge, beforeLookback);
+  }
+  if (limits.minTokenAge !== undefined && !beforeLookback && age < limits.minTokenAge) {
+    throw new TokenTooYoungError(age, limits.minTokenAge);
+  }
+}
+
+/**
+ * Fetches the contract's TokenCreate logs for an inclusive block range
+ */
+export type TokenCreateLogSource = (fromBlock: number, toBlock: number) => Promise<BackfillLog[]>;
+
+/**
+ * Search backward from `latestBlock` in `windowSize` ranges, never looking further back than
+ * `maxLookback` blocks. Returns the creation block, or null when not found within the bound.
+ */
+export async function findTokenCreationBlock(
+  tokenAddress: string,
+  latestBlock: number,
+  fetchLogs: TokenCreateLogSource,
+  windowSize: number,
+  maxLookback: number
+): Promise<number | null> {
+  const iface = getFourTradingInterface();
+  const token = tokenAddress.toLowerCase();
+  const lowest = Math.max(0, latestBlock - maxLookback);
+
+  for (let toBlock = latestBlock; toBlock >= lowest; toBlock -= windowSize) {
+    const fromBlock = Math.max(lowest, toBlock - windowSize + 1);
+    const logs = await fetchLogs(fromBlock, toBlock);
+
+    for (const log of logs) {
+      let parsed;
+      try {
+        parsed = iface.parseLog({ topics: [...log.topics], data: log.data });
+      } catch {
+        continue;
+      }
+      if (parsed?.name === 'TokenCreate' && String(parsed.args.token).toLowerCase() === token) {
+        return log.blockNumber;
+      }
+    }
+  }
+
+  return null;
+}
@@ -0,0 +1,415 @@
+/**
+ * Discovery of tokens still trading on the bonding curve
+ * Scans the contract's logs backward from the chain head in bounded windows, newest creation first,
+ * and confirms graduation with batched `_tokenInfos` reads. Scanned ranges are cached, so later
+ * pages and refreshes only query blocks not seen yet; attaching to a trader keeps the cache current
+ * from the live creation, trade and graduation streams instead.
+ */
+
+import { getFourTradingInterface } from './abi';
+import { BackfillLog } from './backfill';
+import { BACKFILL_PAGE_SIZE, TOKEN_CREATE_LOOKBACK_BLOCKS } from './constants';
+import { ValidationError } from './errors';
+import type { FourTrading } from './fourTrading';
+import { bpsOf, toNumberChecked } from './math';
+import { TokenInfo } from './types';
+
+export interface DirectoryLog extends BackfillLog {
+  index: number; // Log index within the block
+}
+
+/**
+ * Position of a token's creation log; a page continues with tokens created strictly before it
+ */
+export interface TokenDirectoryCursor {
+  block: number;
+  index: number;
+}
+
+export interface TokenSummary {
+  token: string;
+  name: string;
+  symbol: string;
+  createdBlock: number;
+  ageSeconds: number; // Since the token's launch time
+  progressBps: number; // BNB raised as basis points of the graduation target
+  lastTradeAt: number | null; // Unix seconds; null when no trade was seen since the scan reached the token
+}
+
+/**
+ * A creation log from the directory's index
+ */
+export interface TokenCreationRecord {
+  token: string;
+  name: string;
+  symbol: string;
+  creator: string;
+  block: number;
+  index: number;
+}
+
+export interface TokenDirectoryPage {
+  tokens: TokenSummary[];
+  cursor: TokenDirectoryCursor | null; // null once the lookback bound is exhausted
+}
+
+export interface TokenDirectorySource {
+  getBlockNumber(): Promise<number>;
+  getLogs(fromBlock: number, toBlock: number): Promise<DirectoryLog[]>; // Creation, trade and LiquidityAdded logs
+  readTokenInfos(tokens: string[]): Promise<Array<TokenInfo | null>>; // One batched read; null when a read fails
+  getBlockTimestamp(block: number): Promise<number>;
+}
+
+export interface TokenDirectoryOptions {
+  tradingStatus: bigint; // The contract's STATUS_TRADING value
+  windowSize?: number; // Blocks per getLogs query (default: BACKFILL_PAGE_SIZE)
+  maxLookback?: number; // Blocks below the first head seen that are ever scanned (default: TOKEN_CREATE_LOOKBACK_BLOCKS)
+  now?: () => number; // Unix seconds
+}
+
+interface DirectoryEntry {
+  token: string;
+  name: string;
+  symbol: string;
+  creator: string;
+  block: number;
+  index: number;
+  launchTime: number;
+  lastTradeBlock: number | null;
+  lastTradeAt: number | null; // Set directly by live trades, which carry no block
+  graduated: boolean;
+}
+
+interface CreatedToken {
+  token: string;
+  name: string;
+  symbol: string;
+  creator: string;
+  launchTime: number;
+}
+
+function before(a: TokenDirectoryCursor, b: TokenDirectoryCursor): boolean {
+  return a.block < b.block || (a.block === b.block && a.index < b.index);
+}
+
+export class TokenDirectory {
+  private entries: Map<string, DirectoryEntry> = new Map();
+  private pendingTrades: Map<string, number> = new Map(); // Latest trade of tokens whose creation is further back
+  private pendingGraduations: Set<string> = new Set();
+  private blockTimes: Map<number, number> = new Map();
+  private low: number | null = null; // Lowest block scanned
+  private high: number | null = null; // Highest block covered by scans or live events
+  private floor = 0;
+  private live = false;
+  private liveIndex = 0;
+  private windowSize: number;
+  private maxLookback: number;
+  private now: () => number;
+
+  constructor(private source: TokenDirectorySource, private options: TokenDirectoryOptions) {
+    this.windowSize = Math.max(1, options.windowSize ?? BACKFILL_PAGE_SIZE);
+    this.maxLookback = options.maxLookback ?? TOKEN_CREATE_LOOKBACK_BLOCKS;
+    this.now = options.now ?? (() => Math.floor(Date.now() / 1000));
+  }
+
+  /**
+   * Up to `limit` non-graduated tokens, newest first, created before `cursor` (from the head when omitted).
+   * Without a cursor, blocks mined since the last call are scanned first unless the directory is attached.
+   */
+  async list(limit: number, cursor?: TokenDirectoryCursor): Promise<TokenDirectoryPage> {
+    if (!Number.isInteger(limit) || limit <= 0) {
+      throw new ValidationError(`Page limit must be a positive integer, got ${limit}`);
+    }
+
+    if (this.high === null) {
+      await this.init();
+    } else if (!cursor && !this.live) {
+      await this.refresh();
+    }
+
+    let position = cursor ?? { block: this.high + 1, index: 0 };
+    const page: Array<{ entry: DirectoryEntry; info: TokenInfo }> = [];
+
+    while (page.length < limit) {
+      const candidates = [...this.entries.values()]
+        .filter((entry) => !entry.graduated && before(entry, position))
+        .sort((a, b) => (before(a, b) ? 1 : -1));
+
+      for (let i = 0; i < candidates.length && page.length < limit; ) {
+        const batch = candidates.slice(i, i + limit - page.length);
+        i += batch.length;
+        const infos = await this.source.readTokenInfos(batch.map((entry) => entry.token));
+        batch.forEach((entry, j) => {
+          const info = infos[j];
+          if (info && info.status !== this.options.tradingStatus) {
+            entry.graduated = true; // Graduation is one-way
+          } else if (info) {
+            page.push({ entry, info });
+          }
+        });
+        position = batch[batch.length - 1];
+      }
+
+      if (page.length >= limit || this.low! <= this.floor) {
+        break;
+      }
+      position = before(position, { block: this.low!, index: 0 }) ? position : { block: this.low!, index: 0 };
+      await this.scanDown();
+    }
+
+    const now = this.now();
+    const tokens: TokenSummary[] = [];
+    for (const { entry, info } of page) {
+      tokens.push({
+        token: entry.token,
+        name: entry.name,
+        symbol: entry.symbol,
+        createdBlock: entry.block,
+        ageSeconds: Math.max(0, now - entry.launchTime),
+        progressBps: info.maxRaising > 0n ? bpsOf(info.funds, info.maxRaising, 'progress') : 0,
+        lastTradeAt: await this.lastTradeAt(entry),
+      });
+    }
+
+    const last = page[page.length - 1]?.entry;
+    const exhausted = page.length < limit && this.low! <= this.floor;
+    return { tokens, cursor: exhausted || !last ? null : { block: last.block, index: last.index } };
+  }
+
+  /**
+   * Every token created from `fromBlock` to the head, graduated or not, oldest first. Scans whatever
+   * part of that range is not indexed yet; nothing below the lookback bound is ever indexed.
+   */
+  async creationsSince(fromBlock: number): Promise<TokenCreationRecord[]> {
+    await this.init();
+    if (!this.live) {
+      await this.refresh();
+    }
+    while (this.low! > Math.max(fromBlock, this.floor)) {
+      await this.scanDown();
+    }
+    return [...this.entries.values()]
+      .filter((entry) => entry.block >= fromBlock)
+      .sort((a, b) => (before(a, b) ? -1 : 1))
+      .map(({ token, name, symbol, creator, block, index }) => ({ token, name, symbol, creator, block, index }));
+  }
+
+  /**
+   * The token's creation record if the index already holds it; reads nothing
+   */
+  indexedCreation(token: string): TokenCreationRecord | null {
+    const entry = this.entries.get(token.toLowerCase());
+    if (!entry) {
+      return null;
+    }
+    const { name, symbol, creator, block, index } = entry;
+    return { token: entry.token, name, symbol, creator, block, index };
+  }
+
+  /**
+   * Highest block covered by the index, scanning blocks mined since the last scan unless attached
+   */
+  async head(): Promise<number> {
+    await this.init();
+    if (!this.live) {
+      await this.refresh();
+    }
+    return this.high!;
+  }
+
+  /**
+   * Scan blocks mined since the highest covered block
+   */
+  async refresh(): Promise<void> {
+    if (this.high === null) {
+      return;
+    }
+    const head = await this.source.getBlockNumber();
+    for (let fromBlock = this.high + 1; fromBlock <= head; fromBlock += this.windowSize) {
+      const toBlock = Math.min(head, fromBlock + this.windowSize - 1);
+      const logs = await this.source.getLogs(fromBlock, toBlock);
+      logs.sort((a, b) => a.blockNumber - b.blockNumber || a.index - b.index).forEach((log) => this.applyForward(log));
+      this.high = toBlock;
+    }
+  }
+
+  /**
+   * Keep the cache current from a trader's live streams; returns a detach function.
+   * Tokens created while attached are positioned at the latest block seen.
+   */
+  attach(trader: FourTrading): () => void {
+    this.live = true;
+    const ids = [
+      trader.onBlock((block) => {
+        if (this.high !== null && block > this.high) {
+          this.high = block;
+          this.liveIndex = 0;
+        }
+      }),
+      trader.onTokenCreate((event) => {
+        if (this.high !== null) {
+          this.addEntry({ ...event, launchTime: toNumberChecked(event.launchTime, 'launchTime') }, this.high, this.liveIndex++);
+        }
+      }),
+      trader.onTokenPurchase((event) => this.recordLiveTrade(event.token)),
+      trader.onTokenSale((event) => this.recordLiveTrade(event.token)),
+      trader.onLiquidityAdded((event) => this.markGraduated(event.base)),
+    ];
+
+    return () => {
+      this.live = false;
+      for (const id of ids) {
+        trader.off(id);
+      }
+    };
+  }
+
+  private async init(): Promise<void> {
+    if (this.high === null) {
+      const head = await this.source.getBlockNumber();
+      this.high = head;
+      this.low = head + 1;
+      this.floor = Math.max(0, head - this.maxLookback);
+    }
+  }
+
+  private async scanDown(): Promise<void> {
+    const toBlock = this.low! - 1;
+    const fromBlock = Math.max(this.floor, toBlock - this.windowSize + 1);
+    const logs = await this.source.getLogs(fromBlock, toBlock);
+    // Newest first, so the first trade seen for a token is its latest
+    logs.sort((a, b) => b.blockNumber - a.blockNumber || b.index - a.index).forEach((log) => this.applyBackward(log));
+    this.low = fromBlock;
+  }
+
+  private applyBackward(log: DirectoryLog): void {
+    const parsed = this.parse(log);
+    if (!parsed) {
+      return;
+    }
+    const key = parsed.token.toLowerCase();
+    const entry = this.entries.get(key);
+
+    if (parsed.kind === 'create') {
+      if (!entry) {
+        this.addEntry(parsed, log.blockNumber, log.index);
+        const created = this.entries.get(key)!;
+        created.lastTradeBlock = this.pendingTrades.get(key) ?? null;
+        created.graduated = this.pendingGraduations.has(key);
+        this.pendingTrades.delete(key);
+        this.pendingGraduations.delete(key);
+      }
+    } else if (parsed.kind === 'trade') {
+      if (entry) {
+        entry.lastTradeBlock ??= log.blockNumber;
+      } else if (!this.pendingTrades.has(key)) {
+        this.pendingTrades.set(key, log.blockNumber);
+      }
+    } else if (entry) {
+      entry.graduated = true;
+    } else {
+      this.pendingGraduations.add(key);
+    }
+  }
+
+  private applyForward(log: DirectoryLog): void {
+    const parsed = this.parse(log);
+    if (!parsed) {
+      return;
+    }
+    const entry = this.entries.get(parsed.token.toLowerCase());
+
+    if (parsed.kind === 'create') {
+      this.addEntry(parsed, log.blockNumber, log.index);
+    } else if (entry && parsed.kind === 'trade') {
+      entry.lastTradeBlock = log.blockNumber;
+      entry.lastTradeAt = null;
+    } else if (entry) {
+      entry.graduated = true;
+    }
+  }
+
+  private recordLiveTrade(token: string): void {
+    const entry = this.entries.get(token.toLowerCase());
+    if (entry) {
+      entry.lastTradeAt = this.now();
+    }
+  }
+
+  private markGraduated(token: string): void {
+    const entry = this.entries.get(token.toLowerCase());
+    if (entry) {
+      entry.graduated = true;
+    }
+  }
+
+  private addEntry(created: CreatedToken, block: number, index: number): void {
+    const { token, name, symbol, creator, launchTime } = created;
+    const key = token.toLowerCase();
+    if (this.entries.has(key)) {
+      return;
+    }
+    this.entries.set(key, {
+      token,
+      name,
+      symbol,
+      creator,
+      block,
+      index,
+      launchTime,
+      lastTradeBlock: null,
+      lastTradeAt: null,
+      graduated: false,
+    });
+  }
+
+  private async lastTradeAt(entry: DirectoryEntry): Promise<number | null> {
+    if (entry.lastTradeAt !== null || entry.lastTradeBlock === null) {
+      return entry.lastTradeAt;
+    }
+    entry.lastTradeAt = await this.blockTimestamp(entry.lastTradeBlock);
+    return entry.lastTradeAt;
+  }
+
+  /**
+   * Timestamp of `block` (Unix seconds), cached
+   */
+  async blockTimestamp(block: number): Promise<number> {
+    let timestamp = this.blockTimes.get(block);
+    if (timestamp === undefined) {
+      timestamp = await this.source.getBlockTimestamp(block);
+      this.blockTimes.set(block, timestamp);
+    }
+    return timestamp;
+  }
+
+  private parse(log: DirectoryLog):
+    | ({ kind: 'create' } & CreatedToken)
+    | { kind: 'trade' | 'graduate'; token: string }
+    | null {
+    let parsed;
+    try {
+      parsed = getFourTradingInterface().parseLog({ topics: [...log.topics], data: log.data });
+    } catch {
+      return null;
+    }
+    switch (parsed?.name) {
+      case 'TokenCreate':
+        return {
+          kind: 'create',
+          token: String(parsed.args.token),
+          creator: String(parsed.args.creator),
+          name: String(parsed.args.name),
+          symbol: String(parsed.args.symbol),
+          launchTime: toNumberChecked(parsed.args.launchTime, 'launchTime'),
+        };
+      case 'TokenPurchase':
+      case 'TokenSale':
+        return { kind: 'trade', token: String(parsed.args.token) };
+      case 'LiquidityAdded':
+        return { kind: 'graduate', token: String(parsed.args.base) };
+      default:
+        return null;
+    }
+  }
+}
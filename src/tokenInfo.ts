@@ -0,0 +1,47 @@
+/**
+ * Strict decoding of the TokenManager's `_tokenInfos` entry
+ * ABI decoding reads the fields it expects and ignores anything after them, so a contract upgrade
+ * that added or removed fields would be decoded into the wrong names without an error. Every
+ * field of the struct is static, so the return data must be exactly one word per field; any other
+ * length is rejected before decoding. A reorder that keeps the field count has the same length
+ * and is not detected here.
+ */
+
+import { getFourTradingInterface } from './abi';
+import { TokenInfoLayoutError } from './errors';
+import { TokenInfo } from './types';
+
+// Field order of the on-chain struct, as returned by `_tokenInfos(address)`
+export const TOKEN_INFO_FIELDS = [
+  'base', 'quote', 'template', 'totalSupply', 'maxOffers', 'maxRaising', 'launchTime',
+  'offers', 'funds', 'lastPrice', 'K', 'T', 'status',
+] as const;
+
+/**
+ * TokenInfo from raw `_tokenInfos` return data. Throws TokenInfoLayoutError when the data is not
+ * exactly one 32-byte word per field.
+ */
+export function decodeTokenInfo(returnData: string): TokenInfo {
+  const expectedBytes = TOKEN_INFO_FIELDS.length * 32;
+  const actualBytes = (returnData.length - 2) / 2;
+  if (actualBytes !== expectedBytes) {
+    throw new TokenInfoLayoutError(expectedBytes, actualBytes);
+  }
+
+  const info = getFourTradingInterface().decodeFunctionResult('_tokenInfos', returnData);
+  return {
+    base: info.base,
+    quote: info.quote,
+    template: info.template,
+    totalSupply: info.totalSupply,
+    maxOffers: info.maxOffers,
+    maxRaising: info.maxRaising,
+    launchTime: info.launchTime,
+    offers: info.offers,
+    funds: info.funds,
+    lastPrice: info.lastPrice,
+    K: info.K,
+    T: info.T,
+    status: info.status,
+  };
+}
@@ -0,0 +1,122 @@
+/**
+ * Token creation parameters and client-side validation
+ * createToken takes arguments already encoded by the four.meme API; validating the metadata they
+ * were built from catches input the platform would reject before any request is made
+ */
+
+import { TOKEN_LAUNCH_LIMITS } from './constants';
+
+export interface TokenLaunchParams {
+  name: string;
+  symbol: string;
+  description: string;
+  imageUrl?: string; // Required by the platform; templates leave it unset
+  label: string; // Category, one of TOKEN_LAUNCH_LIMITS.labels
+  website?: string;
+  twitter?: string;
+  telegram?: string;
+  preSale: bigint; // Creator's initial buy in wei
+  launchTime?: number; // Unix seconds; unset launches immediately
+}
+
+export interface TokenLaunchViolation {
+  field: keyof TokenLaunchParams;
+  message: string;
+}
+
+/**
+ * Launch parameters for a meme token with defaults for everything but the image
+ */
+export function memeTokenTemplate(name: string, symbol: string): TokenLaunchParams {
+  return {
+    name,
+    symbol,
+    description: `${name} ($${symbol})`,
+    label: 'Meme',
+    preSale: 0n,
+  };
+}
+
+function checkLength(
+  violations: TokenLaunchViolation[],
+  field: keyof TokenLaunchParams,
+  value: string,
+  limits: { min?: number; max: number }
+): void {
+  const length = [...value].length;
+  if (limits.min !== undefined && length < limits.min) {
+    violations.push({ field, message: `${field} must be at least ${limits.min} characters` });
+  } else if (length > limits.max) {
+    violations.push({ field, message: `${field} must be at most ${limits.max} characters, got ${length}` });
+  }
+}
+
+function checkLink(violations: TokenLaunchViolation[], field: keyof TokenLaunchParams, value: string): URL | null {
+  let url: URL;
+  try {
+    url = new URL(value);
+  } catch {
+    violations.push({ field, message: `${field} is not a valid URL` });
+    return null;
+  }
+  if (!TOKEN_LAUNCH_LIMITS.linkProtocols.includes(url.protocol)) {
+    violations.push({ field, message: `${field} must use ${TOKEN_LAUNCH_LIMITS.linkProtocols.join(' or ')}` });
+    return null;
+  }
+  return url;
+}
+
+/**
+ * Every way `params` breaks the platform's limits (TOKEN_LAUNCH_LIMITS); empty when valid
+ */
+export function validateTokenLaunch(params: TokenLaunchParams, now: number = Math.floor(Date.now() / 1000)): TokenLaunchViolation[] {
+  const limits = TOKEN_LAUNCH_LIMITS;
+  const violations: TokenLaunchViolation[] = [];
+
+  checkLength(violations, 'name', params.name.trim(), limits.nameLength);
+  checkLength(violations, 'symbol', params.symbol, limits.symbolLength);
+  if (params.symbol.length > 0 && !limits.symbolPattern.test(params.symbol)) {
+    violations.push({ field: 'symbol', message: 'symbol may only contain letters and digits' });
+  }
+  checkLength(violations, 'description', params.description, limits.descriptionLength);
+
+  if (params.imageUrl === undefined || params.imageUrl === '') {
+    violations.push({ field: 'imageUrl', message: 'imageUrl is required' });
+  } else {
+    const url = checkLink(violations, 'imageUrl', params.imageUrl);
+    const extension = url?.pathname.split('.').pop()?.toLowerCase() ?? '';
+    if (url && !limits.imageExtensions.includes(extension)) {
+      violations.push({ field: 'imageUrl', message: `imageUrl must point to a ${limits.imageExtensions.join(', ')} file` });
+    }
+  }
+
+  for (const field of ['website', 'twitter', 'telegram'] as const) {
+    const value = params[field];
+    if (value !== undefined && value !== '') {
+      checkLink(violations, field, value);
+    }
+  }
+
+  if (!limits.labels.includes(params.label)) {
+    violations.push({ field: 'label', message: `label must be one of ${limits.labels.join(', ')}` });
+  }
+
+  if (params.preSale < limits.preSaleBnb.min || params.preSale > limits.preSaleBnb.max) {
+    violations.push({
+      field: 'preSale',
+      message: `preSale must be between ${limits.preSaleBnb.min} and ${limits.preSaleBnb.max} wei`,
+    });
+  }
+
+  if (params.launchTime !== undefined) {
+    const delay = params.launchTime - now;
+    if (!Number.isInteger(params.launchTime) || delay < limits.launchDelaySeconds.min || delay > limits.launchDelaySeconds.max) {
+      violations.push({
+        field: 'launchTime',
+        message: `launchTime must be within ${limits.launchDelaySeconds.max} seconds from now`,
+      });
+    }
+  }
+
+  return violations;
+}
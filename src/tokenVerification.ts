@@ -0,0 +1,41 @@
+/**
+ * four.meme token verification
+ * Classifies a token by its runtime bytecode hash and TokenManager registration
+ */
+
+import { TokenInfo } from './types';
+
+export type TokenVerificationStatus = 'Official' | 'RegisteredButUnknownBytecode' | 'NotAFourmemeToken';
+
+export interface VerificationReport {
+  token: string;
+  status: TokenVerificationStatus;
+  codeHash: string; // keccak256 of the runtime bytecode (keccak256 of empty bytes for EOAs)
+  registered: boolean; // TokenManager returned sane TokenInfo for the token
+}
+
+/**
+ * Whether a TokenInfo read looks like a token launched through the TokenManager
+ * Unregistered addresses read back as an all-zero struct
+ */
+export function isRegisteredTokenInfo(info: TokenInfo): boolean {
+  return info.totalSupply > 0n && info.launchTime > 0n && info.K > 0n && info.T > 0n;
+}
+
+/**
+ * Classify a token from its code hash and registration.
+ * Unregistered tokens are never official, even if their bytecode matches.
+ */
+export function classifyToken(codeHash: string, registered: boolean, officialHashes: Iterable<string>): TokenVerificationStatus {
+  if (!registered) {
+    return 'NotAFourmemeToken';
+  }
+
+  const hash = codeHash.toLowerCase();
+  for (const official of officialHashes) {
+    if (official.toLowerCase() === hash) {
+      return 'Official';
+    }
+  }
+  return 'RegisteredButUnknownBytecode';
+}
@@ -0,0 +1,135 @@
+/**
+ * In-memory trade history recorder
+ * Keeps one record per fill for bookkeeping and export
+ */
+
+import { ethers } from 'ethers';
+import { Price } from './price';
+import { PerformanceBucket, performanceByHour, performanceByWeekday } from './tradeStats';
+
+export interface TradeRecord {
+  timestamp: number; // Block timestamp (Unix seconds)
+  token: string;
+  direction: 'buy' | 'sell';
+  tokenAmount: bigint;
+  bnbAmount: bigint; // BNB spent (buy, fee included) or received (sell, fee deducted)
+  fee: bigint;
+  price: Price; // BNB per token
+  gasCost: bigint;
+  extraCost?: bigint; // Relay fees, tips and replacement costs attributed to this trade (wei)
+  txHash: string;
+  blockNumber: number;
+  tags: string[]; // Caller tags from the trade params
+  correlationId?: string;
+  subAccount?: string;
+  expectedAmount?: bigint; // Quoted tokens (buy) or BNB (sell), when the caller supplied one
+  realizedPnl?: bigint; // Sells only: realized PnL in wei, unset when the cost basis is unknown
+}
+
+const CSV_HEADER = ['timestamp', 'token', 'direction', 'amount', 'cost_bnb', 'price_bnb', 'fee_bnb', 'gas_cost_bnb', 'extra_cost_bnb', 'tx_hash', 'tags', 'correlation_id'];
+
+/**
+ * RFC 4180 field: quoted, with quotes doubled, when it holds a comma, quote or line break
+ */
+function csvField(value: string): string {
+  return /[",\r\n]/.test(value) ? `"${value.replace(/"/g, '""')}"` : value;
+}
+
+export class TradeHistory {
+  private records: TradeRecord[] = [];
+  private maxRecords: number;
+
+  constructor(maxRecords: number = 10000) {
+    this.maxRecords = maxRecords;
+  }
+
+  record(trade: TradeRecord): void {
+    this.records.push(trade);
+    if (this.records.length > this.maxRecords) {
+      this.records = this.records.slice(-this.maxRecords);
+    }
+  }
+
+  /**
+   * Recorded trades, optionally restricted to one token
+   */
+  getTrades(tokenAddress?: string): TradeRecord[] {
+    if (!tokenAddress) {
+      return [...this.records];
+    }
+    const key = tokenAddress.toLowerCase();
+    return this.records.filter((trade) => trade.token.toLowerCase() === key);
+  }
+
+  /**
+   * Recorded trades carrying `tag`
+   */
+  getTradesByTag(tag: string): TradeRecord[] {
+    return this.records.filter((trade) => trade.tags.includes(tag));
+  }
+
+  /**
+   * Recorded trades with the given correlation id
+   */
+  getTradesByCorrelationId(correlationId: string): TradeRecord[] {
+    return this.records.filter((trade) => trade.correlationId === correlationId);
+  }
+
+  /**
+   * Recorded trades booked to `subAccount`
+   */
+  getTradesBySubAccount(subAccount: string): TradeRecord[] {
+    return this.records.filter((trade) => trade.subAccount === subAccount);
+  }
+
+  /**
+   * Trade count, win rate, slippage and PnL per UTC hour (shifted by `utcOffsetMinutes`)
+   */
+  performanceByHour(utcOffsetMinutes: number = 0, subAccount?: string): PerformanceBucket[] {
+    return performanceByHour(this.scoped(subAccount), utcOffsetMinutes);
+  }
+
+  /**
+   * Trade count, win rate, slippage and PnL per weekday (0 = Sunday, shifted by `utcOffsetMinutes`)
+   */
+  performanceByWeekday(utcOffsetMinutes: number = 0, subAccount?: string): PerformanceBucket[] {
+    return performanceByWeekday(this.scoped(subAccount), utcOffsetMinutes);
+  }
+
+  clear(): void {
+    this.records = [];
+  }
+
+  /**
+   * Export all trades as CSV with human-readable units (ISO timestamps, BNB and token decimals)
+   * Tags are joined with `;`; fields holding commas, quotes or line breaks are quoted as RFC 4180
+   * specifies. `tokenDecimals` gives each trade's token its decimals (default: 18
+   * for all), which scale both the amount and the price per whole token. Pass `subAccount` to
+   * export only the trades booked to it
+   */
+  toCsv(tokenDecimals: (token: string) => number = () => 18, subAccount?: string): string {
+    const rows = this.scoped(subAccount).map((trade) => {
+      const decimals = tokenDecimals(trade.token);
+      return [
+        new Date(trade.timestamp * 1000).toISOString(),
+        trade.token,
+        trade.direction,
+        ethers.formatUnits(trade.tokenAmount, decimals),
+        ethers.formatEther(trade.bnbAmount),
+        trade.price.mul(new Price(10n ** BigInt(decimals), 10n ** 18n)).toDecimalString(18),
+        ethers.formatEther(trade.fee),
+        ethers.formatEther(trade.gasCost),
+        ethers.formatEther(trade.extraCost ?? 0n),
+        trade.txHash,
+        trade.tags.join(';'),
+        trade.correlationId ?? '',
+      ].map(csvField).join(',');
+    });
+
+    return [CSV_HEADER.join(','), ...rows].join('\n') + '\n';
+  }
+
+  private scoped(subAccount?: string): TradeRecord[] {
+    return subAccount === undefined ? this.records : this.getTradesBySubAccount(subAccount);
+  }
+}
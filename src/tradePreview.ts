@@ -0,0 +1,120 @@
+/**
+ * Trade previews
+ * A TradePreview records what a buy or sell computed before sending: each value, each pre-send
+ * check with its outcome, and the transaction that would be broadcast. The send path fills one in
+ * strict mode, where the first failing check throws as it always has; `previewBuy` and
+ * `previewSell` fill one in lenient mode, where every check runs and failures are recorded.
+ */
+
+export type PreviewValue = bigint | number | string | boolean;
+
+export interface PreviewCheck {
+  name: string;
+  passed: boolean;
+  code?: string; // Error code of the failure, e.g. 'TOKEN_TOO_YOUNG'
+  reason?: string; // Error message of the failure
+}
+
+export interface PreviewTransaction {
+  from: string;
+  to: string;
+  data: string;
+  value: bigint;
+  gasLimit?: bigint;
+  gasPrice?: bigint;
+  maxFeePerGas?: bigint;
+  maxPriorityFeePerGas?: bigint;
+}
+
+export class TradePreview {
+  readonly values: Map<string, PreviewValue> = new Map(); // In the order they were computed
+  readonly checks: PreviewCheck[] = [];
+  transaction: PreviewTransaction | null = null; // Null when a failed step left nothing to build it from
+
+  constructor(
+    readonly side: 'buy' | 'sell',
+    readonly tokenAddress: string,
+    readonly strict: boolean = false // Rethrow the first failing check instead of recording it
+  ) {}
+
+  /**
+   * True when every check passed and the transaction was built
+   */
+  get ok(): boolean {
+    return this.transaction !== null && this.checks.every((check) => check.passed);
+  }
+
+  /**
+   * The first failed check: the one that would have stopped the trade
+   */
+  get bindingConstraint(): PreviewCheck | undefined {
+    return this.checks.find((check) => !check.passed);
+  }
+
+  set(name: string, value: PreviewValue | undefined): void {
+    if (value !== undefined) {
+      this.values.set(name, value);
+    }
+  }
+
+  /**
+   * Run one check. Resolves to what `run` returned, or undefined when it failed; in strict mode a
+   * failure throws instead.
+   */
+  async check<T>(name: string, run: () => T | Promise<T>): Promise<T | undefined> {
+    let result: T;
+    try {
+      result = await run();
+    } catch (error: any) {
+      if (this.strict) {
+        throw error;
+      }
+      this.checks.push({ name, passed: false, code: error?.code, reason: error?.message ?? String(error) });
+      return undefined;
+    }
+    this.checks.push({ name, passed: true });
+    return result;
+  }
+
+  /**
+   * Multi-line, human-readable breakdown of the preview
+   */
+  explain(): string {
+    const binding = this.bindingConstraint;
+    const verdict = binding ? `refused by ${binding.name}` : this.transaction ? 'would send' : 'no transaction built';
+    const lines = [`${this.side === 'buy' ? 'Buy' : 'Sell'} preview for ${this.tokenAddress}: ${verdict}`, 'Values:'];
+    for (const [name, value] of this.values) {
+      lines.push(`  ${name}: ${value}`);
+    }
+    lines.push('Checks:');
+    for (const check of this.checks) {
+      const failure = check.passed ? '' : `: ${check.code ? `[${check.code}] ` : ''}${check.reason}`;
+      lines.push(`  ${check.passed ? 'pass' : 'FAIL'} ${check.name}${failure}`);
+    }
+    if (this.transaction) {
+      lines.push('Transaction:');
+      for (const [name, value] of Object.entries(this.transaction)) {
+        if (value !== undefined) {
+          lines.push(`  ${name}: ${value}`);
+        }
+      }
+    }
+    return lines.join('\n');
+  }
+}
+
+/**
+ * The transaction a preview would send, from its built transaction options
+ */
+export function previewTransaction(from: string, to: string, data: string, value: bigint, txOptions: any): PreviewTransaction {
+  return {
+    from,
+    to,
+    data,
+    value,
+    gasLimit: txOptions.gasLimit,
+    gasPrice: txOptions.gasPrice,
+    maxFeePerGas: txOptions.maxFeePerGas,
+    maxPriorityFeePerGas: txOptions.maxPriorityFeePerGas,
+  };
+}
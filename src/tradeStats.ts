@@ -0,0 +1,92 @@
+/**
+ * Time-of-day and day-of-week trading performance
+ * Offline statistics over recorded trades; buckets are in UTC shifted by an optional offset
+ */
+
+import { TradeRecord } from './tradeHistory';
+import { bpsOf } from './math';
+
+export interface PerformanceBucket {
+  bucket: number; // Hour 0-23 or weekday 0-6 (0 = Sunday)
+  trades: number; // Buys and sells
+  closedTrades: number; // Sells with a known realized PnL
+  wins: number; // Closed trades with positive realized PnL
+  winRate: number | null; // wins / closedTrades, null without closed trades
+  averageSlippageBps: number | null; // Mean shortfall vs the quoted amount (positive = worse), null without quotes
+  realizedPnl: bigint; // Sum of realized PnL in wei
+}
+
+type BucketKey = (date: Date) => number;
+
+function bucketStats(trades: TradeRecord[], size: number, utcOffsetMinutes: number, key: BucketKey): PerformanceBucket[] {
+  const buckets = Array.from({ length: size }, (_, bucket) => ({
+    bucket,
+    trades: 0,
+    closedTrades: 0,
+    wins: 0,
+    slippageSum: 0,
+    slippageCount: 0,
+    realizedPnl: 0n,
+  }));
+
+  for (const trade of trades) {
+    // Shift the instant, then read UTC fields so the host timezone never leaks in
+    const local = new Date(trade.timestamp * 1000 + utcOffsetMinutes * 60000);
+    const stats = buckets[key(local)];
+    stats.trades++;
+
+    if (trade.realizedPnl !== undefined) {
+      stats.closedTrades++;
+      stats.realizedPnl += trade.realizedPnl;
+      if (trade.realizedPnl > 0n) {
+        stats.wins++;
+      }
+    }
+
+    const slippage = realizedSlippageBps(trade);
+    if (slippage !== null) {
+      stats.slippageSum += slippage;
+      stats.slippageCount++;
+    }
+  }
+
+  return buckets.map(({ slippageSum, slippageCount, ...stats }) => ({
+    ...stats,
+    winRate: stats.closedTrades > 0 ? stats.wins / stats.closedTrades : null,
+    averageSlippageBps: slippageCount > 0 ? slippageSum / slippageCount : null,
+  }));
+}
+
+/**
+ * Shortfall of the filled amount vs the quoted amount in basis points (positive = worse than quoted)
+ */
+export function realizedSlippageBps(trade: TradeRecord): number | null {
+  if (trade.expectedAmount === undefined || trade.expectedAmount === 0n) {
+    return null;
+  }
+  const actual = trade.direction === 'buy' ? trade.tokenAmount : trade.bnbAmount;
+  return bpsOf(trade.expectedAmount - actual, trade.expectedAmount, 'realized slippage');
+}
+
+/**
+ * Stats per hour of day (24 buckets)
+ * @param utcOffsetMinutes - Shift applied to UTC timestamps, e.g. 480 for UTC+8
+ */
+export function performanceByHour(trades: TradeRecord[], utcOffsetMinutes: number = 0): PerformanceBucket[] {
+  return bucketStats(trades, 24, utcOffsetMinutes, (date) => date.getUTCHours());
+}
+
+/**
+ * Stats per day of week (7 buckets, 0 = Sunday)
+ * @param utcOffsetMinutes - Shift applied to UTC timestamps, e.g. 480 for UTC+8
+ */
+export function performanceByWeekday(trades: TradeRecord[], utcOffsetMinutes: number = 0): PerformanceBucket[] {
+  return bucketStats(trades, 7, utcOffsetMinutes, (date) => date.getUTCDay());
+}
+
+/**
+ * JSON for plotting tools, with bigint PnL as decimal strings
+ */
+export function performanceToJson(buckets: PerformanceBucket[]): string {
+  return JSON.stringify(buckets, (_key, value) => (typeof value === 'bigint' ? value.toString() : value));
+}
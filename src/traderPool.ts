@@ -0,0 +1,144 @@
+/**
+ * Multi-wallet trader pool
+ * Spreads trades over several wallets that share one pair of providers
+ */
+
+import { FourTrading, FourTradingConfig, BuyParams, SellParams, GasOptions } from './fourTrading';
+import { createJsonRpcProvider, createWebSocketProvider } from './connection';
+import { TransactionResult } from './types';
+import { applySlippage } from './utils';
+import { ValidationError } from './errors';
+
+export class TraderPool {
+  private traders: FourTrading[];
+  private locks: Array<Promise<unknown>>;
+  private busy: boolean[];
+  private cursor = 0;
+
+  constructor(traders: FourTrading[]) {
+    if (traders.length === 0) {
+      throw new ValidationError('TraderPool needs at least one trader');
+    }
+    this.traders = traders;
+    this.locks = traders.map(() => Promise.resolve());
+    this.busy = traders.map(() => false);
+  }
+
+  /**
+   * Build one trader per private key, all sharing a single HTTP and WebSocket provider
+   */
+  static fromKeys(config: Omit<FourTradingConfig, 'privateKey'>, privateKeys: string[]): TraderPool {
+    const provider = config.provider ?? createJsonRpcProvider(config.rpcUrl, config.connection);
+    const eventProvider = config.eventProvider ?? createWebSocketProvider(config.wssUrl, config.connection);
+
+    return new TraderPool(privateKeys.map((privateKey) => new FourTrading({
+      ...config,
+      privateKey,
+      provider,
+      eventProvider,
+    })));
+  }
+
+  size(): number {
+    return this.traders.length;
+  }
+
+  getTrader(index: number): FourTrading {
+    this.checkIndex(index);
+    return this.traders[index];
+  }
+
+  getAddresses(): string[] {
+    return this.traders.map((trader) => trader.getWalletAddress());
+  }
+
+  /**
+   * Whether a wallet currently has a transaction in flight
+   */
+  isBusy(index: number): boolean {
+    this.checkIndex(index);
+    return this.busy[index];
+  }
+
+  /**
+   * Sync every wallet's local nonce (needed before blind buys)
+   */
+  async syncNonces(): Promise<number[]> {
+    return await Promise.all(this.traders.map((trader) => trader.syncNonce()));
+  }
+
+  /**
+   * Buy from a specific wallet. Transactions from the same wallet are sent one at a time,
+   * so each wallet's nonce sequence is independent of the others.
+   */
+  async buyFrom(index: number, params: BuyParams): Promise<TransactionResult> {
+    this.checkIndex(index);
+    return await this.withWallet(index, () => this.traders[index].buyToken(params));
+  }
+
+  /**
+   * Sell from a specific wallet
+   */
+  async sellFrom(index: number, params: SellParams): Promise<TransactionResult> {
+    this.checkIndex(index);
+    return await this.withWallet(index, () => this.traders[index].sellToken(params));
+  }
+
+  /**
+   * Buy from the next idle wallet in rotation, with `minAmount` derived from a fresh quote.
+   * When every wallet is busy the buy queues behind the next wallet in rotation.
+   */
+  async buyRoundRobin(
+    tokenAddress: string,
+    fundsInBNB: bigint,
+    slippagePercent: number = 1,
+    gas?: GasOptions
+  ): Promise<TransactionResult & { walletIndex: number }> {
+    const index = this.nextWallet();
+    const quote = await this.traders[index].quoteBuy(tokenAddress, fundsInBNB);
+    const minAmount = applySlippage(quote.tokenAmount, slippagePercent);
+
+    const result = await this.buyFrom(index, { tokenAddress, fundsInBNB, minAmount, gas });
+    return { ...result, walletIndex: index };
+  }
+
+  /**
+   * Pick the next idle wallet starting at the rotation cursor, or the cursor wallet if all are busy
+   */
+  private nextWallet(): number {
+    const count = this.traders.length;
+    let index = this.cursor;
+    for (let offset = 0; offset < count; offset++) {
+      const candidate = (this.cursor + offset) % count;
+      if (!this.busy[candidate]) {
+        index = candidate;
+        break;
+      }
+    }
+    this.cursor = (index + 1) % count;
+    return index;
+  }
+
+  private async withWallet<T>(index: number, task: () => Promise<T>): Promise<T> {
+    const previous = this.locks[index];
+    this.busy[index] = true;
+
+    const run = previous.then(task, task);
+    const settled = run.then(() => undefined, () => undefined);
+    this.locks[index] = settled;
+
+    try {
+      return await run;
+    } finally {
+      if (this.locks[index] === settled) {
+        this.busy[index] = false;
+      }
+    }
+  }
+
+  private checkIndex(index: number): void {
+    if (!Number.isInteger(index) || index < 0 || index >= this.traders.length) {
+      throw new ValidationError(`Wallet index ${index} out of range (pool size ${this.traders.length})`);
+    }
+  }
+}
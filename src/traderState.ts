@@ -0,0 +1,118 @@
+/**
+ * Versioned snapshot of a trader's runtime state, for handing a running bot over to a new process
+ * The snapshot is what the chain can't tell a fresh process: the local nonce, transactions still in
+ * flight, positions, learned gas limits, the sub-account ledger and (optionally) the watchlist.
+ * Event cursors are not included; they already persist through their CursorStore.
+ */
+
+import { TRADER_STATE_VERSION } from './constants';
+import { StateSnapshotError } from './errors';
+import type { GasProfileEntry } from './gasProfiler';
+import type { Position } from './positionTracker';
+import type { OwnPendingTx } from './preemption';
+import type { SubAccountLedgerState } from './subAccounts';
+import type { PendingTrade, TransactionResult } from './types';
+import type { Watchlist, WatchedToken } from './watchlist';
+
+export interface TraderStateSnapshot {
+  version: number; // Schema version, TRADER_STATE_VERSION when written
+  takenAt: number; // Unix milliseconds
+  wallet: string;
+  chainId: bigint;
+  nextNonce: number | null; // Local nonce of the blind path, null if never synced
+  pending: OwnPendingTx[]; // Transactions sent whose fill was not recorded yet
+  positions: Position[];
+  gasProfile: GasProfileEntry[];
+  subAccounts?: SubAccountLedgerState; // When the trader has a subAccounts ledger
+  watchlist?: WatchedToken[]; // When exported with a watchlist
+}
+
+/**
+ * Upgrades a snapshot of one version to the next; it receives and returns plain parsed JSON
+ */
+export type TraderStateMigration = (snapshot: any) => any;
+
+/**
+ * Migrations by the version they upgrade from; empty until the schema first changes
+ */
+export const TRADER_STATE_MIGRATIONS: Readonly<Record<number, TraderStateMigration>> = {};
+
+export interface RestoreOptions {
+  watchlist?: Watchlist; // Receives the snapshot's watched tokens
+  migrations?: Readonly<Record<number, TraderStateMigration>>; // Default: TRADER_STATE_MIGRATIONS
+}
+
+/**
+ * A difference between the snapshot and the chain found on restore
+ */
+export type StateDiscrepancy =
+  | { kind: 'nonce'; expected: number; actual: number } // The local nonce is reset to the chain's pending count
+  | { kind: 'dropped'; txHash: string; nonce: number; nonceUsed: boolean } // Neither mined nor pending; nonceUsed when another transaction took its nonce
+  | { kind: 'balance'; token: string; expected: bigint; actual: bigint }; // Position amount vs the wallet's balance; the position is left as recorded
+
+/**
+ * A snapshot transaction that mined while no process was watching it
+ */
+export interface SettledPending {
+  tx: OwnPendingTx;
+  status: 'confirmed' | 'reverted';
+  result?: TransactionResult; // Its fill, now recorded (confirmed only)
+}
+
+export interface RestoreReport {
+  settled: SettledPending[];
+  pending: PendingTrade[]; // Still in the mempool; `wait` records the fill as usual
+  discrepancies: StateDiscrepancy[];
+}
+
+/**
+ * Bring a parsed snapshot up to `target` (default: the current version) through `migrations`
+ */
+export function migrateTraderState(
+  raw: any,
+  migrations: Readonly<Record<number, TraderStateMigration>> = TRADER_STATE_MIGRATIONS,
+  target: number = TRADER_STATE_VERSION
+): TraderStateSnapshot {
+  const version = raw?.version;
+  if (!Number.isInteger(version) || version < 1) {
+    throw new StateSnapshotError(`not a trader state snapshot (version ${version})`);
+  }
+  if (version > target) {
+    throw new StateSnapshotError(`version ${version} is newer than this SDK's ${target}`);
+  }
+
+  let state = raw;
+  while (state.version < target) {
+    const migrate = migrations[state.version];
+    if (!migrate) {
+      throw new StateSnapshotError(`no migration from version ${state.version}`);
+    }
+    state = { ...migrate(state), version: state.version + 1 };
+  }
+  return state as TraderStateSnapshot;
+}
+
+/**
+ * Serialize a snapshot; bigints are written as `{ "$bigint": "<decimal>" }`
+ */
+export function traderStateToJson(snapshot: TraderStateSnapshot): string {
+  return JSON.stringify(snapshot, (_key, value) => (typeof value === 'bigint' ? { $bigint: value.toString() } : value));
+}
+
+/**
+ * Parse a snapshot written by traderStateToJson and migrate it to the current version
+ */
+export function traderStateFromJson(
+  text: string,
+  migrations: Readonly<Record<number, TraderStateMigration>> = TRADER_STATE_MIGRATIONS
+): TraderStateSnapshot {
+  let raw: unknown;
+  try {
+    raw = JSON.parse(text, (_key, value) =>
+      value && typeof value === 'object' && typeof value.$bigint === 'string' ? BigInt(value.$bigint) : value
+    );
+  } catch (error: any) {
+    throw new StateSnapshotError(`invalid JSON: ${error?.message ?? String(error)}`);
+  }
+  return migrateTraderState(raw, migrations);
+}
@@ -0,0 +1,175 @@
+/**
+ * Trading: the trader and everything it sends trades through
+ * Imported separately (`@fnzero/four-trading-sdk/trading`) by bots that trade but don't need the
+ * event indexing, storage or reporting entry points.
+ *
+ * Everything in `quotes`, plus FourTrading, TraderPool, the strategy framework and the per-trade
+ * machinery behind them: previews, guards, approvals, replacement and confirmation. The trader
+ * connects and signs; position stores and other persistence are in `storage`.
+ */
+
+export * from '../quotes';
+export {
+  FourTrading,
+  FourTradingConfig,
+  BuyParams,
+  SellParams,
+  CurveFractionBuyOptions,
+  ExactBuyOptions,
+  EmergencySellOptions,
+  EmergencySellResult,
+  RawCallOptions,
+  RawSendOptions,
+  ExternalSigner,
+  CreateTokenParams,
+  GasOptions,
+  GasOperation,
+  SecondaryQuoterConfig,
+  TokenCreateListener,
+  TokenPurchaseListener,
+  TokenSaleListener,
+  LiquidityAddedListener,
+  BlockListener,
+  PendingBuyListener,
+} from '../fourTrading';
+export {
+  TransactionResult,
+  CurveFractionFill,
+  ExtraCost,
+  TradeReadScope,
+  PendingTrade,
+  NonceStatus,
+  BatchApproveResult,
+  LiquidationResult,
+  Cancellable,
+} from '../types';
+export { TraderPool } from '../traderPool';
+export {
+  Strategy,
+  StrategyRunner,
+  StrategyRunnerConfig,
+  StrategyDriver,
+  StrategyEvent,
+  TradeExecutor,
+  TradeIntent,
+  TradeEvent,
+  BlockTick,
+  OwnFill,
+  ExpiredIntent,
+  ExpiryStage,
+  RiskLimits,
+  ApprovalConfig,
+  StrategySignal,
+  SignalSource,
+  LiveDriver,
+  LiveExecutor,
+  BacktestDriver,
+  BacktestExecutor,
+} from '../strategy';
+export {
+  ApprovalGate,
+  ApprovalRequest,
+  ApprovalDecision,
+  ApprovalOutcome,
+  ApprovalRecord,
+  AutoApproveGate,
+  DenyAllGate,
+  ChannelApprovalGate,
+  requestApproval,
+} from '../approvalGate';
+export { PositionTracker, Position, RealizedPnl, breakEvenPrice } from '../positionTracker';
+export {
+  SubAccountLedger,
+  SubAccountBalance,
+  SubAccountReconciliation,
+  SubAccountReservation,
+  SubAccountLedgerState,
+  TokenDrift,
+} from '../subAccounts';
+export { PreviewCheck, PreviewTransaction, PreviewValue, TradePreview, previewTransaction } from '../tradePreview';
+export { assertPriceImpact } from '../guards';
+export { ResolvedCurveFraction, resolveCurveFraction, curveFractionAmount, realizedCurveFraction } from '../curveFraction';
+export { ChunkPlan, ChunkPlanOptions, SellCostFn, CHUNK_PLAN_MAX_CHUNKS, localSellCost, planSellChunks, applySell, sellImpactBps, priceImpactBps } from '../chunking';
+export { CooldownProbe, cooldownProbeBlock, inferCooldown } from '../cooldown';
+export { SellSizeProbe, inferMaxSellable, readSellLimitHint } from '../sellLimit';
+export { SnipeMode, LaunchWatchSource, LaunchWaitOptions, isTokenLive, waitForLaunch } from '../snipe';
+export { SweepAction, SweepItem, SweepOptions, SweepReport, SweepSkipReason, sweepCandidates, sweepRecovered, sweepReportToJson } from '../sweep';
+export { ApprovalClaim, ApprovalLock, InFlightApproval, sharedApprovalLock } from '../approvalLock';
+export {
+  GaslessApproval,
+  PERMIT_TYPES,
+  PermitDomain,
+  PermitMessage,
+  PermitRequest,
+  permitRequest,
+  recoverPermitSigner,
+  resolvePermitDomain,
+} from '../permit';
+export {
+  OwnPendingTx,
+  PreemptionAction,
+  PreemptionPolicy,
+  PreemptionDecision,
+  PreemptionOutcome,
+  minReplacementPrice,
+  replacementFees,
+  planPreemption,
+} from '../preemption';
+export {
+  GasAuction,
+  GasAuctionConfig,
+  GasAuctionDriver,
+  GasAuctionEvent,
+  GasAuctionResult,
+  CompetitorBid,
+  MempoolTransaction,
+  competitorBidFrom,
+} from '../gasAuction';
+export { GasProfiler, GasProfiledMethod, GasProfileEntry, GasProfilerOptions, GAS_PROFILE_MULTIPLIER_BPS, GAS_PROFILE_REFRESH_MARGIN_BPS } from '../gasProfiler';
+export { ConfirmationStrategy, DEFAULT_CONFIRMATION, InclusionWatch, waitForFinalized, waitForConfirmations, waitForConfirmationsOrDrop, waitForBalanceAbove } from '../confirmation';
+export { throwIfCancelled, waitUnlessCancelled } from '../cancellation';
+export { ReplicaLagEvent, ReplicaLagListener, ReadAtLeastOptions, readAtLeast } from '../readConsistency';
+export { QuoteDivergence, QuoteDivergenceListener, Quoter, QuoteComparisonOptions, QuoteComparator, quoteDivergenceBps } from '../quoteDivergence';
+export { RawFunction, parseRawFunction, encodeRawCall, decodeRawResult } from '../rawCall';
+export { ReplacementAttempt, totalExtraCost, attributeReplacementCosts, allocateExtraCost, firstReceipt } from '../extraCosts';
+export { CAPABILITY_PROBES, CapabilityProbe, CapabilityProbeSource, isCapabilityAbsent, NodeCapabilities, NodeCapability } from '../capabilities';
+export { TraderStateSnapshot, RestoreOptions, StateDiscrepancy, SettledPending, RestoreReport } from '../traderState';
+export { ConnectionConfig, ConnectionTlsOptions } from '../connection';
+export { Logger, LogLevel, LoggerConfig } from '../logger';
+export { Validator } from '../validator';
+export { applySlippage, applyGasMultiplier, slippagePercentToBps, resolveMinAmount, pickTags, formatBNB, parseBNB } from '../utils';
+export {
+  InvalidAddressError,
+  InvalidAmountError,
+  InsufficientBalanceError,
+  TransactionFailedError,
+  ConnectionError,
+  GasConfigurationError,
+  SlippageExceededError,
+  BnbReserveError,
+  UnverifiedTokenError,
+  TokenTooOldError,
+  TokenCreationNotFoundError,
+  TokenTooYoungError,
+  HolderConcentrationError,
+  SubAccountBalanceError,
+  ChainStalledError,
+  CancelledError,
+  ReplicaLaggingError,
+  RpcDivergenceError,
+  TokenLaunchTimeoutError,
+  SymbolCollisionError,
+  SymbolCollisionUncheckedError,
+  CreatorReputationError,
+  PriceFeedError,
+  StateSnapshotError,
+  TransactionDroppedError,
+  CurveFractionError,
+  GaslessApprovalUnsupportedError,
+  CapabilityMissingError,
+  TargetPriceUnreachableError,
+  PriceImpactTooHighError,
+  BalanceWaitTimeoutError,
+  ApprovalWaitTimeoutError,
+  PositionPersistError,
+} from '../errors';
@@ -2,20 +2,27 @@
  * Type definitions for FOUR Trading Platform
  */
 
+import { Price } from './price';
+
+/**
+ * The TokenManager's `_tokenInfos` entry, one field per struct member in on-chain order
+ * (see TOKEN_INFO_FIELDS). Per-buy limits live on the template (`buyLimits`), and the
+ * PancakeSwap pair of a graduated token comes from the factory (`getPancakePair`).
+ */
 export interface TokenInfo {
-  base: string;
-  quote: string;
-  template: bigint;
+  base: string; // Token address
+  quote: string; // Quote asset; zero address for BNB
+  template: bigint; // Template id (see `_templates`)
   totalSupply: bigint;
-  maxOffers: bigint;
-  maxRaising: bigint;
-  launchTime: bigint;
-  offers: bigint;
-  funds: bigint;
-  lastPrice: bigint;
-  K: bigint;
-  T: bigint;
-  status: bigint;
+  maxOffers: bigint; // Tokens sold on the curve before graduation
+  maxRaising: bigint; // Quote raised at graduation
+  launchTime: bigint; // Unix seconds; trading opens at this time
+  offers: bigint; // Tokens still for sale on the curve
+  funds: bigint; // Quote raised so far
+  lastPrice: bigint; // Price of the last trade
+  K: bigint; // Curve invariant
+  T: bigint; // Virtual token supply at launch
+  status: bigint; // TokenStatus value
 }
 
 export interface TokenInfoEx {
@@ -40,10 +47,98 @@ export interface TokenStatus {
   HALT: bigint;
 }
 
-export interface TransactionResult {
+/**
+ * Caller-supplied labels carried untouched from trade params into results and trade history
+ */
+export interface TradeTags {
+  tags?: string[]; // Free-form labels, e.g. the strategy that produced the trade
+  correlationId?: string; // Caller-defined id linking the trade to an external request
+  subAccount?: string; // Virtual sub-account the trade is booked to (see SubAccountLedger)
+}
+
+/**
+ * Optional abort signal for long-running calls. Aborting before a transaction is broadcast
+ * rejects with CancelledError('beforeBroadcast'); aborting after it only stops the wait and
+ * rejects with CancelledError('afterBroadcast') carrying a PendingTrade for the live transaction.
+ */
+export interface Cancellable {
+  signal?: AbortSignal;
+}
+
+/**
+ * Cost of a trade beyond its own gas, attributed to the trade's cost basis
+ */
+export interface ExtraCost {
+  kind: 'relayFee' | 'bundleTip' | 'replacementGas';
+  amount: bigint; // wei
+  txHash?: string; // Transaction the cost was paid on, when not the trade's own (e.g. a replaced attempt)
+}
+
+export interface TransactionResult extends TradeTags {
   success: boolean;
   txHash: string;
   receipt: any;
+  timestamp: number; // Timestamp (Unix seconds) of the block the transaction was mined in
+  price?: Price; // Exact BNB per token of the wallet's fills in this transaction (spent on buys, received on sells, after fees); unset without fills
+  extraCosts?: ExtraCost[]; // Relay fees, bundle tips and replacement costs attributed to this trade
+  reorgRetryOf?: string; // Hash of the first attempt, when retryOnReorg resubmitted the trade after it was dropped
+  curveFraction?: CurveFractionFill; // Set by buyCurveFraction
+}
+
+/**
+ * The requested and realized share of the remaining curve supply of a buyCurveFraction buy
+ */
+export interface CurveFractionFill {
+  requestedBps: number; // As asked
+  executedBps: number; // After clamping to maxCurveFractionBps
+  clamped: boolean;
+  offersBefore: bigint; // Tokens left on the curve when the buy was sized
+  tokenAmount: bigint; // Tokens bought
+  realizedBps: number; // tokenAmount as basis points of offersBefore, rounded down
+}
+
+/**
+ * Handle to a broadcast transaction whose result is completed later from its receipt
+ */
+export interface PendingTrade extends TradeTags {
+  txHash: string;
+  nonce: number;
+  wait(confirmations?: number, signal?: AbortSignal): Promise<TransactionResult>; // Rejects with CancelledError on abort
+}
+
+/**
+ * Reads pinned to at least the block a trade was included in (see FourTrading.afterTrade)
+ */
+export interface TradeReadScope {
+  minBlock: number;
+  getTokenInfo(tokenAddress: string): Promise<TokenInfo>;
+  getTokenBalance(tokenAddress: string): Promise<string>;
+  getTokenBalanceRaw(tokenAddress: string): Promise<bigint>;
+  getBNBBalance(): Promise<string>;
+}
+
+/**
+ * The wallet's mined and pending transaction counts; `pending - latest` transactions are waiting in the mempool
+ */
+export interface NonceStatus {
+  latest: number; // Transactions mined as of the latest block
+  pending: number; // Mined plus those the node holds in its mempool
+}
+
+export interface BatchApproveResult {
+  token: string;
+  status: 'sufficient' | 'approved' | 'failed'; // 'failed' covers both unreadable allowances and failed approvals
+  allowance: bigint | null; // Allowance before approving, null when it couldn't be read
+  result?: TransactionResult; // Set when approved
+  error?: string; // Set when failed
+}
+
+export interface LiquidationResult {
+  token: string;
+  status: 'sold' | 'skipped' | 'failed'; // 'skipped' is a zero balance
+  balance: bigint | null; // Balance sold, null when it couldn't be read
+  result?: TransactionResult; // Set when sold
+  error?: string; // Set when failed
 }
 
 export interface TokenCreateEvent {
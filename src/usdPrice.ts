@@ -0,0 +1,45 @@
+/**
+ * BNB/USD conversion for fiat-denominated displays
+ * The price comes from a Chainlink aggregator on BSC (CHAINLINK_BNB_USD_FEED unless configured).
+ * Rounds with a non-positive answer or older than the allowed age are rejected rather than shown.
+ */
+
+import { PriceFeedError } from './errors';
+import { toNumberChecked } from './math';
+
+export interface BnbUsdPrice {
+  usdPerBnb: number;
+  answer: bigint; // Raw feed answer, fixed-point with `decimals` decimals
+  decimals: number;
+  updatedAt: number; // Unix seconds the round was last updated
+  feed: string;
+}
+
+/**
+ * Validate a feed round and build its price. Throws PriceFeedError when the answer is not
+ * positive or the round is more than `maxAge` seconds older than `now`.
+ */
+export function bnbUsdPriceFromRound(
+  feed: string,
+  answer: bigint,
+  decimals: number,
+  updatedAt: number,
+  now: number,
+  maxAge: number
+): BnbUsdPrice {
+  if (answer <= 0n) {
+    throw new PriceFeedError(feed, `answer ${answer} is not positive`);
+  }
+  if (now - updatedAt > maxAge) {
+    throw new PriceFeedError(feed, `last updated ${now - updatedAt}s ago, more than the ${maxAge}s allowed`);
+  }
+  return { usdPerBnb: toNumberChecked(answer, 'answer') / 10 ** decimals, answer, decimals, updatedAt, feed };
+}
+
+/**
+ * USD value of `bnbWei`, computed exactly to a millionth of a dollar before converting to a number
+ */
+export function bnbToUsd(bnbWei: bigint, price: BnbUsdPrice): number {
+  const microUsd = (bnbWei * price.answer * 1_000_000n) / 10n ** BigInt(18 + price.decimals);
+  return toNumberChecked(microUsd, 'micro-USD value') / 1_000_000;
+}
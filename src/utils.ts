@@ -1,5 +1,7 @@
 import { ethers, TransactionReceipt, TransactionResponse } from 'ethers';
-import { TokenCreateEvent, TokenPurchaseEvent, TokenSaleEvent } from './types';
+import { TokenCreateEvent, TokenPurchaseEvent, TokenSaleEvent, TradeTags } from './types';
+import { SLIPPAGE_DENOMINATOR } from './constants';
+import { checkedBps, mulDiv, toNumberChecked } from './math';
 
 /**
  * Utility functions for transaction and event parsing
@@ -151,10 +153,103 @@ export function parseTokenAmount(amount: string, decimals: number = 18): bigint
  */
 export function calculatePriceChange(oldPrice: bigint, newPrice: bigint): number {
   if (oldPrice === 0n) return 0;
-  const change = ((Number(newPrice) - Number(oldPrice)) / Number(oldPrice)) * 100;
+  const change = ((toNumberChecked(newPrice, 'newPrice') - toNumberChecked(oldPrice, 'oldPrice')) / toNumberChecked(oldPrice, 'oldPrice')) * 100;
   return Math.round(change * 100) / 100;
 }
 
+/**
+ * Convert a slippage percentage (e.g. 0.5 for 0.5%) to whole basis points
+ * Rounds to the nearest basis point so float artifacts like 99.71 * 100 = 9970.999... don't lose a bp
+ */
+export function slippagePercentToBps(slippagePercent: number): bigint {
+  return checkedBps(slippagePercent * 100, 'slippage');
+}
+
+/**
+ * Minimum amount after applying slippage, rounded down
+ * Never exceeds `amount` and never goes below zero for slippage in [0, 100]
+ */
+export function applySlippage(amount: bigint, slippagePercent: number): bigint {
+  const bps = slippagePercentToBps(slippagePercent);
+  return mulDiv(amount, SLIPPAGE_DENOMINATOR - bps, SLIPPAGE_DENOMINATOR);
+}
+
+/**
+ * Gas limit for a node's `estimate` scaled by `multiplier` (e.g. 1.15), rounded down
+ */
+export function applyGasMultiplier(estimate: bigint, multiplier: number): bigint {
+  return mulDiv(estimate, checkedBps(multiplier * 10000, 'gasMultiplier'), SLIPPAGE_DENOMINATOR);
+}
+
+// Messages nodes and RPC gateways return for a method they don't serve
+const UNSUPPORTED_METHOD = /method .*(not (found|supported|available|allowed|whitelisted)|does not exist|is disabled)|unsupported method/i;
+
+/**
+ * Whether an RPC error says the node doesn't serve the method at all (JSON-RPC -32601 or a
+ * gateway's "not supported" message), as opposed to the call itself failing
+ */
+export function isMethodUnsupportedError(error: any): boolean {
+  for (let current = error, depth = 0; current && depth < 4; current = current.error ?? current.info?.error, depth++) {
+    if (current.code === -32601 || UNSUPPORTED_METHOD.test(String(current.message ?? ''))) {
+      return true;
+    }
+  }
+  return false;
+}
+
+// Messages nodes return for a read of state they have pruned or never had
+const STATE_UNAVAILABLE = /missing trie node|header not found|historical state .*(not available|unavailable)|state (is )?(not available|unavailable|pruned)|required historical state/i;
+
+/**
+ * Whether an RPC error says the node doesn't hold the state of the requested block, as opposed
+ * to the read failing in transit
+ */
+export function isStateUnavailableError(error: any): boolean {
+  for (let current = error, depth = 0; current && depth < 4; current = current.error ?? current.info?.error, depth++) {
+    if (STATE_UNAVAILABLE.test(String(current.message ?? ''))) {
+      return true;
+    }
+  }
+  return false;
+}
+
+// Messages nodes and RPC gateways return for a getLogs query spanning too many blocks or results
+const LOG_RANGE_LIMIT = /block range|range (is )?too (large|wide)|too many (blocks|results|logs)|more than \d+ (results|logs)|limit exceeded|response size exceeded/i;
+
+/**
+ * Whether a getLogs error says the query asked for too much at once (JSON-RPC -32005 or a
+ * provider's range or result-count message), so a smaller block range may succeed
+ */
+export function isLogRangeError(error: any): boolean {
+  for (let current = error, depth = 0; current && depth < 4; current = current.error ?? current.info?.error, depth++) {
+    if (current.code === -32005 || LOG_RANGE_LIMIT.test(String(current.message ?? ''))) {
+      return true;
+    }
+  }
+  return false;
+}
+
+/**
+ * Effective minimum token output for a buy: the larger of the slippage-derived minimum
+ * and the absolute floor, so the trade reverts if either bound is not met
+ */
+export function resolveMinAmount(slippageMin?: bigint, absMinTokens?: bigint): bigint {
+  const slippage = slippageMin ?? 0n;
+  const floor = absMinTokens ?? 0n;
+  return slippage > floor ? slippage : floor;
+}
+
+/**
+ * Copy only the tagging fields out of trade params, omitting those that are unset
+ */
+export function pickTags(source: TradeTags): TradeTags {
+  return {
+    ...(source.tags !== undefined ? { tags: [...source.tags] } : {}),
+    ...(source.correlationId !== undefined ? { correlationId: source.correlationId } : {}),
+    ...(source.subAccount !== undefined ? { subAccount: source.subAccount } : {}),
+  };
+}
+
 /**
  * Validate Ethereum address
  */
@@ -11,6 +11,7 @@ import {
   ValidationError,
 } from './errors';
 import type { GasOptions } from './fourTrading';
+import { MAX_TOKEN_DECIMALS } from './constants';
 
 export class Validator {
   /**
@@ -68,10 +69,22 @@ export class Validator {
       );
     }
 
+    if (gas.type === 'legacy' && hasEIP1559) {
+      throw new GasConfigurationError('Legacy transactions cannot set maxFeePerGas or maxPriorityFeePerGas');
+    }
+
+    if (gas.type === 'eip1559' && hasLegacy) {
+      throw new GasConfigurationError('EIP-1559 transactions cannot set gasPrice');
+    }
+
     if (gas.gasLimit !== undefined) {
       this.validateAmount(gas.gasLimit, 'gasLimit', { min: 21000n });
     }
 
+    if (gas.gasMultiplier !== undefined && !(Number.isFinite(gas.gasMultiplier) && gas.gasMultiplier >= 1)) {
+      throw new GasConfigurationError(`gasMultiplier must be at least 1.0, got ${gas.gasMultiplier}`);
+    }
+
     if (gas.gasPrice !== undefined) {
       this.validateAmount(gas.gasPrice, 'gasPrice');
     }
@@ -150,6 +163,15 @@ export class Validator {
     }
   }
 
+  /**
+   * Validate a token decimals count
+   */
+  static validateDecimals(decimals: number, fieldName: string = 'decimals'): void {
+    if (!Number.isInteger(decimals) || decimals < 0 || decimals > MAX_TOKEN_DECIMALS) {
+      throw new ValidationError(`${fieldName} must be an integer from 0 to ${MAX_TOKEN_DECIMALS}, got ${decimals}`);
+    }
+  }
+
   /**
    * Validate token addresses are not the same
    */
@@ -0,0 +1,108 @@
+/**
+ * four.meme TokenManager versions
+ * Tokens launched before V2 are traded through the V1 manager, whose methods have different names
+ * and arguments. TokenManagerHelper3 reports which manager handles a token; trades are then
+ * encoded for that manager.
+ */
+
+import { ethers } from 'ethers';
+import { getTokenManagerV1Interface } from './abi';
+import { encodeBuyCalldata, encodeBuyExactCalldata, encodeSellCalldata } from './calldata';
+import { UnsupportedVersionError, ValidationError } from './errors';
+import { TokenInfo } from './types';
+
+export type FourmemeVersion = 1 | 2;
+
+export interface TokenManagerRoute {
+  version: FourmemeVersion;
+  manager: string; // TokenManager contract that trades the token
+}
+
+/**
+ * Route for a token from TokenManagerHelper3.getTokenInfo's `version` and `tokenManager`
+ */
+export function routeFromHelperInfo(tokenAddress: string, version: bigint, manager: string): TokenManagerRoute {
+  if ((version !== 1n && version !== 2n) || manager === ethers.ZeroAddress) {
+    throw new UnsupportedVersionError(tokenAddress, manager === ethers.ZeroAddress ? 0n : version);
+  }
+  return { version: Number(version) as FourmemeVersion, manager };
+}
+
+/**
+ * Whether a failed TokenManagerHelper3 read is the chain's answer rather than a transport failure:
+ * a revert, or the empty return data of an address without code. Only these may fall back to the
+ * configured (V2) manager; anything else could be a V1 token and must not be guessed.
+ */
+export function isDefinitiveHelperFailure(error: any): boolean {
+  return error?.code === 'CALL_EXCEPTION' || (error?.code === 'BAD_DATA' && error?.value === '0x');
+}
+
+/**
+ * TokenInfo for a V1 token from TokenManagerHelper3.getTokenInfo. The helper reports no template,
+ * total supply or curve constants, so those read as zero: V1 quotes come from the helper's tryBuy
+ * and trySell, not from local curve math.
+ */
+export function tokenInfoFromHelper(
+  tokenAddress: string,
+  info: { quote: string; lastPrice: bigint; launchTime: bigint; offers: bigint; maxOffers: bigint; funds: bigint; maxFunds: bigint; liquidityAdded: boolean }
+): TokenInfo {
+  return {
+    base: tokenAddress,
+    quote: info.quote,
+    template: 0n,
+    totalSupply: 0n,
+    maxOffers: info.maxOffers,
+    maxRaising: info.maxFunds,
+    launchTime: info.launchTime,
+    offers: info.offers,
+    funds: info.funds,
+    lastPrice: info.lastPrice,
+    K: 0n,
+    T: 0n,
+    status: info.liquidityAdded ? 2n : 0n, // COMPLETED once graduated, else TRADING
+  };
+}
+
+/**
+ * Calldata for an AMAP buy on the token's manager: buyTokenAMAP on V2, purchaseTokenAMAP on V1
+ */
+export function encodeVersionedBuy(
+  version: FourmemeVersion,
+  tokenAddress: string,
+  recipient: string,
+  fundsInBNB: bigint,
+  minAmount: bigint
+): string {
+  return version === 2
+    ? encodeBuyCalldata(tokenAddress, recipient, fundsInBNB, minAmount)
+    : getTokenManagerV1Interface().encodeFunctionData('purchaseTokenAMAP', [0n, tokenAddress, recipient, fundsInBNB, minAmount]);
+}
+
+/**
+ * Calldata for an exact-amount buy on the token's manager: buyToken on V2, purchaseToken on V1
+ */
+export function encodeVersionedBuyExact(
+  version: FourmemeVersion,
+  tokenAddress: string,
+  recipient: string,
+  amount: bigint,
+  maxFunds: bigint
+): string {
+  return version === 2
+    ? encodeBuyExactCalldata(tokenAddress, recipient, amount, maxFunds)
+    : getTokenManagerV1Interface().encodeFunctionData('purchaseToken', [0n, tokenAddress, recipient, amount, maxFunds]);
+}
+
+/**
+ * Calldata for a sell on the token's manager: sellToken on V2, saleToken on V1.
+ * V1 sales take no minimum, so a non-zero `minFunds` is refused rather than silently dropped.
+ */
+export function encodeVersionedSell(version: FourmemeVersion, tokenAddress: string, amount: bigint, minFunds: bigint): string {
+  if (version === 2) {
+    return encodeSellCalldata(tokenAddress, amount, minFunds);
+  }
+  if (minFunds > 0n) {
+    throw new ValidationError(`${tokenAddress} trades on the V1 TokenManager, whose sales cannot enforce minFunds; pass 0 to sell unprotected`);
+  }
+  return getTokenManagerV1Interface().encodeFunctionData('saleToken', [tokenAddress, amount]);
+}
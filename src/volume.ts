@@ -0,0 +1,45 @@
+/**
+ * Trade volume over a block window
+ */
+
+import { BackfillEvent } from './backfill';
+
+export interface VolumeStats {
+  token: string;
+  fromBlock: number; // Inclusive window
+  toBlock: number;
+  buyVolume: bigint; // BNB (wei) spent on buys
+  sellVolume: bigint; // BNB (wei) received from sells
+  buyCount: number;
+  sellCount: number;
+  tradeCount: number;
+}
+
+/**
+ * Sum a token's backfilled trade events into buy and sell BNB volume
+ */
+export function summarizeVolume(token: string, fromBlock: number, toBlock: number, events: BackfillEvent[]): VolumeStats {
+  const stats: VolumeStats = {
+    token,
+    fromBlock,
+    toBlock,
+    buyVolume: 0n,
+    sellVolume: 0n,
+    buyCount: 0,
+    sellCount: 0,
+    tradeCount: 0,
+  };
+
+  for (const { side, event } of events) {
+    if (side === 'buy') {
+      stats.buyVolume += event.cost;
+      stats.buyCount++;
+    } else {
+      stats.sellVolume += event.cost;
+      stats.sellCount++;
+    }
+    stats.tradeCount++;
+  }
+
+  return stats;
+}
@@ -0,0 +1,58 @@
+/**
+ * Wallet trade history
+ * Scans the contract's trade logs over a block range and yields one wallet's buys and sells in chain
+ * order. The trader address isn't an indexed topic, so every trade log is fetched and filtered
+ * client-side; ranges the node refuses as too large are halved until they pass.
+ */
+
+import { BackfillEvent, BackfillLogSource, parseTradeLog } from './backfill';
+import { BACKFILL_PAGE_SIZE } from './constants';
+import { ValidationError } from './errors';
+import { isLogRangeError } from './utils';
+
+export interface WalletHistoryOptions {
+  pageSize?: number; // Blocks per getLogs query to start with (default: BACKFILL_PAGE_SIZE)
+  signal?: AbortSignal; // Stops before the next range; trades already yielded stay yielded
+}
+
+/**
+ * Yield every trade of `account` between `fromBlock` and `toBlock` (inclusive), oldest first.
+ * Each range is fetched once; when the node rejects one as too large, it is retried at half the
+ * size, and later ranges keep the smaller size. Other errors, or a one-block range that still
+ * fails, are thrown.
+ */
+export async function* walletTradeHistory(
+  account: string,
+  fromBlock: number,
+  toBlock: number,
+  fetchLogs: BackfillLogSource,
+  options: WalletHistoryOptions = {}
+): AsyncGenerator<BackfillEvent> {
+  if (!Number.isInteger(fromBlock) || !Number.isInteger(toBlock) || fromBlock < 0) {
+    throw new ValidationError(`Block range must be non-negative integers, got ${fromBlock}-${toBlock}`);
+  }
+  const wallet = account.toLowerCase();
+  let pageSize = Math.max(1, options.pageSize ?? BACKFILL_PAGE_SIZE);
+
+  for (let from = fromBlock; from <= toBlock && !options.signal?.aborted;) {
+    const to = Math.min(from + pageSize - 1, toBlock);
+    let logs;
+    try {
+      logs = await fetchLogs(from, to);
+    } catch (error) {
+      if (pageSize === 1 || !isLogRangeError(error)) {
+        throw error;
+      }
+      pageSize = Math.ceil(pageSize / 2);
+      continue;
+    }
+
+    for (const log of logs) {
+      const trade = parseTradeLog(log);
+      if (trade && String(trade.event.account).toLowerCase() === wallet) {
+        yield trade;
+      }
+    }
+    from = to + 1;
+  }
+}
@@ -0,0 +1,287 @@
+/**
+ * Time-weighted token watchlist
+ * Tracks recent activity per token and prunes tokens that go quiet or graduate
+ */
+
+import type { FourTrading } from './fourTrading';
+import { Price } from './price';
+import { TokenPurchaseEvent, TokenSaleEvent } from './types';
+
+export interface WatchlistConfig {
+  pruneAfterMs: number; // Prune tokens with no trades for this long
+  volumeWindowMs: number; // Window used for volume and activity ranking
+  historyMs: number; // How long price samples are kept (bounds biggestGainers windows)
+  rankSize: number; // Number of top tokens whose order triggers RankChanged
+  autoAddCreations: boolean; // Add tokens from the creation stream when attached to a trader
+}
+
+export interface WatchedToken {
+  token: string;
+  addedAt: number;
+  lastTradeAt?: number;
+  lastPrice?: bigint; // BNB per token scaled by 1e18, as emitted by trade events
+  tradeCount: number;
+  graduated: boolean;
+}
+
+export type WatchlistEvent =
+  | { type: 'added'; token: string }
+  | { type: 'pruned'; token: string; reason: 'inactive' | 'graduated' | 'removed' }
+  | { type: 'rankChanged'; ranking: string[] };
+
+export type WatchlistListener = (event: WatchlistEvent) => void;
+
+interface TradeSample {
+  timestamp: number;
+  price: bigint;
+  volume: bigint;
+}
+
+export class Watchlist {
+  private config: WatchlistConfig;
+  private tokens: Map<string, WatchedToken> = new Map();
+  private samples: Map<string, TradeSample[]> = new Map();
+  private listeners: WatchlistListener[] = [];
+  private ranking: string[] = [];
+  private now: () => number;
+
+  constructor(config: Partial<WatchlistConfig> = {}, now: () => number = Date.now) {
+    this.config = {
+      pruneAfterMs: config.pruneAfterMs ?? 30 * 60000,
+      volumeWindowMs: config.volumeWindowMs ?? 5 * 60000,
+      historyMs: config.historyMs ?? 60 * 60000,
+      rankSize: config.rankSize ?? 10,
+      autoAddCreations: config.autoAddCreations ?? true,
+    };
+    this.now = now;
+  }
+
+  /**
+   * Start watching a token (no-op if already watched)
+   */
+  add(token: string): void {
+    const key = token.toLowerCase();
+    if (this.tokens.has(key)) {
+      return;
+    }
+
+    this.tokens.set(key, { token, addedAt: this.now(), tradeCount: 0, graduated: false });
+    this.samples.set(key, []);
+    this.emit({ type: 'added', token });
+  }
+
+  /**
+   * Stop watching a token
+   */
+  remove(token: string): void {
+    this.drop(token.toLowerCase(), 'removed');
+  }
+
+  has(token: string): boolean {
+    return this.tokens.has(token.toLowerCase());
+  }
+
+  get(token: string): WatchedToken | undefined {
+    const entry = this.tokens.get(token.toLowerCase());
+    return entry ? { ...entry } : undefined;
+  }
+
+  size(): number {
+    return this.tokens.size;
+  }
+
+  /**
+   * Watched tokens, for persisting; price samples are not included and rebuild from new trades
+   */
+  snapshot(): WatchedToken[] {
+    return [...this.tokens.values()].map((entry) => ({ ...entry }));
+  }
+
+  /**
+   * Replace the watched tokens with a previous `snapshot()`, without emitting 'added'
+   */
+  restore(tokens: WatchedToken[]): void {
+    this.tokens.clear();
+    this.samples.clear();
+    this.ranking = [];
+    for (const entry of tokens) {
+      this.tokens.set(entry.token.toLowerCase(), { ...entry });
+      this.samples.set(entry.token.toLowerCase(), []);
+    }
+  }
+
+  /**
+   * Record a trade on a watched token; trades on unwatched tokens are ignored
+   */
+  recordTrade(event: TokenPurchaseEvent | TokenSaleEvent): void {
+    const key = event.token.toLowerCase();
+    const entry = this.tokens.get(key);
+    if (!entry) {
+      return;
+    }
+
+    const timestamp = this.now();
+    entry.lastTradeAt = timestamp;
+    entry.lastPrice = event.price;
+    entry.tradeCount++;
+
+    const samples = this.samples.get(key)!;
+    samples.push({ timestamp, price: event.price, volume: event.cost });
+    this.trimSamples(samples, timestamp);
+
+    this.updateRanking();
+  }
+
+  /**
+   * Mark a token as graduated; it is pruned immediately
+   */
+  markGraduated(token: string): void {
+    const entry = this.tokens.get(token.toLowerCase());
+    if (!entry) {
+      return;
+    }
+    entry.graduated = true;
+    this.drop(token.toLowerCase(), 'graduated');
+  }
+
+  /**
+   * Remove tokens without trades for longer than `pruneAfterMs`
+   * Tokens that never traded are measured from when they were added
+   */
+  prune(): string[] {
+    const now = this.now();
+    const pruned: string[] = [];
+
+    for (const [key, entry] of this.tokens) {
+      const lastActivity = entry.lastTradeAt ?? entry.addedAt;
+      if (now - lastActivity > this.config.pruneAfterMs) {
+        pruned.push(entry.token);
+        this.drop(key, 'inactive');
+      }
+    }
+
+    return pruned;
+  }
+
+  /**
+   * BNB volume traded on a token within the volume window
+   */
+  volume(token: string): bigint {
+    const samples = this.samples.get(token.toLowerCase()) ?? [];
+    const since = this.now() - this.config.volumeWindowMs;
+    return samples
+      .filter((sample) => sample.timestamp >= since)
+      .reduce((sum, sample) => sum + sample.volume, 0n);
+  }
+
+  /**
+   * Tokens with the highest BNB volume within the volume window
+   */
+  mostActive(n: number): Array<{ token: string; volume: bigint }> {
+    return [...this.tokens.values()]
+      .map((entry) => ({ token: entry.token, volume: this.volume(entry.token) }))
+      .filter((entry) => entry.volume > 0n)
+      .sort((a, b) => (a.volume === b.volume ? 0 : a.volume > b.volume ? -1 : 1))
+      .slice(0, n);
+  }
+
+  /**
+   * Tokens with the largest exact percentage price gain over `windowMs`
+   * Compares the latest price to the first sample inside the window
+   */
+  biggestGainers(n: number, windowMs: number): Array<{ token: string; changePercent: Price }> {
+    const since = this.now() - windowMs;
+    const gainers: Array<{ token: string; changePercent: Price }> = [];
+
+    for (const [key, entry] of this.tokens) {
+      const window = (this.samples.get(key) ?? []).filter((sample) => sample.timestamp >= since);
+      if (window.length < 2 || window[0].price === 0n) {
+        continue;
+      }
+
+      const first = Price.fromScaled(window[0].price);
+      const last = Price.fromScaled(window[window.length - 1].price);
+      gainers.push({ token: entry.token, changePercent: first.percentChangeTo(last) });
+    }
+
+    return gainers
+      .sort((a, b) => b.changePercent.compare(a.changePercent))
+      .slice(0, n);
+  }
+
+  /**
+   * Subscribe to watchlist events; returns an unsubscribe function
+   */
+  onEvent(listener: WatchlistListener): () => void {
+    this.listeners.push(listener);
+    return () => {
+      const index = this.listeners.indexOf(listener);
+      if (index > -1) {
+        this.listeners.splice(index, 1);
+      }
+    };
+  }
+
+  /**
+   * Feed the watchlist from a trader's event streams; returns a detach function
+   * Pruning runs on every new block
+   */
+  attach(trader: FourTrading): () => void {
+    const ids: string[] = [
+      trader.onTokenPurchase((event) => this.recordTrade(event)),
+      trader.onTokenSale((event) => this.recordTrade(event)),
+      trader.onLiquidityAdded((event) => this.markGraduated(event.base)),
+      trader.onBlock(() => this.prune()),
+    ];
+
+    if (this.config.autoAddCreations) {
+      ids.push(trader.onTokenCreate((event) => this.add(event.token)));
+    }
+
+    return () => {
+      for (const id of ids) {
+        trader.off(id);
+      }
+    };
+  }
+
+  private drop(key: string, reason: 'inactive' | 'graduated' | 'removed'): void {
+    const entry = this.tokens.get(key);
+    if (!entry) {
+      return;
+    }
+
+    this.tokens.delete(key);
+    this.samples.delete(key);
+    this.emit({ type: 'pruned', token: entry.token, reason });
+    this.updateRanking();
+  }
+
+  private trimSamples(samples: TradeSample[], now: number): void {
+    const cutoff = now - Math.max(this.config.historyMs, this.config.volumeWindowMs);
+    while (samples.length > 0 && samples[0].timestamp < cutoff) {
+      samples.shift();
+    }
+  }
+
+  private updateRanking(): void {
+    const ranking = this.mostActive(this.config.rankSize).map((entry) => entry.token);
+    const changed = ranking.length !== this.ranking.length ||
+      ranking.some((token, index) => token !== this.ranking[index]);
+
+    if (changed) {
+      this.ranking = ranking;
+      this.emit({ type: 'rankChanged', ranking });
+    }
+  }
+
+  private emit(event: WatchlistEvent): void {
+    for (const listener of this.listeners) {
+      try {
+        listener(event);
+      } catch {
+        // Listener errors must not break tracking
+      }
+    }
+  }
+}
@@ -6,6 +6,7 @@
 import { WebSocketProvider } from 'ethers';
 import { Logger } from './logger';
 import { ConnectionError } from './errors';
+import { ConnectionConfig, createWebSocketProvider } from './connection';
 import {
   WS_RECONNECT_INITIAL_DELAY,
   WS_RECONNECT_MAX_DELAY,
@@ -21,6 +22,7 @@ export interface WebSocketManagerConfig {
   autoReconnect?: boolean;
   maxReconnectAttempts?: number;
   heartbeatEnabled?: boolean;
+  connection?: ConnectionConfig;
 }
 
 export class WebSocketManager {
@@ -30,6 +32,7 @@ export class WebSocketManager {
   private autoReconnect: boolean;
   private maxReconnectAttempts: number;
   private heartbeatEnabled: boolean;
+  private connection?: ConnectionConfig;
 
   // Reconnection state
   private reconnectAttempts = 0;
@@ -53,6 +56,7 @@ export class WebSocketManager {
     this.autoReconnect = config.autoReconnect ?? true;
     this.maxReconnectAttempts = config.maxReconnectAttempts ?? WS_RECONNECT_MAX_ATTEMPTS;
     this.heartbeatEnabled = config.heartbeatEnabled ?? true;
+    this.connection = config.connection;
   }
 
   async connect(): Promise<WebSocketProvider> {
@@ -63,7 +67,7 @@ export class WebSocketManager {
     try {
       this.logger.info('Connecting to WebSocket', { url: this.url });
 
-      this.provider = new WebSocketProvider(this.url);
+      this.provider = createWebSocketProvider(this.url, this.connection);
 
       // Setup event listeners
       this.setupEventListeners();
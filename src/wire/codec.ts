@@ -0,0 +1,527 @@
+/**
+ * Binary encoding of trade messages
+ * A message is a version byte, a type byte and a type-specific body. Later versions only append
+ * fields to a body and add message types, so a decoder reads the fields it knows from a newer
+ * message and skips the rest. The layout is documented in the README ("Wire Format").
+ */
+
+import { ethers } from 'ethers';
+import { TextDecoder } from 'util';
+import { MAX_UINT256, WIRE_VERSION } from '../constants';
+import { WireFormatError } from '../errors';
+import { PriceUpdate } from '../priceStream';
+import { TradeEvent, TradeIntent } from '../strategy';
+import { GasOptions } from '../fourTrading';
+import { ExtraCost, TradeTags, TransactionResult } from '../types';
+
+/**
+ * The parts of a TransactionResult that cross a process boundary; the receipt is reduced to its
+ * block, gas used and gas price
+ */
+export interface TradeResultSummary extends TradeTags {
+  success: boolean;
+  txHash: string;
+  timestamp: number; // Unix seconds
+  blockNumber?: number;
+  gasUsed?: bigint;
+  gasPrice?: bigint; // Effective price paid
+  extraCosts?: ExtraCost[];
+}
+
+export type WireMessage =
+  | { type: 'tradeIntent'; intent: TradeIntent }
+  | { type: 'tradeResult'; result: TradeResultSummary }
+  | { type: 'tradeEvent'; trade: TradeEvent }
+  | { type: 'priceUpdate'; update: PriceUpdate };
+
+/**
+ * A message of a type added by a newer version than this decoder's; `body` is left undecoded
+ */
+export interface UnknownWireMessage {
+  type: 'unknown';
+  version: number;
+  typeId: number;
+  body: Uint8Array;
+}
+
+export type DecodedWireMessage = WireMessage | UnknownWireMessage;
+
+export const WIRE_MESSAGE_TYPES = { tradeIntent: 1, tradeResult: 2, tradeEvent: 3, priceUpdate: 4 } as const;
+
+const EXTRA_COST_KINDS: ExtraCost['kind'][] = ['relayFee', 'bundleTip', 'replacementGas'];
+const UTF8 = new TextDecoder('utf-8', { fatal: true });
+
+class Writer {
+  private chunks: Buffer[] = [];
+
+  u8(value: number): void {
+    this.chunks.push(Buffer.from([value]));
+  }
+
+  u16(value: number, what: string): void {
+    if (!Number.isInteger(value) || value < 0 || value > 0xffff) {
+      throw new WireFormatError(`${what} must fit in 16 bits, got ${value}`);
+    }
+    const bytes = Buffer.alloc(2);
+    bytes.writeUInt16BE(value);
+    this.chunks.push(bytes);
+  }
+
+  u64(value: number, what: string): void {
+    if (!Number.isSafeInteger(value) || value < 0) {
+      throw new WireFormatError(`${what} must be a non-negative safe integer, got ${value}`);
+    }
+    const bytes = Buffer.alloc(8);
+    bytes.writeBigUInt64BE(BigInt(value));
+    this.chunks.push(bytes);
+  }
+
+  f64(value: number): void {
+    const bytes = Buffer.alloc(8);
+    bytes.writeDoubleBE(value);
+    this.chunks.push(bytes);
+  }
+
+  uint(value: bigint, what: string): void {
+    if (value < 0n || value > MAX_UINT256) {
+      throw new WireFormatError(`${what} must be a uint256, got ${value}`);
+    }
+    const hex = value === 0n ? '' : value.toString(16);
+    const bytes = Buffer.from(hex.length % 2 ? `0${hex}` : hex, 'hex');
+    this.u8(bytes.length);
+    this.chunks.push(bytes);
+  }
+
+  address(value: string, what: string): void {
+    if (!ethers.isAddress(value)) {
+      throw new WireFormatError(`${what} is not an address: ${value}`);
+    }
+    this.chunks.push(Buffer.from(ethers.getAddress(value).slice(2), 'hex'));
+  }
+
+  hash(value: string, what: string): void {
+    if (!ethers.isHexString(value, 32)) {
+      throw new WireFormatError(`${what} is not a 32-byte hash: ${value}`);
+    }
+    this.chunks.push(Buffer.from(value.slice(2), 'hex'));
+  }
+
+  str(value: string, what: string): void {
+    const bytes = Buffer.from(value, 'utf8');
+    this.u16(bytes.length, `${what} length`);
+    this.chunks.push(bytes);
+  }
+
+  finish(): Buffer {
+    return Buffer.concat(this.chunks);
+  }
+}
+
+class Reader {
+  private offset = 0;
+
+  constructor(private bytes: Buffer, private strict: boolean) {}
+
+  private take(length: number): Buffer {
+    if (this.offset + length > this.bytes.length) {
+      throw new WireFormatError('Message truncated', this.offset);
+    }
+    const slice = this.bytes.subarray(this.offset, this.offset + length);
+    this.offset += length;
+    return slice;
+  }
+
+  u8(): number {
+    return this.take(1)[0];
+  }
+
+  /**
+   * A flags byte; bits outside `known` are an error at this decoder's own version
+   */
+  flags(known: number): number {
+    const at = this.offset;
+    const flags = this.u8();
+    if (this.strict && (flags & ~known) !== 0) {
+      throw new WireFormatError(`Unknown flag bits 0x${(flags & ~known).toString(16)}`, at);
+    }
+    return flags;
+  }
+
+  u16(): number {
+    return this.take(2).readUInt16BE();
+  }
+
+  u64(): number {
+    const at = this.offset;
+    const value = this.take(8).readBigUInt64BE();
+    if (value > BigInt(Number.MAX_SAFE_INTEGER)) {
+      throw new WireFormatError(`Integer ${value} exceeds the safe integer range`, at);
+    }
+    return Number(value);
+  }
+
+  f64(): number {
+    return this.take(8).readDoubleBE();
+  }
+
+  uint(): bigint {
+    const at = this.offset;
+    const length = this.u8();
+    if (length > 32) {
+      throw new WireFormatError(`Integer of ${length} bytes exceeds uint256`, at);
+    }
+    const bytes = this.take(length);
+    return length === 0 ? 0n : BigInt(`0x${bytes.toString('hex')}`);
+  }
+
+  address(): string {
+    return ethers.getAddress(`0x${this.take(20).toString('hex')}`);
+  }
+
+  hash(): string {
+    return `0x${this.take(32).toString('hex')}`;
+  }
+
+  str(): string {
+    const at = this.offset;
+    const bytes = this.take(this.u16());
+    try {
+      return UTF8.decode(bytes);
+    } catch {
+      throw new WireFormatError('Invalid UTF-8 string', at);
+    }
+  }
+
+  rest(): Buffer {
+    return this.take(this.bytes.length - this.offset);
+  }
+
+  remaining(): number {
+    return this.bytes.length - this.offset;
+  }
+
+  position(): number {
+    return this.offset;
+  }
+}
+
+function writeTags(writer: Writer, tags: TradeTags): void {
+  writer.u8((tags.tags ? 1 : 0) | (tags.correlationId !== undefined ? 2 : 0) | (tags.subAccount !== undefined ? 4 : 0));
+  if (tags.tags) {
+    writer.u16(tags.tags.length, 'Tag count');
+    tags.tags.forEach((tag) => writer.str(tag, 'Tag'));
+  }
+  if (tags.correlationId !== undefined) {
+    writer.str(tags.correlationId, 'correlationId');
+  }
+  if (tags.subAccount !== undefined) {
+    writer.str(tags.subAccount, 'subAccount');
+  }
+}
+
+function readTags(reader: Reader): TradeTags {
+  const flags = reader.flags(0b111);
+  const tags: TradeTags = {};
+  if (flags & 1) {
+    const count = reader.u16();
+    tags.tags = Array.from({ length: count }, () => reader.str());
+  }
+  if (flags & 2) {
+    tags.correlationId = reader.str();
+  }
+  if (flags & 4) {
+    tags.subAccount = reader.str();
+  }
+  return tags;
+}
+
+function writeGas(writer: Writer, gas: GasOptions): void {
+  writer.u8(
+    (gas.type !== undefined ? 1 : 0) |
+      (gas.gasLimit !== undefined ? 2 : 0) |
+      (gas.gasMultiplier !== undefined ? 4 : 0) |
+      (gas.gasPrice !== undefined ? 8 : 0) |
+      (gas.maxFeePerGas !== undefined ? 16 : 0) |
+      (gas.maxPriorityFeePerGas !== undefined ? 32 : 0)
+  );
+  if (gas.type !== undefined) {
+    writer.u8(gas.type === 'legacy' ? 0 : 1);
+  }
+  if (gas.gasLimit !== undefined) {
+    writer.uint(gas.gasLimit, 'gasLimit');
+  }
+  if (gas.gasMultiplier !== undefined) {
+    writer.f64(gas.gasMultiplier);
+  }
+  if (gas.gasPrice !== undefined) {
+    writer.uint(gas.gasPrice, 'gasPrice');
+  }
+  if (gas.maxFeePerGas !== undefined) {
+    writer.uint(gas.maxFeePerGas, 'maxFeePerGas');
+  }
+  if (gas.maxPriorityFeePerGas !== undefined) {
+    writer.uint(gas.maxPriorityFeePerGas, 'maxPriorityFeePerGas');
+  }
+}
+
+function readGas(reader: Reader): GasOptions {
+  const flags = reader.flags(0b111111);
+  const gas: GasOptions = {};
+  if (flags & 1) {
+    const at = reader.position();
+    const type = reader.u8();
+    if (type > 1) {
+      throw new WireFormatError(`Unknown transaction type ${type}`, at);
+    }
+    gas.type = type === 0 ? 'legacy' : 'eip1559';
+  }
+  if (flags & 2) {
+    gas.gasLimit = reader.uint();
+  }
+  if (flags & 4) {
+    gas.gasMultiplier = reader.f64();
+  }
+  if (flags & 8) {
+    gas.gasPrice = reader.uint();
+  }
+  if (flags & 16) {
+    gas.maxFeePerGas = reader.uint();
+  }
+  if (flags & 32) {
+    gas.maxPriorityFeePerGas = reader.uint();
+  }
+  return gas;
+}
+
+function readSide(reader: Reader): 'buy' | 'sell' {
+  const at = reader.position();
+  const side = reader.u8();
+  if (side > 1) {
+    throw new WireFormatError(`Unknown trade side ${side}`, at);
+  }
+  return side === 0 ? 'buy' : 'sell';
+}
+
+function writeIntent(writer: Writer, intent: TradeIntent): void {
+  const buy = intent.side === 'buy';
+  const min = buy ? intent.minAmount : intent.minFunds;
+  writer.u8(buy ? 0 : 1);
+  writer.address(intent.tokenAddress, 'tokenAddress');
+  writer.uint(buy ? intent.fundsInBNB : intent.amount, buy ? 'fundsInBNB' : 'amount');
+  writer.u8((min !== undefined ? 1 : 0) | (intent.gas ? 2 : 0));
+  if (min !== undefined) {
+    writer.uint(min, buy ? 'minAmount' : 'minFunds');
+  }
+  if (intent.gas) {
+    writeGas(writer, intent.gas);
+  }
+  writeTags(writer, intent);
+}
+
+function readIntent(reader: Reader): TradeIntent {
+  const side = readSide(reader);
+  const tokenAddress = reader.address();
+  const amount = reader.uint();
+  const flags = reader.flags(0b11);
+  const min = flags & 1 ? reader.uint() : undefined;
+  const gas = flags & 2 ? readGas(reader) : undefined;
+  const tags = readTags(reader);
+
+  const intent: TradeIntent = side === 'buy'
+    ? { side, tokenAddress, fundsInBNB: amount, ...(min !== undefined ? { minAmount: min } : {}) }
+    : { side, tokenAddress, amount, ...(min !== undefined ? { minFunds: min } : {}) };
+  return { ...intent, ...(gas ? { gas } : {}), ...tags };
+}
+
+function writeResult(writer: Writer, result: TradeResultSummary): void {
+  writer.u8(
+    (result.success ? 1 : 0) |
+      (result.blockNumber !== undefined ? 2 : 0) |
+      (result.gasUsed !== undefined ? 4 : 0) |
+      (result.gasPrice !== undefined ? 8 : 0) |
+      (result.extraCosts ? 16 : 0)
+  );
+  writer.hash(result.txHash, 'txHash');
+  writer.u64(result.timestamp, 'timestamp');
+  if (result.blockNumber !== undefined) {
+    writer.u64(result.blockNumber, 'blockNumber');
+  }
+  if (result.gasUsed !== undefined) {
+    writer.uint(result.gasUsed, 'gasUsed');
+  }
+  if (result.gasPrice !== undefined) {
+    writer.uint(result.gasPrice, 'gasPrice');
+  }
+  if (result.extraCosts) {
+    writer.u16(result.extraCosts.length, 'Extra cost count');
+    for (const cost of result.extraCosts) {
+      const kind = EXTRA_COST_KINDS.indexOf(cost.kind);
+      if (kind < 0) {
+        throw new WireFormatError(`Unknown extra cost kind ${cost.kind}`);
+      }
+      writer.u8(kind | (cost.txHash !== undefined ? 0x80 : 0));
+      writer.uint(cost.amount, 'Extra cost amount');
+      if (cost.txHash !== undefined) {
+        writer.hash(cost.txHash, 'Extra cost txHash');
+      }
+    }
+  }
+  writeTags(writer, result);
+}
+
+function readResult(reader: Reader): TradeResultSummary {
+  const flags = reader.flags(0b11111);
+  const result: TradeResultSummary = { success: (flags & 1) !== 0, txHash: reader.hash(), timestamp: reader.u64() };
+  if (flags & 2) {
+    result.blockNumber = reader.u64();
+  }
+  if (flags & 4) {
+    result.gasUsed = reader.uint();
+  }
+  if (flags & 8) {
+    result.gasPrice = reader.uint();
+  }
+  if (flags & 16) {
+    const count = reader.u16();
+    result.extraCosts = Array.from({ length: count }, () => {
+      const at = reader.position();
+      const head = reader.u8();
+      const kind = EXTRA_COST_KINDS[head & 0x7f];
+      if (kind === undefined) {
+        throw new WireFormatError(`Unknown extra cost kind ${head & 0x7f}`, at);
+      }
+      const amount = reader.uint();
+      return { kind, amount, ...(head & 0x80 ? { txHash: reader.hash() } : {}) };
+    });
+  }
+  return { ...result, ...readTags(reader) };
+}
+
+function writeTrade(writer: Writer, trade: TradeEvent): void {
+  const { event } = trade;
+  writer.u8((trade.side === 'sell' ? 1 : 0) | (trade.blockNumber !== undefined ? 2 : 0));
+  writer.address(event.token, 'token');
+  writer.address(event.account, 'account');
+  writer.uint(event.price, 'price');
+  writer.uint(event.amount, 'amount');
+  writer.uint(event.cost, 'cost');
+  writer.uint(event.fee, 'fee');
+  writer.uint(event.offers, 'offers');
+  writer.uint(event.funds, 'funds');
+  if (trade.blockNumber !== undefined) {
+    writer.u64(trade.blockNumber, 'blockNumber');
+  }
+}
+
+function readTrade(reader: Reader): TradeEvent {
+  const flags = reader.flags(0b11);
+  const event = {
+    token: reader.address(),
+    account: reader.address(),
+    price: reader.uint(),
+    amount: reader.uint(),
+    cost: reader.uint(),
+    fee: reader.uint(),
+    offers: reader.uint(),
+    funds: reader.uint(),
+  };
+  return {
+    side: flags & 1 ? 'sell' : 'buy',
+    event,
+    ...(flags & 2 ? { blockNumber: reader.u64() } : {}),
+  };
+}
+
+function writePrice(writer: Writer, update: PriceUpdate): void {
+  writer.u8(update.side === 'buy' ? 0 : 1);
+  writer.address(update.token, 'token');
+  writer.uint(update.price, 'price');
+  writer.u64(update.timestamp, 'timestamp');
+}
+
+function readPrice(reader: Reader): PriceUpdate {
+  const side = readSide(reader);
+  return { side, token: reader.address(), price: reader.uint(), timestamp: reader.u64() };
+}
+
+/**
+ * Encode a message at WIRE_VERSION. Addresses are written as 20 raw bytes and decode checksummed.
+ */
+export function encodeMessage(message: WireMessage): Buffer {
+  const writer = new Writer();
+  writer.u8(WIRE_VERSION);
+  writer.u8(WIRE_MESSAGE_TYPES[message.type]);
+  switch (message.type) {
+    case 'tradeIntent':
+      writeIntent(writer, message.intent);
+      break;
+    case 'tradeResult':
+      writeResult(writer, message.result);
+      break;
+    case 'tradeEvent':
+      writeTrade(writer, message.trade);
+      break;
+    case 'priceUpdate':
+      writePrice(writer, message.update);
+      break;
+  }
+  return writer.finish();
+}
+
+/**
+ * Decode one message. Fields appended by a newer version are skipped, and a message type this
+ * decoder doesn't know is returned as an UnknownWireMessage when its version is newer.
+ */
+export function decodeMessage(bytes: Uint8Array): DecodedWireMessage {
+  const buffer = Buffer.from(bytes.buffer, bytes.byteOffset, bytes.byteLength);
+  if (buffer.length === 0) {
+    throw new WireFormatError('Empty message');
+  }
+  const version = buffer[0];
+  if (version === 0) {
+    throw new WireFormatError('Unsupported wire version 0', 0);
+  }
+  const newer = version > WIRE_VERSION;
+  const reader = new Reader(buffer, !newer);
+  reader.u8();
+  const typeId = reader.u8();
+
+  let message: WireMessage;
+  switch (typeId) {
+    case WIRE_MESSAGE_TYPES.tradeIntent:
+      message = { type: 'tradeIntent', intent: readIntent(reader) };
+      break;
+    case WIRE_MESSAGE_TYPES.tradeResult:
+      message = { type: 'tradeResult', result: readResult(reader) };
+      break;
+    case WIRE_MESSAGE_TYPES.tradeEvent:
+      message = { type: 'tradeEvent', trade: readTrade(reader) };
+      break;
+    case WIRE_MESSAGE_TYPES.priceUpdate:
+      message = { type: 'priceUpdate', update: readPrice(reader) };
+      break;
+    default:
+      if (newer) {
+        return { type: 'unknown', version, typeId, body: reader.rest() };
+      }
+      throw new WireFormatError(`Unknown message type ${typeId}`, 1);
+  }
+
+  if (!newer && reader.remaining() > 0) {
+    throw new WireFormatError(`${reader.remaining()} trailing bytes`, reader.position());
+  }
+  return message;
+}
+
+/**
+ * The wire form of a trade's result
+ */
+export function summarizeResult(result: TransactionResult): TradeResultSummary {
+  const { receipt, ...rest } = result;
+  return {
+    ...rest,
+    ...(receipt?.blockNumber != null ? { blockNumber: Number(receipt.blockNumber) } : {}),
+    ...(receipt?.gasUsed != null ? { gasUsed: BigInt(receipt.gasUsed) } : {}),
+    ...(receipt?.gasPrice != null ? { gasPrice: BigInt(receipt.gasPrice) } : {}),
+  };
+}
@@ -0,0 +1,78 @@
+/**
+ * Length-prefixed framing for byte streams
+ * Each frame is the message length as a big-endian u32 followed by the encoded message, so the
+ * transforms can sit directly on a socket: pipe a WireEncoder into it, and it into a WireDecoder.
+ */
+
+import { Transform, TransformCallback } from 'stream';
+import { WIRE_MAX_FRAME_BYTES } from '../constants';
+import { WireFormatError } from '../errors';
+import { WireMessage, decodeMessage, encodeMessage } from './codec';
+
+/**
+ * One message with its length prefix
+ */
+export function encodeFrame(message: WireMessage): Buffer {
+  const payload = encodeMessage(message);
+  const header = Buffer.alloc(4);
+  header.writeUInt32BE(payload.length);
+  return Buffer.concat([header, payload]);
+}
+
+/**
+ * Object-mode writable side: write WireMessages, read framed bytes
+ */
+export class WireEncoder extends Transform {
+  constructor() {
+    super({ writableObjectMode: true });
+  }
+
+  _transform(message: WireMessage, _encoding: BufferEncoding, callback: TransformCallback): void {
+    try {
+      callback(null, encodeFrame(message));
+    } catch (error) {
+      callback(error as Error);
+    }
+  }
+}
+
+/**
+ * Object-mode readable side: write bytes in chunks of any size, read DecodedWireMessages. A frame
+ * longer than `maxFrameBytes`, an undecodable message, or a stream ending inside a frame is an error.
+ */
+export class WireDecoder extends Transform {
+  private buffered: Buffer = Buffer.alloc(0);
+
+  constructor(private maxFrameBytes: number = WIRE_MAX_FRAME_BYTES) {
+    super({ readableObjectMode: true });
+  }
+
+  _transform(chunk: Buffer, _encoding: BufferEncoding, callback: TransformCallback): void {
+    this.buffered = this.buffered.length > 0 ? Buffer.concat([this.buffered, chunk]) : chunk;
+    try {
+      while (this.buffered.length >= 4) {
+        const length = this.buffered.readUInt32BE(0);
+        if (length > this.maxFrameBytes) {
+          throw new WireFormatError(`Frame of ${length} bytes exceeds the ${this.maxFrameBytes}-byte limit`);
+        }
+        if (this.buffered.length < 4 + length) {
+          break;
+        }
+        const payload = this.buffered.subarray(4, 4 + length);
+        this.buffered = this.buffered.subarray(4 + length);
+        this.push(decodeMessage(payload));
+      }
+      callback();
+    } catch (error) {
+      callback(error as Error);
+    }
+  }
+
+  _flush(callback: TransformCallback): void {
+    callback(
+      this.buffered.length > 0
+        ? new WireFormatError(`Stream ended inside a frame with ${this.buffered.length} bytes buffered`)
+        : null
+    );
+  }
+}
@@ -0,0 +1,22 @@
+/**
+ * Compact binary wire format for trade messages
+ * Imported separately (`@fnzero/four-trading-sdk/wire`) so the main entry point never loads it.
+ *
+ * Encodes trade intents, trade results, trade events and price updates for passing between
+ * processes, e.g. a strategy process and an execution process over a Unix socket. Messages carry
+ * a version byte; streams are framed with a u32 length prefix.
+ */
+
+export {
+  TradeResultSummary,
+  WireMessage,
+  UnknownWireMessage,
+  DecodedWireMessage,
+  WIRE_MESSAGE_TYPES,
+  encodeMessage,
+  decodeMessage,
+  summarizeResult,
+} from './codec';
+export { encodeFrame, WireEncoder, WireDecoder } from './framing';
+export { WIRE_VERSION, WIRE_MAX_FRAME_BYTES } from '../constants';
+export { WireFormatError } from '../errors';
@@ -0,0 +1,154 @@
+import { Transaction, WebSocketProvider } from 'ethers';
+import { getErc20Interface } from '../../src/abi';
+import { ApprovalLock } from '../../src/approvalLock';
+import { FourTrading } from '../../src/fourTrading';
+import { MOCK_PRIVATE_KEY, MOCK_TOKEN, MockChain, MockJsonRpcProvider, MockWebSocket, withMockTrader } from '../../src/testing';
+
+const ONE = 10n ** 18n;
+const BAG = 100_000n * ONE; // What the wallet holds: two sells' worth
+const APPROVE = getErc20Interface().getFunction('approve')!.selector;
+
+// Wait until the chain holds a pending transaction other than `except`
+async function nextHeld(chain: MockChain, except?: Transaction): Promise<Transaction> {
+  for (;;) {
+    const [held] = chain.mempool.values();
+    if (held && held.hash !== except?.hash) {
+      return held;
+    }
+    await new Promise((resolve) => setTimeout(resolve, 10));
+  }
+}
+
+describe('auto-approving sells', () => {
+  beforeAll(() => {
+    jest.spyOn(console, 'log').mockImplementation(() => {});
+    jest.spyOn(console, 'error').mockImplementation(() => {});
+  });
+
+  afterAll(() => {
+    jest.restoreAllMocks();
+  });
+
+  it('approves once before a sell whose allowance falls short, then not again', async () => {
+    await withMockTrader(async (trader, chain) => {
+      chain.tokenBalance = BAG;
+      await trader.sellToken({ tokenAddress: MOCK_TOKEN, amount: BAG / 2n });
+      await trader.sellToken({ tokenAddress: MOCK_TOKEN, amount: BAG / 2n });
+
+      expect(chain.transactions.map((tx) => tx.name)).toEqual(['approve', 'sellToken', 'sellToken']);
+    }, { config: { autoApprove: true, approvalLock: new ApprovalLock() } });
+  });
+
+  it('sends one approval for two racing sells of the same token', async () => {
+    await withMockTrader(async (trader, chain) => {
+      chain.tokenBalance = BAG;
+      const results = await Promise.all([
+        trader.sellToken({ tokenAddress: MOCK_TOKEN, amount: BAG / 2n, autoApprove: true }),
+        trader.sellToken({ tokenAddress: MOCK_TOKEN, amount: BAG / 2n, autoApprove: true }),
+      ]);
+
+      expect(results.every((result) => result.success)).toBe(true);
+      expect(chain.transactions.map((tx) => tx.name)).toEqual(['approve', 'sellToken', 'sellToken']);
+      expect(chain.tokenBalance).toBe(0n);
+    }, { config: { approvalLock: new ApprovalLock() } });
+  });
+
+  it('coordinates handles of the same wallet through a shared lock', async () => {
+    const lock = new ApprovalLock();
+    await withMockTrader(async (trader, chain) => {
+      const provider = new MockJsonRpcProvider(chain);
+      const eventProvider = new WebSocketProvider(() => new MockWebSocket(chain) as any, undefined, { cacheTimeout: -1 });
+      const clone = new FourTrading({
+        rpcUrl: '',
+        wssUrl: '',
+        privateKey: MOCK_PRIVATE_KEY,
+        provider,
+        eventProvider,
+        autoApprove: true,
+        approvalLock: lock,
+      });
+      try {
+        chain.tokenBalance = BAG;
+        await Promise.all([
+          trader.sellToken({ tokenAddress: MOCK_TOKEN, amount: BAG / 2n }),
+          clone.sellToken({ tokenAddress: MOCK_TOKEN, amount: BAG / 2n }),
+        ]);
+
+        expect(chain.transactions.map((tx) => tx.name)).toEqual(['approve', 'sellToken', 'sellToken']);
+      } finally {
+        clone.removeAllListeners();
+        provider.destroy();
+        await eventProvider.destroy();
+      }
+    }, { config: { autoApprove: true, approvalLock: lock } });
+  });
+
+  it('speeds up an approval stuck past the wait timeout instead of waiting on it forever', async () => {
+    await withMockTrader(async (trader, chain) => {
+      chain.tokenBalance = BAG;
+      chain.holdTransactions = true;
+      const first = trader.sellToken({ tokenAddress: MOCK_TOKEN, amount: BAG / 2n });
+      const stuck = await nextHeld(chain);
+      const second = trader.sellToken({ tokenAddress: MOCK_TOKEN, amount: BAG / 2n });
+
+      const speedUp = await nextHeld(chain, stuck);
+      chain.holdTransactions = false;
+      chain.release();
+      await Promise.all([first, second]);
+
+      const price = (tx: Transaction) => tx.maxFeePerGas ?? tx.gasPrice!;
+      expect(speedUp.nonce).toBe(stuck.nonce);
+      expect(price(speedUp)).toBe((price(stuck) * 11n) / 10n);
+      expect(chain.transactions.map((tx) => [tx.name, tx.hash])).toEqual([
+        ['approve', speedUp.hash],
+        ['sellToken', expect.any(String)],
+        ['sellToken', expect.any(String)],
+      ]);
+    }, { config: { autoApprove: true, approvalWaitTimeoutMs: 50, approvalLock: new ApprovalLock() } });
+  }, 15_000);
+
+  it('lets only the first waiter to time out speed up the approval', async () => {
+    await withMockTrader(async (trader, chain) => {
+      const approvals: string[] = [];
+      const handle = chain.handle.bind(chain);
+      chain.handle = (method, params) => {
+        if (method === 'eth_sendRawTransaction' && Transaction.from(params[0]).data.startsWith(APPROVE)) {
+          approvals.push(Transaction.from(params[0]).hash!);
+        }
+        return handle(method, params);
+      };
+      chain.tokenBalance = (BAG * 3n) / 2n;
+      chain.holdTransactions = true;
+      const first = trader.sellToken({ tokenAddress: MOCK_TOKEN, amount: BAG / 2n });
+      const stuck = await nextHeld(chain);
+      const waiters = [
+        trader.sellToken({ tokenAddress: MOCK_TOKEN, amount: BAG / 2n }),
+        trader.sellToken({ tokenAddress: MOCK_TOKEN, amount: BAG / 2n }),
+      ];
+
+      await nextHeld(chain, stuck);
+      await new Promise((resolve) => setTimeout(resolve, 200)); // Several more wait timeouts
+      chain.holdTransactions = false;
+      chain.release();
+      await Promise.all([first, ...waiters]);
+
+      expect(approvals).toHaveLength(2);
+      expect(chain.transactions.map((tx) => tx.name)).toEqual(['approve', 'sellToken', 'sellToken', 'sellToken']);
+    }, { config: { autoApprove: true, approvalWaitTimeoutMs: 50, approvalLock: new ApprovalLock() } });
+  }, 15_000);
+
+  it('lets the waiting sell approve itself when the first approval fails', async () => {
+    await withMockTrader(async (trader, chain) => {
+      chain.tokenBalance = BAG;
+      chain.revertNext = 'approve rejected';
+      const [failed, sold] = await Promise.allSettled([
+        trader.sellToken({ tokenAddress: MOCK_TOKEN, amount: BAG / 2n }),
+        trader.sellToken({ tokenAddress: MOCK_TOKEN, amount: BAG / 2n }),
+      ]);
+
+      expect(failed.status).toBe('rejected');
+      expect(sold.status).toBe('fulfilled');
+      expect(chain.transactions.map((tx) => tx.name)).toEqual(['approve', 'sellToken']);
+    }, { config: { autoApprove: true, approvalLock: new ApprovalLock() } });
+  });
+});
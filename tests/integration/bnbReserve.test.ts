@@ -0,0 +1,62 @@
+import { BnbReserveError } from '../../src/errors';
+import { MOCK_TOKEN, withMockTrader } from '../../src/testing';
+
+const ONE = 10n ** 18n;
+const GAS = { gasLimit: 300_000n, gasPrice: 1_000_000_000n };
+const GAS_COST = GAS.gasLimit * GAS.gasPrice;
+const RESERVE = ONE / 2n;
+
+describe('BNB reserve', () => {
+  beforeAll(() => {
+    jest.spyOn(console, 'log').mockImplementation(() => {});
+    jest.spyOn(console, 'error').mockImplementation(() => {});
+  });
+
+  afterAll(() => {
+    jest.restoreAllMocks();
+  });
+
+  it('buyMax spends the whole balance less gas and the reserve', async () => {
+    await withMockTrader(async (trader, chain) => {
+      const result = await trader.buyMax(MOCK_TOKEN, undefined, undefined, GAS, { tags: ['max'] });
+
+      expect(result.tags).toEqual(['max']);
+      expect(chain.transactions.map((tx) => [tx.name, tx.value])).toEqual([['buyTokenAMAP', 2n * ONE - GAS_COST - RESERVE]]);
+    }, { bnbBalance: 2n * ONE, config: { minBnbReserve: RESERVE } });
+  });
+
+  it('buyMax refuses a balance that cannot cover gas above the reserve', async () => {
+    await withMockTrader(async (trader, chain) => {
+      await expect(trader.buyMax(MOCK_TOKEN, undefined, undefined, GAS)).rejects.toThrow(BnbReserveError);
+      expect(chain.transactions).toEqual([]);
+    }, { bnbBalance: RESERVE + GAS_COST, config: { minBnbReserve: RESERVE } });
+  });
+
+  it('lets a buy spend down to the reserve but not a wei past it', async () => {
+    const balance = 2n * ONE;
+    const spendable = balance - GAS_COST - RESERVE;
+
+    await withMockTrader(async (trader, chain) => {
+      await expect(trader.buyToken({ tokenAddress: MOCK_TOKEN, fundsInBNB: spendable + 1n, gas: GAS })).rejects.toMatchObject({
+        code: 'BNB_RESERVE_VIOLATED',
+        details: { required: (spendable + 1n + GAS_COST).toString(), available: balance.toString(), reserve: RESERVE.toString() },
+      });
+      await trader.buyToken({ tokenAddress: MOCK_TOKEN, fundsInBNB: spendable, gas: GAS });
+
+      expect(chain.transactions.map((tx) => tx.value)).toEqual([spendable]);
+    }, { bnbBalance: balance, config: { minBnbReserve: RESERVE } });
+  });
+
+  it('only warns when a sell runs below the reserve', async () => {
+    const warn = jest.spyOn(console, 'warn').mockImplementation(() => {});
+
+    await withMockTrader(async (trader, chain) => {
+      chain.tokenBalance = 1000n * ONE;
+      await trader.approveToken(MOCK_TOKEN);
+      const result = await trader.sellToken({ tokenAddress: MOCK_TOKEN, amount: 1000n * ONE });
+
+      expect(result.success).toBe(true);
+      expect(warn).toHaveBeenCalledWith(expect.stringContaining('below the configured reserve'));
+    }, { bnbBalance: RESERVE / 2n, config: { minBnbReserve: RESERVE } });
+  });
+});
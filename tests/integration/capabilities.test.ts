@@ -0,0 +1,39 @@
+import { CapabilityMissingError } from '../../src/errors';
+import { MOCK_TOKEN, withMockTrader } from '../../src/testing';
+
+describe('node capabilities', () => {
+  beforeAll(() => {
+    jest.spyOn(console, 'log').mockImplementation(() => {});
+    jest.spyOn(console, 'error').mockImplementation(() => {});
+  });
+
+  afterAll(() => {
+    jest.restoreAllMocks();
+  });
+
+  it('probes the mock node once', async () => {
+    await withMockTrader(async (trader) => {
+      const capabilities = await trader.capabilities();
+
+      expect(capabilities).toEqual({
+        pendingSubscription: true,
+        trace: false,
+        archiveDepth: null,
+        feeHistory: false,
+        batchRequests: true,
+        multicall: true,
+      });
+      expect(await trader.capabilities()).toEqual(capabilities);
+    });
+  });
+
+  it('fails fast when an override denies a capability', async () => {
+    await withMockTrader(async (trader, chain) => {
+      await expect(trader.allowances([MOCK_TOKEN])).rejects.toBeInstanceOf(CapabilityMissingError);
+      await expect(trader.waitForToken(MOCK_TOKEN, 1000, { mode: 'mempool' })).rejects.toMatchObject({ required: 'pendingSubscription' });
+      expect(() => trader.onPendingBuy(MOCK_TOKEN, () => {})).toThrow(CapabilityMissingError);
+      await expect(trader.creatorReputation(MOCK_TOKEN)).rejects.toMatchObject({ feature: 'creatorReputation', required: 'archiveDepth' });
+      expect(chain.transactions).toEqual([]);
+    }, { config: { capabilities: { multicall: false, pendingSubscription: false, archiveDepth: 128 } } });
+  });
+});
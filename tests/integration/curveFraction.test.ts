@@ -0,0 +1,77 @@
+import { CurveFractionError, InvalidAmountError } from '../../src/errors';
+import { MOCK_TOKEN, withMockTrader } from '../../src/testing';
+
+const ONE = 10n ** 18n;
+const OFFERS = 1_000_000n * ONE; // 1 BNB of tokens left at 1,000,000 tokens per BNB
+
+describe('buyCurveFraction', () => {
+  beforeAll(() => {
+    jest.spyOn(console, 'log').mockImplementation(() => {});
+    jest.spyOn(console, 'error').mockImplementation(() => {});
+  });
+
+  afterAll(() => {
+    jest.restoreAllMocks();
+  });
+
+  it('buys the share of the remaining supply through the exact-amount path', async () => {
+    await withMockTrader(async (trader, chain) => {
+      chain.offers = OFFERS;
+
+      const result = await trader.buyCurveFraction(MOCK_TOKEN, 200, 5, { tags: ['fraction'] });
+
+      expect(chain.transactions.map((tx) => [tx.name, tx.args[2]])).toEqual([['buyToken', OFFERS / 50n]]);
+      expect(chain.transactions[0].args[3]).toBe((OFFERS / 50n / 1_000_000n) * 101n / 100n * 105n / 100n); // Cost and fee, plus 5%
+      expect(result.curveFraction).toEqual({
+        requestedBps: 200,
+        executedBps: 200,
+        clamped: false,
+        offersBefore: OFFERS,
+        tokenAmount: OFFERS / 50n,
+        realizedBps: 200,
+      });
+      expect(result.tags).toEqual(['fraction']);
+    });
+  });
+
+  it('spans the remaining supply exactly', async () => {
+    await withMockTrader(async (trader, chain) => {
+      chain.offers = OFFERS;
+
+      const result = await trader.buyCurveFraction(MOCK_TOKEN, 10000, 1);
+
+      expect(result.curveFraction).toMatchObject({ tokenAmount: OFFERS, realizedBps: 10000 });
+      expect(chain.offers).toBe(0n);
+    });
+  });
+
+  it('refuses a share that rounds to zero tokens without sending', async () => {
+    await withMockTrader(async (trader, chain) => {
+      chain.offers = 9_999n;
+
+      await expect(trader.buyCurveFraction(MOCK_TOKEN, 1, 1)).rejects.toBeInstanceOf(InvalidAmountError);
+      expect(chain.transactions).toEqual([]);
+    });
+  });
+
+  it('enforces the per-transaction limit', async () => {
+    await withMockTrader(async (trader, chain) => {
+      chain.offers = OFFERS;
+
+      await expect(trader.buyCurveFraction(MOCK_TOKEN, 1000, 1)).rejects.toBeInstanceOf(CurveFractionError);
+      const clamped = await trader.buyCurveFraction(MOCK_TOKEN, 1000, 1, { clamp: true });
+
+      expect(clamped.curveFraction).toMatchObject({ requestedBps: 1000, executedBps: 500, clamped: true, realizedBps: 500 });
+    }, { config: { maxCurveFractionBps: 500 } });
+  });
+
+  it('clamps when configured', async () => {
+    await withMockTrader(async (trader, chain) => {
+      chain.offers = OFFERS;
+
+      const result = await trader.buyCurveFraction(MOCK_TOKEN, 1000, 1);
+
+      expect(result.curveFraction).toMatchObject({ executedBps: 500, tokenAmount: OFFERS / 20n });
+    }, { config: { maxCurveFractionBps: 500, clampCurveFraction: true } });
+  });
+});
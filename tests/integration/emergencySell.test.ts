@@ -0,0 +1,113 @@
+import { MOCK_TOKEN, MockChain, withMockTrader } from '../../src/testing';
+import { FourTrading } from '../../src/fourTrading';
+
+const ONE = 10n ** 18n;
+const GWEI = 10n ** 9n;
+const BAG = 99_000n * ONE; // 0.1 BNB less the 1% fee, at 1,000,000 tokens per BNB
+
+// Buy a bag, then leave `count` more buys of it pending at 1 gwei
+async function holdPendingBuys(trader: FourTrading, chain: MockChain, count: number): Promise<string[]> {
+  await trader.buyToken({ tokenAddress: MOCK_TOKEN, fundsInBNB: ONE / 10n });
+  await trader.syncNonce();
+  chain.holdTransactions = true;
+  const hashes: string[] = [];
+  for (let i = 0; i < count; i++) {
+    hashes.push((await trader.buyBlind(MOCK_TOKEN, ONE / 10n, 0n, { gasPrice: GWEI, gasLimit: 300_000n })).txHash);
+  }
+  chain.holdTransactions = false;
+  return hashes;
+}
+
+describe('emergencySell', () => {
+  beforeAll(() => {
+    jest.spyOn(console, 'log').mockImplementation(() => {});
+    jest.spyOn(console, 'error').mockImplementation(() => {});
+  });
+
+  afterAll(() => {
+    jest.restoreAllMocks();
+  });
+
+  it('sells the whole balance at the aggressive price when nothing is pending', async () => {
+    await withMockTrader(async (trader, chain) => {
+      await holdPendingBuys(trader, chain, 0);
+
+      const result = await trader.emergencySell(MOCK_TOKEN, 5, { tags: ['stop'] });
+
+      expect(result.decisions).toEqual([]);
+      expect(result.replacements).toEqual([]);
+      expect(result.untrackedPending).toBe(0);
+      expect(result.sell.success).toBe(true);
+      expect(chain.transactions.map((tx) => tx.name)).toEqual(['buyTokenAMAP', 'sellToken']);
+      expect(chain.tokenBalance).toBe(0n);
+      expect(trader.getTradeHistory().getTradesByTag('emergency').map((trade) => trade.tags)).toEqual([['stop', 'emergency']]);
+    });
+  });
+
+  it('cancels a pending buy of the token before selling', async () => {
+    await withMockTrader(async (trader, chain) => {
+      const [blind] = await holdPendingBuys(trader, chain, 1);
+
+      const result = await trader.emergencySell(MOCK_TOKEN, 5);
+
+      expect(result.decisions).toMatchObject([{ txHash: blind, nonce: 1, conflicting: true, action: 'cancel', gasPrice: 3n * GWEI }]);
+      expect(result.replacements[0].txHash).toBeDefined();
+      expect(result.replacements[0].error).toBeUndefined();
+      expect(chain.transactions.map((tx) => tx.name)).toEqual(['buyTokenAMAP', 'transfer', 'sellToken']);
+      expect(chain.mempool.size).toBe(0);
+      expect(chain.tokenBalance).toBe(0n);
+    });
+  });
+
+  it('handles several pending buys by policy, lowest nonce first', async () => {
+    await withMockTrader(async (trader, chain) => {
+      await holdPendingBuys(trader, chain, 3);
+
+      const result = await trader.emergencySell(MOCK_TOKEN, 5, { amount: BAG, policy: { conflictingBuy: 'reprice', maxReplacements: 2 } });
+
+      expect(result.decisions.map((d) => [d.nonce, d.action])).toEqual([[1, 'reprice'], [2, 'reprice'], [3, 'wait']]);
+      expect(result.replacements.filter((outcome) => outcome.txHash)).toHaveLength(2);
+      expect(chain.transactions.map((tx) => tx.name)).toEqual(['buyTokenAMAP', 'buyTokenAMAP', 'buyTokenAMAP', 'sellToken']);
+      expect(chain.transactions[3].args[1]).toBe(BAG);
+      expect(chain.mempool.size).toBe(1); // The waiting buy, still holding its nonce
+    });
+  });
+
+  it('reprices a pending raw send ahead of the exit', async () => {
+    await withMockTrader(async (trader, chain) => {
+      await holdPendingBuys(trader, chain, 0);
+      chain.holdTransactions = true;
+      const approve = trader.rawSend('approve(address,uint256)', [trader.getWalletAddress(), 1n], {
+        to: MOCK_TOKEN,
+        gas: { gasPrice: GWEI, gasLimit: 100_000n },
+      });
+      approve.catch(() => undefined); // Waits on the repriced hash's predecessor; never awaited
+      while (chain.mempool.size === 0) {
+        await new Promise((resolve) => setTimeout(resolve, 10));
+      }
+      chain.holdTransactions = false;
+
+      const result = await trader.emergencySell(MOCK_TOKEN, 5);
+
+      expect(result.decisions.map((d) => [d.nonce, d.conflicting, d.action])).toEqual([[1, false, 'reprice']]);
+      expect(result.replacements[0].txHash).toBeDefined();
+      expect(chain.transactions.map((tx) => tx.name)).toEqual(['buyTokenAMAP', 'approve', 'sellToken']);
+    });
+  });
+
+  it('prices the exit from the caller when given', async () => {
+    await withMockTrader(async (trader, chain) => {
+      await holdPendingBuys(trader, chain, 1);
+
+      const result = await trader.emergencySell(MOCK_TOKEN, 5, { gas: { gasPrice: 10n * GWEI } });
+
+      expect(result.decisions[0].gasPrice).toBe(10n * GWEI);
+    });
+  });
+
+  it('refuses an empty balance', async () => {
+    await withMockTrader(async (trader) => {
+      await expect(trader.emergencySell(MOCK_TOKEN, 5)).rejects.toThrow(/no .* balance/);
+    });
+  });
+});
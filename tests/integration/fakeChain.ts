@@ -0,0 +1,35 @@
+/**
+ * In-process BSC node for integration tests: the SDK's MockChain with sells paying double the
+ * buy price, so a round trip shows a profit
+ */
+
+import { getFourTradingInterface } from '../../src/abi';
+import { FOUR_MEME_ADDRESS } from '../../src/constants';
+import {
+  MOCK_CREATOR,
+  MOCK_MIN_TRADING_FEE,
+  MOCK_PRIVATE_KEY,
+  MOCK_TOKEN,
+  MockChain,
+  MockJsonRpcProvider,
+  MockWebSocket,
+} from '../../src/testing';
+
+export const ONE = 10n ** 18n;
+export const PRIVATE_KEY = MOCK_PRIVATE_KEY;
+export const CONTRACT = FOUR_MEME_ADDRESS;
+export const TOKEN = MOCK_TOKEN;
+export const CREATOR = MOCK_CREATOR;
+export const TOKENS_PER_BNB = 1_000_000n;
+export const GAS_PRICE = 1_000_000_000n;
+export const MIN_TRADING_FEE = MOCK_MIN_TRADING_FEE;
+
+export const four = getFourTradingInterface();
+
+export class FakeChain extends MockChain {
+  constructor() {
+    super({ tokensPerBnb: TOKENS_PER_BNB, sellPriceBps: 20000, gasPrice: GAS_PRICE }); // The price doubled while we held
+  }
+}
+
+export { MockJsonRpcProvider as FakeRpc, MockWebSocket as FakeSocket };
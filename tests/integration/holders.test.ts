@@ -0,0 +1,109 @@
+import { ethers, WebSocketProvider } from 'ethers';
+import { getErc20Interface, getPancakeFactoryInterface } from '../../src/abi';
+import { FOUR_MEME_ADDRESS, PANCAKE_V2_FACTORY_ADDRESS } from '../../src/constants';
+import { FourTrading } from '../../src/fourTrading';
+import { MOCK_PRIVATE_KEY, MOCK_TOKEN, MockChain, MockJsonRpcProvider, MockWebSocket } from '../../src/testing';
+
+const erc20 = getErc20Interface();
+const TRANSFER = erc20.getEvent('Transfer')!.topicHash;
+const SUPPLY = 1000n;
+const ALICE = '0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa';
+const BOB = '0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb';
+const PAIR = '0x7777777777777777777777777777777777777777';
+
+/**
+ * Mock chain that serves the token's creation and Transfer logs, its supply and its PancakeSwap pair
+ */
+class HolderChain extends MockChain {
+  logs: any[] = [this.tokenCreateLog()];
+  transferScans: Array<[number, number]> = []; // Block ranges of each Transfer getLogs
+  pair = ethers.ZeroAddress;
+
+  transfer(from: string, to: string, value: bigint): void {
+    const event = erc20.encodeEventLog('Transfer', [from, to, value]);
+    this.logs.push({ ...this.tokenCreateLog(), address: MOCK_TOKEN, topics: event.topics, data: event.data, logIndex: ethers.toQuantity(this.logs.length) });
+  }
+
+  handle(method: string, params: any[]): unknown {
+    if (method === 'eth_getLogs') {
+      const { topics, fromBlock, toBlock } = params[0];
+      const [from, to] = [Number(fromBlock), Number(toBlock)];
+      if (topics[0] === TRANSFER) {
+        this.transferScans.push([from, to]);
+      }
+      return this.logs.filter((log) => log.topics[0] === topics[0] && Number(log.blockNumber) >= from && Number(log.blockNumber) <= to);
+    }
+    if (method === 'eth_call') {
+      const { to, data } = params[0];
+      if (to.toLowerCase() === PANCAKE_V2_FACTORY_ADDRESS.toLowerCase()) {
+        return getPancakeFactoryInterface().encodeFunctionResult('getPair', [this.pair]);
+      }
+      if (to.toLowerCase() === MOCK_TOKEN.toLowerCase() && data.startsWith(erc20.getFunction('totalSupply')!.selector)) {
+        return erc20.encodeFunctionResult('totalSupply', [SUPPLY]);
+      }
+    }
+    return super.handle(method, params);
+  }
+}
+
+async function withTrader(holderCacheTtlMs: number, run: (trader: FourTrading, chain: HolderChain) => Promise<void>): Promise<void> {
+  const chain = new HolderChain();
+  const provider = new MockJsonRpcProvider(chain);
+  const eventProvider = new WebSocketProvider(() => new MockWebSocket(chain) as any, undefined, { cacheTimeout: -1 });
+  const trader = new FourTrading({ rpcUrl: '', wssUrl: '', privateKey: MOCK_PRIVATE_KEY, provider, eventProvider, holderCacheTtlMs });
+  try {
+    await run(trader, chain);
+  } finally {
+    trader.removeAllListeners();
+    provider.destroy();
+    await eventProvider.destroy();
+  }
+}
+
+// Past the provider's block number cache, so the next read sees the new head
+const nextHead = () => new Promise((resolve) => setTimeout(resolve, 300));
+
+describe('holders', () => {
+  it('reuses a recent scan without reading the chain again', async () => {
+    await withTrader(60_000, async (trader, chain) => {
+      chain.transfer(ethers.ZeroAddress, FOUR_MEME_ADDRESS, SUPPLY);
+      chain.transfer(FOUR_MEME_ADDRESS, ALICE, 300n);
+
+      const first = await trader.holders(MOCK_TOKEN);
+      chain.block += 5;
+      chain.transfer(FOUR_MEME_ADDRESS, BOB, 100n);
+      await nextHead();
+      const second = await trader.holders(MOCK_TOKEN, 1);
+
+      expect(first.holderCount).toBe(1);
+      expect(second.holderCount).toBe(1);
+      expect(second.topHolders.map((holder) => holder.address.toLowerCase())).toEqual([ALICE]);
+      expect(chain.transferScans).toHaveLength(1);
+    });
+  });
+
+  it('extends an expired scan from its watermark and leaves out the pair', async () => {
+    await withTrader(0, async (trader, chain) => {
+      chain.transfer(ethers.ZeroAddress, FOUR_MEME_ADDRESS, SUPPLY);
+      chain.transfer(FOUR_MEME_ADDRESS, ALICE, 300n);
+      const first = await trader.holders(MOCK_TOKEN);
+
+      chain.block += 5;
+      chain.pair = PAIR;
+      chain.transfer(FOUR_MEME_ADDRESS, PAIR, 600n); // Graduation moves the rest into the pool
+      chain.transfer(PAIR, BOB, 100n);
+      await nextHead();
+      const second = await trader.holders(MOCK_TOKEN);
+
+      expect(chain.transferScans).toEqual([
+        [first.fromBlock, first.toBlock],
+        [first.toBlock + 1, first.toBlock + 5],
+      ]);
+      expect(second.topHolders.map((holder) => [holder.address.toLowerCase(), holder.balance])).toEqual([
+        [ALICE, 300n],
+        [BOB, 100n],
+      ]);
+      expect(second.topConcentrationBps).toBe(4000);
+    });
+  });
+});
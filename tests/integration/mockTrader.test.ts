@@ -0,0 +1,284 @@
+import { toQuantity } from 'ethers';
+import { TOKEN_MANAGER_V1_ADDRESS, WBNB_ADDRESS } from '../../src/constants';
+import { PositionPersistError, SubAccountBalanceError, TokenLaunchValidationError, UneconomicalTradeError, ValidationError } from '../../src/errors';
+import { CreateTokenParams, FourTrading } from '../../src/fourTrading';
+import { Price } from '../../src/price';
+import { SubAccountLedger } from '../../src/subAccounts';
+import { memeTokenTemplate } from '../../src/tokenLaunch';
+import { MOCK_PRIVATE_KEY, MOCK_TOKEN, createMockTrader, withMockTrader } from '../../src/testing';
+import { applySlippage } from '../../src/utils';
+import { encodeVersionedSell } from '../../src/version';
+
+const ONE = 10n ** 18n;
+
+describe('mock trader', () => {
+  beforeAll(() => {
+    jest.spyOn(console, 'log').mockImplementation(() => {});
+    jest.spyOn(console, 'error').mockImplementation(() => {});
+  });
+
+  afterAll(() => {
+    jest.restoreAllMocks();
+  });
+
+  it('quotes from the price set on the chain', async () => {
+    await withMockTrader(async (trader, chain) => {
+      expect((await trader.quoteBuy(MOCK_TOKEN, ONE)).tokenAmount).toBe(2000n * ONE);
+
+      chain.tokensPerBnb = 1000n;
+      expect((await trader.quoteBuy(MOCK_TOKEN, ONE)).tokenAmount).toBe(1000n * ONE);
+    }, { tokensPerBnb: 2000n });
+  });
+
+  it('mines trades into receipts the trader parses, and records each transaction', async () => {
+    await withMockTrader(async (trader, chain) => {
+      const buy = await trader.buyToken({ tokenAddress: MOCK_TOKEN, fundsInBNB: ONE / 10n, tags: ['test'] });
+      await trader.approveToken(MOCK_TOKEN);
+      const sell = await trader.sellAll(MOCK_TOKEN);
+
+      expect(buy.success && sell.success).toBe(true);
+      expect(buy.price!.equals(new Price(ONE / 10n, (ONE / 10n - ONE / 1000n) * 1_000_000n))).toBe(true); // Spent, fee included, per token received
+      expect(chain.transactions.map((tx) => tx.name)).toEqual(['buyTokenAMAP', 'approve', 'sellToken']);
+      expect(chain.transactions[0]).toMatchObject({ hash: buy.txHash, value: ONE / 10n });
+      expect(trader.getTradeHistory().getTrades().map((trade) => trade.direction)).toEqual(['buy', 'sell']);
+      expect(trader.getPositionTracker().getPosition(MOCK_TOKEN)!.amount).toBe(0n);
+    });
+  });
+
+  it('stamps a trade with its block even when a replica serves the receipt first', async () => {
+    await withMockTrader(async (trader, chain) => {
+      const handle = chain.handle.bind(chain);
+      let sent = false;
+      let missing = 2;
+      chain.handle = (method, params) => {
+        sent ||= method === 'eth_sendRawTransaction';
+        if (sent && method === 'eth_getBlockByNumber' && params[0] !== 'latest' && missing > 0) {
+          missing -= 1;
+          return null;
+        }
+        return handle(method, params);
+      };
+
+      const result = await trader.buyToken({ tokenAddress: MOCK_TOKEN, fundsInBNB: ONE / 10n });
+      const block = handle('eth_getBlockByNumber', [toQuantity(result.receipt.blockNumber), false]) as any;
+
+      expect(missing).toBe(0);
+      expect(result.timestamp).toBe(Number(block.timestamp));
+    });
+  });
+
+  it('fails a trade whose position update could not be persisted', async () => {
+    await withMockTrader(async (trader) => {
+      jest.spyOn(console, 'error').mockImplementation(() => undefined);
+      await trader.openPositionStore({
+        load: async () => new Map(),
+        apply: async () => {
+          throw new Error('disk full');
+        },
+        clear: async () => undefined,
+      });
+
+      const buy = trader.buyToken({ tokenAddress: MOCK_TOKEN, fundsInBNB: ONE / 10n });
+      await expect(buy).rejects.toThrow(PositionPersistError);
+      await expect(buy).rejects.toMatchObject({ result: { success: true } });
+      expect(trader.getPositionTracker().getPosition(MOCK_TOKEN)!.amount).toBeGreaterThan(0n);
+    });
+  });
+
+  it('rejects the next transaction with a canned revert', async () => {
+    const mock = createMockTrader();
+    try {
+      mock.chain.revertNext = 'Disabled';
+
+      await expect(mock.trader.buyToken({ tokenAddress: MOCK_TOKEN, fundsInBNB: ONE / 10n })).rejects.toThrow(/Disabled/);
+      expect(mock.chain.transactions).toHaveLength(0);
+
+      await mock.trader.buyToken({ tokenAddress: MOCK_TOKEN, fundsInBNB: ONE / 10n });
+      expect(mock.chain.tokenBalance).toBe(99_000n * ONE);
+    } finally {
+      await mock.close();
+    }
+  });
+
+  it('waits for the token balance to rise above a previous reading', async () => {
+    await withMockTrader(async (trader, chain) => {
+      chain.tokenBalance = 10n * ONE;
+      setTimeout(() => (chain.tokenBalance = 25n * ONE), 30);
+
+      expect(await trader.waitForBalanceIncrease(MOCK_TOKEN, 10n * ONE, 5000, 10)).toBe(25n * ONE);
+      await expect(trader.waitForBalanceIncrease(MOCK_TOKEN, 25n * ONE, 50, 10)).rejects.toMatchObject({
+        code: 'BALANCE_WAIT_TIMEOUT',
+        lastBalance: 25n * ONE,
+      });
+    });
+  });
+
+  it('liquidates every token with a balance, skipping empty ones and isolating failures', async () => {
+    await withMockTrader(async (trader, chain) => {
+      const unreadable = '0x' + '12'.repeat(20);
+      chain.tokenBalance = 50_000n * ONE;
+      const quote = await trader.quoteSell(MOCK_TOKEN, chain.tokenBalance);
+
+      const results = await trader.liquidateAll([unreadable, WBNB_ADDRESS, MOCK_TOKEN], 5);
+
+      expect(results.map((result) => [result.token, result.status, result.balance])).toEqual([
+        [unreadable, 'failed', null],
+        [WBNB_ADDRESS, 'skipped', 0n],
+        [MOCK_TOKEN, 'sold', 50_000n * ONE],
+      ]);
+      expect(chain.transactions.map((tx) => tx.name)).toEqual(['approve', 'sellToken']);
+      expect(chain.transactions[1].args[2]).toBe(applySlippage(quote.bnbCost, 5));
+      expect(results[2].result!.tags).toEqual(['liquidate']);
+      expect(chain.tokenBalance).toBe(0n);
+    });
+  });
+
+  it('compares each token\'s allowance with its own threshold', async () => {
+    await withMockTrader(async (trader, chain) => {
+      chain.allowance = 40_000n * ONE;
+
+      const results = await trader.batchApprove([MOCK_TOKEN, MOCK_TOKEN], [30_000n * ONE, 50_000n * ONE]);
+
+      expect(results.map((result) => result.status)).toEqual(['sufficient', 'approved']);
+      expect(chain.transactions.map((tx) => tx.name)).toEqual(['approve']);
+      await expect(trader.batchApprove([MOCK_TOKEN], [ONE, ONE])).rejects.toThrow(ValidationError);
+    });
+  });
+
+  it('reports a token whose approval fails without selling it', async () => {
+    await withMockTrader(async (trader, chain) => {
+      chain.tokenBalance = 50_000n * ONE;
+      chain.revertNext = 'approve rejected';
+
+      const [result] = await trader.liquidateAll([MOCK_TOKEN], 5);
+
+      expect(result).toMatchObject({ token: MOCK_TOKEN, status: 'failed', balance: 50_000n * ONE });
+      expect(result.error).toMatch(/^Approval failed: /);
+      expect(chain.transactions).toEqual([]);
+      expect(chain.tokenBalance).toBe(50_000n * ONE);
+    });
+  });
+
+  it('refuses to create a token without valid launch metadata', async () => {
+    await withMockTrader(async (trader, chain) => {
+      const args = '0x1234';
+
+      await expect(trader.createToken({ args } as CreateTokenParams)).rejects.toThrow(ValidationError);
+      await expect(trader.createToken({ args, launch: memeTokenTemplate('Moon Dog', 'MDOG') })).rejects.toThrow(
+        TokenLaunchValidationError
+      );
+      expect(chain.transactions).toEqual([]);
+    });
+  });
+
+  it('gives concurrent blind buys their own nonces and resyncs after a failed send', async () => {
+    await withMockTrader(async (trader, chain) => {
+      await trader.syncNonce();
+      chain.holdTransactions = true;
+      const gas = { gasPrice: 10n ** 9n, gasLimit: 300_000n };
+
+      const blinds = await Promise.all([
+        trader.buyBlind(MOCK_TOKEN, ONE / 10n, 0n, gas),
+        trader.buyBlind(MOCK_TOKEN, ONE / 10n, 0n, gas),
+      ]);
+      expect(blinds.map((blind) => blind.nonce)).toEqual([0, 1]);
+      expect(chain.mempool.size).toBe(2);
+
+      chain.revertNext = 'rejected';
+      await expect(trader.buyBlind(MOCK_TOKEN, ONE / 10n, 0n, gas)).rejects.toThrow('rejected');
+      expect((await trader.buyBlind(MOCK_TOKEN, ONE / 10n, 0n, gas)).nonce).toBe(2);
+      chain.release();
+    });
+  });
+
+  it('reserves a sub-account spend so concurrent buys cannot overspend it', async () => {
+    const ledger = new SubAccountLedger(ONE);
+    ledger.allocate('sniper', ONE / 10n + ONE / 20n);
+    await withMockTrader(async (trader, chain) => {
+      await trader.syncNonce();
+      chain.holdTransactions = true;
+      const gas = { gasPrice: 10n ** 9n, gasLimit: 300_000n };
+
+      const [first, second] = await Promise.allSettled([
+        trader.buyBlind(MOCK_TOKEN, ONE / 10n, 0n, gas, undefined, { subAccount: 'sniper' }),
+        trader.buyBlind(MOCK_TOKEN, ONE / 10n, 0n, gas, undefined, { subAccount: 'sniper' }),
+      ]);
+      expect(first.status).toBe('fulfilled');
+      expect(second.status === 'rejected' && second.reason).toBeInstanceOf(SubAccountBalanceError);
+      expect(ledger.getBalance('sniper')!.reserved).toBe(ONE / 10n + 300_000n * 10n ** 9n);
+
+      chain.release();
+      if (first.status === 'fulfilled') {
+        await first.value.wait();
+      }
+      expect(ledger.getBalance('sniper')!.reserved).toBe(0n);
+    }, { config: { subAccounts: ledger } });
+  });
+
+  it('refuses to require official tokens when no official code hash is known', () => {
+    const config = { rpcUrl: 'http://127.0.0.1:8545', wssUrl: 'ws://127.0.0.1:8546', privateKey: MOCK_PRIVATE_KEY, requireOfficialToken: true };
+
+    expect(() => new FourTrading(config)).toThrow(ValidationError);
+  });
+
+  it('refuses a secondary quoter without an endpoint of its own', () => {
+    const config = { rpcUrl: 'http://127.0.0.1:8545', wssUrl: 'ws://127.0.0.1:8546', privateKey: MOCK_PRIVATE_KEY };
+
+    expect(() => new FourTrading({ ...config, secondaryQuoter: {} })).toThrow(ValidationError);
+    expect(() => new FourTrading({ ...config, secondaryQuoter: { rpcUrl: config.rpcUrl } })).toThrow(ValidationError);
+  });
+
+  it('refuses a sell margin that is not a non-negative number of basis points', () => {
+    const config = { rpcUrl: 'http://127.0.0.1:8545', wssUrl: 'ws://127.0.0.1:8546', privateKey: MOCK_PRIVATE_KEY };
+
+    expect(() => new FourTrading({ ...config, minSellProceedsMarginBps: -100 })).toThrow(ValidationError);
+    expect(() => new FourTrading({ ...config, minSellProceedsMarginBps: NaN })).toThrow(ValidationError);
+  });
+
+  it('estimates an economical sell against the token\'s own manager', async () => {
+    await withMockTrader(async (trader, chain) => {
+      const estimates: any[] = [];
+      const handle = chain.handle.bind(chain);
+      chain.handle = (method, params) => {
+        if (method === 'eth_estimateGas') {
+          estimates.push(params[0]);
+        }
+        return handle(method, params);
+      };
+      jest.spyOn(trader, 'detectVersion').mockResolvedValue({ version: 1, manager: TOKEN_MANAGER_V1_ADDRESS });
+      chain.tokenBalance = ONE;
+
+      await expect(trader.sellToken({ tokenAddress: MOCK_TOKEN, amount: ONE })).rejects.toThrow(UneconomicalTradeError);
+      expect(estimates.map((tx) => [tx.to.toLowerCase(), tx.data])).toEqual([
+        [TOKEN_MANAGER_V1_ADDRESS.toLowerCase(), encodeVersionedSell(1, MOCK_TOKEN, ONE, 0n)],
+      ]);
+      expect(chain.transactions).toEqual([]);
+    }, { config: { minSellProceedsMarginBps: 10_000_000 } });
+  });
+
+  it('reads and sends through the raw call path', async () => {
+    await withMockTrader(async (trader, chain) => {
+      const wallet = trader.getWalletAddress();
+      chain.tokenBalance = 7n * ONE;
+      expect(await trader.rawCall('balanceOf(address) view returns (uint256)', [wallet], { to: MOCK_TOKEN })).toBe(7n * ONE);
+      const template = await trader.rawCall('_templates(uint256) view returns (address quote, uint256, uint256 maxRaising)', [0n]);
+      expect(template.maxRaising).toBe(24n * ONE);
+
+      const result = await trader.rawSend('buyTokenAMAP(address,address,uint256,uint256)', [MOCK_TOKEN, wallet, ONE / 10n, 0n], {
+        value: ONE / 10n,
+        tags: ['raw'],
+      });
+      expect(result.success).toBe(true);
+      expect(result.tags).toEqual(['raw']);
+      expect(chain.transactions.map((tx) => [tx.name, tx.value])).toEqual([['buyTokenAMAP', ONE / 10n]]);
+      expect(trader.getPositionTracker().getPosition(MOCK_TOKEN)!.amount).toBe(chain.tokenBalance - 7n * ONE);
+      await expect(trader.rawSend('buyTokenAMAP(address,address,uint256,uint256)', [MOCK_TOKEN])).rejects.toMatchObject({ code: 'VALIDATION_ERROR' });
+    });
+  });
+
+  it('passes trader settings through', async () => {
+    await withMockTrader(async (trader) => {
+      await expect(trader.buyToken({ tokenAddress: MOCK_TOKEN, fundsInBNB: ONE })).rejects.toMatchObject({ code: 'BNB_RESERVE_VIOLATED' });
+    }, { bnbBalance: 2n * ONE, config: { minBnbReserve: (3n * ONE) / 2n } });
+  });
+});
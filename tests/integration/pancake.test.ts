@@ -0,0 +1,88 @@
+import { ethers, WebSocketProvider } from 'ethers';
+import { getPancakeFactoryInterface, getPancakeRouterInterface } from '../../src/abi';
+import { WBNB_ADDRESS } from '../../src/constants';
+import { ValidationError } from '../../src/errors';
+import { FourTrading, FourTradingConfig } from '../../src/fourTrading';
+import { MOCK_PRIVATE_KEY, MOCK_TOKEN, MockChain, MockJsonRpcProvider, MockWebSocket } from '../../src/testing';
+
+const router = getPancakeRouterInterface();
+const factory = getPancakeFactoryInterface();
+const FORK_ROUTER = '0x3333333333333333333333333333333333333333';
+const FORK_FACTORY = '0x4444444444444444444444444444444444444444';
+const FORK_WBNB = '0x5555555555555555555555555555555555555555';
+const PAIR = '0x7777777777777777777777777777777777777777';
+
+/**
+ * Mock chain with a PancakeSwap fork whose router wraps BNB at its own address
+ */
+class ForkChain extends MockChain {
+  wethReads = 0;
+  paths: string[][] = [];
+  pairQueries: string[][] = [];
+
+  handle(method: string, params: any[]): unknown {
+    if (method === 'eth_call') {
+      const { to, data } = params[0];
+      if (to.toLowerCase() === FORK_ROUTER.toLowerCase()) {
+        const call = router.parseTransaction({ data })!;
+        if (call.name === 'WETH') {
+          this.wethReads += 1;
+          return router.encodeFunctionResult('WETH', [FORK_WBNB]);
+        }
+        this.paths.push([...call.args[1]]);
+        return router.encodeFunctionResult('getAmountsOut', [[call.args[0], call.args[0] * 2n]]);
+      }
+      if (to.toLowerCase() === FORK_FACTORY.toLowerCase()) {
+        this.pairQueries.push([...factory.decodeFunctionData('getPair', data)]);
+        return factory.encodeFunctionResult('getPair', [PAIR]);
+      }
+    }
+    return super.handle(method, params);
+  }
+}
+
+async function withTrader(config: Partial<FourTradingConfig>, run: (trader: FourTrading, chain: ForkChain) => Promise<void>): Promise<void> {
+  const chain = new ForkChain();
+  const provider = new MockJsonRpcProvider(chain);
+  const eventProvider = new WebSocketProvider(() => new MockWebSocket(chain) as any, undefined, { cacheTimeout: -1 });
+  const trader = new FourTrading({ rpcUrl: '', wssUrl: '', privateKey: MOCK_PRIVATE_KEY, ...config, provider, eventProvider });
+  try {
+    await run(trader, chain);
+  } finally {
+    trader.removeAllListeners();
+    provider.destroy();
+    await eventProvider.destroy();
+  }
+}
+
+describe('PancakeSwap fallback', () => {
+  it('quotes and finds the pair against the configured router\'s WBNB', async () => {
+    await withTrader({ pancakeRouter: FORK_ROUTER, pancakeFactory: FORK_FACTORY }, async (trader, chain) => {
+      expect(await trader.quotePancake(MOCK_TOKEN, 100n, 'buy')).toBe(200n);
+      expect(await trader.quotePancake(MOCK_TOKEN, 100n, 'sell')).toBe(200n);
+      expect(await trader.getPancakePair(MOCK_TOKEN)).toBe(ethers.getAddress(PAIR));
+
+      expect(await trader.getWbnbAddress()).toBe(ethers.getAddress(FORK_WBNB));
+      expect(chain.paths).toEqual([
+        [ethers.getAddress(FORK_WBNB), ethers.getAddress(MOCK_TOKEN)],
+        [ethers.getAddress(MOCK_TOKEN), ethers.getAddress(FORK_WBNB)],
+      ]);
+      expect(chain.pairQueries).toEqual([[ethers.getAddress(MOCK_TOKEN), ethers.getAddress(FORK_WBNB)]]);
+      expect(chain.wethReads).toBe(1);
+    });
+  });
+
+  it('reads the canonical WBNB from the default router', async () => {
+    await withTrader({}, async (trader) => {
+      expect(await trader.getWbnbAddress()).toBe(WBNB_ADDRESS);
+    });
+  });
+
+  it('refuses the DEX features without a router', async () => {
+    await withTrader({ pancakeRouter: ethers.ZeroAddress, pancakeFactory: FORK_FACTORY }, async (trader, chain) => {
+      await expect(trader.quotePancake(MOCK_TOKEN, 100n, 'buy')).rejects.toThrow(ValidationError);
+      await expect(trader.getPancakePair(MOCK_TOKEN)).rejects.toThrow(ValidationError);
+      expect(chain.pairQueries).toEqual([]);
+    });
+  });
+});
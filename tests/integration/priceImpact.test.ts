@@ -0,0 +1,75 @@
+import { PriceImpactTooHighError } from '../../src/errors';
+import { MOCK_TOKEN, withMockTrader } from '../../src/testing';
+import { TokenInfo } from '../../src/types';
+
+const ONE = 10n ** 18n;
+
+// 1,000,000 tokens per BNB, falling with the spend: 0.1 BNB moves the price 9.09%, 0.5 BNB 33.33%
+const thinCurve = async (_info: TokenInfo, funds: bigint) => (funds * 1_000_000n * ONE) / (ONE + funds);
+
+describe('maxPriceImpactBps', () => {
+  beforeAll(() => {
+    jest.spyOn(console, 'log').mockImplementation(() => {});
+    jest.spyOn(console, 'error').mockImplementation(() => {});
+  });
+
+  afterAll(() => {
+    jest.restoreAllMocks();
+  });
+
+  it('refuses buys above the cap before sending', async () => {
+    await withMockTrader(async (trader, chain) => {
+      jest.spyOn(trader, 'calcBuyAmount').mockImplementation(thinCurve);
+
+      await expect(trader.buyToken({ tokenAddress: MOCK_TOKEN, fundsInBNB: ONE / 2n })).rejects.toMatchObject({
+        code: 'PRICE_IMPACT_TOO_HIGH',
+        side: 'buy',
+        impactBps: 3333,
+        maxImpactBps: 1000,
+      });
+      expect(chain.transactions).toEqual([]);
+
+      await trader.buyToken({ tokenAddress: MOCK_TOKEN, fundsInBNB: ONE / 10n });
+      expect(chain.transactions.map((tx) => tx.name)).toEqual(['buyTokenAMAP']);
+    }, { config: { maxPriceImpactBps: 1000 } });
+  });
+
+  it('measures exact-amount buys on their quoted cost', async () => {
+    await withMockTrader(async (trader, chain) => {
+      jest.spyOn(trader, 'calcBuyAmount').mockImplementation(thinCurve);
+
+      await expect(trader.buyTokenExact(MOCK_TOKEN, 500_000n * ONE, ONE)).rejects.toBeInstanceOf(PriceImpactTooHighError);
+      expect(chain.transactions).toEqual([]);
+
+      await trader.buyTokenExact(MOCK_TOKEN, 10_000n * ONE, ONE);
+      expect(chain.transactions.map((tx) => tx.name)).toEqual(['buyToken']);
+    }, { config: { maxPriceImpactBps: 1000 } });
+  });
+
+  it('refuses sells above the cap and reports the impact in previews', async () => {
+    await withMockTrader(async (trader, chain) => {
+      jest.spyOn(trader, 'calcSellCost').mockImplementation(async (_info, amount) => (amount * ONE) / (1_000_000n * ONE + amount));
+
+      await expect(trader.sellToken({ tokenAddress: MOCK_TOKEN, amount: 500_000n * ONE })).rejects.toBeInstanceOf(PriceImpactTooHighError);
+      const preview = await trader.previewSell({ tokenAddress: MOCK_TOKEN, amount: 10_000n * ONE });
+
+      expect(preview.values.get('priceImpactBps')).toBe(99);
+      expect(preview.checks.find((check) => check.name === 'priceImpact')!.passed).toBe(true);
+      expect(chain.transactions).toEqual([]);
+    }, { config: { maxPriceImpactBps: 1000 } });
+  });
+
+  it('leaves the flat mock curve alone and is off without a cap', async () => {
+    await withMockTrader(async (trader) => {
+      const preview = await trader.previewBuy({ tokenAddress: MOCK_TOKEN, fundsInBNB: ONE });
+      expect(preview.values.get('priceImpactBps')).toBe(0);
+    }, { config: { maxPriceImpactBps: 0 } });
+
+    await withMockTrader(async (trader) => {
+      jest.spyOn(trader, 'calcBuyAmount').mockImplementation(thinCurve);
+      const preview = await trader.previewBuy({ tokenAddress: MOCK_TOKEN, fundsInBNB: ONE / 2n });
+
+      expect(preview.values.has('priceImpactBps')).toBe(false);
+    });
+  });
+});
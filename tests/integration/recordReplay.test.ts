@@ -0,0 +1,262 @@
+import { ethers, JsonRpcProvider, Transaction, WebSocketProvider } from 'ethers';
+import {
+  RpcRecorder,
+  RpcReplay,
+  RecordingJsonRpcProvider,
+  RecordingWebSocket,
+  ReplayJsonRpcProvider,
+  createReplayWebSocketProvider,
+} from '../../src/recordReplay';
+import { FourTrading } from '../../src/fourTrading';
+import { AmountOutOfRangeError, RpcDivergenceError, ValidationError } from '../../src/errors';
+import { TokenCreateEvent } from '../../src/types';
+import { CONTRACT, FakeChain, FakeRpc, FakeSocket, GAS_PRICE, MIN_TRADING_FEE, ONE, PRIVATE_KEY, TOKEN, four } from './fakeChain';
+
+/**
+ * Snipe the next launch with 0.1 BNB, then sell the whole bag
+ */
+async function snipeAndExit(provider: JsonRpcProvider, eventProvider: WebSocketProvider) {
+  const trader = new FourTrading({ rpcUrl: '', wssUrl: '', privateKey: PRIVATE_KEY, provider, eventProvider });
+  try {
+    const created = await new Promise<TokenCreateEvent>((resolve) => trader.onTokenCreate(resolve));
+    const buy = await trader.buyToken({ tokenAddress: created.token, fundsInBNB: ONE / 10n });
+    const sell = await trader.sellAll(created.token);
+    return { token: created.token, buy: buy.txHash, sell: sell.txHash, history: trader.exportHistoryCsv() };
+  } finally {
+    trader.removeAllListeners();
+    provider.destroy();
+    await eventProvider.destroy();
+  }
+}
+
+describe('record/replay of a snipe-and-exit session', () => {
+  let journal: ReturnType<RpcRecorder['getEntries']>;
+  let recorded: Awaited<ReturnType<typeof snipeAndExit>>;
+
+  beforeAll(async () => {
+    jest.spyOn(console, 'log').mockImplementation(() => {});
+    const chain = new FakeChain();
+    const recorder = new RpcRecorder();
+    recorded = await snipeAndExit(
+      new RecordingJsonRpcProvider(new FakeRpc(chain), recorder),
+      new WebSocketProvider(() => new RecordingWebSocket(new FakeSocket(chain), recorder), undefined, { cacheTimeout: -1 })
+    );
+    journal = recorder.getEntries();
+  });
+
+  afterAll(() => {
+    jest.restoreAllMocks();
+  });
+
+  it('records the launch notification and both trades', () => {
+    expect(recorded.token.toLowerCase()).toBe(TOKEN.toLowerCase());
+    expect(journal.filter((entry) => entry.method === 'eth_sendRawTransaction')).toHaveLength(2);
+    expect(journal.some((entry) => entry.method === 'eth_subscription')).toBe(true);
+  });
+
+  it('reproduces the session offline from the journal', async () => {
+    const replay = new RpcReplay(journal);
+    const replayed = await snipeAndExit(new ReplayJsonRpcProvider(replay), createReplayWebSocketProvider(replay));
+
+    expect(replayed).toEqual(recorded);
+    expect(replay.divergences).toEqual([]);
+    expect(replay.remaining().filter((entry) => entry.method === 'eth_sendRawTransaction')).toEqual([]);
+  });
+
+  it('fails with a readable diff when the session deviates', async () => {
+    const replay = new RpcReplay(journal);
+    const provider = new ReplayJsonRpcProvider(replay);
+    const eventProvider = createReplayWebSocketProvider(replay);
+    const trader = new FourTrading({ rpcUrl: '', wssUrl: '', privateKey: PRIVATE_KEY, provider, eventProvider });
+
+    try {
+      const created = await new Promise<TokenCreateEvent>((resolve) => trader.onTokenCreate(resolve));
+      // Twice the recorded spend: the fee quote is the first request that differs
+      const error = await trader.buyToken({ tokenAddress: created.token, fundsInBNB: ONE / 5n }).catch((e) => e);
+
+      expect(error).toBeInstanceOf(RpcDivergenceError);
+      expect(error.method).toBe('eth_call');
+      expect(error.message).toContain('first difference at params[0].data');
+    } finally {
+      trader.removeAllListeners();
+      provider.destroy();
+      await eventProvider.destroy();
+    }
+  });
+});
+
+/**
+ * Fake chain behind an RPC gateway that disables eth_estimateGas
+ */
+class NoEstimateChain extends FakeChain {
+  estimates = 0;
+  gasLimits: bigint[] = [];
+
+  handle(method: string, params: any[]): unknown {
+    if (method === 'eth_estimateGas') {
+      this.estimates += 1;
+      throw new Error('the method eth_estimateGas does not exist/is not available');
+    }
+    if (method === 'eth_sendRawTransaction') {
+      this.gasLimits.push(Transaction.from(params[0]).gasLimit);
+    }
+    return super.handle(method, params);
+  }
+}
+
+describe('a node without eth_estimateGas', () => {
+  beforeAll(() => {
+    jest.spyOn(console, 'log').mockImplementation(() => {});
+    jest.spyOn(console, 'warn').mockImplementation(() => {});
+  });
+
+  afterAll(() => {
+    jest.restoreAllMocks();
+  });
+
+  it('trades with the fallback gas limits and stops asking for estimates', async () => {
+    const chain = new NoEstimateChain();
+    const recorded = await snipeAndExit(
+      new FakeRpc(chain),
+      new WebSocketProvider(() => new FakeSocket(chain) as any, undefined, { cacheTimeout: -1 })
+    );
+
+    expect(recorded.token.toLowerCase()).toBe(TOKEN.toLowerCase());
+    expect(chain.gasLimits).toEqual([300000n, 250000n]);
+    expect(chain.estimates).toBe(1);
+  });
+});
+
+describe('an external signer', () => {
+  const hsm = new ethers.Wallet('0x8b3a350cf5c34c9194ca85829a2df0ec3153be0318b5e2d3348e872092edffba');
+
+  beforeAll(() => {
+    jest.spyOn(console, 'log').mockImplementation(() => {});
+    jest.spyOn(console, 'error').mockImplementation(() => {});
+  });
+
+  afterAll(() => {
+    jest.restoreAllMocks();
+  });
+
+  async function withTrader(chain: FakeChain, run: (trader: FourTrading) => Promise<void>): Promise<void> {
+    const provider = new FakeRpc(chain);
+    const eventProvider = new WebSocketProvider(() => new FakeSocket(chain) as any, undefined, { cacheTimeout: -1 });
+    const trader = new FourTrading({ rpcUrl: '', wssUrl: '', privateKey: PRIVATE_KEY, provider, eventProvider });
+    try {
+      await run(trader);
+    } finally {
+      trader.removeAllListeners();
+      provider.destroy();
+      await eventProvider.destroy();
+    }
+  }
+
+  it('signs the built transaction asynchronously and the buy settles from its account', async () => {
+    const chain = new FakeChain();
+    const unsigned: Transaction[] = [];
+
+    await withTrader(chain, async (trader) => {
+      const result = await trader.buyWithExternalSigner({ tokenAddress: TOKEN, fundsInBNB: ONE / 10n }, {
+        address: hsm.address,
+        sign: async (tx) => {
+          unsigned.push(tx);
+          await new Promise((resolve) => setTimeout(resolve, 10)); // A remote round trip
+          return hsm.signingKey.sign(tx.unsignedHash);
+        },
+      });
+
+      expect(result.success).toBe(true);
+      expect((chain.receipts.get(result.txHash) as any).from).toBe(hsm.address);
+    });
+
+    expect(unsigned).toHaveLength(1);
+    expect(unsigned[0]).toMatchObject({ to: CONTRACT, value: ONE / 10n, chainId: 56n, gasPrice: GAS_PRICE, signature: null });
+    expect(four.decodeFunctionData('buyTokenAMAP(address,address,uint256,uint256)', unsigned[0].data)[1]).toBe(hsm.address);
+  });
+
+  it('refuses to broadcast a signature from another key', async () => {
+    const chain = new FakeChain();
+    const other = ethers.Wallet.createRandom();
+
+    await withTrader(chain, async (trader) => {
+      const error = await trader.buyWithExternalSigner({ tokenAddress: TOKEN, fundsInBNB: ONE / 10n }, {
+        address: hsm.address,
+        sign: async (tx) => other.signingKey.sign(tx.unsignedHash),
+      }).catch((e) => e);
+
+      expect(error.message).toContain(`expected ${hsm.address}`);
+    });
+
+    expect(chain.receipts.size).toBe(0);
+  });
+});
+
+describe('buy limits', () => {
+  beforeAll(() => {
+    jest.spyOn(console, 'error').mockImplementation(() => {});
+  });
+
+  afterAll(() => {
+    jest.restoreAllMocks();
+  });
+
+  it('rejects a spend the minimum trading fee would swallow before sending anything', async () => {
+    const chain = new FakeChain();
+    const provider = new FakeRpc(chain);
+    const eventProvider = new WebSocketProvider(() => new FakeSocket(chain) as any, undefined, { cacheTimeout: -1 });
+    const trader = new FourTrading({ rpcUrl: '', wssUrl: '', privateKey: PRIVATE_KEY, provider, eventProvider });
+
+    try {
+      expect(await trader.buyLimits(TOKEN)).toEqual({ minFunds: MIN_TRADING_FEE + 1n, maxFunds: 2n ** 256n - 1n });
+
+      const error = await trader.buyToken({ tokenAddress: TOKEN, fundsInBNB: MIN_TRADING_FEE }).catch((e) => e);
+
+      expect(error).toBeInstanceOf(AmountOutOfRangeError);
+      expect(error).toMatchObject({ amount: MIN_TRADING_FEE, min: MIN_TRADING_FEE + 1n });
+      expect(chain.receipts.size).toBe(0);
+    } finally {
+      trader.removeAllListeners();
+      provider.destroy();
+      await eventProvider.destroy();
+    }
+  });
+});
+
+describe('token decimals overrides', () => {
+  beforeAll(() => {
+    jest.spyOn(console, 'log').mockImplementation(() => {});
+  });
+
+  afterAll(() => {
+    jest.restoreAllMocks();
+  });
+
+  it('formats balances with the configured, per-call and default decimals', async () => {
+    const chain = new FakeChain();
+    const provider = new FakeRpc(chain);
+    const eventProvider = new WebSocketProvider(() => new FakeSocket(chain) as any, undefined, { cacheTimeout: -1 });
+    const trader = new FourTrading({
+      rpcUrl: '', wssUrl: '', privateKey: PRIVATE_KEY, provider, eventProvider,
+      tokenDecimals: { [TOKEN.toLowerCase()]: 9 },
+    });
+
+    try {
+      const buy = await trader.buyToken({ tokenAddress: TOKEN, fundsInBNB: ONE / 10n });
+
+      // 99,000 tokens of 18 decimals, read as 9
+      expect(await trader.getTokenBalance(TOKEN)).toBe('99000000000000.0');
+      expect(await trader.afterTrade(buy).getTokenBalance(TOKEN)).toBe('99000000000000.0');
+      expect(await trader.getTokenBalance(TOKEN, 18)).toBe('99000.0');
+
+      trader.setTokenDecimals(TOKEN, undefined);
+      expect(trader.getTokenDecimals(TOKEN)).toBe(18);
+      expect(await trader.getTokenBalance(TOKEN)).toBe('99000.0');
+      expect(() => trader.setTokenDecimals(TOKEN, 19)).toThrow(ValidationError);
+    } finally {
+      trader.removeAllListeners();
+      provider.destroy();
+      await eventProvider.destroy();
+    }
+  });
+});
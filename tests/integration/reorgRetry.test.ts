@@ -0,0 +1,72 @@
+import { TransactionDroppedError } from '../../src/errors';
+import { MOCK_TOKEN, MockChain, withMockTrader } from '../../src/testing';
+
+const ONE = 10n ** 18n;
+
+// Wait until the chain holds a sent transaction, then evict it as a reorg would
+async function dropNext(chain: MockChain): Promise<string> {
+  for (;;) {
+    const [held] = chain.mempool.values();
+    if (held) {
+      chain.mempool.clear();
+      return held.hash!;
+    }
+    await new Promise((resolve) => setTimeout(resolve, 10));
+  }
+}
+
+describe('retryOnReorg', () => {
+  beforeAll(() => {
+    jest.spyOn(console, 'log').mockImplementation(() => {});
+    jest.spyOn(console, 'error').mockImplementation(() => {});
+  });
+
+  afterAll(() => {
+    jest.restoreAllMocks();
+  });
+
+  it('re-quotes and resubmits a buy dropped before confirming', async () => {
+    await withMockTrader(async (trader, chain) => {
+      chain.holdTransactions = true;
+      const buying = trader.buyToken({ tokenAddress: MOCK_TOKEN, fundsInBNB: ONE / 10n });
+
+      const dropped = await dropNext(chain);
+      chain.holdTransactions = false;
+      const result = await buying;
+
+      expect(result.reorgRetryOf).toBe(dropped);
+      expect(chain.transactions.map((tx) => [tx.name, tx.hash])).toEqual([['buyTokenAMAP', result.txHash]]);
+      expect(trader.getTradeHistory().getTrades().map((trade) => trade.txHash)).toEqual([result.txHash]);
+    }, { config: { retryOnReorg: true } });
+  });
+
+  it('resubmits a dropped sell the same way', async () => {
+    await withMockTrader(async (trader, chain) => {
+      await trader.buyToken({ tokenAddress: MOCK_TOKEN, fundsInBNB: ONE / 10n });
+      await trader.approveToken(MOCK_TOKEN);
+      chain.holdTransactions = true;
+      const selling = trader.sellAll(MOCK_TOKEN);
+
+      const dropped = await dropNext(chain);
+      chain.holdTransactions = false;
+
+      expect((await selling).reorgRetryOf).toBe(dropped);
+      expect(chain.tokenBalance).toBe(0n);
+    }, { config: { retryOnReorg: true } });
+  });
+
+  it('retries only once', async () => {
+    await withMockTrader(async (trader, chain) => {
+      chain.holdTransactions = true;
+      const buying = trader.buyToken({ tokenAddress: MOCK_TOKEN, fundsInBNB: ONE / 10n });
+      const failed = expect(buying).rejects.toBeInstanceOf(TransactionDroppedError);
+
+      await dropNext(chain);
+      const second = await dropNext(chain);
+
+      await failed;
+      await expect(buying).rejects.toMatchObject({ txHash: second });
+      expect(chain.transactions).toEqual([]);
+    }, { config: { retryOnReorg: true } });
+  });
+});
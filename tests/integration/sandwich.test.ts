@@ -0,0 +1,99 @@
+import { ethers, WebSocketProvider } from 'ethers';
+import { getFourTradingInterface } from '../../src/abi';
+import { FourTrading } from '../../src/fourTrading';
+import { MOCK_PRIVATE_KEY, MOCK_TOKEN, MockChain, MockJsonRpcProvider, MockWebSocket } from '../../src/testing';
+
+const ONE = 10n ** 18n;
+const four = getFourTradingInterface();
+const ATTACKER = '0xa77ac0000000000000000000000000000000000a';
+const VICTIM_INDEX = 5;
+
+/**
+ * Mock chain that places the wallet's trades mid-block and serves other trades in that block
+ * from getLogs only, as legs sent through a bot contract would appear
+ */
+class BlockChain extends MockChain {
+  logs: any[] = [];
+
+  // A trade at `index` in the latest block; the bot, not four.meme, is the transaction's `to`
+  trade(name: 'TokenPurchase' | 'TokenSale', index: number, amount: bigint, cost: bigint): string {
+    const hash = ethers.id(`${name}:${index}`);
+    const event = four.encodeEventLog(name, [MOCK_TOKEN, ATTACKER, 0n, amount, cost, cost / 100n, 0n, 0n]);
+    this.logs.push({
+      ...this.tokenCreateLog(),
+      topics: event.topics,
+      data: event.data,
+      transactionHash: hash,
+      transactionIndex: ethers.toQuantity(index),
+    });
+    return hash;
+  }
+
+  handle(method: string, params: any[]): unknown {
+    if (method === 'eth_getTransactionReceipt') {
+      const receipt = super.handle(method, params) as any;
+      return receipt && {
+        ...receipt,
+        transactionIndex: ethers.toQuantity(VICTIM_INDEX),
+        logs: receipt.logs.map((log: any) => ({ ...log, transactionIndex: ethers.toQuantity(VICTIM_INDEX) })),
+      };
+    }
+    if (method === 'eth_getLogs') {
+      const { address, fromBlock, toBlock } = params[0];
+      const own = [...this.receipts.values()].flatMap((receipt: any) => receipt.logs);
+      return [...own, ...this.logs].filter((log) =>
+        log.address.toLowerCase() === address.toLowerCase() && Number(log.blockNumber) >= Number(fromBlock) && Number(log.blockNumber) <= Number(toBlock)
+      );
+    }
+    return super.handle(method, params);
+  }
+}
+
+async function withTrader(run: (trader: FourTrading, chain: BlockChain) => Promise<void>): Promise<void> {
+  const chain = new BlockChain();
+  const provider = new MockJsonRpcProvider(chain);
+  const eventProvider = new WebSocketProvider(() => new MockWebSocket(chain) as any, undefined, { cacheTimeout: -1 });
+  const trader = new FourTrading({ rpcUrl: '', wssUrl: '', privateKey: MOCK_PRIVATE_KEY, provider, eventProvider });
+  try {
+    await run(trader, chain);
+  } finally {
+    trader.removeAllListeners();
+    provider.destroy();
+    await eventProvider.destroy();
+  }
+}
+
+describe('analyzeSandwich', () => {
+  beforeAll(() => {
+    jest.spyOn(console, 'log').mockImplementation(() => {});
+    jest.spyOn(console, 'error').mockImplementation(() => {});
+  });
+
+  afterAll(() => {
+    jest.restoreAllMocks();
+  });
+
+  it('finds legs sent through another contract from the block\'s trade logs', async () => {
+    await withTrader(async (trader, chain) => {
+      const buy = await trader.buyToken({ tokenAddress: MOCK_TOKEN, fundsInBNB: ONE / 10n });
+      const frontRun = chain.trade('TokenPurchase', VICTIM_INDEX - 3, 1000n * ONE, ONE);
+      const backRun = chain.trade('TokenSale', VICTIM_INDEX + 2, 1000n * ONE, 2n * ONE);
+
+      const report = await trader.analyzeSandwich(buy.txHash);
+
+      expect(report).toMatchObject({ sandwiched: true, frontRunTx: frontRun, backRunTx: backRun });
+      expect(report.attacker!.toLowerCase()).toBe(ATTACKER);
+      expect(report.valueExtracted).toBe((2n * ONE - 2n * ONE / 100n) - (ONE + ONE / 100n));
+    });
+  });
+
+  it('does not count a sell that landed before the trade', async () => {
+    await withTrader(async (trader, chain) => {
+      const buy = await trader.buyToken({ tokenAddress: MOCK_TOKEN, fundsInBNB: ONE / 10n });
+      chain.trade('TokenPurchase', VICTIM_INDEX - 3, 1000n * ONE, ONE);
+      chain.trade('TokenSale', VICTIM_INDEX - 1, 1000n * ONE, 2n * ONE);
+
+      expect((await trader.analyzeSandwich(buy.txHash)).sandwiched).toBe(false);
+    });
+  });
+});
@@ -0,0 +1,496 @@
+import {
+  Strategy,
+  StrategyRunner,
+  BacktestDriver,
+  BacktestExecutor,
+  StrategyEvent,
+  TradeEvent,
+  TradeIntent,
+  OwnFill,
+  BlockTick,
+  StrategySignal,
+  ApprovalConfig,
+  ExpiredIntent,
+  TradeExecutor,
+} from '../../src/strategy';
+import { MomentumSignal, MomentumTrigger } from '../../src/momentum';
+import { ApprovalRequest, ChannelApprovalGate, DenyAllGate } from '../../src/approvalGate';
+import { TokenCreateEvent } from '../../src/types';
+
+const ONE = 10n ** 18n;
+const TOKEN = '0x1234567890123456789012345678901234567890';
+const OTHER = '0x9999999999999999999999999999999999999999';
+
+/**
+ * Example strategy: buy every new token, sell once the price doubles or after 60 seconds
+ */
+class BuyOnCreateStrategy implements Strategy {
+  positions: Map<string, { tokens: bigint; entryPrice: bigint; openedAt: number }> = new Map();
+  log: string[] = [];
+  now = 0;
+
+  constructor(private buyAmount: bigint) {}
+
+  onStart(): void {
+    this.log.push('start');
+  }
+
+  onTokenCreated(event: TokenCreateEvent): TradeIntent[] {
+    this.log.push(`created ${event.symbol}`);
+    return [{ side: 'buy', tokenAddress: event.token, fundsInBNB: this.buyAmount }];
+  }
+
+  onOwnFill(fill: OwnFill): void {
+    this.log.push(`fill ${fill.intent.side}`);
+    const key = fill.tokenAddress.toLowerCase();
+    if (fill.intent.side === 'buy') {
+      this.positions.set(key, {
+        tokens: fill.tokenAmount,
+        entryPrice: (fill.bnbAmount * ONE) / fill.tokenAmount,
+        openedAt: this.now,
+      });
+    } else {
+      this.positions.delete(key);
+    }
+  }
+
+  onTradeEvent(trade: TradeEvent): TradeIntent[] {
+    this.log.push(`trade ${trade.event.price / (ONE / 1000n)}`);
+    const position = this.positions.get(trade.event.token.toLowerCase());
+    if (position && trade.event.price >= position.entryPrice * 2n) {
+      return [{ side: 'sell', tokenAddress: trade.event.token, amount: position.tokens }];
+    }
+    return [];
+  }
+
+  onBlock(block: BlockTick): TradeIntent[] {
+    this.now = block.timestamp;
+    const intents: TradeIntent[] = [];
+    for (const [token, position] of this.positions) {
+      if (block.timestamp - position.openedAt >= 60) {
+        intents.push({ side: 'sell', tokenAddress: token, amount: position.tokens });
+      }
+    }
+    return intents;
+  }
+
+  onShutdown(): void {
+    this.log.push('shutdown');
+  }
+}
+
+function created(token: string, symbol: string): StrategyEvent {
+  return {
+    kind: 'tokenCreated',
+    event: {
+      creator: OTHER,
+      token,
+      requestId: 1n,
+      name: symbol,
+      symbol,
+      totalSupply: 1000000000n * ONE,
+      launchTime: 0n,
+      launchFee: 0n,
+    },
+  };
+}
+
+function trade(token: string, price: bigint, blockNumber: number): StrategyEvent {
+  return {
+    kind: 'trade',
+    trade: {
+      side: 'buy',
+      blockNumber,
+      event: {
+        token,
+        account: OTHER,
+        price,
+        amount: ONE,
+        cost: price,
+        fee: 0n,
+        offers: 0n,
+        funds: 0n,
+      },
+    },
+  };
+}
+
+function block(number: number, timestamp: number): StrategyEvent {
+  return { kind: 'block', block: { number, timestamp } };
+}
+
+describe('StrategyRunner (backtest)', () => {
+  const launchPrice = ONE / 1000n; // 0.001 BNB per token
+
+  it('should sell after the price doubles', async () => {
+    const strategy = new BuyOnCreateStrategy(ONE / 10n);
+    const executor = new BacktestExecutor(0n, launchPrice);
+
+    await StrategyRunner.run(strategy, undefined, {
+      driver: new BacktestDriver([
+        block(100, 1000),
+        created(TOKEN, 'MEME'),
+        trade(TOKEN, launchPrice, 101),
+        trade(TOKEN, launchPrice * 2n, 102),
+        trade(TOKEN, launchPrice * 3n, 103),
+      ]),
+      executor,
+    });
+
+    expect(strategy.log).toEqual([
+      'start',
+      'created MEME',
+      'fill buy',
+      'trade 1',
+      'trade 2',
+      'fill sell',
+      'trade 3',
+      'shutdown',
+    ]);
+    expect(strategy.positions.size).toBe(0);
+  });
+
+  it('should sell after 60 seconds without a price move', async () => {
+    const strategy = new BuyOnCreateStrategy(ONE / 10n);
+
+    await StrategyRunner.run(strategy, undefined, {
+      driver: new BacktestDriver([
+        block(100, 1000),
+        created(TOKEN, 'MEME'),
+        block(101, 1030),
+        block(102, 1059),
+        block(103, 1060),
+        block(104, 1063),
+      ]),
+      executor: new BacktestExecutor(0n, launchPrice),
+    });
+
+    expect(strategy.log.filter((entry) => entry.startsWith('fill'))).toEqual(['fill buy', 'fill sell']);
+    expect(strategy.positions.size).toBe(0);
+  });
+
+  it('should deliver own fills before later market events', async () => {
+    const order: string[] = [];
+    const strategy: Strategy = {
+      onTokenCreated: (event) => {
+        order.push('created');
+        return [{ side: 'buy', tokenAddress: event.token, fundsInBNB: ONE }];
+      },
+      onOwnFill: () => {
+        order.push('own fill');
+      },
+      onTradeEvent: () => {
+        order.push('market trade');
+      },
+    };
+
+    await StrategyRunner.run(strategy, undefined, {
+      driver: new BacktestDriver([created(TOKEN, 'MEME'), trade(TOKEN, launchPrice, 2)]),
+      executor: new BacktestExecutor(0n, launchPrice),
+    });
+
+    expect(order).toEqual(['created', 'own fill', 'market trade']);
+  });
+
+  it('should carry intent tags into fills and auto-tag the source hook', async () => {
+    const fills: OwnFill[] = [];
+    const strategy: Strategy = {
+      onTokenCreated: (event) => [
+        { side: 'buy', tokenAddress: event.token, fundsInBNB: ONE, tags: ['sniper'], correlationId: 'run-1' },
+      ],
+      onOwnFill: (fill) => {
+        fills.push(fill);
+      },
+    };
+
+    await StrategyRunner.run(strategy, undefined, {
+      driver: new BacktestDriver([created(TOKEN, 'MEME')]),
+      executor: new BacktestExecutor(0n, launchPrice),
+    });
+
+    expect(fills).toHaveLength(1);
+    expect(fills[0].intent.tags).toEqual(['sniper', 'source:tokenCreated']);
+    expect(fills[0].intent.correlationId).toBe('run-1');
+  });
+
+  it('should apply risk limits to intents', async () => {
+    const strategy = new BuyOnCreateStrategy(ONE);
+
+    const runner = await StrategyRunner.run(strategy, undefined, {
+      driver: new BacktestDriver([created(TOKEN, 'MEME'), created(OTHER, 'COPY')]),
+      executor: new BacktestExecutor(0n, launchPrice),
+      riskLimits: { maxBnbPerTrade: ONE, maxTotalBnbSpent: ONE + ONE / 2n },
+    });
+
+    expect(strategy.positions.size).toBe(1);
+    expect(runner.getTotalBnbSpent()).toBe(ONE);
+  });
+
+  it('should book intents to the runner sub-account and apply its limits', async () => {
+    const fills: OwnFill[] = [];
+    const strategy: Strategy = {
+      onTokenCreated: (event) => [
+        { side: 'buy', tokenAddress: event.token, fundsInBNB: ONE },
+        { side: 'buy', tokenAddress: event.token, fundsInBNB: ONE, subAccount: 'dca' },
+      ],
+      onOwnFill: (fill) => {
+        fills.push(fill);
+      },
+    };
+
+    const runner = await StrategyRunner.run(strategy, undefined, {
+      driver: new BacktestDriver([created(TOKEN, 'MEME'), created(OTHER, 'COPY')]),
+      executor: new BacktestExecutor(0n, launchPrice),
+      subAccount: 'sniper',
+      riskLimits: { subAccounts: { sniper: { maxTotalBnbSpent: ONE } } },
+    });
+
+    expect(fills.map((fill) => fill.intent.subAccount)).toEqual(['sniper', 'dca', 'dca']);
+    expect(runner.getTotalBnbSpent('sniper')).toBe(ONE);
+    expect(runner.getTotalBnbSpent('dca')).toBe(2n * ONE);
+    expect(runner.getTotalBnbSpent()).toBe(3n * ONE);
+  });
+
+  it('should dispatch signals from signal sources right after their event', async () => {
+    const log: string[] = [];
+    const strategy: Strategy = {
+      onTradeEvent: (trade) => {
+        log.push(`trade ${trade.event.account.slice(-1)}`);
+      },
+      onSignal: (signal: StrategySignal) => {
+        const momentum = signal as MomentumSignal;
+        log.push(`signal ${momentum.newBuyers}`);
+        return [{ side: 'buy', tokenAddress: signal.tokenAddress, fundsInBNB: ONE / 10n }];
+      },
+      onOwnFill: (fill) => {
+        log.push(`fill ${fill.intent.tags!.join(',')}`);
+      },
+    };
+    const buyFrom = (account: string): StrategyEvent => {
+      const event = trade(TOKEN, launchPrice, 101);
+      return event.kind === 'trade' ? { ...event, trade: { ...event.trade, event: { ...event.trade.event, account } } } : event;
+    };
+
+    await StrategyRunner.run(strategy, undefined, {
+      driver: new BacktestDriver([
+        block(100, 1000),
+        created(TOKEN, 'MEME'),
+        buyFrom('0x0000000000000000000000000000000000000001'),
+        buyFrom('0x0000000000000000000000000000000000000002'),
+        buyFrom('0x0000000000000000000000000000000000000003'),
+      ]),
+      executor: new BacktestExecutor(0n, launchPrice),
+      signals: [new MomentumTrigger({ minNewBuyers: 2, maxPriceMultiple: 3, cooldownSeconds: 600 })],
+    });
+
+    expect(log).toEqual(['trade 1', 'trade 2', 'signal 2', 'fill source:signal', 'trade 3']);
+  });
+
+  it('should require a trader when driver or executor is missing', () => {
+    expect(() => new StrategyRunner({}, undefined, {})).toThrow();
+  });
+});
+
+describe('StrategyRunner approval gate', () => {
+  const launchPrice = ONE / 1000n;
+  const silent = { info: jest.fn(), warn: jest.fn(), error: jest.fn() } as any;
+
+  function runBuys(intents: TradeIntent[], approval: ApprovalConfig) {
+    const fills: OwnFill[] = [];
+    const strategy: Strategy = {
+      onTokenCreated: () => intents,
+      onOwnFill: (fill) => {
+        fills.push(fill);
+      },
+    };
+    const runner = new StrategyRunner(strategy, undefined, {
+      driver: new BacktestDriver([created(TOKEN, 'MEME')]),
+      executor: new BacktestExecutor(0n, launchPrice),
+      riskLimits: { maxBnbPerTrade: 5n * ONE },
+      approval,
+      logger: silent,
+    });
+    return { runner, fills, done: runner.run() };
+  }
+
+  it('asks only about buys above the threshold that passed the risk limits', async () => {
+    const asked: ApprovalRequest[] = [];
+    const gate = new ChannelApprovalGate((request) => {
+      asked.push(request);
+      gate.reply(request.id, { approved: request.fundsInBNB < 3n * ONE, approver: 'ops' });
+    });
+    const { runner, fills, done } = runBuys([
+      { side: 'buy', tokenAddress: TOKEN, fundsInBNB: ONE },
+      { side: 'buy', tokenAddress: TOKEN, fundsInBNB: 2n * ONE },
+      { side: 'buy', tokenAddress: TOKEN, fundsInBNB: 4n * ONE },
+      { side: 'buy', tokenAddress: TOKEN, fundsInBNB: 6n * ONE }, // Over maxBnbPerTrade
+      { side: 'sell', tokenAddress: TOKEN, amount: ONE },
+    ], { thresholdBnb: ONE, gate });
+    await done;
+
+    expect(asked.map((request) => request.fundsInBNB)).toEqual([2n * ONE, 4n * ONE]);
+    expect(fills.map((fill) => fill.intent.side === 'buy' ? fill.intent.fundsInBNB : 'sell')).toEqual([ONE, 2n * ONE, 'sell']);
+    expect(runner.getApprovalJournal().map(({ outcome, approver }) => [outcome, approver])).toEqual([
+      ['approved', 'ops'],
+      ['denied', 'ops'],
+    ]);
+  });
+
+  it('waits for a late reply and rejects the trade at the timeout', async () => {
+    const gate = new ChannelApprovalGate(() => undefined);
+    const { runner, fills, done } = runBuys([
+      { side: 'buy', tokenAddress: TOKEN, fundsInBNB: 2n * ONE },
+      { side: 'buy', tokenAddress: TOKEN, fundsInBNB: 3n * ONE },
+    ], { thresholdBnb: ONE, gate, timeoutMs: 50 });
+
+    await new Promise((resolve) => setTimeout(resolve, 20));
+    const [first] = gate.pending();
+    expect(gate.reply(first.id, { approved: true })).toBe(true);
+    await done;
+
+    expect(fills).toHaveLength(1);
+    const journal = runner.getApprovalJournal();
+    expect(journal.map((record) => record.outcome)).toEqual(['approved', 'timedOut']);
+    expect(journal[1].latencyMs).toBeGreaterThanOrEqual(45);
+    expect(gate.pending()).toEqual([]);
+    expect(gate.reply(journal[1].id, { approved: true })).toBe(false);
+  });
+
+  it('lets pre-approved buys skip the gate and journals them', async () => {
+    const { runner, fills, done } = runBuys([
+      { side: 'buy', tokenAddress: TOKEN, fundsInBNB: 2n * ONE },
+      { side: 'buy', tokenAddress: TOKEN, fundsInBNB: 2n * ONE, preApproved: true },
+    ], { thresholdBnb: ONE, gate: new DenyAllGate() });
+    await done;
+
+    expect(fills).toHaveLength(1);
+    expect(runner.getApprovalJournal().map((record) => record.outcome)).toEqual(['denied', 'skipped']);
+  });
+
+  it('rejects the trade when the gate fails', async () => {
+    const { runner, fills, done } = runBuys([{ side: 'buy', tokenAddress: TOKEN, fundsInBNB: 2n * ONE }], {
+      thresholdBnb: ONE,
+      gate: new ChannelApprovalGate(() => {
+        throw new Error('bot offline');
+      }),
+    });
+    await done;
+
+    expect(fills).toHaveLength(0);
+    expect(runner.getApprovalJournal()[0]).toMatchObject({ outcome: 'error', reason: 'bot offline' });
+  });
+
+  it('auto-approves when no gate is given', async () => {
+    const { runner, fills, done } = runBuys([{ side: 'buy', tokenAddress: TOKEN, fundsInBNB: 2n * ONE }], { thresholdBnb: ONE });
+    await done;
+
+    expect(fills).toHaveLength(1);
+    expect(runner.getApprovalJournal()[0]).toMatchObject({ outcome: 'approved', approver: 'auto' });
+  });
+});
+
+describe('StrategyRunner intent expiry', () => {
+  const launchPrice = ONE / 1000n;
+  const silent = { info: jest.fn(), warn: jest.fn(), error: jest.fn() } as any;
+  const buy = (funds: bigint, extra: Partial<TradeIntent> = {}): TradeIntent =>
+    ({ side: 'buy', tokenAddress: TOKEN, fundsInBNB: funds, ...extra }) as TradeIntent;
+
+  function setup(intents: TradeIntent[], events: StrategyEvent[] = [created(TOKEN, 'MEME')]) {
+    let now = 1_000_000;
+    const backtest = new BacktestExecutor(0n, launchPrice);
+    const executed: TradeIntent[] = [];
+    const fills: OwnFill[] = [];
+    const expired: ExpiredIntent[] = [];
+    const hooks: { onFill?: (fill: OwnFill) => void } = {};
+    // Each execution takes 15s, as with a backed-up queue
+    const executor: TradeExecutor = {
+      execute: async (intent) => {
+        executed.push(intent);
+        now += 15_000;
+        return backtest.execute(intent);
+      },
+    };
+    const strategy: Strategy = {
+      onTokenCreated: () => intents,
+      onOwnFill: (fill) => {
+        fills.push(fill);
+        hooks.onFill?.(fill);
+      },
+      onIntentExpired: (entry) => {
+        expired.push(entry);
+      },
+    };
+    const config = { driver: new BacktestDriver(events), executor, clock: () => now, logger: silent };
+    const advance = (ms: number) => (now += ms);
+    return { strategy, config, executed, fills, expired, hooks, advance };
+  }
+
+  it('drops intents that expired while queued behind slower ones, without executing them', async () => {
+    const { strategy, config, executed, fills, expired } = setup([
+      buy(ONE),
+      buy(2n * ONE, { expiresAt: 1_000_000 + 10_000 }),
+      buy(3n * ONE, { expiresAt: 1_000_000 + 60_000 }),
+    ]);
+
+    const runner = await StrategyRunner.run(strategy, undefined, config);
+
+    expect(executed.map((intent) => (intent.side === 'buy' ? intent.fundsInBNB : 0n))).toEqual([ONE, 3n * ONE]);
+    expect(fills).toHaveLength(2);
+    expect(runner.getExpirationJournal()).toEqual(expired);
+    expect(expired).toHaveLength(1);
+    expect(expired[0]).toMatchObject({ id: 'intent-2', stage: 'dequeue', enqueuedAt: 1_000_000, queueWaitMs: 15_000 });
+    expect(runner.getTotalBnbSpent()).toBe(4n * ONE);
+  });
+
+  it('checks expiry again right before execution', async () => {
+    const { strategy, config, executed, expired, advance } = setup([buy(2n * ONE, { expiresAt: 1_000_000 + 1_000 })]);
+    const gate = new ChannelApprovalGate((request) => {
+      advance(5_000);
+      gate.reply(request.id, { approved: true, approver: 'ops' });
+    });
+
+    const runner = await StrategyRunner.run(strategy, undefined, { ...config, approval: { thresholdBnb: ONE, gate } });
+
+    expect(runner.getApprovalJournal().map((record) => record.outcome)).toEqual(['approved']);
+    expect(executed).toHaveLength(0);
+    expect(expired).toHaveLength(1);
+    expect(expired[0]).toMatchObject({ stage: 'execution', queueWaitMs: 5_000 });
+  });
+
+  it('expires intents by block once the runner has seen it', async () => {
+    const { strategy, config, executed, expired } = setup(
+      [buy(ONE, { expiresAtBlock: 101 }), buy(2n * ONE, { id: 'late', expiresAtBlock: 100 })],
+      [block(100, 1000), created(TOKEN, 'MEME')]
+    );
+
+    await StrategyRunner.run(strategy, undefined, config);
+
+    expect(executed).toHaveLength(1);
+    expect(expired.map(({ id, blockNumber }) => [id, blockNumber])).toEqual([['late', 100]]);
+  });
+
+  it('swaps a queued intent in place, keeping its id and queue time', async () => {
+    const { strategy, config, fills, expired, hooks } = setup([
+      buy(ONE, { id: 'first' }),
+      buy(2n * ONE, { id: 'second', expiresAt: 1_000_000 + 10_000 }),
+    ]);
+    const runner = new StrategyRunner(strategy, undefined, config);
+    const swaps: boolean[] = [];
+    hooks.onFill = (fill) => {
+      if (fill.intent.id === 'first') {
+        swaps.push(runner.replaceIntent('first', buy(5n * ONE)));
+        swaps.push(runner.replaceIntent('second', buy(4n * ONE, { tags: ['repriced'], expiresAt: 1_000_000 + 20_000 })));
+      }
+    };
+
+    await runner.run();
+
+    expect(swaps).toEqual([false, true]);
+    expect(expired).toHaveLength(0);
+    expect(fills.map((fill) => fill.intent)).toMatchObject([
+      { id: 'first', fundsInBNB: ONE },
+      { id: 'second', fundsInBNB: 4n * ONE, tags: ['repriced', 'source:tokenCreated'] },
+    ]);
+    expect(runner.replaceIntent('second', buy(ONE))).toBe(false);
+  });
+});
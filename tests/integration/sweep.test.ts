@@ -0,0 +1,113 @@
+import { WebSocketProvider } from 'ethers';
+import { FourTrading } from '../../src/fourTrading';
+import { sweepReportToJson } from '../../src/sweep';
+import { WBNB_ADDRESS } from '../../src/constants';
+import { FakeChain, FakeRpc, FakeSocket, ONE, PRIVATE_KEY, TOKEN } from './fakeChain';
+
+const BAG = 99_000n * ONE; // 0.1 BNB less the 1% fee, at 1,000,000 tokens per BNB
+const LEFTOVER = 10_000n * ONE; // Arrives after the position was sold out, e.g. a transfer
+
+async function withTrader(run: (trader: FourTrading, chain: FakeChain) => Promise<void>): Promise<void> {
+  const chain = new FakeChain();
+  const provider = new FakeRpc(chain);
+  const eventProvider = new WebSocketProvider(() => new FakeSocket(chain) as any, undefined, { cacheTimeout: -1 });
+  const trader = new FourTrading({ rpcUrl: '', wssUrl: '', privateKey: PRIVATE_KEY, provider, eventProvider });
+  try {
+    await run(trader, chain);
+  } finally {
+    trader.removeAllListeners();
+    provider.destroy();
+    await eventProvider.destroy();
+  }
+}
+
+// Buy a bag and sell all of it, so the tracked position is closed
+async function closePosition(trader: FourTrading): Promise<void> {
+  await trader.buyToken({ tokenAddress: TOKEN, fundsInBNB: ONE / 10n });
+  await trader.batchApprove([TOKEN], BAG);
+  await trader.sellToken({ tokenAddress: TOKEN, amount: BAG });
+}
+
+describe('sweep', () => {
+  beforeAll(() => {
+    jest.spyOn(console, 'log').mockImplementation(() => {});
+    jest.spyOn(console, 'error').mockImplementation(() => {});
+  });
+
+  afterAll(() => {
+    jest.restoreAllMocks();
+  });
+
+  it('reports what it would do in a dry run and never touches an open position', async () => {
+    await withTrader(async (trader, chain) => {
+      await trader.buyToken({ tokenAddress: TOKEN, fundsInBNB: ONE / 10n });
+      chain.wbnbBalance = ONE / 10n;
+      const mined = chain.receipts.size;
+
+      const report = await trader.sweep({ dryRun: true });
+
+      expect(report.dryRun).toBe(true);
+      expect(report.items).toEqual([
+        expect.objectContaining({ kind: 'wbnb', token: WBNB_ADDRESS, amount: ONE / 10n, action: 'wouldUnwrap', proceeds: ONE / 10n }),
+      ]);
+      expect(report.recovered).toBe(ONE / 10n);
+      expect(chain.receipts.size).toBe(mined);
+
+      const explicit = await trader.sweep({ dryRun: true, tokens: [TOKEN], unwrapWbnb: false });
+      expect(explicit.items.map((item) => [item.kind, item.action, item.skipReason])).toEqual([
+        ['wbnb', 'skipped', 'unwrapDisabled'],
+        ['token', 'skipped', 'openPosition'],
+      ]);
+      expect(explicit.recovered).toBe(0n);
+    });
+  });
+
+  it('unwraps WBNB and sells the leftover of a closed position into trade history', async () => {
+    await withTrader(async (trader, chain) => {
+      await closePosition(trader);
+      chain.tokenBalance += LEFTOVER;
+      await trader.batchApprove([TOKEN], LEFTOVER);
+      chain.wbnbBalance = ONE / 10n;
+
+      const report = await trader.sweep();
+
+      expect(report.items.map((item) => [item.kind, item.action])).toEqual([['wbnb', 'unwrapped'], ['token', 'sold']]);
+      expect(report.items.every((item) => item.txHash !== undefined)).toBe(true);
+      expect(chain.wbnbBalance).toBe(0n);
+      expect(chain.tokenBalance).toBe(0n);
+      expect(report.recovered).toBe(ONE / 10n + report.items[1].proceeds!);
+      expect(trader.getTradeHistory().getTradesByTag('sweep')).toHaveLength(1);
+    });
+  });
+
+  it('leaves dust and unapproved balances with the reason', async () => {
+    await withTrader(async (trader, chain) => {
+      await closePosition(trader);
+      chain.tokenBalance += ONE; // 1 token: a few microBNB, far below sell gas
+      chain.wbnbBalance = 1000n;
+
+      const dust = await trader.sweep();
+      expect(dust.items.map((item) => [item.kind, item.action, item.skipReason])).toEqual([
+        ['wbnb', 'skipped', 'uneconomical'],
+        ['token', 'skipped', 'uneconomical'],
+      ]);
+
+      chain.tokenBalance += LEFTOVER;
+      chain.allowance = 0n;
+      const unapproved = await trader.sweep();
+      expect(unapproved.items[1]).toMatchObject({ action: 'skipped', skipReason: 'notApproved' });
+      expect(chain.tokenBalance).toBe(LEFTOVER + ONE);
+    });
+  });
+
+  it('serializes the report with bigints as strings', async () => {
+    await withTrader(async (trader, chain) => {
+      chain.wbnbBalance = ONE;
+
+      const parsed = JSON.parse(sweepReportToJson(await trader.sweep({ dryRun: true })));
+
+      expect(parsed).toMatchObject({ dryRun: true, recovered: ONE.toString() });
+      expect(parsed.items[0]).toMatchObject({ kind: 'wbnb', amount: ONE.toString(), action: 'wouldUnwrap' });
+    });
+  });
+});
@@ -0,0 +1,98 @@
+import { ethers } from 'ethers';
+import { getFourTradingInterface } from '../../src/abi';
+import { FOUR_MEME_ADDRESS } from '../../src/constants';
+import { SymbolCollisionError, SymbolCollisionUncheckedError } from '../../src/errors';
+import { FourTrading, FourTradingConfig } from '../../src/fourTrading';
+import { MOCK_CREATOR, MOCK_PRIVATE_KEY, MOCK_TOKEN, MockChain, MockJsonRpcProvider, MockWebSocket } from '../../src/testing';
+
+const ONE = 10n ** 18n;
+const four = getFourTradingInterface();
+const COPYCAT = '0x2222222222222222222222222222222222222222';
+
+/**
+ * Mock chain that answers the token directory's log scans and the STATUS_TRADING read
+ */
+class IndexedChain extends MockChain {
+  logs: any[] = [];
+  logQueries = 0;
+
+  created(token: string, block: number): void {
+    const event = four.encodeEventLog('TokenCreate', [MOCK_CREATOR, token, 1n, 'Snipe', 'SNP', 1_000_000_000n * ONE, 0n, 0n]);
+    this.logs.push({ ...this.tokenCreateLog(), topics: event.topics, data: event.data, blockNumber: ethers.toQuantity(block) });
+  }
+
+  handle(method: string, params: any[]): unknown {
+    if (method === 'eth_getLogs') {
+      this.logQueries += 1;
+      const [from, to] = [Number(params[0].fromBlock), Number(params[0].toBlock)];
+      return this.logs.filter((log) => Number(log.blockNumber) >= from && Number(log.blockNumber) <= to);
+    }
+    if (method === 'eth_call' && params[0].data.startsWith(four.getFunction('STATUS_TRADING')!.selector)) {
+      return four.encodeFunctionResult('STATUS_TRADING', [0n]);
+    }
+    return super.handle(method, params);
+  }
+}
+
+async function withTrader(config: Partial<FourTradingConfig>, run: (trader: FourTrading, chain: IndexedChain) => Promise<void>): Promise<void> {
+  const chain = new IndexedChain();
+  const provider = new MockJsonRpcProvider(chain);
+  const eventProvider = new ethers.WebSocketProvider(() => new MockWebSocket(chain) as any, undefined, { cacheTimeout: -1 });
+  const trader = new FourTrading({ rpcUrl: '', wssUrl: '', privateKey: MOCK_PRIVATE_KEY, ...config, provider, eventProvider });
+  try {
+    await run(trader, chain);
+  } finally {
+    trader.detachTokenDirectory();
+    trader.removeAllListeners();
+    provider.destroy();
+    await eventProvider.destroy();
+  }
+}
+
+const buy = (trader: FourTrading) => trader.buyToken({ tokenAddress: MOCK_TOKEN, fundsInBNB: ONE / 10n });
+
+describe('symbol collision gate', () => {
+  beforeAll(() => {
+    jest.spyOn(console, 'log').mockImplementation(() => {});
+    jest.spyOn(console, 'error').mockImplementation(() => {});
+  });
+
+  afterAll(() => {
+    jest.restoreAllMocks();
+  });
+
+  it('refuses a token it cannot compare unless unchecked symbols are allowed', async () => {
+    await withTrader({ symbolCollisionWindowBlocks: 100 }, async (trader, chain) => {
+      await expect(buy(trader)).rejects.toThrow(SymbolCollisionUncheckedError);
+      expect(chain.transactions).toEqual([]);
+    });
+
+    await withTrader({ symbolCollisionWindowBlocks: 100, allowUncheckedSymbols: true }, async (trader, chain) => {
+      await buy(trader);
+      expect(chain.transactions.map((tx) => tx.name)).toEqual(['buyTokenAMAP']);
+    });
+  });
+
+  it('refuses a copycat of a token created inside the window', async () => {
+    await withTrader({ symbolCollisionWindowBlocks: 100 }, async (trader, chain) => {
+      chain.created(COPYCAT, chain.block - 50);
+      chain.created(MOCK_TOKEN, chain.block);
+
+      await expect(buy(trader)).rejects.toThrow(SymbolCollisionError);
+      expect(chain.transactions).toEqual([]);
+    });
+  });
+
+  it('checks later buys against the attached index without scanning logs', async () => {
+    await withTrader({ symbolCollisionWindowBlocks: 100 }, async (trader, chain) => {
+      chain.created(MOCK_TOKEN, chain.block);
+
+      await buy(trader);
+      const scanned = chain.logQueries;
+      await buy(trader);
+
+      expect(chain.logQueries).toBe(scanned);
+      expect(chain.transactions).toHaveLength(2);
+    });
+  });
+});
@@ -0,0 +1,85 @@
+import { WebSocketProvider } from 'ethers';
+import { FourTrading } from '../../src/fourTrading';
+import { CONTRACT, FakeChain, FakeRpc, FakeSocket, MIN_TRADING_FEE, ONE, PRIVATE_KEY, TOKEN, four } from './fakeChain';
+
+const BAG = 99_000n * ONE; // 0.1 BNB less the 1% fee, at 1,000,000 tokens per BNB
+
+async function withTrader(run: (trader: FourTrading, chain: FakeChain) => Promise<void>): Promise<void> {
+  const chain = new FakeChain();
+  const provider = new FakeRpc(chain);
+  const eventProvider = new WebSocketProvider(() => new FakeSocket(chain) as any, undefined, { cacheTimeout: -1 });
+  const trader = new FourTrading({ rpcUrl: '', wssUrl: '', privateKey: PRIVATE_KEY, provider, eventProvider });
+  try {
+    await run(trader, chain);
+  } finally {
+    trader.removeAllListeners();
+    provider.destroy();
+    await eventProvider.destroy();
+  }
+}
+
+describe('trade previews', () => {
+  beforeAll(() => {
+    jest.spyOn(console, 'log').mockImplementation(() => {});
+  });
+
+  afterAll(() => {
+    jest.restoreAllMocks();
+  });
+
+  it('previews a buy with its quote and the transaction it would send, sending nothing', async () => {
+    await withTrader(async (trader, chain) => {
+      const preview = await trader.previewBuy({ tokenAddress: TOKEN, fundsInBNB: ONE / 10n });
+
+      expect(preview.ok).toBe(true);
+      expect(preview.checks.map((check) => check.name)).toEqual([
+        'buyLimits', 'chainProgress', 'officialToken', 'tokenAge', 'holderConcentration', 'symbolCollision',
+        'creatorReputation', 'curveCapacity', 'quote', 'priceImpact', 'bnbReserve', 'subAccountLimits', 'balance', 'managerRoute', 'gas',
+      ]);
+      expect(preview.values.get('quotedTokens')).toBe(BAG);
+      expect(preview.values.get('minFunds')).toBe(MIN_TRADING_FEE + 1n);
+      expect(preview.transaction).toMatchObject({ to: CONTRACT, value: ONE / 10n });
+      expect(typeof preview.transaction!.gasLimit).toBe('bigint');
+
+      const call = four.parseTransaction({ data: preview.transaction!.data })!;
+      expect(call.name).toBe('buyTokenAMAP');
+      expect(call.args[2]).toBe(ONE / 10n);
+      expect(chain.receipts.size).toBe(0);
+    });
+  });
+
+  it('records every failing check and names the first as binding', async () => {
+    await withTrader(async (trader) => {
+      const preview = await trader.previewBuy({ tokenAddress: TOKEN, fundsInBNB: 20n * ONE });
+
+      expect(preview.ok).toBe(false);
+      expect(preview.checks.filter((check) => !check.passed).map((check) => check.name)).toEqual(['balance']);
+      expect(preview.bindingConstraint).toMatchObject({ name: 'balance', code: 'INSUFFICIENT_BALANCE' });
+      expect(preview.transaction).not.toBeNull();
+
+      const explained = preview.explain().split('\n');
+      expect(explained[0]).toBe(`Buy preview for ${TOKEN}: refused by balance`);
+      expect(explained).toContain(`  fundsInBNB: ${20n * ONE}`);
+      expect(explained.some((line) => line.startsWith('  FAIL balance: [INSUFFICIENT_BALANCE] '))).toBe(true);
+      expect(explained).toContain('  pass buyLimits');
+      expect(explained).toContain('Transaction:');
+    });
+  });
+
+  it('checks the balance and allowance a sell needs', async () => {
+    await withTrader(async (trader) => {
+      const before = await trader.previewSell({ tokenAddress: TOKEN, amount: BAG });
+      expect(before.checks.filter((check) => !check.passed).map((check) => check.name)).toEqual(['balance', 'allowance']);
+
+      await trader.buyToken({ tokenAddress: TOKEN, fundsInBNB: ONE / 10n });
+      await trader.batchApprove([TOKEN], BAG);
+      const after = await trader.previewSell({ tokenAddress: TOKEN, amount: BAG });
+
+      expect(after.ok).toBe(true);
+      expect(after.values.get('balance')).toBe(BAG);
+      expect(after.values.get('quotedFunds')).toBeGreaterThan(0n);
+      expect(four.parseTransaction({ data: after.transaction!.data })!.name).toBe('sellToken');
+      expect(after.transaction!.value).toBe(0n);
+    });
+  });
+});
@@ -0,0 +1,184 @@
+import { WebSocketProvider } from 'ethers';
+import { StateSnapshotError } from '../../src/errors';
+import { FourTrading, FourTradingConfig } from '../../src/fourTrading';
+import { SubAccountLedger } from '../../src/subAccounts';
+import { MOCK_PRIVATE_KEY, MOCK_TOKEN, MockChain, MockJsonRpcProvider, MockWebSocket, createMockTrader } from '../../src/testing';
+import { RestoreReport, traderStateToJson } from '../../src/traderState';
+import { Watchlist } from '../../src/watchlist';
+
+const ONE = 10n ** 18n;
+const GWEI = 10n ** 9n;
+const BAG = 99_000n * ONE; // 0.1 BNB less the 1% fee, at 1,000,000 tokens per BNB
+
+// Start a second process's trader on the same chain from `snapshot`
+async function restoreOn(
+  chain: MockChain,
+  snapshot: string,
+  run: (trader: FourTrading, report: RestoreReport) => Promise<void>,
+  watchlist?: Watchlist,
+  settings: Partial<FourTradingConfig> = {}
+): Promise<void> {
+  const provider = new MockJsonRpcProvider(chain);
+  const eventProvider = new WebSocketProvider(() => new MockWebSocket(chain) as any, undefined, { cacheTimeout: -1 });
+  const config = { ...settings, rpcUrl: '', wssUrl: '', privateKey: MOCK_PRIVATE_KEY, provider, eventProvider };
+  try {
+    const { trader, report } = await FourTrading.restore(config, snapshot, { watchlist });
+    await run(trader, report);
+    trader.removeAllListeners();
+  } finally {
+    provider.destroy();
+    await eventProvider.destroy();
+  }
+}
+
+describe('trader state handover', () => {
+  beforeAll(() => {
+    jest.spyOn(console, 'log').mockImplementation(() => {});
+    jest.spyOn(console, 'error').mockImplementation(() => {});
+  });
+
+  afterAll(() => {
+    jest.restoreAllMocks();
+  });
+
+  it('round-trips positions, nonce, gas profile and watchlist with nothing to reconcile', async () => {
+    const old = createMockTrader();
+    try {
+      await old.trader.buyToken({ tokenAddress: MOCK_TOKEN, fundsInBNB: ONE / 10n });
+      await old.trader.syncNonce();
+      const watchlist = new Watchlist();
+      watchlist.add(MOCK_TOKEN);
+      const exported = old.trader.exportState(watchlist);
+      expect(exported.pending).toEqual([]);
+
+      const restoredList = new Watchlist();
+      await restoreOn(old.chain, traderStateToJson(exported), async (trader, report) => {
+        expect(report).toEqual({ settled: [], pending: [], discrepancies: [] });
+        expect(trader.getPositionTracker().getPositions()).toEqual(old.trader.getPositionTracker().getPositions());
+        expect(trader.getGasProfiler().snapshot()).toEqual(old.trader.getGasProfiler().snapshot());
+        expect(restoredList.has(MOCK_TOKEN)).toBe(true);
+
+        const next = trader.exportState(restoredList);
+        expect({ ...next, takenAt: 0 }).toEqual({ ...exported, takenAt: 0 });
+      }, restoredList);
+    } finally {
+      await old.close();
+    }
+  });
+
+  it('records a fill that mined during the handover and reports balance drift', async () => {
+    const old = createMockTrader();
+    try {
+      await old.trader.buyToken({ tokenAddress: MOCK_TOKEN, fundsInBNB: ONE / 10n });
+      await old.trader.syncNonce();
+      old.chain.holdTransactions = true;
+      const blind = await old.trader.buyBlind(MOCK_TOKEN, ONE / 10n, 0n, { gasPrice: GWEI, gasLimit: 300_000n });
+      const snapshot = traderStateToJson(old.trader.exportState());
+
+      old.chain.holdTransactions = false;
+      old.chain.release(); // Mines while neither process watches
+      old.chain.tokenBalance += 1000n * ONE; // A transfer in, unknown to the snapshot
+
+      await restoreOn(old.chain, snapshot, async (trader, report) => {
+        expect(report.settled).toHaveLength(1);
+        expect(report.settled[0]).toMatchObject({ tx: { txHash: blind.txHash, nonce: 1 }, status: 'confirmed' });
+        expect(report.settled[0].result!.txHash).toBe(blind.txHash);
+        expect(report.pending).toEqual([]);
+        expect(report.discrepancies).toEqual([{ kind: 'balance', token: MOCK_TOKEN, expected: 2n * BAG, actual: 2n * BAG + 1000n * ONE }]);
+        expect(trader.getPositionTracker().getPosition(MOCK_TOKEN)!.amount).toBe(2n * BAG);
+        expect(trader.getTradeHistory().getTradesByTag('restored').map((trade) => trade.txHash)).toEqual([blind.txHash]);
+      });
+    } finally {
+      await old.close();
+    }
+  });
+
+  it('hands back transactions still pending and records them once they mine', async () => {
+    const old = createMockTrader();
+    try {
+      await old.trader.buyToken({ tokenAddress: MOCK_TOKEN, fundsInBNB: ONE / 10n });
+      await old.trader.syncNonce();
+      old.chain.holdTransactions = true;
+      const blind = await old.trader.buyBlind(MOCK_TOKEN, ONE / 10n, 0n, { gasPrice: GWEI, gasLimit: 300_000n });
+      const snapshot = traderStateToJson(old.trader.exportState());
+
+      await restoreOn(old.chain, snapshot, async (trader, report) => {
+        expect(report.settled).toEqual([]);
+        expect(report.pending.map((pending) => [pending.txHash, pending.nonce, pending.tags])).toEqual([[blind.txHash, 1, ['restored']]]);
+        expect(report.discrepancies).toEqual([]);
+
+        old.chain.holdTransactions = false;
+        old.chain.release();
+        const result = await report.pending[0].wait();
+        expect(result.success).toBe(true);
+        expect(trader.getPositionTracker().getPosition(MOCK_TOKEN)!.amount).toBe(2n * BAG);
+      });
+    } finally {
+      await old.close();
+    }
+  });
+
+  it('carries the sub-account ledger and books a fill mined during the handover to its sub-account', async () => {
+    const ledger = new SubAccountLedger(ONE);
+    ledger.allocate('sniper', ONE / 2n);
+    const old = createMockTrader({ config: { subAccounts: ledger } });
+    try {
+      await old.trader.buyToken({ tokenAddress: MOCK_TOKEN, fundsInBNB: ONE / 10n, subAccount: 'sniper' });
+      await old.trader.syncNonce();
+      old.chain.holdTransactions = true;
+      const gas = { gasPrice: GWEI, gasLimit: 300_000n };
+      await old.trader.buyBlind(MOCK_TOKEN, ONE / 10n, 0n, gas, undefined, { subAccount: 'sniper' });
+      const snapshot = old.trader.exportState();
+      expect(snapshot.pending.map((tx) => tx.subAccount)).toEqual(['sniper']);
+      expect(snapshot.subAccounts!.accounts.map((account) => account.id)).toEqual(['sniper']);
+
+      old.chain.holdTransactions = false;
+      old.chain.release();
+
+      await expect(restoreOn(old.chain, traderStateToJson(snapshot), async () => {})).rejects.toBeInstanceOf(StateSnapshotError);
+      const restored = new SubAccountLedger();
+      await restoreOn(old.chain, traderStateToJson(snapshot), async (_trader, report) => {
+        expect(report.settled).toHaveLength(1);
+        expect(restored.getUnallocated()).toBe(ONE / 2n);
+        expect(restored.getBalance('sniper')!.tokens.get(MOCK_TOKEN.toLowerCase())).toBe(2n * BAG);
+        expect(restored.getPositionTracker('sniper').getPosition(MOCK_TOKEN)!.amount).toBe(2n * BAG);
+      }, undefined, { subAccounts: restored });
+    } finally {
+      await old.close();
+    }
+  });
+
+  it('reports a dropped transaction and resets the nonce to the chain', async () => {
+    const old = createMockTrader();
+    try {
+      await old.trader.buyToken({ tokenAddress: MOCK_TOKEN, fundsInBNB: ONE / 10n });
+      await old.trader.syncNonce();
+      old.chain.holdTransactions = true;
+      const blind = await old.trader.buyBlind(MOCK_TOKEN, ONE / 10n, 0n, { gasPrice: GWEI, gasLimit: 300_000n });
+      const snapshot = traderStateToJson(old.trader.exportState());
+      old.chain.mempool.clear(); // Evicted by the node
+      old.chain.holdTransactions = false;
+
+      await restoreOn(old.chain, snapshot, async (trader, report) => {
+        expect(report.discrepancies).toEqual([
+          { kind: 'dropped', txHash: blind.txHash, nonce: 1, nonceUsed: false },
+          { kind: 'nonce', expected: 2, actual: 1 },
+        ]);
+        expect((await trader.buyBlind(MOCK_TOKEN, ONE / 10n, 0n, { gasPrice: GWEI, gasLimit: 300_000n })).nonce).toBe(1);
+      });
+    } finally {
+      await old.close();
+    }
+  });
+
+  it('refuses a snapshot of another wallet', async () => {
+    const old = createMockTrader();
+    try {
+      const exported = { ...old.trader.exportState(), wallet: '0x0000000000000000000000000000000000000001' };
+
+      await expect(restoreOn(old.chain, traderStateToJson(exported), async () => {})).rejects.toMatchObject({ code: 'STATE_SNAPSHOT_INVALID' });
+    } finally {
+      await old.close();
+    }
+  });
+});
@@ -0,0 +1,187 @@
+import { WebSocketProvider, ethers } from 'ethers';
+import { FourTrading } from '../../src/fourTrading';
+import { StrategyEvent } from '../../src/strategy';
+import {
+  blindBuyAndConfirm,
+  quoteAndBuyExact,
+  runTakeProfitStrategy,
+  sellAllWithApproval,
+  snipeOnCreateWithExit,
+  twapExit,
+} from '../../src/testing/tradingFlows';
+import { CREATOR, FakeChain, FakeRpc, FakeSocket, GAS_PRICE, ONE, PRIVATE_KEY, TOKEN } from './fakeChain';
+
+const BAG = 99_000n * ONE; // 0.1 BNB less the 1% fee, at 1,000,000 tokens per BNB
+const ENTRY_PRICE = ONE / 1_000_000n; // Wei per whole token the fake chain buys at
+
+// Someone else's buy at `price`, as the strategy runner receives it
+function tradeAt(price: bigint, blockNumber: number): StrategyEvent {
+  const event = { token: TOKEN, account: CREATOR, price, amount: ONE, cost: 0n, fee: 0n, offers: 0n, funds: 0n };
+  return { kind: 'trade', trade: { side: 'buy', event, blockNumber } };
+}
+
+async function withTrader(run: (trader: FourTrading, chain: FakeChain) => Promise<void>): Promise<void> {
+  const chain = new FakeChain();
+  const provider = new FakeRpc(chain);
+  const eventProvider = new WebSocketProvider(() => new FakeSocket(chain) as any, undefined, { cacheTimeout: -1 });
+  const trader = new FourTrading({ rpcUrl: '', wssUrl: '', privateKey: PRIVATE_KEY, provider, eventProvider });
+  try {
+    await run(trader, chain);
+  } finally {
+    trader.removeAllListeners();
+    provider.destroy();
+    await eventProvider.destroy();
+  }
+}
+
+describe('example trading flows', () => {
+  beforeAll(() => {
+    jest.spyOn(console, 'log').mockImplementation(() => {});
+    jest.spyOn(console, 'warn').mockImplementation(() => {});
+  });
+
+  afterAll(() => {
+    jest.restoreAllMocks();
+  });
+
+  it('snipes a launch and takes profit once the bag doubles', async () => {
+    await withTrader(async (trader, chain) => {
+      const seen: string[] = [];
+      const result = await snipeOnCreateWithExit(trader, {
+        fundsInBNB: ONE / 10n,
+        filter: (event) => {
+          seen.push(event.symbol);
+          return event.symbol === 'SNP';
+        },
+        takeProfitMultiple: 1.5,
+        maxHoldMs: 1000,
+        pollMs: 10,
+      });
+
+      expect(seen).toEqual(['SNP']);
+      expect(result).toMatchObject({ token: TOKEN, exitReason: 'takeProfit' });
+      expect(chain.tokenBalance).toBe(0n);
+
+      const [buy, sell] = trader.getTradeHistory().getTrades();
+      expect(buy).toMatchObject({ direction: 'buy', tokenAmount: BAG, bnbAmount: ONE / 10n, tags: ['flow:snipe'] });
+      // 0.198 BNB less the 1% fee
+      expect(sell).toMatchObject({ direction: 'sell', tokenAmount: BAG, bnbAmount: 196_020_000_000_000_000n, tags: ['flow:snipe', 'exit:takeProfit'] });
+      expect(sell.realizedPnl).toBe(96_020_000_000_000_000n);
+    });
+  });
+
+  it('exits at the timeout when the target is out of reach', async () => {
+    await withTrader(async (trader) => {
+      const result = await snipeOnCreateWithExit(trader, { fundsInBNB: ONE / 10n, takeProfitMultiple: 3, maxHoldMs: 30, pollMs: 10 });
+
+      expect(result.exitReason).toBe('timeout');
+      expect(trader.getTradeHistory().getTrades()[1].tags).toEqual(['flow:snipe', 'exit:timeout']);
+    });
+  });
+
+  it('quotes an exact buy and caps the spend at the slippage allowance', async () => {
+    await withTrader(async (trader, chain) => {
+      const amount = 1000n * ONE;
+      const { quotedFunds, maxFunds, result } = await quoteAndBuyExact(trader, TOKEN, amount, 100);
+
+      expect(quotedFunds).toBe(ethers.parseEther('0.00101'));
+      expect(maxFunds).toBe(ethers.parseEther('0.0010201'));
+      expect(result.success).toBe(true);
+      expect(chain.tokenBalance).toBe(amount);
+      expect(trader.getTradeHistory().getTrades()).toEqual([
+        expect.objectContaining({ direction: 'buy', tokenAmount: amount, bnbAmount: quotedFunds, tags: ['flow:buyExact'] }),
+      ]);
+    });
+  });
+
+  it('approves before selling everything, and only when the allowance falls short', async () => {
+    await withTrader(async (trader, chain) => {
+      await trader.buyToken({ tokenAddress: TOKEN, fundsInBNB: ONE / 10n });
+
+      const first = await sellAllWithApproval(trader, TOKEN);
+      expect(first.approval).toMatchObject({ status: 'approved', allowance: 0n });
+      expect(chain.allowance).toBe(ethers.MaxUint256);
+      expect(chain.tokenBalance).toBe(0n);
+
+      await trader.buyToken({ tokenAddress: TOKEN, fundsInBNB: ONE / 10n });
+      const second = await sellAllWithApproval(trader, TOKEN);
+      expect(second.approval.status).toBe('sufficient');
+      expect(trader.getTradeHistory().getTradesByTag('flow:sellAll').map((trade) => trade.tokenAmount)).toEqual([BAG, BAG]);
+    });
+  });
+
+  it('sells the bag in equal TWAP slices', async () => {
+    await withTrader(async (trader, chain) => {
+      await trader.buyToken({ tokenAddress: TOKEN, fundsInBNB: ONE / 10n });
+      const startBlock = chain.block;
+
+      const results = await twapExit(trader, TOKEN, { slices: 3, intervalMs: 10 });
+
+      expect(results.map((result) => result.receipt!.blockNumber)).toEqual([startBlock + 1, startBlock + 2, startBlock + 3]);
+      const slices = trader.getTradeHistory().getTradesByTag('flow:twap');
+      expect(slices.map((trade) => trade.tags[1])).toEqual(['slice:1/3', 'slice:2/3', 'slice:3/3']);
+      expect(slices.map((trade) => trade.tokenAmount)).toEqual([33_000n * ONE, 33_000n * ONE, 33_000n * ONE]);
+      expect(slices.map((trade) => trade.bnbAmount)).toEqual(Array(3).fill(65_340_000_000_000_000n));
+      expect(chain.tokenBalance).toBe(0n);
+    });
+  });
+
+  it('sends a blind buy and books it once its pending handle confirms', async () => {
+    await withTrader(async (trader, chain) => {
+      const { pending, result } = await blindBuyAndConfirm(trader, TOKEN, ONE / 10n, 2n * GAS_PRICE);
+
+      expect(pending).toMatchObject({ nonce: 0, tags: ['flow:blind'] });
+      expect(result).toMatchObject({ success: true, txHash: pending.txHash });
+      expect(chain.transactions).toEqual([expect.objectContaining({ hash: pending.txHash, name: 'buyTokenAMAP', value: ONE / 10n })]);
+      expect(trader.getTradeHistory().getTrades()).toEqual([
+        expect.objectContaining({ direction: 'buy', tokenAmount: BAG, txHash: pending.txHash, tags: ['flow:blind'] }),
+      ]);
+    });
+  });
+
+  it('runs a strategy that buys a launch and exits on a filtered price move', async () => {
+    await withTrader(async (trader, chain) => {
+      const created = { creator: CREATOR, token: TOKEN, requestId: 1n, name: 'Snipe', symbol: 'SNP', totalSupply: 0n, launchTime: 0n, launchFee: 0n };
+      const events: StrategyEvent[] = [
+        { kind: 'tokenCreated', event: created, blockNumber: chain.block },
+        tradeAt((ENTRY_PRICE * 11n) / 10n, chain.block + 1), // First price always passes
+        tradeAt((ENTRY_PRICE * 16n) / 10n, chain.block + 2), // 45% above the last passed price: filtered out
+        tradeAt((ENTRY_PRICE * 22n) / 10n, chain.block + 3), // 100% above it: passes, past the 2x target
+      ];
+
+      const { strategy, runner } = await runTakeProfitStrategy(
+        trader,
+        events,
+        { fundsInBNB: ONE / 10n, takeProfitMultiple: 2, minChangeBps: 5000 },
+        { thresholdBnb: ONE / 100n }
+      );
+
+      expect(strategy.seenPrices).toEqual([(ENTRY_PRICE * 11n) / 10n, (ENTRY_PRICE * 22n) / 10n]);
+      expect(chain.transactions.map((tx) => tx.name)).toEqual(['buyTokenAMAP', 'sellToken']);
+      expect(chain.tokenBalance).toBe(0n);
+      expect(trader.getTradeHistory().getTrades()).toEqual([
+        expect.objectContaining({ direction: 'buy', tokenAmount: BAG, tags: ['flow:strategy', 'source:tokenCreated'] }),
+        expect.objectContaining({
+          direction: 'sell', tokenAmount: BAG, bnbAmount: 196_020_000_000_000_000n, tags: ['flow:strategy', 'exit:takeProfit', 'source:trade'],
+        }),
+      ]);
+      expect(runner.getApprovalJournal()).toEqual([
+        expect.objectContaining({ tokenAddress: TOKEN, fundsInBNB: ONE / 10n, outcome: 'approved' }),
+      ]);
+      expect(runner.getTotalBnbSpent()).toBe(ONE / 10n);
+    });
+  });
+
+  it('stops a TWAP between slices when aborted', async () => {
+    await withTrader(async (trader, chain) => {
+      await trader.buyToken({ tokenAddress: TOKEN, fundsInBNB: ONE / 10n });
+      const controller = new AbortController();
+      setTimeout(() => controller.abort(), 20);
+
+      const results = await twapExit(trader, TOKEN, { slices: 4, intervalMs: 200, signal: controller.signal });
+
+      expect(results).toHaveLength(1);
+      expect(chain.tokenBalance).toBe(BAG - BAG / 4n);
+    });
+  });
+});
@@ -0,0 +1,14 @@
+import { getErc20Interface, getFourTradingInterface } from '../../src/abi';
+
+describe('ABI interfaces', () => {
+  it('should parse each ABI once and share the instance', () => {
+    expect(getFourTradingInterface()).toBe(getFourTradingInterface());
+    expect(getErc20Interface()).toBe(getErc20Interface());
+  });
+
+  it('should expose the functions the SDK calls', () => {
+    expect(getFourTradingInterface().getFunction('buyTokenAMAP(address,address,uint256,uint256)')).not.toBeNull();
+    expect(getErc20Interface().getFunction('balanceOf')).not.toBeNull();
+    expect(getErc20Interface().getFunction('approve')).not.toBeNull();
+  });
+});
@@ -0,0 +1,58 @@
+import { createAccessList, accessListSavings, AccessListRpc } from '../../src/accessList';
+
+const FROM = '0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa';
+const CONTRACT = '0x5c952063c7fc8610FFDB798152D69F0B9550762b';
+const TOKEN = '0x1111111111111111111111111111111111111111';
+const slot = (n: number) => '0x' + n.toString(16).padStart(64, '0');
+
+// Shape of a bonding-curve buy: the token contract plus its balance and curve state slots
+const BUY_ACCESS_LIST = [
+  { address: TOKEN, storageKeys: [slot(1), slot(2), slot(3)] },
+  { address: CONTRACT, storageKeys: [slot(10), slot(11), slot(12), slot(13), slot(14)] },
+];
+
+describe('createAccessList', () => {
+  it('sends eth_createAccessList with hex value and returns the normalized list', async () => {
+    const calls: Array<[string, unknown[]]> = [];
+    const rpc: AccessListRpc = {
+      send: async (method, params) => {
+        calls.push([method, params]);
+        return { accessList: BUY_ACCESS_LIST, gasUsed: '0x2710' };
+      },
+    };
+
+    const result = await createAccessList(rpc, { from: FROM, to: CONTRACT, data: '0x1234', value: 255n });
+
+    expect(calls).toEqual([
+      ['eth_createAccessList', [{ from: FROM, to: CONTRACT, data: '0x1234', value: '0xff' }, 'latest']],
+    ]);
+    expect(result!.gasUsed).toBe(10000n);
+    expect(result!.accessList).toHaveLength(2);
+    expect(result!.accessList[1].storageKeys).toHaveLength(5);
+  });
+
+  it('falls back to null when the node does not support the method', async () => {
+    const rpc: AccessListRpc = {
+      send: async () => {
+        throw new Error('the method eth_createAccessList does not exist/is not available');
+      },
+    };
+    expect(await createAccessList(rpc, { from: FROM, to: CONTRACT, data: '0x' })).toBeNull();
+  });
+
+  it('falls back to null on a malformed response', async () => {
+    const rpc: AccessListRpc = { send: async () => ({ error: 'execution reverted' }) };
+    expect(await createAccessList(rpc, { from: FROM, to: CONTRACT, data: '0x' })).toBeNull();
+  });
+});
+
+describe('accessListSavings', () => {
+  it('saves 100 gas per listed address and storage slot', () => {
+    // 2 addresses: 2600 cold vs 2400 + 100 warm; 8 slots: 2100 cold vs 1900 + 100 warm
+    expect(accessListSavings(BUY_ACCESS_LIST)).toBe(1000n);
+  });
+
+  it('is zero for an empty list', () => {
+    expect(accessListSavings([])).toBe(0n);
+  });
+});
@@ -0,0 +1,55 @@
+import { ApprovalLock } from '../../src/approvalLock';
+
+const WALLET = '0x' + 'aa'.repeat(20);
+const TOKEN = '0x' + 'bb'.repeat(20);
+const MANAGER = '0x' + 'cc'.repeat(20);
+
+describe('ApprovalLock', () => {
+  it('keys by wallet, token and spender regardless of case', () => {
+    expect(ApprovalLock.key(WALLET.toUpperCase().replace('0X', '0x'), TOKEN, MANAGER)).toBe(ApprovalLock.key(WALLET, TOKEN, MANAGER));
+    expect(ApprovalLock.key(WALLET, TOKEN, MANAGER)).not.toBe(ApprovalLock.key(WALLET, MANAGER, TOKEN));
+  });
+
+  it('lets one approval hold a key until it is released', () => {
+    const lock = new ApprovalLock();
+    const key = ApprovalLock.key(WALLET, TOKEN, MANAGER);
+
+    const claim = lock.acquire(key)!;
+    expect(lock.acquire(key)).toBeUndefined();
+    expect(lock.current(key)).toBe(claim.approval);
+    expect(lock.acquire(ApprovalLock.key(WALLET, MANAGER, TOKEN))).toBeDefined();
+
+    claim.release();
+    expect(lock.current(key)).toBeUndefined();
+    expect(lock.acquire(key)).toBeDefined();
+  });
+
+  it('wakes waiters on release and times out without one', async () => {
+    const lock = new ApprovalLock();
+    const claim = lock.acquire('key')!;
+
+    expect(await claim.approval.settledWithin(10)).toBe(false);
+    const waiting = claim.approval.settledWithin(10_000);
+    claim.release();
+    expect(await waiting).toBe(true);
+  });
+
+  it('gives the speed-up to one waiter until it is released', () => {
+    const claim = new ApprovalLock().acquire('key')!;
+
+    expect(claim.approval.claimSpeedUp()).toBe(true);
+    expect(claim.approval.claimSpeedUp()).toBe(false);
+    claim.approval.releaseSpeedUp();
+    expect(claim.approval.claimSpeedUp()).toBe(true);
+  });
+
+  it('records every hash sent for the nonce and hands over a speed-up receipt', async () => {
+    const claim = new ApprovalLock().acquire('key')!;
+    claim.approval.sent('0x01');
+    claim.approval.sent('0x02');
+    claim.approval.replaced({ hash: '0x02' } as any);
+
+    expect(claim.approval.hashes).toEqual(['0x01', '0x02']);
+    expect((await claim.approval.replacementMined).hash).toBe('0x02');
+  });
+});
@@ -0,0 +1,122 @@
+import { BackfillJob, BackfillLog, BackfillEvent, BackfillProgress } from '../../src/backfill';
+import { getFourTradingInterface } from '../../src/abi';
+
+const TOKEN_A = '0x1111111111111111111111111111111111111111';
+const TOKEN_B = '0x2222222222222222222222222222222222222222';
+const TOKEN_C = '0x3333333333333333333333333333333333333333';
+const ACCOUNT = '0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa';
+
+function tradeLog(name: 'TokenPurchase' | 'TokenSale', token: string, blockNumber: number): BackfillLog {
+  const iface = getFourTradingInterface();
+  const encoded = iface.encodeEventLog(name, [token, ACCOUNT, 1n, 2n, 3n, 4n, 5n, 6n]);
+  return { topics: encoded.topics, data: encoded.data, blockNumber, transactionHash: `0x${blockNumber.toString(16)}` };
+}
+
+// Log source over a fixed set of logs, recording each queried range
+function source(logs: BackfillLog[]) {
+  const ranges: Array<[number, number]> = [];
+  const fetchLogs = async (from: number, to: number) => {
+    ranges.push([from, to]);
+    return logs.filter((log) => log.blockNumber >= from && log.blockNumber <= to);
+  };
+  return { fetchLogs, ranges };
+}
+
+async function collect(job: BackfillJob): Promise<BackfillProgress[]> {
+  const progress: BackfillProgress[] = [];
+  for await (const item of job) {
+    progress.push(item);
+  }
+  return progress;
+}
+
+describe('BackfillJob', () => {
+  const logs = [
+    tradeLog('TokenPurchase', TOKEN_A, 10),
+    tradeLog('TokenSale', TOKEN_B, 15),
+    tradeLog('TokenPurchase', TOKEN_C, 25),
+    tradeLog('TokenSale', TOKEN_A, 35),
+  ];
+
+  it('should page ranges once for all tokens and demux by token', async () => {
+    const { fetchLogs, ranges } = source(logs);
+    const job = new BackfillJob([TOKEN_A, TOKEN_B], 0, fetchLogs, { toBlock: 39, pageSize: 20, requestIntervalMs: 0 });
+
+    const progress = await collect(job);
+
+    expect(ranges).toEqual([[0, 19], [20, 39]]);
+    expect(progress.map((item) => item.rangesCompleted)).toEqual([1, 2]);
+    expect(progress[1].rangesTotal).toBe(2);
+    expect(progress[1].eventsPerToken).toEqual({ [TOKEN_A.toLowerCase()]: 2, [TOKEN_B.toLowerCase()]: 1 });
+    expect(job.getEvents(TOKEN_A).map((event) => event.side)).toEqual(['buy', 'sell']);
+    expect(job.getEvents(TOKEN_C)).toEqual([]);
+    expect(job.done).toBe(true);
+  });
+
+  it('should forward events to a sink instead of storing them', async () => {
+    const received: Array<[string, BackfillEvent[]]> = [];
+    const { fetchLogs } = source(logs);
+    const job = new BackfillJob([TOKEN_A], 0, fetchLogs, {
+      toBlock: 39,
+      pageSize: 40,
+      requestIntervalMs: 0,
+      sink: { onEvents: (token, events) => { received.push([token, events]); } },
+    });
+
+    await collect(job);
+
+    expect(received).toHaveLength(1);
+    expect(received[0][0]).toBe(TOKEN_A);
+    expect(received[0][1].map((event) => event.blockNumber)).toEqual([10, 35]);
+    expect(job.getEvents(TOKEN_A)).toEqual([]);
+  });
+
+  it('should leave a resumable cursor when cancelled', async () => {
+    const { fetchLogs } = source(logs);
+    const job = new BackfillJob([TOKEN_A], 0, fetchLogs, { toBlock: 39, pageSize: 10, requestIntervalMs: 0 });
+
+    for await (const item of job) {
+      if (item.toBlock >= 19) {
+        job.cancel();
+      }
+    }
+
+    expect(job.cursor).toBe(20);
+    expect(job.done).toBe(false);
+
+    const { fetchLogs: resumeLogs, ranges } = source(logs);
+    const resumed = new BackfillJob([TOKEN_A], job.cursor, resumeLogs, { toBlock: 39, pageSize: 10, requestIntervalMs: 0 });
+    await collect(resumed);
+
+    expect(ranges[0]).toEqual([20, 29]);
+    expect(resumed.getEvents(TOKEN_A).map((event) => event.blockNumber)).toEqual([35]);
+  });
+
+  it('should stop with partial results when its abort signal fires', async () => {
+    const { fetchLogs } = source(logs);
+    const controller = new AbortController();
+    const job = new BackfillJob([TOKEN_A], 0, fetchLogs, { toBlock: 39, pageSize: 10, requestIntervalMs: 0, signal: controller.signal });
+
+    for await (const item of job) {
+      if (item.toBlock >= 19) {
+        controller.abort();
+      }
+    }
+
+    expect(job.cursor).toBe(20);
+    expect(job.getEvents(TOKEN_A).map((event) => event.blockNumber)).toEqual([10]);
+  });
+
+  it('should keep the cursor on a failed range', async () => {
+    let calls = 0;
+    const job = new BackfillJob([TOKEN_A], 0, async () => {
+      if (++calls === 2) {
+        throw new Error('rate limited');
+      }
+      return [];
+    }, { toBlock: 39, pageSize: 10, requestIntervalMs: 0 });
+
+    await expect(collect(job)).rejects.toThrow('rate limited');
+    expect(job.cursor).toBe(10);
+  });
+});
@@ -0,0 +1,51 @@
+import { WorkerResponse, deserializeError, jsonSafe, serializeError, takeResponse } from '../../src/blocking/protocol';
+import { BnbReserveError, FourTradingError, ValidationError } from '../../src/errors';
+
+describe('blocking protocol', () => {
+  it('should rebuild SDK errors with their class, code and details', () => {
+    const original = new BnbReserveError(10n, 5n, 1n);
+    const rebuilt = deserializeError(structuredClone(serializeError(original)));
+
+    expect(rebuilt).toBeInstanceOf(BnbReserveError);
+    expect(rebuilt).toBeInstanceOf(FourTradingError);
+    expect(rebuilt.message).toBe(original.message);
+    expect((rebuilt as BnbReserveError).code).toBe('BNB_RESERVE_VIOLATED');
+    expect((rebuilt as BnbReserveError).details).toEqual({ required: '10', available: '5', reserve: '1' });
+  });
+
+  it('should keep subclass relationships', () => {
+    const rebuilt = deserializeError(serializeError(new ValidationError('bad input')));
+    expect(rebuilt).toBeInstanceOf(ValidationError);
+    expect(rebuilt.name).toBe('ValidationError');
+  });
+
+  it('should fall back to Error for foreign errors', () => {
+    const rebuilt = deserializeError(serializeError(new TypeError('boom')));
+    expect(rebuilt).toBeInstanceOf(Error);
+    expect(rebuilt.message).toBe('boom');
+    expect(rebuilt.name).toBe('TypeError');
+  });
+
+  it('should convert bigints to strings in JSON-safe values', () => {
+    expect(jsonSafe({ gasUsed: 21000n, logs: [{ index: 1 }] })).toEqual({ gasUsed: '21000', logs: [{ index: 1 }] });
+  });
+
+  it('should skip late responses to timed-out calls and wait for the current one', () => {
+    const queue: WorkerResponse[] = [
+      { id: 1, ok: true, value: 'late' },
+      { id: 2, ok: false, error: { name: 'Error', message: 'also late' } },
+      { id: 3, ok: true, value: 'current' },
+    ];
+    const receive = () => queue.shift();
+
+    expect(takeResponse(receive, 3)).toEqual({ id: 3, ok: true, value: 'current' });
+    expect(queue).toEqual([]);
+  });
+
+  it('should come back empty when only stale responses are queued', () => {
+    const queue: WorkerResponse[] = [{ id: 4, ok: true, value: 'late' }];
+
+    expect(takeResponse(() => queue.shift(), 5)).toBeUndefined();
+    expect(queue).toEqual([]);
+  });
+});
@@ -0,0 +1,265 @@
+import { BlockClock, BlockHeader, BlockHeaderListener, averageBlockInterval, estimateBlockAt } from '../../src/blocks';
+import { CancelledError, ValidationError } from '../../src/errors';
+
+const T0 = 1_700_000_000;
+
+// Recorded 3s headers with skipped slots (6s, 9s and 12s gaps)
+const SKIPPED_SLOTS = recorded(1000, [
+  3, 3, 3, 6, 3, 3, 3, 3, 9, 3, 3, 3, 3, 3, 6, 3, 3, 3, 3, 3, 3, 3, 12, 3, 3, 3, 3, 3, 3, 6,
+  3, 3, 3, 3, 3, 3, 3, 3, 9, 3, 3, 3, 3, 6, 3, 3, 3, 3, 3, 3, 3, 3, 3, 6, 3, 3, 3, 3, 3,
+]);
+
+// Recorded sub-second headers: several blocks share a whole-second timestamp, some skip one
+const SUB_SECOND = [
+  0, 0, 1, 2, 3, 3, 4, 5, 6, 6, 8, 8, 9, 9, 10, 11, 12, 13, 13, 14, 15, 15, 16, 17, 18, 18, 19, 20, 21, 21,
+  22, 23, 24, 24, 25, 26, 27, 27, 29, 29, 30, 30, 31, 32, 33, 34, 34, 35, 36, 36, 37, 38, 39, 39, 40, 41, 42, 42, 43, 44,
+].map((offset, i) => header(5000 + i, T0 + offset));
+
+function header(number: number, timestamp: number): BlockHeader {
+  return { number, timestamp, hash: null };
+}
+
+function recorded(start: number, gaps: number[]): BlockHeader[] {
+  const headers = [header(start, T0)];
+  for (const gap of gaps) {
+    const last = headers[headers.length - 1];
+    headers.push(header(last.number + 1, last.timestamp + gap));
+  }
+  return headers;
+}
+
+// Estimate each header `ahead` blocks on from the `window` headers before it; errors in blocks
+function estimateErrors(headers: BlockHeader[], window: number, ahead: number): number[] {
+  const errors: number[] = [];
+  for (let i = window - 1; i + ahead < headers.length; i++) {
+    const seen = headers.slice(i - window + 1, i + 1);
+    const target = headers[i + ahead];
+    errors.push(Math.abs(estimateBlockAt(headers[i], averageBlockInterval(seen)!, target.timestamp) - target.number));
+  }
+  return errors;
+}
+
+const mean = (values: number[]) => values.reduce((sum, value) => sum + value, 0) / values.length;
+
+// Source whose heads subscription the test drives
+function manualSource(latest: BlockHeader = header(1, T0)) {
+  const listeners = new Set<BlockHeaderListener>();
+  let subscriptions = 0;
+  const source = {
+    latest: jest.fn(async () => latest),
+    subscribe(listener: BlockHeaderListener) {
+      subscriptions++;
+      listeners.add(listener);
+      return () => listeners.delete(listener);
+    },
+  };
+  const emit = (next: BlockHeader) => listeners.forEach((listener) => listener(next));
+  return { source, emit, listeners, subscriptions: () => subscriptions };
+}
+
+describe('averageBlockInterval', () => {
+  it('spans the headers end to end', () => {
+    expect(averageBlockInterval(SKIPPED_SLOTS.slice(0, 5))).toBe(3750);
+    expect(averageBlockInterval(SUB_SECOND.slice(0, 41))).toBe(750);
+  });
+
+  it('is undefined without two distinct blocks', () => {
+    expect(averageBlockInterval([])).toBeUndefined();
+    expect(averageBlockInterval([header(1, T0)])).toBeUndefined();
+    expect(averageBlockInterval([header(1, T0), header(1, T0 + 3)])).toBeUndefined();
+  });
+});
+
+describe('estimateBlockAt', () => {
+  it('counts on from the head at the interval given', () => {
+    expect(estimateBlockAt(header(100, T0), 3000, T0 + 30)).toBe(110);
+    expect(estimateBlockAt(header(100, T0), 750, T0 + 30)).toBe(140);
+    expect(estimateBlockAt(header(100, T0), 3000, T0 + 4)).toBe(101);
+  });
+
+  it('gives the head for timestamps already passed', () => {
+    expect(estimateBlockAt(header(100, T0), 3000, T0 - 60)).toBe(100);
+  });
+
+  it('rejects a non-positive interval', () => {
+    expect(() => estimateBlockAt(header(100, T0), 0, T0)).toThrow(ValidationError);
+  });
+
+  describe('accuracy 20 blocks ahead on recorded headers, averaging 20', () => {
+    it('stays within 3 blocks across skipped slots', () => {
+      const errors = estimateErrors(SKIPPED_SLOTS, 20, 20);
+
+      expect(Math.max(...errors)).toBeLessThanOrEqual(3);
+      expect(mean(errors)).toBeLessThan(1.5);
+    });
+
+    it('stays within 2 blocks with sub-second blocks on whole-second timestamps', () => {
+      const errors = estimateErrors(SUB_SECOND, 20, 20);
+
+      expect(Math.max(...errors)).toBeLessThanOrEqual(2);
+      expect(mean(errors)).toBeLessThan(0.5);
+    });
+
+    it('beats assuming a fixed 3s interval on sub-second blocks', () => {
+      const head = SUB_SECOND[19];
+      const target = SUB_SECOND[39];
+
+      expect(Math.abs(estimateBlockAt(head, 3000, target.timestamp) - target.number)).toBeGreaterThan(10);
+    });
+  });
+});
+
+describe('BlockClock', () => {
+  it('shares one heads subscription between subscribers and drops it after the last leaves', () => {
+    const { source, emit, listeners, subscriptions } = manualSource();
+    const clock = new BlockClock(source);
+    const seen: number[][] = [[], []];
+
+    const stops = seen.map((list) => clock.subscribe((next) => list.push(next.number)));
+    emit(header(2, T0 + 3));
+    stops[0]();
+    emit(header(3, T0 + 6));
+    stops[1]();
+
+    expect(subscriptions()).toBe(1);
+    expect(seen).toEqual([[2], [2, 3]]);
+    expect(listeners.size).toBe(0);
+  });
+
+  it('ignores headers not above the head', () => {
+    const { source, emit } = manualSource();
+    const clock = new BlockClock(source);
+    const seen: number[] = [];
+    const stop = clock.subscribe((next) => seen.push(next.number));
+
+    [2, 3, 3, 2, 4].forEach((number) => emit(header(number, T0 + number * 3)));
+    stop();
+
+    expect(seen).toEqual([2, 3, 4]);
+    expect(clock.head!.number).toBe(4);
+  });
+
+  it('averages over the last `window` headers, assuming the default before two were seen', () => {
+    const clock = new BlockClock(manualSource().source, { window: 3, defaultIntervalMs: 1000 });
+    expect(clock.averageIntervalMs()).toBe(1000);
+
+    clock.observe(header(1, T0));
+    clock.observe(header(2, T0 + 30));
+    clock.observe(header(3, T0 + 33));
+    clock.observe(header(4, T0 + 36));
+
+    expect(clock.averageIntervalMs()).toBe(3000);
+  });
+
+  it('resolves awaitBlock at the first header at or above the block', async () => {
+    const { source, emit } = manualSource();
+    const clock = new BlockClock(source);
+    clock.observe(header(10, T0));
+
+    expect((await clock.awaitBlock(9)).number).toBe(10);
+    const waiting = clock.awaitBlock(12);
+    emit(header(11, T0 + 3));
+    emit(header(13, T0 + 9));
+
+    expect((await waiting).number).toBe(13);
+  });
+
+  it('counts awaitBlocksFromNow from the latest head when none was seen', async () => {
+    const { source, emit } = manualSource(header(50, T0));
+    const clock = new BlockClock(source);
+
+    const waiting = clock.awaitBlocksFromNow(2);
+    await new Promise((resolve) => setTimeout(resolve, 0));
+    emit(header(51, T0 + 3));
+    emit(header(52, T0 + 6));
+
+    expect((await waiting).number).toBe(52);
+    expect(source.latest).toHaveBeenCalledTimes(1);
+    await expect(clock.awaitBlocksFromNow(-1)).rejects.toBeInstanceOf(ValidationError);
+  });
+
+  it('reads the head from the source again once no subscriber keeps it current', async () => {
+    const { source, emit } = manualSource(header(50, T0));
+    const clock = new BlockClock(source);
+    const stop = clock.subscribe(() => undefined);
+    emit(header(50, T0));
+    stop();
+
+    source.latest.mockResolvedValue(header(90, T0 + 120));
+    const waiting = clock.awaitBlocksFromNow(1);
+    await new Promise((resolve) => setTimeout(resolve, 0));
+    emit(header(91, T0 + 123));
+
+    expect((await waiting).number).toBe(91);
+    expect(await clock.estimateBlockAt(T0 + 123)).toBe(91);
+  });
+
+  it('rejects awaitBlock with CancelledError when the signal fires and unsubscribes', async () => {
+    const { source, listeners } = manualSource();
+    const clock = new BlockClock(source);
+    const controller = new AbortController();
+
+    const waiting = clock.awaitBlock(100, controller.signal);
+    controller.abort();
+
+    await expect(waiting).rejects.toBeInstanceOf(CancelledError);
+    expect(listeners.size).toBe(0);
+  });
+
+  it('estimates blocks from the observed interval', async () => {
+    const clock = new BlockClock(manualSource().source, { window: 41 });
+    SUB_SECOND.slice(0, 41).forEach((next) => clock.observe(next));
+
+    expect(await clock.estimateBlockAt(T0 + 30 + 15)).toBe(5040 + 20);
+  });
+
+  it('streams headers until the signal fires', async () => {
+    const { source, emit, listeners } = manualSource();
+    const clock = new BlockClock(source);
+    const controller = new AbortController();
+    const seen: number[] = [];
+
+    const reading = (async () => {
+      for await (const next of clock.headers(controller.signal)) {
+        seen.push(next.number);
+        if (next.number === 3) {
+          controller.abort();
+        }
+      }
+    })();
+    await Promise.resolve();
+    emit(header(2, T0 + 3));
+    emit(header(3, T0 + 6));
+    await reading;
+
+    expect(seen).toEqual([2, 3]);
+    expect(listeners.size).toBe(0);
+  });
+
+  describe('without a heads subscription', () => {
+    beforeEach(() => jest.useFakeTimers());
+    afterEach(() => jest.useRealTimers());
+
+    it('polls the latest header while subscribed', async () => {
+      let latest = header(1, T0);
+      const source = { latest: jest.fn(async () => latest) };
+      const clock = new BlockClock(source, { pollIntervalMs: 1000 });
+      const seen: number[] = [];
+
+      const stop = clock.subscribe((next) => seen.push(next.number));
+      await jest.advanceTimersByTimeAsync(1000);
+      latest = header(2, T0 + 3);
+      await jest.advanceTimersByTimeAsync(1000);
+      stop();
+      latest = header(3, T0 + 6);
+      await jest.advanceTimersByTimeAsync(5000);
+
+      expect(seen).toEqual([1, 2]);
+      expect(source.latest).toHaveBeenCalledTimes(2);
+    });
+  });
+
+  it('rejects a window below 2', () => {
+    expect(() => new BlockClock(manualSource().source, { window: 1 })).toThrow(ValidationError);
+  });
+});
@@ -0,0 +1,30 @@
+import { encodeBuyCalldata, encodeBuyExactCalldata, encodeSellCalldata } from '../../src/calldata';
+import { getFourTradingInterface } from '../../src/abi';
+
+const TOKEN = '0x1111111111111111111111111111111111111111';
+const RECIPIENT = '0x2222222222222222222222222222222222222222';
+
+describe('calldata encoding', () => {
+  const iface = getFourTradingInterface();
+
+  it('should encode buyTokenAMAP with recipient', () => {
+    const data = encodeBuyCalldata(TOKEN, RECIPIENT, 10n ** 17n, 5n);
+
+    expect(data.slice(0, 10)).toBe(iface.getFunction('buyTokenAMAP(address,address,uint256,uint256)')!.selector);
+    const decoded = iface.decodeFunctionData('buyTokenAMAP(address,address,uint256,uint256)', data);
+    expect([decoded[0], decoded[1], decoded[2], decoded[3]]).toEqual([TOKEN, RECIPIENT, 10n ** 17n, 5n]);
+  });
+
+  it('should encode exact-amount buys', () => {
+    const data = encodeBuyExactCalldata(TOKEN, RECIPIENT, 1000n, 2n);
+    const decoded = iface.decodeFunctionData('buyToken(address,address,uint256,uint256)', data);
+    expect(decoded[2]).toBe(1000n);
+    expect(decoded[3]).toBe(2n);
+  });
+
+  it('should encode sells', () => {
+    const data = encodeSellCalldata(TOKEN, 1000n, 3n);
+    const decoded = iface.decodeFunctionData('sellToken(address,uint256,uint256)', data);
+    expect([decoded[0], decoded[1], decoded[2]]).toEqual([TOKEN, 1000n, 3n]);
+  });
+});
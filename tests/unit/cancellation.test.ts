@@ -0,0 +1,98 @@
+import { throwIfCancelled, waitUnlessCancelled } from '../../src/cancellation';
+import { CancelledError } from '../../src/errors';
+import { PendingTrade, TransactionResult } from '../../src/types';
+
+const TX_HASH = '0x' + 'ab'.repeat(32);
+
+function deferred<T>() {
+  let resolve!: (value: T) => void;
+  let reject!: (error: unknown) => void;
+  const promise = new Promise<T>((res, rej) => {
+    resolve = res;
+    reject = rej;
+  });
+  return { promise, resolve, reject };
+}
+
+const handle = (): PendingTrade => ({
+  txHash: TX_HASH,
+  nonce: 7,
+  wait: async () => ({}) as TransactionResult,
+});
+
+/**
+ * Mock of the send path: a broadcast step and a receipt wait, each resolved by the test,
+ * wired the way buyToken/sellToken use the helpers
+ */
+function mockSend(signal: AbortSignal) {
+  const broadcast = deferred<{ hash: string; nonce: number }>();
+  const receipt = deferred<{ blockNumber: number }>();
+  const broadcasts: string[] = [];
+
+  const run = (async () => {
+    throwIfCancelled(signal);
+    const tx = await broadcast.promise;
+    broadcasts.push(tx.hash);
+    return await waitUnlessCancelled(receipt.promise, signal, handle);
+  })();
+
+  return { run, broadcast, receipt, broadcasts };
+}
+
+describe('cancellation', () => {
+  it('aborts cleanly before broadcast', async () => {
+    const controller = new AbortController();
+    controller.abort();
+    const { run, broadcasts } = mockSend(controller.signal);
+
+    const error = await run.catch((e) => e);
+    expect(error).toBeInstanceOf(CancelledError);
+    expect(error.stage).toBe('beforeBroadcast');
+    expect(error.pending).toBeUndefined();
+    expect(broadcasts).toEqual([]);
+  });
+
+  it('stops waiting after broadcast and hands back the pending trade', async () => {
+    const controller = new AbortController();
+    const { run, broadcast, receipt, broadcasts } = mockSend(controller.signal);
+
+    broadcast.resolve({ hash: TX_HASH, nonce: 7 });
+    await Promise.resolve();
+    await Promise.resolve();
+    controller.abort();
+
+    const error = await run.catch((e) => e);
+    expect(broadcasts).toEqual([TX_HASH]);
+    expect(error).toBeInstanceOf(CancelledError);
+    expect(error.stage).toBe('afterBroadcast');
+    expect(error.pending.txHash).toBe(TX_HASH);
+    expect(error.pending.nonce).toBe(7);
+
+    // The abandoned wait failing later must not surface as an unhandled rejection
+    receipt.reject(new Error('dropped'));
+    await Promise.resolve();
+  });
+
+  it('returns the receipt when not cancelled', async () => {
+    const controller = new AbortController();
+    const { run, broadcast, receipt } = mockSend(controller.signal);
+
+    broadcast.resolve({ hash: TX_HASH, nonce: 7 });
+    receipt.resolve({ blockNumber: 42 });
+    await expect(run).resolves.toEqual({ blockNumber: 42 });
+
+    controller.abort();
+  });
+
+  it('passes receipt errors through', async () => {
+    const { run, broadcast, receipt } = mockSend(new AbortController().signal);
+    broadcast.resolve({ hash: TX_HASH, nonce: 7 });
+    receipt.reject(new Error('reverted'));
+    await expect(run).rejects.toThrow('reverted');
+  });
+
+  it('is a plain await without a signal', async () => {
+    await expect(waitUnlessCancelled(Promise.resolve(1), undefined, handle)).resolves.toBe(1);
+    expect(() => throwIfCancelled(undefined)).not.toThrow();
+  });
+});
@@ -0,0 +1,82 @@
+import { CandleAggregator } from '../../src/candles';
+import { Price } from '../../src/price';
+import { TokenPurchaseEvent } from '../../src/types';
+
+const TOKEN = '0x1111111111111111111111111111111111111111';
+const MINUTE = 60000;
+
+function trade(price: bigint, cost: bigint = 1n): TokenPurchaseEvent {
+  return { token: TOKEN, account: TOKEN, price, amount: cost * 10n, cost, fee: 0n, offers: 0n, funds: 0n };
+}
+
+describe('CandleAggregator', () => {
+  it('should aggregate OHLCV within an interval', () => {
+    const candles = new CandleAggregator(TOKEN, MINUTE);
+
+    candles.addTrade(trade(10n, 1n), 1000);
+    candles.addTrade(trade(15n, 2n), 2000);
+    candles.addTrade(trade(8n, 3n), 3000);
+    candles.addTrade(trade(12n, 4n), 4000);
+    const [candle] = candles.closeUntil(MINUTE);
+
+    expect(candle).toMatchObject({ openTime: 0, closeTime: MINUTE, open: 10n, high: 15n, low: 8n, close: 12n, volume: 10n, tokenVolume: 100n, trades: 4 });
+  });
+
+  it('should not emit before the interval ends or before any trade', () => {
+    const candles = new CandleAggregator(TOKEN, MINUTE);
+
+    expect(candles.closeUntil(5 * MINUTE)).toEqual([]);
+    candles.addTrade(trade(10n), 5 * MINUTE + 1);
+    expect(candles.closeUntil(6 * MINUTE - 1)).toEqual([]);
+    expect(candles.closeUntil(6 * MINUTE)).toHaveLength(1);
+  });
+
+  it('should fill quiet intervals with flat candles at the previous close', () => {
+    const candles = new CandleAggregator(TOKEN, MINUTE);
+
+    candles.addTrade(trade(10n), 1000);
+    candles.addTrade(trade(20n), 1000 + 30000);
+    const closed = candles.closeUntil(4 * MINUTE);
+
+    expect(closed.map((candle) => candle.openTime)).toEqual([0, MINUTE, 2 * MINUTE, 3 * MINUTE]);
+    expect(closed.slice(1).every((candle) => candle.trades === 0 && candle.open === 20n && candle.close === 20n && candle.volume === 0n)).toBe(true);
+  });
+
+  it('should close gaps when the next trade arrives several intervals later', () => {
+    const candles = new CandleAggregator(TOKEN, MINUTE);
+
+    candles.addTrade(trade(10n), 1000);
+    const closed = candles.addTrade(trade(30n), 3 * MINUTE + 500);
+
+    expect(closed.map((candle) => candle.openTime)).toEqual([0, MINUTE, 2 * MINUTE]);
+    const [current] = candles.closeUntil(4 * MINUTE);
+    expect(current).toMatchObject({ openTime: 3 * MINUTE, open: 30n, close: 30n, trades: 1 });
+  });
+
+  it('should carry an exact volume-weighted price, flat candles keeping the previous one', () => {
+    const candles = new CandleAggregator(TOKEN, MINUTE);
+
+    candles.addTrade({ ...trade(10n), cost: 1n, amount: 3n }, 1000);
+    candles.addTrade({ ...trade(10n), cost: 1n, amount: 4n }, 2000);
+    const [traded, flat] = candles.closeUntil(2 * MINUTE);
+
+    expect(traded.vwap.equals(new Price(2n, 7n))).toBe(true);
+    expect(flat.trades).toBe(0);
+    expect(flat.vwap.equals(new Price(2n, 7n))).toBe(true);
+  });
+
+  it('should count a trade whose interval was already emitted toward the oldest open one', () => {
+    const candles = new CandleAggregator(TOKEN, MINUTE);
+
+    candles.addTrade(trade(10n), 1000);
+    expect(candles.closeUntil(MINUTE)).toHaveLength(1);
+    expect(candles.addTrade(trade(20n), MINUTE - 1)).toEqual([]);
+    const [late] = candles.closeUntil(2 * MINUTE);
+
+    expect(late).toMatchObject({ openTime: MINUTE, open: 20n, trades: 1 });
+  });
+
+  it('should reject non-positive intervals', () => {
+    expect(() => new CandleAggregator(TOKEN, 0)).toThrow(RangeError);
+  });
+});
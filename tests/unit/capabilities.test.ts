@@ -0,0 +1,152 @@
+import { CapabilityProbe, CapabilityProbeSource } from '../../src/capabilities';
+import { MULTICALL3_ADDRESS } from '../../src/constants';
+import { CapabilityMissingError } from '../../src/errors';
+
+const HEAD = 40_000_000;
+
+// Node answering `methods`, with state readable back to block `oldestState`
+function node(methods: string[], oldestState: number = 0) {
+  const calls: string[] = [];
+  const source: CapabilityProbeSource = {
+    send: async (method, params) => {
+      calls.push(method);
+      if (!methods.includes(method)) {
+        throw new Error(`the method ${method} does not exist/is not available`);
+      }
+      switch (method) {
+        case 'eth_blockNumber':
+          return `0x${HEAD.toString(16)}`;
+        case 'eth_getBalance':
+          if (Number(params[1]) < oldestState) {
+            throw new Error('missing trie node');
+          }
+          return '0x0';
+        case 'eth_getCode':
+          return params[0] === MULTICALL3_ADDRESS ? '0x6080' : '0x';
+        default:
+          return null;
+      }
+    },
+    sendBatch: async (requests) => {
+      if (!methods.includes('batch')) {
+        throw new Error('batch requests are not supported');
+      }
+      return requests.map(() => '0x1');
+    },
+    subscribePending: async () => {
+      if (!methods.includes('eth_subscribe')) {
+        throw new Error('notifications not supported');
+      }
+    },
+  };
+  return { source, calls };
+}
+
+const FULL = ['eth_blockNumber', 'eth_getBalance', 'eth_getCode', 'eth_feeHistory', 'debug_traceCall', 'batch', 'eth_subscribe'];
+
+describe('CapabilityProbe', () => {
+  it('reports everything a full archive node serves', async () => {
+    expect(await new CapabilityProbe(node(FULL).source).all()).toEqual({
+      pendingSubscription: true,
+      trace: true,
+      archiveDepth: null,
+      feeHistory: true,
+      batchRequests: true,
+      multicall: true,
+    });
+  });
+
+  it('reports what a pruned node without extras lacks', async () => {
+    const probe = new CapabilityProbe(node(['eth_blockNumber', 'eth_getBalance', 'eth_getCode'], HEAD - 128).source);
+
+    expect(await probe.all()).toEqual({
+      pendingSubscription: false,
+      trace: false,
+      archiveDepth: 128,
+      feeHistory: false,
+      batchRequests: false,
+      multicall: true,
+    });
+  });
+
+  it('finds the deepest probed depth the node still serves', async () => {
+    expect(await new CapabilityProbe(node(FULL, HEAD - 5_000_000).source).get('archiveDepth')).toBe(100_000);
+    expect(await new CapabilityProbe(node(FULL, HEAD - 10).source).get('archiveDepth')).toBe(0);
+  });
+
+  it('probes each capability once, sharing concurrent probes', async () => {
+    const { source, calls } = node(FULL);
+    const probe = new CapabilityProbe(source);
+
+    await Promise.all([probe.get('multicall'), probe.get('multicall')]);
+    await probe.get('multicall');
+
+    expect(calls).toEqual(['eth_getCode']);
+  });
+
+  it('probes again after reset', async () => {
+    const { source, calls } = node(FULL);
+    const probe = new CapabilityProbe(source);
+
+    await probe.get('feeHistory');
+    probe.reset();
+    expect(probe.known('feeHistory')).toBeUndefined();
+    await probe.get('feeHistory');
+
+    expect(calls).toEqual(['eth_feeHistory', 'eth_feeHistory']);
+  });
+
+  it('rejects without caching when a probe gets no answer', async () => {
+    const { source, calls } = node(FULL);
+    const send = source.send;
+    let timeouts = 2;
+    source.send = async (method, params) => {
+      if (timeouts > 0) {
+        timeouts--;
+        throw Object.assign(new Error('request timeout'), { code: 'TIMEOUT' });
+      }
+      return await send(method, params);
+    };
+    const probe = new CapabilityProbe(source);
+
+    await expect(probe.get('feeHistory')).rejects.toThrow('request timeout');
+    await expect(probe.get('archiveDepth')).rejects.toThrow('request timeout');
+    expect(probe.known('feeHistory')).toBeUndefined();
+    expect(await probe.get('feeHistory')).toBe(true);
+    expect(await probe.get('archiveDepth')).toBeNull();
+    expect(calls.filter((method) => method === 'eth_feeHistory')).toHaveLength(1);
+  });
+
+  it('takes overrides without probing', async () => {
+    const { source, calls } = node(FULL);
+    const probe = new CapabilityProbe(source, { multicall: false, archiveDepth: null });
+
+    expect(probe.known('multicall')).toBe(false);
+    expect(await probe.get('archiveDepth')).toBeNull();
+    await expect(probe.require('allowances', 'multicall')).rejects.toBeInstanceOf(CapabilityMissingError);
+    expect(calls).toEqual([]);
+  });
+
+  it('requires enough archive depth', async () => {
+    const probe = new CapabilityProbe(node(FULL).source, { archiveDepth: 100_000 });
+
+    await expect(probe.require('creatorReputation', 'archiveDepth', 57_600)).resolves.toBeUndefined();
+    await expect(probe.require('creatorReputation', 'archiveDepth', 200_000)).rejects.toMatchObject({
+      code: 'CAPABILITY_MISSING',
+      feature: 'creatorReputation',
+      required: 'archiveDepth',
+      depth: 200_000,
+    });
+  });
+
+  it('checks only what is known without probing', async () => {
+    const { source, calls } = node([]);
+    const probe = new CapabilityProbe(source);
+
+    expect(() => probe.requireKnown('onPendingBuy', 'pendingSubscription')).not.toThrow();
+    await probe.get('pendingSubscription');
+
+    expect(() => probe.requireKnown('onPendingBuy', 'pendingSubscription')).toThrow(/onPendingBuy needs pendingSubscription/);
+    expect(calls).toEqual([]);
+  });
+});
@@ -0,0 +1,83 @@
+import { secondsSinceBlock, assertChainProgressing, headProvesProgress, watchForStall } from '../../src/chainHealth';
+import { ChainStalledError } from '../../src/errors';
+
+const NOW_MS = 1_700_000_100_000;
+const head = (secondsAgo: number) => ({ number: 1234, timestamp: NOW_MS / 1000 - secondsAgo });
+
+describe('secondsSinceBlock', () => {
+  it('measures head age and clamps clock skew to zero', () => {
+    expect(secondsSinceBlock(head(12), NOW_MS)).toBe(12);
+    expect(secondsSinceBlock(head(-5), NOW_MS)).toBe(0);
+  });
+});
+
+describe('assertChainProgressing', () => {
+  it('passes within the threshold', () => {
+    expect(() => assertChainProgressing(head(30), 30, NOW_MS)).not.toThrow();
+  });
+
+  it('throws ChainStalledError past the threshold', () => {
+    try {
+      assertChainProgressing(head(95), 30, NOW_MS);
+      throw new Error('expected a stall');
+    } catch (error) {
+      expect(error).toBeInstanceOf(ChainStalledError);
+      expect((error as ChainStalledError).secondsSinceBlock).toBe(95);
+      expect((error as ChainStalledError).blockNumber).toBe(1234);
+      expect((error as ChainStalledError).code).toBe('CHAIN_STALLED');
+    }
+  });
+});
+
+describe('headProvesProgress', () => {
+  it('vouches for the chain until the seen head ages past the threshold', () => {
+    expect(headProvesProgress(head(30), 30, NOW_MS)).toBe(true);
+    expect(headProvesProgress(head(31), 30, NOW_MS)).toBe(false);
+    expect(headProvesProgress(undefined, 30, NOW_MS)).toBe(false);
+  });
+});
+
+describe('watchForStall', () => {
+  beforeEach(() => jest.useFakeTimers());
+  afterEach(() => jest.useRealTimers());
+
+  it('resolves with the pending value while the chain progresses', async () => {
+    let resolveReceipt!: (value: string) => void;
+    const pending = new Promise<string>((resolve) => (resolveReceipt = resolve));
+    const check = jest.fn(async () => undefined);
+
+    const watched = watchForStall(pending, check, 1000);
+    await jest.advanceTimersByTimeAsync(2500);
+    resolveReceipt('receipt');
+
+    await expect(watched).resolves.toBe('receipt');
+    expect(check).toHaveBeenCalledTimes(2);
+    await jest.advanceTimersByTimeAsync(5000);
+    expect(check).toHaveBeenCalledTimes(2);
+  });
+
+  it('rejects with ChainStalledError instead of hanging', async () => {
+    const pending = new Promise<string>(() => undefined);
+    let stalled = false;
+    const check = async () => {
+      if (stalled) {
+        throw new ChainStalledError(60, 30, 1234);
+      }
+    };
+
+    const watched = watchForStall(pending, check, 1000);
+    const assertion = expect(watched).rejects.toBeInstanceOf(ChainStalledError);
+    await jest.advanceTimersByTimeAsync(1000);
+    stalled = true;
+    await jest.advanceTimersByTimeAsync(1000);
+    await assertion;
+  });
+
+  it('ignores transient check failures', async () => {
+    const pending = Promise.resolve('receipt');
+    const check = async () => {
+      throw new Error('rpc timeout');
+    };
+    await expect(watchForStall(pending, check, 1000)).resolves.toBe('receipt');
+  });
+});
@@ -0,0 +1,113 @@
+import { planSellChunks, applySell, localSellCost, sellImpactBps, priceImpactBps } from '../../src/chunking';
+import { curveParams, curveSellCost } from '../../src/curve';
+import { TokenInfo } from '../../src/types';
+
+const ONE = 10n ** 18n;
+
+// Constant-product curve over virtual reserves: `offers` tokens against `funds` BNB
+const sellCost = async (state: TokenInfo, amount: bigint) => (state.funds * amount) / (state.offers + amount);
+
+function curve(offers: bigint, funds: bigint): TokenInfo {
+  return {
+    base: '0x0', quote: '0x0', template: 0n, totalSupply: 0n, maxOffers: 0n, maxRaising: 0n,
+    launchTime: 0n, offers, funds, lastPrice: 0n, K: 0n, T: 0n, status: 0n,
+  };
+}
+
+describe('sellImpactBps', () => {
+  it('measures the shortfall against the spot price', () => {
+    expect(sellImpactBps(100n, 99n, 1n, 1n)).toBe(100);
+    expect(sellImpactBps(100n, 100n, 1n, 1n)).toBe(0);
+  });
+});
+
+describe('priceImpactBps', () => {
+  it('compares the trade with a tiny one at the current price', async () => {
+    expect(await priceImpactBps(curve(1_000_000n * ONE, 100n * ONE), 10_000n * ONE, sellCost)).toBe(99);
+  });
+
+  it('is zero on a flat price', async () => {
+    expect(await priceImpactBps(curve(0n, 0n), 5n * ONE, async (_state, amount) => amount * 1000n)).toBe(0);
+  });
+});
+
+describe('applySell', () => {
+  it('returns tokens to the curve and removes the proceeds', () => {
+    const after = applySell(curve(100n, 50n), 10n, 5n);
+    expect(after.offers).toBe(110n);
+    expect(after.funds).toBe(45n);
+  });
+});
+
+describe('planSellChunks', () => {
+  const state = curve(1_000_000n * ONE, 100n * ONE);
+
+  it('sells in one chunk when the whole amount fits the limit', async () => {
+    const plan = await planSellChunks(state, 1000n * ONE, 100, sellCost);
+    expect(plan.outcome).toBe('complete');
+    expect(plan.chunks).toEqual([1000n * ONE]);
+  });
+
+  it('keeps every chunk under the limit, accounting for earlier chunks', async () => {
+    const total = 100_000n * ONE;
+    const plan = await planSellChunks(state, total, 100, sellCost);
+
+    expect(plan.outcome).toBe('complete');
+    expect(plan.chunks.length).toBeGreaterThan(1);
+    expect(plan.chunks.reduce((sum, chunk) => sum + chunk, 0n)).toBe(total);
+    expect(plan.impactsBps.every((impact) => impact <= 100)).toBe(true);
+
+    // Each sell lowers the price, so later chunks of a constant-product curve grow in tokens
+    let current = state;
+    for (const chunk of plan.chunks) {
+      const probe = total / 1_000_000n;
+      const impact = sellImpactBps(chunk, await sellCost(current, chunk), probe, await sellCost(current, probe));
+      expect(impact).toBeLessThanOrEqual(100);
+      current = applySell(current, chunk, await sellCost(current, chunk));
+    }
+  });
+
+  it('reports an impact-limited plan instead of looping forever', async () => {
+    const plan = await planSellChunks(state, 10_000_000n * ONE, 10, sellCost, { maxChunks: 5, minChunk: 1n });
+
+    expect(plan.outcome).toBe('impactLimited');
+    expect(plan.chunks).toHaveLength(5);
+    expect(plan.remaining).toBe(10_000_000n * ONE - plan.chunks.reduce((sum, chunk) => sum + chunk, 0n));
+  });
+
+  it('refuses chunk bounds that would plan empty sells', async () => {
+    await expect(planSellChunks(state, ONE, 100, sellCost, { minChunk: 0n })).rejects.toThrow(RangeError);
+    await expect(planSellChunks(state, ONE, 100, sellCost, { maxChunks: 0 })).rejects.toThrow(RangeError);
+  });
+
+  it('replays chunks on the local curve model', async () => {
+    // Mid-curve state of a launch: 800M tokens on sale, 200M of them sold for 10 BNB
+    const maxOffers = 800_000_000n * ONE;
+    const launched: TokenInfo = {
+      ...curve(600_000_000n * ONE, 10n * ONE),
+      maxOffers, T: 1_073_000_000n * ONE, K: 6_720_000_000n * ONE * ONE,
+    };
+    const total = 100_000_000n * ONE;
+
+    const plan = await planSellChunks(launched, total, 200, localSellCost('0x0'));
+
+    expect(plan.outcome).toBe('complete');
+    expect(plan.chunks.length).toBeGreaterThan(1);
+    // Replaying the chunks through applySell lands where one sell of the total would
+    let current = launched;
+    let proceeds = 0n;
+    for (const chunk of plan.chunks) {
+      const cost = curveSellCost(curveParams('0x0', current), chunk);
+      proceeds += cost;
+      current = applySell(current, chunk, cost);
+    }
+    expect(proceeds).toBe(curveSellCost(curveParams('0x0', launched), total));
+  });
+
+  it('stops when no chunk above the minimum fits', async () => {
+    const plan = await planSellChunks(state, 500_000n * ONE, 1, sellCost, { minChunk: 100_000n * ONE });
+    expect(plan.outcome).toBe('impactLimited');
+    expect(plan.chunks).toEqual([]);
+    expect(plan.remaining).toBe(500_000n * ONE);
+  });
+});
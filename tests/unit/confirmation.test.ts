@@ -0,0 +1,163 @@
+import { waitForFinalized, waitForConfirmations, waitForConfirmationsOrDrop, waitForBalanceAbove, DEFAULT_CONFIRMATION, InclusionWatch } from '../../src/confirmation';
+import { BalanceWaitTimeoutError, TransactionDroppedError, TransactionFailedError } from '../../src/errors';
+
+describe('confirmation strategies', () => {
+  it('defaults to a single confirmation', () => {
+    expect(DEFAULT_CONFIRMATION).toEqual({ type: 'confirmations', count: 1 });
+  });
+
+  describe('waitForFinalized', () => {
+    beforeEach(() => jest.useFakeTimers());
+    afterEach(() => jest.useRealTimers());
+
+    it('resolves immediately when the block is already finalized', async () => {
+      const getFinalized = jest.fn(async () => 120);
+      await expect(waitForFinalized(100, getFinalized, 1000)).resolves.toBe(120);
+      expect(getFinalized).toHaveBeenCalledTimes(1);
+    });
+
+    it('polls until the finalized block reaches the trade block', async () => {
+      const heads = [97, 98, 100];
+      const getFinalized = jest.fn(async () => heads.shift()!);
+
+      let done: number | undefined;
+      const waiting = waitForFinalized(100, getFinalized, 1000).then((finalized) => (done = finalized));
+
+      await jest.advanceTimersByTimeAsync(1000);
+      expect(done).toBeUndefined();
+      await jest.advanceTimersByTimeAsync(1000);
+      await waiting;
+
+      expect(done).toBe(100);
+      expect(getFinalized).toHaveBeenCalledTimes(3);
+    });
+
+    it('propagates provider errors', async () => {
+      await expect(waitForFinalized(1, async () => {
+        throw new Error('finalized tag not supported');
+      }, 1000)).rejects.toThrow('finalized tag not supported');
+    });
+  });
+
+  describe('waitForBalanceAbove', () => {
+    beforeEach(() => jest.useFakeTimers());
+    afterEach(() => jest.useRealTimers());
+
+    const TOKEN = '0x1111111111111111111111111111111111111111';
+
+    it('polls until the balance rises above the previous one, retrying failed reads', async () => {
+      const reads: Array<bigint | Error> = [100n, new Error('header not found'), 100n, 250n];
+      const readBalance = jest.fn(async () => {
+        const next = reads.shift()!;
+        if (next instanceof Error) {
+          throw next;
+        }
+        return next;
+      });
+
+      const waiting = waitForBalanceAbove(TOKEN, 100n, readBalance, 10_000, 1000);
+      await jest.advanceTimersByTimeAsync(3000);
+
+      await expect(waiting).resolves.toBe(250n);
+      expect(readBalance).toHaveBeenCalledTimes(4);
+    });
+
+    it('reads one last time at the deadline, then times out with the last balance read', async () => {
+      const readBalance = jest.fn(async () => 100n);
+
+      const waiting = waitForBalanceAbove(TOKEN, 100n, readBalance, 2500, 1000);
+      const failed = expect(waiting).rejects.toMatchObject({ code: 'BALANCE_WAIT_TIMEOUT', previous: 100n, lastBalance: 100n, timeoutMs: 2500 });
+      await jest.advanceTimersByTimeAsync(2500);
+
+      await failed;
+      await expect(waiting).rejects.toBeInstanceOf(BalanceWaitTimeoutError);
+      expect(readBalance).toHaveBeenCalledTimes(4); // 0, 1000, 2000 and 2500ms
+    });
+  });
+
+  describe('waitForConfirmations', () => {
+    const HASH = `0x${'34'.repeat(32)}`;
+
+    it('resolves with a successful receipt after the requested confirmations', async () => {
+      const wait = jest.fn(async () => ({ status: 1, blockNumber: 100 }));
+
+      await expect(waitForConfirmations(HASH, 3, wait)).resolves.toEqual({ status: 1, blockNumber: 100 });
+      expect(wait).toHaveBeenCalledWith(HASH, 3);
+    });
+
+    it('rejects a reverted receipt instead of returning it', async () => {
+      const receipt = { status: 0, blockNumber: 100 };
+      const waiting = waitForConfirmations(HASH, 3, async () => receipt);
+
+      await expect(waiting).rejects.toBeInstanceOf(TransactionFailedError);
+      await expect(waiting).rejects.toMatchObject({ txHash: HASH, details: { receipt } });
+    });
+
+    it('rejects when the wait comes back without a receipt', async () => {
+      await expect(waitForConfirmations(HASH, 2, async () => null)).rejects.toBeInstanceOf(TransactionFailedError);
+    });
+  });
+
+  describe('waitForConfirmationsOrDrop', () => {
+    beforeEach(() => jest.useFakeTimers());
+    afterEach(() => jest.useRealTimers());
+
+    const HASH = `0x${'12'.repeat(32)}`;
+
+    // Scripted chain: each poll takes the next receipt, pending flag and head
+    function scripted(steps: Array<{ receipt: { blockNumber: number } | null; pending?: boolean; head?: number }>) {
+      let step = steps[0];
+      let polls = 0;
+      const watch: InclusionWatch<{ blockNumber: number }> = {
+        getReceipt: async () => {
+          step = steps[Math.min(polls++, steps.length - 1)];
+          return step.receipt;
+        },
+        isPending: async () => step.pending ?? false,
+        getBlockNumber: async () => step.head ?? 0,
+      };
+      return { watch, polls: () => polls };
+    }
+
+    it('resolves once the receipt has enough confirmations', async () => {
+      const { watch, polls } = scripted([
+        { receipt: null, pending: true },
+        { receipt: { blockNumber: 100 }, head: 100 },
+        { receipt: { blockNumber: 100 }, head: 102 },
+      ]);
+
+      const waiting = waitForConfirmationsOrDrop(HASH, 3, watch, 1000);
+      await jest.advanceTimersByTimeAsync(2000);
+
+      await expect(waiting).resolves.toEqual({ blockNumber: 100 });
+      expect(polls()).toBe(3);
+    });
+
+    it('waits again for a transaction reorged back into the mempool', async () => {
+      const { watch } = scripted([
+        { receipt: { blockNumber: 100 }, head: 100 },
+        { receipt: null, pending: true },
+        { receipt: { blockNumber: 101 }, head: 102 },
+      ]);
+
+      const waiting = waitForConfirmationsOrDrop(HASH, 2, watch, 1000);
+      await jest.advanceTimersByTimeAsync(2000);
+
+      await expect(waiting).resolves.toEqual({ blockNumber: 101 });
+    });
+
+    it('rejects once the transaction is neither mined nor pending', async () => {
+      const { watch } = scripted([
+        { receipt: { blockNumber: 100 }, head: 100 },
+        { receipt: null, pending: false },
+      ]);
+
+      const waiting = waitForConfirmationsOrDrop(HASH, 2, watch, 1000);
+      const rejected = expect(waiting).rejects.toBeInstanceOf(TransactionDroppedError);
+      await jest.advanceTimersByTimeAsync(1000);
+
+      await rejected;
+      await expect(waiting).rejects.toMatchObject({ txHash: HASH, code: 'TRANSACTION_FAILED' });
+    });
+  });
+});
@@ -0,0 +1,118 @@
+import * as http from 'http';
+import * as net from 'net';
+import { AddressInfo } from 'net';
+import { createJsonRpcProvider, createFetchRequest } from '../../src/connection';
+
+describe('connection', () => {
+  let upstream: http.Server;
+  let proxy: http.Server;
+  let upstreamUrl: string;
+  let proxyUrl: string;
+  let seenHeaders: http.IncomingHttpHeaders[];
+  let tunnels: string[];
+  let sockets: net.Socket[];
+
+  beforeEach(async () => {
+    seenHeaders = [];
+    tunnels = [];
+    sockets = [];
+
+    // Upstream JSON-RPC stub answering every call with chain id 56
+    upstream = http.createServer((req, res) => {
+      seenHeaders.push(req.headers);
+      let body = '';
+      req.on('data', (chunk) => (body += chunk));
+      req.on('end', () => {
+        const payload = JSON.parse(body);
+        const reply = (request: { id: number }) => ({ jsonrpc: '2.0', id: request.id, result: '0x38' });
+        res.setHeader('content-type', 'application/json');
+        res.end(JSON.stringify(Array.isArray(payload) ? payload.map(reply) : reply(payload)));
+      });
+    });
+
+    // Minimal CONNECT proxy
+    proxy = http.createServer();
+    proxy.on('connect', (req, clientSocket: net.Socket, head) => {
+      tunnels.push(req.url!);
+      const [host, port] = req.url!.split(':');
+      const serverSocket = net.connect(Number(port), host, () => {
+        clientSocket.write('HTTP/1.1 200 Connection Established\r\n\r\n');
+        serverSocket.write(head);
+        serverSocket.pipe(clientSocket);
+        clientSocket.pipe(serverSocket);
+      });
+      sockets.push(clientSocket, serverSocket);
+    });
+
+    await new Promise<void>((resolve) => upstream.listen(0, '127.0.0.1', resolve));
+    await new Promise<void>((resolve) => proxy.listen(0, '127.0.0.1', resolve));
+    upstreamUrl = `http://127.0.0.1:${(upstream.address() as AddressInfo).port}`;
+    proxyUrl = `http://127.0.0.1:${(proxy.address() as AddressInfo).port}`;
+  });
+
+  afterEach(async () => {
+    sockets.forEach((socket) => socket.destroy());
+    upstream.closeAllConnections();
+    proxy.closeAllConnections();
+    await new Promise((resolve) => upstream.close(resolve));
+    await new Promise((resolve) => proxy.close(resolve));
+  });
+
+  it('should apply headers and the request timeout to fetch requests', () => {
+    const request = createFetchRequest(upstreamUrl, { headers: { 'x-api-key': 'secret' }, connectTimeout: 100, requestTimeout: 1234 });
+
+    expect(request.getHeader('x-api-key')).toBe('secret');
+    expect(request.timeout).toBe(1234);
+  });
+
+  it('should fail a proxy tunnel that is not established within the connect timeout', async () => {
+    // Proxy that accepts the CONNECT request but never answers it
+    const stalled = http.createServer();
+    stalled.on('connect', (_req, clientSocket: net.Socket) => sockets.push(clientSocket));
+    await new Promise<void>((resolve) => stalled.listen(0, '127.0.0.1', resolve));
+
+    try {
+      const request = createFetchRequest(upstreamUrl, {
+        proxyUrl: `http://127.0.0.1:${(stalled.address() as AddressInfo).port}`,
+        connectTimeout: 100,
+        requestTimeout: 60_000,
+      });
+      await expect(request.send()).rejects.toThrow('timed out after 100ms');
+    } finally {
+      sockets.forEach((socket) => socket.destroy());
+      await new Promise((resolve) => stalled.close(resolve));
+    }
+  });
+
+  it('should send custom headers through the proxy to the upstream', async () => {
+    const provider = createJsonRpcProvider(upstreamUrl, {
+      proxyUrl,
+      headers: { 'x-api-key': 'secret' },
+    });
+
+    try {
+      const chainId = await provider.send('eth_chainId', []);
+      expect(chainId).toBe('0x38');
+    } finally {
+      provider.destroy();
+    }
+
+    expect(tunnels.length).toBeGreaterThan(0);
+    expect(tunnels[0]).toBe(upstreamUrl.replace('http://', ''));
+    expect(seenHeaders.length).toBeGreaterThan(0);
+    expect(seenHeaders.every((headers) => headers['x-api-key'] === 'secret')).toBe(true);
+  });
+
+  it('should connect directly when no proxy is configured', async () => {
+    const provider = createJsonRpcProvider(upstreamUrl, { headers: { 'x-api-key': 'direct' } });
+
+    try {
+      await provider.send('eth_chainId', []);
+    } finally {
+      provider.destroy();
+    }
+
+    expect(tunnels).toEqual([]);
+    expect(seenHeaders[0]['x-api-key']).toBe('direct');
+  });
+});
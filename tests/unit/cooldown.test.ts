@@ -0,0 +1,53 @@
+import { cooldownProbeBlock, inferCooldown } from '../../src/cooldown';
+import { ValidationError } from '../../src/errors';
+
+const OFFSETS = [3, 10, 30, 60, 300, 900, 3600];
+
+// Simulated token that rejects transfers until `cooldown` seconds have passed
+function probeFor(cooldown: number) {
+  const delays: number[] = [];
+  const probe = async (delay: number) => {
+    delays.push(delay);
+    return delay >= cooldown;
+  };
+  return { probe, delays };
+}
+
+describe('inferCooldown', () => {
+  it('reports no cooldown when the transfer succeeds immediately', async () => {
+    const { probe, delays } = probeFor(0);
+    expect(await inferCooldown(probe, OFFSETS)).toBeNull();
+    expect(delays).toEqual([0]);
+  });
+
+  it('finds the exact cooldown to the second', async () => {
+    for (const cooldown of [1, 3, 7, 45, 61, 3600]) {
+      expect(await inferCooldown(probeFor(cooldown).probe, OFFSETS)).toBe(cooldown);
+    }
+  });
+
+  it('stops at the first succeeding offset before refining', async () => {
+    const { probe, delays } = probeFor(20);
+    await inferCooldown(probe, OFFSETS);
+    expect(delays.slice(0, 4)).toEqual([0, 3, 10, 30]);
+    expect(delays.every((delay) => delay <= 30)).toBe(true);
+  });
+
+  it('rejects tokens that never allow the transfer', async () => {
+    await expect(inferCooldown(async () => false, OFFSETS)).rejects.toBeInstanceOf(ValidationError);
+  });
+});
+
+describe('cooldownProbeBlock', () => {
+  const head = { number: 1000, timestamp: 1_700_000_000 };
+
+  it('advances the block number with the delay', () => {
+    expect(cooldownProbeBlock(head, 3000, 60)).toEqual({ number: 1020, timestamp: head.timestamp + 60 });
+    expect(cooldownProbeBlock(head, 750, 3600)).toEqual({ number: 5800, timestamp: head.timestamp + 3600 });
+  });
+
+  it('probes at least the block after the head', () => {
+    expect(cooldownProbeBlock(head, 3000, 0)).toEqual({ number: 1001, timestamp: head.timestamp });
+    expect(cooldownProbeBlock(head, 3000, 1)).toEqual({ number: 1001, timestamp: head.timestamp + 1 });
+  });
+});
@@ -0,0 +1,102 @@
+import { BlockTransaction, CreationBlockSource, analyzeCreationBlock } from '../../src/creationBlock';
+import { BackfillLog } from '../../src/backfill';
+import { getFourTradingInterface } from '../../src/abi';
+import { ValidationError } from '../../src/errors';
+
+const TOKEN = '0x1111111111111111111111111111111111111111';
+const OTHER_TOKEN = '0x2222222222222222222222222222222222222222';
+const CREATOR = '0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa';
+const INSIDER = '0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb';
+const SNIPER = '0xcccccccccccccccccccccccccccccccccccccccc';
+const BLOCK = 1000;
+const GWEI = 10n ** 9n;
+const E18 = 10n ** 18n;
+
+function log(name: string, args: unknown[], transactionHash: string): BackfillLog {
+  const encoded = getFourTradingInterface().encodeEventLog(name, args);
+  return { topics: encoded.topics, data: encoded.data, blockNumber: BLOCK, transactionHash };
+}
+
+const created = (token: string) =>
+  log('TokenCreate', [CREATOR, token, 1n, 'Token', 'TKN', 1000n * E18, 0n, 0n], '0xc0');
+const bought = (token: string, account: string, amount: bigint, txHash: string) =>
+  log('TokenPurchase', [token, account, 1n, amount, amount / 1000n, 0n, 0n, 0n], txHash);
+const tx = (hash: string, from: string, to: string | null, gwei: bigint, value: bigint = 0n): BlockTransaction =>
+  ({ hash, from, to, value, gasPrice: gwei * GWEI });
+
+function fakeSource(logs: BackfillLog[], blocks: Record<number, BlockTransaction[]>) {
+  const reads: number[] = [];
+  const source: CreationBlockSource = {
+    getBlockLogs: async () => logs,
+    getBlockTransactions: async (block) => {
+      reads.push(block);
+      return blocks[block] ?? [];
+    },
+  };
+  return { source, reads };
+}
+
+describe('analyzeCreationBlock', () => {
+  const creationBlock = [
+    tx('0xc0', CREATOR, TOKEN, 5n),
+    tx('0xc1', CREATOR, TOKEN, 5n),
+    tx('0xc2', INSIDER, TOKEN, 10n),
+    tx('0xc3', SNIPER, TOKEN, 3n),
+  ];
+
+  it('reports buyers, supply share, gas prices and one-hop creator funding', async () => {
+    const { source, reads } = fakeSource(
+      [
+        created(TOKEN),
+        bought(TOKEN, CREATOR, 100n * E18, '0xc1'),
+        bought(TOKEN, INSIDER, 200n * E18, '0xc2'),
+        bought(TOKEN, SNIPER, 50n * E18, '0xc3'),
+        bought(OTHER_TOKEN, SNIPER, 999n * E18, '0xc3'),
+      ],
+      {
+        [BLOCK]: creationBlock,
+        995: [tx('0xf1', CREATOR, INSIDER, 1n, E18)],
+        990: [tx('0xf2', CREATOR, SNIPER, 1n, E18)], // Beyond the lookback
+      }
+    );
+
+    const report = await analyzeCreationBlock(TOKEN, BLOCK, source, 5);
+
+    expect(report).toMatchObject({
+      distinctBuyers: 3,
+      tokensBought: 350n * E18,
+      supplyBps: 3500,
+      creatorBought: true,
+      creatorFundedBuyers: [INSIDER],
+      minGasPrice: 3n * GWEI,
+      maxGasPrice: 10n * GWEI,
+    });
+    expect(report.creator.toLowerCase()).toBe(CREATOR);
+    expect(report.buys.map((buy) => [buy.txHash, buy.byCreator, buy.fundedByCreator])).toEqual([
+      ['0xc1', true, false],
+      ['0xc2', false, true],
+      ['0xc3', false, false],
+    ]);
+    expect(reads).toEqual([BLOCK, 995, 996, 997, 998, 999]);
+  });
+
+  it('skips the funding search when only the creator bought', async () => {
+    const { source, reads } = fakeSource([created(TOKEN), bought(TOKEN, CREATOR, 100n * E18, '0xc1')], { [BLOCK]: creationBlock });
+
+    const report = await analyzeCreationBlock(TOKEN, BLOCK, source, 50);
+    expect(report).toMatchObject({ distinctBuyers: 1, creatorBought: true, creatorFundedBuyers: [] });
+    expect(reads).toEqual([BLOCK]);
+  });
+
+  it('reports an empty launch block', async () => {
+    const { source } = fakeSource([created(TOKEN)], {});
+
+    const report = await analyzeCreationBlock(TOKEN, BLOCK, source, 50);
+    expect(report).toMatchObject({ buys: [], distinctBuyers: 0, supplyBps: 0, creatorBought: false, minGasPrice: null });
+  });
+
+  it('rejects a block without the token\'s creation', async () => {
+    const { source } = fakeSource([created(OTHER_TOKEN)], {});
+    await expect(analyzeCreationBlock(TOKEN, BLOCK, source, 50)).rejects.toThrow(ValidationError);
+  });
+});
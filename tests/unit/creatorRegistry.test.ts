@@ -0,0 +1,146 @@
+import { promises as fs } from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { CreatorRegistry, JsonFileCreatorRegistryStore, MemoryCreatorRegistryStore } from '../../src/creatorRegistry';
+import { FundingHop } from '../../src/fundingTrace';
+
+const DAY = 86_400;
+const CREATOR = '0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa';
+const SIBLING = '0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb';
+const FUNDER = '0xcccccccccccccccccccccccccccccccccccccccc';
+const SOURCE = '0xdddddddddddddddddddddddddddddddddddddddd';
+
+const token = (n: number) => `0x${n.toString(16).padStart(40, '0')}`;
+const found = (address: string, funder: string, block = 100): FundingHop =>
+  ({ address, status: 'found', funder, block, txHash: `0x${block.toString(16)}` });
+
+function registry(options: ConstructorParameters<typeof CreatorRegistry>[0] = {}) {
+  const clock = { now: 1_000 * DAY };
+  return { registry: new CreatorRegistry({ deadAfterSeconds: DAY, ...options }, () => clock.now), clock };
+}
+
+describe('CreatorRegistry outcomes', () => {
+  it('classifies graduated, dumped, dead and active tokens', () => {
+    const { registry: r, clock } = registry();
+    [1, 2, 3, 4].forEach((n) => r.recordCreation(token(n), CREATOR));
+    r.recordGraduation(token(1));
+    r.recordTrade(token(2), CREATOR.replace(/a/g, 'A'), 'sell');
+    r.recordTrade(token(3), SIBLING, 'sell');
+    clock.now += DAY;
+    r.recordTrade(token(4), SIBLING, 'buy');
+
+    const outcomes = r.tokensOf(CREATOR).map((record) => r.outcome(record));
+    expect(outcomes).toEqual(['graduated', 'dumped', 'dead', 'active']);
+  });
+
+  it('ignores creator sells after graduation', () => {
+    const { registry: r } = registry();
+    r.recordCreation(token(1), CREATOR);
+    r.recordGraduation(token(1));
+    r.recordTrade(token(1), CREATOR, 'sell');
+
+    expect(r.outcome(r.tokensOf(CREATOR)[0])).toBe('graduated');
+  });
+});
+
+describe('CreatorRegistry reports', () => {
+  it('scores 50 without resolved history and smooths toward the observed rate', () => {
+    const { registry: r, clock } = registry();
+    expect(r.report(CREATOR, []).score).toBe(50);
+
+    [1, 2, 3].forEach((n) => r.recordCreation(token(n), CREATOR));
+    r.recordGraduation(token(1));
+    clock.now += DAY;
+
+    // (1 + 1) / (3 + 2)
+    expect(r.report(CREATOR, []).score).toBe(40);
+  });
+
+  it('counts creators sharing a funder at half weight', () => {
+    const { registry: r, clock } = registry();
+    r.recordCreation(token(1), SIBLING);
+    r.recordCreation(token(2), SIBLING);
+    r.recordFunding(found(SIBLING, FUNDER));
+    r.recordFunding(found(CREATOR, FUNDER));
+    clock.now += DAY;
+
+    const report = r.report(CREATOR, [found(CREATOR, FUNDER)]);
+
+    expect(report.cluster).toMatchObject({ creators: [SIBLING], tokens: 2, dead: 2 });
+    expect(report.clusterFunders).toEqual([FUNDER]);
+    // (0 + 1) / (1 + 2)
+    expect(report.score).toBe(33);
+  });
+
+  it('reaches creators funded by a sibling of the second-hop funder', () => {
+    const { registry: r } = registry();
+    const middle = '0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee';
+    r.recordCreation(token(1), SIBLING);
+    r.recordGraduation(token(1));
+    r.recordFunding(found(middle, SOURCE));
+    r.recordFunding(found(SIBLING, middle));
+
+    const trace = [found(CREATOR, FUNDER, 200), found(FUNDER, SOURCE, 150)];
+    expect(r.report(CREATOR, trace).cluster.creators).toEqual([SIBLING]);
+    expect(r.report(CREATOR, trace.slice(0, 1)).cluster.creators).toEqual([]);
+  });
+
+  it('skips funders of more wallets than the hub size', () => {
+    const { registry: r } = registry({ hubSize: 2 });
+    r.recordCreation(token(1), SIBLING);
+    [SIBLING, token(100), token(101)].forEach((wallet) => r.recordFunding(found(wallet, FUNDER)));
+
+    const report = r.report(CREATOR, [found(CREATOR, FUNDER)]);
+
+    expect(report.hubFunders).toEqual([FUNDER]);
+    expect(report.cluster.creators).toEqual([]);
+  });
+});
+
+describe('CreatorRegistry storage', () => {
+  it('drops the oldest tokens beyond maxTokens', () => {
+    const { registry: r } = registry({ maxTokens: 2 });
+    [1, 2, 3].forEach((n) => r.recordCreation(token(n), CREATOR));
+
+    expect(r.tokensOf(CREATOR).map((record) => record.token)).toEqual([token(2), token(3)]);
+  });
+
+  it('keeps only funding hops that never change', () => {
+    const { registry: r } = registry();
+    r.recordFunding({ address: CREATOR, status: 'beforeLookback', funder: null, block: null, txHash: null });
+    r.recordFunding({ address: SIBLING, status: 'internal', funder: null, block: 5, txHash: null });
+
+    expect(r.fundingOf(CREATOR)).toBeUndefined();
+    expect(r.fundingOf(SIBLING)?.block).toBe(5);
+  });
+
+  it('restores tokens and funding from a memory store', async () => {
+    const store = new MemoryCreatorRegistryStore();
+    const first = registry({ store }).registry;
+    first.recordCreation(token(1), CREATOR);
+    first.recordFunding(found(CREATOR, FUNDER));
+    await first.flush();
+
+    const second = registry({ store }).registry;
+    await second.load();
+
+    expect(second.snapshot()).toEqual(first.snapshot());
+  });
+
+  it('persists to a JSON file', async () => {
+    const dir = await fs.mkdtemp(path.join(os.tmpdir(), 'creators-'));
+    const store = new JsonFileCreatorRegistryStore(path.join(dir, 'creators.json'));
+    expect(await store.load()).toBeNull();
+
+    const first = registry({ store }).registry;
+    first.recordCreation(token(1), CREATOR);
+    first.recordGraduation(token(1));
+    await first.flush();
+
+    const second = registry({ store }).registry;
+    await second.load();
+
+    expect(second.tokensOf(CREATOR)).toEqual(first.tokensOf(CREATOR));
+    expect(await fs.readdir(dir)).toEqual(['creators.json']);
+  });
+});
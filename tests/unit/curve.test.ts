@@ -0,0 +1,237 @@
+import { buySizeForAvgPrice, curveParams, curveBuyAmount, curveSellCost, calcBuyAtReserves, launchTemplate, simulateBuy } from '../../src/curve';
+import { FeeExceedsAmountError, TargetPriceUnreachableError } from '../../src/errors';
+import { Template, TokenInfo } from '../../src/types';
+
+const ONE = 10n ** 18n;
+const TOKEN = '0x1111111111111111111111111111111111111111';
+
+// Launch-like parameters: 1.07B virtual supply over an 800M offer
+const T = 1_073_000_000n * ONE;
+const K = 6_675_000_000n * ONE * ONE;
+const MAX_OFFERS = 800_000_000n * ONE;
+
+function info(sold: bigint, funds: bigint): TokenInfo {
+  return {
+    base: TOKEN,
+    quote: '0x0000000000000000000000000000000000000000',
+    template: 0n,
+    totalSupply: 1_000_000_000n * ONE,
+    maxOffers: MAX_OFFERS,
+    maxRaising: 24n * ONE,
+    launchTime: 1700000000n,
+    offers: MAX_OFFERS - sold,
+    funds,
+    lastPrice: 0n,
+    K,
+    T,
+    status: 0n,
+  };
+}
+
+describe('curveParams', () => {
+  test('derives virtual reserves that satisfy the invariant', () => {
+    const fresh = curveParams(TOKEN, info(0n, 0n));
+    expect(fresh.sold).toBe(0n);
+    expect(fresh.virtualTokenReserve).toBe(T);
+    expect(fresh.virtualQuoteReserve).toBe(K / T);
+
+    const bought = curveBuyAmount(fresh, ONE);
+    const after = curveParams(TOKEN, info(bought, ONE));
+    const product = after.virtualTokenReserve * after.virtualQuoteReserve;
+    // Rounding only: the product stays within one reserve unit of K
+    expect(product >= K).toBe(true);
+    expect(product - K <= after.virtualQuoteReserve + after.virtualTokenReserve).toBe(true);
+  });
+
+  test('clamps sold at zero when offers exceed maxOffers', () => {
+    const params = curveParams(TOKEN, { ...info(0n, 0n), offers: MAX_OFFERS + 1n });
+    expect(params.sold).toBe(0n);
+  });
+});
+
+describe('curve quotes', () => {
+  test('a round trip never returns more than was paid', () => {
+    const fresh = curveParams(TOKEN, info(0n, 0n));
+    const priorBuy = curveBuyAmount(fresh, 3n * ONE);
+    const params = curveParams(TOKEN, info(priorBuy, 3n * ONE));
+    const tokens = curveBuyAmount(params, ONE);
+    const afterBuy = curveParams(TOKEN, info(params.sold + tokens, params.funds + ONE));
+
+    const back = curveSellCost(afterBuy, tokens);
+    expect(back <= ONE).toBe(true);
+    expect(ONE - back < 1000n).toBe(true);
+  });
+
+  test('later buys get fewer tokens for the same funds', () => {
+    const early = curveBuyAmount(curveParams(TOKEN, info(0n, 0n)), ONE);
+    const sold = curveBuyAmount(curveParams(TOKEN, info(0n, 0n)), 10n * ONE);
+    const late = curveBuyAmount(curveParams(TOKEN, info(sold, 10n * ONE)), ONE);
+    expect(late < early).toBe(true);
+  });
+
+  test('zero inputs quote zero', () => {
+    const params = curveParams(TOKEN, info(0n, 0n));
+    expect(curveBuyAmount(params, 0n)).toBe(0n);
+    expect(curveSellCost(params, 0n)).toBe(0n);
+  });
+});
+
+describe('calcBuyAtReserves', () => {
+  test('matches the curve quote after the default 1% fee', () => {
+    const params = curveParams(TOKEN, info(0n, 0n));
+    const quote = calcBuyAtReserves(params, ONE);
+    expect(quote.fee).toBe(ONE / 100n);
+    expect(quote.bnbCost).toBe(ONE);
+    expect(quote.tokenAmount).toBe(quote.netTokenAmount);
+    expect(quote.grossTokenAmount! > quote.tokenAmount).toBe(true);
+    // K is rebuilt from the reserves, which a fresh curve carries with K / T truncated
+    const diff = quote.tokenAmount - curveBuyAmount(params, ONE - quote.fee);
+    expect(diff < 0n ? -diff : diff).toBeLessThan(ONE);
+  });
+
+  test('a deeper quote reserve gets fewer tokens for the same spend', () => {
+    const reserves = { virtualTokenReserve: T, virtualQuoteReserve: 6n * ONE };
+    const base = calcBuyAtReserves(reserves, ONE);
+    const stressed = calcBuyAtReserves({ ...reserves, virtualQuoteReserve: 12n * ONE }, ONE);
+    expect(stressed.tokenAmount < base.tokenAmount).toBe(true);
+  });
+
+  test('honours a custom fee and rejects one that swallows the spend', () => {
+    const reserves = { virtualTokenReserve: T, virtualQuoteReserve: 6n * ONE };
+    expect(calcBuyAtReserves(reserves, ONE, 0).tokenAmount).toBe(calcBuyAtReserves(reserves, ONE, 0).grossTokenAmount);
+    expect(() => calcBuyAtReserves(reserves, ONE, 10000)).toThrow(FeeExceedsAmountError);
+  });
+});
+
+describe('simulateBuy', () => {
+  test('a buy within the cap leaves the curve open', () => {
+    const params = curveParams(TOKEN, info(0n, 0n));
+    const sim = simulateBuy(params, ONE);
+    expect(sim.fee).toBe(ONE / 100n);
+    expect(sim.refund).toBe(0n);
+    expect(sim.tokensOut).toBe(curveBuyAmount(params, ONE - sim.fee));
+    expect(sim.triggersGraduation).toBe(false);
+    expect(sim.fundsAfter).toBe(ONE - sim.fee);
+    expect(sim.remainingFunds).toBe(24n * ONE - sim.fundsAfter);
+    expect(sim.remainingOffers).toBe(MAX_OFFERS - sim.tokensOut);
+    expect(sim.reservesAfter.virtualTokenReserve).toBe(T - sim.tokensOut);
+    expect(sim.progressBps).toBe(41); // 0.99 of 24 BNB
+  });
+
+  test('a buy past the cap graduates the token and refunds the excess', () => {
+    const params = curveParams(TOKEN, info(0n, 20n * ONE));
+    const sim = simulateBuy(params, 10n * ONE);
+    expect(sim.triggersGraduation).toBe(true);
+    expect(sim.fundsAfter).toBe(24n * ONE);
+    expect(sim.remainingFunds).toBe(0n);
+    // 4 of the 9.9 BNB after fee fill the cap; the fee scales to match
+    expect(sim.fee).toBe((ONE / 10n * 4n * ONE) / (99n * ONE / 10n));
+    expect(sim.bnbUsed).toBe(4n * ONE + sim.fee);
+    expect(sim.refund).toBe(10n * ONE - sim.bnbUsed);
+    expect(sim.progressBps).toBe(10000);
+  });
+
+  test('a buy that exactly fills the cap graduates the token', () => {
+    const sim = simulateBuy(curveParams(TOKEN, info(0n, 23n * ONE)), ONE, 0);
+    expect(sim.refund).toBe(0n);
+    expect(sim.remainingFunds).toBe(0n);
+    expect(sim.triggersGraduation).toBe(true);
+  });
+
+  test('a full curve takes nothing', () => {
+    const sim = simulateBuy(curveParams(TOKEN, info(0n, 24n * ONE)), ONE);
+    expect(sim.tokensOut).toBe(0n);
+    expect(sim.refund).toBe(ONE);
+    expect(sim.fee).toBe(0n);
+    expect(sim.triggersGraduation).toBe(false);
+  });
+});
+
+describe('launchTemplate', () => {
+  const template: Template = {
+    quote: '0x0000000000000000000000000000000000000000',
+    initialLiquidity: 200_000_000n * ONE,
+    maxRaising: 18n * ONE,
+    totalSupply: 1_000_000_000n * ONE,
+    maxOffers: MAX_OFFERS,
+    minTradingFee: ONE / 1000n,
+  };
+
+  test('takes the curve from the token and the fees from its template', () => {
+    const launch = launchTemplate(TOKEN, { ...info(5n * ONE, ONE), template: 2n }, template);
+
+    expect(launch).toMatchObject({ token: TOKEN, templateId: 2n, maxRaising: 24n * ONE, maxOffers: MAX_OFFERS, K, T });
+    expect(launch.initialLiquidity).toBe(200_000_000n * ONE);
+    expect(launch.minTradingFee).toBe(ONE / 1000n);
+  });
+
+  test('prices the curve at launch and at graduation regardless of trading since', () => {
+    const launch = launchTemplate(TOKEN, info(MAX_OFFERS / 2n, 10n * ONE), template);
+    const left = T - MAX_OFFERS;
+
+    expect(launch.initialPrice).toBe((K * ONE) / (T * T));
+    expect(launch.graduationPrice).toBe((K * ONE) / (left * left));
+    expect(launch.graduationPrice > launch.initialPrice).toBe(true);
+  });
+
+  test('has no graduation price when the curve offers its whole virtual supply', () => {
+    expect(launchTemplate(TOKEN, { ...info(0n, 0n), maxOffers: T }, template).graduationPrice).toBe(0n);
+  });
+});
+
+describe('buySizeForAvgPrice', () => {
+  // Average of a vanishing buy: the current price with the 1% fee
+  const startPrice = (params: ReturnType<typeof curveParams>) =>
+    (params.virtualQuoteReserve * 10000n * ONE) / (params.virtualTokenReserve * 9900n);
+
+  test('solves for the spend whose fill averages the target, to the wei', () => {
+    for (const params of [curveParams(TOKEN, info(0n, 0n)), curveParams(TOKEN, info(300_000_000n * ONE, 5n * ONE))]) {
+      for (const multiple of [101n, 150n, 200n]) {
+        const target = (startPrice(params) * multiple) / 100n;
+        const spend = buySizeForAvgPrice(params, target);
+        const fill = calcBuyAtReserves(params, spend);
+
+        expect(fill.pricePerToken).toBeLessThanOrEqual(target);
+        expect(target - fill.pricePerToken).toBeLessThanOrEqual(1n);
+      }
+    }
+  });
+
+  test('spends more for a higher average', () => {
+    const params = curveParams(TOKEN, info(0n, 0n));
+    const cheap = buySizeForAvgPrice(params, (startPrice(params) * 110n) / 100n);
+    const dear = buySizeForAvgPrice(params, (startPrice(params) * 120n) / 100n);
+
+    expect(dear > cheap).toBe(true);
+  });
+
+  test('solves without a fee', () => {
+    const params = curveParams(TOKEN, info(0n, 0n));
+    const target = (params.virtualQuoteReserve * 2n * ONE) / params.virtualTokenReserve;
+    const fill = calcBuyAtReserves(params, buySizeForAvgPrice(params, target, 0), 0);
+
+    expect(target - fill.pricePerToken).toBeLessThanOrEqual(1n);
+  });
+
+  test('rejects averages no buy on the curve reaches', () => {
+    const params = curveParams(TOKEN, info(0n, 0n));
+    const min = startPrice(params);
+    const max = ((params.virtualQuoteReserve + 24n * ONE) * 10000n * ONE) / (params.virtualTokenReserve * 9900n);
+
+    expect(() => buySizeForAvgPrice(params, min)).toThrow(TargetPriceUnreachableError);
+    expect(() => buySizeForAvgPrice(params, max + 1n)).toThrow(TargetPriceUnreachableError);
+    expect(buySizeForAvgPrice(params, max) > 0n).toBe(true);
+    let caught: unknown;
+    try {
+      buySizeForAvgPrice(params, min / 2n);
+    } catch (error) {
+      caught = error;
+    }
+    expect(caught).toMatchObject({ code: 'TARGET_PRICE_UNREACHABLE', minAvgPrice: min, maxAvgPrice: max });
+  });
+
+  test('rejects a full curve', () => {
+    const params = curveParams(TOKEN, info(0n, 24n * ONE));
+    expect(() => buySizeForAvgPrice(params, startPrice(params) * 2n)).toThrow(TargetPriceUnreachableError);
+  });
+});
@@ -0,0 +1,58 @@
+import { CurveFractionError, InvalidAmountError, ValidationError } from '../../src/errors';
+import { curveFractionAmount, realizedCurveFraction, resolveCurveFraction } from '../../src/curveFraction';
+
+const ONE = 10n ** 18n;
+
+describe('resolveCurveFraction', () => {
+  it('passes fractions within the limit through', () => {
+    expect(resolveCurveFraction(200, 10000, false)).toEqual({ bps: 200, clamped: false });
+    expect(resolveCurveFraction(500, 500, false)).toEqual({ bps: 500, clamped: false });
+  });
+
+  it('throws above the limit unless clamping', () => {
+    expect(() => resolveCurveFraction(501, 500, false)).toThrow(CurveFractionError);
+    expect(resolveCurveFraction(501, 500, true)).toEqual({ bps: 500, clamped: true });
+    expect(resolveCurveFraction(20000, 10000, true)).toEqual({ bps: 10000, clamped: true });
+  });
+
+  it('rejects fractions that are not positive whole basis points', () => {
+    for (const bps of [0, -1, 1.5, NaN]) {
+      expect(() => resolveCurveFraction(bps, 10000, true)).toThrow(ValidationError);
+    }
+  });
+
+  it('rejects a limit outside 1-10000', () => {
+    expect(() => resolveCurveFraction(1, 0, false)).toThrow(/maxCurveFractionBps/);
+    expect(() => resolveCurveFraction(1, 10001, false)).toThrow(/maxCurveFractionBps/);
+  });
+});
+
+describe('curveFractionAmount', () => {
+  it('takes the share of the remaining supply, rounded down', () => {
+    expect(curveFractionAmount(800_000_000n * ONE, 200)).toBe(16_000_000n * ONE);
+    expect(curveFractionAmount(10_001n, 5000)).toBe(5_000n);
+  });
+
+  it('spans the remaining supply exactly at 10000 bps', () => {
+    const offers = 123_456_789_123_456_789n;
+    expect(curveFractionAmount(offers, 10000)).toBe(offers);
+  });
+
+  it('throws when the share rounds to zero tokens', () => {
+    expect(() => curveFractionAmount(9_999n, 1)).toThrow(InvalidAmountError);
+    expect(() => curveFractionAmount(0n, 10000)).toThrow(/rounds to zero/);
+    expect(curveFractionAmount(10_000n, 1)).toBe(1n);
+  });
+});
+
+describe('realizedCurveFraction', () => {
+  it('reports the bought share in basis points, rounded down', () => {
+    expect(realizedCurveFraction(16_000_000n * ONE, 800_000_000n * ONE)).toBe(200);
+    expect(realizedCurveFraction(1n, 3n)).toBe(3333);
+    expect(realizedCurveFraction(7n, 7n)).toBe(10000);
+  });
+
+  it('is zero for an empty curve', () => {
+    expect(realizedCurveFraction(0n, 0n)).toBe(0);
+  });
+});
@@ -0,0 +1,157 @@
+import * as fs from 'fs';
+import * as path from 'path';
+
+const SRC = path.join(__dirname, '../../src');
+
+// Modules that connect, subscribe, sign or persist; the slim entry points must not load them
+const HEAVY = ['fourTrading.ts', 'connection.ts', 'websocketManager.ts', 'positionStore.ts', 'traderPool.ts', 'blocking/', 'recordReplay/', 'testing/'];
+
+/**
+ * Every module `entry` loads at runtime, following imports and re-exports but not `import type`,
+ * which the compiler erases. Packages are listed by name.
+ */
+function runtimeGraph(entry: string): Set<string> {
+  const seen = new Set<string>();
+  const pending = [path.join(SRC, entry)];
+  while (pending.length > 0) {
+    const file = pending.pop()!;
+    if (seen.has(file)) {
+      continue;
+    }
+    seen.add(file);
+    const source = fs.readFileSync(file, 'utf8');
+    for (const match of source.matchAll(/^(?:import|export)\s+(type\s+)?[^;]*?from\s+'([^']+)'/gms)) {
+      const [, typeOnly, specifier] = match;
+      if (typeOnly) {
+        continue;
+      }
+      if (!specifier.startsWith('.')) {
+        seen.add(specifier);
+        continue;
+      }
+      const target = path.join(path.dirname(file), specifier);
+      pending.push(fs.existsSync(`${target}.ts`) ? `${target}.ts` : path.join(target, 'index.ts'));
+    }
+  }
+  return new Set([...seen].map((file) => (path.isAbsolute(file) ? path.relative(SRC, file).split(path.sep).join('/') : file)));
+}
+
+function heavyModules(graph: Set<string>, allowed: string[] = []): string[] {
+  return [...graph].filter((file) => HEAVY.some((heavy) => file.startsWith(heavy) && !allowed.includes(heavy)));
+}
+
+function packages(graph: Set<string>): string[] {
+  return [...graph].filter((file) => !file.endsWith('.ts')).sort();
+}
+
+describe('entry points', () => {
+  it('keeps core free of providers, signing, storage and quotes', () => {
+    const graph = runtimeGraph('core/index.ts');
+
+    expect(heavyModules(graph)).toEqual([]);
+    expect([...graph].filter((file) => !file.endsWith('.ts'))).toEqual(['ethers']);
+    expect(graph.has('priceCalculator.ts')).toBe(false);
+    expect(graph.has('multicall.ts')).toBe(false);
+  });
+
+  it('keeps quotes free of subscriptions, signing and storage', () => {
+    const graph = runtimeGraph('quotes/index.ts');
+
+    expect(heavyModules(graph)).toEqual([]);
+    expect([...graph].filter((file) => !file.endsWith('.ts'))).toEqual(['ethers']);
+    expect(graph.has('priceCalculator.ts')).toBe(true);
+  });
+
+  it('keeps trading free of event streams, storage and the other entry points', () => {
+    const graph = runtimeGraph('trading/index.ts');
+
+    expect(heavyModules(graph, ['fourTrading.ts', 'connection.ts', 'traderPool.ts'])).toEqual([]);
+    expect(graph.has('priceCalculator.ts')).toBe(true);
+  });
+
+  it('keeps events free of the trader and storage', () => {
+    const graph = runtimeGraph('events/index.ts');
+
+    expect(heavyModules(graph, ['connection.ts', 'websocketManager.ts'])).toEqual([]);
+    expect(graph.has('strategy.ts')).toBe(true);
+  });
+
+  it('keeps relay free of connections, keys and the filesystem', () => {
+    const graph = runtimeGraph('relay/index.ts');
+
+    expect(heavyModules(graph)).toEqual([]);
+    expect(packages(graph)).toEqual(['ethers']);
+  });
+
+  it('keeps storage free of connections and the trader', () => {
+    const graph = runtimeGraph('storage/index.ts');
+
+    expect(heavyModules(graph, ['positionStore.ts'])).toEqual([]);
+    expect(packages(graph)).toEqual(['ethers', 'fs', 'path']);
+  });
+
+  it('keeps api free of connections, the trader and storage', () => {
+    const graph = runtimeGraph('api/index.ts');
+
+    expect(heavyModules(graph)).toEqual([]);
+    expect(packages(graph)).toEqual(['ethers', 'fs']);
+  });
+
+  it('loads each slim entry point on its own', () => {
+    jest.isolateModules(() => {
+      const core = require('../../src/core');
+      expect(typeof core.encodeBuyCalldata).toBe('function');
+      expect(typeof core.simulateBuy).toBe('function');
+      expect(core.PriceCalculator).toBeUndefined();
+    });
+    jest.isolateModules(() => {
+      const quotes = require('../../src/quotes');
+      expect(typeof quotes.PriceCalculator).toBe('function');
+      expect(typeof quotes.encodeBuyCalldata).toBe('function');
+    });
+    jest.isolateModules(() => {
+      const trading = require('../../src/trading');
+      expect(typeof trading.FourTrading).toBe('function');
+      expect(typeof trading.PriceCalculator).toBe('function');
+    });
+    jest.isolateModules(() => {
+      const events = require('../../src/events');
+      expect(typeof events.CursorStream).toBe('function');
+      expect(events.FourTrading).toBeUndefined();
+    });
+    jest.isolateModules(() => {
+      const relay = require('../../src/relay');
+      expect(typeof relay.replacementFees).toBe('function');
+      expect(typeof relay.encodeBuyCalldata).toBe('function');
+    });
+    jest.isolateModules(() => {
+      const storage = require('../../src/storage');
+      expect(typeof storage.JsonFilePositionStore).toBe('function');
+      expect(typeof storage.traderStateFromJson).toBe('function');
+    });
+    jest.isolateModules(() => {
+      const api = require('../../src/api');
+      expect(typeof api.TokenDirectory).toBe('function');
+      expect(typeof api.computeHolderStats).toBe('function');
+    });
+  });
+
+  it('maps every subpath export to an entry point in src', () => {
+    const pkg = JSON.parse(fs.readFileSync(path.join(SRC, '../package.json'), 'utf8'));
+    const subpaths = Object.entries(pkg.exports as Record<string, any>).filter(([key]) => key !== '.' && !key.includes('*') && !key.endsWith('.json'));
+
+    expect(subpaths.map(([key]) => key)).toEqual(['./core', './quotes', './trading', './events', './relay', './storage', './api', './blocking', './record-replay', './wire', './testing']);
+    for (const [, target] of subpaths) {
+      const source = target.default.replace('./dist/', '').replace(/\.js$/, '.ts');
+      expect(fs.existsSync(path.join(SRC, source))).toBe(true);
+    }
+  });
+
+  it('leaves the main entry point exporting everything', () => {
+    const main = require('../../src');
+
+    expect(typeof main.FourTrading).toBe('function');
+    expect(typeof main.PriceCalculator).toBe('function');
+    expect(typeof main.encodeBuyCalldata).toBe('function');
+  });
+});
@@ -0,0 +1,225 @@
+import { promises as fs } from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import {
+  CursorEvent,
+  CursorEventSource,
+  CursorLog,
+  CursorStream,
+  CursorStreamOptions,
+  CursorStore,
+  JsonFileCursorStore,
+  MemoryCursorStore,
+  eventId,
+  parseTokenCreateLog,
+} from '../../src/eventCursor';
+import { getFourTradingInterface } from '../../src/abi';
+import { TokenCreateEvent } from '../../src/types';
+
+const CREATOR = '0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa';
+const token = (n: number) => `0x${n.toString(16).padStart(40, '0')}`;
+
+/**
+ * A chain of TokenCreate logs, `perBlock` per block from block 100, with a head and live watchers
+ */
+class FakeChain {
+  logs: CursorLog[] = [];
+  head = 99;
+  watchers: Set<(log: CursorLog) => void> = new Set();
+  onGetLogs?: () => void;
+
+  mineBlock(count: number, emit = true): CursorLog[] {
+    this.head += 1;
+    const mined = Array.from({ length: count }, (_, index) => {
+      const n = this.logs.length + 1;
+      const encoded = getFourTradingInterface().encodeEventLog('TokenCreate', [CREATOR, token(n), BigInt(n), `Token ${n}`, `T${n}`, 10n ** 27n, 0n, 0n]);
+      const log = { ...encoded, blockNumber: this.head, transactionHash: `0x${n.toString(16).padStart(64, '0')}`, index };
+      this.logs.push(log);
+      return log;
+    });
+    if (emit) {
+      mined.forEach((log) => this.emit(log));
+    }
+    return mined;
+  }
+
+  emit(log: CursorLog): void {
+    this.watchers.forEach((watcher) => watcher(log));
+  }
+
+  source(): CursorEventSource {
+    return {
+      getBlockNumber: async () => this.head,
+      getLogs: async (fromBlock, toBlock) => {
+        this.onGetLogs?.();
+        return this.logs.filter((log) => log.blockNumber >= fromBlock && log.blockNumber <= toBlock);
+      },
+      watch: (listener) => {
+        this.watchers.add(listener);
+        return () => this.watchers.delete(listener);
+      },
+    };
+  }
+}
+
+function stream(
+  chain: FakeChain,
+  store: CursorStore,
+  listener: (event: CursorEvent<TokenCreateEvent>) => void | Promise<void>,
+  options: CursorStreamOptions = { fromBlock: 100, pageSize: 2 }
+) {
+  return new CursorStream(chain.source(), store, 'creations', parseTokenCreateLog, listener, options);
+}
+
+const flush = () => new Promise((resolve) => setImmediate(resolve));
+const tokensOf = (events: Array<CursorEvent<TokenCreateEvent>>) => events.map((event) => event.token);
+
+describe('CursorStream', () => {
+  it('backfills from the start block, then delivers live logs in order', async () => {
+    const chain = new FakeChain();
+    [2, 1, 3].forEach((count) => chain.mineBlock(count, false));
+    const received: Array<CursorEvent<TokenCreateEvent>> = [];
+
+    const s = stream(chain, new MemoryCursorStore(), (event) => {
+      received.push(event);
+    });
+    await s.start();
+    chain.mineBlock(2);
+    await flush();
+
+    expect(tokensOf(received)).toEqual([1, 2, 3, 4, 5, 6, 7, 8].map(token));
+    expect(received[2]).toMatchObject({ blockNumber: 101, logIndex: 0, eventId: eventId(chain.logs[2].transactionHash, 0) });
+    expect(s.cursor).toEqual({ blockNumber: 103, logIndex: 1 });
+    await s.stop();
+  });
+
+  it('delivers every event exactly once across a crash mid-backfill', async () => {
+    const chain = new FakeChain();
+    for (let i = 0; i < 6; i++) {
+      chain.mineBlock(2, false);
+    }
+    const store = new MemoryCursorStore();
+    const received: string[] = [];
+
+    const crashing = stream(chain, store, (event) => {
+      if (received.length === 5) {
+        throw new Error('crash');
+      }
+      received.push(event.token);
+    });
+    await expect(crashing.start()).rejects.toThrow('crash');
+    expect(chain.watchers.size).toBe(0);
+
+    chain.mineBlock(1, false); // Mined while down
+    const restarted = stream(chain, store, (event) => {
+      received.push(event.token);
+    });
+    await restarted.start();
+    chain.mineBlock(1);
+    await flush();
+
+    expect(received).toEqual(chain.logs.map((_, i) => token(i + 1)));
+    await restarted.stop();
+  });
+
+  it('redelivers an event whose cursor save was lost, with the same event id', async () => {
+    const chain = new FakeChain();
+    [2, 2].forEach((count) => chain.mineBlock(count, false));
+    const inner = new MemoryCursorStore();
+    let saves = 0;
+    const store: CursorStore = {
+      load: (key) => inner.load(key),
+      save: async (key, state) => {
+        if (++saves === 3) {
+          throw new Error('disk full');
+        }
+        await inner.save(key, state);
+      },
+    };
+    const ids: string[] = [];
+
+    await expect(stream(chain, store, (event) => { ids.push(event.eventId); }).start()).rejects.toThrow('disk full');
+    await stream(chain, store, (event) => { ids.push(event.eventId); }).start();
+
+    expect(ids).toHaveLength(5);
+    expect([...new Set(ids)]).toEqual(chain.logs.map((log) => eventId(log.transactionHash, log.index)));
+  });
+
+  it('delivers logs seen live during the backfill once', async () => {
+    const chain = new FakeChain();
+    chain.mineBlock(2, false);
+    const received: string[] = [];
+    let raced = false;
+    chain.onGetLogs = () => {
+      if (!raced) {
+        raced = true;
+        chain.emit(chain.logs[1]); // Also in the backfill range
+        chain.mineBlock(1); // Past the backfill's head
+      }
+    };
+
+    const s = stream(chain, new MemoryCursorStore(), (event) => {
+      received.push(event.token);
+    }, { fromBlock: 100 });
+    await s.start();
+
+    expect(received).toEqual([1, 2, 3].map(token));
+    await s.stop();
+  });
+
+  it('skips events remembered or older than the ring when re-fetching an overlap', async () => {
+    const chain = new FakeChain();
+    for (let i = 0; i < 4; i++) {
+      chain.mineBlock(1, false);
+    }
+    const store = new MemoryCursorStore();
+    const options = { fromBlock: 100, overlapBlocks: 10, ringSize: 2 };
+    await stream(chain, store, () => undefined, options).start();
+    expect((await store.load('creations'))!.recent.map((entry) => entry.blockNumber)).toEqual([102, 103]);
+
+    chain.mineBlock(1, false);
+    const received: string[] = [];
+    await stream(chain, store, (event) => {
+      received.push(event.token);
+    }, options).start();
+
+    expect(received).toEqual([token(5)]);
+  });
+
+  it('stops and reports a failing live delivery', async () => {
+    const chain = new FakeChain();
+    const onError = jest.fn();
+    const s = stream(chain, new MemoryCursorStore(), () => {
+      throw new Error('consumer down');
+    }, { onError });
+    await s.start();
+
+    chain.mineBlock(2);
+    await flush();
+
+    expect(onError).toHaveBeenCalledTimes(1);
+    expect(onError.mock.calls[0][0].message).toBe('consumer down');
+    expect(chain.watchers.size).toBe(0);
+    expect(s.cursor).toBeNull();
+  });
+});
+
+describe('JsonFileCursorStore', () => {
+  it('resumes a stream from a cursor saved on disk', async () => {
+    const dir = path.join(await fs.mkdtemp(path.join(os.tmpdir(), 'cursors-')), 'nested');
+    const chain = new FakeChain();
+    chain.mineBlock(3, false);
+    const store = new JsonFileCursorStore(dir);
+    expect(await store.load('creations')).toBeNull();
+
+    await stream(chain, store, () => undefined).start();
+    chain.mineBlock(1, false);
+    const received: string[] = [];
+    await stream(chain, new JsonFileCursorStore(dir), (event) => {
+      received.push(event.token);
+    }).start();
+
+    expect(received).toEqual([token(4)]);
+    expect(await fs.readdir(dir)).toEqual(['creations.json']);
+  });
+});
@@ -0,0 +1,89 @@
+import { attributeReplacementCosts, allocateExtraCost, totalExtraCost, firstReceipt, ReplacementAttempt } from '../../src/extraCosts';
+import { ValidationError } from '../../src/errors';
+import { parseEther } from 'ethers';
+
+const ORIGINAL = '0x' + 'a1'.repeat(32);
+const SPEED_UP_1 = '0x' + 'b2'.repeat(32);
+const SPEED_UP_2 = '0x' + 'c3'.repeat(32);
+const SPEED_UP_3 = '0x' + 'd4'.repeat(32);
+
+/**
+ * Original transaction sped up three times, every attempt sent through a relay
+ */
+function speedUpChain(): ReplacementAttempt[] {
+  return [
+    { txHash: ORIGINAL, nonce: 7, extraCosts: [{ kind: 'relayFee', amount: parseEther('0.001') }] },
+    { txHash: SPEED_UP_1, nonce: 7, extraCosts: [{ kind: 'relayFee', amount: parseEther('0.001') }] },
+    {
+      txHash: SPEED_UP_2,
+      nonce: 7,
+      extraCosts: [
+        { kind: 'relayFee', amount: parseEther('0.001') },
+        { kind: 'bundleTip', amount: parseEther('0.002') },
+      ],
+    },
+    { txHash: SPEED_UP_3, nonce: 7, extraCosts: [{ kind: 'bundleTip', amount: parseEther('0.003') }] },
+  ];
+}
+
+describe('attributeReplacementCosts', () => {
+  test('charges the confirmed attempt with every attempt\'s fees', () => {
+    const costs = attributeReplacementCosts(speedUpChain(), SPEED_UP_3);
+
+    expect(costs).toEqual([
+      { kind: 'bundleTip', amount: parseEther('0.003') },
+      { kind: 'relayFee', amount: parseEther('0.001'), txHash: ORIGINAL },
+      { kind: 'relayFee', amount: parseEther('0.001'), txHash: SPEED_UP_1 },
+      { kind: 'relayFee', amount: parseEther('0.001'), txHash: SPEED_UP_2 },
+      { kind: 'bundleTip', amount: parseEther('0.002'), txHash: SPEED_UP_2 },
+    ]);
+    expect(totalExtraCost(costs)).toBe(parseEther('0.008'));
+  });
+
+  test('total does not depend on which attempt mined', () => {
+    const total = totalExtraCost(attributeReplacementCosts(speedUpChain(), ORIGINAL));
+    expect(total).toBe(parseEther('0.008'));
+  });
+
+  test('matches the confirmed hash case-insensitively', () => {
+    const costs = attributeReplacementCosts(speedUpChain(), SPEED_UP_1.toUpperCase().replace('0X', '0x'));
+    expect(costs[0]).toEqual({ kind: 'relayFee', amount: parseEther('0.001') });
+  });
+
+  test('rejects a confirmed hash outside the chain', () => {
+    expect(() => attributeReplacementCosts(speedUpChain(), '0x' + 'ee'.repeat(32))).toThrow(ValidationError);
+  });
+
+  test('rejects attempts with a different nonce', () => {
+    const chain = speedUpChain();
+    chain[2] = { ...chain[2], nonce: 8 };
+    expect(() => attributeReplacementCosts(chain, SPEED_UP_3)).toThrow(/nonce 8, expected 7/);
+  });
+});
+
+describe('allocateExtraCost', () => {
+  test('splits pro rata with the remainder on the last fill', () => {
+    expect(allocateExtraCost(10n, [1n, 1n, 1n])).toEqual([3n, 3n, 4n]);
+    expect(allocateExtraCost(100n, [300n, 100n])).toEqual([75n, 25n]);
+  });
+
+  test('handles no fills and zero-sized fills', () => {
+    expect(allocateExtraCost(5n, [])).toEqual([]);
+    expect(allocateExtraCost(5n, [0n, 0n])).toEqual([0n, 5n]);
+  });
+});
+
+describe('firstReceipt', () => {
+  test('resolves with the first non-null result', async () => {
+    const never = new Promise<string | null>(() => {});
+    await expect(firstReceipt([never, Promise.resolve(null), Promise.resolve('mined')])).resolves.toBe('mined');
+  });
+
+  test('resolves null when no attempt produced a receipt', async () => {
+    await expect(firstReceipt([Promise.resolve(null), Promise.reject(new Error('dropped'))])).resolves.toBeNull();
+  });
+
+  test('rethrows when every wait failed', async () => {
+    await expect(firstReceipt([Promise.reject(new Error('a')), Promise.reject(new Error('b'))])).rejects.toThrow('b');
+  });
+});
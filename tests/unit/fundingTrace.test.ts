@@ -0,0 +1,131 @@
+import { BlockTransaction } from '../../src/creationBlock';
+import { FundingHop, FundingTraceSource, traceFirstFunding, traceFunding } from '../../src/fundingTrace';
+import { ValidationError } from '../../src/errors';
+
+const CREATOR = '0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa';
+const FUNDER = '0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb';
+const SOURCE = '0xcccccccccccccccccccccccccccccccccccccccc';
+const ONE = 10n ** 18n;
+
+const transfer = (hash: string, from: string, to: string, value: bigint = ONE): BlockTransaction =>
+  ({ hash, from, to, value, gasPrice: 10n ** 9n });
+
+// Wallets funded at a block, plus the transactions of each block
+function fakeSource(fundedAt: Record<string, number>, blocks: Record<number, BlockTransaction[]>) {
+  let reads = 0;
+  const isFunded = (address: string, block: number) => {
+    reads += 1;
+    const at = fundedAt[address.toLowerCase()];
+    return at !== undefined && block >= at;
+  };
+  const source: FundingTraceSource = {
+    getBalance: async (address, block) => (isFunded(address, block) ? ONE : 0n),
+    getTransactionCount: async () => 0,
+    getBlockTransactions: async (block) => blocks[block] ?? [],
+  };
+  return { source, reads: () => reads };
+}
+
+describe('traceFirstFunding', () => {
+  it('finds the block and sender of the first funding', async () => {
+    const { source, reads } = fakeSource({ [CREATOR]: 9_321 }, {
+      9_321: [transfer('0x01', SOURCE, FUNDER), transfer('0x02', FUNDER, CREATOR.replace(/a/g, 'A'))],
+    });
+
+    const hop = await traceFirstFunding(CREATOR, 10_000, source, 1_000);
+
+    expect(hop).toEqual({ address: CREATOR, status: 'found', funder: FUNDER, block: 9_321, txHash: '0x02' });
+    expect(reads()).toBeLessThan(30);
+  });
+
+  it('reports a funding without a plain transfer as internal', async () => {
+    const { source } = fakeSource({ [CREATOR]: 9_500 }, { 9_500: [transfer('0x01', FUNDER, CREATOR, 0n)] });
+
+    expect(await traceFirstFunding(CREATOR, 10_000, source, 1_000)).toEqual(
+      { address: CREATOR, status: 'internal', funder: null, block: 9_500, txHash: null }
+    );
+  });
+
+  it('distinguishes wallets funded before the lookback from unfunded ones', async () => {
+    const { source } = fakeSource({ [CREATOR]: 8_000 }, {});
+
+    expect((await traceFirstFunding(CREATOR, 10_000, source, 1_000)).status).toBe('beforeLookback');
+    expect((await traceFirstFunding(CREATOR, 7_999, source, 1_000)).status).toBe('unfunded');
+  });
+
+  it('ends the trace on pruned state instead of failing it', async () => {
+    const { source } = fakeSource({ [CREATOR]: 9_321 }, {});
+    const pruned: FundingTraceSource = {
+      ...source,
+      getBalance: async (address, block) => {
+        if (block < 9_900) {
+          throw new Error('missing trie node 3f2a (path ) state is not available');
+        }
+        return await source.getBalance(address, block);
+      },
+    };
+
+    expect((await traceFirstFunding(CREATOR, 10_000, pruned, 1_000)).status).toBe('stateUnavailable');
+    await expect(
+      traceFirstFunding(CREATOR, 10_000, { ...source, getBalance: async () => { throw new Error('request timeout'); } }, 1_000)
+    ).rejects.toThrow('request timeout');
+  });
+
+  it('counts a sent transaction as funded', async () => {
+    const source: FundingTraceSource = {
+      getBalance: async () => 0n,
+      getTransactionCount: async (_, block) => (block >= 50 ? 1 : 0),
+      getBlockTransactions: async () => [],
+    };
+
+    expect(await traceFirstFunding(CREATOR, 100, source, 100)).toMatchObject({ status: 'internal', block: 50 });
+  });
+});
+
+describe('traceFunding', () => {
+  const fundedAt = { [CREATOR]: 9_500, [FUNDER]: 9_100 };
+  const blocks = {
+    9_500: [transfer('0x02', FUNDER, CREATOR)],
+    9_100: [transfer('0x01', SOURCE, FUNDER)],
+  };
+
+  it('follows the funder of the funder from the block it was used', async () => {
+    const { source } = fakeSource(fundedAt, blocks);
+
+    const hops = await traceFunding(CREATOR, 10_000, source, { lookbackBlocks: 500, hops: 2 });
+
+    expect(hops.map((hop) => [hop.address, hop.funder, hop.block])).toEqual([
+      [CREATOR, FUNDER, 9_500],
+      [FUNDER, SOURCE, 9_100],
+    ]);
+  });
+
+  it('stops at the first hop without a funder', async () => {
+    const { source } = fakeSource({ [CREATOR]: 9_500, [FUNDER]: 1_000 }, blocks);
+
+    const hops = await traceFunding(CREATOR, 10_000, source, { lookbackBlocks: 500, hops: 3 });
+
+    expect(hops.map((hop) => hop.status)).toEqual(['found', 'beforeLookback']);
+  });
+
+  it('uses known hops instead of searching', async () => {
+    const { source, reads } = fakeSource(fundedAt, blocks);
+    const known: FundingHop = { address: CREATOR, status: 'found', funder: FUNDER, block: 9_500, txHash: '0x02' };
+
+    const hops = await traceFunding(CREATOR, 10_000, source, {
+      lookbackBlocks: 500,
+      hops: 1,
+      known: (address) => (address === CREATOR ? known : undefined),
+    });
+
+    expect(hops).toEqual([known]);
+    expect(reads()).toBe(0);
+  });
+
+  it('rejects invalid hop counts and lookbacks', async () => {
+    const { source } = fakeSource({}, {});
+
+    await expect(traceFunding(CREATOR, 10_000, source, { lookbackBlocks: 500, hops: 0 })).rejects.toThrow(ValidationError);
+    await expect(traceFunding(CREATOR, 10_000, source, { lookbackBlocks: 0.5, hops: 1 })).rejects.toThrow(ValidationError);
+  });
+});
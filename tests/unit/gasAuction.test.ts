@@ -0,0 +1,172 @@
+import { CompetitorBid, GasAuction, GasAuctionConfig, GasAuctionDriver, competitorBidFrom } from '../../src/gasAuction';
+import { encodeBuyCalldata, encodeSellCalldata } from '../../src/calldata';
+
+const GWEI = 10n ** 9n;
+const CONTRACT = '0x5c952063c7fc8610FFDB798152D69F0B9550762b';
+const TOKEN = '0x1111111111111111111111111111111111111111';
+const RIVAL = '0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb';
+
+const flush = () => new Promise((resolve) => setImmediate(resolve));
+
+/**
+ * Scripted chain: the test pushes competitor bids and blocks; `mine` decides which attempt lands
+ */
+function scriptedDriver() {
+  let competitorListener: ((bid: CompetitorBid) => void) | null = null;
+  let blockListener: (() => void) | null = null;
+  const replacements: bigint[] = [];
+  let mined: string | null = null;
+  let failNextReplace: Error | null = null;
+
+  const driver: GasAuctionDriver = {
+    watchCompetitors: (listener) => {
+      competitorListener = listener;
+      return () => (competitorListener = null);
+    },
+    watchBlocks: (listener) => {
+      blockListener = listener;
+      return () => (blockListener = null);
+    },
+    replace: async (gasPrice) => {
+      if (failNextReplace) {
+        const error = failNextReplace;
+        failNextReplace = null;
+        throw error;
+      }
+      replacements.push(gasPrice);
+      return `0xmine${replacements.length}`;
+    },
+    minedAttempt: async (hashes) => (mined && hashes.includes(mined) ? mined : null),
+  };
+
+  return {
+    driver,
+    replacements,
+    compete: async (txHash: string, gwei: bigint) => {
+      competitorListener?.({ txHash, from: RIVAL, gasPrice: gwei * GWEI });
+      await flush();
+    },
+    markMined: (txHash: string) => (mined = txHash),
+    mine: async (txHash: string) => {
+      mined = txHash;
+      blockListener?.();
+      await flush();
+    },
+    failNextReplace: (error: Error) => (failNextReplace = error),
+    listening: () => competitorListener !== null || blockListener !== null,
+  };
+}
+
+const config: GasAuctionConfig = { increment: GWEI, maxGasPrice: 20n * GWEI, maxReplacements: 3 };
+
+describe('GasAuction', () => {
+  it('outbids each competitor until an attempt mines, then stops', async () => {
+    const chain = scriptedDriver();
+    const result = new GasAuction('0xmine0', 3n * GWEI, chain.driver, config).run();
+
+    await chain.compete('0xrival1', 2n); // Below us: recorded, not answered
+    await chain.compete('0xrival2', 5n);
+    await chain.compete('0xrival3', 7n);
+    await chain.mine('0xmine2');
+    await chain.compete('0xrival4', 15n); // After mining: ignored
+
+    const report = await result;
+    expect(chain.replacements).toEqual([6n * GWEI, 8n * GWEI]);
+    expect(report).toMatchObject({ outcome: 'mined', minedTxHash: '0xmine2', replacements: 2, finalGasPrice: 8n * GWEI });
+    expect(report.attempts).toEqual(['0xmine0', '0xmine1', '0xmine2']);
+    expect(report.history.map((event) => event.type)).toEqual(['bid', 'competitor', 'competitor', 'bid', 'competitor', 'bid', 'mined']);
+    expect(chain.listening()).toBe(false);
+  });
+
+  it('raises by at least the replacement minimum bump', async () => {
+    const chain = scriptedDriver();
+    const result = new GasAuction('0xmine0', 10n * GWEI, chain.driver, config).run();
+
+    await chain.compete('0xrival1', 10n); // +1 gwei would be a 10% bump exactly: 11 gwei
+    await chain.compete('0xrival2', 11n); // 12 gwei is short of 11 * 1.1 = 12.1 gwei
+    await chain.mine('0xmine2');
+
+    expect(chain.replacements).toEqual([11n * GWEI, 12_100_000_000n]);
+    await result;
+  });
+
+  it('bids the cap once, then skips competitors above it', async () => {
+    const chain = scriptedDriver();
+    const result = new GasAuction('0xmine0', 10n * GWEI, chain.driver, config).run();
+
+    await chain.compete('0xrival1', 19n); // 20 gwei target equals the cap
+    await chain.compete('0xrival2', 19n);
+    await chain.compete('0xrival3', 25n);
+    await chain.mine('0xmine0');
+
+    const report = await result;
+    expect(chain.replacements).toEqual([20n * GWEI]);
+    expect(report.minedTxHash).toBe('0xmine0');
+    expect(report.history.filter((event) => event.type === 'skipped')).toEqual([
+      expect.objectContaining({ competitor: '0xrival3', reason: 'cap' }),
+    ]);
+  });
+
+  it('stops replacing after maxReplacements', async () => {
+    const chain = scriptedDriver();
+    const result = new GasAuction('0xmine0', GWEI, chain.driver, { ...config, maxReplacements: 1 }).run();
+
+    await chain.compete('0xrival1', 2n);
+    await chain.compete('0xrival2', 4n);
+    await chain.mine('0xmine1');
+
+    const report = await result;
+    expect(chain.replacements).toEqual([3n * GWEI]);
+    expect(report.history).toContainEqual(expect.objectContaining({ type: 'skipped', reason: 'maxReplacements' }));
+  });
+
+  it('treats a rejected replacement as a prompt to check for mining', async () => {
+    const chain = scriptedDriver();
+    const result = new GasAuction('0xmine0', GWEI, chain.driver, config).run();
+    await flush(); // Let the initial mined check pass
+
+    chain.markMined('0xmine0'); // No block event yet
+    chain.failNextReplace(new Error('nonce too low'));
+    await chain.compete('0xrival1', 2n);
+    const report = await result;
+
+    expect(report).toMatchObject({ outcome: 'mined', minedTxHash: '0xmine0' });
+    expect(report.history).toContainEqual(expect.objectContaining({ type: 'skipped', reason: 'replaceFailed', error: 'nonce too low' }));
+    expect(chain.replacements).toEqual([]);
+  });
+
+  it('reports the chain as pending when cancelled', async () => {
+    const chain = scriptedDriver();
+    const controller = new AbortController();
+    const result = new GasAuction('0xmine0', GWEI, chain.driver, { ...config, signal: controller.signal }).run();
+
+    await chain.compete('0xrival1', 2n);
+    controller.abort();
+
+    expect(await result).toMatchObject({ outcome: 'cancelled', minedTxHash: null, attempts: ['0xmine0', '0xmine1'] });
+    expect(chain.listening()).toBe(false);
+  });
+});
+
+describe('competitorBidFrom', () => {
+  const tx = {
+    hash: '0xrival',
+    from: RIVAL,
+    to: CONTRACT,
+    data: encodeBuyCalldata(TOKEN, RIVAL, 10n ** 17n, 0n),
+    type: 0,
+    gasPrice: 5n * GWEI,
+    maxPriorityFeePerGas: null,
+  };
+
+  it('recognises buys of the token and their effective price', () => {
+    expect(competitorBidFrom(tx, CONTRACT, TOKEN)).toEqual({ txHash: '0xrival', from: RIVAL, gasPrice: 5n * GWEI });
+    expect(competitorBidFrom({ ...tx, type: 2, gasPrice: 9n * GWEI, maxPriorityFeePerGas: 3n * GWEI }, CONTRACT, TOKEN)!.gasPrice).toBe(3n * GWEI);
+  });
+
+  it('ignores other tokens, sells and other contracts', () => {
+    expect(competitorBidFrom(tx, CONTRACT, RIVAL)).toBeNull();
+    expect(competitorBidFrom({ ...tx, data: encodeSellCalldata(TOKEN, 1n, 0n) }, CONTRACT, TOKEN)).toBeNull();
+    expect(competitorBidFrom({ ...tx, to: RIVAL }, CONTRACT, TOKEN)).toBeNull();
+  });
+});
@@ -0,0 +1,78 @@
+import { GasProfiler } from '../../src/gasProfiler';
+
+describe('GasProfiler', () => {
+  it('estimates once on first use and applies the safety multiplier', async () => {
+    const profiler = new GasProfiler({ multiplierBps: 15000 });
+    const estimate = jest.fn(async () => 200000n);
+
+    const [first, second] = await Promise.all([
+      profiler.limitFor('buyTokenAMAP', estimate),
+      profiler.limitFor('buyTokenAMAP', estimate),
+    ]);
+    const third = await profiler.limitFor('buyTokenAMAP', estimate);
+
+    expect([first, second, third]).toEqual([300000n, 300000n, 300000n]);
+    expect(estimate).toHaveBeenCalledTimes(1);
+    expect(profiler.get('buyTokenAMAP')).toBe(300000n);
+    expect(profiler.get('sellToken')).toBeUndefined();
+  });
+
+  it('retries the estimate after a failed first use', async () => {
+    const profiler = new GasProfiler();
+    await expect(profiler.limitFor('sellToken', async () => {
+      throw new Error('execution reverted');
+    })).rejects.toThrow('execution reverted');
+
+    expect(await profiler.limitFor('sellToken', async () => 100000n)).toBe(130000n);
+  });
+
+  it('refreshes from receipts that come close to the limit', async () => {
+    const profiler = new GasProfiler({ multiplierBps: 13000, refreshMarginBps: 1000 });
+    await profiler.limitFor('sellToken', async () => 100000n); // limit 130000
+
+    expect(profiler.observe('sellToken', 110000n)).toBe(false); // 84.6% of the limit
+    expect(profiler.get('sellToken')).toBe(130000n);
+
+    expect(profiler.observe('sellToken', 120000n)).toBe(true); // within 10% of the limit
+    expect(profiler.get('sellToken')).toBe(156000n);
+    expect(profiler.snapshot()[0]).toMatchObject({ source: 'receipt', observed: 120000n });
+  });
+
+  it('learns from a receipt when the method was never estimated', () => {
+    const profiler = new GasProfiler();
+    expect(profiler.observe('approve', 46000n)).toBe(true);
+    expect(profiler.get('approve')).toBe(59800n);
+  });
+
+  it('keeps overrides until reset', async () => {
+    const profiler = new GasProfiler();
+    profiler.override('createToken', 2_000_000n);
+
+    expect(profiler.observe('createToken', 1_999_000n)).toBe(false);
+    expect(await profiler.limitFor('createToken', async () => 1n)).toBe(2_000_000n);
+
+    profiler.reset('createToken');
+    expect(profiler.get('createToken')).toBeUndefined();
+  });
+
+  it('restores a snapshot', async () => {
+    const profiler = new GasProfiler();
+    await profiler.limitFor('buyToken', async () => 250000n);
+    profiler.override('approve', 60000n);
+
+    const restored = new GasProfiler({}, profiler.snapshot());
+    expect(restored.snapshot()).toEqual(profiler.snapshot());
+    expect(restored.get('buyToken')).toBe(325000n);
+  });
+
+  it('replaces its profile on restore', async () => {
+    const profiler = new GasProfiler();
+    await profiler.limitFor('buyToken', async () => 250000n);
+    const snapshot = profiler.snapshot();
+    profiler.override('approve', 60000n);
+
+    profiler.restore(snapshot);
+    expect(profiler.snapshot()).toEqual(snapshot);
+    expect(profiler.get('approve')).toBeUndefined();
+  });
+});
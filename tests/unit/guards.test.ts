@@ -0,0 +1,63 @@
+import { isEconomicalSell, assertEconomicalSell, assertPriceImpact } from '../../src/guards';
+import { PriceImpactTooHighError, UneconomicalTradeError } from '../../src/errors';
+
+describe('guards', () => {
+  describe('isEconomicalSell', () => {
+    const gasCost = 1000000000000000n; // 0.001 BNB
+
+    it('should accept proceeds above gas cost with zero margin', () => {
+      expect(isEconomicalSell(gasCost + 1n, gasCost, 0)).toBe(true);
+    });
+
+    it('should reject proceeds equal to gas cost with zero margin', () => {
+      expect(isEconomicalSell(gasCost, gasCost, 0)).toBe(false);
+    });
+
+    it('should apply the margin exactly at the boundary', () => {
+      // 50% margin requires proceeds strictly above 1.5x gas cost
+      const boundary = (gasCost * 15000n) / 10000n;
+
+      expect(isEconomicalSell(boundary, gasCost, 5000)).toBe(false);
+      expect(isEconomicalSell(boundary + 1n, gasCost, 5000)).toBe(true);
+      expect(isEconomicalSell(boundary - 1n, gasCost, 5000)).toBe(false);
+    });
+
+    it('should accept any positive proceeds when gas is free', () => {
+      expect(isEconomicalSell(1n, 0n, 10000)).toBe(true);
+      expect(isEconomicalSell(0n, 0n, 10000)).toBe(false);
+    });
+  });
+
+  describe('assertEconomicalSell', () => {
+    it('should throw UneconomicalTradeError with both amounts', () => {
+      expect(() => assertEconomicalSell(10n, 20n, 0)).toThrow(UneconomicalTradeError);
+
+      let caught: UneconomicalTradeError | undefined;
+      try {
+        assertEconomicalSell(10n, 20n, 0);
+      } catch (error) {
+        caught = error as UneconomicalTradeError;
+      }
+      expect(caught?.proceeds).toBe(10n);
+      expect(caught?.gasCost).toBe(20n);
+      expect(caught?.code).toBe('UNECONOMICAL_TRADE');
+    });
+
+    it('should not throw for an economical sell', () => {
+      expect(() => assertEconomicalSell(30n, 20n, 0)).not.toThrow();
+    });
+  });
+
+  describe('assertPriceImpact', () => {
+    it('should allow impact up to the cap', () => {
+      expect(() => assertPriceImpact('0xabc', 'buy', 1000, 1000)).not.toThrow();
+    });
+
+    it('should throw PriceImpactTooHighError above the cap', () => {
+      expect(() => assertPriceImpact('0xabc', 'sell', 1001, 1000)).toThrow(PriceImpactTooHighError);
+      expect(() => assertPriceImpact('0xabc', 'sell', 1001, 1000)).toThrow(
+        expect.objectContaining({ side: 'sell', impactBps: 1001, maxImpactBps: 1000, code: 'PRICE_IMPACT_TOO_HIGH' })
+      );
+    });
+  });
+});
@@ -0,0 +1,71 @@
+import { applyTransfers, computeHolderStats, summarizeHolders } from '../../src/holders';
+import { BackfillLog } from '../../src/backfill';
+import { getErc20Interface } from '../../src/abi';
+
+const TOKEN = '0x1111111111111111111111111111111111111111';
+const MANAGER = '0x5c952063c7fc8610FFDB798152D69F0B9550762b';
+const ZERO = '0x0000000000000000000000000000000000000000';
+const ALICE = '0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa';
+const BOB = '0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb';
+const CAROL = '0xcccccccccccccccccccccccccccccccccccccccc';
+const SUPPLY = 1000n;
+
+function transfer(from: string, to: string, value: bigint, blockNumber: number = 1): BackfillLog {
+  const encoded = getErc20Interface().encodeEventLog('Transfer', [from, to, value]);
+  return { topics: encoded.topics, data: encoded.data, blockNumber, transactionHash: `0x${blockNumber.toString(16)}` };
+}
+
+describe('computeHolderStats', () => {
+  // Mint to the manager, who sells off the curve
+  const logs = [
+    transfer(ZERO, MANAGER, SUPPLY),
+    transfer(MANAGER, ALICE, 300n),
+    transfer(MANAGER, BOB, 100n),
+    transfer(MANAGER, CAROL, 50n),
+    transfer(ALICE, BOB, 50n),
+    transfer(CAROL, MANAGER, 50n), // Carol sold everything back
+  ];
+
+  it('counts non-zero balances, excluding the curve and burn addresses', () => {
+    const stats = computeHolderStats(TOKEN, logs, SUPPLY, 10, [MANAGER], 1, 2);
+
+    expect(stats.holderCount).toBe(2);
+    expect(stats.topHolders.map((h) => [h.address.toLowerCase(), h.balance])).toEqual([
+      [ALICE, 250n],
+      [BOB, 150n],
+    ]);
+    expect(stats.topConcentrationBps).toBe(4000);
+    expect(stats.topHolders[0].shareBps).toBe(2500);
+  });
+
+  it('limits the concentration to the top N holders', () => {
+    const stats = computeHolderStats(TOKEN, logs, SUPPLY, 1, [MANAGER], 1, 2);
+
+    expect(stats.holderCount).toBe(2);
+    expect(stats.topHolders).toHaveLength(1);
+    expect(stats.topConcentrationBps).toBe(2500);
+  });
+
+  it('ignores logs that are not transfers and handles an empty supply', () => {
+    const stats = computeHolderStats(TOKEN, [{ ...logs[1], topics: [`0x${'00'.repeat(32)}`] }], 0n, 10, [], 1, 1);
+
+    expect(stats.holderCount).toBe(0);
+    expect(stats.topConcentrationBps).toBe(0);
+  });
+
+  it('gives the same stats when the balances are folded in two parts', () => {
+    const balances = new Map<string, bigint>();
+    applyTransfers(balances, logs.slice(0, 3));
+    applyTransfers(balances, logs.slice(3));
+
+    expect(summarizeHolders(TOKEN, balances, SUPPLY, 10, [MANAGER], 1, 2)).toEqual(computeHolderStats(TOKEN, logs, SUPPLY, 10, [MANAGER], 1, 2));
+  });
+
+  it('leaves out every excluded address, such as the PancakeSwap pair', () => {
+    const pair = '0x7777777777777777777777777777777777777777';
+    const stats = computeHolderStats(TOKEN, [...logs, transfer(MANAGER, pair, 500n)], SUPPLY, 10, [MANAGER, pair], 1, 2);
+
+    expect(stats.holderCount).toBe(2);
+    expect(stats.topConcentrationBps).toBe(4000);
+  });
+});
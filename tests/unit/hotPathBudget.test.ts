@@ -0,0 +1,32 @@
+import { ethers } from 'ethers';
+import { HOT_PATH_BENCHES } from '../../benches/hotPath';
+import { runBench } from '../../benches/harness';
+import { BUY_TOKENS, TOKEN, buyReceipt } from '../../benches/fixtures';
+import { getFourTradingInterface } from '../../src/abi';
+import { FOUR_MEME_ADDRESS } from '../../src/constants';
+import { parseTradeEvents } from '../../src/utils';
+
+describe('hot path benchmarks', () => {
+  it('decode the fixture receipt into the buy it records', async () => {
+    const { purchases } = await parseTradeEvents(buyReceipt(), new ethers.Contract(FOUR_MEME_ADDRESS, getFourTradingInterface()));
+
+    expect(purchases).toHaveLength(1);
+    expect(purchases[0]).toMatchObject({ token: ethers.getAddress(TOKEN), amount: BUY_TOKENS });
+  });
+
+  it('encode the same calldata as the signature-string baseline', async () => {
+    const [encode, baseline] = HOT_PATH_BENCHES;
+
+    expect(await encode.run()).toBe(await baseline.run());
+  });
+
+  describe('budgets', () => {
+    for (const bench of HOT_PATH_BENCHES.filter((candidate) => candidate.budgetUs !== undefined)) {
+      it(`${bench.name} averages under ${bench.budgetUs}µs`, async () => {
+        const result = await runBench(bench, { iterations: 200 });
+
+        expect(result.meanUs).toBeLessThanOrEqual(bench.budgetUs!);
+      });
+    }
+  });
+});
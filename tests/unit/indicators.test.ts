@@ -0,0 +1,76 @@
+import { ethers } from 'ethers';
+import { Crossover, Ema, IndicatorStream, PriceSample, RateOfChange, Rsi, Series, Sma } from '../../src/indicators';
+import { CandleAggregator } from '../../src/candles';
+import { TokenPurchaseEvent } from '../../src/types';
+
+const TOKEN = '0x1111111111111111111111111111111111111111';
+
+function samples(prices: number[]): PriceSample[] {
+  return prices.map((price, index) => ({ price: ethers.parseUnits(price.toString(), 18), timestamp: index * 1000 }));
+}
+
+function run<T>(series: Series<T>, prices: number[]): (T | null)[] {
+  return samples(prices).map((sample) => series.update(sample));
+}
+
+// Reference series and values from the StockCharts indicator tutorials
+const EMA_PRICES = [22.27, 22.19, 22.08, 22.17, 22.18, 22.13, 22.23, 22.43, 22.24, 22.29, 22.15, 22.39, 22.38, 22.61, 23.36];
+const RSI_PRICES = [44.34, 44.09, 44.15, 43.61, 44.33, 44.83, 45.10, 45.42, 45.84, 46.08, 45.89, 46.03, 45.61, 46.28, 46.28, 46.00, 46.03, 46.41, 46.22, 45.64];
+
+describe('indicators', () => {
+  it('SMA averages the last period prices after warm-up', () => {
+    expect(run(new Sma(3), [1, 2, 3, 4, 5])).toEqual([null, null, 2, 3, 4]);
+  });
+
+  it('EMA matches reference values', () => {
+    const values = run(new Ema(10), EMA_PRICES);
+
+    expect(values.slice(0, 9)).toEqual(Array(9).fill(null));
+    [22.22, 22.21, 22.24, 22.27, 22.33, 22.52].forEach((expected, index) => {
+      expect(values[9 + index]).toBeCloseTo(expected, 2);
+    });
+  });
+
+  it('RSI matches reference values', () => {
+    const values = run(new Rsi(14), RSI_PRICES);
+
+    expect(values.slice(0, 14)).toEqual(Array(14).fill(null));
+    [70.46, 66.25, 66.48, 69.35, 66.29, 57.92].forEach((expected, index) => {
+      expect(values[14 + index]).toBeCloseTo(expected, 1);
+    });
+  });
+
+  it('RSI is 100 without losses and 50 without movement', () => {
+    expect(run(new Rsi(2), [1, 2, 3])[2]).toBe(100);
+    expect(run(new Rsi(2), [1, 1, 1])[2]).toBe(50);
+  });
+
+  it('rate of change compares with the price period steps ago', () => {
+    expect(run(new RateOfChange(2), [10, 11, 12, 6])).toEqual([null, null, 20, expect.closeTo(-45.4545, 3)]);
+    expect(run(new RateOfChange(1), [0, 1])).toEqual([null, null]);
+  });
+
+  it('crossover fires once on the step the fast series crosses', () => {
+    const prices = [5, 5, 5, 6, 7, 4, 2, 2];
+    const below = run(new Crossover(new Sma(1), new Sma(3), 'below'), prices);
+    const above = run(new Crossover(new Sma(1), new Sma(3), 'above'), prices);
+
+    expect(below).toEqual([null, null, false, false, false, true, false, false]);
+    expect(above).toEqual([null, null, false, true, false, false, false, false]);
+  });
+
+  it('streams warmed-up values of closed candles, flat gap candles included', () => {
+    const candles = new CandleAggregator(TOKEN, 1000);
+    const stream = new IndicatorStream(new Sma(2));
+    const received: [number, number][] = [];
+    stream.subscribe((value, timestamp) => received.push([value, timestamp]));
+
+    const trade = (price: bigint) => ({ token: TOKEN, price, amount: 1n, cost: 1n } as TokenPurchaseEvent);
+    candles.addTrade(trade(ethers.parseUnits('1', 18)), 0);
+    const closed = candles.addTrade(trade(ethers.parseUnits('3', 18)), 3000);
+    closed.forEach((candle) => stream.push(candle));
+
+    expect(closed).toHaveLength(3);
+    expect(received).toEqual([[1, 2000], [1, 3000]]);
+  });
+});
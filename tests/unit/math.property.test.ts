@@ -0,0 +1,116 @@
+import { bpsOf, checkedBps, mulDiv, setStrictMath, toNumberChecked } from '../../src/math';
+import { applySlippage, calculatePriceChange } from '../../src/utils';
+import { MathTruncationError, ValidationError } from '../../src/errors';
+import { MAX_UINT256 } from '../../src/constants';
+
+// Deterministic PRNG so failures are reproducible from the printed seed
+function mulberry32(seed: number): () => number {
+  let state = seed >>> 0;
+  return () => {
+    state = (state + 0x6d2b79f5) >>> 0;
+    let t = state;
+    t = Math.imul(t ^ (t >>> 15), t | 1);
+    t ^= t + Math.imul(t ^ (t >>> 7), t | 61);
+    return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+  };
+}
+
+function randomUint(random: () => number): bigint {
+  // Mix magnitudes around the float-exact boundary (2^53) and the uint256 boundary
+  const bits = [8, 52, 53, 54, 64, 128, 256][Math.floor(random() * 7)];
+  let value = 0n;
+  for (let i = 0; i < bits; i += 32) {
+    value = (value << 32n) | BigInt(Math.floor(random() * 4294967296));
+  }
+  return value & ((1n << BigInt(bits)) - 1n);
+}
+
+// Run `fn` in both modes: the strict result must equal the lenient one, or strict must throw
+function bothModes<T>(fn: () => T): { lenient: T; strict: T | MathTruncationError } {
+  setStrictMath(false);
+  const lenient = fn();
+  setStrictMath(true);
+  try {
+    return { lenient, strict: fn() };
+  } catch (error) {
+    if (error instanceof MathTruncationError) {
+      return { lenient, strict: error };
+    }
+    throw error;
+  } finally {
+    setStrictMath(false);
+  }
+}
+
+const SAFE = BigInt(Number.MAX_SAFE_INTEGER);
+const SEED = 0x5afe;
+const RUNS = 2000;
+
+describe('checked math (property-based)', () => {
+  const random = mulberry32(SEED);
+
+  afterEach(() => setStrictMath(false));
+
+  it(`toNumberChecked agrees when exact and throws otherwise (seed ${SEED})`, () => {
+    for (let run = 0; run < RUNS; run++) {
+      const value = randomUint(random);
+      const { lenient, strict } = bothModes(() => toNumberChecked(value));
+
+      if (value <= SAFE) {
+        expect(strict).toBe(lenient);
+        expect(BigInt(lenient)).toBe(value);
+      } else {
+        expect(strict).toBeInstanceOf(MathTruncationError);
+      }
+    }
+  });
+
+  it(`applySlippage and bpsOf agree for whole basis points and uint256 values (seed ${SEED})`, () => {
+    for (let run = 0; run < RUNS; run++) {
+      const amount = randomUint(random);
+      const whole = randomUint(random) || 1n;
+      const part = whole > 1n ? randomUint(random) % whole : 0n;
+      const percent = Math.floor(random() * 10001) / 100;
+
+      const slippage = bothModes(() => applySlippage(amount, percent));
+      expect(slippage.strict).toBe(slippage.lenient);
+
+      const share = bothModes(() => bpsOf(part, whole));
+      expect(share.strict).toBe(share.lenient);
+    }
+  });
+
+  it(`calculatePriceChange agrees whenever both prices are exact numbers (seed ${SEED})`, () => {
+    for (let run = 0; run < RUNS; run++) {
+      const oldPrice = randomUint(random) + 1n;
+      const newPrice = randomUint(random);
+      const { lenient, strict } = bothModes(() => calculatePriceChange(oldPrice, newPrice));
+
+      if (oldPrice <= SAFE && newPrice <= SAFE) {
+        expect(strict).toBe(lenient);
+      } else {
+        expect(strict).toBeInstanceOf(MathTruncationError);
+      }
+    }
+  });
+
+  it('strict mode refuses fractional basis points and values beyond uint256', () => {
+    expect(bothModes(() => checkedBps(0.29 * 100))).toEqual({ lenient: 29n, strict: 29n });
+    expect(bothModes(() => checkedBps(12.5)).strict).toBeInstanceOf(MathTruncationError);
+    expect(bothModes(() => applySlippage(1000n, 0.125)).strict).toBeInstanceOf(MathTruncationError);
+    expect(bothModes(() => mulDiv(MAX_UINT256 + 1n, 1n, 1n)).strict).toBeInstanceOf(MathTruncationError);
+    expect(() => mulDiv(1n, 1n, 0n)).toThrow(MathTruncationError);
+  });
+
+  it('refuses non-finite basis points with a ValidationError in both modes', () => {
+    for (const bps of [NaN, Infinity, -Infinity]) {
+      expect(bothModes(() => {
+        try {
+          return checkedBps(bps, 'slippageBps');
+        } catch (error) {
+          return error;
+        }
+      })).toEqual({ lenient: expect.any(ValidationError), strict: expect.any(ValidationError) });
+    }
+  });
+});
@@ -0,0 +1,109 @@
+import { MomentumSignal, MomentumTrigger } from '../../src/momentum';
+import { StrategyEvent } from '../../src/strategy';
+import { ValidationError } from '../../src/errors';
+
+const TOKEN = '0x1111111111111111111111111111111111111111';
+const UNWATCHED = '0x2222222222222222222222222222222222222222';
+const LAUNCH = 10n ** 15n;
+
+const buyer = (n: number) => `0x${n.toString(16).padStart(40, '0')}`;
+
+function buy(account: string, price: bigint = LAUNCH, token: string = TOKEN): StrategyEvent {
+  return {
+    kind: 'trade',
+    trade: { side: 'buy', blockNumber: 7, event: { token, account, price, amount: 1n, cost: 1n, fee: 0n, offers: 0n, funds: 0n } },
+  };
+}
+
+const tick = (timestamp: number): StrategyEvent => ({ kind: 'block', block: { number: timestamp, timestamp } });
+
+function feed(trigger: MomentumTrigger, events: StrategyEvent[]): MomentumSignal[] {
+  return events.flatMap((event) => trigger.observe(event));
+}
+
+describe('MomentumTrigger', () => {
+  const config = { minNewBuyers: 3, maxPriceMultiple: 2, windowSeconds: 30 };
+
+  it('signals once enough first-time buyers arrive within the window', () => {
+    const trigger = new MomentumTrigger(config);
+    trigger.watch(TOKEN);
+
+    const signals = feed(trigger, [tick(1000), buy(buyer(1)), buy(buyer(1)), buy(buyer(2)), tick(1010), buy(buyer(3), LAUNCH * 3n / 2n)]);
+
+    expect(signals).toHaveLength(1);
+    expect(signals[0]).toMatchObject({
+      type: 'momentum',
+      tokenAddress: TOKEN,
+      timestamp: 1010,
+      newBuyers: 3,
+      previousNewBuyers: 0,
+      trackedBuyers: 3,
+      buyersSaturated: false,
+      launchPrice: LAUNCH,
+      priceMultipleBps: 15000,
+      blockNumber: 7,
+    });
+  });
+
+  it('only counts first-time buyers inside the rolling window', () => {
+    const trigger = new MomentumTrigger(config);
+    trigger.watch(TOKEN);
+
+    expect(feed(trigger, [tick(1000), buy(buyer(1)), buy(buyer(2)), tick(1031), buy(buyer(3)), buy(buyer(1))])).toEqual([]);
+    const [signal] = feed(trigger, [buy(buyer(4)), buy(buyer(5))]);
+    expect(signal).toMatchObject({ newBuyers: 3, previousNewBuyers: 2 });
+  });
+
+  it('stays quiet once the price reaches the multiple of the launch price', () => {
+    const trigger = new MomentumTrigger(config);
+    trigger.watch(TOKEN, LAUNCH);
+
+    expect(feed(trigger, [tick(1000), buy(buyer(1)), buy(buyer(2)), buy(buyer(3), LAUNCH * 2n)])).toEqual([]);
+  });
+
+  it('waits out the cooldown between signals', () => {
+    const trigger = new MomentumTrigger({ ...config, cooldownSeconds: 60 });
+    trigger.watch(TOKEN);
+
+    expect(feed(trigger, [tick(1000), buy(buyer(1)), buy(buyer(2)), buy(buyer(3))])).toHaveLength(1);
+    expect(feed(trigger, [tick(1040), buy(buyer(4)), buy(buyer(5)), buy(buyer(6))])).toEqual([]);
+    expect(feed(trigger, [tick(1061), buy(buyer(7))])).toHaveLength(1);
+  });
+
+  it('watches created tokens unless disabled, and ignores unwatched ones', () => {
+    const created: StrategyEvent = {
+      kind: 'tokenCreated',
+      event: { creator: buyer(9), token: TOKEN, requestId: 1n, name: 'T', symbol: 'T', totalSupply: 1n, launchTime: 0n, launchFee: 0n },
+    };
+    const auto = new MomentumTrigger(config);
+    auto.observe(created);
+    expect(auto.isWatching(TOKEN)).toBe(true);
+    expect(feed(auto, [buy(buyer(1), LAUNCH, UNWATCHED), buy(buyer(2), LAUNCH, UNWATCHED), buy(buyer(3), LAUNCH, UNWATCHED)])).toEqual([]);
+
+    const manual = new MomentumTrigger({ ...config, watchNewTokens: false });
+    manual.observe(created);
+    expect(manual.isWatching(TOKEN)).toBe(false);
+  });
+
+  it('forgets the least recently active buyers beyond maxTrackedBuyers', () => {
+    const trigger = new MomentumTrigger({ ...config, minNewBuyers: 4, maxTrackedBuyers: 2 });
+    trigger.watch(TOKEN);
+
+    // buyer(1) is forgotten when buyer(3) arrives, so its second buy counts as new
+    const [signal] = feed(trigger, [tick(1000), buy(buyer(1)), buy(buyer(2)), buy(buyer(3)), buy(buyer(1))]);
+    expect(signal).toMatchObject({ newBuyers: 4, trackedBuyers: 2, buyersSaturated: true });
+  });
+
+  it('drops the oldest token beyond maxTokens', () => {
+    const trigger = new MomentumTrigger({ ...config, maxTokens: 1 });
+    trigger.watch(TOKEN);
+    trigger.watch(UNWATCHED);
+    expect(trigger.isWatching(TOKEN)).toBe(false);
+    expect(trigger.isWatching(UNWATCHED)).toBe(true);
+  });
+
+  it('rejects invalid thresholds', () => {
+    expect(() => new MomentumTrigger({ minNewBuyers: 0, maxPriceMultiple: 2 })).toThrow(ValidationError);
+    expect(() => new MomentumTrigger({ minNewBuyers: 1, maxPriceMultiple: 0 })).toThrow(ValidationError);
+  });
+});
@@ -0,0 +1,156 @@
+import { aggregate3, readAllowances, readBuyQuotes, readTokenInfos, MulticallExecutor } from '../../src/multicall';
+import { TokenInfoLayoutError } from '../../src/errors';
+import { getErc20Interface, getFourTradingInterface, getMulticall3Interface } from '../../src/abi';
+import { TokenInfo } from '../../src/types';
+
+const OWNER = '0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa';
+const SPENDER = '0x5c952063c7fc8610FFDB798152D69F0B9550762b';
+const TOKEN_A = '0x1111111111111111111111111111111111111111';
+const TOKEN_B = '0x2222222222222222222222222222222222222222';
+const TOKEN_C = '0x3333333333333333333333333333333333333333';
+
+/**
+ * Fake Multicall3: answers each sub-call from `respond`, recording how many round trips were made
+ */
+function fakeMulticall(respond: (target: string, callData: string) => { success: boolean; returnData: string }) {
+  const iface = getMulticall3Interface();
+  let roundTrips = 0;
+  const execute: MulticallExecutor = async (data) => {
+    roundTrips++;
+    const [calls] = iface.decodeFunctionData('aggregate3', data);
+    const results = calls.map((call: any) => {
+      expect(call.allowFailure).toBe(true);
+      return respond(call.target, call.callData);
+    });
+    return iface.encodeFunctionResult('aggregate3', [results]);
+  };
+  return { execute, roundTrips: () => roundTrips };
+}
+
+const allowanceOf = (value: bigint) => ({
+  success: true,
+  returnData: getErc20Interface().encodeFunctionResult('allowance', [value]),
+});
+
+describe('readAllowances', () => {
+  it('reads every allowance in one round trip', async () => {
+    const values: Record<string, bigint> = { [TOKEN_A]: 0n, [TOKEN_B]: 10n ** 30n };
+    const { execute, roundTrips } = fakeMulticall((target, callData) => {
+      const [owner, spender] = getErc20Interface().decodeFunctionData('allowance', callData);
+      expect([owner, spender]).toEqual([OWNER, SPENDER]);
+      return allowanceOf(values[target]);
+    });
+
+    expect(await readAllowances(execute, [TOKEN_A, TOKEN_B], OWNER, SPENDER)).toEqual([0n, 10n ** 30n]);
+    expect(roundTrips()).toBe(1);
+  });
+
+  it('isolates tokens whose read reverts or returns garbage', async () => {
+    const { execute } = fakeMulticall((target) => {
+      if (target === TOKEN_B) {
+        return { success: false, returnData: '0x' };
+      }
+      if (target === TOKEN_C) {
+        return { success: true, returnData: '0x' };
+      }
+      return allowanceOf(5n);
+    });
+
+    expect(await readAllowances(execute, [TOKEN_A, TOKEN_B, TOKEN_C], OWNER, SPENDER)).toEqual([5n, null, null]);
+  });
+});
+
+describe('aggregate3', () => {
+  it('skips the call entirely for an empty batch', async () => {
+    const execute = jest.fn();
+    expect(await aggregate3(execute, [])).toEqual([]);
+    expect(execute).not.toHaveBeenCalled();
+  });
+});
+
+describe('readTokenInfos', () => {
+  const four = getFourTradingInterface();
+  const entry = (token: string) =>
+    four.encodeFunctionResult('_tokenInfos', [token, '0x0000000000000000000000000000000000000000', 1n, 10n ** 27n, 8n * 10n ** 26n, 24n * 10n ** 18n, 1700000000n, 0n, 0n, 0n, 0n, 0n, 0n]);
+  const tokenOf = (callData: string) => four.decodeFunctionData('_tokenInfos', callData)[0] as string;
+
+  it('reports reverted reads as null', async () => {
+    const { execute, roundTrips } = fakeMulticall((target, callData) => {
+      expect(target).toBe(SPENDER);
+      return tokenOf(callData) === TOKEN_B ? { success: false, returnData: '0x' } : { success: true, returnData: entry(tokenOf(callData)) };
+    });
+
+    const infos = await readTokenInfos(execute, SPENDER, [TOKEN_A, TOKEN_B]);
+
+    expect(infos.map((info) => info?.base)).toEqual([TOKEN_A, undefined]);
+    expect(roundTrips()).toBe(1);
+  });
+
+  it('fails the batch when an entry has a different field count', async () => {
+    const { execute } = fakeMulticall((_, callData) => ({
+      success: true,
+      returnData: tokenOf(callData) === TOKEN_B ? entry(TOKEN_B) + '0'.repeat(64) : entry(tokenOf(callData)),
+    }));
+
+    await expect(readTokenInfos(execute, SPENDER, [TOKEN_A, TOKEN_B])).rejects.toBeInstanceOf(TokenInfoLayoutError);
+  });
+});
+
+describe('readBuyQuotes', () => {
+  const ONE = 10n ** 18n;
+  const info: TokenInfo = {
+    base: TOKEN_A,
+    quote: '0x0000000000000000000000000000000000000000',
+    template: 1n,
+    totalSupply: 10n ** 27n,
+    maxOffers: 8n * 10n ** 26n,
+    maxRaising: 24n * ONE,
+    launchTime: 1700000000n,
+    offers: 8n * 10n ** 26n,
+    funds: 0n,
+    lastPrice: 0n,
+    K: 0n,
+    T: 0n,
+    status: 0n,
+  };
+
+  // 1% fee (a flat 1 BNB for 3 BNB spends), 1000 tokens per wei of funds
+  function fakeManager(failBuyOf?: bigint) {
+    const iface = getFourTradingInterface();
+    return fakeMulticall((target, callData) => {
+      expect(target).toBe(SPENDER);
+      const { name, args } = iface.parseTransaction({ data: callData })!;
+      const funds: bigint = args[1];
+      if (name === 'calcTradingFee') {
+        const fee = funds === 3n * ONE ? 3n * ONE : funds / 100n;
+        return { success: true, returnData: iface.encodeFunctionResult(name, [fee]) };
+      }
+      if (funds === failBuyOf) {
+        return { success: false, returnData: '0x' };
+      }
+      return { success: true, returnData: iface.encodeFunctionResult(name, [funds * 1000n]) };
+    });
+  }
+
+  it('reads every size in two round trips', async () => {
+    const { execute, roundTrips } = fakeManager();
+    const amounts = [ONE / 10n, ONE, 2n * ONE];
+
+    const reads = await readBuyQuotes(execute, SPENDER, info, amounts);
+
+    expect(reads).toEqual(amounts.map((amount) => ({
+      fee: amount / 100n,
+      grossTokenAmount: amount * 1000n,
+      tokenAmount: (amount - amount / 100n) * 1000n,
+    })));
+    expect(roundTrips()).toBe(2);
+  });
+
+  it('skips the buy reads of sizes the fee consumes and isolates failed reads', async () => {
+    const { execute } = fakeManager(ONE);
+
+    const reads = await readBuyQuotes(execute, SPENDER, info, [3n * ONE, ONE]);
+
+    expect(reads).toEqual([{ fee: 3n * ONE, grossTokenAmount: 0n, tokenAmount: 0n }, null]);
+  });
+});
@@ -0,0 +1,43 @@
+import { TypedDataEncoder, Wallet } from 'ethers';
+import { PERMIT_TYPES, PermitDomain, permitRequest, recoverPermitSigner, resolvePermitDomain } from '../../src/permit';
+
+const TOKEN = '0x1111111111111111111111111111111111111111';
+const SPENDER = '0x5c952063c7fc8610FFDB798152D69F0B9550762b';
+const holder = new Wallet('0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d');
+
+const domain: PermitDomain = { name: 'Moon Dog', version: '1', chainId: 56n, verifyingContract: TOKEN };
+const separator = (version: string) => TypedDataEncoder.hashDomain({ ...domain, version });
+
+describe('resolvePermitDomain', () => {
+  it('matches the version the token reports', () => {
+    expect(resolvePermitDomain(TOKEN, 56n, 'Moon Dog', '3', separator('3'))).toEqual({ ...domain, version: '3' });
+  });
+
+  it('falls back to the common versions when the token reports none', () => {
+    expect(resolvePermitDomain(TOKEN, 56n, 'Moon Dog', undefined, separator('1'))).toEqual(domain);
+    expect(resolvePermitDomain(TOKEN, 56n, 'Moon Dog', undefined, separator('2'))!.version).toBe('2');
+  });
+
+  it('is undefined when no candidate matches', () => {
+    expect(resolvePermitDomain(TOKEN, 97n, 'Moon Dog', '1', separator('1'))).toBeUndefined();
+    expect(resolvePermitDomain(TOKEN, 56n, 'Moon Dog', undefined, `0x${'00'.repeat(32)}`)).toBeUndefined();
+  });
+});
+
+describe('recoverPermitSigner', () => {
+  const request = permitRequest(domain, { owner: holder.address, spender: SPENDER, value: 10n ** 24n, nonce: 0n, deadline: 1_900_000_000n });
+
+  it('recovers the holder from its typed-data signature', async () => {
+    const signature = await holder.signTypedData(request.domain, request.types, request.message);
+
+    expect(request.types).toBe(PERMIT_TYPES);
+    expect(recoverPermitSigner(request, signature)).toBe(holder.address);
+  });
+
+  it('recovers someone else once the nonce has moved on', async () => {
+    const signature = await holder.signTypedData(request.domain, request.types, request.message);
+    const stale = permitRequest(domain, { ...request.message, nonce: 1n });
+
+    expect(recoverPermitSigner(stale, signature)).not.toBe(holder.address);
+  });
+});
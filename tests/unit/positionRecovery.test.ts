@@ -0,0 +1,56 @@
+import { replayPosition } from '../../src/positionRecovery';
+import { BackfillEvent } from '../../src/backfill';
+
+const ONE = 10n ** 18n;
+const TOKEN = '0x1234567890123456789012345678901234567890';
+const WALLET = '0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa';
+const OTHER = '0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb';
+
+function fill(side: 'buy' | 'sell', account: string, amount: bigint, cost: bigint, fee: bigint, blockNumber: number): BackfillEvent {
+  return {
+    side,
+    event: { token: TOKEN, account, price: 0n, amount, cost, fee, offers: 0n, funds: 0n },
+    blockNumber,
+    txHash: `0x${blockNumber.toString(16)}`,
+  };
+}
+
+describe('replayPosition', () => {
+  const fills = [
+    fill('buy', WALLET, 1000n * ONE, (ONE * 99n) / 100n, ONE / 100n, 1), // 1 BNB with the fee
+    fill('buy', OTHER, 5000n * ONE, 5n * ONE, 0n, 2),
+    fill('buy', WALLET, 1000n * ONE, 3n * ONE, 0n, 3),
+    fill('sell', WALLET, 500n * ONE, ONE, ONE / 100n, 4), // 0.99 BNB net
+  ];
+
+  it('replays the wallet\'s fills at average cost', () => {
+    const position = replayPosition(TOKEN, WALLET, fills, 1500n * ONE);
+
+    expect(position).toMatchObject({
+      token: TOKEN,
+      amount: 1500n * ONE,
+      knownAmount: 1500n * ONE,
+      knownCost: 3n * ONE,
+      unknownAmount: 0n,
+      realizedPnl: -ONE / 100n,
+      basisUnknown: false,
+    });
+  });
+
+  it('adds tokens the fills don\'t explain with an unknown basis', () => {
+    const position = replayPosition(TOKEN, WALLET, fills, 2000n * ONE);
+
+    expect(position).toMatchObject({ amount: 2000n * ONE, knownAmount: 1500n * ONE, unknownAmount: 500n * ONE, basisUnknown: true });
+  });
+
+  it('removes tokens that left the wallet at average cost without realizing pnl', () => {
+    const position = replayPosition(TOKEN, WALLET, fills, 750n * ONE);
+
+    expect(position).toMatchObject({ amount: 750n * ONE, knownAmount: 750n * ONE, knownCost: (3n * ONE) / 2n, realizedPnl: -ONE / 100n });
+  });
+
+  it('returns an empty position for a wallet that never traded the token', () => {
+    expect(replayPosition(TOKEN, WALLET, [], 0n)).toMatchObject({ amount: 0n, knownCost: 0n, basisUnknown: false });
+    expect(replayPosition(TOKEN, WALLET, [], ONE)).toMatchObject({ amount: ONE, unknownAmount: ONE, basisUnknown: true });
+  });
+});
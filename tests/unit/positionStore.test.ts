@@ -0,0 +1,149 @@
+import { promises as fs } from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { JsonFilePositionStore, MemoryPositionStore, PositionStore, PositionUpdate } from '../../src/positionStore';
+import { PositionTracker } from '../../src/positionTracker';
+
+const ONE = 10n ** 18n;
+const TOKEN_A = '0x1111111111111111111111111111111111111111';
+const TOKEN_B = '0x2222222222222222222222222222222222222222';
+
+async function tempDir(): Promise<string> {
+  return await fs.mkdtemp(path.join(os.tmpdir(), 'positions-'));
+}
+
+describe('PositionTracker with a store', () => {
+  it('restores positions from a memory store', async () => {
+    const store = new MemoryPositionStore();
+    const tracker = await PositionTracker.open(store);
+    tracker.recordBuy(TOKEN_A, 1000n * ONE, ONE);
+    tracker.recordSell(TOKEN_A, 400n * ONE, ONE);
+    await tracker.flush();
+
+    const restored = await PositionTracker.open(store);
+    expect(restored.getPosition(TOKEN_A)).toEqual(tracker.getPosition(TOKEN_A));
+    expect(store.getTrades().map((trade) => trade.kind)).toEqual(['buy', 'sell']);
+  });
+
+  it('surfaces failed writes on flush', async () => {
+    const failing: PositionStore = {
+      load: async () => new Map(),
+      apply: async () => {
+        throw new Error('disk full');
+      },
+      clear: async () => undefined,
+    };
+    const tracker = await PositionTracker.open(failing);
+    jest.spyOn(console, 'error').mockImplementation(() => undefined);
+
+    tracker.recordBuy(TOKEN_A, ONE, ONE);
+    await expect(tracker.flush()).rejects.toThrow('disk full');
+    await expect(tracker.flush()).resolves.toBeUndefined();
+  });
+});
+
+describe('JsonFilePositionStore', () => {
+  let dir: string;
+
+  beforeEach(async () => {
+    dir = await tempDir();
+  });
+
+  afterEach(async () => {
+    await fs.rm(dir, { recursive: true, force: true });
+  });
+
+  it('restores state across reopen, before and after compaction', async () => {
+    const tracker = await PositionTracker.open(new JsonFilePositionStore(dir, 3));
+    tracker.recordBuy(TOKEN_A, 1000n * ONE, ONE);
+    tracker.markExternalAcquisition(TOKEN_B, 50n * ONE);
+    tracker.recordBuy(TOKEN_A, 1000n * ONE, 3n * ONE); // Triggers compaction
+    tracker.recordSell(TOKEN_A, 500n * ONE, 2n * ONE);
+    await tracker.flush();
+
+    const restored = await PositionTracker.open(new JsonFilePositionStore(dir, 3));
+    expect(restored.getPositions()).toEqual(tracker.getPositions());
+    expect(restored.getPosition(TOKEN_B)!.basisUnknown).toBe(true);
+  });
+
+  it('serializes concurrent trades from trackers sharing one store', async () => {
+    const store = new JsonFilePositionStore(dir, 5);
+    const tracker = await PositionTracker.open(store);
+
+    for (let i = 0; i < 20; i++) {
+      tracker.recordBuy(i % 2 === 0 ? TOKEN_A : TOKEN_B, ONE, ONE);
+    }
+    await tracker.flush();
+
+    const restored = await new JsonFilePositionStore(dir).load();
+    expect(restored.get(TOKEN_A)!.amount).toBe(10n * ONE);
+    expect(restored.get(TOKEN_B)!.amount).toBe(10n * ONE);
+  });
+
+  describe('crash recovery', () => {
+    const update = (amount: bigint): PositionUpdate => ({
+      trade: { kind: 'buy', token: TOKEN_A, tokenAmount: amount, bnbAmount: amount, timestamp: 1 },
+      position: {
+        token: TOKEN_A,
+        amount,
+        knownAmount: amount,
+        knownCost: amount,
+        unknownAmount: 0n,
+        realizedPnl: 0n,
+        basisUnknown: false,
+      },
+    });
+
+    it('compacts after appends already queued, never between them', async () => {
+      const store = new JsonFilePositionStore(dir, 100);
+      const appends = [store.apply(update(ONE)), store.apply(update(2n * ONE))];
+      await store.compact();
+      await Promise.all(appends);
+
+      expect(await fs.readFile(path.join(dir, 'positions.journal'), 'utf8')).toBe('');
+      expect(JSON.parse(await fs.readFile(path.join(dir, 'positions.json'), 'utf8')).seq).toBe(2);
+    });
+
+    it('replays journaled trades the snapshot never received', async () => {
+      // Killed after the journal append, before the next snapshot
+      const store = new JsonFilePositionStore(dir, 100);
+      await store.apply(update(ONE));
+      await store.compact();
+      await store.apply(update(2n * ONE));
+
+      const snapshot = await fs.readFile(path.join(dir, 'positions.json'), 'utf8');
+      expect(JSON.parse(snapshot).seq).toBe(1);
+
+      const positions = await new JsonFilePositionStore(dir).load();
+      expect(positions.get(TOKEN_A)!.amount).toBe(2n * ONE);
+    });
+
+    it('discards a torn journal line written mid-crash and keeps appending cleanly', async () => {
+      const store = new JsonFilePositionStore(dir, 100);
+      await store.apply(update(ONE));
+      await fs.appendFile(path.join(dir, 'positions.journal'), '{"seq":2,"trade":{"kind":"bu');
+
+      const reopened = new JsonFilePositionStore(dir, 100);
+      expect((await reopened.load()).get(TOKEN_A)!.amount).toBe(ONE);
+
+      await reopened.apply(update(3n * ONE));
+      expect((await new JsonFilePositionStore(dir).load()).get(TOKEN_A)!.amount).toBe(3n * ONE);
+    });
+
+    it('skips journal entries already folded into the snapshot', async () => {
+      // Crash between writing the snapshot and truncating the journal
+      const store = new JsonFilePositionStore(dir, 100);
+      await store.apply(update(ONE));
+      await store.apply(update(2n * ONE));
+      const journal = await fs.readFile(path.join(dir, 'positions.journal'), 'utf8');
+      await store.compact();
+      await fs.writeFile(path.join(dir, 'positions.journal'), journal);
+
+      const reopened = new JsonFilePositionStore(dir, 100);
+      expect((await reopened.load()).get(TOKEN_A)!.amount).toBe(2n * ONE);
+
+      await reopened.apply(update(5n * ONE));
+      expect((await new JsonFilePositionStore(dir).load()).get(TOKEN_A)!.amount).toBe(5n * ONE);
+    });
+  });
+});
@@ -0,0 +1,136 @@
+import { PositionTracker, breakEvenPrice } from '../../src/positionTracker';
+import { MemoryPositionStore } from '../../src/positionStore';
+import { Price } from '../../src/price';
+
+const ONE = 10n ** 18n;
+const TOKEN = '0x1234567890123456789012345678901234567890';
+
+describe('PositionTracker', () => {
+  let tracker: PositionTracker;
+
+  beforeEach(() => {
+    tracker = new PositionTracker();
+  });
+
+  describe('known basis', () => {
+    it('should accumulate buys and compute average entry price', () => {
+      tracker.recordBuy(TOKEN, 1000n * ONE, ONE);
+      tracker.recordBuy(TOKEN, 1000n * ONE, 3n * ONE);
+
+      const position = tracker.getPosition(TOKEN)!;
+      expect(position.amount).toBe(2000n * ONE);
+      expect(position.knownCost).toBe(4n * ONE);
+      expect(position.basisUnknown).toBe(false);
+      expect(tracker.averageEntryPrice(TOKEN)!.equals(new Price(1n, 500n))).toBe(true);
+    });
+
+    it('should realize pnl against the average cost', () => {
+      tracker.recordBuy(TOKEN, 1000n * ONE, ONE);
+
+      const realized = tracker.recordSell(TOKEN, 500n * ONE, ONE);
+      expect(realized.costBasis).toBe(ONE / 2n);
+      expect(realized.pnl).toBe(ONE / 2n);
+      expect(realized.basisUnknown).toBe(false);
+      expect(tracker.getPosition(TOKEN)!.amount).toBe(500n * ONE);
+    });
+
+    it('should compute unrealized pnl', () => {
+      tracker.recordBuy(TOKEN, 1000n * ONE, ONE);
+      expect(tracker.unrealizedPnl(TOKEN, new Price(1n, 500n))).toBe(ONE);
+    });
+  });
+
+  describe('unknown basis (airdrops)', () => {
+    it('should represent airdropped tokens explicitly', () => {
+      tracker.markExternalAcquisition(TOKEN, 1000n * ONE);
+
+      const position = tracker.getPosition(TOKEN)!;
+      expect(position.unknownAmount).toBe(1000n * ONE);
+      expect(position.basisUnknown).toBe(true);
+      expect(tracker.averageEntryPrice(TOKEN)).toBeNull();
+      expect(tracker.unrealizedPnl(TOKEN, new Price(1n, 1000n))).toBeNull();
+    });
+
+    it('should report sell-all proceeds of an airdrop as pure profit without dividing by zero', () => {
+      tracker.markExternalAcquisition(TOKEN, 1000n * ONE);
+
+      const realized = tracker.recordSell(TOKEN, 1000n * ONE, 2n * ONE);
+      expect(realized.pnl).toBe(2n * ONE);
+      expect(realized.costBasis).toBe(0n);
+      expect(realized.basisUnknown).toBe(true);
+
+      const position = tracker.getPosition(TOKEN)!;
+      expect(position.amount).toBe(0n);
+      expect(position.basisUnknown).toBe(false);
+      expect(position.realizedPnl).toBe(2n * ONE);
+    });
+
+    it('should treat a sell of an untracked token as basis-unknown', () => {
+      const realized = tracker.recordSell(TOKEN, ONE, ONE);
+      expect(realized.pnl).toBe(ONE);
+      expect(realized.basisUnknown).toBe(true);
+    });
+
+    it('should split mixed positions pro rata', () => {
+      tracker.recordBuy(TOKEN, 1000n * ONE, ONE);
+      tracker.markExternalAcquisition(TOKEN, 1000n * ONE);
+
+      const realized = tracker.recordSell(TOKEN, 1000n * ONE, 2n * ONE);
+      expect(realized.costBasis).toBe(ONE / 2n);
+      expect(realized.pnl).toBe(2n * ONE - ONE / 2n);
+      expect(realized.basisUnknown).toBe(true);
+    });
+
+    it('should accept a manually backfilled basis', () => {
+      tracker.markExternalAcquisition(TOKEN, 1000n * ONE, ONE);
+
+      const position = tracker.getPosition(TOKEN)!;
+      expect(position.basisUnknown).toBe(false);
+      expect(tracker.recordSell(TOKEN, 1000n * ONE, 3n * ONE).pnl).toBe(2n * ONE);
+    });
+  });
+});
+
+describe('PositionTracker.restore', () => {
+  it('should replace the position and persist it', async () => {
+    const store = new MemoryPositionStore();
+    const tracker = await PositionTracker.open(store);
+    tracker.recordBuy(TOKEN, 1000n * ONE, ONE);
+
+    const restored = tracker.restore({
+      token: TOKEN,
+      amount: 400n * ONE,
+      knownAmount: 400n * ONE,
+      knownCost: ONE / 2n,
+      unknownAmount: 0n,
+      realizedPnl: 0n,
+      basisUnknown: false,
+    });
+    await tracker.flush();
+
+    expect(tracker.getPosition(TOKEN)).toEqual(restored);
+    expect((await store.load()).get(TOKEN.toLowerCase())!.amount).toBe(400n * ONE);
+    expect(store.getTrades().map((trade) => trade.kind)).toEqual(['buy', 'restore']);
+  });
+});
+
+describe('breakEvenPrice', () => {
+  it('should recover cost plus gas after the fee', () => {
+    // 1 BNB cost, 0.01 BNB gas, 1% fee over 1000 tokens
+    const price = breakEvenPrice(ONE, 1000n * ONE, ONE / 100n, new Price(1n, 100n));
+    const gross = price.mul(new Price(1000n * ONE));
+    const net = gross.sub(gross.mul(new Price(1n, 100n)));
+
+    expect(net.equals(new Price(ONE + ONE / 100n))).toBe(true);
+    expect(price.gt(Price.fromAmounts(ONE, 1000n * ONE))).toBe(true);
+  });
+
+  it('should equal the entry price without gas or fees', () => {
+    expect(breakEvenPrice(ONE, 1000n * ONE, 0n, Price.ZERO).equals(Price.fromAmounts(ONE, 1000n * ONE))).toBe(true);
+  });
+
+  it('should reject empty positions and a 100% fee', () => {
+    expect(() => breakEvenPrice(ONE, 0n, 0n, Price.ZERO)).toThrow(RangeError);
+    expect(() => breakEvenPrice(ONE, ONE, 0n, new Price(1n))).toThrow(RangeError);
+  });
+});
@@ -0,0 +1,133 @@
+import { OwnPendingTx, minReplacementPrice, planPreemption, replacementFees } from '../../src/preemption';
+
+const GWEI = 10n ** 9n;
+const TOKEN = '0x1111111111111111111111111111111111111111';
+const OTHER = '0x2222222222222222222222222222222222222222';
+const EXIT = 3n * GWEI;
+
+function pending(nonce: number, overrides: Partial<OwnPendingTx> = {}): OwnPendingTx {
+  return { txHash: `0x${nonce.toString(16).padStart(64, '0')}`, nonce, token: TOKEN, side: 'buy', gasPrice: GWEI, ...overrides };
+}
+
+describe('minReplacementPrice', () => {
+  it('adds the minimum bump, rounded up', () => {
+    expect(minReplacementPrice(GWEI, 1000)).toBe(1_100_000_000n);
+    expect(minReplacementPrice(7n, 1000)).toBe(8n);
+    expect(minReplacementPrice(0n, 1000)).toBe(0n);
+  });
+});
+
+describe('replacementFees', () => {
+  const original = { maxFeePerGas: 10n * GWEI, maxPriorityFeePerGas: GWEI };
+
+  it('never lowers an EIP-1559 original fee cap below its minimum bump', () => {
+    expect(replacementFees(original, 2n * GWEI, 1000)).toEqual({ maxFeePerGas: 11n * GWEI, maxPriorityFeePerGas: 2n * GWEI });
+  });
+
+  it('raises both fields to a bid above them', () => {
+    expect(replacementFees(original, 20n * GWEI, 1000)).toEqual({ maxFeePerGas: 20n * GWEI, maxPriorityFeePerGas: 20n * GWEI });
+  });
+
+  it('bumps the tip even when the bid is below it', () => {
+    expect(replacementFees(original, GWEI / 2n, 1000)).toEqual({ maxFeePerGas: 11n * GWEI, maxPriorityFeePerGas: 1_100_000_000n });
+  });
+});
+
+describe('planPreemption', () => {
+  it('has nothing to do without pending transactions', () => {
+    expect(planPreemption(TOKEN, [], EXIT)).toEqual([]);
+  });
+
+  describe('with one conflicting buy', () => {
+    it('cancels it at the exit price', () => {
+      expect(planPreemption(TOKEN, [pending(4)], EXIT)).toEqual([
+        { txHash: pending(4).txHash, nonce: 4, conflicting: true, action: 'cancel', gasPrice: EXIT, reason: 'pending buy of the token being exited' },
+      ]);
+    });
+
+    it('matches the token case-insensitively', () => {
+      expect(planPreemption(TOKEN.toUpperCase().replace('0X', '0x'), [pending(4)], EXIT)[0].conflicting).toBe(true);
+    });
+
+    it('prices the cancel at the minimum bump when that beats the exit price', () => {
+      const [decision] = planPreemption(TOKEN, [pending(4, { gasPrice: 5n * GWEI })], EXIT);
+
+      expect(decision).toMatchObject({ action: 'cancel', gasPrice: 5_500_000_000n });
+    });
+
+    it('reprices it when the policy says so', () => {
+      const [decision] = planPreemption(TOKEN, [pending(4)], EXIT, { conflictingBuy: 'reprice' });
+
+      expect(decision).toMatchObject({ action: 'reprice', gasPrice: EXIT });
+    });
+
+    it('leaves a reprice alone when already priced at the exit', () => {
+      const [decision] = planPreemption(TOKEN, [pending(4, { gasPrice: EXIT })], EXIT, { conflictingBuy: 'reprice' });
+
+      expect(decision).toEqual(expect.objectContaining({ action: 'wait', reason: 'already priced at or above the exit' }));
+      expect(decision.gasPrice).toBeUndefined();
+    });
+
+    it('waits for it when the policy says so', () => {
+      const [decision] = planPreemption(TOKEN, [pending(4)], EXIT, { conflictingBuy: 'wait' });
+
+      expect(decision).toMatchObject({ action: 'wait', conflicting: true });
+    });
+  });
+
+  describe('with other pending transactions', () => {
+    it('does not count sells of the token or buys of other tokens as conflicting', () => {
+      const decisions = planPreemption(TOKEN, [pending(1, { side: 'sell' }), pending(2, { token: OTHER })], EXIT);
+
+      expect(decisions.map((d) => [d.conflicting, d.action])).toEqual([[false, 'reprice'], [false, 'reprice']]);
+    });
+
+    it('waits for them when the policy says so', () => {
+      const decisions = planPreemption(TOKEN, [pending(1, { token: OTHER }), pending(2)], EXIT, { otherPending: 'wait' });
+
+      expect(decisions.map((d) => d.action)).toEqual(['wait', 'cancel']);
+    });
+  });
+
+  describe('with multiple conflicting buys', () => {
+    const queue = [pending(7), pending(5), pending(6, { token: OTHER }), pending(8)];
+
+    it('handles them lowest nonce first', () => {
+      const decisions = planPreemption(TOKEN, queue, EXIT);
+
+      expect(decisions.map((d) => [d.nonce, d.action])).toEqual([[5, 'cancel'], [6, 'reprice'], [7, 'cancel'], [8, 'cancel']]);
+      expect(decisions.every((d) => d.gasPrice === EXIT)).toBe(true);
+    });
+
+    it('stops replacing at the limit, leaving the highest nonces to wait', () => {
+      const decisions = planPreemption(TOKEN, queue, EXIT, { maxReplacements: 2 });
+
+      expect(decisions.map((d) => d.action)).toEqual(['cancel', 'reprice', 'wait', 'wait']);
+      expect(decisions[3].reason).toBe('replacement limit of 2 reached');
+    });
+
+    it('does not count skipped transactions toward the limit', () => {
+      const decisions = planPreemption(TOKEN, [pending(1, { token: OTHER, gasPrice: EXIT }), pending(2), pending(3)], EXIT, { maxReplacements: 1 });
+
+      expect(decisions.map((d) => d.action)).toEqual(['wait', 'cancel', 'wait']);
+    });
+
+    it('uses the configured bump for the price floor', () => {
+      const decisions = planPreemption(TOKEN, [pending(1, { gasPrice: 3n * GWEI }), pending(2)], EXIT, { minBumpBps: 5000 });
+
+      expect(decisions.map((d) => d.gasPrice)).toEqual([4_500_000_000n, EXIT]);
+    });
+
+    it('does not modify the pending list', () => {
+      const copy = [...queue];
+      planPreemption(TOKEN, queue, EXIT);
+
+      expect(queue).toEqual(copy);
+    });
+  });
+
+  it('rejects an invalid replacement limit', () => {
+    expect(() => planPreemption(TOKEN, [], EXIT, { maxReplacements: -1 })).toThrow(/maxReplacements/);
+    expect(() => planPreemption(TOKEN, [], EXIT, { maxReplacements: 1.5 })).toThrow(/maxReplacements/);
+  });
+});
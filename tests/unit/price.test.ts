@@ -0,0 +1,88 @@
+import { Price } from '../../src/price';
+
+describe('Price', () => {
+  describe('construction', () => {
+    it('should reduce numerator and denominator', () => {
+      const price = new Price(10n, 20n);
+      expect(price.numerator).toBe(1n);
+      expect(price.denominator).toBe(2n);
+    });
+
+    it('should normalize a negative denominator', () => {
+      const price = new Price(1n, -2n);
+      expect(price.numerator).toBe(-1n);
+      expect(price.denominator).toBe(2n);
+    });
+
+    it('should reject a zero denominator', () => {
+      expect(() => new Price(1n, 0n)).toThrow(RangeError);
+    });
+
+    it('should return zero price when token amount is zero', () => {
+      expect(Price.fromAmounts(100n, 0n).isZero()).toBe(true);
+    });
+  });
+
+  describe('precision', () => {
+    it('should keep prices around 1e-12 BNB that truncate to zero as wei', () => {
+      // 1 wei of BNB buys 10^30 token wei: 1e-30 BNB per token
+      const price = Price.fromAmounts(1n, 10n ** 30n);
+
+      expect(price.toScaled(18)).toBe(0n);
+      expect(price.isZero()).toBe(false);
+      expect(price.toScaled(36)).toBe(1000000n);
+    });
+
+    it('should format decimal strings', () => {
+      expect(new Price(3n, 2n).toDecimalString(2)).toBe('1.50');
+      expect(Price.fromScaled(1n, 12).toDecimalString(12)).toBe('0.000000000001');
+      expect(new Price(-1n, 4n).toDecimalString(2)).toBe('-0.25');
+      expect(new Price(7n).toDecimalString(0)).toBe('7');
+    });
+
+    it('should convert to a lossy number', () => {
+      expect(new Price(1n, 4n).toNumber()).toBe(0.25);
+      expect(Price.fromAmounts(1n, 10n ** 12n).toNumber()).toBeCloseTo(1e-12, 24);
+    });
+  });
+
+  describe('comparison', () => {
+    it('should compare exactly', () => {
+      const a = new Price(1n, 3n);
+      const b = new Price(333333333333333333n, 10n ** 18n);
+
+      expect(a.gt(b)).toBe(true);
+      expect(b.lt(a)).toBe(true);
+      expect(a.equals(new Price(2n, 6n))).toBe(true);
+      expect(a.compare(a)).toBe(0);
+    });
+  });
+
+  describe('arithmetic', () => {
+    it('should add, subtract, multiply and divide exactly', () => {
+      const half = new Price(1n, 2n);
+      const third = new Price(1n, 3n);
+
+      expect(half.add(third).equals(new Price(5n, 6n))).toBe(true);
+      expect(half.sub(third).equals(new Price(1n, 6n))).toBe(true);
+      expect(half.mul(third).equals(new Price(1n, 6n))).toBe(true);
+      expect(half.div(third).equals(new Price(3n, 2n))).toBe(true);
+    });
+
+    it('should throw when dividing by zero', () => {
+      expect(() => new Price(1n).div(Price.ZERO)).toThrow(RangeError);
+    });
+
+    it('should compute exact percentage change', () => {
+      const oldPrice = Price.fromAmounts(3n, 10n ** 30n);
+      const newPrice = Price.fromAmounts(4n, 10n ** 30n);
+
+      const change = oldPrice.percentChangeTo(newPrice);
+      expect(change.equals(new Price(100n, 3n))).toBe(true);
+    });
+
+    it('should return zero change from a zero price', () => {
+      expect(Price.ZERO.percentChangeTo(new Price(1n)).isZero()).toBe(true);
+    });
+  });
+});
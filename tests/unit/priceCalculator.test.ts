@@ -1,6 +1,33 @@
-import { PriceCalculator } from '../../src/priceCalculator';
+import { PriceCalculator, buyCostBreakdown, buyLimits, buyPriceInfo, checkBuyLimits, zeroBuyReason } from '../../src/priceCalculator';
 import { ethers } from 'ethers';
-import { FeeExceedsAmountError } from '../../src/errors';
+import { AmountOutOfRangeError, FeeExceedsAmountError, QuoteReturnedZeroError } from '../../src/errors';
+import { curveBuyAmount, curveParams, curveSellCost } from '../../src/curve';
+import { Price } from '../../src/price';
+import { getFourTradingInterface } from '../../src/abi';
+
+const MANAGER = '0x5c952063c7fc8610FFDB798152D69F0B9550762b';
+
+/**
+ * Contract shape PriceCalculator reads through: `_tokenInfos` is fetched raw via `runner.call`,
+ * answered here from `read`
+ */
+function rawTokenInfos<T extends object>(contract: T, read: (address: string, blockTag?: number) => Promise<any>) {
+  const iface = getFourTradingInterface();
+  return Object.assign(contract, {
+    interface: iface,
+    target: MANAGER,
+    runner: {
+      call: async (tx: { data: string; blockTag?: number }) => {
+        const [address] = iface.decodeFunctionData('_tokenInfos', tx.data);
+        const info = await read(address, tx.blockTag);
+        return iface.encodeFunctionResult('_tokenInfos', [
+          info.base, info.quote, info.template, info.totalSupply, info.maxOffers, info.maxRaising,
+          info.launchTime, info.offers, info.funds, info.lastPrice, info.K, info.T, info.status,
+        ]);
+      },
+    },
+  });
+}
 
 // Mock contract for testing
 class MockContract {
@@ -26,7 +53,7 @@ class MockContract {
     });
   }
 
-  async _tokenInfos(address: string) {
+  async _tokenInfos(address: string, _overrides?: { blockTag: number }) {
     const info = this.tokenInfos.get(address.toLowerCase());
     if (!info) {
       throw new Error('Token not found');
@@ -34,6 +61,10 @@ class MockContract {
     return info;
   }
 
+  async _templates(template: any) {
+    return { minTradingFee: 1000000000000000n }; // 0.001 BNB
+  }
+
   async calcTradingFee(tokenInfo: any, amount: bigint): Promise<bigint> {
     // 1% fee
     return amount / 100n;
@@ -70,7 +101,9 @@ describe('PriceCalculator', () => {
 
   beforeEach(() => {
     mockContract = new MockContract();
-    calculator = new PriceCalculator(mockContract as any);
+    calculator = new PriceCalculator(rawTokenInfos(mockContract, (address, blockTag) =>
+      blockTag === undefined ? mockContract._tokenInfos(address) : mockContract._tokenInfos(address, { blockTag })
+    ) as any);
   });
 
   describe('getTokenInfo', () => {
@@ -97,6 +130,18 @@ describe('PriceCalculator', () => {
       spy.mockRestore();
     });
 
+    it('should bypass the cache and pass the block tag when reading at a block', async () => {
+      const spy = jest.spyOn(mockContract, '_tokenInfos');
+
+      await calculator.getTokenInfo(testTokenAddress);
+      await calculator.getTokenInfo(testTokenAddress, 120);
+
+      expect(spy).toHaveBeenCalledTimes(2);
+      expect(spy).toHaveBeenLastCalledWith(testTokenAddress, { blockTag: 120 });
+
+      spy.mockRestore();
+    });
+
     it('should normalize address to lowercase for caching', async () => {
       // Use valid hex address (ethers requires 0x prefix)
       const mixedCaseAddress = testTokenAddress; // Already has 0x prefix
@@ -141,6 +186,25 @@ describe('PriceCalculator', () => {
       expect(quote.pricePerToken).toBeGreaterThan(0n);
     });
 
+    it('should report gross tokens exceeding net tokens by the fee', async () => {
+      const bnbAmount = 1000000000000000000n; // 1 BNB
+      const quote = await calculator.quoteBuy(testTokenAddress, bnbAmount);
+
+      expect(quote.netTokenAmount).toBe(quote.tokenAmount);
+      expect(quote.grossTokenAmount!).toBeGreaterThan(quote.netTokenAmount!);
+      // Mock rate: 1 BNB = 1000 tokens, so the fee costs exactly fee * 1000 tokens
+      expect(quote.grossTokenAmount! - quote.netTokenAmount!).toBe(quote.fee * 1000n);
+    });
+
+    it('should match a quote assembled from the same reads', async () => {
+      const bnbAmount = 1000000000000000000n; // 1 BNB
+      const quote = await calculator.quoteBuy(testTokenAddress, bnbAmount);
+      const info = await calculator.getTokenInfo(testTokenAddress);
+
+      expect(buyPriceInfo(info, bnbAmount, quote.fee, quote.grossTokenAmount!, quote.tokenAmount)).toEqual(quote);
+      expect(() => buyPriceInfo(info, bnbAmount, bnbAmount, 0n, 0n)).toThrow(FeeExceedsAmountError);
+    });
+
     it('should reject invalid token address', async () => {
       await expect(
         calculator.quoteBuy('invalid', 1000000000000000000n)
@@ -179,6 +243,33 @@ describe('PriceCalculator', () => {
       expect(quote.tokenAmount).toBeGreaterThan(0n);
     });
 
+    it('should not flag buys that fit in the remaining curve', async () => {
+      const quote = await calculator.quoteBuy(testTokenAddress, 1000000000000000000n); // 1 BNB of 5 BNB left
+
+      expect(quote.willPartiallyFill).toBe(false);
+      expect(quote.fillableAmount).toBe(1000000000000000000n);
+    });
+
+    it('should flag buys that overshoot the curve and report the fillable amount', async () => {
+      const bnbAmount = 10000000000000000000n; // 10 BNB, 9.9 BNB after fee, 5 BNB left
+      const quote = await calculator.quoteBuy(testTokenAddress, bnbAmount);
+      const fee = bnbAmount / 100n;
+      const remaining = 5000000000000000000n;
+
+      expect(quote.willPartiallyFill).toBe(true);
+      expect(quote.fillableAmount).toBe(remaining + (fee * remaining) / (bnbAmount - fee));
+    });
+
+    it('should check partial fills without quoting token amounts', async () => {
+      const spy = jest.spyOn(mockContract, 'calcBuyAmount');
+
+      const fill = await calculator.checkPartialFill(testTokenAddress, 6000000000000000000n);
+
+      expect(fill.willPartiallyFill).toBe(true);
+      expect(spy).not.toHaveBeenCalled();
+      spy.mockRestore();
+    });
+
     it('should use cache for repeated queries', async () => {
       const spy = jest.spyOn(mockContract, '_tokenInfos');
 
@@ -191,6 +282,32 @@ describe('PriceCalculator', () => {
     });
   });
 
+  describe('getBuyLimits', () => {
+    it('should allow spends above the template minimum fee with no maximum', async () => {
+      const limits = await calculator.getBuyLimits(testTokenAddress);
+
+      expect(limits).toEqual({ minFunds: 1000000000000001n, maxFunds: 2n ** 256n - 1n });
+    });
+
+    it('should cache the minimum fee per template', async () => {
+      const spy = jest.spyOn(mockContract, '_templates');
+
+      await calculator.getBuyLimits(testTokenAddress);
+      await calculator.getBuyLimits(testTokenAddress);
+
+      expect(spy).toHaveBeenCalledTimes(1);
+      spy.mockRestore();
+    });
+
+    it('should reject spends outside the limits', () => {
+      const limits = buyLimits(1000n);
+
+      expect(() => checkBuyLimits(testTokenAddress, 1001n, limits)).not.toThrow();
+      expect(() => checkBuyLimits(testTokenAddress, 1000n, limits)).toThrow(AmountOutOfRangeError);
+      expect(() => checkBuyLimits(testTokenAddress, 5000n, { minFunds: 1001n, maxFunds: 4000n })).toThrow('above the maximum of 4000 wei');
+    });
+  });
+
   describe('quoteSell', () => {
     it('should calculate sell quote correctly', async () => {
       const tokenAmount = 1000000000000000000n; // 1000 tokens
@@ -467,7 +584,8 @@ describe('PriceCalculator', () => {
       const originalCalcBuy = mockContract.calcBuyAmount.bind(mockContract);
       mockContract.calcBuyAmount = async () => 0n;
 
-      const quote = await calculator.quoteBuy(testTokenAddress, 1000000000000000000n);
+      await expect(calculator.quoteBuy(testTokenAddress, 1000000000000000000n)).rejects.toThrow(QuoteReturnedZeroError);
+      const quote = await calculator.quoteBuy(testTokenAddress, 1000000000000000000n, { allowDustFills: true });
 
       expect(quote.tokenAmount).toBe(0n);
       expect(quote.pricePerToken).toBe(0n); // Avoid division by zero
@@ -501,3 +619,134 @@ describe('PriceCalculator', () => {
     });
   });
 });
+
+describe('zero-output quotes on the curve math', () => {
+  const token = '0x1234567890123456789012345678901234567890';
+  const ONE = 10n ** 18n;
+  const ceilDiv = (a: bigint, b: bigint) => (a + b - 1n) / b;
+
+  function tokenInfo(T: bigint, initialQuoteReserve: bigint, overrides: Record<string, bigint> = {}) {
+    return {
+      base: token, quote: ethers.ZeroAddress, template: 0n, totalSupply: T,
+      maxOffers: (T * 8n) / 10n, maxRaising: 24n * ONE, launchTime: 0n,
+      offers: (T * 8n) / 10n, funds: 0n, lastPrice: 0n,
+      K: T * initialQuoteReserve, T, status: 0n,
+      ...overrides,
+    };
+  }
+
+  // Fee-free contract computing quotes with the curve.ts formulas; buys stop at the offers left
+  function calculatorFor(info: any) {
+    const params = () => curveParams(token, info);
+    return new PriceCalculator(rawTokenInfos({
+      calcTradingFee: async () => 0n,
+      calcBuyAmount: async (_: unknown, funds: bigint) => {
+        const bought = curveBuyAmount(params(), funds);
+        return bought < info.offers ? bought : info.offers;
+      },
+      calcSellCost: async (_: unknown, amount: bigint) => curveSellCost(params(), amount),
+    }, async () => info) as any);
+  }
+
+  it('rejects the largest spend that rounds to zero tokens and accepts one wei more', async () => {
+    // A coarse curve (1000 token units against 1000 wei of virtual quote) so a buy can round to zero
+    const info = tokenInfo(1000n, 1000n);
+    const { virtualTokenReserve, virtualQuoteReserve, K } = curveParams(token, info);
+    const threshold = ceilDiv(K, virtualTokenReserve - 1n) - virtualQuoteReserve;
+    const calculator = calculatorFor(info);
+
+    const error = await calculator.quoteBuy(token, threshold - 1n).catch((e) => e);
+    expect(error).toBeInstanceOf(QuoteReturnedZeroError);
+    expect(error).toMatchObject({ side: 'buy', reason: 'roundsToZero', amount: threshold - 1n });
+    expect((await calculator.quoteBuy(token, threshold)).tokenAmount).toBe(1n);
+    expect((await calculator.quoteBuy(token, threshold - 1n, { allowDustFills: true })).tokenAmount).toBe(0n);
+  });
+
+  it('rejects the largest sell that rounds to zero BNB and accepts one unit more', async () => {
+    const info = tokenInfo(1_000_000_000n * ONE, 30n * ONE);
+    const { virtualTokenReserve, virtualQuoteReserve, K } = curveParams(token, info);
+    const threshold = ceilDiv(K, virtualQuoteReserve - 1n) - virtualTokenReserve;
+    const calculator = calculatorFor(info);
+
+    await expect(calculator.quoteSell(token, threshold - 1n)).rejects.toMatchObject({ side: 'sell', reason: 'roundsToZero' });
+    expect((await calculator.quoteSell(token, threshold)).bnbCost).toBe(1n);
+    expect(await calculator.quoteSell(token, threshold - 1n, { allowDustFills: true })).toMatchObject({ bnbCost: 0n, fee: 0n });
+  });
+
+  it('reports a curve with nothing left to sell as full', async () => {
+    const info = tokenInfo(1_000_000_000n * ONE, 30n * ONE, { offers: 0n });
+
+    await expect(calculatorFor(info).quoteBuy(token, ONE)).rejects.toMatchObject({ reason: 'curveFull' });
+    expect(zeroBuyReason(tokenInfo(1000n, 1000n, { funds: 24n * ONE }) as any)).toBe('curveFull');
+    expect(zeroBuyReason(tokenInfo(1000n, 1000n) as any)).toBe('roundsToZero');
+  });
+});
+
+describe('V1 tokens', () => {
+  const token = '0x1234567890123456789012345678901234567890';
+  const ONE = 10n ** 18n;
+
+  // A V2 manager that must not be read, and a helper answering for a V1 token
+  function v1Calculator() {
+    const v2 = rawTokenInfos({
+      calcTradingFee: jest.fn(),
+      calcBuyAmount: jest.fn(),
+      calcSellCost: jest.fn(),
+      _templates: jest.fn(),
+    }, async () => {
+      throw new Error('V2 _tokenInfos read for a V1 token');
+    });
+    const helper = {
+      getTokenInfo: jest.fn(async () => ({
+        quote: ethers.ZeroAddress, lastPrice: 5n, minTradingFee: ONE / 1000n, launchTime: 1700000000n,
+        offers: 400n * ONE, maxOffers: 800n * ONE, funds: 3n * ONE, maxFunds: 24n * ONE, liquidityAdded: false,
+      })),
+      tryBuy: jest.fn(async (_token: string, _amount: bigint, funds: bigint) => ({ estimatedAmount: funds * 990n, estimatedCost: 0n, estimatedFee: funds / 100n })),
+      trySell: jest.fn(async (_token: string, amount: bigint) => ({ funds: amount / 1000n, fee: amount / 100_000n })),
+    };
+    return { v2, helper, calculator: new PriceCalculator(v2 as any, helper as any, async () => 1) };
+  }
+
+  it('reads and quotes through the helper, never the V2 manager', async () => {
+    const { v2, helper, calculator } = v1Calculator();
+
+    expect(await calculator.getTokenInfo(token)).toMatchObject({ base: token, maxRaising: 24n * ONE, funds: 3n * ONE, K: 0n, T: 0n });
+    expect(await calculator.quoteBuy(token, ONE)).toMatchObject({ tokenAmount: 990n * ONE, fee: ONE / 100n, willPartiallyFill: false });
+    expect(await calculator.quoteSell(token, 1000n * ONE)).toMatchObject({ bnbCost: ONE - ONE / 100n, fee: ONE / 100n });
+    expect(await calculator.getBuyLimits(token)).toEqual(buyLimits(ONE / 1000n));
+    expect(helper.getTokenInfo).toHaveBeenCalledTimes(1);
+    expect(v2.calcTradingFee).not.toHaveBeenCalled();
+    expect(v2.calcBuyAmount).not.toHaveBeenCalled();
+    expect(v2.calcSellCost).not.toHaveBeenCalled();
+    expect(v2._templates).not.toHaveBeenCalled();
+  });
+
+  it('detects partial fills against the helper-reported raise target', async () => {
+    const { calculator } = v1Calculator();
+
+    expect((await calculator.checkPartialFill(token, 30n * ONE)).willPartiallyFill).toBe(true);
+    expect((await calculator.checkPartialFill(token, ONE)).willPartiallyFill).toBe(false);
+  });
+});
+
+describe('buyCostBreakdown', () => {
+  it('should split a buy into principal, fee and gas', () => {
+    const quote = {
+      tokenAmount: 1000n,
+      bnbCost: 100n,
+      pricePerToken: 0n,
+      price: Price.fromAmounts(100n, 1000n),
+      fee: 1n,
+    };
+
+    expect(buyCostBreakdown(quote, 5n, 10)).toEqual({
+      principal: 99n,
+      fee: 1n,
+      gasCost: 5n,
+      total: 105n,
+      expectedTokens: 1000n,
+      minTokens: 900n,
+      willPartiallyFill: false,
+    });
+  });
+});
@@ -0,0 +1,19 @@
+import { PriceChangeFilter } from '../../src/priceStream';
+
+describe('PriceChangeFilter', () => {
+  it('should pass the first price and moves of at least the threshold since the last emission', () => {
+    const filter = new PriceChangeFilter(100); // 1%
+    const prices = [10000n, 10050n, 10099n, 10100n, 10000n, 9999n];
+
+    expect(prices.map((price) => filter.accept(price))).toEqual([true, false, false, true, false, true]);
+  });
+
+  it('should pass everything with a zero threshold and treat any move off zero as crossing it', () => {
+    const unthrottled = new PriceChangeFilter(0);
+    const throttled = new PriceChangeFilter(100);
+
+    expect([0n, 0n, 5n, 5n].map((price) => unthrottled.accept(price))).toEqual([true, true, true, true]);
+    expect([0n, 0n, 5n].map((price) => throttled.accept(price))).toEqual([true, false, true]);
+    expect(() => new PriceChangeFilter(-1)).toThrow(RangeError);
+  });
+});
@@ -0,0 +1,126 @@
+import { QuoteComparator, QuoteDivergence, Quoter, quoteDivergenceBps } from '../../src/quoteDivergence';
+import { PriceInfo, QuoteOptions } from '../../src/priceCalculator';
+import { Price } from '../../src/price';
+
+const TOKEN = '0x1234567890123456789012345678901234567890';
+
+function priceInfo(tokenAmount: bigint, bnbCost: bigint): PriceInfo {
+  return { tokenAmount, bnbCost, pricePerToken: 0n, price: new Price(1n), fee: 0n };
+}
+
+function quoter(quote: (options?: QuoteOptions) => Promise<PriceInfo>): Quoter {
+  return { quoteBuy: (_token, _amount, options) => quote(options), quoteSell: (_token, _amount, options) => quote(options) };
+}
+
+const delay = <T>(ms: number, value: T) => new Promise<T>((resolve) => setTimeout(() => resolve(value), ms));
+
+describe('quoteDivergenceBps', () => {
+  it('measures the gap relative to the secondary quote', () => {
+    expect(quoteDivergenceBps(1000n, 1000n)).toBe(0);
+    expect(quoteDivergenceBps(990n, 1000n)).toBe(100);
+    expect(quoteDivergenceBps(1010n, 1000n)).toBe(100);
+    expect(quoteDivergenceBps(0n, 0n)).toBe(0);
+    expect(quoteDivergenceBps(1n, 0n)).toBe(Number.POSITIVE_INFINITY);
+  });
+});
+
+describe('QuoteComparator', () => {
+  const options = { thresholdBps: 50, budgetMs: 20 };
+
+  it('returns the latest primary quote and compares both providers at the older head', async () => {
+    const seen: QuoteDivergence[] = [];
+    const secondaryTags: Array<number | undefined> = [];
+    const primaryTags: Array<number | undefined> = [];
+    const comparator = new QuoteComparator(
+      quoter(async (quoteOptions) => {
+        secondaryTags.push(quoteOptions?.blockTag);
+        return priceInfo(1000n, 5n);
+      }),
+      async () => 101,
+      async () => 99,
+      options,
+      (divergence) => seen.push(divergence)
+    );
+
+    const result = await comparator.quote('buy', TOKEN, 5n, async (blockTag) => {
+      primaryTags.push(blockTag);
+      return priceInfo(blockTag === undefined ? 950n : 900n, 5n);
+    });
+
+    expect(result.tokenAmount).toBe(950n);
+    expect(primaryTags).toEqual([undefined, 99]);
+    expect(secondaryTags).toEqual([99]);
+    expect(seen).toHaveLength(1);
+    expect(seen[0]).toMatchObject({
+      side: 'buy', token: TOKEN, amount: 5n, primary: 900n, secondary: 1000n,
+      divergenceBps: 1000, blockTag: 99, primaryBlock: 101, secondaryBlock: 99,
+    });
+  });
+
+  it('compares sell proceeds and stays quiet within the threshold', async () => {
+    const seen: QuoteDivergence[] = [];
+    const comparator = new QuoteComparator(
+      quoter(async () => priceInfo(7n, 10000n)),
+      async () => 1,
+      async () => 1,
+      options,
+      (divergence) => seen.push(divergence)
+    );
+
+    await comparator.quote('sell', TOKEN, 7n, async () => priceInfo(7n, 9960n));
+    expect(seen).toHaveLength(0);
+  });
+
+  it('never fails the primary when the secondary or its block lookup fails', async () => {
+    const seen: QuoteDivergence[] = [];
+    const failing = new QuoteComparator(
+      quoter(async () => {
+        throw new Error('secondary down');
+      }),
+      async () => 1,
+      async () => 1,
+      options,
+      (divergence) => seen.push(divergence)
+    );
+    await expect(failing.quote('buy', TOKEN, 1n, async () => priceInfo(5n, 1n))).resolves.toMatchObject({ tokenAmount: 5n });
+
+    const noBlock = new QuoteComparator(
+      quoter(async () => priceInfo(10n, 1n)),
+      async () => 1,
+      async () => {
+        throw new Error('no block');
+      },
+      options,
+      (divergence) => seen.push(divergence)
+    );
+    // Without the secondary's head there is no common block to compare at
+    await expect(noBlock.quote('buy', TOKEN, 1n, async () => priceInfo(5n, 1n))).resolves.toMatchObject({ tokenAmount: 5n });
+    expect(seen).toHaveLength(0);
+  });
+
+  it('waits at most the budget for a slow secondary, and still reports it later', async () => {
+    const seen: QuoteDivergence[] = [];
+    const comparator = new QuoteComparator(
+      quoter(() => delay(200, priceInfo(1000n, 1n))),
+      async () => 1,
+      async () => 1,
+      options,
+      (divergence) => seen.push(divergence)
+    );
+
+    const started = Date.now();
+    await comparator.quote('buy', TOKEN, 1n, async () => priceInfo(500n, 1n));
+    expect(Date.now() - started).toBeLessThan(150);
+    expect(seen).toHaveLength(0);
+
+    await delay(250, undefined);
+    expect(seen).toHaveLength(1);
+  });
+
+  it('propagates primary failures', async () => {
+    const comparator = new QuoteComparator(quoter(async () => priceInfo(1n, 1n)), async () => 1, async () => 1, options, () => undefined);
+    await expect(comparator.quote('buy', TOKEN, 1n, async () => {
+      throw new Error('primary down');
+    })).rejects.toThrow('primary down');
+  });
+});
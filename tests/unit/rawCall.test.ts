@@ -0,0 +1,38 @@
+import { AbiCoder, FunctionFragment } from 'ethers';
+import { decodeRawResult, encodeRawCall, parseRawFunction } from '../../src/rawCall';
+import { encodeSellCalldata } from '../../src/calldata';
+import { ValidationError } from '../../src/errors';
+
+const TOKEN = '0x' + '44'.repeat(20);
+
+describe('raw calls', () => {
+  it('parses signatures with or without the function keyword', () => {
+    const bare = parseRawFunction('sellToken(address,uint256,uint256)');
+    const full = parseRawFunction('function sellToken(address token, uint256 amount, uint256 minFunds)');
+
+    expect(bare.selector).toBe(full.selector);
+    expect(parseRawFunction(' getFee() view returns (uint256) ').outputs).toHaveLength(1);
+    expect(parseRawFunction(full)).toBe(full);
+  });
+
+  it('rejects malformed signatures and mismatched arguments', () => {
+    expect(() => parseRawFunction('sellToken(address,uint256')).toThrow(ValidationError);
+    expect(() => encodeRawCall(parseRawFunction('sellToken(address,uint256,uint256)'), [TOKEN, 1n])).toThrow(ValidationError);
+  });
+
+  it('encodes the same calldata as the typed encoders', () => {
+    const fragment = parseRawFunction('sellToken(address,uint256,uint256)');
+    expect(encodeRawCall(fragment, [TOKEN, 5n, 2n])).toBe(encodeSellCalldata(TOKEN, 5n, 2n));
+  });
+
+  it('unwraps a single output, keeps several as a result and passes raw data through without outputs', () => {
+    const coder = AbiCoder.defaultAbiCoder();
+    const single = coder.encode(['uint256'], [42n]);
+    const pair = coder.encode(['address', 'uint256'], [TOKEN, 7n]);
+
+    expect(decodeRawResult(parseRawFunction('fee() view returns (uint256)'), single)).toBe(42n);
+    const result = decodeRawResult(FunctionFragment.from('function info() view returns (address base, uint256 offers)'), pair) as any;
+    expect([result.base.toLowerCase(), result.offers]).toEqual([TOKEN, 7n]);
+    expect(decodeRawResult(parseRawFunction('poke()'), pair)).toBe(pair);
+  });
+});
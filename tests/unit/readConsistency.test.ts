@@ -0,0 +1,70 @@
+import { readAtLeast, ReplicaLagEvent } from '../../src/readConsistency';
+import { ReplicaLaggingError } from '../../src/errors';
+
+/**
+ * Load-balanced RPC whose replicas take turns answering: `heads` lists the latest block each
+ * successive request sees, and reads at a block the answering replica hasn't reached fail
+ */
+function laggingRpc(heads: number[], balances: Map<number, bigint>) {
+  let request = 0;
+  const nextHead = () => heads[Math.min(request++, heads.length - 1)];
+  return {
+    getBlockNumber: async () => nextHead(),
+    read: async (blockTag: number) => {
+      if (nextHead() < blockTag) {
+        throw new Error('header not found');
+      }
+      return balances.get(blockTag) ?? balances.get(Math.max(...balances.keys()))!;
+    },
+  };
+}
+
+const options = (onLag?: (event: ReplicaLagEvent) => void) => ({ retries: 5, intervalMs: 1, onLag });
+
+describe('readAtLeast', () => {
+  test('reads immediately when the node is already at the block', async () => {
+    const rpc = laggingRpc([100], new Map([[100, 5n]]));
+    const onLag = jest.fn();
+
+    await expect(readAtLeast(100, rpc.getBlockNumber, rpc.read, options(onLag))).resolves.toBe(5n);
+    expect(onLag).not.toHaveBeenCalled();
+  });
+
+  test('waits for a lagging replica and reports the lag', async () => {
+    // The trade landed in block 100; the first replica is still at 98 and holds the old balance
+    const rpc = laggingRpc([98, 99, 100, 100], new Map([[98, 0n], [99, 0n], [100, 5n]]));
+    const events: ReplicaLagEvent[] = [];
+
+    const balance = await readAtLeast(100, rpc.getBlockNumber, rpc.read, options((event) => events.push(event)));
+
+    expect(balance).toBe(5n);
+    expect(events).toHaveLength(1);
+    expect(events[0]).toMatchObject({ minBlock: 100, observedBlock: 100, retries: 2 });
+  });
+
+  test('retries when the read lands on a replica behind the pinned block', async () => {
+    // Head check hits an up-to-date replica, the read hits one at 99, then both are caught up
+    const rpc = laggingRpc([100, 99, 100, 100], new Map([[100, 5n]]));
+    const onLag = jest.fn();
+
+    await expect(readAtLeast(100, rpc.getBlockNumber, rpc.read, options(onLag))).resolves.toBe(5n);
+    expect(onLag).toHaveBeenCalledWith(expect.objectContaining({ retries: 1 }));
+  });
+
+  test('gives up with ReplicaLaggingError when the node never catches up', async () => {
+    const rpc = laggingRpc([97], new Map([[97, 0n]]));
+
+    const error = await readAtLeast(100, rpc.getBlockNumber, rpc.read, options()).catch((e) => e);
+    expect(error).toBeInstanceOf(ReplicaLaggingError);
+    expect(error.minBlock).toBe(100);
+    expect(error.observedBlock).toBe(97);
+  });
+
+  test('rethrows the read error when the node is caught up but reads keep failing', async () => {
+    const read = jest.fn().mockRejectedValue(new Error('execution reverted'));
+
+    await expect(readAtLeast(100, async () => 101, read, options())).rejects.toThrow('execution reverted');
+    expect(read).toHaveBeenCalledTimes(6);
+    expect(read).toHaveBeenCalledWith(101);
+  });
+});
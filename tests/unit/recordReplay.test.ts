@@ -0,0 +1,139 @@
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import {
+  RpcRecorder,
+  RpcReplay,
+  RpcJournalEntry,
+  RpcTransport,
+  RecordingWebSocket,
+  firstDifference,
+  readJournal,
+} from '../../src/recordReplay';
+import { RpcDivergenceError } from '../../src/errors';
+
+function entry(seq: number, method: string, params: unknown, result: unknown, transport: RpcTransport = 'http'): RpcJournalEntry {
+  return { seq, time: 0, transport, method, params, result };
+}
+
+function notification(seq: number, subscription: string, result: unknown): RpcJournalEntry {
+  return { seq, time: 0, transport: 'ws', method: 'eth_subscription', subscription, result };
+}
+
+describe('RpcRecorder', () => {
+  test('numbers exchanges and appends them to the journal file', async () => {
+    const file = path.join(fs.mkdtempSync(path.join(os.tmpdir(), 'rpc-journal-')), 'session.jsonl');
+    const recorder = new RpcRecorder(file);
+
+    recorder.record({ transport: 'http', method: 'eth_blockNumber', params: [], result: '0x10' });
+    recorder.record({ transport: 'ws', method: 'eth_subscription', subscription: '0x1', result: { blockNumber: '0x10' } });
+
+    const journal = await readJournal(file);
+    expect(journal.map((e) => e.seq)).toEqual([0, 1]);
+    expect(journal[0]).toMatchObject({ method: 'eth_blockNumber', result: '0x10' });
+    expect(journal).toEqual(recorder.getEntries());
+  });
+});
+
+describe('RpcReplay', () => {
+  test('serves identical requests in recorded order', () => {
+    const replay = new RpcReplay([entry(0, 'eth_blockNumber', [], '0x1'), entry(1, 'eth_blockNumber', [], '0x2')]);
+
+    expect(replay.serve('http', 'eth_blockNumber', []).result).toBe('0x1');
+    expect(replay.serve('http', 'eth_blockNumber', []).result).toBe('0x2');
+    expect(replay.remaining()).toHaveLength(0);
+  });
+
+  test('repeats the last response for extra polls unless disabled', () => {
+    const journal = [entry(0, 'eth_blockNumber', [], '0x1')];
+
+    const lenient = new RpcReplay(journal);
+    lenient.serve('http', 'eth_blockNumber', []);
+    expect(lenient.serve('http', 'eth_blockNumber', []).result).toBe('0x1');
+
+    const strict = new RpcReplay(journal, { allowRepeats: false });
+    strict.serve('http', 'eth_blockNumber', []);
+    expect(() => strict.serve('http', 'eth_blockNumber', [])).toThrow(RpcDivergenceError);
+  });
+
+  test('reports where a diverging request differs from the expected one', () => {
+    const replay = new RpcReplay([entry(0, 'eth_call', [{ to: '0xaa', data: '0x01' }, 'latest'], '0x')]);
+
+    const error = (() => {
+      try {
+        replay.serve('http', 'eth_call', [{ to: '0xaa', data: '0x02' }, 'latest']);
+      } catch (e) {
+        return e as RpcDivergenceError;
+      }
+    })()!;
+
+    expect(error).toBeInstanceOf(RpcDivergenceError);
+    expect(error.expectedSeq).toBe(0);
+    expect(error.diff).toContain('- #0 eth_call');
+    expect(error.diff).toContain('params[0].data: expected "0x01", got "0x02"');
+    expect(replay.divergences).toEqual([error]);
+    expect(() => replay.assertComplete()).toThrow(error);
+  });
+
+  test('holds notifications until the requests recorded before them are replayed', async () => {
+    const replay = new RpcReplay([
+      entry(0, 'eth_subscribe', ['newHeads'], '0x9', 'ws'),
+      notification(1, '0x9', { number: '0x1' }),
+      entry(2, 'eth_blockNumber', [], '0x1'),
+      notification(3, '0x9', { number: '0x2' }),
+    ]);
+    const delivered: unknown[] = [];
+    replay.attach('ws', (e) => delivered.push(e.result));
+
+    expect(delivered).toEqual([]);
+    replay.serve('ws', 'eth_subscribe', ['newHeads']);
+    await Promise.resolve();
+    expect(delivered).toEqual([{ number: '0x1' }]);
+    replay.serve('http', 'eth_blockNumber', []);
+    await Promise.resolve();
+    expect(delivered).toEqual([{ number: '0x1' }, { number: '0x2' }]);
+    expect(() => replay.assertComplete()).not.toThrow();
+  });
+
+  test('chain id checks are neither ordered nor required', () => {
+    const replay = new RpcReplay([
+      entry(0, 'eth_chainId', [], '0x38'),
+      entry(1, 'eth_chainId', [], '0x38'),
+      notification(2, '0x9', {}),
+    ]);
+    const delivered = jest.fn();
+    replay.attach('ws', delivered);
+
+    expect(delivered).toHaveBeenCalledTimes(1);
+    expect(replay.remaining()).toHaveLength(0);
+  });
+});
+
+describe('firstDifference', () => {
+  test('returns null for equal values and the path of the first mismatch otherwise', () => {
+    expect(firstDifference({ a: [1, 2] }, { a: [1, 2] })).toBeNull();
+    expect(firstDifference({ a: [1, 2] }, { a: [1, 3] })).toBe('params.a[1]: expected 2, got 3');
+    expect(firstDifference({ a: 1 }, { a: 1, b: 2 })).toBe('params.b: expected undefined, got 2');
+  });
+});
+
+describe('RecordingWebSocket', () => {
+  test('journals responses to its requests and pushed notifications', () => {
+    const socket = { onopen: null, onmessage: null as any, onerror: null, readyState: 1, send: jest.fn(), close: jest.fn() };
+    const recorder = new RpcRecorder();
+    const recording = new RecordingWebSocket(socket, recorder);
+    const received: string[] = [];
+    recording.onmessage = (message: { data: string }) => received.push(message.data);
+
+    recording.send(JSON.stringify({ jsonrpc: '2.0', id: 7, method: 'eth_subscribe', params: ['newHeads'] }));
+    socket.onmessage({ data: JSON.stringify({ jsonrpc: '2.0', id: 7, result: '0x9' }) });
+    socket.onmessage({ data: JSON.stringify({ jsonrpc: '2.0', method: 'eth_subscription', params: { subscription: '0x9', result: { number: '0x1' } } }) });
+
+    expect(socket.send).toHaveBeenCalledTimes(1);
+    expect(received).toHaveLength(2);
+    expect(recorder.getEntries()).toEqual([
+      expect.objectContaining({ seq: 0, transport: 'ws', method: 'eth_subscribe', params: ['newHeads'], result: '0x9' }),
+      expect.objectContaining({ seq: 1, transport: 'ws', method: 'eth_subscription', subscription: '0x9', result: { number: '0x1' } }),
+    ]);
+  });
+});
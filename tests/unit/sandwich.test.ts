@@ -0,0 +1,69 @@
+import { BlockTrade, detectSandwich } from '../../src/sandwich';
+
+const ONE = 10n ** 18n;
+const TOKEN = '0x1111111111111111111111111111111111111111';
+const OTHER_TOKEN = '0x2222222222222222222222222222222222222222';
+const VICTIM = '0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa';
+const ATTACKER = '0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb';
+const BYSTANDER = '0xcccccccccccccccccccccccccccccccccccccccc';
+
+function trade(txIndex: number, side: 'buy' | 'sell', account: string, amount: bigint, bnbAmount: bigint, token = TOKEN): BlockTrade {
+  return { txHash: `0x${txIndex}`, txIndex, side, token, account, amount, bnbAmount };
+}
+
+describe('detectSandwich', () => {
+  const victim = trade(5, 'buy', VICTIM, 1000n * ONE, ONE);
+
+  it('should detect a buy-then-sell bracketing the victim', () => {
+    const trades = [
+      trade(4, 'buy', ATTACKER, 2000n * ONE, ONE),
+      victim,
+      trade(6, 'sell', ATTACKER, 2000n * ONE, ONE + ONE / 10n),
+    ];
+
+    const report = detectSandwich(victim, trades, 100);
+
+    expect(report.sandwiched).toBe(true);
+    expect(report.attacker).toBe(ATTACKER);
+    expect(report.frontRunTx).toBe('0x4');
+    expect(report.backRunTx).toBe('0x6');
+    expect(report.valueExtracted).toBe(ONE / 10n);
+  });
+
+  it('should pro-rate the front-run cost for a partial back-run', () => {
+    const trades = [
+      trade(1, 'buy', ATTACKER, 2000n * ONE, ONE),
+      victim,
+      trade(9, 'sell', ATTACKER, 1000n * ONE, ONE),
+    ];
+
+    expect(detectSandwich(victim, trades, 100).valueExtracted).toBe(ONE / 2n);
+  });
+
+  it('should ignore other tokens, the victim itself and unmatched addresses', () => {
+    const trades = [
+      trade(2, 'buy', ATTACKER, ONE, ONE, OTHER_TOKEN),
+      trade(3, 'buy', BYSTANDER, ONE, ONE),
+      trade(4, 'buy', VICTIM, ONE, ONE),
+      victim,
+      trade(6, 'sell', ATTACKER, ONE, ONE * 2n, OTHER_TOKEN),
+      trade(7, 'sell', VICTIM, ONE, ONE * 2n),
+    ];
+
+    const report = detectSandwich(victim, trades, 100);
+
+    expect(report.sandwiched).toBe(false);
+    expect(report.valueExtracted).toBe(0n);
+    expect(report.token).toBe(TOKEN);
+  });
+
+  it('should not count a sell that happened before the victim', () => {
+    const trades = [
+      trade(2, 'buy', ATTACKER, ONE, ONE),
+      trade(3, 'sell', ATTACKER, ONE, ONE * 2n),
+      victim,
+    ];
+
+    expect(detectSandwich(victim, trades, 100).sandwiched).toBe(false);
+  });
+});
@@ -0,0 +1,115 @@
+import { getMulticall3Interface, getSellLimitInterface } from '../../src/abi';
+import { ValidationError } from '../../src/errors';
+import { inferMaxSellable, readSellLimitHint } from '../../src/sellLimit';
+
+const ONE = 10n ** 18n;
+const BALANCE = 1_000_000n * ONE;
+const SHARES = [10, 100, 1000, 5000];
+const TOKEN = '0x1111111111111111111111111111111111111111';
+
+// Simulated token that reverts sells above `cap`
+function probeFor(cap: bigint) {
+  const sizes: bigint[] = [];
+  const probe = async (amount: bigint) => {
+    sizes.push(amount);
+    return amount <= cap;
+  };
+  return { probe, sizes };
+}
+
+describe('inferMaxSellable', () => {
+  it('returns the balance when all of it can be sold', async () => {
+    const { probe, sizes } = probeFor(BALANCE);
+
+    expect(await inferMaxSellable(probe, BALANCE, SHARES, 10)).toBe(BALANCE);
+    expect(sizes).toEqual([BALANCE]);
+  });
+
+  it('finds the cap within the precision, never above it', async () => {
+    for (const cap of [BALANCE / 3n, BALANCE / 100n, 12_345n * ONE, BALANCE - 1n]) {
+      const found = await inferMaxSellable(probeFor(cap).probe, BALANCE, SHARES, 10);
+
+      expect(found).toBeLessThanOrEqual(cap);
+      expect(cap - found).toBeLessThanOrEqual(BALANCE / 1000n);
+    }
+  });
+
+  it('tries increasing sizes and stops at the first rejected one before refining', async () => {
+    const { probe, sizes } = probeFor(BALANCE / 20n);
+    await inferMaxSellable(probe, BALANCE, SHARES, 10);
+
+    expect(sizes.slice(0, 5)).toEqual([BALANCE, BALANCE / 1000n, BALANCE / 100n, BALANCE / 10n, expect.any(BigInt)]);
+    expect(sizes.slice(4).every((size) => size > BALANCE / 100n && size < BALANCE / 10n)).toBe(true);
+  });
+
+  it('takes an exact reported cap in one probe and refines from it', async () => {
+    const cap = 37_000n * ONE;
+    const { probe, sizes } = probeFor(cap);
+
+    const found = await inferMaxSellable(probe, BALANCE, SHARES, 10, cap);
+
+    expect(found).toBeGreaterThanOrEqual(cap - BALANCE / 1000n);
+    expect(sizes).toContain(cap);
+  });
+
+  it('does not trust a reported cap the simulation rejects', async () => {
+    const found = await inferMaxSellable(probeFor(5_000n * ONE).probe, BALANCE, SHARES, 10, 50_000n * ONE);
+
+    expect(found).toBeLessThanOrEqual(5_000n * ONE);
+  });
+
+  it('refines to the unit with zero precision', async () => {
+    expect(await inferMaxSellable(probeFor(123_456_789n).probe, BALANCE, SHARES, 0)).toBe(123_456_789n);
+  });
+
+  it('returns 0n when no size can be sold', async () => {
+    expect(await inferMaxSellable(async () => false, BALANCE, SHARES, 10)).toBe(0n);
+  });
+
+  it('returns 0n for an empty balance without probing', async () => {
+    const { probe, sizes } = probeFor(BALANCE);
+
+    expect(await inferMaxSellable(probe, 0n, SHARES, 10)).toBe(0n);
+    expect(sizes).toEqual([]);
+  });
+
+  it('rejects a negative balance', async () => {
+    await expect(inferMaxSellable(async () => true, -1n, SHARES, 10)).rejects.toBeInstanceOf(ValidationError);
+  });
+});
+
+describe('readSellLimitHint', () => {
+  const multicall = getMulticall3Interface();
+  const iface = getSellLimitInterface();
+
+  // Executor answering each getter with `answers[name]` (reverting when missing)
+  function executorFor(answers: Record<string, string>) {
+    return async (data: string) => {
+      const [calls] = multicall.decodeFunctionData('aggregate3', data);
+      const results = calls.map((call: any) => {
+        const name = iface.parseTransaction({ data: call.callData })!.name;
+        return name in answers ? [true, answers[name]] : [false, '0x'];
+      });
+      return multicall.encodeFunctionResult('aggregate3', [results]);
+    };
+  }
+
+  const uint = (name: string, value: bigint) => iface.encodeFunctionResult(name, [value]);
+
+  it('is undefined when the token exposes no cap', async () => {
+    expect(await readSellLimitHint(executorFor({}), TOKEN)).toBeUndefined();
+  });
+
+  it('takes the smallest non-zero cap', async () => {
+    const hint = await readSellLimitHint(
+      executorFor({ maxTxAmount: uint('maxTxAmount', 500n), maxSellAmount: uint('maxSellAmount', 200n), _maxTxAmount: uint('_maxTxAmount', 0n) }),
+      TOKEN
+    );
+
+    expect(hint).toBe(200n);
+  });
+
+  it('ignores getters returning something other than a uint256', async () => {
+    expect(await readSellLimitHint(executorFor({ maxTxAmount: '0x01' }), TOKEN)).toBeUndefined();
+  });
+});
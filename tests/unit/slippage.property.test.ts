@@ -0,0 +1,113 @@
+import { applyGasMultiplier, applySlippage, isMethodUnsupportedError, resolveMinAmount, slippagePercentToBps } from '../../src/utils';
+import { MAX_UINT256 } from '../../src/constants';
+
+// Deterministic PRNG so failures are reproducible from the printed seed
+function mulberry32(seed: number): () => number {
+  let state = seed >>> 0;
+  return () => {
+    state = (state + 0x6d2b79f5) >>> 0;
+    let t = state;
+    t = Math.imul(t ^ (t >>> 15), t | 1);
+    t ^= t + Math.imul(t ^ (t >>> 7), t | 61);
+    return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+  };
+}
+
+function randomUint256(random: () => number): bigint {
+  // Mix magnitudes: small amounts, wei-scale amounts and full-width values
+  const bits = [8, 64, 128, 256][Math.floor(random() * 4)];
+  let value = 0n;
+  for (let i = 0; i < bits; i += 32) {
+    value = (value << 32n) | BigInt(Math.floor(random() * 4294967296));
+  }
+  return value & ((1n << BigInt(bits)) - 1n);
+}
+
+const SEED = 0x5eed;
+const RUNS = 2000;
+
+describe('slippage math (property-based)', () => {
+  const random = mulberry32(SEED);
+
+  it(`should match the reference rational computation (seed ${SEED})`, () => {
+    for (let run = 0; run < RUNS; run++) {
+      const amount = randomUint256(random);
+      const bps = BigInt(Math.floor(random() * 10001)); // 0..10000
+      const slippagePercent = Number(bps) / 100;
+
+      const result = applySlippage(amount, slippagePercent);
+      const reference = (amount * (10000n - bps)) / 10000n;
+
+      expect(slippagePercentToBps(slippagePercent)).toBe(bps);
+      expect(result).toBe(reference);
+    }
+  });
+
+  it(`should never exceed the input or underflow (seed ${SEED})`, () => {
+    for (let run = 0; run < RUNS; run++) {
+      const amount = randomUint256(random);
+      const slippagePercent = random() * 100;
+
+      const result = applySlippage(amount, slippagePercent);
+
+      expect(result).toBeLessThanOrEqual(amount);
+      expect(result).toBeGreaterThanOrEqual(0n);
+      expect(result).toBeLessThanOrEqual(MAX_UINT256);
+    }
+  });
+
+  it('should be monotonic in slippage', () => {
+    for (let run = 0; run < RUNS; run++) {
+      const amount = randomUint256(random);
+      const a = Math.floor(random() * 10001) / 100;
+      const b = Math.floor(random() * 10001) / 100;
+      const [low, high] = a <= b ? [a, b] : [b, a];
+
+      expect(applySlippage(amount, high)).toBeLessThanOrEqual(applySlippage(amount, low));
+    }
+  });
+
+  it('should handle float artifacts at basis-point boundaries', () => {
+    // (100 - 0.29) * 100 = 9970.999999999998 in floating point
+    expect(applySlippage(10000n, 0.29)).toBe(9971n);
+    expect(applySlippage(MAX_UINT256, 0)).toBe(MAX_UINT256);
+    expect(applySlippage(MAX_UINT256, 100)).toBe(0n);
+  });
+});
+
+describe('resolveMinAmount', () => {
+  it('should take the larger of the slippage minimum and the absolute floor', () => {
+    expect(resolveMinAmount(900n, 1000n)).toBe(1000n);
+    expect(resolveMinAmount(1200n, 1000n)).toBe(1200n);
+    expect(resolveMinAmount(undefined, 1000n)).toBe(1000n);
+    expect(resolveMinAmount(900n, undefined)).toBe(900n);
+    expect(resolveMinAmount()).toBe(0n);
+  });
+});
+
+describe('applyGasMultiplier', () => {
+  it('should scale the estimate and round down', () => {
+    expect(applyGasMultiplier(200000n, 1.15)).toBe(230000n);
+    expect(applyGasMultiplier(200000n, 1.5)).toBe(300000n);
+    expect(applyGasMultiplier(200001n, 1.15)).toBe(230001n);
+    expect(applyGasMultiplier(200000n, 1)).toBe(200000n);
+  });
+});
+
+describe('isMethodUnsupportedError', () => {
+  test('recognizes unsupported-method errors however ethers wraps them', () => {
+    expect(isMethodUnsupportedError({ code: -32601, message: 'Method not found' })).toBe(true);
+    expect(isMethodUnsupportedError({ code: 'UNKNOWN_ERROR', error: { code: -32601, message: 'x' } })).toBe(true);
+    expect(isMethodUnsupportedError({
+      code: 'UNKNOWN_ERROR',
+      info: { error: { code: -32000, message: 'the method eth_estimateGas does not exist/is not available' } },
+    })).toBe(true);
+    expect(isMethodUnsupportedError(new Error('Method eth_estimateGas is not whitelisted'))).toBe(true);
+  });
+
+  test('leaves failing calls alone', () => {
+    expect(isMethodUnsupportedError({ code: 'CALL_EXCEPTION', message: 'execution reverted: Slippage' })).toBe(false);
+    expect(isMethodUnsupportedError({ code: -32000, message: 'insufficient funds for gas * price + value' })).toBe(false);
+    expect(isMethodUnsupportedError(undefined)).toBe(false);
+  });
+});
@@ -0,0 +1,71 @@
+import { netBuyVolumePerBlock, suggestSlippage } from '../../src/slippageSuggestion';
+import { BackfillEvent } from '../../src/backfill';
+import { CurveParams } from '../../src/curve';
+
+const TOKEN = '0x1234567890123456789012345678901234567890';
+const ONE = 10n ** 18n;
+
+// Fresh curve: 1B virtual tokens against 30 BNB of virtual quote
+const T = 1_000_000_000n * ONE;
+const CURVE: CurveParams = {
+  token: TOKEN,
+  K: T * 30n * ONE,
+  T,
+  sold: 0n,
+  funds: 0n,
+  virtualTokenReserve: T,
+  virtualQuoteReserve: 30n * ONE,
+  maxOffers: (T * 8n) / 10n,
+  maxRaising: 24n * ONE,
+  lastPrice: 0n,
+};
+
+function trade(side: 'buy' | 'sell', cost: bigint, blockNumber: number): BackfillEvent {
+  return {
+    side,
+    event: { token: TOKEN, account: TOKEN, price: 1n, amount: 10n, cost, fee: 0n, offers: 0n, funds: 0n },
+    blockNumber,
+    txHash: `0x${blockNumber.toString(16)}`,
+  };
+}
+
+describe('netBuyVolumePerBlock', () => {
+  it('nets sells against buys per block and floors net selling at zero', () => {
+    const volumes = netBuyVolumePerBlock([
+      trade('buy', 5n * ONE, 101),
+      trade('sell', 2n * ONE, 101),
+      trade('sell', 4n * ONE, 102),
+      trade('buy', ONE, 103),
+    ]);
+
+    expect(volumes).toEqual([0n, ONE, 3n * ONE]);
+  });
+});
+
+describe('suggestSlippage', () => {
+  it('falls back to the floor without recent trades', () => {
+    expect(suggestSlippage(CURVE, ONE / 100n, [])).toEqual({ bps: 50, aheadBnb: 0n, blocksSampled: 0, tradesSampled: 0 });
+    expect(suggestSlippage(CURVE, ONE / 100n, [], { minBps: 0 }).bps).toBe(0);
+  });
+
+  it('covers the output lost to the percentile block of buying landing first', () => {
+    const suggestion = suggestSlippage(CURVE, ONE / 100n, [trade('buy', 3n * ONE, 101)]);
+
+    // A small buy's output scales with (x / (x + ahead))^2: 1 - (30 / 33)^2 is about 17.36%
+    expect(suggestion).toEqual({ bps: 1736, aheadBnb: 3n * ONE, blocksSampled: 1, tradesSampled: 1 });
+  });
+
+  it('ignores spikes above the percentile', () => {
+    const events = Array.from({ length: 20 }, (_, i) => trade('buy', ONE / 10n, 100 + i));
+    events.push(trade('buy', 10n * ONE, 200));
+
+    expect(suggestSlippage(CURVE, ONE / 100n, events).aheadBnb).toBe(ONE / 10n);
+    expect(suggestSlippage(CURVE, ONE / 100n, events, { percentile: 100 }).aheadBnb).toBe(10n * ONE);
+  });
+
+  it('asks less of a larger buy, whose quote already includes its own impact', () => {
+    const events = [trade('buy', ONE, 101)];
+
+    expect(suggestSlippage(CURVE, 5n * ONE, events).bps).toBeLessThan(suggestSlippage(CURVE, ONE / 100n, events).bps);
+  });
+});
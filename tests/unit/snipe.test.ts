@@ -0,0 +1,99 @@
+import { LaunchWatchSource, isTokenLive, waitForLaunch } from '../../src/snipe';
+import { CancelledError, TokenLaunchTimeoutError } from '../../src/errors';
+import { TokenInfo } from '../../src/types';
+
+const TOKEN = '0x1111111111111111111111111111111111111111';
+const TRADING = 0n;
+const NOW = 1_700_000_000;
+
+const registered = (overrides: Partial<TokenInfo> = {}) =>
+  ({ totalSupply: 1n, launchTime: BigInt(NOW - 1), K: 1n, T: 1n, status: TRADING, ...overrides } as TokenInfo);
+const unregistered = { totalSupply: 0n, launchTime: 0n, K: 0n, T: 0n, status: 0n } as TokenInfo;
+
+const flush = () => new Promise((resolve) => setImmediate(resolve));
+
+function fakeSource(initial: TokenInfo) {
+  let info = initial;
+  const reads: Array<number | undefined> = [];
+  const listeners: { block?: (block: number) => void; creation?: () => void; pending?: () => void } = {};
+  const source: LaunchWatchSource = {
+    readInfo: async (blockTag) => {
+      reads.push(blockTag);
+      return info;
+    },
+    watchBlocks: (listener) => {
+      listeners.block = listener;
+      return () => delete listeners.block;
+    },
+    watchCreation: (listener) => {
+      listeners.creation = listener;
+      return () => delete listeners.creation;
+    },
+    watchPendingBuys: (listener) => {
+      listeners.pending = listener;
+      return () => delete listeners.pending;
+    },
+  };
+  return { source, reads, listeners, set: (next: TokenInfo) => (info = next) };
+}
+
+describe('isTokenLive', () => {
+  it('needs a registered, trading token past its launch time', () => {
+    expect(isTokenLive(registered(), TRADING, NOW)).toBe(true);
+    expect(isTokenLive(unregistered, TRADING, NOW)).toBe(false);
+    expect(isTokenLive(registered({ status: 1n }), TRADING, NOW)).toBe(false);
+    expect(isTokenLive(registered({ launchTime: BigInt(NOW + 60) }), TRADING, NOW)).toBe(false);
+  });
+});
+
+describe('waitForLaunch', () => {
+  it('resolves with the info read at the first block showing the token live', async () => {
+    const chain = fakeSource(unregistered);
+    const launched = waitForLaunch(TOKEN, chain.source, TRADING, { timeoutMs: 10_000, now: () => NOW });
+    await flush();
+
+    chain.listeners.block!(100);
+    await flush();
+    const live = registered({ funds: 5n });
+    chain.set(live);
+    chain.listeners.block!(101);
+
+    expect(await launched).toBe(live);
+    expect(chain.reads).toEqual([undefined, 100, 101]);
+    expect(chain.listeners).toEqual({});
+  });
+
+  it('checks again on the token creation event', async () => {
+    const chain = fakeSource(unregistered);
+    const launched = waitForLaunch(TOKEN, chain.source, TRADING, { timeoutMs: 10_000, now: () => NOW });
+    await flush();
+
+    chain.set(registered());
+    chain.listeners.creation!();
+    await expect(launched).resolves.toMatchObject({ status: TRADING });
+  });
+
+  it('only lets a pending buy skip the launch time in mempool mode', async () => {
+    const scheduled = registered({ launchTime: BigInt(NOW + 3) });
+    const block = fakeSource(scheduled);
+    const blockMode = waitForLaunch(TOKEN, block.source, TRADING, { timeoutMs: 50, now: () => NOW });
+    expect(block.listeners.pending).toBeUndefined();
+    await expect(blockMode).rejects.toThrow(TokenLaunchTimeoutError);
+
+    const mempool = fakeSource(scheduled);
+    const mempoolMode = waitForLaunch(TOKEN, mempool.source, TRADING, { timeoutMs: 10_000, mode: 'mempool', now: () => NOW });
+    await flush();
+    mempool.listeners.pending!();
+    await expect(mempoolMode).resolves.toBe(scheduled);
+  });
+
+  it('stops watching when cancelled', async () => {
+    const chain = fakeSource(unregistered);
+    const controller = new AbortController();
+    const launched = waitForLaunch(TOKEN, chain.source, TRADING, { timeoutMs: 10_000, signal: controller.signal });
+
+    controller.abort();
+    await expect(launched).rejects.toThrow(CancelledError);
+    expect(chain.listeners).toEqual({});
+  });
+});
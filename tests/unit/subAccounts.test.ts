@@ -0,0 +1,93 @@
+import { SubAccountLedger } from '../../src/subAccounts';
+import { SubAccountBalanceError, ValidationError } from '../../src/errors';
+
+const ONE = 10n ** 18n;
+const TOKEN = '0x1234567890123456789012345678901234567890';
+
+describe('SubAccountLedger', () => {
+  let ledger: SubAccountLedger;
+
+  beforeEach(() => {
+    ledger = new SubAccountLedger(10n * ONE);
+    ledger.allocate('sniper', 2n * ONE);
+    ledger.allocate('dca', 3n * ONE);
+  });
+
+  it('should allocate from unallocated funds only', () => {
+    expect(ledger.getUnallocated()).toBe(5n * ONE);
+    expect(() => ledger.allocate('extra', 6n * ONE)).toThrow(SubAccountBalanceError);
+  });
+
+  it('should enforce each sub-account allocation', () => {
+    expect(() => ledger.assertCanBuy('sniper', 2n * ONE)).not.toThrow();
+    expect(() => ledger.assertCanBuy('sniper', 2n * ONE + 1n)).toThrow(SubAccountBalanceError);
+    expect(() => ledger.assertCanSell('sniper', TOKEN, 1n)).toThrow(SubAccountBalanceError);
+    expect(() => ledger.assertCanBuy('unknown', 1n)).toThrow(ValidationError);
+  });
+
+  it('should hold reserved BNB back from other buys until released', () => {
+    const first = ledger.reserve('sniper', ONE + ONE / 2n);
+    expect(ledger.getBalance('sniper')).toMatchObject({ bnb: 2n * ONE, reserved: ONE + ONE / 2n });
+    expect(() => ledger.reserve('sniper', ONE)).toThrow(SubAccountBalanceError);
+    expect(() => ledger.release('sniper', ONE)).toThrow(SubAccountBalanceError);
+
+    first.release();
+    first.release();
+    expect(ledger.getBalance('sniper')!.reserved).toBe(0n);
+    expect(() => ledger.reserve('sniper', 2n * ONE)).not.toThrow();
+  });
+
+  it('should book fills and gas to the spending sub-account', () => {
+    ledger.recordBuy('sniper', TOKEN, 1000n * ONE, ONE);
+    ledger.chargeGas('sniper', ONE / 1000n);
+    const realized = ledger.recordSell('sniper', TOKEN, 1000n * ONE, 2n * ONE);
+
+    expect(realized.pnl).toBe(ONE);
+    expect(ledger.getBalance('sniper')).toMatchObject({ bnb: 3n * ONE - ONE / 1000n, gasSpent: ONE / 1000n });
+    expect(ledger.getBalance('dca')!.bnb).toBe(3n * ONE);
+    expect(ledger.realizedPnl('sniper')).toBe(ONE);
+    expect(ledger.realizedPnl('dca')).toBe(0n);
+  });
+
+  it('should move BNB and tokens with their basis between sub-accounts', () => {
+    ledger.recordBuy('sniper', TOKEN, 1000n * ONE, ONE);
+    ledger.transfer('dca', 'sniper', ONE);
+    ledger.transferTokens('sniper', 'dca', TOKEN, 400n * ONE);
+
+    expect(ledger.getBalance('sniper')!.bnb).toBe(2n * ONE);
+    expect(ledger.getBalance('dca')!.bnb).toBe(2n * ONE);
+    expect(ledger.getBalance('dca')!.tokens.get(TOKEN.toLowerCase())).toBe(400n * ONE);
+    expect(ledger.getPositionTracker('dca').getPosition(TOKEN)!.knownCost).toBe((ONE * 4n) / 10n);
+    expect(ledger.realizedPnl()).toBe(0n);
+    expect(() => ledger.transfer('dca', 'sniper', 3n * ONE)).toThrow(SubAccountBalanceError);
+  });
+
+  it('should restore balances and positions from a snapshot', () => {
+    ledger.recordBuy('sniper', TOKEN, 1000n * ONE, ONE);
+    ledger.chargeGas('sniper', ONE / 1000n);
+    ledger.reserve('dca', ONE);
+
+    const restored = new SubAccountLedger();
+    restored.restore(ledger.snapshot());
+    expect(restored.getUnallocated()).toBe(5n * ONE);
+    expect(restored.getBalances()).toEqual([
+      { ...ledger.getBalance('sniper')!, reserved: 0n },
+      { ...ledger.getBalance('dca')!, reserved: 0n },
+    ]);
+    expect(restored.getPositionTracker('sniper').getPositions()).toEqual(ledger.getPositionTracker('sniper').getPositions());
+  });
+
+  it('should report drift against on-chain balances', () => {
+    ledger.recordBuy('sniper', TOKEN, 1000n * ONE, ONE);
+    ledger.chargeGas('sniper', ONE / 1000n);
+
+    const inSync = ledger.reconcile(9n * ONE - ONE / 1000n, new Map([[TOKEN, 1000n * ONE]]));
+    expect(inSync.inSync).toBe(true);
+
+    // Gas of an unrecorded transaction and tokens sold outside the SDK
+    const drifted = ledger.reconcile(9n * ONE - 2n * (ONE / 1000n), new Map([[TOKEN, 900n * ONE]]));
+    expect(drifted.inSync).toBe(false);
+    expect(drifted.bnbDrift).toBe(-(ONE / 1000n));
+    expect(drifted.tokens).toEqual([{ token: TOKEN.toLowerCase(), expected: 1000n * ONE, actual: 900n * ONE, drift: -100n * ONE }]);
+  });
+});
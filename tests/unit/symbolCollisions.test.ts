@@ -0,0 +1,88 @@
+import { HOMOGLYPHS, editDistance, findCollisions, matchCollision, normalizeTokenText } from '../../src/symbolCollisions';
+
+describe('normalizeTokenText', () => {
+  it('folds case, whitespace and zero-width characters', () => {
+    expect(normalizeTokenText(' Pe Pe ')).toBe('pepe');
+    expect(normalizeTokenText('PE\u200BPE\uFEFF')).toBe('pepe');
+  });
+
+  it('strips accents and compatibility forms', () => {
+    expect(normalizeTokenText('PÉPÈ')).toBe('pepe');
+    expect(normalizeTokenText('ＰＥＰＥ')).toBe('pepe'); // Fullwidth
+  });
+
+  it('maps Cyrillic and Greek lookalikes to the letters they imitate', () => {
+    expect(normalizeTokenText('РЕРЕ')).toBe('pepe'); // Cyrillic ER IE ER IE
+    expect(normalizeTokenText('DΟGE')).toBe('doge'); // Greek capital omicron
+    expect(normalizeTokenText('саt')).toBe('cat'); // Cyrillic es, a
+  });
+
+  it('tells case-dependent homoglyphs apart', () => {
+    expect(normalizeTokenText('Ν')).toBe('n'); // Greek capital nu
+    expect(normalizeTokenText('ν')).toBe('v'); // Greek small nu
+  });
+
+  it('reads digits that pass for letters', () => {
+    expect(normalizeTokenText('D0GE')).toBe(normalizeTokenText('DOGE'));
+    expect(normalizeTokenText('1UNA')).toBe(normalizeTokenText('lUNA'));
+  });
+
+  it('maps every lookalike to a single ASCII letter', () => {
+    for (const [glyph, letter] of Object.entries(HOMOGLYPHS)) {
+      expect(letter).toMatch(/^[A-Za-z]$/);
+      expect(glyph).not.toBe(letter);
+    }
+  });
+});
+
+describe('editDistance', () => {
+  it('counts single-character edits', () => {
+    expect(editDistance('pepe', 'pepe', 1)).toBe(0);
+    expect(editDistance('pepe', 'pepee', 1)).toBe(1);
+    expect(editDistance('pepe', 'papa', 2)).toBe(2);
+  });
+
+  it('stops at the limit', () => {
+    expect(editDistance('pepe', 'doge', 1)).toBe(2);
+    expect(editDistance('a', 'abcdef', 1)).toBe(2);
+  });
+});
+
+describe('matchCollision', () => {
+  const original = { symbol: 'PEPE', name: 'Pepe The Frog' };
+
+  it('matches symbols equal after normalization', () => {
+    expect(matchCollision(original, { symbol: 'pepe', name: 'Other' })).toBe('symbol');
+    expect(matchCollision(original, { symbol: 'РЕPE', name: 'Other' })).toBe('symbol');
+    expect(matchCollision(original, { symbol: 'PEPE2', name: 'Other' })).toBeNull();
+  });
+
+  it('matches names within one edit only when a name is given', () => {
+    expect(matchCollision(original, { symbol: 'FROG', name: 'PepeTheFrogg' })).toBe('name');
+    expect(matchCollision(original, { symbol: 'FROG', name: 'Pepe Tha Frag' })).toBeNull();
+    expect(matchCollision({ symbol: 'PEPE' }, { symbol: 'FROG', name: 'Pepe The Frog' })).toBeNull();
+  });
+
+  it('needs exact names when they are short', () => {
+    expect(matchCollision({ symbol: 'A', name: 'Cat' }, { symbol: 'B', name: 'Cot' })).toBeNull();
+    expect(matchCollision({ symbol: 'A', name: 'Cat' }, { symbol: 'B', name: 'C A T' })).toBe('name');
+  });
+
+  it('never matches empty text', () => {
+    expect(matchCollision({ symbol: ' ', name: '' }, { symbol: '', name: '' })).toBeNull();
+  });
+});
+
+describe('findCollisions', () => {
+  it('tags matching candidates in input order', () => {
+    const candidates = [
+      { token: '0x1', symbol: 'DOGE', name: 'Doge' },
+      { token: '0x2', symbol: 'PEPE', name: 'Pepe' },
+      { token: '0x3', symbol: 'FROG', name: 'P e p e' },
+    ];
+    expect(findCollisions({ symbol: 'pepe', name: 'Pepe' }, candidates)).toEqual([
+      { token: '0x2', symbol: 'PEPE', name: 'Pepe', reason: 'symbol' },
+      { token: '0x3', symbol: 'FROG', name: 'P e p e', reason: 'name' },
+    ]);
+  });
+});
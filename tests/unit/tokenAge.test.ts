@@ -0,0 +1,68 @@
+import { assertTokenAge, findTokenCreationBlock } from '../../src/tokenAge';
+import { TokenTooOldError, TokenTooYoungError } from '../../src/errors';
+import { BackfillLog } from '../../src/backfill';
+import { getFourTradingInterface } from '../../src/abi';
+
+const TOKEN_A = '0x1111111111111111111111111111111111111111';
+const TOKEN_B = '0x2222222222222222222222222222222222222222';
+const CREATOR = '0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa';
+
+function createLog(token: string, blockNumber: number): BackfillLog {
+  const iface = getFourTradingInterface();
+  const encoded = iface.encodeEventLog('TokenCreate', [CREATOR, token, 1n, 'Name', 'SYM', 10n ** 27n, 1700000000n, 0n]);
+  return { topics: encoded.topics, data: encoded.data, blockNumber, transactionHash: `0x${blockNumber.toString(16)}` };
+}
+
+function source(logs: BackfillLog[]) {
+  const ranges: Array<[number, number]> = [];
+  const fetchLogs = async (from: number, to: number) => {
+    ranges.push([from, to]);
+    return logs.filter((log) => log.blockNumber >= from && log.blockNumber <= to);
+  };
+  return { fetchLogs, ranges };
+}
+
+describe('findTokenCreationBlock', () => {
+  const logs = [createLog(TOKEN_A, 120), createLog(TOKEN_B, 950)];
+
+  it('searches backward in windows and stops at the match', async () => {
+    const { fetchLogs, ranges } = source(logs);
+    const block = await findTokenCreationBlock(TOKEN_A, 1000, fetchLogs, 300, 10000);
+
+    expect(block).toBe(120);
+    expect(ranges).toEqual([[701, 1000], [401, 700], [101, 400]]);
+  });
+
+  it('ignores other tokens created in the same window', async () => {
+    const { fetchLogs } = source(logs);
+    expect(await findTokenCreationBlock(TOKEN_B, 1000, fetchLogs, 300, 10000)).toBe(950);
+  });
+
+  it('never looks further back than the lookback bound', async () => {
+    const { fetchLogs, ranges } = source(logs);
+    const block = await findTokenCreationBlock(TOKEN_A, 1000, fetchLogs, 300, 500);
+
+    expect(block).toBeNull();
+    expect(ranges).toEqual([[701, 1000], [500, 700]]);
+  });
+});
+
+describe('assertTokenAge', () => {
+  it('checks an exact age against both limits', () => {
+    expect(() => assertTokenAge(600, { minTokenAge: 60, maxTokenAge: 3600 })).not.toThrow();
+    expect(() => assertTokenAge(7200, { maxTokenAge: 3600 })).toThrow(TokenTooOldError);
+    expect(() => assertTokenAge(30, { minTokenAge: 60 })).toThrow(TokenTooYoungError);
+  });
+
+  it('treats a token created before the lookback as older than any maximum', () => {
+    let error: unknown;
+    try {
+      assertTokenAge(600, { maxTokenAge: 3600 }, true);
+    } catch (caught) {
+      error = caught;
+    }
+    expect(error).toBeInstanceOf(TokenTooOldError);
+    expect(error).toMatchObject({ age: 600, maxAge: 3600, beforeLookback: true });
+    expect(() => assertTokenAge(30, { minTokenAge: 60 }, true)).not.toThrow();
+  });
+});
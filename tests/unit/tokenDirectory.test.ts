@@ -0,0 +1,102 @@
+import { DirectoryLog, TokenDirectory, TokenDirectorySource } from '../../src/tokenDirectory';
+import { getFourTradingInterface } from '../../src/abi';
+import { TokenInfo } from '../../src/types';
+
+const CREATOR = '0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa';
+const WBNB = '0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c';
+const TOKENS = [1, 2, 3, 4, 5].map((n) => `0x${String(n).repeat(40)}`);
+const TRADING = 0n;
+
+function log(name: string, args: unknown[], blockNumber: number, index: number = 0): DirectoryLog {
+  const encoded = getFourTradingInterface().encodeEventLog(name, args);
+  return { topics: encoded.topics, data: encoded.data, blockNumber, index, transactionHash: `0x${blockNumber.toString(16)}` };
+}
+
+const created = (token: string, block: number) =>
+  log('TokenCreate', [CREATOR, token, 1n, 'Name', `T${block}`, 10n ** 27n, BigInt(block), 0n], block);
+const traded = (token: string, block: number) =>
+  log('TokenPurchase', [token, CREATOR, 1n, 1n, 1n, 0n, 1n, 1n], block, 1);
+const graduated = (token: string, block: number) => log('LiquidityAdded', [token, 1n, WBNB, 1n], block, 2);
+
+function fakeSource(logs: DirectoryLog[], head: number, statuses: Record<string, bigint> = {}) {
+  const ranges: Array<[number, number]> = [];
+  const state = { head };
+  const source: TokenDirectorySource = {
+    getBlockNumber: async () => state.head,
+    getLogs: async (from, to) => {
+      ranges.push([from, to]);
+      return logs.filter((entry) => entry.blockNumber >= from && entry.blockNumber <= to);
+    },
+    readTokenInfos: async (tokens) =>
+      tokens.map((token) => ({ status: statuses[token] ?? TRADING, funds: 25n, maxRaising: 100n } as TokenInfo)),
+    getBlockTimestamp: async (block) => block * 10,
+  };
+  return { source, ranges, state };
+}
+
+describe('TokenDirectory', () => {
+  const logs = [
+    created(TOKENS[0], 110),
+    created(TOKENS[1], 250),
+    traded(TOKENS[0], 260),
+    created(TOKENS[2], 420),
+    graduated(TOKENS[1], 430),
+    created(TOKENS[3], 480),
+    traded(TOKENS[3], 490),
+    traded(TOKENS[3], 495),
+  ];
+
+  it('pages newest-first through non-graduated tokens in backward windows', async () => {
+    const { source, ranges } = fakeSource(logs, 500);
+    const directory = new TokenDirectory(source, { tradingStatus: TRADING, windowSize: 100, maxLookback: 500, now: () => 1000 });
+
+    const first = await directory.list(2);
+    expect(first.tokens.map((t) => t.token)).toEqual([TOKENS[3], TOKENS[2]]);
+    expect(first.tokens[0]).toMatchObject({ symbol: 'T480', ageSeconds: 520, progressBps: 2500, lastTradeAt: 4950 });
+    expect(first.tokens[1].lastTradeAt).toBeNull();
+    expect(ranges).toEqual([[401, 500]]);
+
+    // TOKENS[1] graduated above its creation, TOKENS[0]'s trade was seen before its creation
+    const second = await directory.list(2, first.cursor!);
+    expect(second.tokens.map((t) => t.token)).toEqual([TOKENS[0]]);
+    expect(second.tokens[0].lastTradeAt).toBe(2600);
+    expect(second.cursor).toBeNull();
+    expect(ranges).toEqual([[401, 500], [301, 400], [201, 300], [101, 200], [0, 100]]);
+  });
+
+  it('drops tokens whose on-chain status left trading', async () => {
+    const { source } = fakeSource(logs, 500, { [TOKENS[3]]: 1n });
+    const directory = new TokenDirectory(source, { tradingStatus: TRADING, windowSize: 100, maxLookback: 500 });
+
+    const page = await directory.list(1);
+    expect(page.tokens.map((t) => t.token)).toEqual([TOKENS[2]]);
+    expect(page.cursor).toEqual({ block: 420, index: 0 });
+  });
+
+  it('refreshes only blocks mined since the last scan', async () => {
+    const source = fakeSource([...logs, created(TOKENS[4], 530), graduated(TOKENS[2], 540)], 500);
+    const directory = new TokenDirectory(source.source, { tradingStatus: TRADING, windowSize: 100, maxLookback: 500 });
+
+    await directory.list(2);
+    source.state.head = 550;
+    const page = await directory.list(2);
+
+    expect(page.tokens.map((t) => t.token)).toEqual([TOKENS[4], TOKENS[3]]);
+    expect(source.ranges).toEqual([[401, 500], [501, 550]]);
+  });
+
+  it('lists every creation since a block, graduated ones included, oldest first', async () => {
+    const source = fakeSource(logs, 500);
+    const directory = new TokenDirectory(source.source, { tradingStatus: TRADING, windowSize: 100, maxLookback: 500 });
+
+    const records = await directory.creationsSince(250);
+    expect(records.map((record) => record.token)).toEqual([TOKENS[1], TOKENS[2], TOKENS[3]]);
+    expect(records[0]).toMatchObject({ creator: CREATOR, symbol: 'T250', block: 250 });
+    expect(source.ranges).toEqual([[401, 500], [301, 400], [201, 300]]);
+  });
+
+  it('rejects a non-positive limit', async () => {
+    const directory = new TokenDirectory(fakeSource(logs, 500).source, { tradingStatus: TRADING });
+    await expect(directory.list(0)).rejects.toThrow('positive integer');
+  });
+});
@@ -0,0 +1,111 @@
+import * as fs from 'fs';
+import * as path from 'path';
+import { TOKEN_INFO_FIELDS, decodeTokenInfo } from '../../src/tokenInfo';
+import { TokenInfoLayoutError } from '../../src/errors';
+import { getFourTradingInterface } from '../../src/abi';
+import type { CapturedTokenInfo } from '../../scripts/captureFixtures';
+
+const ONE = 10n ** 18n;
+const word = (value: bigint) => value.toString(16).padStart(64, '0');
+
+// Mainnet `_tokenInfos` return data written by `npm run capture:fixtures`
+const CAPTURED_PATH = path.join(__dirname, '../fixtures/tokenInfos.json');
+const CAPTURED: CapturedTokenInfo[] = fs.existsSync(CAPTURED_PATH) ? JSON.parse(fs.readFileSync(CAPTURED_PATH, 'utf8')) : [];
+
+// Hand-built return data in the TokenManager's 13-word layout, for the layout checks: a token
+// mid-curve and a graduated one
+const TRADING =
+  '0x0000000000000000000000005a49a1ab0b3c4a2ee8d1c2a0e0b1f6b7f6ce4444' +
+  '0000000000000000000000000000000000000000000000000000000000000000' +
+  '0000000000000000000000000000000000000000000000000000000000000001' +
+  '0000000000000000000000000000000000000000033b2e3c9fd0803ce8000000' +
+  '00000000000000000000000000000000000000000295be96e640669720000000' +
+  '0000000000000000000000000000000000000000000000014d1120d7b1600000' +
+  '0000000000000000000000000000000000000000000000000000000067748580' +
+  '0000000000000000000000000000000000000000001fa853db850f78b3c78000' +
+  '00000000000000000000000000000000000000000000000042c7a881432e0000' +
+  '000000000000000000000000000000000000000000000000000000065e2fe180' +
+  '0000000000000000000000000000000000000000000000000000000000000000' +
+  '0000000000000000000000000000000000000000000000000000000000000000' +
+  '0000000000000000000000000000000000000000000000000000000000000000';
+
+const GRADUATED =
+  '0x0000000000000000000000008b3f7d0e9c3a1e6f5b2d4c8a7e6f1d2c3b4a4444' +
+  '0000000000000000000000000000000000000000000000000000000000000000' +
+  '0000000000000000000000000000000000000000000000000000000000000001' +
+  '0000000000000000000000000000000000000000033b2e3c9fd0803ce8000000' +
+  '00000000000000000000000000000000000000000295be96e640669720000000' +
+  '0000000000000000000000000000000000000000000000014d1120d7b1600000' +
+  '0000000000000000000000000000000000000000000000000000000067733400' +
+  '0000000000000000000000000000000000000000000000000000000000000000' +
+  '0000000000000000000000000000000000000000000000014d1120d7b1600000' +
+  '000000000000000000000000000000000000000000000000000000174876e800' +
+  '0000000000000000000000000000000000000000000000000000000000000000' +
+  '0000000000000000000000000000000000000000000000000000000000000000' +
+  '0000000000000000000000000000000000000000000000000000000000000002';
+
+describe('decodeTokenInfo', () => {
+  it('lists the fields of the ABI struct in order', () => {
+    const outputs = getFourTradingInterface().getFunction('_tokenInfos')!.outputs;
+    expect(outputs.map((output) => output.name)).toEqual([...TOKEN_INFO_FIELDS]);
+  });
+
+  it('decodes every field of a token on the curve', () => {
+    const info = decodeTokenInfo(TRADING);
+
+    expect(Object.keys(info)).toEqual([...TOKEN_INFO_FIELDS]);
+    expect(info).toMatchObject({
+      quote: '0x0000000000000000000000000000000000000000',
+      template: 1n,
+      totalSupply: 1_000_000_000n * ONE,
+      maxOffers: 800_000_000n * ONE,
+      maxRaising: 24n * ONE,
+      launchTime: 1735689600n,
+      offers: 612_345_678n * ONE,
+      funds: 4_812_000_000_000_000_000n,
+      lastPrice: 27_350_000_000n,
+      K: 0n,
+      T: 0n,
+      status: 0n,
+    });
+    expect(info.base.toLowerCase()).toBe('0x5a49a1ab0b3c4a2ee8d1c2a0e0b1f6b7f6ce4444');
+  });
+
+  it('decodes a graduated token', () => {
+    const info = decodeTokenInfo(GRADUATED);
+
+    expect(info).toMatchObject({ offers: 0n, funds: 24n * ONE, launchTime: 1735603200n, lastPrice: 100_000_000_000n, status: 2n });
+  });
+
+  // Field values only make sense in this order: a reorder of the struct would break these
+  CAPTURED.forEach(({ token, block, returnData }) => {
+    it(`decodes ${token} as captured on mainnet at block ${block}`, () => {
+      const info = decodeTokenInfo(returnData);
+
+      expect(info.base.toLowerCase()).toBe(token.toLowerCase());
+      expect(info.maxOffers).toBeGreaterThan(0n);
+      expect(info.maxOffers).toBeLessThanOrEqual(info.totalSupply);
+      expect(info.offers).toBeLessThanOrEqual(info.maxOffers);
+      expect(Number(info.launchTime)).toBeGreaterThan(1_690_000_000); // After four.meme launched
+      expect(info.status).toBeLessThanOrEqual(3n);
+    });
+  });
+
+  it('rejects return data with a field appended', () => {
+    const error = (() => {
+      try {
+        decodeTokenInfo(TRADING + word(1n));
+      } catch (e) {
+        return e;
+      }
+    })();
+
+    expect(error).toBeInstanceOf(TokenInfoLayoutError);
+    expect(error).toMatchObject({ code: 'TOKEN_INFO_LAYOUT', expectedBytes: 416, actualBytes: 448 });
+  });
+
+  it('rejects return data with a field missing', () => {
+    expect(() => decodeTokenInfo(TRADING.slice(0, -64))).toThrow(TokenInfoLayoutError);
+    expect(() => decodeTokenInfo('0x')).toThrow(TokenInfoLayoutError);
+  });
+});
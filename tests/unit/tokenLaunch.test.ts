@@ -0,0 +1,35 @@
+import { memeTokenTemplate, validateTokenLaunch } from '../../src/tokenLaunch';
+import { TOKEN_LAUNCH_LIMITS } from '../../src/constants';
+
+const NOW = 1700000000;
+
+describe('validateTokenLaunch', () => {
+  it('should accept a completed meme template', () => {
+    const params = { ...memeTokenTemplate('Moon Dog', 'MDOG'), imageUrl: 'https://cdn.example.com/dog.png' };
+
+    expect(validateTokenLaunch(params, NOW)).toEqual([]);
+  });
+
+  it('should report every violation at once', () => {
+    const violations = validateTokenLaunch({
+      name: 'A name that is far too long for the platform',
+      symbol: 'MOON-DOG!',
+      description: 'x'.repeat(TOKEN_LAUNCH_LIMITS.descriptionLength.max + 1),
+      imageUrl: 'http://cdn.example.com/dog.bmp',
+      label: 'Memes',
+      website: 'not a url',
+      preSale: TOKEN_LAUNCH_LIMITS.preSaleBnb.max + 1n,
+      launchTime: NOW + TOKEN_LAUNCH_LIMITS.launchDelaySeconds.max + 1,
+    }, NOW);
+
+    expect(violations.map((violation) => violation.field)).toEqual([
+      'name', 'symbol', 'description', 'imageUrl', 'website', 'label', 'preSale', 'launchTime',
+    ]);
+  });
+
+  it('should require an image for templates', () => {
+    expect(validateTokenLaunch(memeTokenTemplate('Dog', 'DOG'), NOW)).toEqual([
+      { field: 'imageUrl', message: 'imageUrl is required' },
+    ]);
+  });
+});
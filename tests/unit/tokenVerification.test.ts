@@ -0,0 +1,40 @@
+import { classifyToken, isRegisteredTokenInfo } from '../../src/tokenVerification';
+import { TokenInfo } from '../../src/types';
+
+const OFFICIAL = '0x' + 'ab'.repeat(32);
+const OTHER = '0x' + 'cd'.repeat(32);
+
+function tokenInfo(overrides: Partial<TokenInfo> = {}): TokenInfo {
+  return {
+    base: '0x1111111111111111111111111111111111111111',
+    quote: '0x0000000000000000000000000000000000000000',
+    template: 1n,
+    totalSupply: 1000000000n * 10n ** 18n,
+    maxOffers: 800000000n * 10n ** 18n,
+    maxRaising: 24n * 10n ** 18n,
+    launchTime: 1700000000n,
+    offers: 0n,
+    funds: 0n,
+    lastPrice: 0n,
+    K: 1n,
+    T: 1n,
+    status: 0n,
+    ...overrides,
+  };
+}
+
+describe('token verification', () => {
+  it('should treat an all-zero TokenInfo as unregistered', () => {
+    expect(isRegisteredTokenInfo(tokenInfo())).toBe(true);
+    expect(isRegisteredTokenInfo(tokenInfo({ totalSupply: 0n, launchTime: 0n, K: 0n, T: 0n }))).toBe(false);
+    expect(isRegisteredTokenInfo(tokenInfo({ launchTime: 0n }))).toBe(false);
+  });
+
+  it('should classify tokens by registration and code hash', () => {
+    expect(classifyToken(OFFICIAL, true, [OFFICIAL])).toBe('Official');
+    expect(classifyToken(OFFICIAL.toUpperCase().replace('0X', '0x'), true, [OFFICIAL])).toBe('Official');
+    expect(classifyToken(OTHER, true, [OFFICIAL])).toBe('RegisteredButUnknownBytecode');
+    expect(classifyToken(OFFICIAL, false, [OFFICIAL])).toBe('NotAFourmemeToken');
+    expect(classifyToken(OTHER, false, [])).toBe('NotAFourmemeToken');
+  });
+});
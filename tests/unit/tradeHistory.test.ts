@@ -0,0 +1,111 @@
+import { TradeHistory, TradeRecord } from '../../src/tradeHistory';
+import { Price } from '../../src/price';
+
+const ONE = 10n ** 18n;
+const TOKEN_A = '0x1111111111111111111111111111111111111111';
+const TOKEN_B = '0x2222222222222222222222222222222222222222';
+
+function record(overrides: Partial<TradeRecord> = {}): TradeRecord {
+  return {
+    timestamp: 1700000000,
+    token: TOKEN_A,
+    direction: 'buy',
+    tokenAmount: 1000n * ONE,
+    bnbAmount: ONE / 2n,
+    fee: ONE / 200n,
+    price: Price.fromAmounts(ONE / 2n, 1000n * ONE),
+    gasCost: 150000n * 1000000000n,
+    txHash: '0xabc',
+    blockNumber: 1,
+    tags: [],
+    ...overrides,
+  };
+}
+
+describe('TradeHistory', () => {
+  it('should record and filter trades by token', () => {
+    const history = new TradeHistory();
+    history.record(record());
+    history.record(record({ token: TOKEN_B }));
+
+    expect(history.getTrades()).toHaveLength(2);
+    expect(history.getTrades(TOKEN_B.toUpperCase().replace('0X', '0x'))).toHaveLength(1);
+  });
+
+  it('should cap the number of stored records', () => {
+    const history = new TradeHistory(2);
+    history.record(record({ txHash: '0x1' }));
+    history.record(record({ txHash: '0x2' }));
+    history.record(record({ txHash: '0x3' }));
+
+    expect(history.getTrades().map((trade) => trade.txHash)).toEqual(['0x2', '0x3']);
+  });
+
+  it('should export CSV in human-readable units', () => {
+    const history = new TradeHistory();
+    history.record(record());
+    history.record(record({ direction: 'sell', bnbAmount: ONE, txHash: '0xdef', timestamp: 1700000060 }));
+
+    const lines = history.toCsv().trim().split('\n');
+    expect(lines[0]).toBe('timestamp,token,direction,amount,cost_bnb,price_bnb,fee_bnb,gas_cost_bnb,extra_cost_bnb,tx_hash,tags,correlation_id');
+    expect(lines[1]).toBe(
+      `2023-11-14T22:13:20.000Z,${TOKEN_A},buy,1000.0,0.5,0.000500000000000000,0.005,0.00015,0.0,0xabc,,`
+    );
+    expect(lines[2].split(',')[2]).toBe('sell');
+    expect(lines[2].split(',')[4]).toBe('1.0');
+  });
+
+  it('should export each token with its own decimals', () => {
+    const history = new TradeHistory();
+    history.record(record());
+    history.record(record({ token: TOKEN_B, tokenAmount: 1000n * 10n ** 9n, price: Price.fromAmounts(ONE / 2n, 1000n * 10n ** 9n) }));
+
+    const rows = history.toCsv((token) => (token === TOKEN_B ? 9 : 18)).trim().split('\n').slice(1).map((line) => line.split(','));
+    expect(rows.map((fields) => [fields[3], fields[5]])).toEqual([
+      ['1000.0', '0.000500000000000000'],
+      ['1000.0', '0.000500000000000000'],
+    ]);
+  });
+
+  it('should filter trades and CSV export by sub-account', () => {
+    const history = new TradeHistory();
+    history.record(record({ subAccount: 'sniper', txHash: '0x1' }));
+    history.record(record({ subAccount: 'dca', txHash: '0x2' }));
+    history.record(record({ txHash: '0x3' }));
+
+    expect(history.getTradesBySubAccount('sniper').map((trade) => trade.txHash)).toEqual(['0x1']);
+    expect(history.toCsv(undefined, 'dca').trim().split('\n')).toHaveLength(2);
+    expect(history.toCsv().trim().split('\n')).toHaveLength(4);
+  });
+
+  it('should filter trades by tag and correlation id', () => {
+    const history = new TradeHistory();
+    history.record(record({ tags: ['momentum'], correlationId: 'req-1' }));
+    history.record(record({ tags: ['momentum', 'sniper'] }));
+    history.record(record({ tags: ['sniper'], correlationId: 'req-2' }));
+
+    expect(history.getTradesByTag('momentum')).toHaveLength(2);
+    expect(history.getTradesByTag('sniper')).toHaveLength(2);
+    expect(history.getTradesByTag('unknown')).toHaveLength(0);
+    expect(history.getTradesByCorrelationId('req-2')[0].tags).toEqual(['sniper']);
+  });
+
+  it('should preserve tags in the CSV export', () => {
+    const history = new TradeHistory();
+    history.record(record({ tags: ['momentum', 'source:trade'], correlationId: 'req-1' }));
+
+    const fields = history.toCsv().trim().split('\n')[1].split(',');
+    expect(fields.slice(-2)).toEqual(['momentum;source:trade', 'req-1']);
+  });
+
+  it('should quote CSV fields holding commas, quotes or line breaks', () => {
+    const history = new TradeHistory();
+    history.record(record({ tags: ['a,b', 'say "hi"'], correlationId: 'line\nbreak' }));
+
+    expect(history.toCsv().endsWith(',"a,b;say ""hi""","line\nbreak"\n')).toBe(true);
+  });
+
+  it('should export only the header when empty', () => {
+    expect(new TradeHistory().toCsv()).toBe('timestamp,token,direction,amount,cost_bnb,price_bnb,fee_bnb,gas_cost_bnb,extra_cost_bnb,tx_hash,tags,correlation_id\n');
+  });
+});
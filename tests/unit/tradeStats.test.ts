@@ -0,0 +1,72 @@
+import { performanceByHour, performanceByWeekday, performanceToJson, realizedSlippageBps } from '../../src/tradeStats';
+import { TradeRecord } from '../../src/tradeHistory';
+import { Price } from '../../src/price';
+
+const ONE = 10n ** 18n;
+const TOKEN = '0x1111111111111111111111111111111111111111';
+
+// 2024-01-06 is a Saturday
+const SAT_2330_UTC = Date.UTC(2024, 0, 6, 23, 30) / 1000;
+const SUN_0010_UTC = Date.UTC(2024, 0, 7, 0, 10) / 1000;
+
+function record(overrides: Partial<TradeRecord> = {}): TradeRecord {
+  return {
+    timestamp: SAT_2330_UTC,
+    token: TOKEN,
+    direction: 'buy',
+    tokenAmount: 1000n * ONE,
+    bnbAmount: ONE,
+    fee: 0n,
+    price: Price.fromAmounts(ONE, 1000n * ONE),
+    gasCost: 0n,
+    txHash: '0x1',
+    blockNumber: 1,
+    tags: [],
+    ...overrides,
+  };
+}
+
+describe('trade performance stats', () => {
+  const journal = [
+    record({ timestamp: SAT_2330_UTC, expectedAmount: 1010n * ONE }),
+    record({ timestamp: SAT_2330_UTC + 600, direction: 'sell', bnbAmount: 2n * ONE, realizedPnl: ONE }),
+    record({ timestamp: SUN_0010_UTC, direction: 'sell', bnbAmount: ONE / 2n, realizedPnl: -ONE / 2n, expectedAmount: ONE }),
+  ];
+
+  it('should bucket by UTC hour across a day boundary', () => {
+    const byHour = performanceByHour(journal);
+
+    expect(byHour).toHaveLength(24);
+    expect(byHour[23]).toMatchObject({ trades: 2, closedTrades: 1, wins: 1, winRate: 1, realizedPnl: ONE });
+    expect(byHour[0]).toMatchObject({ trades: 1, closedTrades: 1, wins: 0, winRate: 0, realizedPnl: -ONE / 2n });
+    expect(byHour[12]).toMatchObject({ trades: 0, winRate: null, averageSlippageBps: null, realizedPnl: 0n });
+  });
+
+  it('should apply the UTC offset before bucketing', () => {
+    const byHour = performanceByHour(journal, 120); // UTC+2
+
+    expect(byHour[1].trades).toBe(2);
+    expect(byHour[2].trades).toBe(1);
+  });
+
+  it('should bucket by weekday and move trades across the day boundary with the offset', () => {
+    expect(performanceByWeekday(journal).map((bucket) => bucket.trades)).toEqual([1, 0, 0, 0, 0, 0, 2]);
+    expect(performanceByWeekday(journal, 60).map((bucket) => bucket.trades)).toEqual([3, 0, 0, 0, 0, 0, 0]);
+    expect(performanceByWeekday(journal, -60).map((bucket) => bucket.trades)).toEqual([0, 0, 0, 0, 0, 0, 3]);
+  });
+
+  it('should measure realized slippage against the quoted amount', () => {
+    expect(realizedSlippageBps(journal[0])).toBe(99); // 10 of 1010 tokens short
+    expect(realizedSlippageBps(journal[2])).toBe(5000);
+    expect(realizedSlippageBps(journal[1])).toBeNull();
+
+    const byHour = performanceByHour(journal);
+    expect(byHour[23].averageSlippageBps).toBe(99);
+  });
+
+  it('should serialize buckets with bigint PnL as strings', () => {
+    const parsed = JSON.parse(performanceToJson(performanceByHour(journal)));
+    expect(parsed[23].realizedPnl).toBe(ONE.toString());
+    expect(parsed[0].realizedPnl).toBe((-ONE / 2n).toString());
+  });
+});
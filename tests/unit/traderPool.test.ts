@@ -0,0 +1,87 @@
+import { TraderPool } from '../../src/traderPool';
+import { FourTrading } from '../../src/fourTrading';
+import { ValidationError } from '../../src/errors';
+
+const TOKEN = '0x1111111111111111111111111111111111111111';
+
+// Trader stand-in whose buys resolve when released
+function fakeTrader(address: string) {
+  const pending: Array<() => void> = [];
+  const buys: any[] = [];
+  const trader = {
+    getWalletAddress: () => address,
+    quoteBuy: async () => ({ tokenAmount: 1000n }),
+    buyToken: (params: any) => {
+      buys.push(params);
+      return new Promise((resolve) => pending.push(() => resolve({ success: true, txHash: `${address}-${buys.length}` })));
+    },
+  };
+  return { trader: trader as unknown as FourTrading, buys, release: () => pending.shift()?.() };
+}
+
+const flush = () => new Promise((resolve) => setImmediate(resolve));
+
+describe('TraderPool', () => {
+  it('should rotate buys across wallets and apply slippage to the quote', async () => {
+    const wallets = [fakeTrader('a'), fakeTrader('b')];
+    const pool = new TraderPool(wallets.map((wallet) => wallet.trader));
+
+    const first = pool.buyRoundRobin(TOKEN, 10n, 10);
+    const second = pool.buyRoundRobin(TOKEN, 10n, 10);
+    await flush();
+
+    expect(wallets[0].buys).toHaveLength(1);
+    expect(wallets[1].buys).toHaveLength(1);
+    expect(wallets[0].buys[0].minAmount).toBe(900n);
+
+    wallets[0].release();
+    wallets[1].release();
+    expect((await first).walletIndex).toBe(0);
+    expect((await second).walletIndex).toBe(1);
+  });
+
+  it('should skip busy wallets', async () => {
+    const wallets = [fakeTrader('a'), fakeTrader('b'), fakeTrader('c')];
+    const pool = new TraderPool(wallets.map((wallet) => wallet.trader));
+
+    const pinned = pool.buyFrom(1, { tokenAddress: TOKEN, fundsInBNB: 1n });
+    await flush();
+    expect(pool.isBusy(1)).toBe(true);
+
+    const a = pool.buyRoundRobin(TOKEN, 1n);
+    await flush();
+    const c = pool.buyRoundRobin(TOKEN, 1n);
+    await flush();
+
+    wallets.forEach((wallet) => wallet.release());
+    expect((await a).walletIndex).toBe(0);
+    expect((await c).walletIndex).toBe(2);
+    await pinned;
+    expect(pool.isBusy(1)).toBe(false);
+  });
+
+  it('should send one transaction at a time per wallet', async () => {
+    const wallet = fakeTrader('a');
+    const pool = new TraderPool([wallet.trader]);
+
+    const first = pool.buyFrom(0, { tokenAddress: TOKEN, fundsInBNB: 1n });
+    const second = pool.buyFrom(0, { tokenAddress: TOKEN, fundsInBNB: 2n });
+    await flush();
+    expect(wallet.buys).toHaveLength(1);
+
+    wallet.release();
+    await first;
+    await flush();
+    expect(wallet.buys).toHaveLength(2);
+
+    wallet.release();
+    await second;
+    expect(pool.isBusy(0)).toBe(false);
+  });
+
+  it('should validate wallet indices', () => {
+    const pool = new TraderPool([fakeTrader('a').trader]);
+    expect(() => pool.getTrader(1)).toThrow(ValidationError);
+    expect(() => new TraderPool([])).toThrow(ValidationError);
+  });
+});
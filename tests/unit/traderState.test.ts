@@ -0,0 +1,74 @@
+import { StateSnapshotError } from '../../src/errors';
+import { TraderStateSnapshot, migrateTraderState, traderStateFromJson, traderStateToJson } from '../../src/traderState';
+
+const ONE = 10n ** 18n;
+const TOKEN = '0x1111111111111111111111111111111111111111';
+
+const snapshot: TraderStateSnapshot = {
+  version: 1,
+  takenAt: 1_700_000_000_000,
+  wallet: '0x70997970C51812dc3A010C7d01b50e0d17dc79C8',
+  chainId: 56n,
+  nextNonce: 7,
+  pending: [{ txHash: `0x${'ab'.repeat(32)}`, nonce: 6, token: TOKEN, side: 'buy', gasPrice: 10n ** 9n }],
+  positions: [
+    { token: TOKEN, amount: 5n * ONE, knownAmount: 5n * ONE, knownCost: ONE / 10n, unknownAmount: 0n, realizedPnl: -1n, basisUnknown: false },
+  ],
+  gasProfile: [{ method: 'buyTokenAMAP', limit: 260000n, observed: 200000n, source: 'receipt', updatedAt: 1_700_000_000_000 }],
+  watchlist: [{ token: TOKEN, addedAt: 1, lastTradeAt: 2, lastPrice: 123n, tradeCount: 1, graduated: false }],
+};
+
+describe('trader state snapshots', () => {
+  it('round-trips through JSON with bigints intact', () => {
+    expect(traderStateFromJson(traderStateToJson(snapshot))).toEqual(snapshot);
+  });
+
+  it('keeps a never-synced nonce as null', () => {
+    expect(traderStateFromJson(traderStateToJson({ ...snapshot, nextNonce: null })).nextNonce).toBeNull();
+  });
+
+  it('rejects text that is not JSON', () => {
+    expect(() => traderStateFromJson('{')).toThrow(StateSnapshotError);
+  });
+
+  describe('migrateTraderState', () => {
+    it('leaves a current snapshot as is', () => {
+      expect(migrateTraderState(snapshot)).toBe(snapshot);
+    });
+
+    it('runs each migration in order up to the target', () => {
+      const calls: number[] = [];
+      const migrations = {
+        1: (old: any) => {
+          calls.push(old.version);
+          return { ...old, renamed: old.positions };
+        },
+        2: (old: any) => {
+          calls.push(old.version);
+          const { positions, ...rest } = old;
+          return { ...rest, extra: positions === undefined };
+        },
+      };
+
+      const migrated: any = migrateTraderState(snapshot, migrations, 3);
+
+      expect(calls).toEqual([1, 2]);
+      expect(migrated.version).toBe(3);
+      expect(migrated.renamed).toEqual(snapshot.positions);
+      expect(migrated.extra).toBe(false);
+    });
+
+    it('rejects a snapshot with no migration path', () => {
+      expect(() => migrateTraderState(snapshot, {}, 2)).toThrow(/no migration from version 1/);
+    });
+
+    it('rejects a snapshot newer than the SDK', () => {
+      expect(() => migrateTraderState({ ...snapshot, version: 2 })).toThrow(/newer/);
+    });
+
+    it('rejects anything without a version', () => {
+      expect(() => migrateTraderState({ positions: [] })).toThrow(StateSnapshotError);
+      expect(() => migrateTraderState(null)).toThrow(StateSnapshotError);
+    });
+  });
+});
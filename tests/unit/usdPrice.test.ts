@@ -0,0 +1,52 @@
+import { bnbToUsd, bnbUsdPriceFromRound } from '../../src/usdPrice';
+import { MathTruncationError, PriceFeedError } from '../../src/errors';
+import { setStrictMath } from '../../src/math';
+import { CHAINLINK_BNB_USD_FEED } from '../../src/constants';
+
+const ONE = 10n ** 18n;
+const NOW = 1_750_000_000;
+
+describe('bnbUsdPriceFromRound', () => {
+  it('scales the answer by the feed decimals', () => {
+    const price = bnbUsdPriceFromRound(CHAINLINK_BNB_USD_FEED, 61234567890n, 8, NOW - 30, NOW, 3600);
+
+    expect(price).toEqual({ usdPerBnb: 612.3456789, answer: 61234567890n, decimals: 8, updatedAt: NOW - 30, feed: CHAINLINK_BNB_USD_FEED });
+  });
+
+  it('refuses a stale round', () => {
+    expect(() => bnbUsdPriceFromRound(CHAINLINK_BNB_USD_FEED, 61234567890n, 8, NOW - 3601, NOW, 3600)).toThrow(PriceFeedError);
+    expect(() => bnbUsdPriceFromRound(CHAINLINK_BNB_USD_FEED, 61234567890n, 8, NOW - 3600, NOW, 3600)).not.toThrow();
+  });
+
+  it('refuses a non-positive answer', () => {
+    expect(() => bnbUsdPriceFromRound(CHAINLINK_BNB_USD_FEED, 0n, 8, NOW, NOW, 3600)).toThrow(/not positive/);
+    expect(() => bnbUsdPriceFromRound(CHAINLINK_BNB_USD_FEED, -1n, 8, NOW, NOW, 3600)).toThrow(PriceFeedError);
+  });
+});
+
+describe('bnbToUsd', () => {
+  const price = bnbUsdPriceFromRound(CHAINLINK_BNB_USD_FEED, 60000000000n, 8, NOW, NOW, 3600); // $600
+
+  it('converts wei to dollars', () => {
+    expect(bnbToUsd(ONE, price)).toBe(600);
+    expect(bnbToUsd(ONE / 4n, price)).toBe(150);
+    expect(bnbToUsd(0n, price)).toBe(0);
+  });
+
+  it('keeps millionths of a dollar for dust amounts', () => {
+    expect(bnbToUsd(10n ** 12n, price)).toBe(0.0006);
+  });
+
+  it('refuses values a JS number cannot hold exactly in strict math mode', () => {
+    const whale = 10n ** 13n * ONE; // $6e15, past Number.MAX_SAFE_INTEGER in millionths of a dollar
+    expect(bnbToUsd(whale, price)).toBe(6e15);
+
+    setStrictMath(true);
+    try {
+      expect(() => bnbToUsd(whale, price)).toThrow(MathTruncationError);
+      expect(bnbToUsd(ONE, price)).toBe(600);
+    } finally {
+      setStrictMath(false);
+    }
+  });
+});
@@ -169,6 +169,12 @@ describe('Validator', () => {
       }).not.toThrow();
     });
 
+    it('should reject price fields that contradict the transaction type', () => {
+      expect(() => Validator.validateGasOptions({ type: 'legacy', maxFeePerGas: 6000000000n })).toThrow(GasConfigurationError);
+      expect(() => Validator.validateGasOptions({ type: 'eip1559', gasPrice: 5000000000n })).toThrow(GasConfigurationError);
+      expect(() => Validator.validateGasOptions({ type: 'legacy', gasPrice: 5000000000n })).not.toThrow();
+    });
+
     it('should reject priority fee exceeding max fee', () => {
       expect(() => {
         Validator.validateGasOptions({
@@ -202,6 +208,13 @@ describe('Validator', () => {
         });
       }).not.toThrow();
     });
+
+    it('should reject gas multipliers below 1.0', () => {
+      expect(() => Validator.validateGasOptions({ gasMultiplier: 0.9 })).toThrow(GasConfigurationError);
+      expect(() => Validator.validateGasOptions({ gasMultiplier: NaN })).toThrow(GasConfigurationError);
+      expect(() => Validator.validateGasOptions({ gasMultiplier: 1 })).not.toThrow();
+      expect(() => Validator.validateGasOptions({ gasMultiplier: 1.15 })).not.toThrow();
+    });
   });
 
   describe('validateRpcUrl', () => {
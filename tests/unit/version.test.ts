@@ -0,0 +1,54 @@
+import { ethers } from 'ethers';
+import {
+  encodeVersionedBuy,
+  encodeVersionedBuyExact,
+  encodeVersionedSell,
+  isDefinitiveHelperFailure,
+  routeFromHelperInfo,
+} from '../../src/version';
+import { encodeBuyCalldata, encodeSellCalldata } from '../../src/calldata';
+import { getTokenManagerV1Interface } from '../../src/abi';
+import { UnsupportedVersionError, ValidationError } from '../../src/errors';
+import { TOKEN_MANAGER_V1_ADDRESS } from '../../src/constants';
+
+const TOKEN = '0x1111111111111111111111111111111111111111';
+const RECIPIENT = '0x2222222222222222222222222222222222222222';
+
+describe('TokenManager versions', () => {
+  const v1 = getTokenManagerV1Interface();
+
+  it('routes helper-reported versions and rejects the rest', () => {
+    expect(routeFromHelperInfo(TOKEN, 1n, TOKEN_MANAGER_V1_ADDRESS)).toEqual({ version: 1, manager: TOKEN_MANAGER_V1_ADDRESS });
+    expect(() => routeFromHelperInfo(TOKEN, 0n, ethers.ZeroAddress)).toThrow(UnsupportedVersionError);
+    expect(() => routeFromHelperInfo(TOKEN, 3n, TOKEN_MANAGER_V1_ADDRESS)).toThrow(UnsupportedVersionError);
+  });
+
+  it('encodes V2 trades exactly as before', () => {
+    expect(encodeVersionedBuy(2, TOKEN, RECIPIENT, 10n, 5n)).toBe(encodeBuyCalldata(TOKEN, RECIPIENT, 10n, 5n));
+    expect(encodeVersionedSell(2, TOKEN, 1000n, 3n)).toBe(encodeSellCalldata(TOKEN, 1000n, 3n));
+  });
+
+  it('encodes V1 trades with the V1 method names', () => {
+    const buy = v1.decodeFunctionData('purchaseTokenAMAP', encodeVersionedBuy(1, TOKEN, RECIPIENT, 10n, 5n));
+    expect([...buy]).toEqual([0n, TOKEN, RECIPIENT, 10n, 5n]);
+
+    const exact = v1.decodeFunctionData('purchaseToken', encodeVersionedBuyExact(1, TOKEN, RECIPIENT, 1000n, 2n));
+    expect([...exact]).toEqual([0n, TOKEN, RECIPIENT, 1000n, 2n]);
+
+    const sell = v1.decodeFunctionData('saleToken', encodeVersionedSell(1, TOKEN, 1000n, 0n));
+    expect([...sell]).toEqual([TOKEN, 1000n]);
+  });
+
+  it('refuses a V1 sell minimum it cannot enforce', () => {
+    expect(() => encodeVersionedSell(1, TOKEN, 1000n, 1n)).toThrow(ValidationError);
+  });
+
+  it('falls back only on answers from the chain, not transport failures', () => {
+    expect(isDefinitiveHelperFailure({ code: 'CALL_EXCEPTION' })).toBe(true);
+    expect(isDefinitiveHelperFailure({ code: 'BAD_DATA', value: '0x' })).toBe(true);
+    expect(isDefinitiveHelperFailure({ code: 'BAD_DATA', value: '0x1234' })).toBe(false);
+    expect(isDefinitiveHelperFailure({ code: 'TIMEOUT' })).toBe(false);
+    expect(isDefinitiveHelperFailure({ code: 'SERVER_ERROR' })).toBe(false);
+    expect(isDefinitiveHelperFailure(new Error('socket hang up'))).toBe(false);
+  });
+});
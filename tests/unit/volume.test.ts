@@ -0,0 +1,40 @@
+import { summarizeVolume } from '../../src/volume';
+import { BackfillEvent } from '../../src/backfill';
+
+const TOKEN = '0x1234567890123456789012345678901234567890';
+
+function trade(side: 'buy' | 'sell', cost: bigint, blockNumber: number): BackfillEvent {
+  return {
+    side,
+    event: { token: TOKEN, account: TOKEN, price: 1n, amount: 10n, cost, fee: 0n, offers: 0n, funds: 0n },
+    blockNumber,
+    txHash: `0x${blockNumber.toString(16)}`,
+  };
+}
+
+describe('summarizeVolume', () => {
+  it('sums buy and sell BNB volume separately', () => {
+    const stats = summarizeVolume(TOKEN, 100, 199, [
+      trade('buy', 300n, 101),
+      trade('sell', 50n, 120),
+      trade('buy', 700n, 150),
+    ]);
+
+    expect(stats).toEqual({
+      token: TOKEN,
+      fromBlock: 100,
+      toBlock: 199,
+      buyVolume: 1000n,
+      sellVolume: 50n,
+      buyCount: 2,
+      sellCount: 1,
+      tradeCount: 3,
+    });
+  });
+
+  it('reports zero volume for a quiet window', () => {
+    const stats = summarizeVolume(TOKEN, 1, 10, []);
+    expect(stats.buyVolume + stats.sellVolume).toBe(0n);
+    expect(stats.tradeCount).toBe(0);
+  });
+});
@@ -0,0 +1,98 @@
+import { BackfillEvent, BackfillLog } from '../../src/backfill';
+import { getFourTradingInterface } from '../../src/abi';
+import { isLogRangeError } from '../../src/utils';
+import { walletTradeHistory } from '../../src/walletHistory';
+
+const TOKEN = '0x1111111111111111111111111111111111111111';
+const WALLET = '0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa';
+const OTHER = '0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb';
+
+function tradeLog(name: 'TokenPurchase' | 'TokenSale', account: string, blockNumber: number): BackfillLog {
+  const encoded = getFourTradingInterface().encodeEventLog(name, [TOKEN, account, 1n, BigInt(blockNumber), 3n, 4n, 5n, 6n]);
+  return { topics: encoded.topics, data: encoded.data, blockNumber, transactionHash: `0x${blockNumber.toString(16)}` };
+}
+
+// Log source that rejects ranges wider than `maxRange` blocks, recording each queried range
+function source(logs: BackfillLog[], maxRange = Infinity) {
+  const ranges: Array<[number, number]> = [];
+  const fetchLogs = async (from: number, to: number) => {
+    ranges.push([from, to]);
+    if (to - from + 1 > maxRange) {
+      throw Object.assign(new Error('could not coalesce error'), { error: { code: -32005, message: `block range too large, max ${maxRange}` } });
+    }
+    return logs.filter((log) => log.blockNumber >= from && log.blockNumber <= to);
+  };
+  return { fetchLogs, ranges };
+}
+
+async function collect(trades: AsyncIterable<BackfillEvent>): Promise<BackfillEvent[]> {
+  const collected: BackfillEvent[] = [];
+  for await (const trade of trades) {
+    collected.push(trade);
+  }
+  return collected;
+}
+
+describe('walletTradeHistory', () => {
+  const logs = [
+    tradeLog('TokenPurchase', WALLET.toUpperCase().replace('0X', '0x'), 10),
+    tradeLog('TokenPurchase', OTHER, 12),
+    tradeLog('TokenSale', WALLET, 35),
+    tradeLog('TokenSale', OTHER, 36),
+    tradeLog('TokenPurchase', WALLET, 79),
+  ];
+
+  it('yields only the wallet trades, oldest first', async () => {
+    const { fetchLogs, ranges } = source(logs);
+
+    const trades = await collect(walletTradeHistory(WALLET, 0, 79, fetchLogs, { pageSize: 40 }));
+
+    expect(ranges).toEqual([[0, 39], [40, 79]]);
+    expect(trades.map((trade) => [trade.side, trade.blockNumber, trade.event.amount])).toEqual([
+      ['buy', 10, 10n],
+      ['sell', 35, 35n],
+      ['buy', 79, 79n],
+    ]);
+  });
+
+  it('halves ranges the node rejects and keeps the smaller size', async () => {
+    const { fetchLogs, ranges } = source(logs, 25);
+
+    const trades = await collect(walletTradeHistory(WALLET, 0, 79, fetchLogs, { pageSize: 100 }));
+
+    expect(trades.map((trade) => trade.blockNumber)).toEqual([10, 35, 79]);
+    expect(ranges).toEqual([[0, 79], [0, 49], [0, 24], [25, 49], [50, 74], [75, 79]]);
+  });
+
+  it('throws errors that a smaller range would not fix', async () => {
+    const failing = async () => {
+      throw new Error('connection refused');
+    };
+
+    await expect(collect(walletTradeHistory(WALLET, 0, 79, failing))).rejects.toThrow('connection refused');
+    await expect(collect(walletTradeHistory(WALLET, 0, 9, source([], 0).fetchLogs, { pageSize: 4 }))).rejects.toThrow('coalesce');
+  });
+
+  it('stops before the next range once aborted', async () => {
+    const { fetchLogs, ranges } = source(logs);
+    const controller = new AbortController();
+    const trades: BackfillEvent[] = [];
+
+    for await (const trade of walletTradeHistory(WALLET, 0, 79, fetchLogs, { pageSize: 20, signal: controller.signal })) {
+      trades.push(trade);
+      controller.abort();
+    }
+
+    expect(trades).toHaveLength(1);
+    expect(ranges).toEqual([[0, 19]]);
+  });
+});
+
+describe('isLogRangeError', () => {
+  it('recognizes range and result-count limits from common providers', () => {
+    expect(isLogRangeError({ code: -32005, message: 'query returned more than 10000 results' })).toBe(true);
+    expect(isLogRangeError(new Error('exceed maximum block range: 5000'))).toBe(true);
+    expect(isLogRangeError({ error: { message: 'Log response size exceeded.' } })).toBe(true);
+    expect(isLogRangeError(new Error('execution reverted'))).toBe(false);
+  });
+});
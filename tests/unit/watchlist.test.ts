@@ -0,0 +1,156 @@
+import { Watchlist, WatchlistEvent } from '../../src/watchlist';
+import { Price } from '../../src/price';
+import { TokenPurchaseEvent } from '../../src/types';
+
+const ONE = 10n ** 18n;
+const TOKEN_A = '0x1111111111111111111111111111111111111111';
+const TOKEN_B = '0x2222222222222222222222222222222222222222';
+const TOKEN_C = '0x3333333333333333333333333333333333333333';
+
+function trade(token: string, price: bigint, cost: bigint): TokenPurchaseEvent {
+  return {
+    token,
+    account: '0x0000000000000000000000000000000000000001',
+    price,
+    amount: ONE,
+    cost,
+    fee: 0n,
+    offers: 0n,
+    funds: 0n,
+  };
+}
+
+describe('Watchlist', () => {
+  let now: number;
+  let watchlist: Watchlist;
+  let events: WatchlistEvent[];
+
+  beforeEach(() => {
+    now = 0;
+    watchlist = new Watchlist({ pruneAfterMs: 60000, volumeWindowMs: 30000, historyMs: 120000, rankSize: 2 }, () => now);
+    events = [];
+    watchlist.onEvent((event) => events.push(event));
+  });
+
+  it('should add tokens once and emit Added', () => {
+    watchlist.add(TOKEN_A);
+    watchlist.add(TOKEN_A.toUpperCase().replace('0X', '0x'));
+
+    expect(watchlist.size()).toBe(1);
+    expect(events).toEqual([{ type: 'added', token: TOKEN_A }]);
+  });
+
+  it('should ignore trades on unwatched tokens', () => {
+    watchlist.recordTrade(trade(TOKEN_A, ONE, ONE));
+    expect(watchlist.size()).toBe(0);
+    expect(events).toEqual([]);
+  });
+
+  it('should track last trade, price and windowed volume', () => {
+    watchlist.add(TOKEN_A);
+
+    now = 1000;
+    watchlist.recordTrade(trade(TOKEN_A, 100n, ONE));
+    now = 20000;
+    watchlist.recordTrade(trade(TOKEN_A, 120n, 2n * ONE));
+
+    expect(watchlist.get(TOKEN_A)).toMatchObject({ lastTradeAt: 20000, lastPrice: 120n, tradeCount: 2 });
+    expect(watchlist.volume(TOKEN_A)).toBe(3n * ONE);
+
+    // First trade leaves the 30s volume window
+    now = 31001;
+    expect(watchlist.volume(TOKEN_A)).toBe(2n * ONE);
+  });
+
+  it('should prune inactive tokens', () => {
+    watchlist.add(TOKEN_A);
+    watchlist.add(TOKEN_B);
+
+    now = 50000;
+    watchlist.recordTrade(trade(TOKEN_B, 100n, ONE));
+
+    now = 60001;
+    expect(watchlist.prune()).toEqual([TOKEN_A]);
+    expect(watchlist.has(TOKEN_B)).toBe(true);
+    expect(events).toContainEqual({ type: 'pruned', token: TOKEN_A, reason: 'inactive' });
+
+    now = 110001;
+    expect(watchlist.prune()).toEqual([TOKEN_B]);
+    expect(watchlist.size()).toBe(0);
+  });
+
+  it('should prune graduated tokens immediately', () => {
+    watchlist.add(TOKEN_A);
+    watchlist.markGraduated(TOKEN_A);
+
+    expect(watchlist.has(TOKEN_A)).toBe(false);
+    expect(events).toContainEqual({ type: 'pruned', token: TOKEN_A, reason: 'graduated' });
+  });
+
+  it('should rank most active tokens and emit RankChanged', () => {
+    watchlist.add(TOKEN_A);
+    watchlist.add(TOKEN_B);
+    watchlist.add(TOKEN_C);
+
+    watchlist.recordTrade(trade(TOKEN_A, 100n, ONE));
+    watchlist.recordTrade(trade(TOKEN_B, 100n, 2n * ONE));
+    watchlist.recordTrade(trade(TOKEN_C, 100n, 3n * ONE));
+
+    expect(watchlist.mostActive(2).map((entry) => entry.token)).toEqual([TOKEN_C, TOKEN_B]);
+
+    const rankings = events
+      .filter((event): event is Extract<WatchlistEvent, { type: 'rankChanged' }> => event.type === 'rankChanged')
+      .map((event) => event.ranking);
+    expect(rankings).toEqual([[TOKEN_A], [TOKEN_B, TOKEN_A], [TOKEN_C, TOKEN_B]]);
+  });
+
+  it('should not emit RankChanged when order is unchanged', () => {
+    watchlist.add(TOKEN_A);
+    watchlist.recordTrade(trade(TOKEN_A, 100n, ONE));
+    const count = events.length;
+
+    watchlist.recordTrade(trade(TOKEN_A, 100n, ONE));
+    expect(events.length).toBe(count);
+  });
+
+  it('should compute biggest gainers over a window exactly', () => {
+    watchlist.add(TOKEN_A);
+    watchlist.add(TOKEN_B);
+
+    now = 0;
+    watchlist.recordTrade(trade(TOKEN_A, 300n, ONE));
+    watchlist.recordTrade(trade(TOKEN_B, 100n, ONE));
+    now = 50000;
+    watchlist.recordTrade(trade(TOKEN_A, 400n, ONE));
+    watchlist.recordTrade(trade(TOKEN_B, 150n, ONE));
+    now = 90000;
+    watchlist.recordTrade(trade(TOKEN_A, 800n, ONE));
+    watchlist.recordTrade(trade(TOKEN_B, 150n, ONE));
+
+    const gainers = watchlist.biggestGainers(2, 100000);
+    expect(gainers.map((entry) => entry.token)).toEqual([TOKEN_A, TOKEN_B]);
+    expect(gainers[0].changePercent.equals(new Price(500n, 3n))).toBe(true);
+    expect(gainers[1].changePercent.equals(new Price(50n))).toBe(true);
+
+    // A shorter window only sees the later samples
+    const recent = watchlist.biggestGainers(1, 45000);
+    expect(recent[0].token).toBe(TOKEN_A);
+    expect(recent[0].changePercent.equals(new Price(100n))).toBe(true);
+  });
+
+  it('restores a snapshot of the watched tokens without announcing them', () => {
+    watchlist.add(TOKEN_A);
+    watchlist.recordTrade(trade(TOKEN_A, ONE, ONE));
+    const snapshot = watchlist.snapshot();
+
+    const restored = new Watchlist({}, () => now);
+    const announced: WatchlistEvent[] = [];
+    restored.onEvent((event) => announced.push(event));
+    restored.add(TOKEN_B);
+    restored.restore(snapshot);
+
+    expect(restored.snapshot()).toEqual(snapshot);
+    expect(restored.has(TOKEN_B)).toBe(false);
+    expect(announced).toEqual([{ type: 'added', token: TOKEN_B }]);
+  });
+});
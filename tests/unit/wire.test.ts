@@ -0,0 +1,246 @@
+import { PassThrough } from 'stream';
+import {
+  DecodedWireMessage,
+  WireDecoder,
+  WireEncoder,
+  WireMessage,
+  WIRE_VERSION,
+  decodeMessage,
+  encodeFrame,
+  encodeMessage,
+  summarizeResult,
+} from '../../src/wire';
+import { WireFormatError } from '../../src/errors';
+import { GasOptions } from '../../src/fourTrading';
+import { TradeTags } from '../../src/types';
+
+const TOKEN = '0x1111111111111111111111111111111111111111';
+const ACCOUNT = '0x2222222222222222222222222222222222222222';
+const TX = `0x${'ab'.repeat(32)}`;
+const ONE = 10n ** 18n;
+
+const GAS_FIELDS: GasOptions = {
+  type: 'eip1559',
+  gasLimit: 300000n,
+  gasMultiplier: 1.25,
+  gasPrice: 0n,
+  maxFeePerGas: 3n * 10n ** 9n,
+  maxPriorityFeePerGas: 10n ** 9n,
+};
+
+const TAG_VARIANTS: TradeTags[] = [
+  {},
+  { tags: [] },
+  { tags: ['momentum', 'été ✓'], correlationId: 'req-1' },
+  { subAccount: 'alpha' },
+  { tags: ['x'], correlationId: '', subAccount: 'beta' },
+];
+
+// Every subset of the gas fields
+function gasVariants(): GasOptions[] {
+  const keys = Object.keys(GAS_FIELDS) as Array<keyof GasOptions>;
+  return Array.from({ length: 1 << keys.length }, (_, mask) =>
+    Object.fromEntries(keys.filter((_, bit) => mask & (1 << bit)).map((key) => [key, GAS_FIELDS[key]]))
+  );
+}
+
+function roundTrip(message: WireMessage): DecodedWireMessage {
+  return decodeMessage(encodeMessage(message));
+}
+
+function priceUpdate(): WireMessage {
+  return { type: 'priceUpdate', update: { token: TOKEN, price: 0x0102n, side: 'sell', timestamp: 1700000000000 } };
+}
+
+async function collect(decoder: WireDecoder): Promise<DecodedWireMessage[]> {
+  const messages: DecodedWireMessage[] = [];
+  for await (const message of decoder) {
+    messages.push(message);
+  }
+  return messages;
+}
+
+describe('wire codec', () => {
+  test('round-trips trade intents with every combination of optional fields', () => {
+    for (const gas of [undefined, ...gasVariants()]) {
+      for (const tags of TAG_VARIANTS) {
+        for (const min of [undefined, 0n, 123n * ONE]) {
+          const buy: WireMessage = {
+            type: 'tradeIntent',
+            intent: { side: 'buy', tokenAddress: TOKEN, fundsInBNB: ONE, ...(min !== undefined ? { minAmount: min } : {}), ...(gas ? { gas } : {}), ...tags },
+          };
+          const sell: WireMessage = {
+            type: 'tradeIntent',
+            intent: { side: 'sell', tokenAddress: TOKEN, amount: 2n ** 256n - 1n, ...(min !== undefined ? { minFunds: min } : {}), ...(gas ? { gas } : {}), ...tags },
+          };
+          expect(roundTrip(buy)).toEqual(buy);
+          expect(roundTrip(sell)).toEqual(sell);
+        }
+      }
+    }
+  });
+
+  test('round-trips trade results with and without their optional fields', () => {
+    const variants: WireMessage[] = TAG_VARIANTS.flatMap((tags) => [
+      { type: 'tradeResult', result: { success: false, txHash: TX, timestamp: 0, ...tags } },
+      {
+        type: 'tradeResult',
+        result: {
+          success: true,
+          txHash: TX,
+          timestamp: 1700000000,
+          blockNumber: 45_000_000,
+          gasUsed: 150000n,
+          gasPrice: 10n ** 9n,
+          extraCosts: [
+            { kind: 'relayFee', amount: 1000n },
+            { kind: 'bundleTip', amount: 0n, txHash: TX },
+            { kind: 'replacementGas', amount: ONE },
+          ],
+          ...tags,
+        },
+      },
+      { type: 'tradeResult', result: { success: true, txHash: TX, timestamp: 1, extraCosts: [], ...tags } },
+    ]);
+    for (const message of variants) {
+      expect(roundTrip(message)).toEqual(message);
+    }
+  });
+
+  test('round-trips trade events and price updates', () => {
+    const event = { token: TOKEN, account: ACCOUNT, price: 28n * 10n ** 9n, amount: 5n * ONE, cost: ONE / 10n, fee: ONE / 1000n, offers: 0n, funds: 24n * ONE };
+    const messages: WireMessage[] = [
+      { type: 'tradeEvent', trade: { side: 'buy', event } },
+      { type: 'tradeEvent', trade: { side: 'sell', event, blockNumber: 0 } },
+      priceUpdate(),
+      { type: 'priceUpdate', update: { token: TOKEN, price: 0n, side: 'buy', timestamp: 0 } },
+    ];
+    for (const message of messages) {
+      expect(roundTrip(message)).toEqual(message);
+    }
+  });
+
+  test('writes the documented layout', () => {
+    expect(encodeMessage(priceUpdate()).toString('hex')).toBe(
+      `${'01'}${'04'}${'01'}${'11'.repeat(20)}${'020102'}${'0000018bcfe56800'}`
+    );
+  });
+
+  test('decodes addresses checksummed whatever case they were written in', () => {
+    const lower = '0x52908400098527886e0f7030069857d2e4169ee7';
+    const decoded = roundTrip({ type: 'priceUpdate', update: { token: lower, price: 1n, side: 'buy', timestamp: 0 } });
+    expect(decoded.type === 'priceUpdate' && decoded.update.token).toBe('0x52908400098527886E0F7030069857D2E4169EE7');
+  });
+
+  test('rejects values the format cannot carry', () => {
+    const bad: WireMessage[] = [
+      { type: 'priceUpdate', update: { token: TOKEN, price: -1n, side: 'buy', timestamp: 0 } },
+      { type: 'priceUpdate', update: { token: TOKEN, price: 2n ** 256n, side: 'buy', timestamp: 0 } },
+      { type: 'priceUpdate', update: { token: 'not-an-address', price: 1n, side: 'buy', timestamp: 0 } },
+      { type: 'priceUpdate', update: { token: TOKEN, price: 1n, side: 'buy', timestamp: 1.5 } },
+      { type: 'tradeResult', result: { success: true, txHash: '0x1234', timestamp: 0 } },
+      { type: 'tradeIntent', intent: { side: 'buy', tokenAddress: TOKEN, fundsInBNB: 1n, correlationId: 'x'.repeat(70000) } },
+    ];
+    for (const message of bad) {
+      expect(() => encodeMessage(message)).toThrow(WireFormatError);
+    }
+  });
+
+  test('rejects every truncation of a message', () => {
+    const encoded = encodeMessage({
+      type: 'tradeIntent',
+      intent: { side: 'buy', tokenAddress: TOKEN, fundsInBNB: ONE, minAmount: 1n, gas: GAS_FIELDS, tags: ['t'], correlationId: 'c' },
+    });
+    for (let length = 0; length < encoded.length; length++) {
+      expect(() => decodeMessage(encoded.subarray(0, length))).toThrow(WireFormatError);
+    }
+  });
+});
+
+describe('wire versions', () => {
+  test('reads the known fields of a newer message and skips appended ones', () => {
+    const encoded = encodeMessage(priceUpdate());
+    const newer = Buffer.concat([encoded, Buffer.from([0xde, 0xad, 0xbe, 0xef])]);
+    newer[0] = WIRE_VERSION + 1;
+    expect(decodeMessage(newer)).toEqual(priceUpdate());
+  });
+
+  test('ignores flag bits set by a newer version', () => {
+    const encoded = encodeMessage({ type: 'tradeResult', result: { success: true, txHash: TX, timestamp: 5 } });
+    encoded[2] |= 0x80;
+    expect(() => decodeMessage(encoded)).toThrow(WireFormatError);
+    encoded[0] = WIRE_VERSION + 1;
+    expect(decodeMessage(encoded)).toEqual({ type: 'tradeResult', result: { success: true, txHash: TX, timestamp: 5 } });
+  });
+
+  test('passes through message types added by a newer version', () => {
+    const decoded = decodeMessage(Buffer.from([WIRE_VERSION + 1, 42, 1, 2, 3]));
+    expect(decoded).toEqual({ type: 'unknown', version: WIRE_VERSION + 1, typeId: 42, body: Buffer.from([1, 2, 3]) });
+  });
+
+  test('rejects unknown types, trailing bytes and version 0 at its own version', () => {
+    expect(() => decodeMessage(Buffer.from([WIRE_VERSION, 42]))).toThrow(WireFormatError);
+    expect(() => decodeMessage(Buffer.concat([encodeMessage(priceUpdate()), Buffer.from([0])]))).toThrow(/trailing/);
+    const zero = encodeMessage(priceUpdate());
+    zero[0] = 0;
+    expect(() => decodeMessage(zero)).toThrow(/version 0/);
+  });
+});
+
+describe('wire framing', () => {
+  const messages: WireMessage[] = [
+    priceUpdate(),
+    { type: 'tradeIntent', intent: { side: 'sell', tokenAddress: TOKEN, amount: ONE, tags: ['exit'] } },
+    { type: 'tradeResult', result: { success: true, txHash: TX, timestamp: 1700000000, correlationId: 'req-1' } },
+  ];
+
+  test('decodes frames split across and packed into chunks', async () => {
+    const bytes = Buffer.concat(messages.map(encodeFrame));
+
+    const bytewise = new WireDecoder();
+    const bytewiseOut = collect(bytewise);
+    for (const byte of bytes) {
+      bytewise.write(Buffer.from([byte]));
+    }
+    bytewise.end();
+    expect(await bytewiseOut).toEqual(messages);
+
+    const packed = new WireDecoder();
+    const packedOut = collect(packed);
+    packed.end(bytes);
+    expect(await packedOut).toEqual(messages);
+  });
+
+  test('an encoder piped into a decoder carries messages across a byte stream', async () => {
+    const encoder = new WireEncoder();
+    const decoder = new WireDecoder();
+    encoder.pipe(new PassThrough()).pipe(decoder);
+    const out = collect(decoder);
+    messages.forEach((message) => encoder.write(message));
+    encoder.end();
+    expect(await out).toEqual(messages);
+  });
+
+  test('fails on oversized frames and streams ending mid-frame', async () => {
+    const oversized = new WireDecoder(16);
+    oversized.end(encodeFrame({ type: 'tradeResult', result: { success: true, txHash: TX, timestamp: 0 } }));
+    await expect(collect(oversized)).rejects.toThrow(/exceeds the 16-byte limit/);
+
+    const cut = new WireDecoder();
+    cut.end(encodeFrame(priceUpdate()).subarray(0, 10));
+    await expect(collect(cut)).rejects.toThrow(/ended inside a frame/);
+  });
+});
+
+describe('summarizeResult', () => {
+  test('keeps the block and gas of the receipt and drops the rest', () => {
+    const summary = summarizeResult({
+      success: true,
+      txHash: TX,
+      timestamp: 1700000000,
+      receipt: { blockNumber: 45_000_000, gasUsed: 150000n, gasPrice: 10n ** 9n, logs: [] },
+      tags: ['t'],
+    });
+    expect(summary).toEqual({ success: true, txHash: TX, timestamp: 1700000000, blockNumber: 45_000_000, gasUsed: 150000n, gasPrice: 10n ** 9n, tags: ['t'] });
+  });
+});